@@ -0,0 +1,149 @@
+//! Query benchmarks over a synthetic table
+//!
+//! The table is written to a temporary directory during setup, so no Pinot
+//! install is needed. Run with `cargo bench -p datafusion-pinot`.
+//!
+//! Besides wall-clock time, the bytes and allocations of one run of each
+//! query are printed, counted by a wrapper around the system allocator.
+//! The `string_views` group compares scanning a RAW STRING column as
+//! `Utf8` and as zero-copy `Utf8View`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use datafusion::prelude::*;
+use datafusion_pinot::PinotTable;
+use pinot_segment::testutil::SegmentBuilder;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+const NUM_SEGMENTS: usize = 4;
+const DOCS_PER_SEGMENT: usize = 50_000;
+
+/// Rows of the single-segment table scanned by the `string_views` group
+const STRING_VIEW_DOCS: usize = 100_000;
+
+const QUERIES: [(&str, &str); 4] = [
+    ("count_star", "SELECT COUNT(*) FROM events"),
+    ("scan_dictionary_column", "SELECT hits FROM events"),
+    ("scan_raw_column", "SELECT player FROM events"),
+    (
+        "group_by",
+        "SELECT team, COUNT(*), SUM(hits) FROM events GROUP BY team",
+    ),
+];
+
+/// System allocator that counts allocations and allocated bytes
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Run `sql` once, printing the rows it returned and the allocations it made
+fn report_allocations(runtime: &Runtime, ctx: &SessionContext, name: &str, sql: &str) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let rows = runtime.block_on(run_query(ctx, sql));
+    println!(
+        "{}: {} rows, {} allocations, {} bytes allocated",
+        name,
+        rows,
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes
+    );
+}
+
+/// Write `NUM_SEGMENTS` segments of `events` under `table_dir`
+///
+/// `hits` and `team` are dictionary-encoded; `player` is RAW and unique.
+fn write_table(table_dir: &Path) {
+    let teams: Vec<String> = (0..30).map(|i| format!("team_{:02}", i)).collect();
+    for segment in 0..NUM_SEGMENTS {
+        let docs = segment * DOCS_PER_SEGMENT..(segment + 1) * DOCS_PER_SEGMENT;
+        let players: Vec<String> = docs.clone().map(|i| format!("player_{}", i)).collect();
+        let segment_name = format!("events_{}", segment);
+        SegmentBuilder::new("events", &segment_name)
+            .int_column("hits", docs.clone().map(|i| (i % 500) as i32).collect())
+            .string_column("team", docs.map(|i| teams[i % 30].as_str()).collect())
+            .raw_string_column("player", players.iter().map(|s| s.as_str()).collect())
+            .write(&table_dir.join(&segment_name).join("v3"))
+            .unwrap();
+    }
+}
+
+/// Write one segment whose `url` column is RAW, with values too long to be
+/// inlined in a string view
+fn write_url_table(table_dir: &Path) {
+    let urls: Vec<String> = (0..STRING_VIEW_DOCS)
+        .map(|i| format!("https://example.com/articles/{}", i))
+        .collect();
+    SegmentBuilder::new("pages", "pages_0")
+        .raw_string_column("url", urls.iter().map(|s| s.as_str()).collect())
+        .write(&table_dir.join("pages_0").join("v3"))
+        .unwrap();
+}
+
+async fn run_query(ctx: &SessionContext, sql: &str) -> usize {
+    let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    batches.iter().map(|b| b.num_rows()).sum()
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let table_dir = tempfile::tempdir().unwrap();
+    write_table(table_dir.path());
+
+    let runtime = Runtime::new().unwrap();
+    let ctx = SessionContext::new();
+    let table = PinotTable::open_table(table_dir.path()).unwrap();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+
+    let mut group = c.benchmark_group("queries");
+    for (name, sql) in QUERIES {
+        report_allocations(&runtime, &ctx, name, sql);
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter(|| run_query(&ctx, sql));
+        });
+    }
+    group.finish();
+}
+
+fn bench_string_views(c: &mut Criterion) {
+    let table_dir = tempfile::tempdir().unwrap();
+    write_url_table(table_dir.path());
+
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("string_views");
+    for (name, string_views) in [("scan_raw_utf8", false), ("scan_raw_utf8_view", true)] {
+        let ctx = SessionContext::new();
+        let table = PinotTable::open_table(table_dir.path())
+            .unwrap()
+            .with_string_view_arrays(string_views);
+        ctx.register_table("pages", Arc::new(table)).unwrap();
+
+        let sql = "SELECT url FROM pages";
+        report_allocations(&runtime, &ctx, name, sql);
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter(|| run_query(&ctx, sql));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_queries, bench_string_views);
+criterion_main!(benches);
@@ -28,6 +28,9 @@
 //!    cargo run --features controller --example query_controller
 //!    ```
 //!
+//!    Set `PINOT_SCHEMA` to serve the tables under another schema name than
+//!    `default`.
+//!
 //! ## Note
 //!
 //! This example uses HTTP only (not HTTPS). The controller feature is configured
@@ -39,6 +42,8 @@
 #[cfg(feature = "controller")]
 use datafusion::prelude::*;
 #[cfg(feature = "controller")]
+use datafusion_pinot::catalog::DEFAULT_SCHEMA;
+#[cfg(feature = "controller")]
 use datafusion_pinot::PinotCatalog;
 #[cfg(feature = "controller")]
 use std::env;
@@ -55,6 +60,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let segment_dir = env::var("PINOT_SEGMENT_DIR")
         .unwrap_or_else(|_| "/tmp/pinot/quickstart/PinotServerDataDir0".to_string());
 
+    let schema_name = env::var("PINOT_SCHEMA").unwrap_or_else(|_| DEFAULT_SCHEMA.to_string());
+
     println!("Pinot Controller: {}", controller_url);
     println!("Segment Directory: {}", segment_dir);
     println!();
@@ -67,6 +74,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let catalog = PinotCatalog::builder()
         .controller(&controller_url)
         .with_segment_dir(&segment_dir)
+        .with_schema_name(&schema_name)
         .build()?;
 
     ctx.register_catalog("pinot", Arc::new(catalog));
@@ -74,7 +82,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // List available tables
     println!("Discovering tables from controller...");
     let catalog = ctx.catalog("pinot").unwrap();
-    let schema = catalog.schema("default").unwrap();
+    let schema = catalog.schema(&schema_name).unwrap();
     let tables = schema.table_names();
     println!("Available tables: {:?}", tables);
     println!();
@@ -85,9 +93,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("---");
 
         // Count query
-        let count_query = "SELECT COUNT(*) as total_records FROM pinot.default.baseballStats";
+        let count_query = format!(
+            "SELECT COUNT(*) as total_records FROM pinot.{}.baseballStats",
+            schema_name
+        );
         println!("Query: {}", count_query);
-        let df = ctx.sql(count_query).await?;
+        let df = ctx.sql(&count_query).await?;
         let results = df.collect().await?;
         println!("Results:");
         for batch in results {
@@ -96,14 +107,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!();
 
         // Sample query with aggregation (using quoted identifiers for case-sensitive columns)
-        let sample_query =
+        let sample_query = format!(
             "SELECT \"playerName\", AVG(\"homeRuns\") as avg_home_runs \
-             FROM pinot.default.baseballStats \
+             FROM pinot.{}.baseballStats \
              GROUP BY \"playerName\" \
              ORDER BY avg_home_runs DESC \
-             LIMIT 5";
+             LIMIT 5",
+            schema_name
+        );
         println!("Query: {}", sample_query);
-        let df = ctx.sql(sample_query).await?;
+        let df = ctx.sql(&sample_query).await?;
         let results = df.collect().await?;
         println!("Results:");
         for batch in results {
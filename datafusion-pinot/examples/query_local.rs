@@ -1,5 +1,6 @@
 use datafusion::prelude::*;
-use datafusion_pinot::PinotCatalog;
+use datafusion_pinot::catalog::DEFAULT_SCHEMA;
+use datafusion_pinot::{PinotCatalog, PinotTable};
 use std::sync::Arc;
 
 /// Example: Query local Pinot segments using DataFusion
@@ -24,11 +25,16 @@ use std::sync::Arc;
 ///
 /// Usage:
 ///   PINOT_DATA_DIR=/path/to/data cargo run --example query_local
+///
+/// Set `PINOT_SCHEMA` to serve the tables under another schema name than
+/// `default`.
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Point to your Pinot data directory
     let data_dir = std::env::var("PINOT_DATA_DIR")
         .unwrap_or_else(|_| "/tmp/pinot/quickstart/PinotServerDataDir0".to_string());
+    let schema_name =
+        std::env::var("PINOT_SCHEMA").unwrap_or_else(|_| DEFAULT_SCHEMA.to_string());
 
     println!("📊 DataFusion + Pinot Integration Example");
     println!("=========================================\n");
@@ -39,20 +45,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create and register Pinot catalog (filesystem mode)
     println!("🔌 Connecting to Pinot data directory: {}", data_dir);
 
-    // Option 1: Using the simple constructor (recommended for filesystem mode)
-    let catalog = PinotCatalog::new(&data_dir)?;
+    // Option 1: Using the simple constructor, serving tables under `default`
+    // let catalog = PinotCatalog::new(&data_dir)?;
 
     // Option 2: Using the builder pattern (for more control)
-    // let catalog = PinotCatalog::builder()
-    //     .filesystem(&data_dir)
-    //     .build()?;
+    let catalog = PinotCatalog::builder()
+        .filesystem(&data_dir)
+        .with_schema_name(&schema_name)
+        .build()?;
 
     ctx.register_catalog("pinot", Arc::new(catalog));
 
     // Discover available tables
     println!("📋 Discovering tables...\n");
     let catalog = ctx.catalog("pinot").unwrap();
-    let schema = catalog.schema("default").unwrap();
+    let schema = catalog.schema(&schema_name).unwrap();
     let tables = schema.table_names();
     println!("Found {} tables:", tables.len());
     for table in &tables {
@@ -60,11 +67,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     println!();
 
+    // Per-segment metadata, as loaded when the table was opened
+    if let Some(provider) = schema.table("baseballStats").await? {
+        if let Some(table) = provider.as_any().downcast_ref::<PinotTable>() {
+            println!("📦 baseballStats segments:");
+            for info in table.segment_infos() {
+                println!(
+                    "  - {} ({} docs, {} columns, crc={:?}, created={:?}, time range={:?})",
+                    info.name,
+                    info.total_docs,
+                    info.num_columns,
+                    info.crc,
+                    info.creation_time,
+                    info.time_range_millis
+                );
+            }
+            println!();
+        }
+    }
+
     // Example 1: Simple COUNT query
     println!("📊 Example 1: Count total rows");
-    println!("SQL: SELECT COUNT(*) FROM pinot.default.\"baseballStats\"");
+    println!("SQL: SELECT COUNT(*) FROM pinot.{}.\"baseballStats\"", schema_name);
     let df = ctx
-        .sql("SELECT COUNT(*) FROM pinot.default.\"baseballStats\"")
+        .sql(&format!("SELECT COUNT(*) FROM pinot.{}.\"baseballStats\"", schema_name))
         .await?;
     let results = df.collect().await?;
     println!("Result:");
@@ -76,7 +102,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📊 Example 2: Select specific columns");
     println!("SQL: SELECT \"playerID\", \"hits\", \"homeRuns\" FROM ... LIMIT 10");
     let df = ctx
-        .sql("SELECT \"playerID\", \"hits\", \"homeRuns\" FROM pinot.default.\"baseballStats\" LIMIT 10")
+        .sql(&format!("SELECT \"playerID\", \"hits\", \"homeRuns\" FROM pinot.{}.\"baseballStats\" LIMIT 10", schema_name))
         .await?;
     let results = df.collect().await?;
     println!("Result:");
@@ -88,7 +114,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📊 Example 3: Aggregate statistics");
     println!("SQL: SELECT SUM(\"hits\"), AVG(\"homeRuns\"), MAX(\"strikeouts\") FROM ...");
     let df = ctx
-        .sql("SELECT SUM(\"hits\") as total_hits, AVG(\"homeRuns\") as avg_homeruns, MAX(\"strikeouts\") as max_strikeouts FROM pinot.default.\"baseballStats\"")
+        .sql(&format!("SELECT SUM(\"hits\") as total_hits, AVG(\"homeRuns\") as avg_homeruns, MAX(\"strikeouts\") as max_strikeouts FROM pinot.{}.\"baseballStats\"", schema_name))
         .await?;
     let results = df.collect().await?;
     println!("Result:");
@@ -100,7 +126,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📊 Example 4: Group by and aggregate");
     println!("SQL: SELECT \"teamID\", COUNT(*), SUM(\"hits\") FROM ... GROUP BY \"teamID\" LIMIT 10");
     let df = ctx
-        .sql("SELECT \"teamID\", COUNT(*) as games, SUM(\"hits\") as total_hits FROM pinot.default.\"baseballStats\" GROUP BY \"teamID\" ORDER BY total_hits DESC LIMIT 10")
+        .sql(&format!("SELECT \"teamID\", COUNT(*) as games, SUM(\"hits\") as total_hits FROM pinot.{}.\"baseballStats\" GROUP BY \"teamID\" ORDER BY total_hits DESC LIMIT 10", schema_name))
         .await?;
     let results = df.collect().await?;
     println!("Result:");
@@ -113,7 +139,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("📊 Example 5: Query multiple tables");
         println!("SQL: SELECT COUNT(*) FROM dimBaseballTeams");
         let df = ctx
-            .sql("SELECT COUNT(*) FROM pinot.default.\"dimBaseballTeams\"")
+            .sql(&format!("SELECT COUNT(*) FROM pinot.{}.\"dimBaseballTeams\"", schema_name))
             .await?;
         let results = df.collect().await?;
         println!("Result:");
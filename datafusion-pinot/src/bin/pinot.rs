@@ -0,0 +1,96 @@
+//! Command line tools for Pinot segments
+//!
+//! Usage:
+//!   pinot export [--format csv|json|parquet] (--segment <dir> | --table <dir>) [--output <path>]
+//!
+//! `export` writes every row of a segment (or of all segments of a table
+//! directory) to `--output`, or to stdout when no output is given.
+
+use datafusion_pinot::export::{write_table, ExportFormat};
+use datafusion_pinot::PinotTable;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: pinot export [--format csv|json|parquet] \
+(--segment <dir> | --table <dir>) [--output <path>]";
+
+/// Arguments of `pinot export`
+struct ExportArgs {
+    format: ExportFormat,
+    segment: Option<String>,
+    table: Option<String>,
+    output: Option<String>,
+}
+
+fn parse_export_args(args: &[String]) -> Result<ExportArgs, String> {
+    let mut parsed = ExportArgs {
+        format: ExportFormat::Csv,
+        segment: None,
+        table: None,
+        output: None,
+    };
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", flag))
+        };
+        match flag.as_str() {
+            "--format" => parsed.format = value()?.parse().map_err(|e| format!("{}", e))?,
+            "--segment" => parsed.segment = Some(value()?),
+            "--table" => parsed.table = Some(value()?),
+            "--output" => parsed.output = Some(value()?),
+            _ => return Err(format!("Unknown argument {}", flag)),
+        }
+    }
+    if parsed.segment.is_some() == parsed.table.is_some() {
+        return Err("Pass exactly one of --segment and --table".to_string());
+    }
+    Ok(parsed)
+}
+
+async fn export(args: ExportArgs) -> Result<usize, Box<dyn std::error::Error>> {
+    let table = match (&args.segment, &args.table) {
+        (Some(segment), _) => PinotTable::open(segment)?,
+        (None, Some(table)) => PinotTable::open_table(table)?,
+        (None, None) => unreachable!("checked by parse_export_args"),
+    };
+
+    let writer: Box<dyn Write + Send> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(std::io::stdout()),
+    };
+    let mut writer = BufWriter::new(writer);
+    let rows = write_table(&table, &mut writer, args.format).await?;
+    writer.flush()?;
+    Ok(rows)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let export_args = match args.split_first() {
+        Some((command, rest)) if command == "export" => parse_export_args(rest),
+        _ => Err(USAGE.to_string()),
+    };
+    let export_args = match export_args {
+        Ok(export_args) => export_args,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    match export(export_args).await {
+        Ok(rows) => {
+            eprintln!("Exported {} rows", rows);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Export failed: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
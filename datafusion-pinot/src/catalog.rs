@@ -1,24 +1,55 @@
 use datafusion::catalog::{CatalogProvider, SchemaProvider};
 use datafusion::datasource::TableProvider;
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use glob::{MatchOptions, Pattern};
 use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::runtime::{Handle, RuntimeFlavor};
 
 use crate::error::{Error, Result};
-use crate::metadata_provider::{FileSystemMetadataProvider, MetadataProvider};
+use crate::lazy_table::LazyPinotTable;
+use crate::metadata_provider::{
+    CachedMetadataProvider, ChainedMetadataProvider, FileSystemMetadataProvider, MetadataProvider,
+    StaticMetadataProvider,
+};
+use crate::options::PinotReadOptions;
+use crate::segment::is_temporary_dir_name;
+use crate::system::{PinotSystemSchemaProvider, SYSTEM_SCHEMA};
 use crate::table::PinotTable;
 
 #[cfg(feature = "controller")]
-use crate::controller::PinotControllerClient;
+use crate::controller::{ControllerAuth, PinotControllerClient};
 
 #[cfg(feature = "controller")]
 use crate::metadata_provider::ControllerMetadataProvider;
 
+#[cfg(feature = "object-store")]
+use crate::metadata_provider::ObjectStoreMetadataProvider;
+#[cfg(feature = "object-store")]
+use object_store::ObjectStore;
+
+/// Name of the schema serving the tables of the catalog's primary source,
+/// unless renamed with [`PinotCatalogBuilder::with_schema_name`]
+pub const DEFAULT_SCHEMA: &str = "default";
+
 /// Catalog provider for Pinot tables
-#[derive(Debug)]
+///
+/// Tables are served from the `default` schema (or the names set with
+/// [`PinotCatalogBuilder::with_schema_name`] and
+/// [`PinotCatalogBuilder::with_schema_alias`]), plus one schema per cluster
+/// added with [`PinotCatalogBuilder::add_cluster`]; the `system` schema holds
+/// introspection tables such as `segments`.
+///
+/// Clones share the schema providers, and so the tables they have opened.
+#[derive(Debug, Clone)]
 pub struct PinotCatalog {
-    schema_provider: Arc<PinotSchemaProvider>,
+    /// Table schemas in declaration order, each served by one provider per
+    /// catalog merged into it
+    schemas: Vec<(String, Vec<Arc<PinotSchemaProvider>>)>,
 }
 
 impl PinotCatalog {
@@ -35,9 +66,7 @@ impl PinotCatalog {
         }
 
         let metadata_provider = Arc::new(FileSystemMetadataProvider::new(data_dir));
-        let schema_provider = Arc::new(PinotSchemaProvider::new(metadata_provider));
-
-        Ok(Self { schema_provider })
+        Ok(Self::from_schema_provider(PinotSchemaProvider::new(metadata_provider)))
     }
 
     /// Create a builder for configuring a Pinot catalog
@@ -66,8 +95,75 @@ impl PinotCatalog {
     /// This is a low-level API for advanced use cases. Most users should use
     /// `new()` or `builder()` instead.
     pub fn from_provider(metadata_provider: Arc<dyn MetadataProvider>) -> Self {
-        let schema_provider = Arc::new(PinotSchemaProvider::new(metadata_provider));
-        Self { schema_provider }
+        Self::from_schema_provider(PinotSchemaProvider::new(metadata_provider))
+    }
+
+    fn from_schema_provider(schema_provider: PinotSchemaProvider) -> Self {
+        schema_provider.list_table_names_blocking();
+        Self {
+            schemas: vec![(DEFAULT_SCHEMA.to_string(), vec![Arc::new(schema_provider)])],
+        }
+    }
+
+    /// Combine the schemas of two catalogs, e.g. to query several clusters
+    /// from one session
+    ///
+    /// Schemas only in `other` are added. A schema present in both serves
+    /// the tables of both catalogs; a table found in both is served from
+    /// `self`, and must have the same Arrow schema in each. Tables present
+    /// in both are opened to compare their schemas.
+    ///
+    /// # Errors
+    /// Returns error if a table common to both catalogs has different
+    /// schemas, or cannot be opened to compare them.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::new("/data/cluster_a")?
+    ///     .merge(PinotCatalog::new("/data/cluster_b")?)?;
+    /// ```
+    pub fn merge(mut self, other: PinotCatalog) -> Result<PinotCatalog> {
+        for (name, providers) in other.schemas {
+            match self.schemas.iter_mut().find(|(existing, _)| *existing == name) {
+                Some((_, existing)) => {
+                    block_on(check_merged_schemas(
+                        name.clone(),
+                        existing.clone(),
+                        providers.clone(),
+                    ))??;
+                    existing.extend(providers);
+                }
+                None => self.schemas.push((name, providers)),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Invalidate cached segment lists and opened tables, and list the
+    /// tables again
+    ///
+    /// Segment paths and opened tables are cached after first use, and
+    /// `table_names()`/`table_exist()` answer from the last table listing.
+    /// Call this when tables or segments have been added or removed so the
+    /// next query re-scans the metadata source.
+    pub fn refresh(&self) {
+        for provider in self.schema_providers() {
+            provider.refresh();
+        }
+    }
+
+    /// Invalidate the cached segment list and opened table of one table
+    ///
+    /// The next query against `table_name` (matched case-insensitively, in
+    /// every schema) lists its segments again and re-opens them.
+    pub fn refresh_table(&self, table_name: &str) {
+        for provider in self.schema_providers() {
+            provider.refresh_table(table_name);
+        }
+    }
+
+    fn schema_providers(&self) -> impl Iterator<Item = &Arc<PinotSchemaProvider>> {
+        self.schemas.iter().flat_map(|(_, providers)| providers)
     }
 }
 
@@ -77,15 +173,119 @@ impl CatalogProvider for PinotCatalog {
     }
 
     fn schema_names(&self) -> Vec<String> {
-        vec!["default".to_string()]
+        self.schemas
+            .iter()
+            .map(|(name, _)| name.clone())
+            .chain(std::iter::once(SYSTEM_SCHEMA.to_string()))
+            .collect()
     }
 
     fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
-        if name == "default" {
-            Some(self.schema_provider.clone())
-        } else {
-            None
+        if name == SYSTEM_SCHEMA {
+            return Some(Arc::new(PinotSystemSchemaProvider::with_schemas(
+                self.schemas.clone(),
+            )));
+        }
+
+        let (_, providers) = self.schemas.iter().find(|(schema, _)| schema == name)?;
+        match providers.as_slice() {
+            [provider] => Some(provider.clone()),
+            _ => Some(Arc::new(MergedSchemaProvider {
+                members: providers.clone(),
+            })),
+        }
+    }
+}
+
+/// Fail if a table of both `existing` and `incoming` differs in schema
+async fn check_merged_schemas(
+    schema_name: String,
+    existing: Vec<Arc<PinotSchemaProvider>>,
+    incoming: Vec<Arc<PinotSchemaProvider>>,
+) -> Result<()> {
+    let existing = MergedSchemaProvider { members: existing };
+    let incoming = MergedSchemaProvider { members: incoming };
+    let existing_names = existing.member_table_names().await?;
+    for name in incoming.member_table_names().await? {
+        if !existing_names.iter().any(|t| t.eq_ignore_ascii_case(&name)) {
+            continue;
+        }
+
+        let open_failed = |e: DataFusionError| {
+            Error::Internal(format!(
+                "Failed to open table '{}.{}' to merge catalogs: {}",
+                schema_name, name, e
+            ))
+        };
+        let ours = existing.table(&name).await.map_err(open_failed)?;
+        let theirs = incoming.table(&name).await.map_err(open_failed)?;
+        let (Some(ours), Some(theirs)) = (ours, theirs) else {
+            continue;
+        };
+        if ours.schema() != theirs.schema() {
+            return Err(Error::Internal(format!(
+                "Table '{}.{}' has different schemas in the merged catalogs: {:?} vs {:?}",
+                schema_name,
+                name,
+                ours.schema().fields(),
+                theirs.schema().fields()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Schema serving the tables of several merged catalogs
+///
+/// A table is looked up in each member in turn, so the first catalog holding
+/// a table name serves it.
+#[derive(Debug)]
+struct MergedSchemaProvider {
+    members: Vec<Arc<PinotSchemaProvider>>,
+}
+
+impl MergedSchemaProvider {
+    /// Table names of all members, without case-insensitive duplicates
+    async fn member_table_names(&self) -> Result<Vec<String>> {
+        let mut names: Vec<String> = Vec::new();
+        for member in &self.members {
+            for name in member.cached_table_names().await? {
+                if !names.iter().any(|t| t.eq_ignore_ascii_case(&name)) {
+                    names.push(name);
+                }
+            }
+        }
+        Ok(names)
+    }
+}
+
+#[async_trait::async_trait]
+impl SchemaProvider for MergedSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for name in self.members.iter().flat_map(|m| m.table_names()) {
+            if !names.iter().any(|t| t.eq_ignore_ascii_case(&name)) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    async fn table(&self, name: &str) -> DataFusionResult<Option<Arc<dyn TableProvider>>> {
+        for member in &self.members {
+            if let Some(table) = member.table(name).await? {
+                return Ok(Some(table));
+            }
         }
+        Ok(None)
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        self.members.iter().any(|m| m.table_exist(name))
     }
 }
 
@@ -95,6 +295,13 @@ impl CatalogProvider for PinotCatalog {
 /// - **Filesystem mode**: Discovers tables by scanning local directories
 /// - **Controller mode**: Discovers tables via HTTP API, reads data from local filesystem
 ///
+/// Any other `MetadataProvider` can be plugged in with `provider()`.
+///
+/// Sources add up rather than replace each other: with several, the primary
+/// schema serves the tables of all of them through a
+/// [`ChainedMetadataProvider`], earlier sources winning for tables found in
+/// more than one.
+///
 /// # Example - Filesystem Mode
 /// ```ignore
 /// let catalog = PinotCatalog::builder()
@@ -109,9 +316,58 @@ impl CatalogProvider for PinotCatalog {
 ///     .with_segment_dir("/tmp/pinot/quickstart/PinotServerDataDir0")
 ///     .build()?;
 /// ```
+///
+/// # Example - Controller tables plus local ad-hoc tables
+/// ```ignore
+/// let catalog = PinotCatalog::builder()
+///     .controller("http://localhost:9000")
+///     .with_segment_dir("/tmp/pinot/quickstart/PinotServerDataDir0")
+///     .filesystem("/data/adhoc")
+///     .build()?;
+/// ```
 #[derive(Default)]
 pub struct PinotCatalogBuilder {
-    source: Option<PinotCatalogSource>,
+    /// Sources of the primary schema, chained in the order they were added
+    sources: Vec<PinotCatalogSource>,
+    /// Schema serving the primary source's tables, `default` when unset
+    schema_name: Option<String>,
+    /// Further schemas serving the primary source's tables
+    schema_aliases: Vec<String>,
+    /// Extra clusters, each served under its own schema
+    clusters: Vec<(String, Arc<dyn MetadataProvider>)>,
+    read_options: PinotReadOptions,
+    table_ttl: Option<Duration>,
+    /// TTL of the cache wrapped around the primary source's provider
+    metadata_cache_ttl: Option<Duration>,
+    change_detection: bool,
+    cache_table_names: bool,
+    /// Table name patterns a table must match one of, when any are given
+    include_tables: Vec<String>,
+    /// Table name patterns hiding a table, even one that is included
+    exclude_tables: Vec<String>,
+    case_insensitive_names: bool,
+    validate_on_build: bool,
+    /// Path prefix of every controller API call
+    #[cfg(feature = "controller")]
+    controller_base_path: Option<String>,
+    /// Failure threshold and reset timeout of the controller circuit breaker
+    #[cfg(feature = "controller")]
+    controller_circuit_breaker: Option<(u32, Duration)>,
+    /// Credentials sent with every controller request
+    #[cfg(feature = "controller")]
+    controller_auth: Option<ControllerAuth>,
+    /// Extra headers sent with every controller request
+    #[cfg(feature = "controller")]
+    controller_headers: Vec<(String, String)>,
+    /// PEM certificate and key presented to the controller
+    #[cfg(feature = "tls")]
+    client_cert: Option<(Vec<u8>, Vec<u8>)>,
+    /// PEM CA certificates trusted for the controller
+    #[cfg(feature = "tls")]
+    root_cert: Option<Vec<u8>>,
+    /// Local directory object store segments are downloaded into
+    #[cfg(feature = "object-store")]
+    segment_cache_dir: Option<PathBuf>,
 }
 
 /// Configuration source for PinotCatalog
@@ -119,12 +375,31 @@ pub enum PinotCatalogSource {
     /// Filesystem-based discovery (scans local directories)
     FileSystem { data_dir: PathBuf },
 
+    /// Filesystem-based discovery over several data directories
+    FileSystemDirs { data_dirs: Vec<PathBuf> },
+
+    /// Filesystem-based discovery with one schema per subdirectory
+    FileSystemSchemas { data_dir: PathBuf },
+
     /// Controller-based discovery (HTTP API + local filesystem)
     #[cfg(feature = "controller")]
     Controller {
         base_url: String,
         segment_dir: PathBuf,
     },
+
+    /// Object store discovery (deep store listing + local segment cache)
+    #[cfg(feature = "object-store")]
+    ObjectStore {
+        url: String,
+        store: Arc<dyn ObjectStore>,
+    },
+
+    /// Explicit table name to segment paths mapping
+    Static(Arc<StaticMetadataProvider>),
+
+    /// Discovery through a caller-supplied metadata provider
+    Provider(Arc<dyn MetadataProvider>),
 }
 
 impl PinotCatalogBuilder {
@@ -140,7 +415,55 @@ impl PinotCatalogBuilder {
     ///     .build()?;
     /// ```
     pub fn filesystem<P: Into<PathBuf>>(mut self, data_dir: P) -> Self {
-        self.source = Some(PinotCatalogSource::FileSystem {
+        self.sources.push(PinotCatalogSource::FileSystem {
+            data_dir: data_dir.into(),
+        });
+        self
+    }
+
+    /// Configure catalog to scan several data directories
+    ///
+    /// Tables found in more than one directory are queried as one table over
+    /// all of their segments.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .filesystem_dirs([
+    ///         "/tmp/pinot/quickstart/PinotServerDataDir0",
+    ///         "/tmp/pinot/quickstart/PinotServerDataDir1",
+    ///     ])
+    ///     .build()?;
+    /// ```
+    pub fn filesystem_dirs<I, P>(mut self, data_dirs: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.sources.push(PinotCatalogSource::FileSystemDirs {
+            data_dirs: data_dirs.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// Configure catalog to serve each subdirectory of `data_dir` as a schema
+    ///
+    /// Every immediate subdirectory is scanned like a data directory and its
+    /// tables are served under a schema named after it, replacing the
+    /// `default` schema. Subdirectories are listed when the catalog is built;
+    /// temporary (`tmp`, `tmp-<uuid>`, `*.tmp`), hidden and
+    /// `*_OFFLINE`/`*_REALTIME` table directories are not schemas.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // /data/pinot/marketing/campaigns_OFFLINE, /data/pinot/sales/orders_OFFLINE
+    /// let catalog = PinotCatalog::builder()
+    ///     .filesystem_schemas("/data/pinot")
+    ///     .build()?;
+    /// // SELECT * FROM pinot.sales.orders
+    /// ```
+    pub fn filesystem_schemas<P: Into<PathBuf>>(mut self, data_dir: P) -> Self {
+        self.sources.push(PinotCatalogSource::FileSystemSchemas {
             data_dir: data_dir.into(),
         });
         self
@@ -166,22 +489,15 @@ impl PinotCatalogBuilder {
     #[cfg(feature = "controller")]
     pub fn controller(mut self, base_url: impl Into<String>) -> Self {
         // If we already have a controller source, update the URL
-        // Otherwise, create a new controller source with empty segment_dir
-        match self.source {
+        // Otherwise, add a new controller source with empty segment_dir
+        match self.controller_source() {
             Some(PinotCatalogSource::Controller {
-                ref segment_dir, ..
-            }) => {
-                self.source = Some(PinotCatalogSource::Controller {
-                    base_url: base_url.into(),
-                    segment_dir: segment_dir.clone(),
-                });
-            }
-            _ => {
-                self.source = Some(PinotCatalogSource::Controller {
-                    base_url: base_url.into(),
-                    segment_dir: PathBuf::new(),
-                });
-            }
+                base_url: existing, ..
+            }) => *existing = base_url.into(),
+            _ => self.sources.push(PinotCatalogSource::Controller {
+                base_url: base_url.into(),
+                segment_dir: PathBuf::new(),
+            }),
         }
         self
     }
@@ -195,171 +511,1168 @@ impl PinotCatalogBuilder {
     #[cfg(feature = "controller")]
     pub fn with_segment_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
         // If we already have a controller source, update the segment_dir
-        // Otherwise, create a new controller source with empty base_url
-        match self.source {
-            Some(PinotCatalogSource::Controller { ref base_url, .. }) => {
-                self.source = Some(PinotCatalogSource::Controller {
-                    base_url: base_url.clone(),
-                    segment_dir: dir.into(),
-                });
-            }
-            _ => {
-                self.source = Some(PinotCatalogSource::Controller {
-                    base_url: String::new(),
-                    segment_dir: dir.into(),
-                });
-            }
+        // Otherwise, add a new controller source with empty base_url
+        match self.controller_source() {
+            Some(PinotCatalogSource::Controller { segment_dir, .. }) => *segment_dir = dir.into(),
+            _ => self.sources.push(PinotCatalogSource::Controller {
+                base_url: String::new(),
+                segment_dir: dir.into(),
+            }),
         }
         self
     }
 
-    /// Build the PinotCatalog
-    ///
-    /// # Errors
-    /// Returns error if:
-    /// - No source has been configured
-    /// - Data directory doesn't exist (filesystem mode)
-    /// - Controller URL or segment directory missing (controller mode)
-    pub fn build(self) -> Result<PinotCatalog> {
-        let source = self
-            .source
-            .ok_or_else(|| Error::Internal("No catalog source configured".to_string()))?;
-
-        match source {
-            PinotCatalogSource::FileSystem { data_dir } => {
-                if !data_dir.exists() {
-                    return Err(Error::Internal(format!(
-                        "Data directory does not exist: {}",
-                        data_dir.display()
-                    )));
-                }
-
-                let metadata_provider = Arc::new(FileSystemMetadataProvider::new(data_dir));
-                Ok(PinotCatalog::from_provider(metadata_provider))
-            }
-
-            #[cfg(feature = "controller")]
-            PinotCatalogSource::Controller {
-                base_url,
-                segment_dir,
-            } => {
-                if base_url.is_empty() {
-                    return Err(Error::Internal(
-                        "Controller URL not specified".to_string(),
-                    ));
-                }
-
-                if segment_dir.as_os_str().is_empty() {
-                    return Err(Error::Internal(
-                        "Segment directory not specified".to_string(),
-                    ));
-                }
-
-                if !segment_dir.exists() {
-                    return Err(Error::Internal(format!(
-                        "Segment directory does not exist: {}",
-                        segment_dir.display()
-                    )));
-                }
-
-                let client = Arc::new(PinotControllerClient::new(base_url));
-                let metadata_provider =
-                    Arc::new(ControllerMetadataProvider::new(client, segment_dir));
-                Ok(PinotCatalog::from_provider(metadata_provider))
-            }
-        }
+    /// The controller source added so far, if any
+    #[cfg(feature = "controller")]
+    fn controller_source(&mut self) -> Option<&mut PinotCatalogSource> {
+        self.sources
+            .iter_mut()
+            .find(|source| matches!(source, PinotCatalogSource::Controller { .. }))
     }
-}
-
-/// Schema provider for Pinot (discovers tables using MetadataProvider)
-#[derive(Debug)]
-pub struct PinotSchemaProvider {
-    metadata_provider: Arc<dyn MetadataProvider>,
-}
 
-impl PinotSchemaProvider {
-    pub fn new(metadata_provider: Arc<dyn MetadataProvider>) -> Self {
-        Self { metadata_provider }
+    /// Configure catalog to discover tables in an object store
+    ///
+    /// Requires the `object-store` feature to be enabled. Tables are the
+    /// `<table>_OFFLINE` and `<table>_REALTIME` directories under the path of
+    /// `url`; segments are downloaded into a local cache when their table is
+    /// first queried (see [`ObjectStoreMetadataProvider`]).
+    ///
+    /// # Example
+    /// ```ignore
+    /// let store = AmazonS3Builder::from_env().with_bucket_name("pinot").build()?;
+    /// let catalog = PinotCatalog::builder()
+    ///     .object_store("s3://pinot/deep-store", Arc::new(store))
+    ///     .with_segment_cache_dir("/var/cache/pinot")
+    ///     .build()?;
+    /// ```
+    #[cfg(feature = "object-store")]
+    pub fn object_store(mut self, url: impl Into<String>, store: Arc<dyn ObjectStore>) -> Self {
+        self.sources.push(PinotCatalogSource::ObjectStore {
+            url: url.into(),
+            store,
+        });
+        self
     }
-}
 
-#[async_trait::async_trait]
-impl SchemaProvider for PinotSchemaProvider {
-    fn as_any(&self) -> &dyn Any {
+    /// Download object store segments into `dir` instead of a directory
+    /// under the system temp directory
+    #[cfg(feature = "object-store")]
+    pub fn with_segment_cache_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.segment_cache_dir = Some(dir.into());
         self
     }
 
-    fn table_names(&self) -> Vec<String> {
-        // Convert async to sync by spawning a separate thread with its own runtime
-        // This avoids "cannot block_on within a runtime" errors
-        let provider = self.metadata_provider.clone();
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            rt.block_on(provider.list_tables()).unwrap_or_default()
-        })
-        .join()
-        .unwrap_or_default()
+    /// Check the source when building, failing fast if it is unusable
+    ///
+    /// In controller mode the controller is pinged; with
+    /// [`static_tables`](Self::static_tables) every segment path must exist.
+    /// Off by default, in which case a bad controller URL or path only
+    /// surfaces at the first query. Has no effect for other sources.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .controller("http://localhost:9000")
+    ///     .with_segment_dir("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .with_validate_on_build(true)
+    ///     .build()?;
+    /// ```
+    pub fn with_validate_on_build(mut self, validate: bool) -> Self {
+        self.validate_on_build = validate;
+        self
     }
 
-    async fn table(&self, name: &str) -> DataFusionResult<Option<Arc<dyn TableProvider>>> {
-        // DataFusion lowercases table names, so we need to find the actual case-sensitive name
-        let tables = self.metadata_provider.list_tables().await
-            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    /// Prefix every controller API path with `path`, e.g. `/pinot/v1` for a
+    /// controller behind a gateway
+    ///
+    /// See [`PinotControllerClient::with_base_path`]. Has no effect outside
+    /// controller mode.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .controller("http://gateway:8080")
+    ///     .controller_base_path("/pinot/v1")
+    ///     .with_segment_dir("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .build()?;
+    /// ```
+    #[cfg(feature = "controller")]
+    pub fn controller_base_path(mut self, path: &str) -> Self {
+        self.controller_base_path = Some(path.to_string());
+        self
+    }
 
-        // Find the actual table name (case-insensitive match)
-        let actual_name = tables.iter()
-            .find(|t| t.eq_ignore_ascii_case(name))
-            .map(|s| s.as_str());
+    /// Fail controller requests fast after `failure_threshold` consecutive
+    /// failures, probing the controller again every `reset_timeout`
+    ///
+    /// Spares a controller that is down from being asked for tables on every
+    /// lookup. See [`PinotControllerClient::with_circuit_breaker`]. Has no
+    /// effect outside controller mode.
+    #[cfg(feature = "controller")]
+    pub fn controller_circuit_breaker(mut self, failure_threshold: u32, reset_timeout: Duration) -> Self {
+        self.controller_circuit_breaker = Some((failure_threshold, reset_timeout));
+        self
+    }
 
-        let table_name = match actual_name {
-            Some(name) => name,
-            None => return Ok(None),
-        };
+    /// Authenticate every controller request with `auth`
+    ///
+    /// See [`PinotControllerClientBuilder::auth`](crate::controller::PinotControllerClientBuilder::auth). Has no effect outside
+    /// controller mode.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .controller("http://localhost:9000")
+    ///     .controller_auth(ControllerAuth::Basic {
+    ///         username: "admin".to_string(),
+    ///         password: "verysecret".to_string(),
+    ///     })
+    ///     .with_segment_dir("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .build()?;
+    /// ```
+    #[cfg(feature = "controller")]
+    pub fn controller_auth(mut self, auth: ControllerAuth) -> Self {
+        self.controller_auth = Some(auth);
+        self
+    }
 
-        // Get segment paths from metadata provider
-        let segment_paths = match self.metadata_provider.get_segment_paths(table_name).await {
-            Ok(paths) => paths,
-            Err(_) => return Ok(None),
-        };
+    /// Send the header `name: value` with every controller request
+    ///
+    /// The header is validated in `build()`; see
+    /// [`PinotControllerClientBuilder::header`](crate::controller::PinotControllerClientBuilder::header). Has no effect outside
+    /// controller mode.
+    #[cfg(feature = "controller")]
+    pub fn controller_header(mut self, name: &str, value: &str) -> Self {
+        self.controller_headers.push((name.to_string(), value.to_string()));
+        self
+    }
 
-        // Open table from segment paths
-        match PinotTable::open_segments(&segment_paths, table_name) {
-            Ok(table) => Ok(Some(Arc::new(table))),
-            Err(e) => Err(DataFusionError::External(Box::new(e))),
-        }
+    /// Present a client certificate to a controller requiring mutual TLS
+    ///
+    /// Requires the `tls` feature. The PEM data is parsed in `build()`; see
+    /// [`PinotControllerClient::with_client_cert`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .controller("https://pinot-controller:9000")
+    ///     .with_segment_dir("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .controller_root_cert(std::fs::read("ca.crt")?)
+    ///     .controller_client_cert(std::fs::read("client.crt")?, std::fs::read("client.key")?)
+    ///     .build()?;
+    /// ```
+    #[cfg(feature = "tls")]
+    pub fn controller_client_cert(
+        mut self,
+        cert_pem: impl Into<Vec<u8>>,
+        key_pem: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.client_cert = Some((cert_pem.into(), key_pem.into()));
+        self
     }
 
-    fn table_exist(&self, name: &str) -> bool {
-        // Convert async to sync by spawning a separate thread with its own runtime
-        // This avoids "cannot block_on within a runtime" errors
-        // Note: DataFusion lowercases table names, so we need case-insensitive matching
-        let provider = self.metadata_provider.clone();
-        let name = name.to_string();
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            let tables = rt.block_on(provider.list_tables()).unwrap_or_default();
-            // Case-insensitive search
-            tables.iter().any(|t| t.eq_ignore_ascii_case(&name))
-        })
-        .join()
-        .unwrap_or(false)
+    /// Trust the PEM CA certificate(s) for the controller's certificate
+    ///
+    /// Requires the `tls` feature; see [`PinotControllerClient::with_root_cert`].
+    #[cfg(feature = "tls")]
+    pub fn controller_root_cert(mut self, ca_pem: impl Into<Vec<u8>>) -> Self {
+        self.root_cert = Some(ca_pem.into());
+        self
     }
-}
+
+    /// Configure catalog to serve an explicit table name to segment paths
+    /// mapping
+    ///
+    /// Segment paths (`v3` directories) need not follow any directory layout.
+    /// To add or remove tables at runtime, pass a shared
+    /// [`StaticMetadataProvider`] to [`provider`](Self::provider) instead.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .static_tables(HashMap::from([(
+    ///         "events".to_string(),
+    ///         vec![PathBuf::from("/data/events_0/v3")],
+    ///     )]))
+    ///     .with_validate_on_build(true)
+    ///     .build()?;
+    /// ```
+    pub fn static_tables(mut self, tables: HashMap<String, Vec<PathBuf>>) -> Self {
+        self.sources.push(PinotCatalogSource::Static(Arc::new(
+            StaticMetadataProvider::new(tables),
+        )));
+        self
+    }
+
+    /// Configure catalog to discover tables through a custom metadata provider
+    ///
+    /// Use this for providers other than the built-in filesystem and
+    /// controller ones, e.g. an object-store provider or a test double.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .provider(Arc::new(MyMetadataProvider::new()))
+    ///     .build()?;
+    /// ```
+    pub fn provider(mut self, metadata_provider: Arc<dyn MetadataProvider>) -> Self {
+        self.sources.push(PinotCatalogSource::Provider(metadata_provider));
+        self
+    }
+
+    /// Serve the primary source's tables under the schema `name` instead of
+    /// `default`
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .filesystem("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .with_schema_name("analytics")
+    ///     .build()?;
+    /// // SELECT * FROM pinot.analytics."baseballStats"
+    /// ```
+    pub fn with_schema_name(mut self, name: &str) -> Self {
+        self.schema_name = Some(name.to_string());
+        self
+    }
+
+    /// Also serve the primary source's tables under the schema `name`
+    ///
+    /// Can be called once per alias. Every name shares one schema provider,
+    /// so a table opened (and cached) through one name is reused through
+    /// the others, and refreshing the catalog refreshes them all.
+    pub fn with_schema_alias(mut self, name: &str) -> Self {
+        self.schema_aliases.push(name.to_string());
+        self
+    }
+
+    /// Serve the tables of another cluster under the schema `name`
+    ///
+    /// Can be called once per cluster, with or without a primary source;
+    /// the primary source's tables stay in their own schema(s). Read options
+    /// and the table TTL apply to every cluster.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .add_cluster("cluster_a", Arc::new(FileSystemMetadataProvider::new("/data/a")))
+    ///     .add_cluster("cluster_b", Arc::new(FileSystemMetadataProvider::new("/data/b")))
+    ///     .build()?;
+    /// // SELECT * FROM pinot.cluster_a."baseballStats"
+    /// ```
+    pub fn add_cluster(mut self, name: &str, provider: Arc<dyn MetadataProvider>) -> Self {
+        self.clusters.push((name.to_string(), provider));
+        self
+    }
+
+    /// Re-open tables once they have been cached for `ttl`
+    ///
+    /// Opened tables are cached until [`PinotCatalog::refresh`] or
+    /// [`PinotCatalog::refresh_table`]; a TTL also re-lists a table's
+    /// segments, and the tables, periodically in long-running processes.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .filesystem("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .with_table_ttl(Duration::from_secs(300))
+    ///     .build()?;
+    /// ```
+    pub fn with_table_ttl(mut self, ttl: Duration) -> Self {
+        self.table_ttl = Some(ttl);
+        self
+    }
+
+    /// Pick up segments and tables that appear after they were first looked up
+    ///
+    /// Every table lookup re-lists the table's segments and re-opens the
+    /// table only when the list changed; a lookup of an unknown table
+    /// re-lists the tables. Listing a local data directory is cheap, but
+    /// with a controller each lookup costs a request.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .filesystem("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .with_change_detection(true)
+    ///     .build()?;
+    /// ```
+    pub fn with_change_detection(mut self, enabled: bool) -> Self {
+        self.change_detection = enabled;
+        self
+    }
+
+    /// Keep the table listing when a lookup misses it
+    ///
+    /// The catalog lists its tables when built, and `table_names()` and
+    /// `table_exist()` answer from the last listing without blocking. Off by
+    /// default: looking up a table missing from the listing lists the tables
+    /// again, so tables created after the catalog are found. When on, new
+    /// tables are only seen after [`PinotCatalog::refresh`], once the
+    /// listing outlives [`with_table_ttl`](Self::with_table_ttl), or on
+    /// lookup with [`with_change_detection`](Self::with_change_detection).
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .controller("http://localhost:9000")
+    ///     .with_segment_dir("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .with_table_name_cache(true)
+    ///     .build()?;
+    /// ```
+    pub fn with_table_name_cache(mut self, enabled: bool) -> Self {
+        self.cache_table_names = enabled;
+        self
+    }
+
+    /// Cache the primary source's table and segment lists for `ttl`
+    ///
+    /// Wraps whichever provider the source builds in a
+    /// [`CachedMetadataProvider`], so lookups that would ask the source
+    /// again, such as those of [`with_change_detection`](Self::with_change_detection)
+    /// or an expired [`with_table_ttl`](Self::with_table_ttl), are answered
+    /// from memory while fresh. [`PinotCatalog::refresh`] clears the cache.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .controller("http://localhost:9000")
+    ///     .with_segment_dir("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .with_change_detection(true)
+    ///     .with_metadata_cache(Duration::from_secs(10))
+    ///     .build()?;
+    /// ```
+    pub fn with_metadata_cache(mut self, ttl: Duration) -> Self {
+        self.metadata_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Expose only tables matching one of `patterns`
+    ///
+    /// Patterns are exact table names or globs (`*`, `?`, `[...]`), matched
+    /// case-insensitively. The filter applies to every schema of the catalog,
+    /// whatever its metadata provider; tables left out behave as if they did
+    /// not exist. Repeated calls add patterns.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .filesystem("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .include_tables(["baseballStats", "airline*"])
+    ///     .build()?;
+    /// ```
+    pub fn include_tables<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.include_tables.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Hide tables matching any of `patterns`
+    ///
+    /// Takes precedence over [`include_tables`](Self::include_tables): a
+    /// table matching both lists is hidden.
+    pub fn exclude_tables<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.exclude_tables.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Resolve SQL identifiers to Pinot's camelCase names regardless of case
+    ///
+    /// DataFusion lowercases unquoted identifiers, so without this
+    /// `SELECT playerid FROM pinot.default.baseballstats` fails on the
+    /// `playerID` column. With it, a table name resolves to the one table
+    /// matching it case-insensitively (an exact match wins, and a name
+    /// matching several tables only by case is an error), and columns are
+    /// reported lowercased through
+    /// [`PinotReadOptions::lowercase_column_names`], whatever the read
+    /// options say. Off by default.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .filesystem("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .with_case_insensitive_names(true)
+    ///     .build()?;
+    /// ```
+    pub fn with_case_insensitive_names(mut self, enabled: bool) -> Self {
+        self.case_insensitive_names = enabled;
+        self
+    }
+
+    /// Set the read options applied to every table the catalog opens
+    ///
+    /// # Example
+    /// ```ignore
+    /// let catalog = PinotCatalog::builder()
+    ///     .filesystem("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///     .with_read_options(PinotReadOptions::default().with_batch_size(1024))
+    ///     .build()?;
+    /// ```
+    pub fn with_read_options(mut self, options: PinotReadOptions) -> Self {
+        self.read_options = options;
+        self
+    }
+
+    /// Build the PinotCatalog
+    ///
+    /// # Errors
+    /// Returns error if:
+    /// - No source or cluster has been configured
+    /// - A schema name or alias is set without a primary source
+    /// - A schema or cluster name is repeated, or is `system`
+    /// - A table include or exclude pattern is not a valid glob
+    /// - Data directory doesn't exist (filesystem mode)
+    /// - Controller URL or segment directory missing (controller mode)
+    /// - Controller unreachable or a static segment path missing, with
+    ///   `with_validate_on_build(true)`
+    /// - Invalid controller TLS certificates (`tls` feature)
+    /// - Object store URL without a scheme (`object-store` feature)
+    pub fn build(self) -> Result<PinotCatalog> {
+        if self.sources.is_empty() && self.clusters.is_empty() {
+            return Err(Error::Internal("No catalog source configured".to_string()));
+        }
+
+        let table_filter = TableFilter::new(&self.include_tables, &self.exclude_tables)?;
+        let mut named: Vec<(String, Arc<PinotSchemaProvider>)> = Vec::new();
+        match self.sources.as_slice() {
+            [PinotCatalogSource::FileSystemSchemas { data_dir }] => {
+                if self.schema_name.is_some() || !self.schema_aliases.is_empty() {
+                    return Err(Error::Internal(
+                        "Schema names cannot be set with filesystem_schemas".to_string(),
+                    ));
+                }
+                for (name, schema_dir) in Self::schema_dirs(data_dir)? {
+                    let metadata_provider = Arc::new(FileSystemMetadataProvider::new(schema_dir));
+                    let provider = self.schema_provider(
+                        self.with_metadata_cache_of(metadata_provider),
+                        &table_filter,
+                    );
+                    named.push((name, provider));
+                }
+            }
+            [] if self.schema_name.is_some() || !self.schema_aliases.is_empty() => {
+                return Err(Error::Internal(
+                    "Schema name set without a primary catalog source".to_string(),
+                ));
+            }
+            [] => {}
+            sources => {
+                let provider = self.schema_provider(self.primary_provider(sources)?, &table_filter);
+                let primary = self.schema_name.as_deref().unwrap_or(DEFAULT_SCHEMA);
+                named.push((primary.to_string(), provider.clone()));
+                for alias in &self.schema_aliases {
+                    named.push((alias.clone(), provider.clone()));
+                }
+            }
+        }
+        for (name, metadata_provider) in &self.clusters {
+            named.push((
+                name.clone(),
+                self.schema_provider(metadata_provider.clone(), &table_filter),
+            ));
+        }
+
+        let mut schemas: Vec<(String, Vec<Arc<PinotSchemaProvider>>)> = Vec::new();
+        for (name, provider) in named {
+            if name == SYSTEM_SCHEMA || schemas.iter().any(|(existing, _)| *existing == name) {
+                return Err(Error::Internal(format!(
+                    "Schema name '{}' is already in use",
+                    name
+                )));
+            }
+            schemas.push((name, vec![provider]));
+        }
+        Ok(PinotCatalog { schemas })
+    }
+
+    /// Schema provider over `metadata_provider` with the builder's read
+    /// options, table TTL, change detection, table name cache, table filter
+    /// and name resolution
+    fn schema_provider(
+        &self,
+        metadata_provider: Arc<dyn MetadataProvider>,
+        table_filter: &TableFilter,
+    ) -> Arc<PinotSchemaProvider> {
+        let mut read_options = self.read_options.clone();
+        if self.case_insensitive_names {
+            read_options = read_options.with_lowercase_column_names(true);
+        }
+        let mut schema_provider = PinotSchemaProvider::new(metadata_provider)
+            .with_read_options(read_options)
+            .with_table_filter(table_filter.clone())
+            .with_case_insensitive_names(self.case_insensitive_names)
+            .with_change_detection(self.change_detection)
+            .with_table_name_cache(self.cache_table_names);
+        if let Some(ttl) = self.table_ttl {
+            schema_provider = schema_provider.with_table_ttl(ttl);
+        }
+        schema_provider.list_table_names_blocking();
+        Arc::new(schema_provider)
+    }
+
+    /// Metadata provider for the primary source
+    fn source_provider(&self, source: &PinotCatalogSource) -> Result<Arc<dyn MetadataProvider>> {
+        let metadata_provider: Arc<dyn MetadataProvider> = match source {
+            PinotCatalogSource::FileSystem { data_dir } => {
+                if !data_dir.exists() {
+                    return Err(Error::Internal(format!(
+                        "Data directory does not exist: {}",
+                        data_dir.display()
+                    )));
+                }
+
+                Arc::new(FileSystemMetadataProvider::new(data_dir.clone()))
+            }
+
+            PinotCatalogSource::FileSystemSchemas { .. } => {
+                return Err(Error::Internal(
+                    "filesystem_schemas cannot be combined with other sources".to_string(),
+                ));
+            }
+
+            PinotCatalogSource::FileSystemDirs { data_dirs } => {
+                if data_dirs.is_empty() {
+                    return Err(Error::Internal("No data directories specified".to_string()));
+                }
+
+                if let Some(data_dir) = data_dirs.iter().find(|dir| !dir.exists()) {
+                    return Err(Error::Internal(format!(
+                        "Data directory does not exist: {}",
+                        data_dir.display()
+                    )));
+                }
+
+                Arc::new(FileSystemMetadataProvider::new_multi(data_dirs.clone()))
+            }
+
+            #[cfg(feature = "controller")]
+            PinotCatalogSource::Controller {
+                base_url,
+                segment_dir,
+            } => {
+                if base_url.is_empty() {
+                    return Err(Error::Internal(
+                        "Controller URL not specified".to_string(),
+                    ));
+                }
+
+                if segment_dir.as_os_str().is_empty() {
+                    return Err(Error::Internal(
+                        "Segment directory not specified".to_string(),
+                    ));
+                }
+
+                if !segment_dir.exists() {
+                    return Err(Error::Internal(format!(
+                        "Segment directory does not exist: {}",
+                        segment_dir.display()
+                    )));
+                }
+
+                let mut builder = PinotControllerClient::builder(base_url.clone());
+                if let Some(auth) = &self.controller_auth {
+                    builder = builder.auth(auth.clone());
+                }
+                for (name, value) in &self.controller_headers {
+                    builder = builder.header(name, value);
+                }
+                let mut client = builder.build()?;
+                if let Some(base_path) = &self.controller_base_path {
+                    client = client.with_base_path(base_path);
+                }
+                if let Some((failure_threshold, reset_timeout)) = self.controller_circuit_breaker {
+                    client = client.with_circuit_breaker(failure_threshold, reset_timeout);
+                }
+                #[cfg(feature = "tls")]
+                {
+                    if let Some(ca_pem) = &self.root_cert {
+                        client = client.with_root_cert(ca_pem)?;
+                    }
+                    if let Some((cert_pem, key_pem)) = &self.client_cert {
+                        client = client.with_client_cert(cert_pem, key_pem)?;
+                    }
+                }
+                let client = Arc::new(client);
+                if self.validate_on_build {
+                    let client = client.clone();
+                    block_on(async move { client.ping().await })??;
+                }
+                Arc::new(ControllerMetadataProvider::new(client, segment_dir.clone()))
+            }
+
+            #[cfg(feature = "object-store")]
+            PinotCatalogSource::ObjectStore { url, store } => {
+                let mut provider = ObjectStoreMetadataProvider::new(url.clone(), store.clone())?;
+                if let Some(cache_dir) = &self.segment_cache_dir {
+                    provider = provider.with_cache_dir(cache_dir.clone());
+                }
+                Arc::new(provider)
+            }
+
+            PinotCatalogSource::Static(metadata_provider) => {
+                if self.validate_on_build {
+                    metadata_provider.validate()?;
+                }
+                metadata_provider.clone()
+            }
+
+            PinotCatalogSource::Provider(metadata_provider) => metadata_provider.clone(),
+        };
+        Ok(metadata_provider)
+    }
+
+    /// Metadata provider of the primary schema: the one source's provider,
+    /// or a [`ChainedMetadataProvider`] over all of them in the order they
+    /// were added
+    fn primary_provider(&self, sources: &[PinotCatalogSource]) -> Result<Arc<dyn MetadataProvider>> {
+        let metadata_provider = match sources {
+            [source] => self.source_provider(source)?,
+            sources => Arc::new(ChainedMetadataProvider::new(
+                sources
+                    .iter()
+                    .map(|source| self.source_provider(source))
+                    .collect::<Result<_>>()?,
+            )),
+        };
+        Ok(self.with_metadata_cache_of(metadata_provider))
+    }
+
+    /// `metadata_provider`, wrapped in a cache if
+    /// [`with_metadata_cache`](Self::with_metadata_cache) was set
+    fn with_metadata_cache_of(
+        &self,
+        metadata_provider: Arc<dyn MetadataProvider>,
+    ) -> Arc<dyn MetadataProvider> {
+        match self.metadata_cache_ttl {
+            Some(ttl) => Arc::new(CachedMetadataProvider::new(metadata_provider, ttl)),
+            None => metadata_provider,
+        }
+    }
+
+    /// Schema names and directories of the subdirectories of `data_dir`,
+    /// sorted by name
+    fn schema_dirs(data_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+        let entries = std::fs::read_dir(data_dir).map_err(|e| {
+            Error::Internal(format!(
+                "Failed to read data directory {}: {}",
+                data_dir.display(),
+                e
+            ))
+        })?;
+
+        let mut schema_dirs = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| Error::Internal(e.to_string()))?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let is_table_dir = name.ends_with("_OFFLINE") || name.ends_with("_REALTIME");
+            if !path.is_dir() || is_table_dir || is_temporary_dir_name(name) {
+                continue;
+            }
+            schema_dirs.push((name.to_string(), path.clone()));
+        }
+        schema_dirs.sort();
+        Ok(schema_dirs)
+    }
+}
+
+/// Table names a schema exposes, from the builder's include and exclude
+/// patterns
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TableFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl TableFilter {
+    fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Pattern::new(pattern).map_err(|e| {
+                        Error::Internal(format!("Invalid table pattern '{}': {}", pattern, e))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    /// Whether `table_name` matches an include pattern (or none are set) and
+    /// no exclude pattern
+    fn allows(&self, table_name: &str) -> bool {
+        let matches = |patterns: &[Pattern]| {
+            patterns.iter().any(|pattern| {
+                pattern.as_str().eq_ignore_ascii_case(table_name)
+                    || pattern.matches_with(table_name, TABLE_PATTERN_OPTIONS)
+            })
+        };
+        (self.include.is_empty() || matches(&self.include)) && !matches(&self.exclude)
+    }
+}
+
+const TABLE_PATTERN_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// Drive `future` to completion from sync code, such as catalog
+/// construction, `refresh()` and `merge()`
+///
+/// On a multi-threaded runtime the calling worker blocks in place, so the
+/// future runs on that runtime and the HTTP connections it opens stay
+/// usable. A current-thread runtime cannot be blocked without panicking, so
+/// there the future runs on a helper thread with its own runtime, as it does
+/// on a fresh runtime when no runtime is running at all.
+pub(crate) fn block_on<F>(future: F) -> Result<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    fn run<F: Future>(future: F) -> Result<F::Output> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map(|rt| rt.block_on(future))
+            .map_err(|e| Error::Internal(format!("Failed to start runtime: {}", e)))
+    }
+
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            Ok(tokio::task::block_in_place(|| handle.block_on(future)))
+        }
+        Ok(_) => std::thread::spawn(move || run(future))
+            .join()
+            .map_err(|_| Error::Internal("Blocking task panicked".to_string()))?,
+        Err(_) => run(future),
+    }
+}
+
+/// A [`PinotTable`], or a [`LazyPinotTable`] over a provider-supplied schema
+type CachedTable = Arc<dyn TableProvider>;
+
+/// Schema provider for Pinot (discovers tables using MetadataProvider)
+///
+/// Per-table segment paths and opened tables are cached after the first
+/// lookup, so repeated queries do not re-open segments; `refresh()` and
+/// `refresh_table()` clear them. The sync `table_names()` and
+/// `table_exist()` answer from the last table listing without blocking; the
+/// async `table()` lists the tables again when a name is missing from it
+/// (unless [`with_table_name_cache`](Self::with_table_name_cache) keeps it)
+/// or it has outlived the table TTL, and `refresh()` lists them again.
+/// Catalogs list the tables once when built. Tables whose schema the
+/// metadata provider knows are served as [`LazyPinotTable`]s, which open
+/// their segments on first scan, skipping those the provider's segment
+/// descriptors show a scan's filters exclude.
+#[derive(Debug)]
+pub struct PinotSchemaProvider {
+    metadata_provider: Arc<dyn MetadataProvider>,
+    /// Last table listing, served to the sync `SchemaProvider` methods, and
+    /// when it was made
+    table_names: RwLock<Option<(Vec<String>, Instant)>>,
+    /// Keep `table_names` when a lookup misses it, until refreshed
+    cache_table_names: bool,
+    segment_paths: RwLock<HashMap<String, Vec<PathBuf>>>,
+    /// Opened tables and when they were opened
+    tables: RwLock<HashMap<String, (CachedTable, Instant)>>,
+    /// Age after which a cached table is re-opened; `None` keeps it until refreshed
+    table_ttl: Option<Duration>,
+    /// Re-list segments on every lookup and re-open tables whose segments changed
+    change_detection: bool,
+    read_options: PinotReadOptions,
+    /// Tables listed by the metadata provider that are exposed
+    table_filter: TableFilter,
+    /// Reject table names matching several tables only by case
+    case_insensitive_names: bool,
+}
+
+impl PinotSchemaProvider {
+    pub fn new(metadata_provider: Arc<dyn MetadataProvider>) -> Self {
+        Self {
+            metadata_provider,
+            table_names: RwLock::new(None),
+            cache_table_names: false,
+            segment_paths: RwLock::new(HashMap::new()),
+            tables: RwLock::new(HashMap::new()),
+            table_ttl: None,
+            change_detection: false,
+            read_options: PinotReadOptions::default(),
+            table_filter: TableFilter::default(),
+            case_insensitive_names: false,
+        }
+    }
+
+    /// Re-list segments and re-open a cached table once it is `ttl` old, and
+    /// re-list the tables on lookup once their listing is
+    pub fn with_table_ttl(mut self, ttl: Duration) -> Self {
+        self.table_ttl = Some(ttl);
+        self
+    }
+
+    /// Re-list a table's segments on every lookup, re-opening the table when
+    /// they changed, and re-list the tables when a lookup finds none
+    pub fn with_change_detection(mut self, enabled: bool) -> Self {
+        self.change_detection = enabled;
+        self
+    }
+
+    /// Keep the listed table names until [`refresh`](Self::refresh) (or the
+    /// table TTL) instead of listing them again when a lookup misses them
+    pub fn with_table_name_cache(mut self, enabled: bool) -> Self {
+        self.cache_table_names = enabled;
+        self
+    }
+
+    /// Set the read options applied to every table this provider opens
+    pub fn with_read_options(mut self, options: PinotReadOptions) -> Self {
+        self.read_options = options;
+        self
+    }
+
+    /// Expose only the listed tables `table_filter` allows
+    pub(crate) fn with_table_filter(mut self, table_filter: TableFilter) -> Self {
+        self.table_filter = table_filter;
+        self
+    }
+
+    /// Fail lookups of a table name matching several tables only by case,
+    /// instead of taking the first
+    pub fn with_case_insensitive_names(mut self, enabled: bool) -> Self {
+        self.case_insensitive_names = enabled;
+        self
+    }
+
+    /// Read options applied to every table this provider opens
+    pub fn read_options(&self) -> &PinotReadOptions {
+        &self.read_options
+    }
+
+    /// Invalidate cached segment lists and opened tables, and list the
+    /// tables again
+    pub fn refresh(&self) {
+        self.segment_paths.write().unwrap().clear();
+        self.tables.write().unwrap().clear();
+        self.metadata_provider.refresh();
+        self.list_table_names_blocking();
+    }
+
+    /// Invalidate the cached segment list and opened table of one table
+    pub fn refresh_table(&self, table_name: &str) {
+        self.segment_paths
+            .write()
+            .unwrap()
+            .retain(|name, _| !name.eq_ignore_ascii_case(table_name));
+        self.tables
+            .write()
+            .unwrap()
+            .retain(|name, _| !name.eq_ignore_ascii_case(table_name));
+        self.metadata_provider.refresh();
+    }
+
+    /// Drop the opened table `table_name` (matched case-insensitively), so
+    /// the next lookup lists its segments and opens them again
+    ///
+    /// Unlike [`refresh_table`](Self::refresh_table), caches of the metadata
+    /// provider are left alone. Returns whether a table was cached.
+    pub fn evict(&self, table_name: &str) -> bool {
+        self.segment_paths
+            .write()
+            .unwrap()
+            .retain(|name, _| !name.eq_ignore_ascii_case(table_name));
+        let mut tables = self.tables.write().unwrap();
+        let cached = tables.len();
+        tables.retain(|name, _| !name.eq_ignore_ascii_case(table_name));
+        tables.len() < cached
+    }
+
+    /// Opened table, unless it was never opened or has outlived the TTL
+    fn cached_table(&self, table_name: &str) -> Option<CachedTable> {
+        let tables = self.tables.read().unwrap();
+        let (table, opened_at) = tables.get(table_name)?;
+        match self.table_ttl {
+            Some(ttl) if opened_at.elapsed() >= ttl => None,
+            _ => Some(table.clone()),
+        }
+    }
+
+    /// Whether a fresh listing of `table_name`'s segments differs from the
+    /// cached one, which it then replaces
+    ///
+    /// A failed listing counts as a change, so the lookup lists again.
+    async fn segments_changed(&self, table_name: &str) -> bool {
+        let listed = self.metadata_provider.get_segment_paths(table_name).await;
+        let mut segment_paths = self.segment_paths.write().unwrap();
+        match listed {
+            Ok(paths) if segment_paths.get(table_name) == Some(&paths) => false,
+            Ok(paths) => {
+                segment_paths.insert(table_name.to_string(), paths);
+                true
+            }
+            Err(_) => {
+                segment_paths.remove(table_name);
+                true
+            }
+        }
+    }
+
+    /// Table names of the last listing, listed again when there is none or
+    /// it has outlived the table TTL
+    ///
+    /// The flag tells whether the names were just listed.
+    async fn listed_table_names(&self) -> Result<(Vec<String>, bool)> {
+        if let Some((names, listed_at)) = self.table_names.read().unwrap().as_ref() {
+            if self.table_ttl.is_none_or(|ttl| listed_at.elapsed() < ttl) {
+                return Ok((names.clone(), false));
+            }
+        }
+        self.list_table_names().await.map(|names| (names, true))
+    }
+
+    /// Table names of the last listing, or of a new one if it is stale
+    pub(crate) async fn cached_table_names(&self) -> Result<Vec<String>> {
+        self.listed_table_names().await.map(|(names, _)| names)
+    }
+
+    /// List the tables from the metadata provider, replacing the listing the
+    /// sync methods answer from
+    pub(crate) async fn list_table_names(&self) -> Result<Vec<String>> {
+        let names = self.allowed_tables(self.metadata_provider.list_tables().await?);
+        *self.table_names.write().unwrap() = Some((names.clone(), Instant::now()));
+        Ok(names)
+    }
+
+    /// List the tables through [`block_on`], for catalog construction and
+    /// `refresh()`
+    ///
+    /// A failed listing is logged and leaves no listing, so the sync methods
+    /// report no tables until a lookup lists them.
+    pub(crate) fn list_table_names_blocking(&self) {
+        let provider = self.metadata_provider.clone();
+        let names = match block_on(async move { provider.list_tables().await }) {
+            Ok(Ok(names)) => Some((self.allowed_tables(names), Instant::now())),
+            Ok(Err(e)) | Err(e) => {
+                tracing::warn!(error = %e, "failed to list tables");
+                None
+            }
+        };
+        *self.table_names.write().unwrap() = names;
+    }
+
+    /// Table names of the last listing, for sync DataFusion callbacks
+    ///
+    /// Never lists: before the first listing there are no tables.
+    fn last_table_names(&self) -> Vec<String> {
+        self.table_names
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|(names, _)| names.clone())
+            .unwrap_or_default()
+    }
+
+    /// Case-preserved name of the listed table `name` refers to
+    ///
+    /// DataFusion lowercases unquoted table names, so names also match
+    /// case-insensitively. An exact match wins; otherwise the first match is
+    /// taken, or with case-insensitive names the only one.
+    fn resolve_table_name<'a>(&self, tables: &'a [String], name: &str) -> Result<Option<&'a str>> {
+        if let Some(exact) = tables.iter().find(|t| *t == name) {
+            return Ok(Some(exact));
+        }
+        let mut matches = tables.iter().filter(|t| t.eq_ignore_ascii_case(name));
+        let first = matches.next();
+        if self.case_insensitive_names && matches.next().is_some() {
+            let candidates: Vec<&str> = tables
+                .iter()
+                .filter(|t| t.eq_ignore_ascii_case(name))
+                .map(|t| t.as_str())
+                .collect();
+            return Err(Error::Internal(format!(
+                "Table name '{}' is ambiguous: it matches {}",
+                name,
+                candidates.join(", ")
+            )));
+        }
+        Ok(first.map(|t| t.as_str()))
+    }
+
+    /// Drop listed tables the table filter hides
+    fn allowed_tables(&self, mut names: Vec<String>) -> Vec<String> {
+        names.retain(|name| self.table_filter.allows(name));
+        names
+    }
+
+    /// Segment paths of a table listed from the metadata provider now,
+    /// leaving the cached ones the opened tables were built from alone
+    pub(crate) async fn list_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
+        self.metadata_provider.get_segment_paths(table_name).await
+    }
+
+    /// Segment paths for a table, fetched from the metadata provider on first use
+    pub(crate) async fn cached_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
+        if let Some(paths) = self.segment_paths.read().unwrap().get(table_name) {
+            return Ok(paths.clone());
+        }
+
+        let paths = self.metadata_provider.get_segment_paths(table_name).await?;
+        self.segment_paths
+            .write()
+            .unwrap()
+            .insert(table_name.to_string(), paths.clone());
+        Ok(paths)
+    }
+}
+
+#[async_trait::async_trait]
+impl SchemaProvider for PinotSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        self.last_table_names()
+    }
+
+    async fn table(&self, name: &str) -> DataFusionResult<Option<Arc<dyn TableProvider>>> {
+        // DataFusion lowercases table names, so we need to find the actual case-sensitive name
+        let (mut tables, just_listed) = self
+            .listed_table_names()
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        // The table may have appeared since the tables were listed
+        if !just_listed
+            && (!self.cache_table_names || self.change_detection)
+            && self
+                .resolve_table_name(&tables, name)
+                .map_err(|e| DataFusionError::External(Box::new(e)))?
+                .is_none()
+        {
+            tables = self
+                .list_table_names()
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        }
+
+        let actual_name = self
+            .resolve_table_name(&tables, name)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        let table_name = match actual_name {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+
+        if let Some(table) = self.cached_table(table_name) {
+            if !self.change_detection || !self.segments_changed(table_name).await {
+                return Ok(Some(table));
+            }
+            // Re-open over the segment list segments_changed just cached
+            self.tables.write().unwrap().remove(table_name);
+        } else if self.tables.write().unwrap().remove(table_name).is_some() {
+            // Expired: list the segments again
+            self.segment_paths.write().unwrap().remove(table_name);
+        }
+
+        // Get segment paths from metadata provider
+        // A listed table whose segments cannot be listed is an error, not
+        // a missing table
+        let segment_paths = self
+            .cached_segment_paths(table_name)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        // A schema known to the provider spares opening the segments until
+        // a query scans them
+        let schema = match self.metadata_provider.get_table_schema(table_name).await {
+            Ok(schema) => schema,
+            Err(e) => {
+                tracing::warn!(
+                    table = table_name,
+                    error = %e,
+                    "failed to fetch table schema, deriving it from the segments"
+                );
+                None
+            }
+        };
+        let table: CachedTable = match schema {
+            Some(schema) => {
+                let table = LazyPinotTable::new(
+                    table_name,
+                    schema,
+                    segment_paths,
+                    self.read_options.clone(),
+                );
+                // Descriptors let scans skip opening segments their filters exclude
+                match self.metadata_provider.get_segment_infos(table_name).await {
+                    Ok(descriptors) => Arc::new(table.with_segment_descriptors(descriptors)),
+                    Err(e) => {
+                        tracing::warn!(
+                            table = table_name,
+                            error = %e,
+                            "failed to describe segments, scans will open all of them"
+                        );
+                        Arc::new(table)
+                    }
+                }
+            }
+            None => Arc::new(
+                PinotTable::open_segments_with_options(
+                    &segment_paths,
+                    table_name,
+                    self.read_options.clone(),
+                )
+                .map_err(|e| DataFusionError::External(Box::new(e)))?,
+            ),
+        };
+        self.tables
+            .write()
+            .unwrap()
+            .insert(table_name.to_string(), (table.clone(), Instant::now()));
+        Ok(Some(table))
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        // Note: DataFusion lowercases table names, so we need case-insensitive matching
+        self.last_table_names()
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(name))
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const DATA_DIR: &str = "/tmp/pinot/quickstart/PinotServerDataDir0";
+    /// Data directory holding a `baseballStats` table, as the quickstart's does
+    fn quickstart_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        pinot_segment::testutil::write_baseball_stats(
+            &dir.path()
+                .join("baseballStats_OFFLINE/baseballStats_OFFLINE_0/v3"),
+            100,
+        )
+        .unwrap();
+        dir
+    }
 
     #[test]
     fn test_catalog_creation() {
-        if !Path::new(DATA_DIR).exists() {
-            println!("Skipping test: data directory not found");
-            return;
-        }
-
-        let catalog = PinotCatalog::new(DATA_DIR).expect("Failed to create catalog");
+        let dir = quickstart_dir();
+        let catalog = PinotCatalog::new(dir.path()).expect("Failed to create catalog");
 
         let schema_names = catalog.schema_names();
         assert!(schema_names.contains(&"default".to_string()));
@@ -367,52 +1680,36 @@ mod tests {
 
     #[test]
     fn test_discover_tables() {
-        if !Path::new(DATA_DIR).exists() {
-            println!("Skipping test: data directory not found");
-            return;
-        }
-
-        let catalog = PinotCatalog::new(DATA_DIR).expect("Failed to create catalog");
+        let dir = quickstart_dir();
+        let catalog = PinotCatalog::new(dir.path()).expect("Failed to create catalog");
         let schema = catalog.schema("default").expect("Should have default schema");
 
-        let table_names = schema.table_names();
-        println!("Discovered tables: {:?}", table_names);
-
-        assert!(!table_names.is_empty(), "Should discover at least one table");
-        assert!(table_names.contains(&"baseballStats".to_string()));
+        assert_eq!(schema.table_names(), vec!["baseballStats".to_string()]);
     }
 
     #[tokio::test]
     async fn test_get_table() {
-        if !Path::new(DATA_DIR).exists() {
-            println!("Skipping test: data directory not found");
-            return;
-        }
-
-        let catalog = PinotCatalog::new(DATA_DIR).expect("Failed to create catalog");
+        let dir = quickstart_dir();
+        let catalog = PinotCatalog::new(dir.path()).expect("Failed to create catalog");
         let schema = catalog.schema("default").expect("Should have default schema");
 
         let table = schema
             .table("baseballStats")
             .await
-            .expect("Failed to get table");
-
-        assert!(table.is_some(), "Should find baseballStats table");
+            .expect("Failed to get table")
+            .expect("Should find baseballStats table");
 
-        let table = table.unwrap();
         let schema = table.schema();
-        assert!(schema.fields().len() > 0, "Table should have fields");
+        for column in ["hits", "homeRuns", "teamID", "playerID"] {
+            assert!(schema.field_with_name(column).is_ok(), "{}", column);
+        }
     }
 
     #[test]
     fn test_builder_filesystem_mode() {
-        if !Path::new(DATA_DIR).exists() {
-            println!("Skipping test: data directory not found");
-            return;
-        }
-
+        let dir = quickstart_dir();
         let catalog = PinotCatalog::builder()
-            .filesystem(DATA_DIR)
+            .filesystem(dir.path())
             .build()
             .expect("Failed to build catalog");
 
@@ -442,17 +1739,216 @@ mod tests {
             .contains("does not exist"));
     }
 
+    #[tokio::test]
+    async fn test_refresh_discovers_new_tables() {
+        use pinot_segment::testutil::SegmentBuilder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write_table = |name: &str| {
+            let segment_dir = dir
+                .path()
+                .join(format!("{}_OFFLINE", name))
+                .join(format!("{}_0", name))
+                .join("v3");
+            SegmentBuilder::new(name, &format!("{}_0", name))
+                .int_column("id", vec![1, 2, 3])
+                .write(&segment_dir)
+                .unwrap();
+        };
+
+        write_table("first");
+        let catalog = PinotCatalog::builder()
+            .filesystem(dir.path())
+            .with_table_name_cache(true)
+            .build()
+            .expect("Failed to create catalog");
+        let schema = catalog.schema("default").unwrap();
+        assert_eq!(schema.table_names(), vec!["first".to_string()]);
+
+        // Cached until refreshed
+        write_table("second");
+        assert!(!schema.table_exist("second"));
+        assert!(schema.table("second").await.unwrap().is_none());
+
+        catalog.refresh();
+        assert_eq!(
+            schema.table_names(),
+            vec!["first".to_string(), "second".to_string()]
+        );
+        assert!(schema.table("second").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_new_tables_found_without_table_name_cache() {
+        use pinot_segment::testutil::SegmentBuilder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write_table = |name: &str| {
+            SegmentBuilder::new(name, &format!("{}_0", name))
+                .int_column("id", vec![1, 2, 3])
+                .write(&dir.path().join(format!("{}_OFFLINE/{}_0/v3", name, name)))
+                .unwrap();
+        };
+
+        write_table("first");
+        let catalog = PinotCatalog::new(dir.path()).expect("Failed to create catalog");
+        let schema = catalog.schema("default").unwrap();
+        assert_eq!(schema.table_names(), vec!["first".to_string()]);
+
+        // A lookup missing the listing lists again, no refresh needed; the
+        // sync methods only see the new listing afterwards
+        write_table("second");
+        assert!(!schema.table_exist("second"));
+        assert!(schema.table("second").await.unwrap().is_some());
+        assert!(schema.table_exist("second"));
+        assert_eq!(
+            schema.table_names(),
+            vec!["first".to_string(), "second".to_string()]
+        );
+    }
+
+    /// Serves a fixed table -> segment paths mapping
+    #[derive(Debug)]
+    struct StaticProvider {
+        tables: Vec<(String, Vec<PathBuf>)>,
+    }
+
+    #[async_trait::async_trait]
+    impl MetadataProvider for StaticProvider {
+        async fn list_tables(&self) -> Result<Vec<String>> {
+            Ok(self.tables.iter().map(|(name, _)| name.clone()).collect())
+        }
+
+        async fn get_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
+            self.tables
+                .iter()
+                .find(|(name, _)| name == table_name)
+                .map(|(_, paths)| paths.clone())
+                .ok_or_else(|| Error::Internal(format!("Table not found: {}", table_name)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builder_custom_provider() {
+        use pinot_segment::testutil::SegmentBuilder;
+
+        // Segments live outside any Pinot data directory layout
+        let dir = tempfile::tempdir().unwrap();
+        let segment_dir = dir.path().join("anywhere");
+        SegmentBuilder::new("events", "events_0")
+            .int_column("id", vec![1, 2, 3])
+            .write(&segment_dir)
+            .unwrap();
+
+        let provider = Arc::new(StaticProvider {
+            tables: vec![("events".to_string(), vec![segment_dir])],
+        });
+        let catalog = PinotCatalog::builder()
+            .provider(provider)
+            .with_read_options(PinotReadOptions::default().with_batch_size(2))
+            .build()
+            .expect("Failed to build catalog");
+
+        let schema = catalog.schema("default").unwrap();
+        assert_eq!(schema.table_names(), vec!["events".to_string()]);
+        let table = schema.table("events").await.unwrap().unwrap();
+        let table = table.as_any().downcast_ref::<PinotTable>().unwrap();
+        assert_eq!(table.segment_doc_counts(), vec![("events_0", 3)]);
+        let provider = schema.as_any().downcast_ref::<PinotSchemaProvider>().unwrap();
+        assert_eq!(provider.read_options().batch_size, 2);
+        assert!(schema.table("missing").await.unwrap().is_none());
+    }
+
+    /// Lists a table whose segments cannot be listed
+    #[derive(Debug)]
+    struct UnlistableSegmentsProvider;
+
+    #[async_trait::async_trait]
+    impl MetadataProvider for UnlistableSegmentsProvider {
+        async fn list_tables(&self) -> Result<Vec<String>> {
+            Ok(vec!["events".to_string()])
+        }
+
+        async fn get_segment_paths(&self, _table_name: &str) -> Result<Vec<PathBuf>> {
+            Err(Error::Internal("segment listing failed".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_table_reports_segment_listing_errors() {
+        let catalog = PinotCatalog::builder()
+            .provider(Arc::new(UnlistableSegmentsProvider))
+            .build()
+            .unwrap();
+        let schema = catalog.schema("default").unwrap();
+
+        // The table is listed, so failing to list its segments is an error
+        // rather than "table not found"
+        let err = schema.table("events").await.unwrap_err();
+        assert!(matches!(err, DataFusionError::External(_)), "{:?}", err);
+        assert!(err.to_string().contains("segment listing failed"), "{}", err);
+        assert!(schema.table("missing").await.unwrap().is_none());
+    }
+
+    /// Lists one table, counting the listings
+    #[derive(Debug, Default)]
+    struct CountingListProvider {
+        listings: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl MetadataProvider for CountingListProvider {
+        async fn list_tables(&self) -> Result<Vec<String>> {
+            self.listings.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(vec!["events".to_string()])
+        }
+
+        async fn get_segment_paths(&self, _table_name: &str) -> Result<Vec<PathBuf>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_sync_table_names_use_last_listing() {
+        use std::sync::atomic::Ordering;
+
+        let provider = Arc::new(CountingListProvider::default());
+        let catalog = PinotCatalog::builder()
+            .provider(provider.clone())
+            .with_table_ttl(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let schema = catalog.schema("default").unwrap();
+        assert_eq!(provider.listings.load(Ordering::SeqCst), 1);
+
+        for _ in 0..3 {
+            assert_eq!(schema.table_names(), vec!["events".to_string()]);
+            assert!(schema.table_exist("EVENTS"));
+        }
+        assert_eq!(provider.listings.load(Ordering::SeqCst), 1);
+
+        // A lookup missing the listing lists again, once
+        assert!(schema.table("missing").await.unwrap().is_none());
+        assert_eq!(provider.listings.load(Ordering::SeqCst), 2);
+
+        // So does a lookup after the listing outlived the TTL
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let _ = schema.table("events").await;
+        assert_eq!(provider.listings.load(Ordering::SeqCst), 3);
+        assert!(schema.table_exist("events"));
+        assert_eq!(provider.listings.load(Ordering::SeqCst), 3);
+
+        catalog.refresh();
+        assert_eq!(provider.listings.load(Ordering::SeqCst), 4);
+    }
+
     #[cfg(feature = "controller")]
     #[test]
     fn test_builder_controller_mode() {
-        if !Path::new(DATA_DIR).exists() {
-            println!("Skipping test: data directory not found");
-            return;
-        }
-
+        let dir = quickstart_dir();
         let result = PinotCatalog::builder()
             .controller("http://localhost:9000")
-            .with_segment_dir(DATA_DIR)
+            .with_segment_dir(dir.path())
             .build();
 
         // Should succeed in building (controller connection will be tested separately)
@@ -462,8 +1958,9 @@ mod tests {
     #[cfg(feature = "controller")]
     #[test]
     fn test_builder_controller_missing_url() {
+        let dir = quickstart_dir();
         let result = PinotCatalog::builder()
-            .with_segment_dir(DATA_DIR)
+            .with_segment_dir(dir.path())
             .build();
 
         assert!(result.is_err());
@@ -0,0 +1,243 @@
+//! One-line registration of Pinot catalogs and segments on a `SessionContext`
+//!
+//! Also home of the `pinot_segment` and `pinot_table` table functions, which
+//! query a segment or table directory straight from SQL.
+
+use datafusion::catalog::{TableFunctionImpl, TableProvider};
+use datafusion::common::{plan_datafusion_err, plan_err, ScalarValue};
+use datafusion::dataframe::DataFrame;
+use datafusion::error::Result as DataFusionResult;
+use datafusion::execution::SessionStateBuilder;
+use datafusion::logical_expr::Expr;
+use datafusion::prelude::SessionContext;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::catalog::{PinotCatalog, PinotCatalogBuilder};
+use crate::error::{Error, Result};
+use crate::segment_info::SegmentInfoColumnsRule;
+use crate::star_tree::StarTreeRule;
+use crate::table::PinotTable;
+
+/// Pinot helpers for [`SessionContext`]
+///
+/// # Example
+/// ```no_run
+/// use datafusion::prelude::SessionContext;
+/// use datafusion_pinot::SessionContextExt;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let ctx = SessionContext::new();
+/// ctx.register_pinot("pinot", "/tmp/pinot/quickstart/PinotServerDataDir0")?;
+/// ctx.sql("SELECT COUNT(*) FROM pinot.default.\"baseballStats\"")
+///     .await?
+///     .show()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait SessionContextExt {
+    /// Register a filesystem catalog over `data_dir` as `catalog_name`
+    ///
+    /// Shorthand for [`PinotCatalog::new`] plus `register_catalog`; tables
+    /// are then queried as `<catalog_name>.default."<table>"`. Also registers
+    /// the segment info rule (see
+    /// [`register_pinot_segment_info_rule`](Self::register_pinot_segment_info_rule)).
+    ///
+    /// # Errors
+    /// Returns error if `data_dir` does not exist.
+    fn register_pinot<P: AsRef<Path>>(&self, catalog_name: &str, data_dir: P) -> Result<()>;
+
+    /// Build `builder` and register the catalog as `catalog_name`, along
+    /// with the segment info rule
+    ///
+    /// # Errors
+    /// Returns the error of [`PinotCatalogBuilder::build`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use datafusion::prelude::SessionContext;
+    /// use datafusion_pinot::{PinotCatalog, SessionContextExt};
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let ctx = SessionContext::new();
+    /// ctx.register_pinot_catalog(
+    ///     "pinot",
+    ///     PinotCatalog::builder()
+    ///         .filesystem("/tmp/pinot/quickstart/PinotServerDataDir0")
+    ///         .with_case_insensitive_names(true),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn register_pinot_catalog(
+        &self,
+        catalog_name: &str,
+        builder: PinotCatalogBuilder,
+    ) -> Result<()>;
+
+    /// DataFrame over the segment directory at `path`
+    ///
+    /// # Errors
+    /// Returns error if the segment cannot be opened.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use datafusion::prelude::SessionContext;
+    /// use datafusion_pinot::SessionContextExt;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_pinot_segment("/path/to/segment/v3")?;
+    /// df.limit(0, Some(10))?.show().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn read_pinot_segment<P: AsRef<Path>>(&self, path: P) -> Result<DataFrame>;
+
+    /// Register the `pinot_segment` and `pinot_table` table functions
+    ///
+    /// # Example
+    /// ```no_run
+    /// use datafusion::prelude::SessionContext;
+    /// use datafusion_pinot::SessionContextExt;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let ctx = SessionContext::new();
+    /// ctx.register_pinot_functions();
+    /// ctx.sql("SELECT * FROM pinot_segment('/path/to/segment/v3') LIMIT 5")
+    ///     .await?
+    ///     .show()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn register_pinot_functions(&self);
+
+    /// Add [`SegmentInfoColumnsRule`] in front of the session's analyzer rules
+    ///
+    /// The `__segment_name__` and `__segment_index__` columns of tables with
+    /// segment info columns are then only returned when a query names them,
+    /// not by `SELECT *` or `DESCRIBE`. Registering it again has no effect.
+    fn register_pinot_segment_info_rule(&self);
+
+    /// Add [`StarTreeRule`] to the session's optimizer rules
+    ///
+    /// Aggregations over Pinot tables whose segments all carry a matching
+    /// star-tree index are then answered from the pre-aggregated records;
+    /// `EXPLAIN` shows a `StarTreeExec` in place of the aggregate.
+    /// Registering it again has no effect.
+    fn register_pinot_star_tree_rule(&self);
+}
+
+impl SessionContextExt for SessionContext {
+    fn register_pinot<P: AsRef<Path>>(&self, catalog_name: &str, data_dir: P) -> Result<()> {
+        self.register_catalog(catalog_name, Arc::new(PinotCatalog::new(data_dir)?));
+        self.register_pinot_segment_info_rule();
+        Ok(())
+    }
+
+    fn register_pinot_catalog(
+        &self,
+        catalog_name: &str,
+        builder: PinotCatalogBuilder,
+    ) -> Result<()> {
+        self.register_catalog(catalog_name, Arc::new(builder.build()?));
+        self.register_pinot_segment_info_rule();
+        Ok(())
+    }
+
+    fn read_pinot_segment<P: AsRef<Path>>(&self, path: P) -> Result<DataFrame> {
+        let path = path.as_ref();
+        let table = PinotTable::open(path).map_err(|e| {
+            Error::Internal(format!("Failed to open segment {}: {}", path.display(), e))
+        })?;
+        self.read_table(Arc::new(table))
+            .map_err(|e| Error::DataFusion(e.to_string()))
+    }
+
+    fn register_pinot_functions(&self) {
+        self.register_udtf("pinot_segment", Arc::new(PinotSegmentFunction));
+        self.register_udtf("pinot_table", Arc::new(PinotTableFunction));
+    }
+
+    fn register_pinot_segment_info_rule(&self) {
+        let state = self.state_ref();
+        let mut state = state.write();
+        let mut rules = state.analyzer().rules.clone();
+        if rules
+            .iter()
+            .any(|rule| rule.name() == SegmentInfoColumnsRule::NAME)
+        {
+            return;
+        }
+        // Wildcards must still be unexpanded when the rule sees them
+        rules.insert(0, Arc::new(SegmentInfoColumnsRule::new()));
+        *state = SessionStateBuilder::new_from_existing(state.clone())
+            .with_analyzer_rules(rules)
+            .build();
+    }
+
+    fn register_pinot_star_tree_rule(&self) {
+        let registered = self
+            .state_ref()
+            .read()
+            .optimizers()
+            .iter()
+            .any(|rule| rule.name() == StarTreeRule::NAME);
+        if !registered {
+            self.add_optimizer_rule(Arc::new(StarTreeRule::new()));
+        }
+    }
+}
+
+/// `pinot_segment('<segment dir>')`: the segment as a table
+///
+/// The segment is opened while the query is planned, so a bad path fails
+/// planning.
+#[derive(Debug, Default)]
+pub struct PinotSegmentFunction;
+
+impl TableFunctionImpl for PinotSegmentFunction {
+    fn call(&self, args: &[Expr]) -> DataFusionResult<Arc<dyn TableProvider>> {
+        let path = path_argument("pinot_segment", args)?;
+        let table = PinotTable::open(&path).map_err(|e| {
+            plan_datafusion_err!("pinot_segment: failed to open segment {}: {}", path, e)
+        })?;
+        Ok(Arc::new(table))
+    }
+}
+
+/// `pinot_table('<table dir>')`: every segment of a table directory as one
+/// table
+///
+/// The segments are opened while the query is planned, so a bad path fails
+/// planning.
+#[derive(Debug, Default)]
+pub struct PinotTableFunction;
+
+impl TableFunctionImpl for PinotTableFunction {
+    fn call(&self, args: &[Expr]) -> DataFusionResult<Arc<dyn TableProvider>> {
+        let path = path_argument("pinot_table", args)?;
+        let table = PinotTable::open_table(&path).map_err(|e| {
+            plan_datafusion_err!("pinot_table: failed to open table {}: {}", path, e)
+        })?;
+        Ok(Arc::new(table))
+    }
+}
+
+/// The single string literal argument of table function `name`
+fn path_argument(name: &str, args: &[Expr]) -> DataFusionResult<String> {
+    match args {
+        [Expr::Literal(
+            ScalarValue::Utf8(Some(path))
+            | ScalarValue::LargeUtf8(Some(path))
+            | ScalarValue::Utf8View(Some(path)),
+        )] => Ok(path.clone()),
+        _ => plan_err!(
+            "{} takes one string literal path, e.g. {}('/path/to/dir')",
+            name,
+            name
+        ),
+    }
+}
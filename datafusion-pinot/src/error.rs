@@ -1,51 +1,130 @@
-use std::fmt;
-
-#[derive(Debug)]
+#[derive(Debug, thiserror::Error)]
 pub enum Error {
-    PinotSegment(pinot_segment::Error),
+    #[error("Pinot segment error: {0}")]
+    PinotSegment(#[from] pinot_segment::Error),
+    #[error("DataFusion error: {0}")]
     DataFusion(String),
+    #[error("Arrow error: {0}")]
     Arrow(String),
+    #[error("Internal error: {0}")]
     Internal(String),
+    #[error("Unsupported feature: {0}")]
     UnsupportedFeature(String),
 
     // Controller-specific errors (feature-gated)
     #[cfg(feature = "controller")]
+    #[error("HTTP client error: {0}")]
     HttpClient(String),
 
+    /// A controller request that failed, by why it failed
+    #[cfg(feature = "controller")]
+    #[error("HTTP {kind} error: {message}")]
+    HttpRequest { kind: HttpErrorKind, message: String },
+
     #[cfg(feature = "controller")]
+    #[error("JSON parse error: {0}")]
     JsonParse(String),
+
+    #[cfg(feature = "object-store")]
+    #[error("Object store error: {0}")]
+    ObjectStore(String),
 }
 
-impl fmt::Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Error {
+    /// Whether the error is a missing segment file rather than corrupt data
+    pub fn is_not_found(&self) -> bool {
         match self {
-            Error::PinotSegment(e) => write!(f, "Pinot segment error: {}", e),
-            Error::DataFusion(msg) => write!(f, "DataFusion error: {}", msg),
-            Error::Arrow(msg) => write!(f, "Arrow error: {}", msg),
-            Error::Internal(msg) => write!(f, "Internal error: {}", msg),
-            Error::UnsupportedFeature(msg) => write!(f, "Unsupported feature: {}", msg),
+            Error::PinotSegment(e) => e.is_not_found(),
+            _ => false,
+        }
+    }
 
-            #[cfg(feature = "controller")]
-            Error::HttpClient(msg) => write!(f, "HTTP client error: {}", msg),
+    /// Why a controller request failed, for errors of controller requests
+    #[cfg(feature = "controller")]
+    pub fn http_error_kind(&self) -> Option<HttpErrorKind> {
+        match self {
+            Error::HttpRequest { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+}
 
-            #[cfg(feature = "controller")]
-            Error::JsonParse(msg) => write!(f, "JSON parse error: {}", msg),
+/// Category of a failed controller request
+#[cfg(feature = "controller")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpErrorKind {
+    /// No connection could be made (controller down, wrong host or port)
+    Connect,
+    /// No response within the client's timeout
+    Timeout,
+    /// The response body was not what the endpoint returns
+    Decode,
+    /// The controller answered with a non-success status
+    Status,
+    /// The controller rejected the request's credentials (401) or denied
+    /// them access (403)
+    Unauthorized,
+    /// Any other failure, such as an invalid URL
+    Request,
+}
+
+#[cfg(feature = "controller")]
+impl HttpErrorKind {
+    /// Category of a reqwest error; timeouts of the connect phase count as
+    /// timeouts
+    pub fn of(err: &reqwest::Error) -> Self {
+        if err.is_timeout() {
+            HttpErrorKind::Timeout
+        } else if err.is_connect() {
+            HttpErrorKind::Connect
+        } else if err.is_decode() {
+            HttpErrorKind::Decode
+        } else if err.is_status() {
+            HttpErrorKind::Status
+        } else {
+            HttpErrorKind::Request
         }
     }
 }
 
-impl std::error::Error for Error {}
+#[cfg(feature = "controller")]
+impl std::fmt::Display for HttpErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            HttpErrorKind::Connect => "connect",
+            HttpErrorKind::Timeout => "timeout",
+            HttpErrorKind::Decode => "decode",
+            HttpErrorKind::Status => "status",
+            HttpErrorKind::Unauthorized => "unauthorized",
+            HttpErrorKind::Request => "request",
+        };
+        f.write_str(name)
+    }
+}
 
-impl From<pinot_segment::Error> for Error {
-    fn from(err: pinot_segment::Error) -> Self {
-        Error::PinotSegment(err)
+/// `err` followed by its sources, as reqwest's own message leaves out the
+/// cause (e.g. "Connection refused")
+#[cfg(feature = "controller")]
+pub(crate) fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        let cause_message = cause.to_string();
+        if !message.contains(&cause_message) {
+            message = format!("{}: {}", message, cause_message);
+        }
+        source = cause.source();
     }
+    message
 }
 
 #[cfg(feature = "controller")]
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
-        Error::HttpClient(err.to_string())
+        Error::HttpRequest {
+            kind: HttpErrorKind::of(&err),
+            message: error_chain(&err),
+        }
     }
 }
 
@@ -56,4 +135,37 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(feature = "object-store")]
+impl From<object_store::Error> for Error {
+    fn from(err: object_store::Error) -> Self {
+        Error::ObjectStore(err.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_segment_errors_keep_their_source() {
+        let missing = pinot_segment::testutil::SegmentBuilder::new("t", "t_0");
+        let dir = tempfile::tempdir().unwrap();
+        missing.write(dir.path()).unwrap();
+        std::fs::remove_file(dir.path().join("index_map")).unwrap();
+
+        let err = crate::PinotTable::open(dir.path()).unwrap_err();
+        assert!(err.is_not_found(), "{}", err);
+        let segment_error = err
+            .source()
+            .unwrap()
+            .downcast_ref::<pinot_segment::Error>()
+            .unwrap();
+        let io_error = segment_error.source().unwrap().downcast_ref::<std::io::Error>();
+        assert_eq!(io_error.unwrap().kind(), std::io::ErrorKind::NotFound);
+
+        assert!(!Error::Internal("corrupt".to_string()).is_not_found());
+    }
+}
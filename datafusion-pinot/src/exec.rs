@@ -1,44 +1,93 @@
 use datafusion::arrow::array::{
-    ArrayRef, Float32Array, Float64Array, Int32Array, Int64Array, RecordBatch, StringArray,
+    make_array, new_null_array, Array, ArrayRef, BinaryBuilder, BooleanArray, BooleanBufferBuilder,
+    DictionaryArray, Float32Builder, Float64Builder, Int32Array, Int32Builder, Int64Builder,
+    RecordBatch, StringArray, StringBuilder, StringDictionaryBuilder, UInt32Array,
 };
-use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::buffer::{BooleanBuffer, NullBuffer};
+use datafusion::arrow::compute::{filter_record_batch, take_record_batch};
+use datafusion::arrow::datatypes::{DataType as ArrowDataType, Int32Type, Schema, SchemaRef};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::ipc::reader::StreamReader;
+use datafusion::arrow::ipc::writer::StreamWriter;
 use datafusion::arrow::record_batch::RecordBatchOptions;
+use datafusion::common::Statistics;
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::disk_manager::{DiskManager, RefCountedTempFile};
+use datafusion::execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion::execution::{RecordBatchStream, SendableRecordBatchStream, TaskContext};
-use datafusion::physical_expr::EquivalenceProperties;
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_expr::{EquivalenceProperties, LexOrdering, PhysicalSortExpr};
 use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::metrics::{
+    Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+};
 use datafusion::physical_plan::{
     DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties,
 };
 use futures::stream::Stream;
-use pinot_segment::{DataType as PinotDataType, SegmentReader};
+use futures::task::AtomicWaker;
+use pinot_segment::{DataType as PinotDataType, SegmentReader, ValueRange};
 use std::any::Any;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::fs::File;
+use std::future::Future;
+use std::io::{BufReader, BufWriter};
+use std::ops::Range;
+use std::path::PathBuf;
 use std::pin::Pin;
-use std::sync::Arc;
-use std::task::{Context, Poll};
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 use crate::error::{Error, Result};
-use crate::schema::create_projected_schema;
+use crate::options::PinotReadOptions;
+use crate::schema::{
+    create_projected_schema, is_segment_info_column, with_lowercase_names, with_nullability,
+    SEGMENT_INDEX_COLUMN, SEGMENT_NAME_COLUMN,
+};
+use crate::segment::SegmentSource;
+use crate::statistics::merge_segment_statistics;
 
-const BATCH_SIZE: usize = 8192;
+/// Initial value-buffer capacity per row for STRING and BYTES builders (grows as needed)
+const STRING_BYTES_PER_ROW_HINT: usize = 8;
+
+/// Batches a partition decodes ahead of its consumer
+const PRELOAD_BATCHES: usize = 2;
 
 /// Execution plan for reading Pinot segments (supports multi-segment tables)
 #[derive(Debug)]
 pub struct PinotExec {
-    segments: Vec<Arc<SegmentReader>>,
+    segments: Vec<Arc<dyn SegmentSource>>,
     schema: SchemaRef,
-    projection: Option<Vec<usize>>,
+    /// `schema` with the nullability segments are read with; differs only
+    /// when `nullable_schema` declares every output field nullable
+    read_schema: SchemaRef,
     plan_properties: PlanProperties,
+    /// Segments excluded by `PinotTable::scan` before planning
+    pruned_segments: usize,
+    metrics: ExecutionPlanMetricsSet,
+    options: PinotReadOptions,
+    /// Pushed-down numeric ranges, answered by segments with a range index
+    range_filters: Vec<(String, ValueRange)>,
+    /// Pushed-down `IS NULL` (true) / `IS NOT NULL` (false) filters on
+    /// nullable columns, answered exactly through null value vectors
+    null_filters: Vec<(String, bool)>,
+    /// Rows the consumer needs at most, pushed down from `LIMIT`
+    limit: Option<usize>,
+    /// Bytes of batches a partition buffers in memory before spilling
+    memory_limit: Option<usize>,
 }
 
 impl PinotExec {
     pub fn new(
-        segments: Vec<Arc<SegmentReader>>,
+        segments: Vec<Arc<dyn SegmentSource>>,
         schema: SchemaRef,
         projection: Option<Vec<usize>>,
     ) -> Self {
-        let num_partitions = segments.len();
+        // Keep a single (empty) partition when every segment was pruned
+        let num_partitions = segments.len().max(1);
 
         // Calculate projected schema
         let projected_schema = if let Some(ref proj) = projection {
@@ -47,8 +96,9 @@ impl PinotExec {
             schema.clone()
         };
 
+        let orderings = sorted_column_orderings(&segments, &projected_schema, &projected_schema);
         let plan_properties = PlanProperties::new(
-            EquivalenceProperties::new(projected_schema.clone()),
+            EquivalenceProperties::new_with_orderings(projected_schema.clone(), &orderings),
             Partitioning::UnknownPartitioning(num_partitions),
             EmissionType::Incremental,
             Boundedness::Bounded,
@@ -56,82 +106,252 @@ impl PinotExec {
 
         Self {
             segments,
+            read_schema: projected_schema.clone(),
             schema: projected_schema,
-            projection,
             plan_properties,
+            pruned_segments: 0,
+            metrics: ExecutionPlanMetricsSet::new(),
+            options: PinotReadOptions::default(),
+            range_filters: Vec::new(),
+            null_filters: Vec::new(),
+            limit: None,
+            memory_limit: None,
+        }
+    }
+
+    /// Read with the given table options (batch size, validDocIds)
+    ///
+    /// Options that change the schema (dictionary arrays, null substitution)
+    /// take effect through the schema passed to [`PinotExec::new`].
+    ///
+    /// With `nullable_schema` every output field becomes nullable, and with
+    /// `lowercase_column_names` output fields are renamed, while segments are
+    /// still read with the names and nullability of the schema passed in.
+    pub fn with_options(self, options: PinotReadOptions) -> Self {
+        if !options.nullable_schema && !options.lowercase_column_names {
+            return Self { options, ..self };
+        }
+
+        let mut schema = self.read_schema.clone();
+        if options.nullable_schema {
+            schema = with_nullability(&schema, |_| true);
+        }
+        if options.lowercase_column_names {
+            schema = with_lowercase_names(&schema);
+        }
+        let orderings = sorted_column_orderings(&self.segments, &self.read_schema, &schema);
+        let eq_properties = EquivalenceProperties::new_with_orderings(schema.clone(), &orderings);
+        Self {
+            plan_properties: self.plan_properties.clone().with_eq_properties(eq_properties),
+            schema,
+            options,
+            ..self
         }
     }
 
-    /// Read all columns once (optimization to avoid re-reading for each batch)
-    fn read_columns_once(
+    /// Restrict rows through range indexes for the given column ranges
+    ///
+    /// Segments without a range index on a column are read in full for it;
+    /// the filters must still be applied above this plan.
+    pub fn with_range_filters(self, range_filters: Vec<(String, ValueRange)>) -> Self {
+        Self {
+            range_filters,
+            ..self
+        }
+    }
+
+    /// Keep only rows where each column is null (`true`) or not null (`false`)
+    ///
+    /// Rows are selected exactly, so the filters need not be applied above
+    /// this plan. Only pass nullable columns: non-nullable ones are never
+    /// read as null.
+    pub fn with_null_filters(self, null_filters: Vec<(String, bool)>) -> Self {
+        Self {
+            null_filters,
+            ..self
+        }
+    }
+
+    /// Read at most `limit` documents from each segment
+    ///
+    /// Only the forward index bytes of those documents are read, so
+    /// `LIMIT 10` over a large segment decodes 10 rows instead of all of
+    /// them. Segments whose rows are narrowed by range indexes or
+    /// validDocIds are still read in full.
+    pub fn with_limit(self, limit: Option<usize>) -> Self {
+        Self { limit, ..self }
+    }
+
+    /// Buffer at most `memory_limit` bytes of batches per partition
+    ///
+    /// Batches are decoded on a blocking thread as fast as it can go rather
+    /// than a few ahead of the consumer. Once the buffered batches outgrow
+    /// the budget, or what the task's memory pool grants, the oldest ones are
+    /// spilled to an Arrow IPC file in the runtime's temporary directory and
+    /// read back when the stream reaches them.
+    pub fn with_memory_limit(self, memory_limit: usize) -> Self {
+        Self {
+            memory_limit: Some(memory_limit),
+            ..self
+        }
+    }
+
+    /// Record how many segments were pruned from the scan
+    ///
+    /// Shown in EXPLAIN output and reported as the `segments_pruned` metric.
+    pub fn with_pruned_segments(self, pruned_segments: usize) -> Self {
+        MetricBuilder::new(&self.metrics)
+            .global_counter("segments_pruned")
+            .add(pruned_segments);
+        Self {
+            pruned_segments,
+            ..self
+        }
+    }
+
+    /// Number of segments pruned from the scan
+    pub fn pruned_segments(&self) -> usize {
+        self.pruned_segments
+    }
+
+    /// Decode the columns of `schema` (the projected schema) from a segment
+    /// into a single RecordBatch
+    ///
+    /// Values are appended straight into pre-sized Arrow builders as they are
+    /// decoded from the forward index, so no intermediate `Vec` is materialized.
+    /// Columns are read once per segment; `execute` slices the result into batches.
+    /// Only the index regions of the columns in `schema` are read.
+    /// Documents flagged in the null value vector of a nullable field are
+    /// returned as nulls.
+    pub fn create_batch_with_builders(
         segment_reader: &SegmentReader,
-        _schema: &SchemaRef,
-        projection: &Option<Vec<usize>>,
-    ) -> Result<Vec<ArrayRef>> {
-        let column_names: Vec<String> = if let Some(ref proj) = projection {
-            proj.iter()
-                .map(|&idx| {
-                    segment_reader
-                        .metadata()
-                        .columns
-                        .keys()
-                        .nth(idx)
-                        .unwrap()
-                        .clone()
-                })
-                .collect()
-        } else {
-            segment_reader
-                .metadata()
-                .columns
-                .keys()
-                .cloned()
-                .collect()
-        };
+        schema: &SchemaRef,
+    ) -> Result<RecordBatch> {
+        let total_docs = segment_reader.metadata().total_docs;
+        Self::create_batch_in_range(segment_reader, schema, 0..total_docs)
+    }
+
+    /// Like [`create_batch_with_builders`](Self::create_batch_with_builders),
+    /// decoding only the documents in `docs`
+    ///
+    /// Only the parts of each forward index holding those documents are read.
+    pub fn create_batch_in_range(
+        segment_reader: &SegmentReader,
+        schema: &SchemaRef,
+        docs: Range<u32>,
+    ) -> Result<RecordBatch> {
+        Self::decode_batch_in_range(segment_reader, schema, docs, None)
+    }
+
+    /// [`create_batch_in_range`](Self::create_batch_in_range), with the nulls
+    /// of nullable columns sliced from `null_masks` when given rather than
+    /// read from the segment
+    pub(crate) fn decode_batch_in_range(
+        segment_reader: &SegmentReader,
+        schema: &SchemaRef,
+        docs: Range<u32>,
+        null_masks: Option<&HashMap<String, NullBuffer>>,
+    ) -> Result<RecordBatch> {
+        let total_docs = segment_reader.metadata().total_docs;
+        let docs = docs.start.min(total_docs)..docs.end.min(total_docs);
+        let num_rows = docs.len();
 
         // Handle empty projection (e.g., COUNT(*) queries)
-        if column_names.is_empty() {
-            return Ok(vec![]);
+        if schema.fields().is_empty() {
+            let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+            return RecordBatch::try_new_with_options(schema.clone(), vec![], &options)
+                .map_err(|e| Error::Internal(format!("Failed to create RecordBatch: {}", e)));
         }
 
-        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(column_names.len());
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
 
-        for column_name in column_names.iter() {
-            let col_meta = segment_reader
-                .metadata()
-                .get_column(column_name)
-                .map_err(|e| Error::Internal(e.to_string()))?;
+        // Resolve columns by name: each segment's metadata map iterates in its
+        // own order, so projection indices are only meaningful for the table schema
+        for (idx, field) in schema.fields().iter().enumerate() {
+            let column_name = field.name();
+            // A projection may repeat a column (`SELECT hits, hits * 2`);
+            // share the array decoded for its first occurrence
+            if let Some(first) = schema.fields()[..idx]
+                .iter()
+                .position(|f| f.name() == column_name)
+            {
+                arrays.push(arrays[first].clone());
+                continue;
+            }
+            // Tables opened with union schemas expose columns that older
+            // segments never had; those read as all-null
+            let metadata = segment_reader.metadata();
+            if field.is_nullable() && !metadata.columns.contains_key(column_name) {
+                arrays.push(new_null_array(field.data_type(), num_rows));
+                continue;
+            }
+            let col_meta = segment_reader.metadata().get_column(column_name)?;
 
             let array: ArrayRef = match col_meta.data_type {
+                _ if matches!(field.data_type(), ArrowDataType::Dictionary(_, _)) => {
+                    Self::read_string_dictionary_array(
+                        segment_reader,
+                        column_name,
+                        col_meta.has_dictionary,
+                        docs.clone(),
+                    )?
+                }
+                PinotDataType::String if field.data_type() == &ArrowDataType::Utf8View => Arc::new(
+                    segment_reader.read_string_view_array_in_range(column_name, docs.clone())?,
+                ),
                 PinotDataType::Int => {
-                    let values = segment_reader
-                        .read_int_column(column_name)
-                        .map_err(|e| Error::Internal(e.to_string()))?;
-                    Arc::new(Int32Array::from(values))
+                    let mut builder = Int32Builder::with_capacity(num_rows);
+                    segment_reader
+                        .for_each_int_in_range(column_name, docs.clone(), |v| {
+                            builder.append_value(v)
+                        })?;
+                    Arc::new(builder.finish())
                 }
                 PinotDataType::Long => {
-                    let values = segment_reader
-                        .read_long_column(column_name)
-                        .map_err(|e| Error::Internal(e.to_string()))?;
-                    Arc::new(Int64Array::from(values))
+                    let mut builder = Int64Builder::with_capacity(num_rows);
+                    segment_reader
+                        .for_each_long_in_range(column_name, docs.clone(), |v| {
+                            builder.append_value(v)
+                        })?;
+                    Arc::new(builder.finish())
                 }
                 PinotDataType::Float => {
-                    let values = segment_reader
-                        .read_float_column(column_name)
-                        .map_err(|e| Error::Internal(e.to_string()))?;
-                    Arc::new(Float32Array::from(values))
+                    let mut builder = Float32Builder::with_capacity(num_rows);
+                    segment_reader
+                        .for_each_float_in_range(column_name, docs.clone(), |v| {
+                            builder.append_value(v)
+                        })?;
+                    Arc::new(builder.finish())
                 }
                 PinotDataType::Double => {
-                    let values = segment_reader
-                        .read_double_column(column_name)
-                        .map_err(|e| Error::Internal(e.to_string()))?;
-                    Arc::new(Float64Array::from(values))
+                    let mut builder = Float64Builder::with_capacity(num_rows);
+                    segment_reader
+                        .for_each_double_in_range(column_name, docs.clone(), |v| {
+                            builder.append_value(v)
+                        })?;
+                    Arc::new(builder.finish())
                 }
                 PinotDataType::String => {
-                    let values = segment_reader
-                        .read_string_column(column_name)
-                        .map_err(|e| Error::Internal(e.to_string()))?;
-                    Arc::new(StringArray::from(values))
+                    let mut builder = StringBuilder::with_capacity(
+                        num_rows,
+                        num_rows * STRING_BYTES_PER_ROW_HINT,
+                    );
+                    segment_reader
+                        .for_each_string_in_range(column_name, docs.clone(), |v| {
+                            builder.append_value(v)
+                        })?;
+                    Arc::new(builder.finish())
+                }
+                PinotDataType::Bytes => {
+                    let mut builder = BinaryBuilder::with_capacity(
+                        num_rows,
+                        num_rows * STRING_BYTES_PER_ROW_HINT,
+                    );
+                    segment_reader
+                        .for_each_bytes_in_range(column_name, docs.clone(), |v| {
+                            builder.append_value(v)
+                        })?;
+                    Arc::new(builder.finish())
                 }
                 _ => {
                     return Err(Error::UnsupportedFeature(format!(
@@ -141,45 +361,241 @@ impl PinotExec {
                 }
             };
 
-            arrays.push(array);
+            arrays.push(Self::apply_null_value_vector(
+                segment_reader,
+                column_name,
+                field.is_nullable(),
+                docs.start,
+                array,
+                null_masks,
+            )?);
         }
 
-        Ok(arrays)
+        RecordBatch::try_new(schema.clone(), arrays)
+            .map_err(|e| Error::Internal(format!("Failed to create RecordBatch: {}", e)))
     }
 
-    /// Create a batch by slicing pre-read column arrays
-    fn create_batch_from_arrays(
-        column_arrays: &[ArrayRef],
-        schema: &SchemaRef,
-        offset: usize,
-        limit: usize,
-    ) -> Result<RecordBatch> {
-        // Handle empty projection (e.g., COUNT(*) queries)
-        if column_arrays.is_empty() {
-            let options = RecordBatchOptions::new().with_row_count(Some(limit));
-            return RecordBatch::try_new_with_options(schema.clone(), vec![], &options)
-                .map_err(|e| Error::Internal(format!("Failed to create RecordBatch: {}", e)));
+    /// Read a STRING column as `DictionaryArray<Int32, Utf8>`
+    ///
+    /// Dictionary-encoded columns reuse the Pinot dictionary and dict ids as-is;
+    /// RAW columns are dictionary-encoded while reading.
+    fn read_string_dictionary_array(
+        segment_reader: &SegmentReader,
+        column_name: &str,
+        has_dictionary: bool,
+        docs: Range<u32>,
+    ) -> Result<ArrayRef> {
+        let num_rows = docs.len();
+        if !has_dictionary {
+            let mut builder = StringDictionaryBuilder::<Int32Type>::with_capacity(
+                num_rows,
+                0,
+                num_rows * STRING_BYTES_PER_ROW_HINT,
+            );
+            segment_reader
+                .for_each_string_in_range(column_name, docs, |v| {
+                    builder.append_value(v);
+                })?;
+            return Ok(Arc::new(builder.finish()));
         }
 
-        // Slice each column array for this batch
-        let sliced_arrays: Vec<ArrayRef> = column_arrays
+        let (dictionary, dict_ids) = segment_reader
+            .read_string_dictionary_column_in_range(column_name, docs)?;
+        let values = StringArray::from_iter_values(dictionary.string_values().unwrap_or_default());
+        let keys: Int32Array = dict_ids.into_iter().map(|id| id as i32).collect();
+
+        let array = DictionaryArray::<Int32Type>::try_new(keys, Arc::new(values))
+            .map_err(|e| Error::Internal(format!("Invalid dictionary for {}: {}", column_name, e)))?;
+        Ok(Arc::new(array))
+    }
+
+    /// Mark the documents in the column's null value vector as null
+    ///
+    /// `array` holds the documents starting at `first_doc_id`. Only nullable
+    /// fields are touched; non-nullable ones keep the default null value
+    /// Pinot stored for those documents. With `null_masks`, a column's nulls
+    /// are sliced from its mask, and a column without one has no nulls.
+    fn apply_null_value_vector(
+        segment_reader: &SegmentReader,
+        column_name: &str,
+        nullable: bool,
+        first_doc_id: u32,
+        array: ArrayRef,
+        null_masks: Option<&HashMap<String, NullBuffer>>,
+    ) -> Result<ArrayRef> {
+        if !nullable {
+            return Ok(array);
+        }
+        let nulls = match null_masks {
+            Some(masks) => match masks.get(column_name) {
+                Some(mask) => mask.slice(first_doc_id as usize, array.len()),
+                None => return Ok(array),
+            },
+            None => {
+                let Some(null_doc_ids) = segment_reader.read_null_doc_ids(column_name)? else {
+                    return Ok(array);
+                };
+                let null_doc_ids: Vec<u32> = null_doc_ids
+                    .into_iter()
+                    .filter_map(|doc_id| doc_id.checked_sub(first_doc_id))
+                    .collect();
+                NullBuffer::new(!&doc_id_bitmap(array.len(), &null_doc_ids))
+            }
+        };
+
+        let data = array
+            .to_data()
+            .into_builder()
+            .nulls(Some(nulls))
+            .build()
+            .map_err(|e| Error::Internal(format!("Invalid nulls for {}: {}", column_name, e)))?;
+        Ok(make_array(data))
+    }
+
+    /// Doc id sets a segment's rows must belong to: one per range filter the
+    /// segment's range indexes answer, one per null filter that excludes
+    /// documents, plus its validDocIds when honored
+    fn selected_doc_ids(&self, segment: &dyn SegmentSource) -> Result<Vec<Vec<u32>>> {
+        let mut selections = Vec::new();
+        for (column, range) in &self.range_filters {
+            if let Some(doc_ids) = segment.range_doc_ids(column, range)? {
+                selections.push(doc_ids);
+            }
+        }
+        for (column, is_null) in &self.null_filters {
+            let null_doc_ids = segment.null_doc_ids(column)?;
+            if *is_null {
+                selections.push(null_doc_ids.to_vec());
+            } else if !null_doc_ids.is_empty() {
+                let total_docs = segment.metadata().total_docs;
+                selections.push(null_doc_ids.complement(total_docs).to_vec());
+            }
+        }
+        if self.options.honor_valid_doc_ids {
+            if let Some(valid_doc_ids) = segment.read_valid_doc_ids()? {
+                selections.push(valid_doc_ids);
+            }
+        }
+        Ok(selections)
+    }
+
+    /// `schema` without the segment info columns
+    fn stored_columns(schema: &SchemaRef) -> SchemaRef {
+        if !schema.fields().iter().any(|f| is_segment_info_column(f.name())) {
+            return schema.clone();
+        }
+        let fields: Vec<_> = schema
+            .fields()
             .iter()
-            .map(|array| array.slice(offset, limit))
+            .filter(|f| !is_segment_info_column(f.name()))
+            .cloned()
             .collect();
+        Arc::new(Schema::new(fields))
+    }
 
-        RecordBatch::try_new(schema.clone(), sliced_arrays)
+    /// Fill in the segment info columns of `schema` around the stored
+    /// columns read into `batch`, taking on `schema`'s nullability
+    fn add_segment_info_columns(
+        batch: RecordBatch,
+        schema: &SchemaRef,
+        segment_name: &str,
+        partition: usize,
+    ) -> Result<RecordBatch> {
+        if batch.schema_ref() == schema {
+            return Ok(batch);
+        }
+
+        let num_rows = batch.num_rows();
+        let mut stored = batch.columns().iter();
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+        for field in schema.fields() {
+            let array: ArrayRef = match field.name().as_str() {
+                SEGMENT_NAME_COLUMN => Arc::new(StringArray::from_iter_values(
+                    std::iter::repeat_n(segment_name, num_rows),
+                )),
+                SEGMENT_INDEX_COLUMN => Arc::new(Int32Array::from(vec![partition as i32; num_rows])),
+                _ => stored
+                    .next()
+                    .ok_or_else(|| Error::Internal(format!("Column {} was not read", field.name())))?
+                    .clone(),
+            };
+            arrays.push(array);
+        }
+
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        RecordBatch::try_new_with_options(schema.clone(), arrays, &options)
             .map_err(|e| Error::Internal(format!("Failed to create RecordBatch: {}", e)))
     }
 }
 
+/// Ascending orderings of the projected columns every segment is sorted on
+///
+/// Each partition scans one segment in doc id order, so a column Pinot
+/// recorded as `isSorted` in all segments lets DataFusion drop the sort (one
+/// partition) or merge the partitions instead of sorting. Nullable fields are
+/// skipped: substituted nulls keep the position of their default value.
+/// Columns are looked up by their `read_schema` names and ordered by the
+/// names of the same fields in `schema`.
+fn sorted_column_orderings(
+    segments: &[Arc<dyn SegmentSource>],
+    read_schema: &SchemaRef,
+    schema: &SchemaRef,
+) -> Vec<LexOrdering> {
+    if segments.is_empty() {
+        return Vec::new();
+    }
+
+    read_schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| !field.is_nullable())
+        .filter(|(_, field)| {
+            segments.iter().all(|s| {
+                s.metadata()
+                    .columns
+                    .get(field.name())
+                    .is_some_and(|c| c.is_sorted)
+            })
+        })
+        .map(|(idx, _)| {
+            LexOrdering::new(vec![PhysicalSortExpr::new_default(Arc::new(Column::new(
+                schema.field(idx).name(),
+                idx,
+            )))])
+        })
+        .collect()
+}
+
+/// Bitmap of `num_rows` bits with the listed doc ids set
+fn doc_id_bitmap(num_rows: usize, doc_ids: &[u32]) -> BooleanBuffer {
+    let mut bitmap = BooleanBufferBuilder::new(num_rows);
+    bitmap.append_n(num_rows, false);
+    for &doc_id in doc_ids {
+        // Ids past the forward index (see DocCountMismatchPolicy) are ignored
+        if (doc_id as usize) < num_rows {
+            bitmap.set_bit(doc_id as usize, true);
+        }
+    }
+    bitmap.finish()
+}
+
 impl DisplayAs for PinotExec {
     fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "PinotExec: segments={}, partitions={}",
+            "PinotExec: segments={}, partitions={}, pruned_segments={}",
             self.segments.len(),
-            self.segments.len()
-        )
+            self.plan_properties.partitioning.partition_count(),
+            self.pruned_segments
+        )?;
+        if let Some(limit) = self.limit {
+            write!(f, ", limit={}", limit)?;
+        }
+        if let Some(memory_limit) = self.memory_limit {
+            write!(f, ", memory_limit={}", memory_limit)?;
+        }
+        Ok(())
     }
 }
 
@@ -211,11 +627,29 @@ impl ExecutionPlan for PinotExec {
         Ok(self)
     }
 
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        let statistics = merge_segment_statistics(&self.segments, &self.schema, &self.options);
+        // Null filters drop rows the segment metadata still counts
+        if self.null_filters.is_empty() {
+            Ok(statistics)
+        } else {
+            Ok(statistics.to_inexact())
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
     fn execute(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> DataFusionResult<SendableRecordBatchStream> {
+        if self.segments.is_empty() && partition == 0 {
+            return Ok(Box::pin(PinotStream::new(self.schema.clone(), vec![])));
+        }
+
         // Each partition reads from one segment
         let segment_reader = self
             .segments
@@ -229,29 +663,179 @@ impl ExecutionPlan for PinotExec {
             })?
             .clone();
 
-        let total_docs = segment_reader.metadata().total_docs as usize;
         let schema = self.schema.clone();
-        let projection = self.projection.clone();
-
-        // OPTIMIZATION: Read all columns ONCE instead of re-reading for each batch
-        let column_arrays = Self::read_columns_once(&segment_reader, &schema, &projection)
-            .map_err(|e| DataFusionError::External(Box::new(e)))?;
-
-        // Create batches by slicing the pre-read column data
-        let batches = (0..total_docs)
-            .step_by(BATCH_SIZE)
-            .map(|offset| {
-                let limit = BATCH_SIZE.min(total_docs - offset);
-                Self::create_batch_from_arrays(&column_arrays, &schema, offset, limit)
-            })
-            .collect::<Result<Vec<_>>>()
-            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let to_df_error = |e: Error| DataFusionError::External(Box::new(e));
 
-        Ok(Box::pin(PinotStream {
-            schema,
-            batches,
-            index: 0,
-        }))
+        // Range indexes and null value vectors may rule out every document,
+        // sparing the decode
+        let selections = self
+            .selected_doc_ids(segment_reader.as_ref())
+            .map_err(to_df_error)?;
+
+        // Documents present in every selection
+        let total_docs = segment_reader.metadata().total_docs;
+        let selection = selections
+            .iter()
+            .map(|doc_ids| doc_id_bitmap(total_docs as usize, doc_ids))
+            .reduce(|a, b| &a & &b);
+
+        // COUNT(*) fast path: with nothing projected the row count comes
+        // straight from segment.total.docs (narrowed by the selections, such
+        // as validDocIds), so emit one zero-column batch instead of a batch
+        // per batch_size window
+        if schema.fields().is_empty() {
+            let row_count = selection
+                .as_ref()
+                .map_or(total_docs as usize, |mask| mask.count_set_bits());
+            let options = RecordBatchOptions::new().with_row_count(Some(row_count));
+            let batch = RecordBatch::try_new_with_options(schema.clone(), vec![], &options)?;
+            return Ok(Box::pin(PinotStream::new(schema, vec![batch])));
+        }
+
+        if selections.iter().any(|doc_ids| doc_ids.is_empty()) {
+            return Ok(Box::pin(PinotStream::new(schema, vec![])));
+        }
+
+        // A pushed-down LIMIT bounds the read unless rows are filtered afterwards
+        let end_doc = match self.limit {
+            Some(limit) if selections.is_empty() => {
+                total_docs.min(u32::try_from(limit).unwrap_or(u32::MAX))
+            }
+            _ => total_docs,
+        };
+        let batches = SegmentBatches {
+            segment: segment_reader,
+            // Segment info columns are not stored in the segment
+            read_schema: Self::stored_columns(&self.read_schema),
+            schema: schema.clone(),
+            selection,
+            partition,
+            batch_size: u32::try_from(self.options.batch_size.max(1)).unwrap_or(u32::MAX),
+            next_doc: 0,
+            end_doc,
+            null_masks: None,
+        };
+
+        match self.memory_limit {
+            Some(memory_limit) => {
+                let reservation = MemoryConsumer::new(format!("PinotExec[{}]", partition))
+                    .with_can_spill(true)
+                    .register(context.memory_pool());
+                let spill = SpillContext {
+                    schema: schema.clone(),
+                    memory_limit,
+                    disk_manager: context.runtime_env().disk_manager.clone(),
+                    spill_count: MetricBuilder::new(&self.metrics).spill_count(partition),
+                    spilled_bytes: MetricBuilder::new(&self.metrics).spilled_bytes(partition),
+                    spilled_rows: MetricBuilder::new(&self.metrics).spilled_rows(partition),
+                };
+                Ok(Box::pin(PinotStream::spawn_spilling(
+                    schema,
+                    batches,
+                    reservation,
+                    spill,
+                )))
+            }
+            None => Ok(Box::pin(PinotStream::spawn(schema, batches))),
+        }
+    }
+}
+
+/// The batches of one partition, each decoding the next `batch_size`
+/// documents of its segment
+///
+/// Decoding window by window keeps only the batches in flight in memory,
+/// rather than the whole segment.
+struct SegmentBatches {
+    segment: Arc<dyn SegmentSource>,
+    /// Stored columns of `schema`, the ones decoded from the segment
+    read_schema: SchemaRef,
+    schema: SchemaRef,
+    /// Documents to keep, from range indexes, null filters and validDocIds;
+    /// `None` keeps every document
+    selection: Option<BooleanBuffer>,
+    partition: usize,
+    batch_size: u32,
+    next_doc: u32,
+    end_doc: u32,
+    /// Validity of each nullable column with a null value vector over the
+    /// whole segment, loaded with the first window and sliced for each one
+    null_masks: Option<HashMap<String, NullBuffer>>,
+}
+
+impl SegmentBatches {
+    /// Read the null value vector of every nullable column once, rather than
+    /// once per window
+    fn load_null_masks(&self) -> Result<HashMap<String, NullBuffer>> {
+        let total_docs = self.segment.metadata().total_docs as usize;
+        let mut masks = HashMap::new();
+        for field in self.read_schema.fields() {
+            if !field.is_nullable() || masks.contains_key(field.name()) {
+                continue;
+            }
+            if !self.segment.metadata().columns.contains_key(field.name())
+                || !self.segment.has_null_value_vector(field.name())
+            {
+                continue;
+            }
+            let null_doc_ids = self.segment.null_doc_ids(field.name())?.to_vec();
+            let mask = NullBuffer::new(!&doc_id_bitmap(total_docs, &null_doc_ids));
+            masks.insert(field.name().clone(), mask);
+        }
+        Ok(masks)
+    }
+
+    /// Decode the next window that keeps any documents
+    fn read_next(&mut self) -> Result<Option<RecordBatch>> {
+        while self.next_doc < self.end_doc {
+            let start = self.next_doc;
+            let count = self.batch_size.min(self.end_doc - start);
+            self.next_doc += count;
+
+            let null_masks = match &self.null_masks {
+                Some(masks) => masks,
+                None => self.null_masks.insert(self.load_null_masks()?),
+            };
+            let batch = self.segment.read_batch_range_with_nulls(
+                &self.read_schema,
+                start,
+                count,
+                null_masks,
+            )?;
+            let batch = match &self.selection {
+                Some(selection) => {
+                    let mask = selection.slice(start as usize, batch.num_rows());
+                    filter_record_batch(&batch, &BooleanArray::new(mask, None)).map_err(|e| {
+                        Error::Internal(format!("Failed to filter documents: {}", e))
+                    })?
+                }
+                None => batch,
+            };
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            return PinotExec::add_segment_info_columns(
+                batch,
+                &self.schema,
+                &self.segment.metadata().segment_name,
+                self.partition,
+            )
+            .map(Some);
+        }
+        Ok(None)
+    }
+}
+
+impl Iterator for SegmentBatches {
+    type Item = DataFusionResult<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.read_next().transpose()?;
+        if batch.is_err() {
+            // Stop at the first error
+            self.next_doc = self.end_doc;
+        }
+        Some(batch.map_err(|e| DataFusionError::External(Box::new(e))))
     }
 }
 
@@ -260,19 +844,342 @@ struct PinotStream {
     schema: SchemaRef,
     batches: Vec<RecordBatch>,
     index: usize,
+    /// Batches decoded by a background task, emitted after `batches`
+    preloaded: Option<PreloadedBatches>,
+    /// Batches decoded by a background task under a memory limit, emitted
+    /// after `batches`
+    spilling: Option<SpillingBatches>,
+}
+
+/// Receiving end of a blocking task decoding batches ahead of the stream
+struct PreloadedBatches {
+    receiver: mpsc::Receiver<DataFusionResult<RecordBatch>>,
+    task: JoinHandle<()>,
+}
+
+/// What a spilling producer needs besides the batches it decodes
+struct SpillContext {
+    schema: SchemaRef,
+    memory_limit: usize,
+    disk_manager: Arc<DiskManager>,
+    spill_count: Count,
+    spilled_bytes: Count,
+    spilled_rows: Count,
+}
+
+/// Receiving end of a blocking task decoding every batch of a partition,
+/// spilling the oldest buffered ones past the memory limit
+struct SpillingBatches {
+    shared: Arc<SpillShared>,
+    /// Reads the spilled batches back in the order they were written
+    reader: Option<StreamReader<BufReader<File>>>,
+    task: JoinHandle<()>,
+}
+
+/// State shared by a spilling producer and its stream
+struct SpillShared {
+    state: Mutex<SpillState>,
+    /// Wakes the stream when the front of the queue changes
+    waker: AtomicWaker,
+}
+
+struct SpillState {
+    /// Decoded batches in output order; the spilled ones always come first,
+    /// in the order they were written to the spill file
+    queue: VecDeque<QueuedBatch>,
+    /// Memory-pool reservation for the batches held in memory
+    reservation: MemoryReservation,
+    /// Created when the first batch spills; deleted with the stream
+    spill_file: Option<RefCountedTempFile>,
+    /// The producer has queued its last batch
+    finished: bool,
+    /// The stream was dropped: the producer stops
+    cancelled: bool,
+}
+
+enum QueuedBatch {
+    InMemory(RecordBatch),
+    /// Being written to the spill file
+    Spilling,
+    Spilled,
+    Failed(DataFusionError),
+}
+
+impl PinotStream {
+    fn new(schema: SchemaRef, batches: Vec<RecordBatch>) -> Self {
+        Self {
+            schema,
+            batches,
+            index: 0,
+            preloaded: None,
+            spilling: None,
+        }
+    }
+
+    /// Stream `batches`, decoded on a blocking thread up to
+    /// [`PRELOAD_BATCHES`] batches ahead of the consumer
+    ///
+    /// The next batch is decompressed while DataFusion works on the current
+    /// one, without holding up the runtime's worker threads with file reads.
+    /// The thread ends once the batches run out or the stream is dropped.
+    fn spawn(schema: SchemaRef, batches: SegmentBatches) -> Self {
+        // The channel holds all but one of the batches read ahead; the
+        // producer holds the last while blocked in blocking_send
+        let (sender, receiver) = mpsc::channel(PRELOAD_BATCHES - 1);
+        let task = tokio::task::spawn_blocking(move || {
+            for batch in batches {
+                if sender.blocking_send(batch).is_err() {
+                    // The stream was dropped
+                    break;
+                }
+            }
+        });
+        Self {
+            preloaded: Some(PreloadedBatches { receiver, task }),
+            ..Self::new(schema, vec![])
+        }
+    }
+
+    /// Stream `batches`, all decoded on a blocking thread while the stream is
+    /// consumed, holding at most `spill.memory_limit` bytes of them in memory
+    fn spawn_spilling(
+        schema: SchemaRef,
+        batches: SegmentBatches,
+        reservation: MemoryReservation,
+        spill: SpillContext,
+    ) -> Self {
+        let shared = Arc::new(SpillShared {
+            state: Mutex::new(SpillState {
+                queue: VecDeque::new(),
+                reservation,
+                spill_file: None,
+                finished: false,
+                cancelled: false,
+            }),
+            waker: AtomicWaker::new(),
+        });
+        let producer = shared.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            let result = produce_spilling(&producer, batches, spill);
+            let mut state = producer.state.lock().unwrap();
+            if let Err(e) = result {
+                // A batch that failed to spill is where the stream ends
+                if let Some(spilling) = state
+                    .queue
+                    .iter()
+                    .position(|queued| matches!(queued, QueuedBatch::Spilling))
+                {
+                    state.queue.truncate(spilling);
+                }
+                state.queue.push_back(QueuedBatch::Failed(e));
+            }
+            state.finished = true;
+            drop(state);
+            producer.waker.wake();
+        });
+        Self {
+            spilling: Some(SpillingBatches {
+                shared,
+                reader: None,
+                task,
+            }),
+            ..Self::new(schema, vec![])
+        }
+    }
+}
+
+/// Queue every batch of `batches` in `shared`, spilling the oldest batches
+/// held in memory whenever the next one would not fit
+fn produce_spilling(
+    shared: &SpillShared,
+    batches: SegmentBatches,
+    spill: SpillContext,
+) -> DataFusionResult<()> {
+    let mut writer: Option<StreamWriter<BufWriter<File>>> = None;
+    for batch in batches {
+        // Slices share the buffers of the whole segment; copy the rows out
+        // so the batch's size is its own and the segment can be freed
+        let batch = batch?;
+        let indices = UInt32Array::from_iter_values(0..batch.num_rows() as u32);
+        let batch = take_record_batch(&batch, &indices)?;
+        let size = batch.get_array_memory_size();
+
+        let mut batch = Some(batch);
+        while let Some(next) = batch.take() {
+            let mut state = shared.state.lock().unwrap();
+            if state.cancelled {
+                return Ok(());
+            }
+            if state.reservation.size() + size <= spill.memory_limit
+                && state.reservation.try_grow(size).is_ok()
+            {
+                state.queue.push_back(QueuedBatch::InMemory(next));
+                break;
+            }
+
+            // Spill the oldest batch in memory, or this one if none is
+            let oldest = state
+                .queue
+                .iter()
+                .position(|queued| matches!(queued, QueuedBatch::InMemory(_)));
+            let spilled = match oldest {
+                Some(idx) => {
+                    batch = Some(next);
+                    match std::mem::replace(&mut state.queue[idx], QueuedBatch::Spilling) {
+                        QueuedBatch::InMemory(oldest) => oldest,
+                        _ => unreachable!("position matched an in-memory batch"),
+                    }
+                }
+                None => {
+                    state.queue.push_back(QueuedBatch::Spilling);
+                    next
+                }
+            };
+            let writer = match &mut writer {
+                Some(writer) => writer,
+                None => {
+                    let file = spill.disk_manager.create_tmp_file("PinotExec spill")?;
+                    let stream_writer = StreamWriter::try_new(
+                        BufWriter::new(File::create(file.path())?),
+                        &spill.schema,
+                    )?;
+                    state.spill_file = Some(file);
+                    spill.spill_count.add(1);
+                    writer.insert(stream_writer)
+                }
+            };
+            drop(state);
+
+            // Written without the lock, so the stream can go on emitting
+            // the batches ahead of it
+            writer.write(&spilled)?;
+            writer.flush()?;
+            spill.spilled_rows.add(spilled.num_rows());
+
+            let mut state = shared.state.lock().unwrap();
+            let spilling = state
+                .queue
+                .iter()
+                .position(|queued| matches!(queued, QueuedBatch::Spilling))
+                .ok_or_else(|| DataFusionError::Internal("Spilled batch not queued".into()))?;
+            state.queue[spilling] = QueuedBatch::Spilled;
+            if oldest.is_some() {
+                let released = spilled.get_array_memory_size().min(state.reservation.size());
+                state.reservation.shrink(released);
+            }
+            drop(state);
+            shared.waker.wake();
+        }
+        shared.waker.wake();
+    }
+
+    if let Some(mut writer) = writer {
+        writer.finish()?;
+        let file = writer.into_inner()?.into_inner().map_err(|e| e.into_error())?;
+        spill
+            .spilled_bytes
+            .add(file.metadata()?.len() as usize);
+    }
+    Ok(())
+}
+
+impl SpillingBatches {
+    /// Next queued batch, once the producer has decoded (or spilled) it
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<DataFusionResult<RecordBatch>>> {
+        // Registered before looking at the queue, so a batch queued in
+        // between still wakes the stream
+        self.shared.waker.register(cx.waker());
+        let mut state = self.shared.state.lock().unwrap();
+        match state.queue.front() {
+            None if state.finished => {
+                drop(state);
+                // The producer is done or panicked
+                let finished = ready!(Pin::new(&mut self.task).poll(cx));
+                return Poll::Ready(finished.err().map(|e| Err(DataFusionError::ExecutionJoin(e))));
+            }
+            None | Some(QueuedBatch::Spilling) => return Poll::Pending,
+            Some(_) => {}
+        }
+
+        match state.queue.pop_front() {
+            Some(QueuedBatch::InMemory(batch)) => {
+                let released = batch.get_array_memory_size().min(state.reservation.size());
+                state.reservation.shrink(released);
+                Poll::Ready(Some(Ok(batch)))
+            }
+            Some(QueuedBatch::Failed(e)) => Poll::Ready(Some(Err(e))),
+            _ => {
+                let path = state
+                    .spill_file
+                    .as_ref()
+                    .map(|file| file.path().to_path_buf());
+                drop(state);
+                Poll::Ready(Some(self.read_spilled(path)))
+            }
+        }
+    }
+
+    /// Read the next batch of the spill file at `path`
+    fn read_spilled(&mut self, path: Option<PathBuf>) -> DataFusionResult<RecordBatch> {
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            None => {
+                let path = path
+                    .ok_or_else(|| DataFusionError::Internal("Spill file missing".into()))?;
+                let file = BufReader::new(File::open(path)?);
+                self.reader.insert(StreamReader::try_new(file, None)?)
+            }
+        };
+        reader
+            .next()
+            .unwrap_or_else(|| Err(ArrowError::IpcError("Spill file ended early".into())))
+            .map_err(DataFusionError::from)
+    }
+}
+
+impl Drop for SpillingBatches {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.state.lock() {
+            state.cancelled = true;
+        }
+    }
 }
 
 impl Stream for PinotStream {
     type Item = DataFusionResult<RecordBatch>;
 
-    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        if self.index < self.batches.len() {
-            let batch = self.batches[self.index].clone();
-            self.index += 1;
-            Poll::Ready(Some(Ok(batch)))
-        } else {
-            Poll::Ready(None)
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.index < this.batches.len() {
+            // Hand the batch over instead of cloning it, so its memory goes
+            // with it
+            let empty = RecordBatch::new_empty(this.schema.clone());
+            let batch = std::mem::replace(&mut this.batches[this.index], empty);
+            this.index += 1;
+            return Poll::Ready(Some(Ok(batch)));
+        }
+
+        if let Some(preloaded) = this.preloaded.as_mut() {
+            if let Some(batch) = ready!(preloaded.receiver.poll_recv(cx)) {
+                return Poll::Ready(Some(batch));
+            }
+            // The sender is gone: the task finished or panicked
+            let finished = ready!(Pin::new(&mut preloaded.task).poll(cx));
+            this.preloaded = None;
+            if let Err(e) = finished {
+                return Poll::Ready(Some(Err(DataFusionError::ExecutionJoin(e))));
+            }
+        }
+
+        if let Some(spilling) = this.spilling.as_mut() {
+            let batch = ready!(spilling.poll_next(cx));
+            if batch.is_none() {
+                // Delete the spill file as soon as it is exhausted
+                this.spilling = None;
+            }
+            return Poll::Ready(batch);
         }
+        Poll::Ready(None)
     }
 }
 
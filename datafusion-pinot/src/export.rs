@@ -0,0 +1,169 @@
+//! Export a table's rows as CSV, newline-delimited JSON or Parquet
+//!
+//! Rows are streamed segment after segment through
+//! [`PinotTable::execute_merged`], so a table can be fed to other tools
+//! without planning a query, e.g.
+//! `pinot export --format csv --segment /path/v3 > output.csv`.
+
+use datafusion::arrow::csv::WriterBuilder;
+use datafusion::arrow::json::LineDelimitedWriter;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::TableProvider;
+use datafusion::execution::TaskContext;
+use datafusion::parquet::arrow::ArrowWriter;
+use futures::StreamExt;
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::table::PinotTable;
+
+/// File format an export writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// One JSON object per row, one row per line
+    Json,
+    Parquet,
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "parquet" => Ok(ExportFormat::Parquet),
+            _ => Err(Error::UnsupportedFeature(format!(
+                "Export format {} (expected csv, json or parquet)",
+                s
+            ))),
+        }
+    }
+}
+
+/// How [`write_csv`] lays out rows
+///
+/// The defaults write RFC 4180 CSV: comma-separated, double-quoted where
+/// needed, with a header row of the schema's field names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvWriterOptions {
+    /// Byte separating the values of a row
+    pub delimiter: u8,
+    /// Byte quoting values that hold the delimiter, a quote or a line break
+    pub quote: u8,
+    /// Write the field names as the first row
+    pub header: bool,
+}
+
+impl Default for CsvWriterOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            header: true,
+        }
+    }
+}
+
+impl CsvWriterOptions {
+    /// Separate values with `delimiter` (e.g. `b'\t'` for TSV)
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Quote values with `quote`
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Write (or leave out) the header row
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+}
+
+/// Write every row of `table` to `writer` as CSV
+///
+/// The header row is written even when the table has no rows. Returns the
+/// number of rows written.
+pub async fn write_csv<W: Write>(
+    table: &PinotTable,
+    writer: W,
+    options: CsvWriterOptions,
+) -> Result<usize> {
+    let mut csv = WriterBuilder::new()
+        .with_delimiter(options.delimiter)
+        .with_quote(options.quote)
+        .with_header(options.header)
+        .build(writer);
+    csv.write(&RecordBatch::new_empty(table.schema()))
+        .map_err(|e| Error::Arrow(e.to_string()))?;
+    for_each_batch(table, |batch| {
+        csv.write(batch).map_err(|e| Error::Arrow(e.to_string()))
+    })
+    .await
+}
+
+/// Write every row of `table` to `writer` as newline-delimited JSON
+///
+/// Null values are left out of their row's object. Returns the number of
+/// rows written.
+pub async fn write_json<W: Write>(table: &PinotTable, writer: W) -> Result<usize> {
+    let mut json = LineDelimitedWriter::new(writer);
+    let rows = for_each_batch(table, |batch| {
+        json.write(batch).map_err(|e| Error::Arrow(e.to_string()))
+    })
+    .await?;
+    json.finish().map_err(|e| Error::Arrow(e.to_string()))?;
+    Ok(rows)
+}
+
+/// Write every row of `table` to `writer` as a Parquet file
+///
+/// Returns the number of rows written.
+pub async fn write_parquet<W: Write + Send>(table: &PinotTable, writer: W) -> Result<usize> {
+    let mut parquet = ArrowWriter::try_new(writer, table.schema(), None)
+        .map_err(|e| Error::Arrow(e.to_string()))?;
+    let rows = for_each_batch(table, |batch| {
+        parquet
+            .write(batch)
+            .map_err(|e| Error::Arrow(e.to_string()))
+    })
+    .await?;
+    parquet.close().map_err(|e| Error::Arrow(e.to_string()))?;
+    Ok(rows)
+}
+
+/// Write every row of `table` to `writer` in `format`
+pub async fn write_table<W: Write + Send>(
+    table: &PinotTable,
+    writer: W,
+    format: ExportFormat,
+) -> Result<usize> {
+    match format {
+        ExportFormat::Csv => write_csv(table, writer, CsvWriterOptions::default()).await,
+        ExportFormat::Json => write_json(table, writer).await,
+        ExportFormat::Parquet => write_parquet(table, writer).await,
+    }
+}
+
+/// Pass each batch of a full scan of `table` to `f`, returning the row count
+async fn for_each_batch<F>(table: &PinotTable, mut f: F) -> Result<usize>
+where
+    F: FnMut(&RecordBatch) -> Result<()>,
+{
+    let mut stream = table.execute_merged(Arc::new(TaskContext::default()));
+    let mut rows = 0;
+    while let Some(batch) = stream.next().await {
+        let batch = batch.map_err(|e| Error::DataFusion(e.to_string()))?;
+        f(&batch)?;
+        rows += batch.num_rows();
+    }
+    Ok(rows)
+}
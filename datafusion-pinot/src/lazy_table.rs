@@ -0,0 +1,342 @@
+//! Table planned against a schema known up front, opened on first scan
+//!
+//! When a [`MetadataProvider`](crate::MetadataProvider) knows a table's
+//! schema (e.g. from the controller's `/schemas` endpoint), the catalog serves
+//! a [`LazyPinotTable`] instead of opening every segment just to derive it.
+//! Segments are opened when a query first scans the table, and the columns
+//! the query reads are checked against them then. Only the first segment is
+//! opened up front, to leave out the columns the readers cannot decode. With the provider's
+//! [`SegmentDescriptor`]s, a scan whose filters exclude some segments opens
+//! only the others.
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::{DataType, Schema, SchemaRef};
+use datafusion::catalog::Session;
+use datafusion::common::stats::Precision;
+use datafusion::common::Statistics;
+use datafusion::datasource::TableProvider;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::logical_expr::{Expr, TableProviderFilterPushDown, TableType};
+use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::ExecutionPlan;
+use std::any::Any;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use crate::error::{Error, Result};
+use crate::metadata_provider::SegmentDescriptor;
+use crate::options::PinotReadOptions;
+use crate::pruning::descriptor_may_match;
+use crate::schema::{
+    with_lowercase_names, with_nullability, with_segment_info_columns, with_string_views,
+};
+use crate::segment::SegmentSource;
+use crate::table::{unreadable_columns, ExcludedColumn, PinotTable};
+use pinot_segment::SegmentReader;
+
+/// TableProvider serving a provided schema and opening segments on first scan
+///
+/// Every column is reported nullable, as with
+/// [`PinotReadOptions::nullable_schema`], since the schema does not say which
+/// columns have null values. Unless
+/// [`PinotReadOptions::strict_column_types`] is set, columns the readers
+/// cannot decode are left out, as tables opened from segments leave them
+/// out: BOOLEAN columns, and those the first segment holds in a form it
+/// cannot read (RAW numeric, multi-value, unsupported chunk compression).
+/// Segments of a table normally agree on these, as the table config sets
+/// them; a scan reading a column only a later segment cannot decode fails. [`PinotReadOptions::dictionary_arrays`] is not
+/// applied: which STRING columns are dictionary-encoded is only known from
+/// the segments, so they are read as `Utf8`.
+///
+/// Scans pruned down to part of the table by
+/// [`with_segment_descriptors`](Self::with_segment_descriptors) open the
+/// remaining segments for that scan only, while the table is not open yet;
+/// any other scan opens the whole table and keeps it.
+#[derive(Debug)]
+pub struct LazyPinotTable {
+    table_name: String,
+    /// Schema queries are planned against
+    schema: SchemaRef,
+    /// Columns of the provided schema left out of `schema` as unreadable
+    excluded_columns: Vec<ExcludedColumn>,
+    segment_paths: Vec<PathBuf>,
+    /// Provider descriptors of the segments, for pruning before opening them
+    descriptors: Option<Vec<SegmentDescriptor>>,
+    options: PinotReadOptions,
+    /// The opened table, or why opening it failed
+    table: OnceLock<std::result::Result<PinotTable, String>>,
+}
+
+impl LazyPinotTable {
+    /// Create a table over `segment_paths` reporting `schema`
+    ///
+    /// Only the first segment is opened, to find the columns it cannot
+    /// decode; the others are opened on first scan.
+    pub fn new(
+        table_name: &str,
+        schema: SchemaRef,
+        segment_paths: Vec<PathBuf>,
+        options: PinotReadOptions,
+    ) -> Self {
+        let excluded_columns = if options.strict_column_types {
+            Vec::new()
+        } else {
+            first_segment_unreadable_columns(table_name, &segment_paths, &schema)
+        };
+        let fields: Vec<_> = schema
+            .fields()
+            .iter()
+            .filter(|f| options.strict_column_types || f.data_type() != &DataType::Boolean)
+            .filter(|f| !excluded_columns.iter().any(|c| &c.name == f.name()))
+            .cloned()
+            .collect();
+        let mut schema = Arc::new(Schema::new(fields));
+        if options.string_view_arrays {
+            schema = with_string_views(&schema);
+        }
+        if options.segment_info_columns {
+            schema = with_segment_info_columns(&schema);
+        }
+        schema = with_nullability(&schema, |_| true);
+        if options.lowercase_column_names {
+            schema = with_lowercase_names(&schema);
+        }
+
+        Self {
+            table_name: table_name.to_string(),
+            schema,
+            excluded_columns,
+            segment_paths,
+            descriptors: None,
+            options: options
+                .with_dictionary_arrays(false)
+                .with_nullable_schema(true),
+            table: OnceLock::new(),
+        }
+    }
+
+    /// Prune segments on their descriptors' time ranges before opening them,
+    /// and report the descriptors' document count as the row estimate
+    ///
+    /// Segment paths without a descriptor are never pruned.
+    pub fn with_segment_descriptors(mut self, descriptors: Vec<SegmentDescriptor>) -> Self {
+        self.descriptors = Some(descriptors);
+        self
+    }
+
+    /// Descriptors set by [`with_segment_descriptors`](Self::with_segment_descriptors)
+    pub fn segment_descriptors(&self) -> Option<&[SegmentDescriptor]> {
+        self.descriptors.as_deref()
+    }
+
+    /// Paths of the segments whose descriptors `filters` exclude
+    fn pruned_paths(&self, filters: &[Expr]) -> HashSet<&Path> {
+        self.descriptors
+            .iter()
+            .flatten()
+            .filter(|descriptor| !descriptor_may_match(descriptor, filters))
+            .map(|descriptor| descriptor.path.as_path())
+            .collect()
+    }
+
+    /// Columns of the provided schema left out because the first segment
+    /// cannot decode them; empty with
+    /// [`PinotReadOptions::strict_column_types`]
+    pub fn excluded_columns(&self) -> &[ExcludedColumn] {
+        &self.excluded_columns
+    }
+
+    /// Whether the segments have been opened
+    pub fn is_open(&self) -> bool {
+        self.table.get().is_some()
+    }
+
+    /// The table over the segments, opening them on first use
+    ///
+    /// A failure is remembered, so later calls report it without retrying.
+    ///
+    /// # Errors
+    /// Returns error if the segments cannot be opened as one table.
+    pub fn table(&self) -> Result<&PinotTable> {
+        let opened = self.table.get_or_init(|| {
+            PinotTable::open_segments_with_options(
+                &self.segment_paths,
+                &self.table_name,
+                self.options.clone(),
+            )
+            .map_err(|e| e.to_string())
+        });
+        opened.as_ref().map_err(|message| {
+            Error::Internal(format!(
+                "Failed to open segments of table '{}': {}",
+                self.table_name, message
+            ))
+        })
+    }
+
+    /// Positions in `table`'s schema of the columns of `projection`
+    ///
+    /// Columns are matched by name and must have the type of the provided
+    /// schema.
+    fn table_projection(
+        &self,
+        table: &PinotTable,
+        projection: Option<&Vec<usize>>,
+    ) -> Result<Vec<usize>> {
+        let table_schema = table.schema();
+        let indices: Vec<usize> = match projection {
+            Some(projection) => projection.clone(),
+            None => (0..self.schema.fields().len()).collect(),
+        };
+        indices
+            .into_iter()
+            .map(|idx| {
+                let field = self.schema.field(idx);
+                let table_idx = table_schema.index_of(field.name()).map_err(|_| {
+                    match table.excluded_columns().iter().find(|c| &c.name == field.name()) {
+                        Some(excluded) => Error::Internal(format!(
+                            "Column '{}' of table '{}' cannot be read from segment {}: {}",
+                            field.name(),
+                            self.table_name,
+                            excluded.segment_name,
+                            excluded.reason
+                        )),
+                        None => Error::Internal(format!(
+                            "Column '{}' of the schema of table '{}' is missing from its segments",
+                            field.name(),
+                            self.table_name
+                        )),
+                    }
+                })?;
+                let table_type = table_schema.field(table_idx).data_type();
+                if table_type != field.data_type() {
+                    return Err(Error::Internal(format!(
+                        "Column '{}' of table '{}' is {} in its schema but {} in its segments",
+                        field.name(),
+                        self.table_name,
+                        field.data_type(),
+                        table_type
+                    )));
+                }
+                Ok(table_idx)
+            })
+            .collect()
+    }
+}
+
+/// Columns of `schema` the first of `segment_paths` holds but cannot decode
+///
+/// A first segment that fails to open excludes nothing; opening the table
+/// on first scan reports the failure.
+fn first_segment_unreadable_columns(
+    table_name: &str,
+    segment_paths: &[PathBuf],
+    schema: &Schema,
+) -> Vec<ExcludedColumn> {
+    let Some(path) = segment_paths.first() else {
+        return Vec::new();
+    };
+    let reader = match SegmentReader::open(path) {
+        Ok(reader) => reader,
+        Err(e) => {
+            tracing::warn!(
+                table = table_name,
+                path = %path.display(),
+                error = %e,
+                "failed to open first segment to check its columns"
+            );
+            return Vec::new();
+        }
+    };
+    let segments: [Arc<dyn SegmentSource>; 1] = [Arc::new(reader)];
+    let excluded = unreadable_columns(&segments, schema);
+    for column in &excluded {
+        tracing::warn!(
+            column = %column.name,
+            segment = %column.segment_name,
+            reason = %column.reason,
+            "excluding unreadable column from table schema"
+        );
+    }
+    excluded
+}
+
+#[async_trait]
+impl TableProvider for LazyPinotTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    fn statistics(&self) -> Option<Statistics> {
+        let descriptors = self.descriptors.as_ref()?;
+        let num_rows: usize = descriptors.iter().map(|d| d.total_docs as usize).sum();
+        Some(Statistics {
+            num_rows: Precision::Inexact(num_rows),
+            total_byte_size: Precision::Absent,
+            column_statistics: Statistics::unknown_column(&self.schema),
+        })
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> DataFusionResult<Vec<TableProviderFilterPushDown>> {
+        // Filters prune segments and documents once the table is open;
+        // DataFusion still evaluates them against the rows we return
+        Ok(vec![TableProviderFilterPushDown::Inexact; filters.len()])
+    }
+
+    async fn scan(
+        &self,
+        state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let to_df_error = |e: Error| DataFusionError::External(Box::new(e));
+        let pruned = self.pruned_paths(filters);
+        let pruned_table;
+        let table = if pruned.is_empty() || self.is_open() {
+            self.table().map_err(to_df_error)?
+        } else {
+            let paths: Vec<&PathBuf> = self
+                .segment_paths
+                .iter()
+                .filter(|path| !pruned.contains(path.as_path()))
+                .collect();
+            if paths.is_empty() {
+                let schema = match projection {
+                    Some(projection) => Arc::new(self.schema.project(projection)?),
+                    None => self.schema.clone(),
+                };
+                return Ok(Arc::new(EmptyExec::new(schema)));
+            }
+            tracing::debug!(
+                table = %self.table_name,
+                pruned = pruned.len(),
+                opened = paths.len(),
+                "opening segments left after pruning"
+            );
+            pruned_table = PinotTable::open_segments_with_options(
+                &paths,
+                &self.table_name,
+                self.options.clone(),
+            )
+            .map_err(to_df_error)?;
+            &pruned_table
+        };
+        let projection = self
+            .table_projection(table, projection)
+            .map_err(to_df_error)?;
+        table.scan(state, Some(&projection), filters, limit).await
+    }
+}
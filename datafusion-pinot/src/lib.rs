@@ -32,6 +32,12 @@
 //! # }
 //! ```
 //!
+//! [`SessionContextExt`] shortens the registration to
+//! `ctx.register_pinot("pinot", data_dir)?` and reads a single segment with
+//! `ctx.read_pinot_segment(path)?`. After `ctx.register_pinot_functions()`,
+//! SQL can query a directory directly:
+//! `SELECT * FROM pinot_segment('/path/to/segment/v3') LIMIT 5`.
+//!
 //! # Controller Mode (requires `controller` feature)
 //!
 //! Enable the `controller` feature in your `Cargo.toml`:
@@ -71,29 +77,65 @@
 //! - **Metadata Providers**: Abstract table/segment discovery
 //!   - `FileSystemMetadataProvider`: Scans local directories
 //!   - `ControllerMetadataProvider`: Uses HTTP API (feature-gated)
+//!   - `StaticMetadataProvider`: Serves an explicit table to segments mapping
+//!   - `ObjectStoreMetadataProvider`: Lists a deep store bucket and caches
+//!     segments locally (feature-gated)
+//!   - `CachedMetadataProvider`: Reuses another provider's answers for a TTL
+//!   - `ChainedMetadataProvider`: Serves the tables of several providers
 //!
 //! - **DataFusion Integration**: Implements TableProvider and ExecutionPlan
 //!   - `PinotCatalog`: Catalog-level table discovery
 //!   - `PinotTable`: TableProvider implementation
+//!   - `LazyPinotTable`: TableProvider over a provider-supplied schema that
+//!     opens segments on first scan
+//!   - `PinotSystemSchemaProvider`: `system.segments` introspection table
 //!   - Schema mapping from Pinot to Arrow types
 
 pub mod catalog;
+pub mod context;
 pub mod error;
 pub mod exec;
+pub mod export;
+pub mod lazy_table;
 pub mod metadata_provider;
+pub mod options;
+pub mod pruning;
 pub mod schema;
+pub mod segment;
+pub mod segment_info;
+pub mod star_tree;
+pub mod statistics;
+pub mod system;
 pub mod table;
 
 #[cfg(feature = "controller")]
 pub mod controller;
 
 pub use catalog::{PinotCatalog, PinotCatalogBuilder, PinotCatalogSource};
+pub use context::{PinotSegmentFunction, PinotTableFunction, SessionContextExt};
 pub use error::{Error, Result};
-pub use metadata_provider::{FileSystemMetadataProvider, MetadataProvider};
-pub use table::PinotTable;
+pub use export::{CsvWriterOptions, ExportFormat};
+pub use lazy_table::LazyPinotTable;
+pub use metadata_provider::{
+    CachedMetadataProvider, ChainedMetadataProvider, FileSystemMetadataProvider, MetadataProvider,
+    SegmentDescriptor, StaticMetadataProvider,
+};
+pub use options::PinotReadOptions;
+pub use segment::{MockSegmentReader, SegmentSource};
+pub use segment_info::SegmentInfoColumnsRule;
+pub use star_tree::{StarTreeExec, StarTreeRule};
+pub use system::PinotSystemSchemaProvider;
+pub use table::{ExcludedColumn, PinotTable, SegmentInfo, SkippedSegment};
 
 #[cfg(feature = "controller")]
-pub use controller::PinotControllerClient;
+pub use controller::{
+    CircuitState, ControllerAuth, PinotControllerClient, PinotControllerClientBuilder,
+};
+#[cfg(feature = "controller")]
+pub use error::HttpErrorKind;
 
 #[cfg(feature = "controller")]
 pub use metadata_provider::ControllerMetadataProvider;
+
+#[cfg(feature = "object-store")]
+pub use metadata_provider::ObjectStoreMetadataProvider;
@@ -3,21 +3,88 @@
 //! This module defines the `MetadataProvider` trait that abstracts the source
 //! of metadata (table lists, segment locations) from the catalog implementation.
 //!
-//! Two implementations are provided:
+//! Four implementations are provided:
 //! - `FileSystemMetadataProvider`: Discovers tables by scanning local directories
 //! - `ControllerMetadataProvider`: Discovers tables via HTTP calls to Pinot controller
+//! - `StaticMetadataProvider`: Serves an explicit table to segment paths mapping
+//! - `ObjectStoreMetadataProvider`: Discovers tables in a deep store bucket
+//!   and caches their segments locally
+//!
+//! `CachedMetadataProvider` wraps any of them to reuse their answers for a
+//! while, and `ChainedMetadataProvider` serves the tables of several.
 
 use crate::error::{Error, Result};
+use crate::segment::{resolve_symlink, segment_data_dir, segment_dir_name, sort_segment_paths};
 use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use pinot_segment::{SegmentMetadata, TimeUnit};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 
-#[cfg(feature = "controller")]
 use std::sync::Arc;
 
 #[cfg(feature = "controller")]
 use crate::controller::PinotControllerClient;
 
+#[cfg(feature = "object-store")]
+use crate::segment::is_temporary_dir_name;
+#[cfg(feature = "object-store")]
+use futures::TryStreamExt;
+#[cfg(feature = "object-store")]
+use object_store::{path::Path as ObjectPath, ObjectMeta, ObjectStore};
+#[cfg(feature = "object-store")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// What a metadata provider knows of one segment without opening it
+///
+/// Enough to prune segments on their time range and to size scans before any
+/// segment is opened (see [`MetadataProvider::get_segment_infos`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentDescriptor {
+    pub name: String,
+    /// Segment directory, as returned by [`MetadataProvider::get_segment_paths`]
+    pub path: PathBuf,
+    pub total_docs: u32,
+    /// Time column the segment is partitioned on
+    pub time_column: Option<String>,
+    /// Unit the time column stores its values in
+    pub time_unit: Option<TimeUnit>,
+    /// Time column range in epoch milliseconds (inclusive), if recorded
+    pub time_range_millis: Option<(i64, i64)>,
+    /// Checksum of the segment data
+    pub crc: Option<u64>,
+}
+
+impl SegmentDescriptor {
+    /// Describe the segment at `path` from its already parsed metadata
+    pub fn from_metadata(path: impl Into<PathBuf>, metadata: &SegmentMetadata) -> Self {
+        Self {
+            name: metadata.segment_name.clone(),
+            path: path.into(),
+            total_docs: metadata.total_docs,
+            time_column: metadata.time_column.clone(),
+            time_unit: metadata.time_unit,
+            time_range_millis: metadata.time_range_millis(),
+            crc: metadata.crc,
+        }
+    }
+
+    /// Describe the segment at `path` by parsing its `metadata.properties`
+    /// (and `creation.meta`), without opening its indexes
+    ///
+    /// # Errors
+    /// Returns error if the metadata is missing or cannot be parsed.
+    pub fn read(path: &Path) -> Result<Self> {
+        let metadata = SegmentMetadata::from_file(&path.join("metadata.properties"))?;
+        Ok(Self::from_metadata(path, &metadata))
+    }
+}
+
 /// Trait for discovering Pinot table metadata and segment locations
 ///
 /// This trait abstracts whether metadata comes from:
@@ -65,7 +132,8 @@ pub trait MetadataProvider: Send + Sync + std::fmt::Debug {
     /// Get filesystem paths to all segments for a table
     ///
     /// Returns paths to segment directories (typically pointing to the `v3/` subdirectory
-    /// containing the segment metadata and data files).
+    /// containing the segment metadata and data files). The built-in providers
+    /// order them by segment sequence number, as Pinot does.
     ///
     /// # Arguments
     /// * `table_name` - Name of the table (without type suffix)
@@ -85,6 +153,52 @@ pub trait MetadataProvider: Send + Sync + std::fmt::Debug {
     /// // Returns: ["/tmp/pinot/.../baseballStats_OFFLINE/seg1/v3", ...]
     /// ```
     async fn get_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>>;
+
+    /// Describe every segment of a table, in the order of
+    /// [`get_segment_paths`](Self::get_segment_paths)
+    ///
+    /// Lets tables prune segments on their time range and size scans before
+    /// opening any of them. Providers that know segment metadata cheaply
+    /// should override this; the default parses each segment's
+    /// `metadata.properties`.
+    ///
+    /// # Arguments
+    /// * `table_name` - Name of the table (without type suffix)
+    ///
+    /// # Errors
+    /// Returns error if the segments cannot be listed or a segment's metadata
+    /// cannot be read
+    async fn get_segment_infos(&self, table_name: &str) -> Result<Vec<SegmentDescriptor>> {
+        self.get_segment_paths(table_name)
+            .await?
+            .iter()
+            .map(|path| SegmentDescriptor::read(path))
+            .collect()
+    }
+
+    /// Arrow schema of a table, when the provider knows it without opening
+    /// any segment
+    ///
+    /// The catalog then plans queries against this schema and opens the
+    /// table's segments only when a query scans it (see
+    /// [`LazyPinotTable`](crate::LazyPinotTable)). The default knows no
+    /// schema, so tables are opened to derive it from their segments.
+    ///
+    /// # Arguments
+    /// * `table_name` - Name of the table (without type suffix)
+    ///
+    /// # Errors
+    /// Returns error if the schema source is unavailable or the schema cannot
+    /// be mapped to Arrow
+    async fn get_table_schema(&self, _table_name: &str) -> Result<Option<SchemaRef>> {
+        Ok(None)
+    }
+
+    /// Drop any cached metadata so the next call reads from the source again
+    ///
+    /// Called by `PinotCatalog::refresh()`. Providers without caches need not
+    /// override this.
+    fn refresh(&self) {}
 }
 
 /// Filesystem-based metadata provider
@@ -93,6 +207,23 @@ pub trait MetadataProvider: Send + Sync + std::fmt::Debug {
 /// subdirectories. This is the default implementation that works with Pinot's
 /// standard directory layout.
 ///
+/// Segments are read from `<segment>/v3`, or from `<segment>` itself when it
+/// has no `v3/` level (segments untarred flat). Staging directories (`tmp`,
+/// `tmp-<uuid>`, `*.tmp`) are skipped, and so are segments with no
+/// `metadata.properties` or `columns.psf`, which are still being written.
+///
+/// Symlinked table and segment directories are followed and the returned
+/// segment paths are the resolved ones; dangling links and link cycles are
+/// skipped.
+///
+/// A provider built with [`FileSystemMetadataProvider::new_multi`] scans several
+/// data directories (e.g. one per server) and treats a table found in more than
+/// one of them as a single table holding all of their segments. A segment
+/// present in several directories is read once, from the newest copy.
+///
+/// Segment descriptors are parsed from `metadata.properties` on first use and
+/// cached until that file changes. Clones share the cache.
+///
 /// # Example
 /// ```ignore
 /// use datafusion_pinot::metadata_provider::FileSystemMetadataProvider;
@@ -102,113 +233,628 @@ pub trait MetadataProvider: Send + Sync + std::fmt::Debug {
 /// ```
 #[derive(Debug, Clone)]
 pub struct FileSystemMetadataProvider {
-    data_dir: PathBuf,
+    data_dirs: Vec<PathBuf>,
+    descriptors: Arc<RwLock<DescriptorCache>>,
 }
 
+/// Segment descriptors by segment path, with the `metadata.properties`
+/// modification time they were parsed at
+type DescriptorCache = HashMap<PathBuf, (Option<SystemTime>, SegmentDescriptor)>;
+
 impl FileSystemMetadataProvider {
     /// Create a new filesystem metadata provider
     ///
     /// # Arguments
     /// * `data_dir` - Root directory containing table directories (e.g., `/tmp/pinot/quickstart/PinotServerDataDir0`)
     pub fn new<P: Into<PathBuf>>(data_dir: P) -> Self {
+        Self::new_multi(vec![data_dir.into()])
+    }
+
+    /// Create a provider scanning several data directories
+    ///
+    /// Table lists are merged and each table's segments are the union of its
+    /// segments in every directory; a segment name present in several
+    /// directories is read from the copy modified last, with a warning.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let provider = FileSystemMetadataProvider::new_multi(vec![
+    ///     PathBuf::from("/tmp/pinot/quickstart/PinotServerDataDir0"),
+    ///     PathBuf::from("/tmp/pinot/quickstart/PinotServerDataDir1"),
+    /// ]);
+    /// ```
+    pub fn new_multi(data_dirs: Vec<PathBuf>) -> Self {
         Self {
-            data_dir: data_dir.into(),
+            data_dirs,
+            descriptors: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    /// Get the data directory path
+    /// Get the data directory path (the first one for multi-directory providers)
     pub fn data_dir(&self) -> &Path {
-        &self.data_dir
+        self.data_dirs
+            .first()
+            .map_or(Path::new(""), PathBuf::as_path)
+    }
+
+    /// Get all scanned data directory paths
+    pub fn data_dirs(&self) -> &[PathBuf] {
+        &self.data_dirs
+    }
+
+    /// Table directory for `table_name` under `data_dir`, OFFLINE before
+    /// REALTIME, with a symlinked table directory resolved
+    fn table_dir(data_dir: &Path, table_name: &str) -> Option<PathBuf> {
+        let offline_dir = data_dir.join(format!("{}_OFFLINE", table_name));
+        let realtime_dir = data_dir.join(format!("{}_REALTIME", table_name));
+
+        if offline_dir.is_dir() {
+            resolve_symlink(&offline_dir)
+        } else if realtime_dir.is_dir() {
+            resolve_symlink(&realtime_dir)
+        } else {
+            None
+        }
+    }
+
+    /// Push the segment directories of `table_dir` onto `segment_paths`:
+    /// `<segment>/v3`, or `<segment>` for segments without a `v3/` level
+    fn collect_segment_paths(table_dir: &Path, segment_paths: &mut Vec<PathBuf>) -> Result<()> {
+        let entries = fs::read_dir(table_dir)
+            .map_err(|e| Error::Internal(format!("Failed to read table directory: {}", e)))?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| Error::Internal(e.to_string()))?;
+            let path = entry.path();
+
+            // Temporary and incomplete segment directories are skipped
+            if path.is_dir() {
+                segment_paths.extend(segment_data_dir(&path));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keep one segment path per segment name
+    ///
+    /// A segment found in several data directories is a leftover of a move
+    /// between servers; the copy whose `metadata.properties` was modified last
+    /// wins.
+    fn dedup_segments(segment_paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let mut by_name: HashMap<OsString, PathBuf> = HashMap::new();
+        for path in segment_paths {
+            let Some(name) = segment_dir_name(&path) else {
+                continue;
+            };
+            let name = name.to_os_string();
+            match by_name.entry(name.clone()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(path);
+                }
+                // The same directory reached through several symlinks
+                Entry::Occupied(entry) if *entry.get() == path => {}
+                Entry::Occupied(mut entry) => {
+                    let kept = if Self::modified(&path) > Self::modified(entry.get()) {
+                        entry.insert(path.clone());
+                        path
+                    } else {
+                        entry.get().clone()
+                    };
+                    tracing::warn!(
+                        segment = %name.to_string_lossy(),
+                        kept = %kept.display(),
+                        "segment found in several data directories, keeping the newest"
+                    );
+                }
+            }
+        }
+        by_name.into_values().collect()
+    }
+
+    /// Modification time of the segment's `metadata.properties`
+    fn modified(segment_path: &Path) -> Option<SystemTime> {
+        fs::metadata(segment_path.join("metadata.properties"))
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    /// Descriptor of the segment at `path`, parsed again only when its
+    /// `metadata.properties` changed since it was cached
+    fn segment_descriptor(&self, path: &Path) -> Result<SegmentDescriptor> {
+        let modified = Self::modified(path);
+        if let Some((cached_at, descriptor)) = self.descriptors.read().unwrap().get(path) {
+            if modified.is_some() && *cached_at == modified {
+                return Ok(descriptor.clone());
+            }
+        }
+
+        let descriptor = SegmentDescriptor::read(path)?;
+        self.descriptors
+            .write()
+            .unwrap()
+            .insert(path.to_path_buf(), (modified, descriptor.clone()));
+        Ok(descriptor)
+    }
+
+    /// Data directories joined for error messages
+    fn data_dirs_display(&self) -> String {
+        self.data_dirs
+            .iter()
+            .map(|dir| dir.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
     }
 }
 
 #[async_trait]
 impl MetadataProvider for FileSystemMetadataProvider {
     async fn list_tables(&self) -> Result<Vec<String>> {
-        let entries = fs::read_dir(&self.data_dir)
-            .map_err(|e| Error::Internal(format!("Failed to read data directory: {}", e)))?;
-
         let mut table_names = Vec::new();
 
-        for entry in entries {
-            let entry = entry.map_err(|e| Error::Internal(e.to_string()))?;
-            let path = entry.path();
+        for data_dir in &self.data_dirs {
+            let entries = fs::read_dir(data_dir)
+                .map_err(|e| Error::Internal(format!("Failed to read data directory: {}", e)))?;
+
+            for entry in entries {
+                let entry = entry.map_err(|e| Error::Internal(e.to_string()))?;
+                let path = entry.path();
 
-            // Look for directories ending with _OFFLINE or _REALTIME
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if name.ends_with("_OFFLINE") {
-                    // Strip _OFFLINE suffix to get table name
-                    let table_name = name.strip_suffix("_OFFLINE").unwrap().to_string();
-                    table_names.push(table_name);
-                } else if name.ends_with("_REALTIME") {
-                    // Strip _REALTIME suffix
-                    let table_name = name.strip_suffix("_REALTIME").unwrap().to_string();
-                    if !table_names.contains(&table_name) {
-                        table_names.push(table_name);
+                // Look for directories, or links to them, ending with
+                // _OFFLINE or _REALTIME
+                if !path.is_dir() {
+                    continue;
+                }
+                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                    let table_name = name
+                        .strip_suffix("_OFFLINE")
+                        .or_else(|| name.strip_suffix("_REALTIME"));
+                    if let Some(table_name) = table_name {
+                        table_names.push(table_name.to_string());
                     }
                 }
             }
         }
 
+        // Sort and drop tables seen under both suffixes or in several directories
         table_names.sort();
+        table_names.dedup();
         Ok(table_names)
     }
 
     async fn table_exists(&self, name: &str) -> bool {
-        let offline_dir = self.data_dir.join(format!("{}_OFFLINE", name));
-        let realtime_dir = self.data_dir.join(format!("{}_REALTIME", name));
-        offline_dir.exists() || realtime_dir.exists()
+        self.data_dirs
+            .iter()
+            .any(|data_dir| Self::table_dir(data_dir, name).is_some())
     }
 
     async fn get_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
-        // Try OFFLINE first, then REALTIME
-        let offline_dir = self.data_dir.join(format!("{}_OFFLINE", table_name));
-        let realtime_dir = self.data_dir.join(format!("{}_REALTIME", table_name));
-
-        let table_dir = if offline_dir.exists() {
-            offline_dir
-        } else if realtime_dir.exists() {
-            realtime_dir
-        } else {
+        // Data directories linking to the same table directory scan it once
+        let mut seen = HashSet::new();
+        let table_dirs: Vec<PathBuf> = self
+            .data_dirs
+            .iter()
+            .filter_map(|data_dir| Self::table_dir(data_dir, table_name))
+            .filter(|table_dir| seen.insert(table_dir.clone()))
+            .collect();
+
+        if table_dirs.is_empty() {
             return Err(Error::Internal(format!(
                 "Table '{}' not found in {}",
                 table_name,
-                self.data_dir.display()
+                self.data_dirs_display()
             )));
-        };
-
-        // Read all segment directories
-        let entries = fs::read_dir(&table_dir)
-            .map_err(|e| Error::Internal(format!("Failed to read table directory: {}", e)))?;
+        }
 
         let mut segment_paths = Vec::new();
-        for entry in entries {
-            let entry = entry.map_err(|e| Error::Internal(e.to_string()))?;
-            let path = entry.path();
-
-            // Skip non-directories and temporary directories
-            if !path.is_dir() || path.file_name().unwrap().to_str().unwrap() == "tmp" {
-                continue;
-            }
-
-            // Check if it's a valid segment (has v3 subdirectory)
-            let v3_path = path.join("v3");
-            if v3_path.exists() && v3_path.is_dir() {
-                segment_paths.push(v3_path);
-            }
+        for table_dir in &table_dirs {
+            Self::collect_segment_paths(table_dir, &mut segment_paths)?;
         }
 
         if segment_paths.is_empty() {
+            let table_dirs: Vec<String> = table_dirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect();
             return Err(Error::Internal(format!(
                 "No valid segments found in {}",
-                table_dir.display()
+                table_dirs.join(", ")
             )));
         }
 
-        // Sort for consistent ordering
-        segment_paths.sort();
+        let mut segment_paths = Self::dedup_segments(segment_paths);
+        // Sort by sequence number, as Pinot orders segments
+        sort_segment_paths(&mut segment_paths);
         Ok(segment_paths)
     }
+
+    async fn get_segment_infos(&self, table_name: &str) -> Result<Vec<SegmentDescriptor>> {
+        self.get_segment_paths(table_name)
+            .await?
+            .iter()
+            .map(|path| self.segment_descriptor(path))
+            .collect()
+    }
+
+    fn refresh(&self) {
+        self.descriptors.write().unwrap().clear();
+    }
+}
+
+/// Metadata provider over an explicit table name to segment paths mapping
+///
+/// For tests and for deployments whose discovery is managed elsewhere: the
+/// segment paths (`v3` directories) need not follow any directory layout.
+/// Tables can be added and removed while the provider is in use; catalogs
+/// cache table lists and segment paths, so call `PinotCatalog::refresh()` or
+/// enable change detection for them to notice.
+///
+/// # Example
+/// ```ignore
+/// use datafusion_pinot::StaticMetadataProvider;
+///
+/// let provider = Arc::new(StaticMetadataProvider::new(HashMap::from([(
+///     "events".to_string(),
+///     vec![PathBuf::from("/data/events_0/v3")],
+/// )])));
+/// provider.insert_table("clicks", vec![PathBuf::from("/data/clicks_0/v3")]);
+/// provider.set_table_schema("clicks", clicks_schema);
+/// let catalog = PinotCatalog::builder().provider(provider.clone()).build()?;
+/// ```
+#[derive(Debug, Default)]
+pub struct StaticMetadataProvider {
+    tables: RwLock<HashMap<String, Vec<PathBuf>>>,
+    /// Schemas given with `set_table_schema`, by table name
+    schemas: RwLock<HashMap<String, SchemaRef>>,
+}
+
+impl StaticMetadataProvider {
+    /// Create a provider serving `tables`, a map of table name to segment paths
+    pub fn new(tables: HashMap<String, Vec<PathBuf>>) -> Self {
+        Self {
+            tables: RwLock::new(tables),
+            schemas: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Serve `schema` as the schema of table `name`; returns the old one
+    ///
+    /// Catalogs then plan queries on the table against `schema` and open
+    /// its segments on first scan, instead of opening them to derive it.
+    /// The columns a query reads must have the types the segments store.
+    pub fn set_table_schema(
+        &self,
+        name: impl Into<String>,
+        schema: SchemaRef,
+    ) -> Option<SchemaRef> {
+        self.schemas.write().unwrap().insert(name.into(), schema)
+    }
+
+    /// Add table `name`, or replace its segment paths; returns the old ones
+    pub fn insert_table(
+        &self,
+        name: impl Into<String>,
+        segment_paths: Vec<PathBuf>,
+    ) -> Option<Vec<PathBuf>> {
+        self.tables
+            .write()
+            .unwrap()
+            .insert(name.into(), segment_paths)
+    }
+
+    /// Remove table `name` and its schema; returns its segment paths if it
+    /// was present
+    pub fn remove_table(&self, name: &str) -> Option<Vec<PathBuf>> {
+        self.schemas.write().unwrap().remove(name);
+        self.tables.write().unwrap().remove(name)
+    }
+
+    /// Check that every segment path exists
+    ///
+    /// # Errors
+    /// Returns error naming the first missing path and its table.
+    pub fn validate(&self) -> Result<()> {
+        let tables = self.tables.read().unwrap();
+        for (name, segment_paths) in tables.iter() {
+            if let Some(missing) = segment_paths.iter().find(|path| !path.exists()) {
+                return Err(Error::Internal(format!(
+                    "Segment path of table '{}' does not exist: {}",
+                    name,
+                    missing.display()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for StaticMetadataProvider {
+    async fn list_tables(&self) -> Result<Vec<String>> {
+        let mut table_names: Vec<String> = self.tables.read().unwrap().keys().cloned().collect();
+        table_names.sort();
+        Ok(table_names)
+    }
+
+    async fn table_exists(&self, name: &str) -> bool {
+        self.tables.read().unwrap().contains_key(name)
+    }
+
+    async fn get_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
+        self.tables
+            .read()
+            .unwrap()
+            .get(table_name)
+            .cloned()
+            .ok_or_else(|| Error::Internal(format!("Table '{}' not found", table_name)))
+    }
+
+    async fn get_table_schema(&self, table_name: &str) -> Result<Option<SchemaRef>> {
+        Ok(self.schemas.read().unwrap().get(table_name).cloned())
+    }
+}
+
+/// Caching decorator for any metadata provider
+///
+/// Table lists and per-table segment paths are served from memory until they
+/// are older than their TTL, after which the next call asks the wrapped
+/// provider again. Only one refresh of the table list, and one of each
+/// table's segment paths, runs at a time: concurrent callers wait for it and
+/// share its answer, while refreshes of other tables proceed. Errors are not
+/// cached.
+///
+/// # Example
+/// ```ignore
+/// use datafusion_pinot::{CachedMetadataProvider, FileSystemMetadataProvider};
+///
+/// let provider = CachedMetadataProvider::new(
+///     Arc::new(FileSystemMetadataProvider::new("/tmp/pinot/quickstart/PinotServerDataDir0")),
+///     Duration::from_secs(30),
+/// )
+/// .with_segments_ttl(Duration::from_secs(5));
+/// let catalog = PinotCatalog::from_provider(Arc::new(provider));
+/// ```
+#[derive(Debug)]
+pub struct CachedMetadataProvider<P: MetadataProvider + ?Sized> {
+    inner: Arc<P>,
+    tables_ttl: Duration,
+    segments_ttl: Duration,
+    /// Table names with when they were listed
+    tables: RwLock<Option<(Instant, Vec<String>)>>,
+    /// Segment paths by table, with when they were listed
+    segment_paths: RwLock<HashMap<String, (Instant, Vec<PathBuf>)>>,
+    /// Held while the table list is refreshed
+    tables_refresh: futures::lock::Mutex<()>,
+    /// Per table, held while its segment paths are refreshed
+    segments_refresh: Mutex<HashMap<String, Arc<futures::lock::Mutex<()>>>>,
+}
+
+impl<P: MetadataProvider + ?Sized> CachedMetadataProvider<P> {
+    /// Cache the answers of `inner` for `ttl`
+    pub fn new(inner: Arc<P>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            tables_ttl: ttl,
+            segments_ttl: ttl,
+            tables: RwLock::new(None),
+            segment_paths: RwLock::new(HashMap::new()),
+            tables_refresh: futures::lock::Mutex::new(()),
+            segments_refresh: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Keep table lists for `ttl` instead of the TTL given to `new`
+    pub fn with_tables_ttl(mut self, ttl: Duration) -> Self {
+        self.tables_ttl = ttl;
+        self
+    }
+
+    /// Keep segment paths for `ttl` instead of the TTL given to `new`
+    pub fn with_segments_ttl(mut self, ttl: Duration) -> Self {
+        self.segments_ttl = ttl;
+        self
+    }
+
+    /// Get the wrapped provider
+    pub fn inner(&self) -> &Arc<P> {
+        &self.inner
+    }
+
+    /// Drop every cached answer
+    pub fn invalidate(&self) {
+        *self.tables.write().unwrap() = None;
+        self.segment_paths.write().unwrap().clear();
+    }
+
+    /// Drop the cached segment paths of `table_name`
+    pub fn invalidate_table(&self, table_name: &str) {
+        self.segment_paths.write().unwrap().remove(table_name);
+    }
+
+    fn fresh_tables(&self) -> Option<Vec<String>> {
+        match &*self.tables.read().unwrap() {
+            Some((listed, tables)) if listed.elapsed() < self.tables_ttl => Some(tables.clone()),
+            _ => None,
+        }
+    }
+
+    /// Lock serializing the segment path refreshes of `table_name`
+    fn segments_refresh_lock(&self, table_name: &str) -> Arc<futures::lock::Mutex<()>> {
+        self.segments_refresh
+            .lock()
+            .unwrap()
+            .entry(table_name.to_string())
+            .or_default()
+            .clone()
+    }
+
+    fn fresh_segment_paths(&self, table_name: &str) -> Option<Vec<PathBuf>> {
+        match self.segment_paths.read().unwrap().get(table_name) {
+            Some((listed, paths)) if listed.elapsed() < self.segments_ttl => Some(paths.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl<P: MetadataProvider + ?Sized> MetadataProvider for CachedMetadataProvider<P> {
+    async fn list_tables(&self) -> Result<Vec<String>> {
+        if let Some(tables) = self.fresh_tables() {
+            return Ok(tables);
+        }
+        let _refresh = self.tables_refresh.lock().await;
+        // Answered by the refresh this call waited for
+        if let Some(tables) = self.fresh_tables() {
+            return Ok(tables);
+        }
+        let tables = self.inner.list_tables().await?;
+        *self.tables.write().unwrap() = Some((Instant::now(), tables.clone()));
+        Ok(tables)
+    }
+
+    async fn get_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
+        if let Some(paths) = self.fresh_segment_paths(table_name) {
+            return Ok(paths);
+        }
+        let refresh = self.segments_refresh_lock(table_name);
+        let _refresh = refresh.lock().await;
+        // Answered by the refresh this call waited for
+        if let Some(paths) = self.fresh_segment_paths(table_name) {
+            return Ok(paths);
+        }
+        let paths = self.inner.get_segment_paths(table_name).await?;
+        self.segment_paths
+            .write()
+            .unwrap()
+            .insert(table_name.to_string(), (Instant::now(), paths.clone()));
+        Ok(paths)
+    }
+
+    async fn get_segment_infos(&self, table_name: &str) -> Result<Vec<SegmentDescriptor>> {
+        self.inner.get_segment_infos(table_name).await
+    }
+
+    async fn get_table_schema(&self, table_name: &str) -> Result<Option<SchemaRef>> {
+        self.inner.get_table_schema(table_name).await
+    }
+
+    fn refresh(&self) {
+        self.invalidate();
+        self.inner.refresh();
+    }
+}
+
+/// Metadata provider serving the tables of several providers
+///
+/// For hybrid setups, e.g. most tables from a controller plus a few ad-hoc
+/// tables in a local directory. Tables are the union of every provider's
+/// tables; a table several providers list is served by the first of them.
+/// A provider that fails to list its tables is skipped with a warning, so it
+/// cannot hide the tables of the others.
+///
+/// # Example
+/// ```ignore
+/// use datafusion_pinot::{ChainedMetadataProvider, FileSystemMetadataProvider};
+///
+/// let provider = ChainedMetadataProvider::new(vec![
+///     Arc::new(ControllerMetadataProvider::new(client, "/data/pinot")),
+///     Arc::new(FileSystemMetadataProvider::new("/data/adhoc")),
+/// ]);
+/// ```
+#[derive(Debug)]
+pub struct ChainedMetadataProvider {
+    providers: Vec<Arc<dyn MetadataProvider>>,
+}
+
+impl ChainedMetadataProvider {
+    /// Chain `providers`, earlier ones taking precedence
+    pub fn new(providers: Vec<Arc<dyn MetadataProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Get the chained providers, in order of precedence
+    pub fn providers(&self) -> &[Arc<dyn MetadataProvider>] {
+        &self.providers
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for ChainedMetadataProvider {
+    async fn list_tables(&self) -> Result<Vec<String>> {
+        let mut table_names = Vec::new();
+        let mut last_error = None;
+        let mut listed = false;
+        for (index, provider) in self.providers.iter().enumerate() {
+            match provider.list_tables().await {
+                Ok(names) => {
+                    table_names.extend(names);
+                    listed = true;
+                }
+                Err(e) => {
+                    tracing::warn!(provider = index, error = %e, "failed to list tables");
+                    last_error = Some(e);
+                }
+            }
+        }
+        // Only fail when no provider could list its tables
+        if let (false, Some(e)) = (listed, last_error) {
+            return Err(e);
+        }
+
+        table_names.sort();
+        table_names.dedup();
+        Ok(table_names)
+    }
+
+    async fn table_exists(&self, name: &str) -> bool {
+        for provider in &self.providers {
+            if provider.table_exists(name).await {
+                return true;
+            }
+        }
+        false
+    }
+
+    async fn get_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
+        for provider in &self.providers {
+            if provider.table_exists(table_name).await {
+                return provider.get_segment_paths(table_name).await;
+            }
+        }
+        Err(Error::Internal(format!(
+            "Table '{}' not found in any of {} providers",
+            table_name,
+            self.providers.len()
+        )))
+    }
+
+    async fn get_segment_infos(&self, table_name: &str) -> Result<Vec<SegmentDescriptor>> {
+        for provider in &self.providers {
+            if provider.table_exists(table_name).await {
+                return provider.get_segment_infos(table_name).await;
+            }
+        }
+        Err(Error::Internal(format!(
+            "Table '{}' not found in any of {} providers",
+            table_name,
+            self.providers.len()
+        )))
+    }
+
+    async fn get_table_schema(&self, table_name: &str) -> Result<Option<SchemaRef>> {
+        for provider in &self.providers {
+            if provider.table_exists(table_name).await {
+                return provider.get_table_schema(table_name).await;
+            }
+        }
+        Ok(None)
+    }
+
+    fn refresh(&self) {
+        for provider in &self.providers {
+            provider.refresh();
+        }
+    }
 }
 
 /// Controller-based metadata provider (hybrid mode)
@@ -219,7 +865,8 @@ impl MetadataProvider for FileSystemMetadataProvider {
 /// segment access.
 ///
 /// # Hybrid Approach
-/// - **Metadata**: Controller HTTP API provides table list and segment names
+/// - **Metadata**: Controller HTTP API provides table list, segment names and
+///   table schemas
 /// - **Data**: Local filesystem provides actual segment files
 ///
 /// # Example
@@ -300,37 +947,134 @@ impl MetadataProvider for ControllerMetadataProvider {
     }
 
     async fn get_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
-        // Get segment names from controller (try OFFLINE first, then REALTIME)
-        let segment_names = self.client.list_segments(table_name, "OFFLINE").await?;
+        Ok(self
+            .local_segments(table_name)
+            .await?
+            .into_iter()
+            .map(|segment| segment.path)
+            .collect())
+    }
 
-        if segment_names.is_empty() {
-            // Try REALTIME if OFFLINE is empty
-            let realtime_segments = self.client.list_segments(table_name, "REALTIME").await?;
-            if realtime_segments.is_empty() {
-                return Err(Error::Internal(format!(
-                    "No segments found for table '{}' in controller",
-                    table_name
-                )));
-            }
-            return self.map_segments_to_paths(table_name, &realtime_segments, "REALTIME");
-        }
+    /// Descriptors from the controller's segment metadata endpoint
+    ///
+    /// A segment the controller has no metadata for, or whose metadata
+    /// cannot be fetched, is described from its local `metadata.properties`.
+    async fn get_segment_infos(&self, table_name: &str) -> Result<Vec<SegmentDescriptor>> {
+        use futures::{StreamExt, TryStreamExt};
 
-        self.map_segments_to_paths(table_name, &segment_names, "OFFLINE")
+        let segments = self.local_segments(table_name).await?;
+        futures::stream::iter(segments)
+            .map(|segment| self.segment_descriptor(segment))
+            .buffered(SEGMENT_METADATA_CONCURRENCY)
+            .try_collect()
+            .await
     }
+
+    /// The table's schema from the controller's `/schemas/{tableName}`
+    async fn get_table_schema(&self, table_name: &str) -> Result<Option<SchemaRef>> {
+        match self.client.get_schema(table_name).await? {
+            Some(schema) => Ok(Some(schema.to_arrow_schema()?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Segment metadata requests the controller provider keeps in flight
+#[cfg(feature = "controller")]
+const SEGMENT_METADATA_CONCURRENCY: usize = 8;
+
+/// Segment of a controller-listed table available locally
+#[cfg(feature = "controller")]
+#[derive(Debug)]
+struct LocalSegment {
+    /// Table name with type suffix, e.g. `events_REALTIME`
+    table_name_with_type: String,
+    name: String,
+    path: PathBuf,
 }
 
 #[cfg(feature = "controller")]
 impl ControllerMetadataProvider {
+    /// Segments the controller lists for `table_name` that are present
+    /// locally, sorted by sequence number
+    async fn local_segments(&self, table_name: &str) -> Result<Vec<LocalSegment>> {
+        // Hybrid tables have segments of both types
+        let (offline_segments, realtime_segments) = futures::try_join!(
+            self.client.list_segments(table_name, "OFFLINE"),
+            self.client.list_segments(table_name, "REALTIME"),
+        )?;
+        if offline_segments.is_empty() && realtime_segments.is_empty() {
+            return Err(Error::Internal(format!(
+                "No segments found for table '{}' in controller",
+                table_name
+            )));
+        }
+
+        let mut segments = Vec::new();
+        if !offline_segments.is_empty() {
+            segments.extend(self.map_segments_to_paths(table_name, &offline_segments, "OFFLINE")?);
+        }
+        if !realtime_segments.is_empty() {
+            segments.extend(self.realtime_segment_paths(table_name, &realtime_segments)?);
+        }
+
+        if segments.is_empty() {
+            return Err(Error::Internal(format!(
+                "No valid segment paths found for table '{}'",
+                table_name
+            )));
+        }
+
+        // Sort by sequence number, as Pinot orders segments
+        segments.sort_by_cached_key(|segment| crate::segment::SegmentSortKey::new(&segment.path));
+        Ok(segments)
+    }
+
+    /// Descriptor of `segment` from the controller, or from its local
+    /// metadata when the controller cannot provide it
+    async fn segment_descriptor(&self, segment: LocalSegment) -> Result<SegmentDescriptor> {
+        let metadata = self
+            .client
+            .get_segment_metadata(&segment.table_name_with_type, &segment.name)
+            .await;
+        let metadata = match metadata {
+            Ok(Some(metadata)) => metadata,
+            Ok(None) => return SegmentDescriptor::read(&segment.path),
+            Err(e) => {
+                tracing::warn!(
+                    segment = %segment.name,
+                    error = %e,
+                    "failed to fetch segment metadata, reading it locally"
+                );
+                return SegmentDescriptor::read(&segment.path);
+            }
+        };
+
+        let time_range_millis = metadata.start_time_millis.zip(metadata.end_time_millis);
+        Ok(SegmentDescriptor {
+            name: metadata.segment_name,
+            path: segment.path,
+            total_docs: metadata.total_docs,
+            time_column: metadata.time_column,
+            time_unit: metadata
+                .time_unit
+                .and_then(|unit| TimeUnit::from_string(&unit).ok()),
+            time_range_millis,
+            crc: metadata.crc.and_then(|crc| crc.parse().ok()),
+        })
+    }
+
     /// Map segment names from controller to local filesystem paths
+    ///
+    /// Every segment must be present locally.
     fn map_segments_to_paths(
         &self,
         table_name: &str,
         segment_names: &[String],
         table_type: &str,
-    ) -> Result<Vec<PathBuf>> {
-        let table_dir = self
-            .segment_dir
-            .join(format!("{}_{}", table_name, table_type));
+    ) -> Result<Vec<LocalSegment>> {
+        let table_name_with_type = format!("{}_{}", table_name, table_type);
+        let table_dir = self.segment_dir.join(&table_name_with_type);
 
         if !table_dir.exists() {
             return Err(Error::Internal(format!(
@@ -339,30 +1083,393 @@ impl ControllerMetadataProvider {
             )));
         }
 
-        let mut paths = Vec::new();
+        let mut segments = Vec::new();
         for segment_name in segment_names {
-            let segment_path = table_dir.join(segment_name).join("v3");
-            if segment_path.exists() {
-                paths.push(segment_path);
+            let segment_dir = table_dir.join(segment_name);
+            match segment_data_dir(&segment_dir) {
+                Some(path) => segments.push(LocalSegment {
+                    table_name_with_type: table_name_with_type.clone(),
+                    name: segment_name.clone(),
+                    path,
+                }),
+                None => {
+                    return Err(Error::Internal(format!(
+                        "Segment {} not found locally at {}",
+                        segment_name,
+                        segment_dir.join("v3").display()
+                    )))
+                }
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Local directories of the REALTIME segments among `segment_names`
+    ///
+    /// Scans `<segment_dir>/<table>_REALTIME` for directories named after
+    /// the segments (e.g. `events__0__12__20240101T0000Z`). Segments still
+    /// consuming, or served by another server, have no local copy and are
+    /// skipped.
+    fn realtime_segment_paths(
+        &self,
+        table_name: &str,
+        segment_names: &[String],
+    ) -> Result<Vec<LocalSegment>> {
+        let table_name_with_type = format!("{}_REALTIME", table_name);
+        let table_dir = self.segment_dir.join(&table_name_with_type);
+        if !table_dir.is_dir() {
+            tracing::debug!(table = table_name, "no local REALTIME segments");
+            return Ok(Vec::new());
+        }
+
+        let segment_names: HashSet<&str> = segment_names.iter().map(String::as_str).collect();
+        let entries = fs::read_dir(&table_dir)
+            .map_err(|e| Error::Internal(format!("Failed to read table directory: {}", e)))?;
+
+        let mut segments = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| Error::Internal(e.to_string()))?;
+            let Some(name) = entry
+                .file_name()
+                .to_str()
+                .filter(|name| segment_names.contains(name))
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            if let Some(path) = segment_data_dir(&entry.path()) {
+                segments.push(LocalSegment {
+                    table_name_with_type: table_name_with_type.clone(),
+                    name,
+                    path,
+                });
+            }
+        }
+
+        if segments.len() < segment_names.len() {
+            tracing::debug!(
+                table = table_name,
+                local = segments.len(),
+                listed = segment_names.len(),
+                "some REALTIME segments are not available locally"
+            );
+        }
+        Ok(segments)
+    }
+}
+
+/// Object store metadata provider (deep store mode)
+///
+/// Discovers tables in a bucket laid out like a Pinot data directory,
+/// `<table>_OFFLINE/<segment>/...` with or without a `v3/` level, under the
+/// path of the store URL. Segments are read from local copies: each one is
+/// downloaded into `<cache_dir>/<table>_<TYPE>/<version>/<segment>/v3` when
+/// its table is opened, and downloaded again into a new `<version>` once its
+/// `metadata.properties` changes in the store. Earlier copies are left in
+/// place for tables still reading them; clear the cache directory to reclaim
+/// their space.
+///
+/// Segments without a `metadata.properties` are still being uploaded and are
+/// skipped until it appears.
+///
+/// # Example
+/// ```ignore
+/// use datafusion_pinot::ObjectStoreMetadataProvider;
+/// use object_store::aws::AmazonS3Builder;
+///
+/// let store = AmazonS3Builder::from_env().with_bucket_name("pinot").build()?;
+/// let provider = ObjectStoreMetadataProvider::new("s3://pinot/deep-store", Arc::new(store))?
+///     .with_cache_dir("/var/cache/pinot");
+/// let tables = provider.list_tables().await?;
+/// ```
+#[cfg(feature = "object-store")]
+#[derive(Debug, Clone)]
+pub struct ObjectStoreMetadataProvider {
+    url: String,
+    store: Arc<dyn ObjectStore>,
+    /// Path of the table directories within the store
+    root: ObjectPath,
+    cache_dir: PathBuf,
+}
+
+#[cfg(feature = "object-store")]
+impl ObjectStoreMetadataProvider {
+    /// Create a provider over the tables under `url` in `store`
+    ///
+    /// Only the path of `url` is used, to locate the table directories (e.g.
+    /// `deep-store` for `s3://pinot/deep-store`); `store` must already point
+    /// at the bucket. Segments are cached under the system temp directory
+    /// unless [`with_cache_dir`](Self::with_cache_dir) is called.
+    ///
+    /// # Errors
+    /// Returns error if `url` has no scheme or an invalid path.
+    pub fn new(url: impl Into<String>, store: Arc<dyn ObjectStore>) -> Result<Self> {
+        let url = url.into();
+        let root = Self::url_path(&url)?;
+        let cache_dir = std::env::temp_dir()
+            .join("datafusion-pinot")
+            .join(Self::cache_name(&url));
+        Ok(Self {
+            url,
+            store,
+            root,
+            cache_dir,
+        })
+    }
+
+    /// Download segments into `cache_dir` instead of the temp directory
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    /// Get the store URL
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get the object store
+    pub fn store(&self) -> &Arc<dyn ObjectStore> {
+        &self.store
+    }
+
+    /// Get the local directory segments are downloaded into
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Path within the store of `url`, e.g. `deep-store` for
+    /// `s3://pinot/deep-store/`
+    fn url_path(url: &str) -> Result<ObjectPath> {
+        let (_, rest) = url
+            .split_once("://")
+            .ok_or_else(|| Error::Internal(format!("Object store URL has no scheme: {}", url)))?;
+        let path = rest.split_once('/').map_or("", |(_, path)| path);
+        ObjectPath::parse(path)
+            .map_err(|e| Error::Internal(format!("Invalid object store URL {}: {}", url, e)))
+    }
+
+    /// Directory name of `url` under the default cache directory
+    fn cache_name(url: &str) -> String {
+        url.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+
+    /// Names of the directories directly under `prefix`
+    async fn child_dirs(&self, prefix: &ObjectPath) -> Result<Vec<String>> {
+        let listing = self.store.list_with_delimiter(Some(prefix)).await?;
+        Ok(listing
+            .common_prefixes
+            .iter()
+            .filter_map(|dir| dir.filename().map(str::to_string))
+            .collect())
+    }
+
+    /// Directory name of `table_name` in the store, OFFLINE before REALTIME
+    async fn table_dir(&self, table_name: &str) -> Result<Option<String>> {
+        let dirs = self.child_dirs(&self.root).await?;
+        Ok(["OFFLINE", "REALTIME"]
+            .iter()
+            .map(|table_type| format!("{}_{}", table_name, table_type))
+            .find(|dir| dirs.contains(dir)))
+    }
+
+    /// Local `v3` directory of a segment, downloaded unless the cached copy is
+    /// current; `None` while the segment has no `metadata.properties`
+    ///
+    /// Each version of a segment, keyed by the ETag of its
+    /// `metadata.properties`, gets its own directory, so a changed segment
+    /// yields a new path and tables already reading the old copy keep reading
+    /// consistent files. A download goes to a staging directory that is only
+    /// renamed into place once every file is written.
+    async fn sync_segment(&self, table_dir: &str, segment_name: &str) -> Result<Option<PathBuf>> {
+        let prefix = self.root.child(table_dir).child(segment_name);
+        let objects: Vec<_> = self.store.list(Some(&prefix)).try_collect().await?;
+
+        // Each object with its path relative to the v3 directory
+        let mut files: Vec<(ObjectPath, Vec<String>)> = Vec::new();
+        let mut metadata_object = None;
+        for object in objects {
+            let Some(parts) = object.location.prefix_match(&prefix) else {
+                continue;
+            };
+            let mut parts: Vec<String> = parts.map(|part| part.as_ref().to_string()).collect();
+            if parts.len() > 1 && parts[0] == "v3" {
+                parts.remove(0);
+            }
+            if parts == ["metadata.properties"] {
+                metadata_object = Some(object);
             } else {
-                return Err(Error::Internal(format!(
-                    "Segment {} not found locally at {}",
-                    segment_name,
-                    segment_path.display()
-                )));
+                files.push((object.location, parts));
+            }
+        }
+        let Some(metadata_object) = metadata_object else {
+            tracing::debug!(segment = %prefix, "skipping incomplete segment");
+            return Ok(None);
+        };
+
+        let version_dir = self
+            .cache_dir
+            .join(table_dir)
+            .join(Self::segment_version(&metadata_object));
+        let segment_dir = version_dir.join(segment_name);
+        let v3_path = segment_dir.join("v3");
+        if blocking_io({
+            let segment_dir = segment_dir.clone();
+            move || Ok(segment_dir.exists())
+        })
+        .await
+        .map_err(|e| cache_error(segment_name, &segment_dir, e))?
+        {
+            return Ok(Some(v3_path));
+        }
+
+        // Unique per download, so concurrent syncs never share files
+        let staging_dir = version_dir.join(format!(
+            "{}.{}-{}.tmp",
+            segment_name,
+            std::process::id(),
+            STAGING_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let staging_v3 = staging_dir.join("v3");
+        files.push((metadata_object.location, vec!["metadata.properties".to_string()]));
+        for (location, parts) in files {
+            let bytes = self.store.get(&location).await?.bytes().await?;
+            let path = parts
+                .iter()
+                .fold(staging_v3.clone(), |path, part| path.join(part));
+            blocking_io(move || {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, &bytes)
+            })
+            .await
+            .map_err(|e| cache_error(segment_name, &staging_dir, e))?;
+        }
+
+        // Another sync may have finished the same version first; keep its copy
+        let renamed = blocking_io({
+            let (staging_dir, segment_dir) = (staging_dir.clone(), segment_dir.clone());
+            move || match fs::rename(&staging_dir, &segment_dir) {
+                Ok(()) => Ok(true),
+                Err(_) if segment_dir.exists() => {
+                    fs::remove_dir_all(&staging_dir)?;
+                    Ok(false)
+                }
+                Err(e) => Err(e),
+            }
+        })
+        .await
+        .map_err(|e| cache_error(segment_name, &segment_dir, e))?;
+        if renamed {
+            tracing::debug!(segment = %prefix, path = %v3_path.display(), "downloaded segment");
+        }
+        Ok(Some(v3_path))
+    }
+
+    /// Cache directory name for the version of a segment described by its
+    /// `metadata.properties` object: the object's ETag, or its modification
+    /// time and size for stores without ETags
+    fn segment_version(metadata: &ObjectMeta) -> String {
+        let version = match &metadata.e_tag {
+            Some(e_tag) => e_tag.clone(),
+            None => format!(
+                "{}-{}",
+                metadata.last_modified.timestamp_millis(),
+                metadata.size
+            ),
+        };
+        Self::cache_name(version.trim_matches('"'))
+    }
+}
+
+/// Distinguishes the staging directories of concurrent downloads
+#[cfg(feature = "object-store")]
+static STAGING_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Run blocking filesystem calls off the async runtime's worker threads
+#[cfg(feature = "object-store")]
+async fn blocking_io<T: Send + 'static>(
+    f: impl FnOnce() -> std::io::Result<T> + Send + 'static,
+) -> std::io::Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+#[cfg(feature = "object-store")]
+fn cache_error(segment_name: &str, path: &Path, e: std::io::Error) -> Error {
+    Error::Internal(format!(
+        "Failed to cache segment {} in {}: {}",
+        segment_name,
+        path.display(),
+        e
+    ))
+}
+
+#[cfg(feature = "object-store")]
+#[async_trait]
+impl MetadataProvider for ObjectStoreMetadataProvider {
+    async fn list_tables(&self) -> Result<Vec<String>> {
+        let mut table_names: Vec<String> = self
+            .child_dirs(&self.root)
+            .await?
+            .iter()
+            .filter_map(|name| {
+                name.strip_suffix("_OFFLINE")
+                    .or_else(|| name.strip_suffix("_REALTIME"))
+            })
+            .map(str::to_string)
+            .collect();
+
+        // Sort and drop tables seen under both suffixes
+        table_names.sort();
+        table_names.dedup();
+        Ok(table_names)
+    }
+
+    async fn table_exists(&self, name: &str) -> bool {
+        matches!(self.table_dir(name).await, Ok(Some(_)))
+    }
+
+    async fn get_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
+        let table_dir = self.table_dir(table_name).await?.ok_or_else(|| {
+            Error::Internal(format!("Table '{}' not found in {}", table_name, self.url))
+        })?;
+
+        let mut segment_paths = Vec::new();
+        for segment_name in self
+            .child_dirs(&self.root.child(table_dir.as_str()))
+            .await?
+        {
+            if is_temporary_dir_name(&segment_name) {
+                tracing::debug!(segment = %segment_name, "skipping temporary directory");
+                continue;
+            }
+            if let Some(v3_path) = self.sync_segment(&table_dir, &segment_name).await? {
+                segment_paths.push(v3_path);
             }
         }
 
-        if paths.is_empty() {
+        if segment_paths.is_empty() {
             return Err(Error::Internal(format!(
-                "No valid segment paths found for table '{}'",
-                table_name
+                "No valid segments found in {}/{}",
+                self.url.trim_end_matches('/'),
+                table_dir
             )));
         }
 
-        // Sort for consistent ordering
-        paths.sort();
-        Ok(paths)
+        // Sort by sequence number, as Pinot orders segments
+        sort_segment_paths(&mut segment_paths);
+        Ok(segment_paths)
     }
 }
 
@@ -370,60 +1477,447 @@ impl ControllerMetadataProvider {
 mod tests {
     use super::*;
 
+    /// Data directory laid out like the quickstart's, with a `baseballStats`
+    /// table of two segments and a `dimBaseballTeams` table
+    fn quickstart_dir() -> tempfile::TempDir {
+        use pinot_segment::testutil::{write_baseball_stats, SegmentBuilder};
+
+        let dir = tempfile::tempdir().unwrap();
+        let table_dir = dir.path().join("baseballStats_OFFLINE");
+        for segment in ["baseballStats_OFFLINE_0", "baseballStats_OFFLINE_1"] {
+            write_baseball_stats(&table_dir.join(segment).join("v3"), 100).unwrap();
+        }
+        SegmentBuilder::new("dimBaseballTeams", "dimBaseballTeams_OFFLINE_0")
+            .string_column("teamID", vec!["ATL", "BOS"])
+            .write(
+                &dir.path()
+                    .join("dimBaseballTeams_OFFLINE/dimBaseballTeams_OFFLINE_0/v3"),
+            )
+            .unwrap();
+        dir
+    }
+
     #[tokio::test]
     async fn test_filesystem_provider_list_tables() {
-        // This test requires a running Pinot instance with data
-        let data_dir = "/tmp/pinot/quickstart/PinotServerDataDir0";
-        if !Path::new(data_dir).exists() {
-            println!("Skipping test: data directory not found");
-            return;
+        let dir = quickstart_dir();
+        let provider = FileSystemMetadataProvider::new(dir.path());
+        let mut tables = provider.list_tables().await.unwrap();
+        tables.sort();
+
+        // Table names come without the _OFFLINE or _REALTIME suffix
+        assert_eq!(tables, vec!["baseballStats", "dimBaseballTeams"]);
+    }
+
+    #[tokio::test]
+    async fn test_filesystem_provider_table_exists() {
+        let dir = quickstart_dir();
+        let provider = FileSystemMetadataProvider::new(dir.path());
+
+        assert!(provider.table_exists("baseballStats").await);
+        assert!(!provider.table_exists("nonexistent_table_12345").await);
+    }
+
+    #[tokio::test]
+    async fn test_filesystem_provider_get_segment_paths() {
+        let dir = quickstart_dir();
+        let provider = FileSystemMetadataProvider::new(dir.path());
+        let paths = provider.get_segment_paths("baseballStats").await.unwrap();
+
+        let table_dir = dir.path().join("baseballStats_OFFLINE");
+        assert_eq!(
+            paths,
+            vec![
+                table_dir.join("baseballStats_OFFLINE_0").join("v3"),
+                table_dir.join("baseballStats_OFFLINE_1").join("v3"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filesystem_provider_skips_temporary_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let table_dir = dir.path().join("events_OFFLINE");
+        let segment = |name: &str, files: &[&str]| {
+            let v3_dir = table_dir.join(name).join("v3");
+            fs::create_dir_all(&v3_dir).unwrap();
+            for file in files {
+                fs::write(v3_dir.join(file), "").unwrap();
+            }
+        };
+        let complete = ["metadata.properties", "columns.psf"];
+        segment("events_0", &complete);
+        segment("tmp", &complete);
+        segment("tmp-6f1c2a9e-0b7d-4d0e-9a55-3f1e8c2b7a10", &complete);
+        segment("events_1.tmp", &complete);
+        segment(".events_2", &complete);
+        segment("events_3", &["columns.psf"]);
+        segment("events_4", &["metadata.properties"]);
+
+        let provider = FileSystemMetadataProvider::new(dir.path());
+        let paths = provider.get_segment_paths("events").await.unwrap();
+        assert_eq!(paths, vec![table_dir.join("events_0").join("v3")]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_filesystem_provider_follows_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        let volume = root.join("volume");
+        for segment in ["events_0", "events_1"] {
+            let v3_dir = volume.join(segment).join("v3");
+            fs::create_dir_all(&v3_dir).unwrap();
+            fs::write(v3_dir.join("metadata.properties"), "").unwrap();
+            fs::write(v3_dir.join("columns.psf"), "").unwrap();
+        }
+        let data_dir = root.join("data0");
+        let table_dir = data_dir.join("events_OFFLINE");
+        fs::create_dir_all(&table_dir).unwrap();
+        fs::rename(volume.join("events_0"), table_dir.join("events_0")).unwrap();
+        symlink(volume.join("events_1"), table_dir.join("events_1")).unwrap();
+        symlink(volume.join("missing"), table_dir.join("events_2")).unwrap();
+        symlink(table_dir.join("events_3"), table_dir.join("events_3")).unwrap();
+
+        let provider = FileSystemMetadataProvider::new(&data_dir);
+        let paths = provider.get_segment_paths("events").await.unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                table_dir.join("events_0").join("v3"),
+                volume.join("events_1").join("v3"),
+            ]
+        );
+
+        // A second data directory linking to the same table directory
+        let linked_dir = root.join("data1");
+        fs::create_dir_all(&linked_dir).unwrap();
+        symlink(&table_dir, linked_dir.join("events_OFFLINE")).unwrap();
+        let provider = FileSystemMetadataProvider::new_multi(vec![data_dir, linked_dir.clone()]);
+        assert_eq!(provider.get_segment_paths("events").await.unwrap(), paths);
+        let provider = FileSystemMetadataProvider::new(&linked_dir);
+        assert_eq!(provider.list_tables().await.unwrap(), vec!["events"]);
+    }
+
+    /// Provider counting calls, yielding before it answers so that
+    /// concurrent calls overlap
+    #[derive(Debug, Default)]
+    struct CountingProvider {
+        list_calls: std::sync::atomic::AtomicUsize,
+        segment_calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl CountingProvider {
+        fn counts(&self) -> (usize, usize) {
+            use std::sync::atomic::Ordering;
+            (
+                self.list_calls.load(Ordering::SeqCst),
+                self.segment_calls.load(Ordering::SeqCst),
+            )
         }
+    }
 
-        let provider = FileSystemMetadataProvider::new(data_dir);
-        let tables = provider.list_tables().await.unwrap();
+    #[async_trait]
+    impl MetadataProvider for CountingProvider {
+        async fn list_tables(&self) -> Result<Vec<String>> {
+            self.list_calls
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            Ok(vec!["events".to_string()])
+        }
 
-        assert!(!tables.is_empty(), "Should discover at least one table");
-        // Table names should not contain _OFFLINE or _REALTIME suffixes
-        for table in &tables {
-            assert!(!table.ends_with("_OFFLINE"));
-            assert!(!table.ends_with("_REALTIME"));
+        async fn get_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
+            self.segment_calls
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            match table_name {
+                "events" => Ok(vec![PathBuf::from("/data/events_0/v3")]),
+                _ => Err(Error::Internal(format!("Table '{}' not found", table_name))),
+            }
         }
     }
 
     #[tokio::test]
-    async fn test_filesystem_provider_table_exists() {
-        let data_dir = "/tmp/pinot/quickstart/PinotServerDataDir0";
-        if !Path::new(data_dir).exists() {
-            println!("Skipping test: data directory not found");
-            return;
+    async fn test_cached_provider() {
+        let inner = Arc::new(CountingProvider::default());
+        let provider = CachedMetadataProvider::new(inner.clone(), Duration::from_secs(60));
+
+        for _ in 0..3 {
+            assert_eq!(provider.list_tables().await.unwrap(), vec!["events"]);
+            assert!(provider.table_exists("events").await);
+            assert_eq!(provider.get_segment_paths("events").await.unwrap().len(), 1);
         }
+        assert_eq!(inner.counts(), (1, 1));
 
-        let provider = FileSystemMetadataProvider::new(data_dir);
+        // Errors are not cached
+        assert!(provider.get_segment_paths("clicks").await.is_err());
+        assert!(provider.get_segment_paths("clicks").await.is_err());
+        assert_eq!(inner.counts(), (1, 3));
 
-        // Test with a known table
-        assert!(provider.table_exists("baseballStats").await);
+        provider.invalidate_table("events");
+        provider.get_segment_paths("events").await.unwrap();
+        provider.list_tables().await.unwrap();
+        assert_eq!(inner.counts(), (1, 4));
 
-        // Test with non-existent table
-        assert!(!provider.table_exists("nonexistent_table_12345").await);
+        provider.invalidate();
+        provider.get_segment_paths("events").await.unwrap();
+        provider.list_tables().await.unwrap();
+        assert_eq!(inner.counts(), (2, 5));
     }
 
     #[tokio::test]
-    async fn test_filesystem_provider_get_segment_paths() {
-        let data_dir = "/tmp/pinot/quickstart/PinotServerDataDir0";
-        if !Path::new(data_dir).exists() {
-            println!("Skipping test: data directory not found");
-            return;
+    async fn test_cached_provider_ttl() {
+        let inner = Arc::new(CountingProvider::default());
+        let provider = CachedMetadataProvider::new(inner.clone(), Duration::from_secs(60))
+            .with_segments_ttl(Duration::ZERO);
+
+        for _ in 0..3 {
+            provider.list_tables().await.unwrap();
+            provider.get_segment_paths("events").await.unwrap();
         }
+        assert_eq!(inner.counts(), (1, 3));
 
-        let provider = FileSystemMetadataProvider::new(data_dir);
-        let paths = provider.get_segment_paths("baseballStats").await.unwrap();
+        // Without the cache every call reaches the provider
+        for _ in 0..3 {
+            inner.list_tables().await.unwrap();
+        }
+        assert_eq!(inner.counts(), (4, 3));
+    }
 
-        assert!(!paths.is_empty(), "Should find at least one segment");
+    #[tokio::test]
+    async fn test_cached_provider_coalesces_refreshes() {
+        let inner = Arc::new(CountingProvider::default());
+        let provider = CachedMetadataProvider::new(inner.clone(), Duration::from_secs(60));
+
+        let lists = futures::future::join_all((0..5).map(|_| provider.list_tables())).await;
+        let paths =
+            futures::future::join_all((0..5).map(|_| provider.get_segment_paths("events"))).await;
+        assert!(lists.iter().all(|r| r.is_ok()));
+        assert!(paths.iter().all(|r| r.is_ok()));
+        assert_eq!(inner.counts(), (1, 1));
+
+        // Uncached, the same calls overlap
+        let inner = Arc::new(CountingProvider::default());
+        futures::future::join_all((0..5).map(|_| inner.list_tables())).await;
+        assert_eq!(inner.counts(), (5, 0));
+    }
 
-        // All paths should end with v3
-        for path in &paths {
-            assert!(path.ends_with("v3"), "Path should end with v3: {:?}", path);
-            assert!(path.exists(), "Segment path should exist: {:?}", path);
+    /// Provider whose `slow` table answers only once `release` is notified
+    #[derive(Debug, Default)]
+    struct GatedProvider {
+        release: tokio::sync::Notify,
+    }
+
+    #[async_trait]
+    impl MetadataProvider for GatedProvider {
+        async fn list_tables(&self) -> Result<Vec<String>> {
+            Ok(vec!["slow".to_string(), "fast".to_string()])
         }
+
+        async fn get_segment_paths(&self, table_name: &str) -> Result<Vec<PathBuf>> {
+            if table_name == "slow" {
+                self.release.notified().await;
+            }
+            Ok(vec![PathBuf::from(format!("/data/{}_0/v3", table_name))])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_provider_refreshes_tables_independently() {
+        let inner = Arc::new(GatedProvider::default());
+        let provider = CachedMetadataProvider::new(inner.clone(), Duration::from_secs(60));
+
+        // The slow table's refresh must not hold up the fast table's
+        let slow = provider.get_segment_paths("slow");
+        let fast = async {
+            let paths = provider.get_segment_paths("fast").await;
+            inner.release.notify_one();
+            paths
+        };
+        let (slow, fast) = tokio::time::timeout(Duration::from_secs(10), async {
+            futures::join!(slow, fast)
+        })
+        .await
+        .expect("refresh of one table blocked another");
+        assert_eq!(slow.unwrap(), vec![PathBuf::from("/data/slow_0/v3")]);
+        assert_eq!(fast.unwrap(), vec![PathBuf::from("/data/fast_0/v3")]);
+    }
+
+    /// Provider whose every call fails, like an unreachable controller
+    #[derive(Debug)]
+    struct FailingProvider;
+
+    #[async_trait]
+    impl MetadataProvider for FailingProvider {
+        async fn list_tables(&self) -> Result<Vec<String>> {
+            Err(Error::Internal("controller down".to_string()))
+        }
+
+        async fn get_segment_paths(&self, _table_name: &str) -> Result<Vec<PathBuf>> {
+            Err(Error::Internal("controller down".to_string()))
+        }
+    }
+
+    fn static_provider(tables: &[(&str, &str)]) -> Arc<dyn MetadataProvider> {
+        Arc::new(StaticMetadataProvider::new(
+            tables
+                .iter()
+                .map(|(name, path)| (name.to_string(), vec![PathBuf::from(path)]))
+                .collect(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_chained_provider_precedence() {
+        let provider = ChainedMetadataProvider::new(vec![
+            static_provider(&[("events", "/a/events/v3"), ("clicks", "/a/clicks/v3")]),
+            static_provider(&[("events", "/b/events/v3"), ("orders", "/b/orders/v3")]),
+        ]);
+
+        assert_eq!(
+            provider.list_tables().await.unwrap(),
+            vec!["clicks", "events", "orders"]
+        );
+        assert!(provider.table_exists("orders").await);
+        assert!(!provider.table_exists("missing").await);
+        assert_eq!(
+            provider.get_segment_paths("events").await.unwrap(),
+            vec![PathBuf::from("/a/events/v3")]
+        );
+        assert_eq!(
+            provider.get_segment_paths("orders").await.unwrap(),
+            vec![PathBuf::from("/b/orders/v3")]
+        );
+        assert!(provider.get_segment_paths("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_chained_provider_errors_do_not_mask_tables() {
+        let provider = ChainedMetadataProvider::new(vec![
+            Arc::new(FailingProvider),
+            static_provider(&[("adhoc", "/adhoc/v3")]),
+        ]);
+        assert_eq!(provider.list_tables().await.unwrap(), vec!["adhoc"]);
+        assert!(provider.table_exists("adhoc").await);
+        assert_eq!(
+            provider.get_segment_paths("adhoc").await.unwrap(),
+            vec![PathBuf::from("/adhoc/v3")]
+        );
+
+        // With no provider able to list, the error surfaces
+        let provider = ChainedMetadataProvider::new(vec![
+            Arc::new(FailingProvider),
+            Arc::new(FailingProvider),
+        ]);
+        let err = provider.list_tables().await.unwrap_err();
+        assert!(err.to_string().contains("controller down"), "{}", err);
+    }
+
+    #[cfg(feature = "object-store")]
+    async fn put(store: &object_store::memory::InMemory, path: &str, data: &str) {
+        store
+            .put(&ObjectPath::from(path), data.as_bytes().to_vec().into())
+            .await
+            .unwrap();
+    }
+
+    #[cfg(feature = "object-store")]
+    #[tokio::test]
+    async fn test_object_store_provider() {
+        let store = Arc::new(object_store::memory::InMemory::new());
+        put(
+            &store,
+            "deep/events_OFFLINE/seg_0/v3/metadata.properties",
+            "a",
+        )
+        .await;
+        put(&store, "deep/events_OFFLINE/seg_0/v3/columns.psf", "data").await;
+        // Segment files directly under the segment directory
+        put(&store, "deep/events_OFFLINE/seg_1/metadata.properties", "b").await;
+        put(&store, "deep/events_OFFLINE/seg_1/star_tree/index", "tree").await;
+        // Still being uploaded
+        put(&store, "deep/events_OFFLINE/seg_2/v3/columns.psf", "data").await;
+        put(
+            &store,
+            "deep/clicks_REALTIME/seg_0/v3/metadata.properties",
+            "c",
+        )
+        .await;
+        put(
+            &store,
+            "other/ignored_OFFLINE/seg_0/v3/metadata.properties",
+            "d",
+        )
+        .await;
+
+        let cache = tempfile::tempdir().unwrap();
+        let provider = ObjectStoreMetadataProvider::new("memory:///deep/", store.clone())
+            .unwrap()
+            .with_cache_dir(cache.path());
+
+        assert_eq!(
+            provider.list_tables().await.unwrap(),
+            vec!["clicks", "events"]
+        );
+        assert!(provider.table_exists("clicks").await);
+        assert!(!provider.table_exists("ignored").await);
+
+        let paths = provider.get_segment_paths("events").await.unwrap();
+        let events_dir = cache.path().join("events_OFFLINE");
+        assert_eq!(paths.len(), 2);
+        for (path, segment) in paths.iter().zip(["seg_0", "seg_1"]) {
+            assert!(path.starts_with(&events_dir), "{}", path.display());
+            assert!(path.ends_with(format!("{}/v3", segment)), "{}", path.display());
+        }
+        assert_eq!(
+            fs::read_to_string(paths[0].join("columns.psf")).unwrap(),
+            "data"
+        );
+        assert_eq!(
+            fs::read_to_string(paths[1].join("star_tree/index")).unwrap(),
+            "tree"
+        );
+        // An unchanged segment is not downloaded again
+        assert_eq!(provider.get_segment_paths("events").await.unwrap(), paths);
+
+        // A changed metadata.properties downloads a new copy next to the old
+        put(
+            &store,
+            "deep/events_OFFLINE/seg_0/v3/metadata.properties",
+            "a2",
+        )
+        .await;
+        put(&store, "deep/events_OFFLINE/seg_0/v3/columns.psf", "data2").await;
+        let new_paths = provider.get_segment_paths("events").await.unwrap();
+        assert_ne!(new_paths[0], paths[0]);
+        assert_eq!(new_paths[1], paths[1]);
+        assert_eq!(
+            fs::read_to_string(new_paths[0].join("columns.psf")).unwrap(),
+            "data2"
+        );
+        assert_eq!(
+            fs::read_to_string(paths[0].join("columns.psf")).unwrap(),
+            "data"
+        );
+        // No staging directories are left behind
+        for entry in fs::read_dir(new_paths[0].parent().unwrap().parent().unwrap()).unwrap() {
+            let name = entry.unwrap().file_name();
+            assert!(!name.to_string_lossy().ends_with(".tmp"), "{:?}", name);
+        }
+
+        let err = provider.get_segment_paths("missing").await.unwrap_err();
+        assert!(err.to_string().contains("not found"), "{}", err);
+    }
+
+    #[cfg(feature = "object-store")]
+    #[test]
+    fn test_object_store_provider_url() {
+        let store = Arc::new(object_store::memory::InMemory::new());
+        let provider = ObjectStoreMetadataProvider::new("s3://bucket", store.clone()).unwrap();
+        assert_eq!(provider.root, ObjectPath::default());
+        let provider = ObjectStoreMetadataProvider::new("s3://bucket/a/b/", store.clone()).unwrap();
+        assert_eq!(provider.root, ObjectPath::from("a/b"));
+        assert!(ObjectStoreMetadataProvider::new("bucket/a", store).is_err());
     }
 }
@@ -0,0 +1,176 @@
+//! Per-table read options
+
+use pinot_segment::Utf8Policy;
+
+/// Default number of rows per emitted RecordBatch
+pub const DEFAULT_BATCH_SIZE: usize = 8192;
+
+/// Options controlling how a [`PinotTable`](crate::PinotTable) reads its segments
+///
+/// The defaults reproduce the plain behavior: batches of 8192 rows, STRING
+/// columns as `Utf8`, lossy UTF-8 decoding, every document
+/// returned, null rows holding the column's default null value, every
+/// segment of a table required to share one schema and to open cleanly, and
+/// columns that cannot be decoded left out of the schema.
+///
+/// # Example
+/// ```ignore
+/// let options = PinotReadOptions::default()
+///     .with_batch_size(1024)
+///     .with_dictionary_arrays(true);
+/// let table = PinotTable::open_table(table_dir)?.with_options(options);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinotReadOptions {
+    /// Maximum number of rows per RecordBatch
+    pub batch_size: usize,
+    /// Emit dictionary-encoded STRING columns as `Dictionary(Int32, Utf8)`
+    pub dictionary_arrays: bool,
+    /// Emit STRING columns as `Utf8View`; RAW columns then point into their
+    /// decompressed chunks instead of copying each value. Columns emitted as
+    /// dictionary arrays keep their dictionary type
+    pub string_view_arrays: bool,
+    /// UTF-8 decoding of STRING values, dictionary-encoded or RAW
+    pub utf8_policy: Utf8Policy,
+    /// Skip documents missing from an upsert segment's validDocIds snapshot
+    pub honor_valid_doc_ids: bool,
+    /// Return Arrow nulls for documents flagged in a column's null value
+    /// vector instead of the stored default null value
+    pub substitute_nulls: bool,
+    /// Declare every field nullable, for consumers that need nullable
+    /// columns (e.g. to join with nullable tables). Only changes the schema:
+    /// values still come back null only where `substitute_nulls` or
+    /// `union_schemas` make them so
+    pub nullable_schema: bool,
+    /// Accept segments whose columns differ by taking the union of their
+    /// schemas; columns a segment lacks are read as nulls. Only consulted
+    /// when the table is opened
+    pub union_schemas: bool,
+    /// Leave out segments that fail to open (logging a warning) instead of
+    /// failing the table; opening still fails when no segment opens. Only
+    /// consulted when the table is opened
+    pub skip_unreadable_segments: bool,
+    /// Append `__segment_name__` and `__segment_index__` columns telling
+    /// which segment each row came from; see
+    /// [`SegmentInfoColumnsRule`](crate::SegmentInfoColumnsRule) for keeping
+    /// them out of `SELECT *`
+    pub segment_info_columns: bool,
+    /// Keep columns the readers cannot decode (BOOLEAN, multi-value, RAW
+    /// numeric, unsupported chunk compression) in the schema, failing
+    /// the scans that read them, instead of leaving them out with a warning
+    pub strict_column_types: bool,
+    /// Open only the first segment when the table is opened and the others
+    /// the first time they are needed (a scan, statistics or a per-segment
+    /// accessor), so resolving the schema of a many-segment table stays
+    /// cheap. Schema mismatches and unreadable segments past the first then
+    /// surface at scan time. Ignored with `union_schemas`, which needs every
+    /// segment's schema. Only consulted when the table is opened
+    pub lazy_segments: bool,
+    /// Report column names lowercased, so unquoted SQL identifiers (which
+    /// DataFusion lowercases) find camelCase Pinot columns. Segments are
+    /// still read by their stored names; columns whose lowercased names
+    /// would collide keep their stored names
+    pub lowercase_column_names: bool,
+}
+
+impl Default for PinotReadOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_BATCH_SIZE,
+            dictionary_arrays: false,
+            string_view_arrays: false,
+            utf8_policy: Utf8Policy::default(),
+            honor_valid_doc_ids: false,
+            substitute_nulls: false,
+            nullable_schema: false,
+            union_schemas: false,
+            skip_unreadable_segments: false,
+            segment_info_columns: false,
+            strict_column_types: false,
+            lazy_segments: false,
+            lowercase_column_names: false,
+        }
+    }
+}
+
+impl PinotReadOptions {
+    /// Set the maximum number of rows per RecordBatch (at least 1)
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Emit dictionary-encoded STRING columns as Arrow dictionary arrays
+    pub fn with_dictionary_arrays(mut self, enabled: bool) -> Self {
+        self.dictionary_arrays = enabled;
+        self
+    }
+
+    /// Emit STRING columns as Arrow string view arrays
+    pub fn with_string_view_arrays(mut self, enabled: bool) -> Self {
+        self.string_view_arrays = enabled;
+        self
+    }
+
+    /// Decode every STRING value with the given UTF-8 policy
+    pub fn with_utf8_policy(mut self, policy: Utf8Policy) -> Self {
+        self.utf8_policy = policy;
+        self
+    }
+
+    /// Skip documents superseded by newer upserts
+    pub fn with_honor_valid_doc_ids(mut self, enabled: bool) -> Self {
+        self.honor_valid_doc_ids = enabled;
+        self
+    }
+
+    /// Return Arrow nulls for documents flagged in null value vectors
+    pub fn with_substitute_nulls(mut self, enabled: bool) -> Self {
+        self.substitute_nulls = enabled;
+        self
+    }
+
+    /// Declare every field nullable without changing the values returned
+    pub fn with_nullable_schema(mut self, enabled: bool) -> Self {
+        self.nullable_schema = enabled;
+        self
+    }
+
+    /// Union the schemas of segments that add or drop columns instead of
+    /// rejecting them
+    pub fn with_union_schemas(mut self, enabled: bool) -> Self {
+        self.union_schemas = enabled;
+        self
+    }
+
+    /// Open tables without the segments that cannot be read, recording them
+    /// in [`PinotTable::skipped_segments`](crate::PinotTable::skipped_segments)
+    pub fn with_skip_unreadable_segments(mut self, enabled: bool) -> Self {
+        self.skip_unreadable_segments = enabled;
+        self
+    }
+
+    /// Add the `__segment_name__` and `__segment_index__` columns
+    pub fn with_segment_info_columns(mut self, enabled: bool) -> Self {
+        self.segment_info_columns = enabled;
+        self
+    }
+
+    /// Keep unreadable columns in the schema and fail when they are scanned
+    pub fn with_strict_column_types(mut self, enabled: bool) -> Self {
+        self.strict_column_types = enabled;
+        self
+    }
+
+    /// Defer opening all but the first segment until a scan needs them
+    pub fn with_lazy_segments(mut self, enabled: bool) -> Self {
+        self.lazy_segments = enabled;
+        self
+    }
+
+    /// Report column names lowercased, reading the stored columns
+    pub fn with_lowercase_column_names(mut self, enabled: bool) -> Self {
+        self.lowercase_column_names = enabled;
+        self
+    }
+}
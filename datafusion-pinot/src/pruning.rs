@@ -0,0 +1,403 @@
+//! Segment pruning based on pushed-down filters
+//!
+//! Pinot records the min/max value of a table's time column in every
+//! segment's metadata (`segment.start.time` / `segment.end.time`). When a
+//! query constrains that column, segments whose range cannot overlap the
+//! predicate are excluded from the plan before any column data is read.
+//!
+//! Pruning is conservative: anything we cannot reason about (OR, NOT, casts,
+//! non-literal comparisons, segments without time metadata) keeps the segment.
+//!
+//! The same comparisons on other numeric columns are turned into
+//! [`ValueRange`]s, which `PinotExec` resolves through range indexes.
+//! `IS NULL` / `IS NOT NULL` filters are resolved through null value vectors.
+
+use crate::metadata_provider::SegmentDescriptor;
+use datafusion::logical_expr::{Between, BinaryExpr, Expr, Operator};
+use datafusion::scalar::ScalarValue;
+use pinot_segment::{RangeValue, SegmentMetadata, TimeUnit, ValueRange};
+use std::ops::Bound;
+
+/// Inclusive time range in epoch milliseconds; `None` bounds are open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TimeRange {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+}
+
+impl TimeRange {
+    fn with_lower(mut self, value: i64) -> Self {
+        self.start = Some(self.start.map_or(value, |s| s.max(value)));
+        self
+    }
+
+    fn with_upper(mut self, value: i64) -> Self {
+        self.end = Some(self.end.map_or(value, |e| e.min(value)));
+        self
+    }
+
+    /// Whether this range intersects `[start, end]`
+    pub fn overlaps(&self, start: i64, end: i64) -> bool {
+        self.start.is_none_or(|s| s <= end) && self.end.is_none_or(|e| e >= start)
+    }
+}
+
+/// Returns `false` only when the filters provably exclude every row of the segment
+pub fn segment_may_match(metadata: &SegmentMetadata, filters: &[Expr]) -> bool {
+    time_range_may_match(
+        metadata.time_column.as_deref(),
+        metadata.time_unit,
+        metadata.time_range_millis(),
+        filters,
+    )
+}
+
+/// Like [`segment_may_match`], for a segment known only by its descriptor
+pub fn descriptor_may_match(descriptor: &SegmentDescriptor, filters: &[Expr]) -> bool {
+    time_range_may_match(
+        descriptor.time_column.as_deref(),
+        descriptor.time_unit,
+        descriptor.time_range_millis,
+        filters,
+    )
+}
+
+/// Whether filters on `time_column` may match a segment whose time column
+/// spans `time_range_millis`; `true` when any of them is unknown
+fn time_range_may_match(
+    time_column: Option<&str>,
+    unit: Option<TimeUnit>,
+    time_range_millis: Option<(i64, i64)>,
+    filters: &[Expr],
+) -> bool {
+    let (Some(time_column), Some(unit), Some((start, end))) = (time_column, unit, time_range_millis)
+    else {
+        return true;
+    };
+
+    match time_range_from_filters(filters, time_column, unit) {
+        Some(range) => range.overlaps(start, end),
+        None => true,
+    }
+}
+
+/// Derive the time range implied by a conjunction of filters
+///
+/// Integer literals are interpreted in the time column's `unit`; timestamp
+/// literals carry their own unit. Returns `None` when no filter constrains
+/// `time_column`.
+pub fn time_range_from_filters(
+    filters: &[Expr],
+    time_column: &str,
+    unit: TimeUnit,
+) -> Option<TimeRange> {
+    let mut range = TimeRange::default();
+    let mut constrained = false;
+    for filter in filters {
+        constrained |= collect_bounds(filter, time_column, unit, &mut range);
+    }
+    constrained.then_some(range)
+}
+
+fn collect_bounds(expr: &Expr, column: &str, unit: TimeUnit, range: &mut TimeRange) -> bool {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+            if *op == Operator::And {
+                let l = collect_bounds(left, column, unit, range);
+                let r = collect_bounds(right, column, unit, range);
+                return l || r;
+            }
+
+            // Normalize to `column <op> literal`
+            let (op, literal) = if is_column(left, column) {
+                (*op, right)
+            } else if is_column(right, column) {
+                match op.swap() {
+                    Some(op) => (op, left),
+                    None => return false,
+                }
+            } else {
+                return false;
+            };
+
+            // Integer literals share the column's unit, so strict bounds can
+            // be tightened by one; timestamp literals are widened to inclusive
+            let bound = match op {
+                Operator::Eq | Operator::GtEq | Operator::LtEq => literal_millis(literal, unit, 0),
+                Operator::Gt => literal_millis(literal, unit, 1),
+                Operator::Lt => literal_millis(literal, unit, -1),
+                _ => return false,
+            };
+            let Some((floor, ceil)) = bound else {
+                return false;
+            };
+            match op {
+                Operator::Eq => *range = range.with_lower(floor).with_upper(ceil),
+                Operator::Gt | Operator::GtEq => *range = range.with_lower(floor),
+                _ => *range = range.with_upper(ceil),
+            }
+            true
+        }
+        Expr::Between(Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        }) if is_column(expr, column) => {
+            match (literal_millis(low, unit, 0), literal_millis(high, unit, 0)) {
+                (Some(low), Some(high)) => {
+                    *range = range.with_lower(low.0).with_upper(high.1);
+                    true
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Derive the value range a conjunction of filters implies for `column`
+///
+/// Returns `None` when no filter constrains `column` with a numeric literal.
+pub fn value_range_from_filters(filters: &[Expr], column: &str) -> Option<ValueRange> {
+    let mut range = ValueRange::default();
+    let mut constrained = false;
+    for filter in filters {
+        constrained |= collect_value_bounds(filter, column, &mut range);
+    }
+    constrained.then_some(range)
+}
+
+fn collect_value_bounds(expr: &Expr, column: &str, range: &mut ValueRange) -> bool {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+            if *op == Operator::And {
+                let l = collect_value_bounds(left, column, range);
+                let r = collect_value_bounds(right, column, range);
+                return l || r;
+            }
+
+            let (op, literal) = if is_column(left, column) {
+                (*op, right)
+            } else if is_column(right, column) {
+                match op.swap() {
+                    Some(op) => (op, left),
+                    None => return false,
+                }
+            } else {
+                return false;
+            };
+            let Some(value) = literal_value(literal) else {
+                return false;
+            };
+            *range = match op {
+                Operator::Eq => range
+                    .with_lower(Bound::Included(value))
+                    .with_upper(Bound::Included(value)),
+                Operator::Gt => range.with_lower(Bound::Excluded(value)),
+                Operator::GtEq => range.with_lower(Bound::Included(value)),
+                Operator::Lt => range.with_upper(Bound::Excluded(value)),
+                Operator::LtEq => range.with_upper(Bound::Included(value)),
+                _ => return false,
+            };
+            true
+        }
+        Expr::Between(Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        }) if is_column(expr, column) => match (literal_value(low), literal_value(high)) {
+            (Some(low), Some(high)) => {
+                *range = range
+                    .with_lower(Bound::Included(low))
+                    .with_upper(Bound::Included(high));
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Column and whether it must be null, for an `IS NULL` / `IS NOT NULL` filter
+pub fn null_filter(expr: &Expr) -> Option<(&str, bool)> {
+    let (operand, is_null) = match expr {
+        Expr::IsNull(operand) => (operand, true),
+        Expr::IsNotNull(operand) => (operand, false),
+        _ => return None,
+    };
+    match operand.as_ref() {
+        Expr::Column(c) => Some((c.name.as_str(), is_null)),
+        _ => None,
+    }
+}
+
+/// Numeric literal as a [`RangeValue`]
+fn literal_value(expr: &Expr) -> Option<RangeValue> {
+    let Expr::Literal(scalar) = expr else {
+        return None;
+    };
+    let value = match scalar {
+        ScalarValue::Int8(Some(v)) => RangeValue::Long(*v as i64),
+        ScalarValue::Int16(Some(v)) => RangeValue::Long(*v as i64),
+        ScalarValue::Int32(Some(v)) => RangeValue::Long(*v as i64),
+        ScalarValue::Int64(Some(v)) => RangeValue::Long(*v),
+        ScalarValue::UInt8(Some(v)) => RangeValue::Long(*v as i64),
+        ScalarValue::UInt16(Some(v)) => RangeValue::Long(*v as i64),
+        ScalarValue::UInt32(Some(v)) => RangeValue::Long(*v as i64),
+        ScalarValue::UInt64(Some(v)) => RangeValue::Long(i64::try_from(*v).ok()?),
+        ScalarValue::Float32(Some(v)) => RangeValue::Double(*v as f64),
+        ScalarValue::Float64(Some(v)) => RangeValue::Double(*v),
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn is_column(expr: &Expr, column: &str) -> bool {
+    match expr {
+        Expr::Column(c) => c.name == column,
+        _ => false,
+    }
+}
+
+/// Literal value as (floor, ceil) epoch milliseconds
+///
+/// `integer_offset` is added to integer literals (in the column's unit) before
+/// conversion and ignored for timestamp literals.
+fn literal_millis(expr: &Expr, unit: TimeUnit, integer_offset: i64) -> Option<(i64, i64)> {
+    let integer = |v: i64| (v.saturating_add(integer_offset), unit);
+    let (value, unit) = match expr {
+        Expr::Literal(scalar) => match scalar {
+            ScalarValue::Int8(Some(v)) => integer(*v as i64),
+            ScalarValue::Int16(Some(v)) => integer(*v as i64),
+            ScalarValue::Int32(Some(v)) => integer(*v as i64),
+            ScalarValue::Int64(Some(v)) => integer(*v),
+            ScalarValue::UInt8(Some(v)) => integer(*v as i64),
+            ScalarValue::UInt16(Some(v)) => integer(*v as i64),
+            ScalarValue::UInt32(Some(v)) => integer(*v as i64),
+            ScalarValue::UInt64(Some(v)) => integer(i64::try_from(*v).ok()?),
+            ScalarValue::TimestampSecond(Some(v), _) => (*v, TimeUnit::Seconds),
+            ScalarValue::TimestampMillisecond(Some(v), _) => (*v, TimeUnit::Milliseconds),
+            ScalarValue::TimestampMicrosecond(Some(v), _) => (*v, TimeUnit::Microseconds),
+            ScalarValue::TimestampNanosecond(Some(v), _) => (*v, TimeUnit::Nanoseconds),
+            ScalarValue::Date32(Some(v)) => (*v as i64, TimeUnit::Days),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some((unit.to_millis_floor(value), unit.to_millis_ceil(value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::prelude::{col, lit};
+
+    #[test]
+    fn test_range_from_comparisons() {
+        let filters = vec![col("ts").gt_eq(lit(100i64)), col("ts").lt(lit(200i64))];
+        let range = time_range_from_filters(&filters, "ts", TimeUnit::Milliseconds).unwrap();
+        assert_eq!(
+            range,
+            TimeRange {
+                start: Some(100),
+                end: Some(199)
+            }
+        );
+
+        // Literal on the left-hand side
+        let filters = vec![lit(5i32).lt(col("ts"))];
+        let range = time_range_from_filters(&filters, "ts", TimeUnit::Seconds).unwrap();
+        assert_eq!(
+            range,
+            TimeRange {
+                start: Some(6_000),
+                end: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_range_from_between_and_conjunction() {
+        let filters = vec![col("ts")
+            .between(lit(10i64), lit(20i64))
+            .and(col("other").eq(lit(1i32)))];
+        let range = time_range_from_filters(&filters, "ts", TimeUnit::Days).unwrap();
+        assert_eq!(
+            range,
+            TimeRange {
+                start: Some(10 * 86_400_000),
+                end: Some(20 * 86_400_000)
+            }
+        );
+    }
+
+    #[test]
+    fn test_unconstrained_filters() {
+        let filters = vec![
+            col("other").gt(lit(1i64)),
+            col("ts").gt(lit(1i64)).or(col("ts").lt(lit(0i64))),
+            col("ts").not_between(lit(1i64), lit(2i64)),
+        ];
+        assert!(time_range_from_filters(&filters, "ts", TimeUnit::Milliseconds).is_none());
+        assert!(time_range_from_filters(&[], "ts", TimeUnit::Milliseconds).is_none());
+    }
+
+    #[test]
+    fn test_timestamp_literal_normalized() {
+        let filters = vec![col("ts").gt(lit(ScalarValue::TimestampSecond(Some(3), None)))];
+        let range = time_range_from_filters(&filters, "ts", TimeUnit::Days).unwrap();
+        assert_eq!(range.start, Some(3_000));
+    }
+
+    #[test]
+    fn test_value_range_from_filters() {
+        let filters = vec![col("hits")
+            .between(lit(10i32), lit(20i32))
+            .and(col("hits").gt(lit(12i64)))];
+        let range = value_range_from_filters(&filters, "hits").unwrap();
+        assert_eq!(
+            range,
+            ValueRange::new(
+                Bound::Excluded(RangeValue::Long(12)),
+                Bound::Included(RangeValue::Long(20))
+            )
+        );
+
+        let filters = vec![lit(2.5f64).gt_eq(col("avg"))];
+        let range = value_range_from_filters(&filters, "avg").unwrap();
+        assert_eq!(
+            range,
+            ValueRange::new(Bound::Unbounded, Bound::Included(RangeValue::Double(2.5)))
+        );
+
+        let filters = vec![col("hits").gt(lit(1i32)).or(col("hits").lt(lit(0i32)))];
+        assert_eq!(value_range_from_filters(&filters, "hits"), None);
+        assert_eq!(value_range_from_filters(&filters, "avg"), None);
+    }
+
+    #[test]
+    fn test_null_filter() {
+        assert_eq!(null_filter(&col("team").is_null()), Some(("team", true)));
+        assert_eq!(null_filter(&col("team").is_not_null()), Some(("team", false)));
+        assert_eq!(null_filter(&(col("hits") + lit(1i32)).is_null()), None);
+        assert_eq!(null_filter(&col("hits").gt(lit(1i32))), None);
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let range = TimeRange {
+            start: Some(10),
+            end: None,
+        };
+        assert!(range.overlaps(0, 10));
+        assert!(!range.overlaps(0, 9));
+        let range = TimeRange {
+            start: None,
+            end: Some(5),
+        };
+        assert!(range.overlaps(5, 100));
+        assert!(!range.overlaps(6, 100));
+    }
+}
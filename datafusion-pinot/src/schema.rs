@@ -4,6 +4,26 @@ use std::sync::Arc;
 
 use crate::error::Result;
 
+/// Column holding the name of the segment a row was read from
+pub const SEGMENT_NAME_COLUMN: &str = "__segment_name__";
+
+/// Column holding the scan partition (segment position) a row was read from
+pub const SEGMENT_INDEX_COLUMN: &str = "__segment_index__";
+
+/// Whether `name` is one of the columns added by [`with_segment_info_columns`]
+pub fn is_segment_info_column(name: &str) -> bool {
+    name == SEGMENT_NAME_COLUMN || name == SEGMENT_INDEX_COLUMN
+}
+
+/// Append the `__segment_name__` (Utf8) and `__segment_index__` (Int32) columns
+pub fn with_segment_info_columns(schema: &Schema) -> SchemaRef {
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields.push(Field::new(SEGMENT_NAME_COLUMN, ArrowDataType::Utf8, false));
+    fields.push(Field::new(SEGMENT_INDEX_COLUMN, ArrowDataType::Int32, false));
+
+    Arc::new(Schema::new(fields))
+}
+
 /// Convert Pinot data type to Arrow data type
 pub fn pinot_to_arrow_type(pinot_type: &PinotDataType) -> ArrowDataType {
     match pinot_type {
@@ -17,7 +37,31 @@ pub fn pinot_to_arrow_type(pinot_type: &PinotDataType) -> ArrowDataType {
     }
 }
 
+/// Convert Arrow data type to the Pinot data type it is read back as
+///
+/// Dictionary types map through their value type. Returns `None` for types
+/// Pinot has no equivalent for.
+pub fn arrow_to_pinot_type(arrow_type: &ArrowDataType) -> Option<PinotDataType> {
+    match arrow_type {
+        ArrowDataType::Int32 => Some(PinotDataType::Int),
+        ArrowDataType::Int64 => Some(PinotDataType::Long),
+        ArrowDataType::Float32 => Some(PinotDataType::Float),
+        ArrowDataType::Float64 => Some(PinotDataType::Double),
+        ArrowDataType::Utf8 | ArrowDataType::Utf8View => Some(PinotDataType::String),
+        ArrowDataType::Binary => Some(PinotDataType::Bytes),
+        ArrowDataType::Boolean => Some(PinotDataType::Boolean),
+        ArrowDataType::Dictionary(_, value_type) => arrow_to_pinot_type(value_type),
+        _ => None,
+    }
+}
+
 /// Create Arrow schema from Pinot segment metadata
+///
+/// Every field is non-nullable: Pinot stores a default null value for
+/// missing values, so a plain scan never returns nulls. `PinotTable` marks
+/// fields nullable where nulls can appear, taking the union over its
+/// segments: columns some segment lacks (with union schemas) and, with null
+/// substitution, columns some segment has a null value vector for.
 pub fn create_arrow_schema(metadata: &SegmentMetadata) -> Result<SchemaRef> {
     let fields: Vec<Field> = metadata
         .columns
@@ -26,7 +70,7 @@ pub fn create_arrow_schema(metadata: &SegmentMetadata) -> Result<SchemaRef> {
             Field::new(
                 name.clone(),
                 pinot_to_arrow_type(&col_meta.data_type),
-                false, // nullable = false (Pinot columns are non-nullable)
+                false,
             )
         })
         .collect();
@@ -34,6 +78,119 @@ pub fn create_arrow_schema(metadata: &SegmentMetadata) -> Result<SchemaRef> {
     Ok(Arc::new(Schema::new(fields)))
 }
 
+/// Arrow type used for STRING columns emitted as dictionary arrays
+pub fn string_dictionary_type() -> ArrowDataType {
+    ArrowDataType::Dictionary(Box::new(ArrowDataType::Int32), Box::new(ArrowDataType::Utf8))
+}
+
+/// Switch dictionary-encoded STRING columns to `Dictionary(Int32, Utf8)`
+///
+/// `metadata` decides which columns are dictionary-encoded; segments that
+/// store such a column RAW are re-encoded at scan time.
+pub fn with_string_dictionaries(schema: &Schema, metadata: &SegmentMetadata) -> SchemaRef {
+    let fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let dictionary_encoded = metadata
+                .columns
+                .get(field.name())
+                .is_some_and(|c| c.data_type == PinotDataType::String && c.has_dictionary);
+            if dictionary_encoded {
+                field.as_ref().clone().with_data_type(string_dictionary_type())
+            } else {
+                field.as_ref().clone()
+            }
+        })
+        .collect();
+
+    Arc::new(Schema::new(fields))
+}
+
+/// Switch `Utf8` (STRING) columns to `Utf8View`
+///
+/// Dictionary-typed columns are left as they are.
+pub fn with_string_views(schema: &Schema) -> SchemaRef {
+    let fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|field| match field.data_type() {
+            ArrowDataType::Utf8 => field.as_ref().clone().with_data_type(ArrowDataType::Utf8View),
+            _ => field.as_ref().clone(),
+        })
+        .collect();
+
+    Arc::new(Schema::new(fields))
+}
+
+/// Switch multi-value columns to `List(<value type>)`
+///
+/// `create_arrow_schema` keeps multi-value columns at their scalar type; the
+/// scan cannot decode multi-value forward indexes yet and reports an
+/// unsupported-feature error when such a column is read. Callers describing
+/// a segment's true shape (e.g. for display) can opt into list types here.
+pub fn with_multi_value_lists(schema: &Schema, metadata: &SegmentMetadata) -> SchemaRef {
+    let fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|field| match metadata.columns.get(field.name()) {
+            Some(col_meta) if col_meta.is_multi_value => {
+                let item = Field::new("item", pinot_to_arrow_type(&col_meta.data_type), true);
+                field
+                    .as_ref()
+                    .clone()
+                    .with_data_type(ArrowDataType::List(Arc::new(item)))
+            }
+            _ => field.as_ref().clone(),
+        })
+        .collect();
+
+    Arc::new(Schema::new(fields))
+}
+
+/// Set each field's nullability from `is_nullable(field)`
+pub fn with_nullability<F: Fn(&Field) -> bool>(schema: &Schema, is_nullable: F) -> SchemaRef {
+    let fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|field| field.as_ref().clone().with_nullable(is_nullable(field)))
+        .collect();
+
+    Arc::new(Schema::new(fields))
+}
+
+/// Lowercase every field name, leaving the segment info columns and the
+/// fields whose lowercased names would collide as they are
+pub fn with_lowercase_names(schema: &Schema) -> SchemaRef {
+    let lowercase = |field: &Field| field.name().to_lowercase();
+    let fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let name = lowercase(field);
+            let collides = schema
+                .fields()
+                .iter()
+                .filter(|other| lowercase(other) == name)
+                .count()
+                > 1;
+            if is_segment_info_column(field.name()) || name == *field.name() {
+                field.as_ref().clone()
+            } else if collides {
+                tracing::warn!(
+                    column = %field.name(),
+                    "keeping the case of a column whose lowercased name is taken"
+                );
+                field.as_ref().clone()
+            } else {
+                field.as_ref().clone().with_name(name)
+            }
+        })
+        .collect();
+
+    Arc::new(Schema::new(fields))
+}
+
 /// Create projected Arrow schema from column indices
 pub fn create_projected_schema(
     schema: &Schema,
@@ -87,6 +244,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_arrow_to_pinot_type_round_trip() {
+        for pinot_type in [
+            PinotDataType::Int,
+            PinotDataType::Long,
+            PinotDataType::Float,
+            PinotDataType::Double,
+            PinotDataType::String,
+            PinotDataType::Bytes,
+            PinotDataType::Boolean,
+        ] {
+            assert_eq!(
+                arrow_to_pinot_type(&pinot_to_arrow_type(&pinot_type)),
+                Some(pinot_type)
+            );
+        }
+        assert_eq!(
+            arrow_to_pinot_type(&string_dictionary_type()),
+            Some(PinotDataType::String)
+        );
+        assert_eq!(arrow_to_pinot_type(&ArrowDataType::Date32), None);
+    }
+
+    #[test]
+    fn test_with_multi_value_lists() {
+        let dir = tempfile::tempdir().unwrap();
+        pinot_segment::testutil::SegmentBuilder::new("t", "t_0")
+            .string_column("tags", vec!["a"])
+            .int_column("id", vec![1])
+            .property("column.tags.isSingleValue", "false")
+            .property("column.tags.maxNumberOfMultiValues", "3")
+            .write(dir.path())
+            .unwrap();
+        let reader = pinot_segment::SegmentReader::open(dir.path()).unwrap();
+        let metadata = reader.metadata();
+
+        let schema = create_arrow_schema(metadata).unwrap();
+        assert_eq!(
+            schema.field_with_name("tags").unwrap().data_type(),
+            &ArrowDataType::Utf8
+        );
+
+        let schema = with_multi_value_lists(&schema, metadata);
+        assert_eq!(
+            schema.field_with_name("tags").unwrap().data_type(),
+            &ArrowDataType::List(Arc::new(Field::new("item", ArrowDataType::Utf8, true)))
+        );
+        assert_eq!(
+            schema.field_with_name("id").unwrap().data_type(),
+            &ArrowDataType::Int32
+        );
+    }
+
     #[test]
     fn test_create_projected_schema() {
         let fields = vec![
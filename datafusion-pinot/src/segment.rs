@@ -0,0 +1,477 @@
+//! Read interface shared by on-disk and in-memory segments
+//!
+//! `PinotTable` and `PinotExec` only need a segment's metadata and a way to
+//! decode a projected RecordBatch from it. [`SegmentSource`] captures that, so
+//! tables can be backed by real segment files ([`SegmentReader`]) or by Arrow
+//! data held in memory ([`MockSegmentReader`]).
+
+use datafusion::arrow::array::{new_null_array, Array, ArrayRef, RecordBatch};
+use datafusion::arrow::buffer::NullBuffer;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatchOptions;
+use pinot_segment::{ColumnMetadata, DocIdSet, SegmentMetadata, SegmentReader, ValueRange};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::exec::PinotExec;
+use crate::schema::arrow_to_pinot_type;
+
+/// A segment that can be scanned by [`PinotExec`]
+pub trait SegmentSource: fmt::Debug + Send + Sync {
+    /// Segment and column metadata
+    fn metadata(&self) -> &SegmentMetadata;
+
+    /// Decode the columns of `schema` (resolved by name) into a single
+    /// RecordBatch holding every document of the segment
+    fn read_batch(&self, schema: &SchemaRef) -> Result<RecordBatch>;
+
+    /// Decode documents `[start, start + count)` only, clamped to the segment
+    ///
+    /// The default decodes the whole segment and slices the result;
+    /// implementations that can read part of a segment should override it.
+    fn read_batch_range(&self, schema: &SchemaRef, start: u32, count: u32) -> Result<RecordBatch> {
+        let batch = self.read_batch(schema)?;
+        let start = (start as usize).min(batch.num_rows());
+        let count = (count as usize).min(batch.num_rows() - start);
+        Ok(batch.slice(start, count))
+    }
+
+    /// Like [`read_batch_range`](Self::read_batch_range), taking the nulls
+    /// of nullable columns from `null_masks` instead of reading them
+    ///
+    /// `null_masks` holds, for each nullable column of `schema` with a null
+    /// value vector, its validity over the whole segment; a scan loads them
+    /// once and reuses them for every window. The default ignores them,
+    /// for segments whose decoded values already carry their nulls.
+    fn read_batch_range_with_nulls(
+        &self,
+        schema: &SchemaRef,
+        start: u32,
+        count: u32,
+        _null_masks: &HashMap<String, NullBuffer>,
+    ) -> Result<RecordBatch> {
+        self.read_batch_range(schema, start, count)
+    }
+
+    /// Check that the column can be decoded, without decoding it
+    ///
+    /// Returns the error a read of the column would fail with. The default
+    /// accepts every column.
+    fn check_column_readable(&self, _column_name: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether some documents of the column may be null
+    fn has_null_value_vector(&self, column_name: &str) -> bool;
+
+    /// Whether the segment has a validDocIds snapshot (upsert tables only)
+    fn has_valid_doc_ids(&self) -> bool;
+
+    /// Doc ids of the validDocIds snapshot, or `None` without a snapshot
+    fn read_valid_doc_ids(&self) -> Result<Option<Vec<u32>>>;
+
+    /// Doc ids whose value of `column_name` falls in `range`, or `None` when
+    /// the segment has no range index to answer it
+    fn range_doc_ids(&self, column_name: &str, range: &ValueRange) -> Result<Option<Vec<u32>>>;
+
+    /// Doc ids read as null when `column_name` is a nullable field: every
+    /// document if the segment lacks the column
+    fn null_doc_ids(&self, column_name: &str) -> Result<DocIdSet>;
+
+    /// Directory the segment was read from; `None` for in-memory segments
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Estimated size in bytes of the segment's decoded values, for
+    /// cost-based planning; the default reports 0 (unknown)
+    fn estimate_size_bytes(&self) -> u64 {
+        0
+    }
+
+    /// Estimated size in bytes of one column's decoded values; 0 when the
+    /// column is unknown or the segment cannot tell
+    fn estimate_column_size_bytes(&self, _column_name: &str) -> u64 {
+        0
+    }
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Whether a directory named `name` is a staging directory rather than a
+/// segment or table: `tmp`, `tmp-<uuid>`, `*.tmp` and hidden directories
+pub(crate) fn is_temporary_dir_name(name: &str) -> bool {
+    name == "tmp" || name.starts_with("tmp-") || name.ends_with(".tmp") || name.starts_with('.')
+}
+
+/// `path`, or the directory it links to when it is a symlink
+///
+/// `None`, with a debug log, for dangling links and link cycles, which
+/// `fs::canonicalize` rejects instead of following forever.
+pub(crate) fn resolve_symlink(path: &Path) -> Option<PathBuf> {
+    let is_symlink = fs::symlink_metadata(path)
+        .map(|metadata| metadata.file_type().is_symlink())
+        .unwrap_or(false);
+    if !is_symlink {
+        return Some(path.to_path_buf());
+    }
+    match fs::canonicalize(path) {
+        Ok(resolved) => Some(resolved),
+        Err(e) => {
+            tracing::debug!(path = %path.display(), error = %e, "skipping unresolvable symlink");
+            None
+        }
+    }
+}
+
+/// Directory holding the files of the segment directory `segment_dir`
+///
+/// That is `<segment>/v3` when it holds a `metadata.properties`, otherwise
+/// `segment_dir` itself for segments untarred flat or written in an older
+/// layout. A symlinked `segment_dir` is resolved first, so readers open, and
+/// errors name, the files it points to. `None`, with a debug log, for
+/// staging directories (see [`is_temporary_dir_name`]), unresolvable links
+/// and segments still being written or downloaded: those missing
+/// `metadata.properties` or `columns.psf`.
+pub(crate) fn segment_data_dir(segment_dir: &Path) -> Option<PathBuf> {
+    let name = segment_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    if is_temporary_dir_name(name) {
+        tracing::debug!(segment = %segment_dir.display(), "skipping temporary directory");
+        return None;
+    }
+    let segment_dir = resolve_symlink(segment_dir)?;
+
+    let v3_dir = segment_dir.join("v3");
+    let data_dir = if v3_dir.join("metadata.properties").is_file() {
+        v3_dir
+    } else if segment_dir.join("metadata.properties").is_file() {
+        segment_dir.clone()
+    } else {
+        tracing::debug!(
+            segment = %segment_dir.display(),
+            "skipping incomplete segment without metadata.properties"
+        );
+        return None;
+    };
+
+    if !data_dir.join("columns.psf").is_file() {
+        tracing::debug!(
+            segment = %segment_dir.display(),
+            "skipping incomplete segment without columns.psf"
+        );
+        return None;
+    }
+    Some(data_dir)
+}
+
+/// Name of the segment read from `data_dir`, a path returned by
+/// [`segment_data_dir`]
+pub(crate) fn segment_dir_name(data_dir: &Path) -> Option<&std::ffi::OsStr> {
+    if data_dir.file_name()? == "v3" {
+        data_dir.parent()?.file_name()
+    } else {
+        data_dir.file_name()
+    }
+}
+
+/// Sort key ordering segments the way Pinot numbers them
+///
+/// Names are split into the part before the sequence number, the sequence
+/// number compared numerically (so `_OFFLINE_2` precedes `_OFFLINE_10`) and,
+/// for realtime `{table}__{partition}__{sequence}__{creationTime}` names,
+/// the creation time. Names without a sequence number sort by name.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct SegmentSortKey {
+    prefix: String,
+    sequence: Option<u64>,
+    creation_time: String,
+    path: PathBuf,
+}
+
+impl SegmentSortKey {
+    pub(crate) fn new(data_dir: &Path) -> Self {
+        let name = segment_dir_name(data_dir)
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        let (prefix, sequence, creation_time) = split_segment_name(name)
+            .unwrap_or((name, None, ""));
+        Self {
+            prefix: prefix.to_string(),
+            sequence,
+            creation_time: creation_time.to_string(),
+            path: data_dir.to_path_buf(),
+        }
+    }
+}
+
+/// `(prefix, sequence, creation time)` of a segment name, `None` when it
+/// carries no sequence number
+fn split_segment_name(name: &str) -> Option<(&str, Option<u64>, &str)> {
+    // Realtime (LLC): {table}__{partition}__{sequence}__{yyyyMMdd'T'HHmm'Z'},
+    // whose timestamp format sorts chronologically as text
+    let parts: Vec<&str> = name.rsplitn(3, "__").collect();
+    if let [creation_time, sequence, prefix] = parts[..] {
+        if let Ok(sequence) = sequence.parse() {
+            return Some((prefix, Some(sequence), creation_time));
+        }
+    }
+
+    // Offline: {table}_OFFLINE_{sequence} or {table}_{minTime}_{maxTime}_{sequence}
+    let (prefix, sequence) = name.rsplit_once('_')?;
+    Some((prefix, Some(sequence.parse().ok()?), ""))
+}
+
+/// Sort segment data directories by [`SegmentSortKey`]
+pub(crate) fn sort_segment_paths(segment_paths: &mut [PathBuf]) {
+    segment_paths.sort_by_cached_key(|path| SegmentSortKey::new(path));
+}
+
+impl SegmentSource for SegmentReader {
+    fn metadata(&self) -> &SegmentMetadata {
+        SegmentReader::metadata(self)
+    }
+
+    fn read_batch(&self, schema: &SchemaRef) -> Result<RecordBatch> {
+        PinotExec::create_batch_with_builders(self, schema)
+    }
+
+    fn read_batch_range(&self, schema: &SchemaRef, start: u32, count: u32) -> Result<RecordBatch> {
+        PinotExec::create_batch_in_range(self, schema, start..start.saturating_add(count))
+    }
+
+    fn read_batch_range_with_nulls(
+        &self,
+        schema: &SchemaRef,
+        start: u32,
+        count: u32,
+        null_masks: &HashMap<String, NullBuffer>,
+    ) -> Result<RecordBatch> {
+        PinotExec::decode_batch_in_range(
+            self,
+            schema,
+            start..start.saturating_add(count),
+            Some(null_masks),
+        )
+    }
+
+    fn check_column_readable(&self, column_name: &str) -> Result<()> {
+        Ok(SegmentReader::check_column_readable(self, column_name)?)
+    }
+
+    fn has_null_value_vector(&self, column_name: &str) -> bool {
+        SegmentReader::has_null_value_vector(self, column_name)
+    }
+
+    fn has_valid_doc_ids(&self) -> bool {
+        SegmentReader::has_valid_doc_ids(self)
+    }
+
+    fn read_valid_doc_ids(&self) -> Result<Option<Vec<u32>>> {
+        Ok(SegmentReader::read_valid_doc_ids(self)?)
+    }
+
+    fn range_doc_ids(&self, column_name: &str, range: &ValueRange) -> Result<Option<Vec<u32>>> {
+        Ok(SegmentReader::range_doc_ids(self, column_name, range)?)
+    }
+
+    fn estimate_size_bytes(&self) -> u64 {
+        SegmentReader::estimate_size_bytes(self)
+    }
+
+    fn estimate_column_size_bytes(&self, column_name: &str) -> u64 {
+        self.metadata()
+            .columns
+            .get(column_name)
+            .map(|column| SegmentReader::estimate_column_size_bytes(self, column))
+            .unwrap_or(0)
+    }
+
+    fn null_doc_ids(&self, column_name: &str) -> Result<DocIdSet> {
+        let Some(col_meta) = self.metadata().columns.get(column_name) else {
+            return Ok(DocIdSet::all(self.total_docs()));
+        };
+        if !col_meta.has_null_value && !self.has_null_value_vector(column_name) {
+            return Ok(DocIdSet::default());
+        }
+        Ok(self
+            .null_bitmap_reader(column_name)?
+            .map(|reader| reader.get_null_doc_ids())
+            .unwrap_or_default())
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(self.segment_dir())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// In-memory segment backed by a single Arrow RecordBatch
+///
+/// Metadata is synthesized from the batch: every column is RAW with no
+/// recorded min/max, so statistics only report row counts. Columns holding
+/// nulls behave like columns with a null value vector.
+#[derive(Debug, Clone)]
+pub struct MockSegmentReader {
+    metadata: SegmentMetadata,
+    batch: RecordBatch,
+}
+
+impl MockSegmentReader {
+    /// Wrap `batch` as segment `segment_name` of `table_name`
+    pub fn new(batch: RecordBatch, table_name: &str, segment_name: &str) -> Result<Self> {
+        let total_docs = u32::try_from(batch.num_rows()).map_err(|_| {
+            Error::Internal(format!(
+                "Batch for segment {} has too many rows: {}",
+                segment_name,
+                batch.num_rows()
+            ))
+        })?;
+
+        let mut columns = HashMap::new();
+        for field in batch.schema().fields() {
+            let data_type = arrow_to_pinot_type(field.data_type()).ok_or_else(|| {
+                Error::UnsupportedFeature(format!(
+                    "Arrow type {} of column {} has no Pinot equivalent",
+                    field.data_type(),
+                    field.name()
+                ))
+            })?;
+            columns.insert(
+                field.name().clone(),
+                ColumnMetadata {
+                    name: field.name().clone(),
+                    data_type,
+                    cardinality: 0,
+                    total_docs,
+                    bits_per_element: 0,
+                    has_dictionary: false,
+                    is_sorted: false,
+                    has_null_value: batch
+                        .column_by_name(field.name())
+                        .is_some_and(|c| c.null_count() > 0),
+                    is_multi_value: false,
+                    max_multi_values: 0,
+                    total_number_of_entries: total_docs,
+                    length_of_each_entry: 0,
+                    padded_length: 0,
+                    front_coded_dictionary: false,
+                    min_value: None,
+                    max_value: None,
+                    default_null_value: None,
+                },
+            );
+        }
+
+        let metadata = SegmentMetadata {
+            segment_name: segment_name.to_string(),
+            table_name: table_name.to_string(),
+            total_docs,
+            columns,
+            time_column: None,
+            start_time: None,
+            end_time: None,
+            time_unit: None,
+            segment_version: None,
+            creator_version: None,
+            generator_class: None,
+            crc: None,
+            creation_time: None,
+            star_trees: Vec::new(),
+        };
+
+        Ok(Self { metadata, batch })
+    }
+
+    /// The batch backing this segment
+    pub fn batch(&self) -> &RecordBatch {
+        &self.batch
+    }
+}
+
+impl SegmentSource for MockSegmentReader {
+    fn metadata(&self) -> &SegmentMetadata {
+        &self.metadata
+    }
+
+    fn read_batch(&self, schema: &SchemaRef) -> Result<RecordBatch> {
+        let arrays = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                if field.is_nullable() && self.batch.column_by_name(field.name()).is_none() {
+                    return Ok(new_null_array(field.data_type(), self.batch.num_rows()));
+                }
+                let column = self.batch.column_by_name(field.name()).ok_or_else(|| {
+                    Error::Internal(format!(
+                        "Column {} not found in segment {}",
+                        field.name(),
+                        self.metadata.segment_name
+                    ))
+                })?;
+                if column.data_type() == field.data_type() {
+                    return Ok(column.clone());
+                }
+                cast(column, field.data_type())
+                    .map_err(|e| Error::Internal(format!("Failed to cast {}: {}", field.name(), e)))
+            })
+            .collect::<Result<Vec<ArrayRef>>>()?;
+
+        let options = RecordBatchOptions::new().with_row_count(Some(self.batch.num_rows()));
+        RecordBatch::try_new_with_options(schema.clone(), arrays, &options)
+            .map_err(|e| Error::Internal(format!("Failed to create RecordBatch: {}", e)))
+    }
+
+    fn has_null_value_vector(&self, column_name: &str) -> bool {
+        self.batch
+            .column_by_name(column_name)
+            .is_some_and(|c| c.null_count() > 0)
+    }
+
+    fn has_valid_doc_ids(&self) -> bool {
+        false
+    }
+
+    fn read_valid_doc_ids(&self) -> Result<Option<Vec<u32>>> {
+        Ok(None)
+    }
+
+    fn range_doc_ids(&self, _column_name: &str, _range: &ValueRange) -> Result<Option<Vec<u32>>> {
+        Ok(None)
+    }
+
+    fn estimate_size_bytes(&self) -> u64 {
+        self.batch.get_array_memory_size() as u64
+    }
+
+    fn estimate_column_size_bytes(&self, column_name: &str) -> u64 {
+        self.batch
+            .column_by_name(column_name)
+            .map(|column| column.get_array_memory_size() as u64)
+            .unwrap_or(0)
+    }
+
+    fn null_doc_ids(&self, column_name: &str) -> Result<DocIdSet> {
+        let Some(column) = self.batch.column_by_name(column_name) else {
+            return Ok(DocIdSet::all(self.metadata.total_docs));
+        };
+        Ok((0..column.len())
+            .filter(|&row| column.is_null(row))
+            .map(|row| row as u32)
+            .collect())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
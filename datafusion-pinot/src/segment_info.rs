@@ -0,0 +1,128 @@
+//! Keeps the segment info columns out of `SELECT *` and `DESCRIBE`
+//!
+//! Tables opened with segment info columns carry `__segment_name__` and
+//! `__segment_index__` in their scan schema so queries can name them;
+//! [`SegmentInfoColumnsRule`] drops them wherever the query did not.
+
+use datafusion::arrow::datatypes::Schema;
+use datafusion::common::tree_node::{Transformed, TransformedResult};
+use datafusion::common::{DFSchema, Result};
+use datafusion::config::ConfigOptions;
+use datafusion::logical_expr::utils::{expand_qualified_wildcard, expand_wildcard, find_base_plan};
+use datafusion::logical_expr::{DescribeTable, Expr, LogicalPlan, Projection};
+use datafusion::optimizer::AnalyzerRule;
+use std::sync::Arc;
+
+use crate::schema::is_segment_info_column;
+
+/// Analyzer rule hiding the segment info columns unless projected by name
+///
+/// Expands `SELECT *` (and `SELECT t.*`) over an input with segment info
+/// columns to the other columns, and leaves them out of `DESCRIBE`. It must
+/// run before DataFusion's wildcard expansion;
+/// [`SessionContextExt::register_pinot_segment_info_rule`](crate::SessionContextExt::register_pinot_segment_info_rule)
+/// puts it there. Wildcards with `REPLACE` are left as they are.
+#[derive(Debug, Default)]
+pub struct SegmentInfoColumnsRule;
+
+impl SegmentInfoColumnsRule {
+    /// Name the rule is registered under
+    pub const NAME: &'static str = "pinot_segment_info_columns";
+
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl AnalyzerRule for SegmentInfoColumnsRule {
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> Result<LogicalPlan> {
+        // Nodes above a rewritten projection cache its old schema
+        let mut rewritten = false;
+        plan.transform_up_with_subqueries(|plan| {
+            let plan = hide_segment_info_columns(plan)?;
+            if plan.transformed {
+                rewritten = true;
+                Ok(plan)
+            } else if rewritten {
+                plan.data.recompute_schema().map(Transformed::yes)
+            } else {
+                Ok(plan)
+            }
+        })
+        .data()
+    }
+
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+}
+
+fn hide_segment_info_columns(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+    match plan {
+        LogicalPlan::Projection(Projection { expr, input, .. })
+            if expr
+                .iter()
+                .any(|e| hides_columns(e, find_base_plan(&input).schema())) =>
+        {
+            let base = find_base_plan(&input);
+            let mut visible = Vec::with_capacity(expr.len());
+            for e in expr {
+                match e {
+                    Expr::Wildcard { qualifier, options } if options.replace.is_none() => {
+                        let expanded = match &qualifier {
+                            Some(qualifier) => {
+                                expand_qualified_wildcard(qualifier, base.schema(), Some(&options))?
+                            }
+                            None => expand_wildcard(base.schema(), base, Some(&options))?,
+                        };
+                        visible.extend(expanded.into_iter().filter(|e| !is_segment_info_expr(e)));
+                    }
+                    e => visible.push(e),
+                }
+            }
+            Ok(Transformed::yes(LogicalPlan::Projection(
+                Projection::try_new(visible, input)?,
+            )))
+        }
+        LogicalPlan::DescribeTable(DescribeTable {
+            schema,
+            output_schema,
+        }) if schema
+            .fields()
+            .iter()
+            .any(|f| is_segment_info_column(f.name())) =>
+        {
+            let fields: Vec<_> = schema
+                .fields()
+                .iter()
+                .filter(|f| !is_segment_info_column(f.name()))
+                .cloned()
+                .collect();
+            Ok(Transformed::yes(LogicalPlan::DescribeTable(
+                DescribeTable {
+                    schema: Arc::new(Schema::new(fields)),
+                    output_schema,
+                },
+            )))
+        }
+        plan => Ok(Transformed::no(plan)),
+    }
+}
+
+/// Whether `expr` is a wildcard expanding to a segment info column of `schema`
+fn hides_columns(expr: &Expr, schema: &DFSchema) -> bool {
+    let Expr::Wildcard { qualifier, options } = expr else {
+        return false;
+    };
+    options.replace.is_none()
+        && schema.iter().any(|(field_qualifier, field)| {
+            is_segment_info_column(field.name())
+                && qualifier
+                    .as_ref()
+                    .is_none_or(|qualifier| field_qualifier == Some(qualifier))
+        })
+}
+
+fn is_segment_info_expr(expr: &Expr) -> bool {
+    matches!(expr, Expr::Column(column) if is_segment_info_column(&column.name))
+}
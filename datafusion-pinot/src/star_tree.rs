@@ -0,0 +1,508 @@
+//! Answer aggregations from star-tree indexes instead of scanning
+//!
+//! [`StarTreeRule`] is a logical optimizer rule. It rewrites an `Aggregate`
+//! directly over a [`PinotTable`] scan when every segment has a star tree
+//! that pre-aggregates the query: plain column GROUP BY keys that are
+//! dimensions of the tree, and `SUM`/`MIN`/`MAX` of a metric of the tree or
+//! `COUNT(*)`. The aggregate is replaced by a scan whose plan is a
+//! [`StarTreeExec`], which reads the pre-aggregated records of each segment
+//! and merges them across segments.
+//!
+//! The rewrite is only applied when the star trees give the exact answer:
+//! no filters or limit on the scan, no DISTINCT or FILTER on the aggregates,
+//! and no null value vectors or validDocIds on the segments.
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{new_empty_array, ArrayRef, Float64Array, RecordBatch};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Schema, SchemaRef};
+use datafusion::catalog::Session;
+use datafusion::common::tree_node::Transformed;
+use datafusion::common::{Column, DFSchema, ScalarValue};
+use datafusion::datasource::{provider_as_source, source_as_provider, TableProvider};
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::expr::{AggregateFunction, Cast};
+use datafusion::logical_expr::{
+    Aggregate, Expr, LogicalPlan, LogicalPlanBuilder, TableScan, TableType,
+};
+use datafusion::optimizer::optimizer::ApplyOrder;
+use datafusion::optimizer::{OptimizerConfig, OptimizerRule};
+use datafusion::physical_expr::EquivalenceProperties;
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties,
+};
+use futures::stream;
+use pinot_segment::{AggFn, ColumnValue, SegmentReader, StarTreeIndexReader};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::table::PinotTable;
+
+/// Optimizer rule answering matching aggregations from star-tree indexes
+///
+/// Register it with `SessionContextExt::register_pinot_star_tree_rule`, or
+/// add it to a `SessionState` with `add_optimizer_rule`.
+#[derive(Debug, Default)]
+pub struct StarTreeRule;
+
+impl StarTreeRule {
+    /// Name the rule is registered under
+    pub const NAME: &'static str = "pinot_star_tree";
+
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl OptimizerRule for StarTreeRule {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::BottomUp)
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> DataFusionResult<Transformed<LogicalPlan>> {
+        let LogicalPlan::Aggregate(aggregate) = &plan else {
+            return Ok(Transformed::no(plan));
+        };
+        let LogicalPlan::TableScan(scan) = aggregate.input.as_ref() else {
+            return Ok(Transformed::no(plan));
+        };
+        match star_tree_plan(aggregate, scan)? {
+            Some(rewritten) => Ok(Transformed::yes(rewritten)),
+            None => Ok(Transformed::no(plan)),
+        }
+    }
+}
+
+/// The star-tree scan replacing `aggregate`, or `None` when the star trees
+/// cannot answer it exactly
+fn star_tree_plan(
+    aggregate: &Aggregate,
+    scan: &TableScan,
+) -> DataFusionResult<Option<LogicalPlan>> {
+    if !scan.filters.is_empty() || scan.fetch.is_some() {
+        return Ok(None);
+    }
+    let provider = source_as_provider(&scan.source)?;
+    let Some(table) = provider.as_any().downcast_ref::<PinotTable>() else {
+        return Ok(None);
+    };
+    if table.options().lowercase_column_names {
+        return Ok(None);
+    }
+
+    let Some(group_by) = aggregate
+        .group_expr
+        .iter()
+        .map(|expr| match expr {
+            Expr::Column(column) => Some(column.name.clone()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(None);
+    };
+    let Some(aggregates) = aggregate
+        .aggr_expr
+        .iter()
+        .map(|expr| star_tree_aggregate(expr, aggregate.input.schema()))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(None);
+    };
+
+    let Some(indexes) = star_tree_indexes(table, &group_by, &aggregates) else {
+        return Ok(None);
+    };
+    tracing::debug!(
+        group_by = ?group_by,
+        segments = indexes.len(),
+        "answering aggregate from star-tree indexes"
+    );
+
+    let schema = Arc::new(Schema::new(
+        aggregate
+            .schema
+            .fields()
+            .iter()
+            .map(|field| field.as_ref().clone())
+            .collect::<Vec<_>>(),
+    ));
+    let source = StarTreeTable {
+        schema,
+        query: Arc::new(StarTreeQuery {
+            indexes,
+            group_by,
+            aggregates,
+        }),
+    };
+
+    // Alias each column back to the aggregate's qualified output name
+    let projection = aggregate
+        .schema
+        .iter()
+        .map(|(qualifier, field)| {
+            Expr::Column(Column::new(Some(scan.table_name.clone()), field.name()))
+                .alias_qualified(qualifier.cloned(), field.name())
+        })
+        .collect::<Vec<_>>();
+    let plan = LogicalPlanBuilder::scan(
+        scan.table_name.clone(),
+        provider_as_source(Arc::new(source)),
+        None,
+    )?
+    .project(projection)?
+    .build()?;
+    Ok(Some(plan))
+}
+
+/// `(aggregation, metric column)` of a star-tree answerable aggregate
+///
+/// `COUNT(column)` counts documents like `COUNT(*)` once null value vectors
+/// are ruled out; the column of `COUNT(*)` is `*`.
+fn star_tree_aggregate(expr: &Expr, input: &DFSchema) -> Option<(AggFn, String)> {
+    let expr = match expr {
+        Expr::Alias(alias) => alias.expr.as_ref(),
+        expr => expr,
+    };
+    let Expr::AggregateFunction(AggregateFunction {
+        func,
+        args,
+        distinct: false,
+        filter: None,
+        order_by: None,
+        ..
+    }) = expr
+    else {
+        return None;
+    };
+    let aggregation = match func.name() {
+        "sum" => AggFn::Sum,
+        "count" => AggFn::Count,
+        "min" => AggFn::Min,
+        "max" => AggFn::Max,
+        _ => return None,
+    };
+    match (aggregation, args.as_slice()) {
+        (AggFn::Count, [Expr::Literal(value)]) if !value.is_null() => {
+            Some((aggregation, "*".to_string()))
+        }
+        (_, [argument]) => metric_column(argument, input).map(|column| (aggregation, column)),
+        _ => None,
+    }
+}
+
+/// Column an aggregate argument reads, seen through the widening casts type
+/// coercion adds (`SUM(int)` sums `CAST(int AS BIGINT)`)
+fn metric_column(expr: &Expr, input: &DFSchema) -> Option<String> {
+    match expr {
+        Expr::Column(column) => Some(column.name.clone()),
+        Expr::Cast(Cast { expr, data_type }) => {
+            let Expr::Column(column) = expr.as_ref() else {
+                return None;
+            };
+            let source = input.field_from_column(column).ok()?.data_type();
+            let widening = match data_type {
+                DataType::Int64 => matches!(source, DataType::Int32 | DataType::Int64),
+                DataType::Float64 => source.is_numeric(),
+                _ => false,
+            };
+            widening.then(|| column.name.clone())
+        }
+        _ => None,
+    }
+}
+
+/// A star tree of every segment of `table` answering the query, the first
+/// matching one where a segment has several, or `None` when one segment
+/// cannot answer the query exactly
+fn star_tree_indexes(
+    table: &PinotTable,
+    group_by: &[String],
+    aggregates: &[(AggFn, String)],
+) -> Option<Vec<Arc<StarTreeIndexReader>>> {
+    let segments = table
+        .all_segments()
+        .inspect_err(|e| tracing::debug!(error = %e, "star tree not used"))
+        .ok()?;
+    let group_by: Vec<&str> = group_by.iter().map(String::as_str).collect();
+    let columns: Vec<&str> = aggregates
+        .iter()
+        .map(|(_, column)| column.as_str())
+        .filter(|column| *column != "*")
+        .chain(group_by.iter().copied())
+        .collect();
+
+    let mut indexes = Vec::with_capacity(segments.len());
+    for segment in segments {
+        if segment.has_valid_doc_ids()
+            || columns
+                .iter()
+                .any(|column| segment.has_null_value_vector(column))
+        {
+            return None;
+        }
+        let reader = segment.as_any().downcast_ref::<SegmentReader>()?;
+        if !reader.has_star_tree_index() {
+            return None;
+        }
+        let trees = reader
+            .star_tree_indexes()
+            .inspect_err(|e| {
+                tracing::debug!(
+                    segment = reader.metadata().segment_name,
+                    error = %e,
+                    "star tree not used"
+                )
+            })
+            .ok()?;
+        let index = trees.into_iter().find(|index| {
+            aggregates
+                .iter()
+                .all(|(aggregation, column)| index.supports(&group_by, column, *aggregation))
+                && index.supports(&group_by, "*", AggFn::Count)
+        })?;
+        indexes.push(Arc::new(index));
+    }
+    Some(indexes)
+}
+
+/// What a [`StarTreeExec`] computes
+#[derive(Debug)]
+struct StarTreeQuery {
+    indexes: Vec<Arc<StarTreeIndexReader>>,
+    group_by: Vec<String>,
+    aggregates: Vec<(AggFn, String)>,
+}
+
+impl StarTreeQuery {
+    /// Merge the star-tree answers of every segment into one batch of
+    /// `schema`: the group-by columns followed by the aggregates
+    fn execute(&self, schema: &SchemaRef) -> DataFusionResult<RecordBatch> {
+        let group_by: Vec<&str> = self.group_by.iter().map(String::as_str).collect();
+        let mut keys: Vec<Vec<ColumnValue>> = Vec::new();
+        let mut positions: HashMap<Vec<ColumnValue>, usize> = HashMap::new();
+        let mut values: Vec<Vec<Option<f64>>> = Vec::new();
+
+        for index in &self.indexes {
+            // Groups come from the document counts, so a query without
+            // aggregates (SELECT DISTINCT) still lists them
+            let counted = [(AggFn::Count, "*".to_string())];
+            let aggregates = if self.aggregates.is_empty() {
+                &counted[..]
+            } else {
+                &self.aggregates[..]
+            };
+            for (i, (aggregation, column)) in aggregates.iter().enumerate() {
+                for (key, value) in index
+                    .query(&group_by, column, *aggregation)
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?
+                {
+                    let position = *positions.entry(key.clone()).or_insert_with(|| {
+                        keys.push(key);
+                        values.push(vec![None; aggregates.len()]);
+                        keys.len() - 1
+                    });
+                    let slot = &mut values[position][i];
+                    *slot = Some(match *slot {
+                        Some(merged) => aggregation.merge(merged, value),
+                        None => value,
+                    });
+                }
+            }
+        }
+
+        // An ungrouped aggregate has one row even over no documents
+        if group_by.is_empty() && keys.is_empty() {
+            keys.push(Vec::new());
+            values.push(
+                self.aggregates
+                    .iter()
+                    .map(|(aggregation, _)| (*aggregation == AggFn::Count).then_some(0.0))
+                    .collect(),
+            );
+        }
+
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+        for (i, field) in schema.fields().iter().take(group_by.len()).enumerate() {
+            let array = if keys.is_empty() {
+                new_empty_array(field.data_type())
+            } else {
+                ScalarValue::iter_to_array(keys.iter().map(|key| column_scalar(&key[i])))?
+            };
+            columns.push(cast(&array, field.data_type())?);
+        }
+        for (i, field) in schema.fields().iter().skip(group_by.len()).enumerate() {
+            let array = Float64Array::from_iter(values.iter().map(|row| row[i]));
+            columns.push(cast(&array, field.data_type())?);
+        }
+        Ok(RecordBatch::try_new(schema.clone(), columns)?)
+    }
+}
+
+/// Arrow scalar of a star-tree dimension value
+fn column_scalar(value: &ColumnValue) -> ScalarValue {
+    match value {
+        ColumnValue::Int(v) => ScalarValue::Int32(Some(*v)),
+        ColumnValue::Long(v) => ScalarValue::Int64(Some(*v)),
+        ColumnValue::Float(v) => ScalarValue::Float32(Some(*v)),
+        ColumnValue::Double(v) => ScalarValue::Float64(Some(*v)),
+        ColumnValue::String(v) => ScalarValue::Utf8(Some(v.clone())),
+        ColumnValue::Bytes(v) => ScalarValue::Binary(Some(v.clone())),
+    }
+}
+
+/// Table provider standing in for a star-tree answered aggregate
+#[derive(Debug)]
+struct StarTreeTable {
+    schema: SchemaRef,
+    query: Arc<StarTreeQuery>,
+}
+
+#[async_trait]
+impl TableProvider for StarTreeTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(StarTreeExec::new(
+            self.query.clone(),
+            self.schema.clone(),
+            projection.cloned(),
+        )?))
+    }
+}
+
+/// Execution plan answering an aggregate from star-tree indexes
+///
+/// Produces a single partition with one row per group.
+#[derive(Debug)]
+pub struct StarTreeExec {
+    query: Arc<StarTreeQuery>,
+    /// Group-by columns followed by the aggregates
+    schema: SchemaRef,
+    projection: Option<Vec<usize>>,
+    plan_properties: PlanProperties,
+}
+
+impl StarTreeExec {
+    fn new(
+        query: Arc<StarTreeQuery>,
+        schema: SchemaRef,
+        projection: Option<Vec<usize>>,
+    ) -> DataFusionResult<Self> {
+        let projected_schema = match &projection {
+            Some(projection) => Arc::new(schema.project(projection)?),
+            None => schema.clone(),
+        };
+        let plan_properties = PlanProperties::new(
+            EquivalenceProperties::new(projected_schema),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        );
+        Ok(Self {
+            query,
+            schema,
+            projection,
+            plan_properties,
+        })
+    }
+}
+
+impl DisplayAs for StarTreeExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        let aggregates: Vec<String> = self
+            .query
+            .aggregates
+            .iter()
+            .map(|(aggregation, column)| aggregation.metric_name(column))
+            .collect();
+        write!(
+            f,
+            "StarTreeExec: group_by=[{}], aggregates=[{}], segments={}",
+            self.query.group_by.join(", "),
+            aggregates.join(", "),
+            self.query.indexes.len()
+        )
+    }
+}
+
+impl ExecutionPlan for StarTreeExec {
+    fn name(&self) -> &str {
+        "StarTreeExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(format!(
+                "Partition {} out of range (StarTreeExec has 1 partition)",
+                partition
+            )));
+        }
+        let query = self.query.clone();
+        let schema = self.schema.clone();
+        let projection = self.projection.clone();
+        let batch = stream::once(async move {
+            let batch = query.execute(&schema)?;
+            match projection {
+                Some(projection) => Ok(batch.project(&projection)?),
+                None => Ok(batch),
+            }
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            batch,
+        )))
+    }
+}
@@ -0,0 +1,164 @@
+//! Table and plan statistics derived from segment metadata
+//!
+//! Every segment records its document count and, per column, the min/max
+//! values seen by the segment writer. Reporting these as exact statistics lets
+//! DataFusion answer `COUNT(*)`, `MIN(col)` and `MAX(col)` without a scan.
+
+use datafusion::arrow::datatypes::{DataType as ArrowDataType, Schema};
+use datafusion::common::stats::Precision;
+use datafusion::common::{ColumnStatistics, Statistics};
+use datafusion::scalar::ScalarValue;
+use std::sync::Arc;
+
+use crate::options::PinotReadOptions;
+use crate::segment::SegmentSource;
+
+/// Statistics of a single segment for the columns in `schema`
+///
+/// Min/max are exact when the writer recorded them and the column has no
+/// null value vector (null documents hold a substituted default value, which
+/// the recorded min/max may or may not reflect). Unknown columns are absent.
+/// The byte size is the segment's inexact estimate of the decoded values of
+/// the columns in `schema` (see [`SegmentSource::estimate_column_size_bytes`]).
+pub fn segment_statistics(segment: &dyn SegmentSource, schema: &Schema) -> Statistics {
+    let metadata = segment.metadata();
+
+    let column_statistics = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let Some(col_meta) = metadata.columns.get(field.name()) else {
+                return ColumnStatistics::new_unknown();
+            };
+
+            let bound = |value: &Option<String>| {
+                let scalar = value
+                    .as_deref()
+                    .and_then(|v| parse_scalar(v, field.data_type()));
+                match scalar {
+                    Some(scalar) if segment.has_null_value_vector(field.name()) => {
+                        Precision::Inexact(scalar)
+                    }
+                    Some(scalar) => Precision::Exact(scalar),
+                    None => Precision::Absent,
+                }
+            };
+
+            let null_count = if segment.has_null_value_vector(field.name()) {
+                Precision::Absent
+            } else {
+                Precision::Exact(0)
+            };
+
+            ColumnStatistics {
+                null_count,
+                min_value: bound(&col_meta.min_value),
+                max_value: bound(&col_meta.max_value),
+                distinct_count: if col_meta.has_dictionary {
+                    Precision::Exact(col_meta.cardinality as usize)
+                } else {
+                    Precision::Absent
+                },
+            }
+        })
+        .collect();
+
+    let total_byte_size: u64 = schema
+        .fields()
+        .iter()
+        .map(|field| segment.estimate_column_size_bytes(field.name()))
+        .sum();
+
+    Statistics {
+        num_rows: Precision::Exact(metadata.total_docs as usize),
+        total_byte_size: Precision::Inexact(total_byte_size as usize),
+        column_statistics,
+    }
+}
+
+/// Merge per-segment statistics into statistics for the whole scan
+///
+/// Row and null counts and byte sizes are summed; min/max keep the overall
+/// extremes and stay exact only if every non-empty segment reported exact
+/// values. Distinct counts cannot be combined across segments and become
+/// inexact upper bounds.
+///
+/// When `options` skip superseded upsert documents, segments with a
+/// validDocIds snapshot return fewer rows than their metadata records, so the
+/// result is downgraded to inexact.
+pub fn merge_segment_statistics(
+    segments: &[Arc<dyn SegmentSource>],
+    schema: &Schema,
+    options: &PinotReadOptions,
+) -> Statistics {
+    let mut merged: Option<Statistics> = None;
+
+    // Empty segments contribute no values, and Pinot records no min/max for them
+    for segment in segments.iter().filter(|s| s.metadata().total_docs > 0) {
+        let stats = segment_statistics(segment.as_ref(), schema);
+        merged = Some(match merged {
+            None => stats,
+            Some(acc) => Statistics {
+                num_rows: acc.num_rows.add(&stats.num_rows),
+                total_byte_size: acc.total_byte_size.add(&stats.total_byte_size),
+                column_statistics: acc
+                    .column_statistics
+                    .iter()
+                    .zip(stats.column_statistics.iter())
+                    .map(|(a, b)| ColumnStatistics {
+                        null_count: a.null_count.add(&b.null_count),
+                        min_value: a.min_value.min(&b.min_value),
+                        max_value: a.max_value.max(&b.max_value),
+                        distinct_count: a.distinct_count.add(&b.distinct_count).to_inexact(),
+                    })
+                    .collect(),
+            },
+        });
+    }
+
+    let merged = merged.unwrap_or_else(|| Statistics {
+        num_rows: Precision::Exact(0),
+        total_byte_size: Precision::Exact(0),
+        column_statistics: Statistics::unknown_column(schema),
+    });
+
+    if options.honor_valid_doc_ids && segments.iter().any(|s| s.has_valid_doc_ids()) {
+        merged.to_inexact()
+    } else {
+        merged
+    }
+}
+
+/// Parse a metadata.properties min/max value as the column's Arrow type
+fn parse_scalar(value: &str, data_type: &ArrowDataType) -> Option<ScalarValue> {
+    match data_type {
+        ArrowDataType::Int32 => value.parse().ok().map(|v| ScalarValue::Int32(Some(v))),
+        ArrowDataType::Int64 => value.parse().ok().map(|v| ScalarValue::Int64(Some(v))),
+        ArrowDataType::Float32 => value.parse().ok().map(|v| ScalarValue::Float32(Some(v))),
+        ArrowDataType::Float64 => value.parse().ok().map(|v| ScalarValue::Float64(Some(v))),
+        ArrowDataType::Utf8 => Some(ScalarValue::Utf8(Some(value.to_string()))),
+        ArrowDataType::Utf8View => Some(ScalarValue::Utf8View(Some(value.to_string()))),
+        ArrowDataType::Dictionary(key_type, value_type) => parse_scalar(value, value_type)
+            .map(|v| ScalarValue::Dictionary(key_type.clone(), Box::new(v))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scalar() {
+        assert_eq!(
+            parse_scalar("-5", &ArrowDataType::Int32),
+            Some(ScalarValue::Int32(Some(-5)))
+        );
+        assert_eq!(
+            parse_scalar("Infinity", &ArrowDataType::Float64),
+            Some(ScalarValue::Float64(Some(f64::INFINITY)))
+        );
+        assert_eq!(parse_scalar("abc", &ArrowDataType::Int64), None);
+        assert_eq!(parse_scalar("x", &ArrowDataType::Binary), None);
+    }
+}
@@ -0,0 +1,206 @@
+//! Synthetic `system` schema exposing what the catalog discovered
+//!
+//! `SELECT * FROM pinot.system.segments` returns one row per (schema, table,
+//! segment) with the segment's doc count, location, time range and index
+//! inventory.
+//! The table is rebuilt each time it is resolved: tables and their segments
+//! are listed from the metadata provider again and the segments re-opened,
+//! so it shows what the source holds now rather than what the catalog cached.
+
+use datafusion::arrow::array::{ArrayRef, Int64Builder, ListBuilder, StringBuilder, UInt64Builder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::catalog::SchemaProvider;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use pinot_segment::SegmentReader;
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::catalog::{PinotSchemaProvider, DEFAULT_SCHEMA};
+use crate::error::{Error, Result};
+
+/// Name of the schema holding the system tables
+pub const SYSTEM_SCHEMA: &str = "system";
+
+/// Name of the per-segment system table
+pub const SEGMENTS_TABLE: &str = "segments";
+
+/// Schema provider for the catalog's system tables
+#[derive(Debug)]
+pub struct PinotSystemSchemaProvider {
+    /// Table schemas by name, each served by one or more merged providers
+    schemas: Vec<(String, Vec<Arc<PinotSchemaProvider>>)>,
+}
+
+impl PinotSystemSchemaProvider {
+    /// Create a system schema describing the tables of `tables`, reported
+    /// under the [`DEFAULT_SCHEMA`] name
+    pub fn new(tables: Arc<PinotSchemaProvider>) -> Self {
+        Self::with_schemas(vec![(DEFAULT_SCHEMA.to_string(), vec![tables])])
+    }
+
+    /// Create a system schema describing the tables of several schemas
+    ///
+    /// When a schema has several providers, a table name is reported from
+    /// the first provider holding it, as the schema serves it.
+    pub fn with_schemas(schemas: Vec<(String, Vec<Arc<PinotSchemaProvider>>)>) -> Self {
+        Self { schemas }
+    }
+
+    /// Arrow schema of the `segments` table
+    pub fn segments_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("schema_name", DataType::Utf8, false),
+            Field::new("table_name", DataType::Utf8, false),
+            Field::new("segment_name", DataType::Utf8, false),
+            Field::new("path", DataType::Utf8, false),
+            Field::new("total_docs", DataType::Int64, false),
+            Field::new("num_columns", DataType::Int64, false),
+            Field::new("start_time_millis", DataType::Int64, true),
+            Field::new("end_time_millis", DataType::Int64, true),
+            Field::new("crc", DataType::UInt64, true),
+            Field::new("creation_time", DataType::Int64, true),
+            Field::new(
+                "indexes",
+                DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+                false,
+            ),
+        ]))
+    }
+
+    /// Build the `segments` batch by listing every table's segments and
+    /// opening them
+    ///
+    /// Segments are opened on the blocking thread pool. Those that fail to
+    /// open are skipped with a warning when the catalog's read options skip
+    /// unreadable segments; otherwise the error is returned.
+    async fn segments_batch(&self) -> Result<RecordBatch> {
+        let mut schema_names = StringBuilder::new();
+        let mut table_names = StringBuilder::new();
+        let mut segment_names = StringBuilder::new();
+        let mut paths = StringBuilder::new();
+        let mut total_docs = Int64Builder::new();
+        let mut num_columns = Int64Builder::new();
+        let mut start_times = Int64Builder::new();
+        let mut end_times = Int64Builder::new();
+        let mut crcs = UInt64Builder::new();
+        let mut creation_times = Int64Builder::new();
+        let mut indexes = ListBuilder::new(StringBuilder::new());
+
+        // Within a schema, a table is served by the first provider holding it
+        let mut served: Vec<(&str, String, &PinotSchemaProvider)> = Vec::new();
+        for (schema_name, providers) in &self.schemas {
+            for provider in providers {
+                for table_name in provider.list_table_names().await? {
+                    let duplicate = served.iter().any(|(schema, table, _)| {
+                        schema == schema_name && table.eq_ignore_ascii_case(&table_name)
+                    });
+                    if !duplicate {
+                        served.push((schema_name, table_name, provider));
+                    }
+                }
+            }
+        }
+
+        for (schema_name, table_name, provider) in served {
+            let skip_unreadable = provider.read_options().skip_unreadable_segments;
+            let segment_paths = provider.list_segment_paths(&table_name).await?;
+            let opened = tokio::task::spawn_blocking(move || {
+                segment_paths
+                    .into_iter()
+                    .map(|path| {
+                        let reader = SegmentReader::open(&path);
+                        (path, reader)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .map_err(|e| Error::Internal(format!("Segment open task failed: {}", e)))?;
+            for (path, reader) in opened {
+                let reader = match reader {
+                    Ok(reader) => reader,
+                    Err(e) if skip_unreadable => {
+                        tracing::warn!(
+                            table = %table_name,
+                            path = %path.display(),
+                            error = %e,
+                            "skipping unreadable segment in system table"
+                        );
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                let metadata = reader.metadata();
+                let time_range = metadata.time_range_millis();
+
+                schema_names.append_value(schema_name);
+                table_names.append_value(&table_name);
+                segment_names.append_value(&metadata.segment_name);
+                paths.append_value(path.display().to_string());
+                total_docs.append_value(metadata.total_docs as i64);
+                num_columns.append_value(metadata.columns.len() as i64);
+                start_times.append_option(time_range.map(|(start, _)| start));
+                end_times.append_option(time_range.map(|(_, end)| end));
+                crcs.append_option(metadata.crc);
+                creation_times.append_option(metadata.creation_time);
+
+                // "<column>.<index_type>", as the entries are keyed in index_map
+                let mut columns: Vec<&String> = metadata.columns.keys().collect();
+                columns.sort();
+                for column in columns {
+                    for index_type in reader.index_map().index_types_for_column(column) {
+                        indexes
+                            .values()
+                            .append_value(format!("{}.{}", column, index_type));
+                    }
+                }
+                indexes.append(true);
+            }
+        }
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(schema_names.finish()),
+            Arc::new(table_names.finish()),
+            Arc::new(segment_names.finish()),
+            Arc::new(paths.finish()),
+            Arc::new(total_docs.finish()),
+            Arc::new(num_columns.finish()),
+            Arc::new(start_times.finish()),
+            Arc::new(end_times.finish()),
+            Arc::new(crcs.finish()),
+            Arc::new(creation_times.finish()),
+            Arc::new(indexes.finish()),
+        ];
+        RecordBatch::try_new(Self::segments_schema(), columns)
+            .map_err(|e| Error::Arrow(e.to_string()))
+    }
+}
+
+#[async_trait::async_trait]
+impl SchemaProvider for PinotSystemSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        vec![SEGMENTS_TABLE.to_string()]
+    }
+
+    async fn table(&self, name: &str) -> DataFusionResult<Option<Arc<dyn TableProvider>>> {
+        if !name.eq_ignore_ascii_case(SEGMENTS_TABLE) {
+            return Ok(None);
+        }
+
+        let batch = self
+            .segments_batch()
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+        Ok(Some(Arc::new(table)))
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        name.eq_ignore_ascii_case(SEGMENTS_TABLE)
+    }
+}
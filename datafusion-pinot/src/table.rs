@@ -1,45 +1,133 @@
-use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::array::RecordBatch;
+use datafusion::arrow::datatypes::{Field, Schema, SchemaRef};
 use async_trait::async_trait;
 use datafusion::catalog::Session;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::{Column, Statistics};
 use datafusion::datasource::TableProvider;
-use datafusion::error::Result as DataFusionResult;
-use datafusion::logical_expr::{Expr, TableType};
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::{Expr, TableProviderFilterPushDown, TableType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::ExecutionPlan;
-use pinot_segment::SegmentReader;
+use futures::{stream, StreamExt};
+use pinot_segment::{SegmentMetadata, SegmentReader, TableType as PinotTableType};
 use std::any::Any;
 use std::fs;
-use std::path::Path;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::thread;
 
 use crate::error::{Error, Result};
 use crate::exec::PinotExec;
-use crate::schema::create_arrow_schema;
+use crate::options::PinotReadOptions;
+use crate::pruning::{null_filter, segment_may_match, value_range_from_filters};
+use crate::schema::{
+    create_arrow_schema, with_lowercase_names, with_nullability, with_segment_info_columns,
+    with_string_dictionaries, with_string_views,
+};
+use crate::segment::{segment_data_dir, sort_segment_paths, MockSegmentReader, SegmentSource};
+use crate::statistics::merge_segment_statistics;
+
+/// A segment left out of a table because it could not be opened
+#[derive(Debug, Clone)]
+pub struct SkippedSegment {
+    pub path: PathBuf,
+    /// Why opening the segment failed
+    pub error: String,
+}
+
+/// A column left out of a table's schema because it cannot be decoded
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExcludedColumn {
+    pub name: String,
+    /// First segment found holding the column in an unreadable form
+    pub segment_name: String,
+    /// Why the column cannot be read
+    pub reason: String,
+}
+
+/// Summary of one segment backing a table, from metadata read at open time
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentInfo {
+    pub name: String,
+    /// Segment directory; `None` for in-memory segments
+    pub path: Option<PathBuf>,
+    pub total_docs: u32,
+    /// Segment checksum, when the segment has a `creation.meta` file
+    pub crc: Option<u64>,
+    /// Creation time in epoch milliseconds, when the segment has a `creation.meta` file
+    pub creation_time: Option<i64>,
+    /// Time column range in epoch milliseconds (inclusive), if recorded
+    pub time_range_millis: Option<(i64, i64)>,
+    pub num_columns: usize,
+}
+
+/// Segments of a lazily opened table past the first, opened on first use
+#[derive(Debug, Clone)]
+struct DeferredSegments {
+    paths: Vec<PathBuf>,
+    /// Every segment of the table, or why opening the deferred ones failed
+    opened: OnceLock<std::result::Result<OpenedSegments, String>>,
+}
+
+#[derive(Debug, Clone)]
+struct OpenedSegments {
+    segments: Vec<Arc<dyn SegmentSource>>,
+    skipped_segments: Vec<SkippedSegment>,
+}
 
 /// TableProvider for Pinot table (one or more segments)
-#[derive(Debug)]
+///
+/// Clones share the open segments, so one table can be registered under
+/// several names or in several `SessionContext`s without re-opening them.
+/// Lazily deferred segments a clone has not seen opened are opened by each
+/// clone on its own.
+#[derive(Debug, Clone)]
 pub struct PinotTable {
-    segments: Vec<Arc<SegmentReader>>,
+    /// Open segments; only the first under [`PinotReadOptions::lazy_segments`]
+    segments: Vec<Arc<dyn SegmentSource>>,
+    /// Segments left out under [`PinotReadOptions::skip_unreadable_segments`]
+    skipped_segments: Vec<SkippedSegment>,
+    /// Segments not opened yet under [`PinotReadOptions::lazy_segments`]
+    deferred: Option<DeferredSegments>,
+    /// Columns left out unless [`PinotReadOptions::strict_column_types`] is set
+    excluded_columns: Vec<ExcludedColumn>,
+    /// Schema derived from the segments, before read options are applied
+    base_schema: SchemaRef,
+    /// Allow-list set by [`Self::with_columns`]; `None` exposes every column
+    columns: Option<Vec<String>>,
     schema: SchemaRef,
     _table_name: String,
+    options: PinotReadOptions,
 }
 
 impl PinotTable {
     /// Open a single Pinot segment and create a table
     pub fn open<P: AsRef<Path>>(segment_path: P) -> Result<Self> {
-        let segment_reader = SegmentReader::open(segment_path.as_ref())
-            .map_err(|e| Error::Internal(e.to_string()))?;
+        let segment_reader = SegmentReader::open(segment_path.as_ref())?;
 
         let schema = create_arrow_schema(segment_reader.metadata())?;
         let table_name = segment_reader.metadata().table_name.clone();
 
-        Ok(Self {
+        let table = Self {
             segments: vec![Arc::new(segment_reader)],
+            skipped_segments: Vec::new(),
+            deferred: None,
+            excluded_columns: Vec::new(),
+            base_schema: schema.clone(),
+            columns: None,
             schema,
             _table_name: table_name,
-        })
+            options: PinotReadOptions::default(),
+        };
+        Ok(table.with_options(PinotReadOptions::default()))
     }
 
     /// Open all segments for a Pinot table
+    ///
+    /// Each segment is read from `<segment>/v3`, or from `<segment>` itself
+    /// when it was untarred without a `v3/` level.
     pub fn open_table<P: AsRef<Path>>(table_dir: P) -> Result<Self> {
         let table_dir = table_dir.as_ref();
 
@@ -52,15 +140,9 @@ impl PinotTable {
             let entry = entry.map_err(|e| Error::Internal(e.to_string()))?;
             let path = entry.path();
 
-            // Skip non-directories and temporary directories
-            if !path.is_dir() || path.file_name().unwrap().to_str().unwrap() == "tmp" {
-                continue;
-            }
-
-            // Check if it's a valid segment (has v3 subdirectory)
-            let v3_path = path.join("v3");
-            if v3_path.exists() && v3_path.is_dir() {
-                segment_paths.push(v3_path);
+            // Temporary and incomplete segment directories are skipped
+            if path.is_dir() {
+                segment_paths.extend(segment_data_dir(&path));
             }
         }
 
@@ -71,8 +153,8 @@ impl PinotTable {
             )));
         }
 
-        // Sort for consistent ordering
-        segment_paths.sort();
+        // Sort by sequence number, as Pinot orders segments
+        sort_segment_paths(&mut segment_paths);
 
         // Use open_segments to load all segments
         let table_name = table_dir
@@ -85,12 +167,32 @@ impl PinotTable {
     /// Open segments from a list of paths
     ///
     /// This method is used by the catalog to open tables when segment paths
-    /// are provided by a MetadataProvider.
+    /// are provided by a MetadataProvider. Every segment must derive the same
+    /// schema as the first one; use [`Self::open_segments_with_options`] with
+    /// [`PinotReadOptions::union_schemas`] to accept added or dropped columns.
     ///
     /// # Arguments
     /// * `segment_paths` - Vector of paths to segment directories (typically v3 directories)
     /// * `table_name` - Name of the table (used for error messages if segments have no metadata)
     pub fn open_segments<P: AsRef<Path>>(segment_paths: &[P], table_name: &str) -> Result<Self> {
+        Self::open_segments_with_options(segment_paths, table_name, PinotReadOptions::default())
+    }
+
+    /// Open segments from a list of paths and apply `options`
+    ///
+    /// Segment schemas are reconciled according to
+    /// [`PinotReadOptions::union_schemas`]: by default a segment whose columns
+    /// or column types differ from the first segment is an error, otherwise
+    /// the table schema is the union of all segment schemas and columns
+    /// missing from some segments become nullable. Segments that fail to open
+    /// are an error unless [`PinotReadOptions::skip_unreadable_segments`] is set.
+    /// With [`PinotReadOptions::lazy_segments`] only the first segment is
+    /// opened and checked here.
+    pub fn open_segments_with_options<P: AsRef<Path>>(
+        segment_paths: &[P],
+        table_name: &str,
+        options: PinotReadOptions,
+    ) -> Result<Self> {
         if segment_paths.is_empty() {
             return Err(Error::Internal(format!(
                 "No segments provided for table '{}'",
@@ -99,46 +201,777 @@ impl PinotTable {
         }
 
         // Load all segments
-        let mut segments = Vec::new();
-        let mut schema = None;
+        let paths: Vec<&Path> = segment_paths.iter().map(|p| p.as_ref()).collect();
+        if options.lazy_segments && !options.union_schemas && paths.len() > 1 {
+            return Self::open_lazily(&paths, table_name, options);
+        }
+        let (readers, skipped_segments) =
+            open_segment_readers(&paths, table_name, options.skip_unreadable_segments, false)?;
+        let segments = readers
+            .into_iter()
+            .map(|r| Arc::new(r) as Arc<dyn SegmentSource>)
+            .collect();
+        let table = Self::from_sources(segments, table_name, options)?;
+        Ok(Self {
+            skipped_segments,
+            ..table
+        })
+    }
+
+    /// Open the first readable segment and defer the others to first use
+    fn open_lazily(paths: &[&Path], table_name: &str, options: PinotReadOptions) -> Result<Self> {
+        let mut skipped_segments = Vec::new();
+        for (idx, path) in paths.iter().enumerate() {
+            match SegmentReader::open(path) {
+                Ok(reader) => {
+                    let mut table =
+                        Self::from_sources(vec![Arc::new(reader)], table_name, options.clone())?;
+                    table.skipped_segments = skipped_segments;
+                    table.deferred = Some(DeferredSegments {
+                        paths: paths[idx + 1..].iter().map(|p| p.to_path_buf()).collect(),
+                        opened: OnceLock::new(),
+                    });
+                    // Nullability depends on the segments not opened yet
+                    return Ok(table.with_options(options));
+                }
+                Err(e) if options.skip_unreadable_segments => {
+                    tracing::warn!(
+                        "Skipping unreadable segment {:?} of table '{}': {}",
+                        path,
+                        table_name,
+                        e
+                    );
+                    skipped_segments.push(SkippedSegment {
+                        path: path.to_path_buf(),
+                        error: e.to_string(),
+                    });
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Err(Error::Internal(format!(
+            "Failed to open any of {} segments of table '{}'",
+            paths.len(),
+            table_name
+        )))
+    }
+
+    /// Every segment in scan order, opening deferred segments on first use
+    ///
+    /// Deferred segments must have the schema of the first segment. A
+    /// failure is remembered, so later calls report it without retrying.
+    pub(crate) fn all_segments(&self) -> Result<&[Arc<dyn SegmentSource>]> {
+        let Some(deferred) = &self.deferred else {
+            return Ok(&self.segments);
+        };
+        let opened = deferred
+            .opened
+            .get_or_init(|| self.open_deferred(&deferred.paths).map_err(|e| e.to_string()));
+        match opened {
+            Ok(opened) => Ok(&opened.segments),
+            Err(message) => Err(Error::Internal(format!(
+                "Failed to open segments of table '{}': {}",
+                self._table_name, message
+            ))),
+        }
+    }
+
+    /// Open `paths` and append them to the segments opened so far
+    fn open_deferred(&self, paths: &[PathBuf]) -> Result<OpenedSegments> {
+        let paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+        let (readers, skipped) = open_segment_readers(
+            &paths,
+            &self._table_name,
+            self.options.skip_unreadable_segments,
+            true,
+        )?;
+
+        let first = self.segments[0].metadata();
+        let mut segments = self.segments.clone();
+        for reader in readers {
+            let reader = reader.with_utf8_policy(self.options.utf8_policy);
+            let segment_schema = create_arrow_schema(reader.metadata())?;
+            reconcile_schemas(
+                self.base_schema.as_ref().clone(),
+                &segment_schema,
+                first,
+                reader.metadata(),
+                false,
+            )?;
+            segments.push(Arc::new(reader));
+        }
+
+        let mut skipped_segments = self.skipped_segments.clone();
+        skipped_segments.extend(skipped);
+        Ok(OpenedSegments {
+            segments,
+            skipped_segments,
+        })
+    }
+
+    /// Segments opened so far: every segment unless some are still deferred
+    fn opened_segments(&self) -> &[Arc<dyn SegmentSource>] {
+        match self.deferred.as_ref().and_then(|d| d.opened.get()) {
+            Some(Ok(opened)) => &opened.segments,
+            _ => &self.segments,
+        }
+    }
+
+    /// Whether some segments have not been opened yet
+    fn has_unopened_segments(&self) -> bool {
+        self.deferred
+            .as_ref()
+            .is_some_and(|d| !matches!(d.opened.get(), Some(Ok(_))))
+    }
+
+    /// Every segment for accessors that cannot fail, opening deferred
+    /// segments; falls back to the segments opened so far with a warning
+    fn segments_or_opened(&self) -> &[Arc<dyn SegmentSource>] {
+        self.all_segments().unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "using only the segments opened so far");
+            self.opened_segments()
+        })
+    }
+
+    /// Schema the table reports, with every field nullable under
+    /// [`PinotReadOptions::nullable_schema`] and lowercased names under
+    /// [`PinotReadOptions::lowercase_column_names`]
+    ///
+    /// `self.schema` keeps the stored names and the nullability rows are
+    /// read with, which is what scans and null filter pushdown go by.
+    fn output_schema(&self) -> SchemaRef {
+        let mut schema = self.schema.clone();
+        if self.options.nullable_schema {
+            schema = with_nullability(&schema, |_| true);
+        }
+        if self.options.lowercase_column_names {
+            schema = with_lowercase_names(&schema);
+        }
+        schema
+    }
+
+    /// `filter` with the columns of the reported schema renamed to the
+    /// stored columns of `self.schema`
+    fn stored_column_filter(&self, filter: &Expr) -> Expr {
+        if !self.options.lowercase_column_names {
+            return filter.clone();
+        }
+        let output_schema = self.output_schema();
+        filter
+            .clone()
+            .transform(|expr| match expr {
+                Expr::Column(column) => match output_schema.index_of(&column.name) {
+                    Ok(idx) => Ok(Transformed::yes(Expr::Column(Column::new(
+                        column.relation,
+                        self.schema.field(idx).name(),
+                    )))),
+                    Err(_) => Ok(Transformed::no(Expr::Column(column))),
+                },
+                expr => Ok(Transformed::no(expr)),
+            })
+            .map(|transformed| transformed.data)
+            .unwrap_or_else(|_| filter.clone())
+    }
+
+    /// Column and null-ness of an `IS [NOT] NULL` filter on a nullable column
+    ///
+    /// Only nullable fields get null value vectors applied when read, so
+    /// only their filters can be answered from the vectors.
+    fn nullable_column_filter<'a>(&self, filter: &'a Expr) -> Option<(&'a str, bool)> {
+        null_filter(filter).filter(|(column, _)| {
+            self.schema
+                .field_with_name(column)
+                .is_ok_and(|field| field.is_nullable())
+        })
+    }
+
+    /// Create a table from segments that are already open
+    ///
+    /// For embedders that locate and open segments themselves (e.g. after
+    /// fetching them from object storage). Schemas are derived and validated
+    /// exactly as in [`Self::open_segments`].
+    pub fn from_readers(readers: Vec<Arc<SegmentReader>>, table_name: &str) -> Result<Self> {
+        if readers.is_empty() {
+            return Err(Error::Internal(format!(
+                "No segments provided for table '{}'",
+                table_name
+            )));
+        }
+
+        let segments = readers
+            .into_iter()
+            .map(|r| r as Arc<dyn SegmentSource>)
+            .collect();
+        Self::from_sources(segments, table_name, PinotReadOptions::default())
+    }
+
+    /// Derive the table schema from non-empty `segments` and apply `options`
+    fn from_sources(
+        segments: Vec<Arc<dyn SegmentSource>>,
+        table_name: &str,
+        options: PinotReadOptions,
+    ) -> Result<Self> {
+        let mut schema: Option<Schema> = None;
         let mut actual_table_name = table_name.to_string();
 
-        for segment_path in segment_paths {
-            let segment_reader = SegmentReader::open(segment_path.as_ref()).map_err(|e| {
-                Error::Internal(format!(
-                    "Failed to open segment {:?}: {}",
-                    segment_path.as_ref(),
-                    e
-                ))
-            })?;
-
-            if schema.is_none() {
-                schema = Some(create_arrow_schema(segment_reader.metadata())?);
-                actual_table_name = segment_reader.metadata().table_name.clone();
+        for segment in &segments {
+            let segment_schema = create_arrow_schema(segment.metadata())?;
+            schema = Some(match schema {
+                None => {
+                    actual_table_name = segment.metadata().table_name.clone();
+                    segment_schema.as_ref().clone()
+                }
+                Some(schema) => reconcile_schemas(
+                    schema,
+                    &segment_schema,
+                    segments[0].metadata(),
+                    segment.metadata(),
+                    options.union_schemas,
+                )?,
+            });
+        }
+
+        let schema = Arc::new(schema.unwrap());
+        let table = Self {
+            segments,
+            skipped_segments: Vec::new(),
+            deferred: None,
+            excluded_columns: Vec::new(),
+            base_schema: schema.clone(),
+            columns: None,
+            schema,
+            _table_name: actual_table_name,
+            options: PinotReadOptions::default(),
+        };
+        Ok(table.with_options(options))
+    }
+
+    /// Create a table backed by in-memory Arrow data instead of segment files
+    ///
+    /// Each batch becomes one [`MockSegmentReader`] segment, so scans are
+    /// partitioned per batch exactly like per-segment scans of a real table.
+    /// Every batch must contain the columns of `schema` with the same types.
+    pub fn from_record_batches(
+        schema: SchemaRef,
+        batches: Vec<RecordBatch>,
+        table_name: &str,
+    ) -> Result<Self> {
+        if batches.is_empty() {
+            return Err(Error::Internal(format!(
+                "No record batches provided for table '{}'",
+                table_name
+            )));
+        }
+
+        let mut segments: Vec<Arc<dyn SegmentSource>> = Vec::with_capacity(batches.len());
+        for (idx, batch) in batches.into_iter().enumerate() {
+            for field in schema.fields() {
+                let matches = batch
+                    .schema()
+                    .field_with_name(field.name())
+                    .is_ok_and(|f| f.data_type() == field.data_type());
+                if !matches {
+                    return Err(Error::Internal(format!(
+                        "Batch {} of table '{}' has no column {} of type {}",
+                        idx,
+                        table_name,
+                        field.name(),
+                        field.data_type()
+                    )));
+                }
             }
 
-            segments.push(Arc::new(segment_reader));
+            let segment_name = format!("{}_{}", table_name, idx);
+            segments.push(Arc::new(MockSegmentReader::new(batch, table_name, &segment_name)?));
         }
 
         Ok(Self {
             segments,
-            schema: schema.unwrap(),
-            _table_name: actual_table_name,
+            skipped_segments: Vec::new(),
+            deferred: None,
+            excluded_columns: Vec::new(),
+            base_schema: schema.clone(),
+            columns: None,
+            schema,
+            _table_name: table_name.to_string(),
+            options: PinotReadOptions::default(),
         })
     }
 
+    /// Apply read options, replacing any set before
+    ///
+    /// Dictionary arrays, string view arrays, null substitution, segment info
+    /// columns and strict column types change the table schema, so set options before
+    /// registering the table.
+    pub fn with_options(mut self, options: PinotReadOptions) -> Self {
+        if options.utf8_policy != self.options.utf8_policy {
+            let with_policy = |segments: &[Arc<dyn SegmentSource>]| -> Vec<Arc<dyn SegmentSource>> {
+                segments
+                    .iter()
+                    .map(|s| match s.as_any().downcast_ref::<SegmentReader>() {
+                        Some(reader) => Arc::new(reader.clone().with_utf8_policy(options.utf8_policy))
+                            as Arc<dyn SegmentSource>,
+                        None => s.clone(),
+                    })
+                    .collect()
+            };
+            self.segments = with_policy(&self.segments);
+            if let Some(Some(Ok(opened))) = self.deferred.as_mut().map(|d| d.opened.get_mut()) {
+                opened.segments = with_policy(&opened.segments);
+            }
+        }
+
+        let allowed_schema = match &self.columns {
+            Some(columns) => Schema::new(
+                self.base_schema
+                    .fields()
+                    .iter()
+                    .filter(|f| columns.contains(f.name()))
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            ),
+            None => self.base_schema.as_ref().clone(),
+        };
+        let excluded_columns = if options.strict_column_types {
+            Vec::new()
+        } else {
+            unreadable_columns(self.opened_segments(), &allowed_schema)
+        };
+        for column in &excluded_columns {
+            if !self.excluded_columns.contains(column) {
+                tracing::warn!(
+                    column = %column.name,
+                    segment = %column.segment_name,
+                    reason = %column.reason,
+                    "excluding unreadable column from table schema"
+                );
+            }
+        }
+        let schema = Schema::new(
+            allowed_schema
+                .fields()
+                .iter()
+                .filter(|f| !excluded_columns.iter().any(|c| &c.name == f.name()))
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+        self.excluded_columns = excluded_columns;
+
+        // Which columns are dictionary-encoded is taken from the first
+        // segment (every constructor rejects an empty segment list)
+        let schema = if options.dictionary_arrays {
+            with_string_dictionaries(&schema, self.segments[0].metadata())
+        } else {
+            Arc::new(schema)
+        };
+        let schema = if options.string_view_arrays {
+            with_string_views(&schema)
+        } else {
+            schema
+        };
+        // Segments not opened yet may have a null value vector for any column
+        let segments = self.opened_segments();
+        let unopened = self.has_unopened_segments();
+        let schema = with_nullability(&schema, |field| {
+            field.is_nullable()
+                || (options.substitute_nulls
+                    && (unopened || segments.iter().any(|s| s.has_null_value_vector(field.name()))))
+        });
+        self.schema = if options.segment_info_columns {
+            with_segment_info_columns(&schema)
+        } else {
+            schema
+        };
+
+        self.options = options;
+        self
+    }
+
+    /// Expose only `columns` of the table, whatever the query
+    ///
+    /// Unlike a query projection this changes the table schema: other
+    /// columns cannot be selected and are never decoded, which keeps wide
+    /// tables manageable and hides columns that should not be queried.
+    /// Columns keep their table order. Calling it again replaces the list.
+    ///
+    /// # Errors
+    /// Returns error if a listed column is not a column of the table.
+    pub fn with_columns(mut self, columns: &[&str]) -> Result<Self> {
+        if let Some(missing) = columns
+            .iter()
+            .find(|column| self.base_schema.field_with_name(column).is_err())
+        {
+            return Err(Error::Internal(format!(
+                "Column '{}' not found in table '{}'",
+                missing, self._table_name
+            )));
+        }
+        self.columns = Some(columns.iter().map(|c| c.to_string()).collect());
+        let options = self.options.clone();
+        Ok(self.with_options(options))
+    }
+
+    /// Emit dictionary-encoded STRING columns as Arrow `DictionaryArray`s
+    ///
+    /// Keeps Pinot's compact dictionary encoding through the query instead of
+    /// decoding a string per row. Off by default because not every DataFusion
+    /// operator handles dictionary arrays efficiently. Shorthand for setting
+    /// [`PinotReadOptions::dictionary_arrays`].
+    pub fn with_dictionary_arrays(self, enabled: bool) -> Self {
+        let options = self.options.clone().with_dictionary_arrays(enabled);
+        self.with_options(options)
+    }
+
+    /// Emit STRING columns as Arrow `StringViewArray`s (`Utf8View`)
+    ///
+    /// RAW columns are then read without copying or allocating per value:
+    /// each view points into the decompressed chunk holding it. Shorthand
+    /// for setting [`PinotReadOptions::string_view_arrays`].
+    pub fn with_string_view_arrays(self, enabled: bool) -> Self {
+        let options = self.options.clone().with_string_view_arrays(enabled);
+        self.with_options(options)
+    }
+
+    /// Add `__segment_name__` and `__segment_index__` columns to the scan
+    /// schema
+    ///
+    /// Each row then tells which segment it was read from, e.g.
+    /// `SELECT __segment_name__, COUNT(*) FROM t GROUP BY __segment_name__`.
+    /// The index is the scan partition, i.e. the segment's position among the
+    /// segments left after pruning. With
+    /// [`SegmentInfoColumnsRule`](crate::SegmentInfoColumnsRule) registered,
+    /// `SELECT *` and `DESCRIBE` leave the columns out, so they are only
+    /// returned when named. Shorthand for setting
+    /// [`PinotReadOptions::segment_info_columns`].
+    pub fn with_segment_info_columns(self, enabled: bool) -> Self {
+        let options = self.options.clone().with_segment_info_columns(enabled);
+        self.with_options(options)
+    }
+
+    /// Read options in effect for this table
+    pub fn options(&self) -> &PinotReadOptions {
+        &self.options
+    }
+
     /// Get the number of segments
+    ///
+    /// Counts segments not opened yet by their paths, without opening them.
     pub fn num_segments(&self) -> usize {
-        self.segments.len()
+        match &self.deferred {
+            Some(deferred) if self.has_unopened_segments() => {
+                self.segments.len() + deferred.paths.len()
+            }
+            _ => self.opened_segments().len(),
+        }
+    }
+
+    /// Segments of the table in scan order
+    ///
+    /// On-disk segments are [`SegmentReader`]s and can be recovered with
+    /// `segment.as_any().downcast_ref::<SegmentReader>()`. Opens segments
+    /// deferred by [`PinotReadOptions::lazy_segments`]; if that fails, only
+    /// the segments opened so far are returned and scans report the error.
+    /// The same holds for the other per-segment accessors.
+    pub fn segments(&self) -> &[Arc<dyn SegmentSource>] {
+        self.segments_or_opened()
+    }
+
+    /// Name of each segment in scan order
+    pub fn segment_names(&self) -> Vec<&str> {
+        self.segments_or_opened()
+            .iter()
+            .map(|s| s.metadata().segment_name.as_str())
+            .collect()
+    }
+
+    /// Number of documents in each segment, as `(segment_name, total_docs)` in scan order
+    pub fn segment_doc_counts(&self) -> Vec<(&str, u32)> {
+        self.segments_or_opened()
+            .iter()
+            .map(|s| (s.metadata().segment_name.as_str(), s.metadata().total_docs))
+            .collect()
+    }
+
+    /// Name, path, size and time range of each segment in scan order
+    ///
+    /// Assembled from metadata loaded when the segments were opened; no I/O
+    /// beyond opening deferred segments.
+    pub fn segment_infos(&self) -> Vec<SegmentInfo> {
+        self.segments_or_opened()
+            .iter()
+            .map(|s| {
+                let metadata = s.metadata();
+                SegmentInfo {
+                    name: metadata.segment_name.clone(),
+                    path: s.path().map(Path::to_path_buf),
+                    total_docs: metadata.total_docs,
+                    crc: metadata.crc,
+                    creation_time: metadata.creation_time,
+                    time_range_millis: metadata.time_range_millis(),
+                    num_columns: metadata.columns.len(),
+                }
+            })
+            .collect()
+    }
+
+    /// Segments that failed to open and were left out of the table
+    ///
+    /// Always empty unless the table was opened with
+    /// [`PinotReadOptions::skip_unreadable_segments`]. Segments deferred by
+    /// [`PinotReadOptions::lazy_segments`] are only listed once opened.
+    pub fn skipped_segments(&self) -> &[SkippedSegment] {
+        match self.deferred.as_ref().and_then(|d| d.opened.get()) {
+            Some(Ok(opened)) => &opened.skipped_segments,
+            _ => &self.skipped_segments,
+        }
+    }
+
+    /// Columns left out of the schema because some segment cannot decode them
+    ///
+    /// Querying such a column fails at planning as an unknown column; the
+    /// reason recorded here says why it was excluded. Always empty with
+    /// [`PinotReadOptions::strict_column_types`].
+    pub fn excluded_columns(&self) -> &[ExcludedColumn] {
+        &self.excluded_columns
     }
 
     /// Get total number of documents across all segments
     pub fn total_docs(&self) -> u64 {
-        self.segments
+        self.segments_or_opened()
             .iter()
             .map(|s| s.metadata().total_docs as u64)
             .sum()
     }
+
+    /// Estimated size in bytes of the table's decoded values, summed over
+    /// its segments
+    ///
+    /// Reported to DataFusion as the inexact `total_byte_size` of the
+    /// table's statistics, which its join planning compares.
+    pub fn estimated_size_bytes(&self) -> u64 {
+        self.segment_sizes().iter().sum()
+    }
+
+    /// Estimated size in bytes of each segment's decoded values, in scan order
+    pub fn segment_sizes(&self) -> Vec<u64> {
+        self.segments_or_opened()
+            .iter()
+            .map(|s| s.estimate_size_bytes())
+            .collect()
+    }
+
+    /// OFFLINE/REALTIME type of each segment, as `(segment_name, type)` in scan order
+    ///
+    /// The type is inferred from table and segment names (see
+    /// [`SegmentMetadata::table_type`](pinot_segment::SegmentMetadata::table_type));
+    /// `None` when the names don't say.
+    pub fn segment_table_types(&self) -> Vec<(&str, Option<PinotTableType>)> {
+        self.segments_or_opened()
+            .iter()
+            .map(|s| (s.metadata().segment_name.as_str(), s.metadata().table_type()))
+            .collect()
+    }
+
+    /// Scan every column of every segment as one stream, segment after segment
+    ///
+    /// Rows come in scan order (segments in [`Self::segment_names`] order,
+    /// each in doc id order) with the table's read options applied. Each
+    /// segment is decoded only once the previous one is exhausted, so this
+    /// suits single consumers such as writing one output file without
+    /// planning a query that coalesces the per-segment partitions.
+    pub fn execute_merged(&self, context: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let segments = match self.all_segments() {
+            Ok(segments) => segments.to_vec(),
+            Err(e) => {
+                let error = stream::once(async move { Err(DataFusionError::External(Box::new(e))) });
+                return Box::pin(RecordBatchStreamAdapter::new(self.output_schema(), error));
+            }
+        };
+        let num_segments = segments.len();
+        let exec = PinotExec::new(segments, self.schema.clone(), None)
+            .with_options(self.options.clone());
+        let schema = exec.schema();
+        let batches = stream::iter(0..num_segments)
+            .map(move |partition| match exec.execute(partition, context.clone()) {
+                Ok(partition_stream) => partition_stream.boxed(),
+                Err(e) => stream::once(async { Err(e) }).boxed(),
+            })
+            .flatten();
+        Box::pin(RecordBatchStreamAdapter::new(schema, batches))
+    }
+
+    /// Whether any segment is known to be REALTIME, and so possibly still consuming
+    pub fn has_realtime_segments(&self) -> bool {
+        self.segments_or_opened()
+            .iter()
+            .any(|s| s.metadata().table_type() == Some(PinotTableType::Realtime))
+    }
+}
+
+/// Columns of `schema` that some segment holds but cannot decode
+pub(crate) fn unreadable_columns(segments: &[Arc<dyn SegmentSource>], schema: &Schema) -> Vec<ExcludedColumn> {
+    schema
+        .fields()
+        .iter()
+        .filter_map(|field| {
+            segments
+                .iter()
+                .filter(|s| s.metadata().columns.contains_key(field.name()))
+                .find_map(|s| {
+                    s.check_column_readable(field.name()).err().map(|e| ExcludedColumn {
+                        name: field.name().clone(),
+                        segment_name: s.metadata().segment_name.clone(),
+                        reason: e.to_string(),
+                    })
+                })
+        })
+        .collect()
+}
+
+/// Open every segment directory, spreading the work over the available cores
+///
+/// Opening parses metadata.properties and index_map, which dominates the
+/// first query against tables with many segments. Readers are returned in
+/// the order of `paths`; if any segment fails to open, the error lists all
+/// of the failures rather than only the first. With `skip_unreadable` the
+/// failures are logged and returned instead, unless no segment opened and
+/// the table has no segment open already (`have_open_segment`).
+fn open_segment_readers(
+    paths: &[&Path],
+    table_name: &str,
+    skip_unreadable: bool,
+    have_open_segment: bool,
+) -> Result<(Vec<SegmentReader>, Vec<SkippedSegment>)> {
+    let num_threads = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(paths.len())
+        .max(1);
+    let chunk_size = paths.len().div_ceil(num_threads).max(1);
+
+    let open_chunk = |chunk: &[&Path]| -> Vec<std::result::Result<SegmentReader, SkippedSegment>> {
+        chunk
+            .iter()
+            .map(|path| {
+                SegmentReader::open(path).map_err(|e| SkippedSegment {
+                    path: path.to_path_buf(),
+                    error: e.to_string(),
+                })
+            })
+            .collect()
+    };
+
+    let results: Vec<std::result::Result<SegmentReader, SkippedSegment>> = if num_threads == 1 {
+        open_chunk(paths)
+    } else {
+        thread::scope(|scope| {
+            let handles: Vec<_> = paths
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || open_chunk(chunk)))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("segment open thread panicked"))
+                .collect()
+        })
+    };
+
+    let mut readers = Vec::with_capacity(results.len());
+    let mut failures = Vec::new();
+    for result in results {
+        match result {
+            Ok(reader) => readers.push(reader),
+            Err(failure) => failures.push(failure),
+        }
+    }
+
+    if failures.is_empty() {
+        return Ok((readers, failures));
+    }
+    if skip_unreadable && (have_open_segment || !readers.is_empty()) {
+        for failure in &failures {
+            tracing::warn!(
+                "Skipping unreadable segment {:?} of table '{}': {}",
+                failure.path,
+                table_name,
+                failure.error
+            );
+        }
+        return Ok((readers, failures));
+    }
+
+    let messages: Vec<String> = failures
+        .iter()
+        .map(|f| format!("{:?}: {}", f.path, f.error))
+        .collect();
+    Err(Error::Internal(format!(
+        "Failed to open {} of {} segments of table '{}': {}",
+        failures.len(),
+        paths.len(),
+        table_name,
+        messages.join("; ")
+    )))
+}
+
+/// Fold `segment_schema` into the schema accumulated from earlier segments
+///
+/// Columns are matched by name since each segment's metadata orders them
+/// differently. A column whose type changed is always an error; an added or
+/// dropped column is an error unless `union` is set, in which case it is kept
+/// and made nullable.
+fn reconcile_schemas(
+    schema: Schema,
+    segment_schema: &Schema,
+    first: &SegmentMetadata,
+    segment: &SegmentMetadata,
+    union: bool,
+) -> Result<Schema> {
+    let mut fields: Vec<Field> = Vec::with_capacity(schema.fields().len());
+    for field in schema.fields() {
+        match segment_schema.field_with_name(field.name()) {
+            Ok(other) if other.data_type() != field.data_type() => {
+                return Err(Error::Internal(format!(
+                    "Schema mismatch in segment '{}': column '{}' is {} but {} in earlier segments",
+                    segment.segment_name,
+                    field.name(),
+                    other.data_type(),
+                    field.data_type()
+                )));
+            }
+            Ok(other) => fields.push(
+                field
+                    .as_ref()
+                    .clone()
+                    .with_nullable(field.is_nullable() || other.is_nullable()),
+            ),
+            Err(_) if union => fields.push(field.as_ref().clone().with_nullable(true)),
+            Err(_) => {
+                return Err(Error::Internal(format!(
+                    "Schema mismatch in segment '{}': column '{}' of segment '{}' is missing",
+                    segment.segment_name,
+                    field.name(),
+                    first.segment_name
+                )));
+            }
+        }
+    }
+
+    for field in segment_schema.fields() {
+        if schema.field_with_name(field.name()).is_ok() {
+            continue;
+        }
+        if !union {
+            return Err(Error::Internal(format!(
+                "Schema mismatch in segment '{}': column '{}' does not exist in segment '{}'",
+                segment.segment_name,
+                field.name(),
+                first.segment_name
+            )));
+        }
+        fields.push(field.as_ref().clone().with_nullable(true));
+    }
+
+    Ok(Schema::new(fields))
 }
 
 #[async_trait]
@@ -148,25 +981,88 @@ impl TableProvider for PinotTable {
     }
 
     fn schema(&self) -> SchemaRef {
-        self.schema.clone()
+        self.output_schema()
     }
 
     fn table_type(&self) -> TableType {
         TableType::Base
     }
 
+    fn statistics(&self) -> Option<Statistics> {
+        Some(merge_segment_statistics(
+            self.segments_or_opened(),
+            &self.schema,
+            &self.options,
+        ))
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> DataFusionResult<Vec<TableProviderFilterPushDown>> {
+        // IS [NOT] NULL on a nullable column is answered exactly through
+        // null value vectors; other filters are only used to prune segments
+        // and documents, and DataFusion still evaluates them against the rows
+        // we return
+        Ok(filters
+            .iter()
+            .map(|filter| {
+                let filter = self.stored_column_filter(filter);
+                match self.nullable_column_filter(&filter) {
+                    Some(_) => TableProviderFilterPushDown::Exact,
+                    None => TableProviderFilterPushDown::Inexact,
+                }
+            })
+            .collect())
+    }
+
     async fn scan(
         &self,
         _state: &dyn Session,
         projection: Option<&Vec<usize>>,
-        _filters: &[Expr],
-        _limit: Option<usize>,
+        filters: &[Expr],
+        limit: Option<usize>,
     ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(PinotExec::new(
-            self.segments.clone(),
-            self.schema.clone(),
-            projection.cloned(),
-        )))
+        let filters: Vec<Expr> = filters
+            .iter()
+            .map(|filter| self.stored_column_filter(filter))
+            .collect();
+        let filters = filters.as_slice();
+        let all_segments = self
+            .all_segments()
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let segments: Vec<Arc<dyn SegmentSource>> = all_segments
+            .iter()
+            .filter(|s| segment_may_match(s.metadata(), filters))
+            .cloned()
+            .collect();
+        let pruned = all_segments.len() - segments.len();
+
+        let range_filters = self
+            .schema
+            .fields()
+            .iter()
+            .filter(|field| field.data_type().is_numeric())
+            .filter_map(|field| {
+                value_range_from_filters(filters, field.name())
+                    .map(|range| (field.name().clone(), range))
+            })
+            .collect();
+
+        let null_filters = filters
+            .iter()
+            .filter_map(|filter| self.nullable_column_filter(filter))
+            .map(|(column, is_null)| (column.to_string(), is_null))
+            .collect();
+
+        Ok(Arc::new(
+            PinotExec::new(segments, self.schema.clone(), projection.cloned())
+                .with_options(self.options.clone())
+                .with_range_filters(range_filters)
+                .with_null_filters(null_filters)
+                .with_limit(limit)
+                .with_pruned_segments(pruned),
+        ))
     }
 }
 
@@ -174,41 +1070,33 @@ impl TableProvider for PinotTable {
 mod tests {
     use super::*;
 
-    const SEGMENT_DIR: &str = "/tmp/pinot/quickstart/PinotServerDataDir0/baseballStats_OFFLINE/baseballStats_OFFLINE_0_e40936cc-16f8-490e-a85f-bc61a9abee66/v3";
-    const TABLE_DIR: &str = "/tmp/pinot/quickstart/PinotServerDataDir0/baseballStats_OFFLINE";
+    use pinot_segment::testutil::write_baseball_stats;
 
     #[test]
     fn test_pinot_table_open() {
-        if !Path::new(SEGMENT_DIR).exists() {
-            println!("Skipping test: segment directory not found");
-            return;
-        }
+        let dir = tempfile::tempdir().unwrap();
+        write_baseball_stats(dir.path(), 100).unwrap();
 
-        let table = PinotTable::open(SEGMENT_DIR).expect("Failed to open table");
+        let table = PinotTable::open(dir.path()).expect("Failed to open table");
 
-        // Verify schema
         let schema = table.schema();
-        assert!(schema.fields().len() > 0, "Schema should have fields");
-
-        // Check some expected columns
         assert!(schema.field_with_name("playerID").is_ok());
         assert!(schema.field_with_name("teamID").is_ok());
         assert!(schema.field_with_name("hits").is_ok());
+        assert_eq!(table.total_docs(), 100);
     }
 
     #[test]
     fn test_open_table_multi_segment() {
-        if !Path::new(TABLE_DIR).exists() {
-            println!("Skipping test: table directory not found");
-            return;
+        let dir = tempfile::tempdir().unwrap();
+        let table_dir = dir.path().join("baseballStats_OFFLINE");
+        for segment in ["baseballStats_OFFLINE_0", "baseballStats_OFFLINE_1"] {
+            write_baseball_stats(&table_dir.join(segment).join("v3"), 100).unwrap();
         }
 
-        let table = PinotTable::open_table(TABLE_DIR).expect("Failed to open table");
-
-        assert!(table.num_segments() >= 1, "Should have at least one segment");
-        assert!(table.total_docs() > 0, "Should have documents");
+        let table = PinotTable::open_table(&table_dir).expect("Failed to open table");
 
-        println!("Loaded {} segments with {} total docs",
-                 table.num_segments(), table.total_docs());
+        assert_eq!(table.num_segments(), 2);
+        assert_eq!(table.total_docs(), 200);
     }
 }
@@ -0,0 +1,140 @@
+//! Allocation behaviour of the scan path, measured with a counting global allocator
+//!
+//! Kept in its own test binary so no other test allocates concurrently.
+
+use datafusion::arrow::array::{Array, StringArray};
+use datafusion_pinot::exec::PinotExec;
+use datafusion_pinot::schema::{create_arrow_schema, create_projected_schema};
+use pinot_segment::testutil::SegmentBuilder;
+use pinot_segment::SegmentReader;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            let current = CURRENT_BYTES.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            CURRENT_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+            let current = CURRENT_BYTES.fetch_add(new_size, Ordering::Relaxed) + new_size;
+            PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Run `f`, returning its result plus (allocation count, peak bytes above baseline)
+fn measure<T>(f: impl FnOnce() -> T) -> (T, usize, usize) {
+    let baseline = CURRENT_BYTES.load(Ordering::Relaxed);
+    PEAK_BYTES.store(baseline, Ordering::Relaxed);
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+
+    let result = f();
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    let peak = PEAK_BYTES.load(Ordering::Relaxed).saturating_sub(baseline);
+    (result, allocations, peak)
+}
+
+#[test]
+fn test_builder_scan_allocates_less_than_vec_path() {
+    const NUM_ROWS: usize = 50_000;
+
+    let dir = tempfile::tempdir().unwrap();
+    let teams: Vec<String> = (0..NUM_ROWS).map(|i| format!("team_{:02}", i % 30)).collect();
+    SegmentBuilder::new("allocTable", "allocTable_OFFLINE_0")
+        .string_column("team", teams.iter().map(|s| s.as_str()).collect())
+        .int_column("hits", (0..NUM_ROWS as i32).map(|i| i % 250).collect())
+        .write(dir.path())
+        .unwrap();
+
+    let reader = SegmentReader::open(dir.path()).unwrap();
+    let schema = create_arrow_schema(reader.metadata()).unwrap();
+    let team_idx = schema.index_of("team").unwrap();
+    let projected = create_projected_schema(&schema, &[team_idx]).unwrap();
+
+    // Previous approach: decode into Vec<String>, then copy into a StringArray
+    let (vec_array, vec_allocations, vec_peak) =
+        measure(|| StringArray::from(reader.read_string_column("team").unwrap()));
+
+    // Builder approach used by PinotExec
+    let (batch, builder_allocations, builder_peak) = measure(|| {
+        PinotExec::create_batch_with_builders(&reader, &projected).unwrap()
+    });
+
+    assert_eq!(batch.num_rows(), NUM_ROWS);
+    let builder_array = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(builder_array.len(), vec_array.len());
+    assert_eq!(builder_array, &vec_array);
+
+    assert!(
+        builder_allocations * 10 < vec_allocations,
+        "builder path should avoid per-row allocations: builder {} allocations, Vec {} allocations",
+        builder_allocations,
+        vec_allocations
+    );
+    assert!(
+        builder_peak < vec_peak,
+        "builder path should lower peak memory: builder {} peak bytes, Vec {} peak bytes",
+        builder_peak,
+        vec_peak
+    );
+}
+
+#[test]
+fn test_windowed_scan_reads_only_the_window() {
+    const NUM_ROWS: usize = 200_000;
+
+    let dir = tempfile::tempdir().unwrap();
+    let hits: Vec<i32> = (0..NUM_ROWS as i32).map(|i| i % 100).collect();
+    SegmentBuilder::new("allocTable", "allocTable_OFFLINE_0")
+        .int_column("hits", hits.clone())
+        .write(dir.path())
+        .unwrap();
+
+    let reader = SegmentReader::open(dir.path()).unwrap();
+    let schema = create_arrow_schema(reader.metadata()).unwrap();
+
+    let (full, _, full_peak) =
+        measure(|| PinotExec::create_batch_with_builders(&reader, &schema).unwrap());
+    let (window, _, window_peak) =
+        measure(|| PinotExec::create_batch_in_range(&reader, &schema, 5000..5010).unwrap());
+
+    assert_eq!(full.num_rows(), NUM_ROWS);
+    assert_eq!(window, full.slice(5000, 10));
+
+    assert!(
+        window_peak * 100 < full_peak,
+        "windowed read should skip most of the forward index ({} vs {} peak bytes)",
+        window_peak,
+        full_peak
+    );
+}
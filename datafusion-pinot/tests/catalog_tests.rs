@@ -1,96 +1,1363 @@
+use datafusion::arrow::array::{Array, Int64Array, ListArray, StringArray};
+use datafusion::catalog::{CatalogProvider, SchemaProvider};
 use datafusion::prelude::*;
-use datafusion_pinot::PinotCatalog;
+use datafusion_pinot::catalog::PinotSchemaProvider;
+use datafusion_pinot::{
+    FileSystemMetadataProvider, MetadataProvider, PinotCatalog, PinotTable, StaticMetadataProvider,
+};
+use pinot_segment::testutil::{write_baseball_stats, SegmentBuilder, BASEBALL_STATS_TEAMS};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
-const DATA_DIR: &str = "/tmp/pinot/quickstart/PinotServerDataDir0";
+/// Data directory laid out like the quickstart's: `baseballStats` with
+/// 100 rows and `dimBaseballTeams` with the eight teams
+fn quickstart_dir() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    write_baseball_stats(
+        &dir.path()
+            .join("baseballStats_OFFLINE/baseballStats_OFFLINE_0/v3"),
+        100,
+    )
+    .unwrap();
+    SegmentBuilder::new("dimBaseballTeams", "dimBaseballTeams_OFFLINE_0")
+        .string_column("teamID", BASEBALL_STATS_TEAMS.to_vec())
+        .write(
+            &dir.path()
+                .join("dimBaseballTeams_OFFLINE/dimBaseballTeams_OFFLINE_0/v3"),
+        )
+        .unwrap();
+    dir
+}
 
 #[tokio::test]
 async fn test_catalog_table_discovery() {
-    if !Path::new(DATA_DIR).exists() {
-        println!("Skipping test: data directory not found");
-        return;
-    }
-
+    let dir = quickstart_dir();
     let ctx = SessionContext::new();
 
     // Register Pinot catalog
-    let catalog = PinotCatalog::new(DATA_DIR).expect("Failed to create catalog");
+    let catalog = PinotCatalog::new(dir.path()).expect("Failed to create catalog");
     ctx.register_catalog("pinot", Arc::new(catalog));
 
     // Query using fully qualified table name (use quotes for case-sensitive names)
-    let df = ctx
-        .sql("SELECT COUNT(*) FROM pinot.default.\"baseballStats\"")
+    assert_eq!(
+        count_rows(&ctx, "pinot.default.\"baseballStats\"").await,
+        100
+    );
+}
+
+#[tokio::test]
+async fn test_catalog_multiple_tables() {
+    let dir = quickstart_dir();
+    let ctx = SessionContext::new();
+    let catalog = PinotCatalog::new(dir.path()).expect("Failed to create catalog");
+    ctx.register_catalog("pinot", Arc::new(catalog));
+
+    assert_eq!(
+        count_rows(&ctx, "pinot.default.\"baseballStats\"").await,
+        100
+    );
+    assert_eq!(
+        count_rows(&ctx, "pinot.default.\"dimBaseballTeams\"").await,
+        8
+    );
+}
+
+#[tokio::test]
+async fn test_catalog_select_query() {
+    let dir = quickstart_dir();
+    let ctx = SessionContext::new();
+    let catalog = PinotCatalog::new(dir.path()).expect("Failed to create catalog");
+    ctx.register_catalog("pinot", Arc::new(catalog));
+
+    // Execute SELECT with projection
+    let results = ctx
+        .sql("SELECT \"playerID\", \"hits\" FROM pinot.default.\"baseballStats\" LIMIT 10")
+        .await
+        .expect("Failed to create DataFrame")
+        .collect()
+        .await
+        .expect("Failed to collect results");
+
+    assert_eq!(results[0].num_columns(), 2);
+    let num_rows: usize = results.iter().map(|batch| batch.num_rows()).sum();
+    assert_eq!(num_rows, 10);
+}
+
+#[tokio::test]
+async fn test_system_segments_table() {
+    let dir = tempfile::tempdir().unwrap();
+    let events_dir = dir.path().join("events_OFFLINE");
+    for (segment_name, ids) in [("events_0", vec![1, 2, 3]), ("events_1", vec![4, 5])] {
+        SegmentBuilder::new("events", segment_name)
+            .int_column("id", ids)
+            .property("segment.time.column.name", "id")
+            .property("segment.start.time", "1")
+            .property("segment.end.time", "5")
+            .property("segment.time.unit", "MILLISECONDS")
+            .range_index("id", 2)
+            .write(&events_dir.join(segment_name).join("v3"))
+            .unwrap();
+    }
+    SegmentBuilder::new("users", "users_0")
+        .string_column("name", vec!["a"])
+        .write(&dir.path().join("users_OFFLINE").join("users_0").join("v3"))
+        .unwrap();
+
+    let catalog = PinotCatalog::new(dir.path()).unwrap();
+    assert_eq!(catalog.schema_names(), vec!["default", "system"]);
+    assert_eq!(
+        catalog.schema("system").unwrap().table_names(),
+        vec!["segments"]
+    );
+
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    let batches = ctx
+        .sql(
+            "SELECT table_name, segment_name, path, total_docs, start_time_millis, indexes \
+             FROM pinot.system.segments ORDER BY segment_name",
+        )
+        .await
+        .unwrap()
+        .collect()
         .await
-        .expect("Failed to create DataFrame");
+        .unwrap();
+    assert_eq!(batches.len(), 1);
+    let batch = &batches[0];
+    assert_eq!(batch.num_rows(), 3);
 
-    let results = df.collect().await.expect("Failed to collect results");
+    let strings = |i: usize| {
+        batch
+            .column(i)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+    };
+    assert_eq!(strings(0).value(0), "events");
+    assert_eq!(strings(0).value(2), "users");
+    assert_eq!(strings(1).value(1), "events_1");
+    assert!(strings(2).value(2).ends_with("v3"));
 
-    println!("Catalog query results: {:?}", results[0]);
+    let docs = batch
+        .column(3)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(docs.values(), &[3, 2, 1]);
+    let start_times = batch
+        .column(4)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(start_times.value(0), 1);
+    assert!(start_times.is_null(2));
 
-    assert_eq!(results.len(), 1);
-    assert_eq!(results[0].num_rows(), 1);
+    let indexes = batch
+        .column(5)
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .unwrap();
+    let events_indexes = indexes.value(0);
+    let events_indexes = events_indexes
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    let events_indexes: Vec<&str> = events_indexes.iter().flatten().collect();
+    assert!(
+        events_indexes.contains(&"id.range_index"),
+        "{:?}",
+        events_indexes
+    );
+    assert!(
+        events_indexes.contains(&"id.forward_index"),
+        "{:?}",
+        events_indexes
+    );
 
-    println!("✓ Catalog-based table discovery successful");
+    // Rows can be filtered like any other table
+    let batches = ctx
+        .sql("SELECT sum(total_docs) FROM pinot.system.segments WHERE table_name = 'events'")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let total = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(total.value(0), 5);
+
+    // A segment flushed after the table was opened shows up on the next scan
+    assert_eq!(count_rows(&ctx, "pinot.default.events").await, 5);
+    SegmentBuilder::new("events", "events_2")
+        .int_column("id", vec![6, 7, 8, 9])
+        .write(&events_dir.join("events_2").join("v3"))
+        .unwrap();
+    let batches = ctx
+        .sql("SELECT count(*), sum(total_docs) FROM pinot.system.segments WHERE table_name = 'events'")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let column = |i: usize| {
+        batches[0]
+            .column(i)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap()
+            .value(0)
+    };
+    assert_eq!((column(0), column(1)), (3, 9));
+}
+
+/// `events_OFFLINE` with one segment per entry of `segments`
+fn write_events_table(data_dir: &Path, segments: &[(&str, Vec<i32>)]) {
+    for (segment_name, ids) in segments {
+        SegmentBuilder::new("events", segment_name)
+            .int_column("id", ids.clone())
+            .write(
+                &data_dir
+                    .join("events_OFFLINE")
+                    .join(segment_name)
+                    .join("v3"),
+            )
+            .unwrap();
+    }
+}
+
+async fn count_events(ctx: &SessionContext) -> i64 {
+    let batches = ctx
+        .sql("SELECT COUNT(*) FROM pinot.default.events")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap()
+        .value(0)
 }
 
 #[tokio::test]
-async fn test_catalog_multiple_tables() {
-    if !Path::new(DATA_DIR).exists() {
-        println!("Skipping test: data directory not found");
-        return;
+async fn test_catalog_caches_tables() {
+    let dir = tempfile::tempdir().unwrap();
+    write_events_table(dir.path(), &[("events_0", vec![1, 2, 3])]);
+
+    let catalog = Arc::new(PinotCatalog::new(dir.path()).unwrap());
+    let schema = catalog.schema("default").unwrap();
+    let first = schema.table("events").await.unwrap().unwrap();
+    let second = schema.table("events").await.unwrap().unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", catalog.clone());
+    assert_eq!(count_events(&ctx).await, 3);
+
+    // Segments added on disk are picked up only once the table is refreshed
+    write_events_table(dir.path(), &[("events_1", vec![4, 5])]);
+    assert_eq!(count_events(&ctx).await, 3);
+    catalog.refresh_table("EVENTS");
+    assert_eq!(count_events(&ctx).await, 5);
+    let refreshed = schema.table("events").await.unwrap().unwrap();
+    assert!(!Arc::ptr_eq(&first, &refreshed));
+
+    write_events_table(dir.path(), &[("events_2", vec![6])]);
+    catalog.refresh();
+    assert_eq!(count_events(&ctx).await, 6);
+}
+
+#[tokio::test]
+async fn test_catalog_table_ttl() {
+    let dir = tempfile::tempdir().unwrap();
+    write_events_table(dir.path(), &[("events_0", vec![1, 2, 3])]);
+
+    let catalog = PinotCatalog::builder()
+        .filesystem(dir.path())
+        .with_table_ttl(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    let schema = catalog.schema("default").unwrap();
+    let first = schema.table("events").await.unwrap().unwrap();
+    assert!(Arc::ptr_eq(
+        &first,
+        &schema.table("events").await.unwrap().unwrap()
+    ));
+
+    write_events_table(dir.path(), &[("events_1", vec![4, 5])]);
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let reopened = schema.table("events").await.unwrap().unwrap();
+    assert!(!Arc::ptr_eq(&first, &reopened));
+    let reopened = reopened.as_any().downcast_ref::<PinotTable>().unwrap();
+    assert_eq!(reopened.total_docs(), 5);
+}
+
+async fn count_rows(ctx: &SessionContext, table: &str) -> i64 {
+    let batches = ctx
+        .sql(&format!("SELECT COUNT(*) FROM {}", table))
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap()
+        .value(0)
+}
+
+#[tokio::test]
+async fn test_catalog_merge() {
+    let cluster_a = tempfile::tempdir().unwrap();
+    write_events_table(cluster_a.path(), &[("events_0", vec![1, 2, 3])]);
+    SegmentBuilder::new("users", "users_0")
+        .string_column("name", vec!["a"])
+        .write(
+            &cluster_a
+                .path()
+                .join("users_OFFLINE")
+                .join("users_0")
+                .join("v3"),
+        )
+        .unwrap();
+    let cluster_b = tempfile::tempdir().unwrap();
+    write_events_table(cluster_b.path(), &[("events_0", vec![4, 5])]);
+    SegmentBuilder::new("orders", "orders_0")
+        .long_column("amount", vec![10, 20])
+        .write(
+            &cluster_b
+                .path()
+                .join("orders_OFFLINE")
+                .join("orders_0")
+                .join("v3"),
+        )
+        .unwrap();
+
+    let catalog = PinotCatalog::new(cluster_a.path())
+        .unwrap()
+        .merge(PinotCatalog::new(cluster_b.path()).unwrap())
+        .unwrap();
+    assert_eq!(catalog.schema_names(), vec!["default", "system"]);
+    let mut names = catalog.schema("default").unwrap().table_names();
+    names.sort();
+    assert_eq!(names, vec!["events", "orders", "users"]);
+
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    // A table in both catalogs is served from the first
+    assert_eq!(count_events(&ctx).await, 3);
+    assert_eq!(count_rows(&ctx, "pinot.default.orders").await, 2);
+    assert_eq!(count_rows(&ctx, "pinot.default.users").await, 1);
+    assert_eq!(count_rows(&ctx, "pinot.system.segments").await, 3);
+
+    // Same table name with a different schema
+    let cluster_c = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .long_column("id", vec![6])
+        .write(
+            &cluster_c
+                .path()
+                .join("events_OFFLINE")
+                .join("events_0")
+                .join("v3"),
+        )
+        .unwrap();
+    let message = PinotCatalog::new(cluster_a.path())
+        .unwrap()
+        .merge(PinotCatalog::new(cluster_c.path()).unwrap())
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("default.events"), "{}", message);
+}
+
+#[tokio::test]
+async fn test_catalog_add_cluster() {
+    let cluster_a = tempfile::tempdir().unwrap();
+    write_events_table(cluster_a.path(), &[("events_0", vec![1, 2, 3])]);
+    let cluster_b = tempfile::tempdir().unwrap();
+    write_events_table(cluster_b.path(), &[("events_0", vec![4, 5])]);
+
+    let catalog = PinotCatalog::builder()
+        .add_cluster(
+            "cluster_a",
+            Arc::new(FileSystemMetadataProvider::new(cluster_a.path())),
+        )
+        .add_cluster(
+            "cluster_b",
+            Arc::new(FileSystemMetadataProvider::new(cluster_b.path())),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(
+        catalog.schema_names(),
+        vec!["cluster_a", "cluster_b", "system"]
+    );
+
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    assert_eq!(count_rows(&ctx, "pinot.cluster_a.events").await, 3);
+    assert_eq!(count_rows(&ctx, "pinot.cluster_b.events").await, 2);
+    assert_eq!(
+        count_rows(
+            &ctx,
+            "pinot.system.segments WHERE schema_name = 'cluster_b'"
+        )
+        .await,
+        1
+    );
+
+    // Primary source and clusters side by side
+    let catalog = PinotCatalog::builder()
+        .filesystem(cluster_a.path())
+        .add_cluster(
+            "cluster_b",
+            Arc::new(FileSystemMetadataProvider::new(cluster_b.path())),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(
+        catalog.schema_names(),
+        vec!["default", "cluster_b", "system"]
+    );
+
+    for name in ["default", "system"] {
+        let result = PinotCatalog::builder()
+            .filesystem(cluster_a.path())
+            .add_cluster(
+                name,
+                Arc::new(FileSystemMetadataProvider::new(cluster_b.path())),
+            )
+            .build();
+        assert!(result.is_err(), "{}", name);
+    }
+}
+
+#[tokio::test]
+async fn test_catalog_schema_name() {
+    let dir = tempfile::tempdir().unwrap();
+    write_events_table(dir.path(), &[("events_0", vec![1, 2, 3])]);
+
+    let catalog = PinotCatalog::builder()
+        .filesystem(dir.path())
+        .with_schema_name("analytics")
+        .with_schema_alias("tenant_a")
+        .build()
+        .unwrap();
+    assert_eq!(
+        catalog.schema_names(),
+        vec!["analytics", "tenant_a", "system"]
+    );
+    assert!(catalog.schema("default").is_none());
+
+    // Aliases share one provider, and so its opened tables
+    let analytics = catalog.schema("analytics").unwrap();
+    let tenant_a = catalog.schema("tenant_a").unwrap();
+    assert!(Arc::ptr_eq(
+        &analytics.table("events").await.unwrap().unwrap(),
+        &tenant_a.table("events").await.unwrap().unwrap()
+    ));
+
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    assert_eq!(count_rows(&ctx, "pinot.analytics.events").await, 3);
+    assert_eq!(
+        count_rows(&ctx, "pinot.tenant_a.events WHERE id > 1").await,
+        2
+    );
+    assert_eq!(
+        count_rows(
+            &ctx,
+            "pinot.system.segments WHERE schema_name = 'analytics'"
+        )
+        .await,
+        1
+    );
+    assert!(ctx.sql("SELECT * FROM pinot.default.events").await.is_err());
+
+    let invalid = [
+        PinotCatalog::builder()
+            .filesystem(dir.path())
+            .with_schema_name("system"),
+        PinotCatalog::builder()
+            .filesystem(dir.path())
+            .with_schema_alias("default"),
+        PinotCatalog::builder()
+            .filesystem(dir.path())
+            .with_schema_name("analytics")
+            .add_cluster(
+                "analytics",
+                Arc::new(FileSystemMetadataProvider::new(dir.path())),
+            ),
+        PinotCatalog::builder()
+            .with_schema_name("analytics")
+            .add_cluster(
+                "cluster_a",
+                Arc::new(FileSystemMetadataProvider::new(dir.path())),
+            ),
+    ];
+    for builder in invalid {
+        assert!(builder.build().is_err());
     }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_table_names_on_multi_thread_runtime() {
+    let dir = tempfile::tempdir().unwrap();
+    write_events_table(dir.path(), &[("events_0", vec![1, 2, 3])]);
+
+    // Nothing is cached yet, so the sync methods list tables in place
+    let catalog = PinotCatalog::new(dir.path()).unwrap();
+    let schema = catalog.schema("default").unwrap();
+    assert_eq!(schema.table_names(), vec!["events".to_string()]);
+    assert!(schema.table_exist("EVENTS"));
+    assert!(!schema.table_exist("missing"));
 
     let ctx = SessionContext::new();
-    let catalog = PinotCatalog::new(DATA_DIR).expect("Failed to create catalog");
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    assert_eq!(count_events(&ctx).await, 3);
+}
+
+#[tokio::test]
+async fn test_table_names_on_current_thread_runtime() {
+    let dir = tempfile::tempdir().unwrap();
+    write_events_table(dir.path(), &[("events_0", vec![1, 2, 3])]);
+
+    let catalog = PinotCatalog::new(dir.path()).unwrap();
+    let schema = catalog.schema("default").unwrap();
+    assert_eq!(schema.table_names(), vec!["events".to_string()]);
+    assert!(schema.table_exist("events"));
+}
+
+#[tokio::test]
+async fn test_catalog_table_filters() {
+    let dir = tempfile::tempdir().unwrap();
+    for table in ["events", "events_archive", "clicks", "users"] {
+        SegmentBuilder::new(table, &format!("{}_0", table))
+            .int_column("id", vec![1, 2])
+            .write(
+                &dir.path()
+                    .join(format!("{}_OFFLINE", table))
+                    .join(format!("{}_0", table))
+                    .join("v3"),
+            )
+            .unwrap();
+    }
+    let table_names = |catalog: &PinotCatalog| {
+        let mut names = catalog.schema("default").unwrap().table_names();
+        names.sort();
+        names
+    };
+
+    // Globs and exact names; excludes win over includes
+    let catalog = PinotCatalog::builder()
+        .filesystem(dir.path())
+        .include_tables(["events*", "Clicks"])
+        .exclude_tables(["*_archive"])
+        .build()
+        .unwrap();
+    assert_eq!(table_names(&catalog), vec!["clicks", "events"]);
+
+    let schema = catalog.schema("default").unwrap();
+    assert!(schema.table_exist("events"));
+    assert!(!schema.table_exist("events_archive"));
+    assert!(!schema.table_exist("users"));
+    assert!(schema.table("events_archive").await.unwrap().is_none());
+    assert!(schema.table("users").await.unwrap().is_none());
+
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    assert_eq!(count_rows(&ctx, "pinot.default.clicks").await, 2);
+    assert!(ctx.sql("SELECT * FROM pinot.default.users").await.is_err());
+    assert_eq!(
+        count_rows(&ctx, "pinot.system.segments WHERE table_name = 'users'").await,
+        0
+    );
+
+    // Without includes every table not excluded is exposed
+    let catalog = PinotCatalog::builder()
+        .filesystem(dir.path())
+        .exclude_tables(["users", "events_?rchive"])
+        .build()
+        .unwrap();
+    assert_eq!(table_names(&catalog), vec!["clicks", "events"]);
+
+    // A table matching both lists is hidden
+    let catalog = PinotCatalog::builder()
+        .filesystem(dir.path())
+        .include_tables(["users"])
+        .exclude_tables(["users"])
+        .build()
+        .unwrap();
+    assert!(table_names(&catalog).is_empty());
+
+    assert!(PinotCatalog::builder()
+        .filesystem(dir.path())
+        .include_tables(["events["])
+        .build()
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_catalog_case_insensitive_names() {
+    let dir = tempfile::tempdir().unwrap();
+    for table in ["baseballStats", "Events", "events"] {
+        SegmentBuilder::new(table, &format!("{}_0", table))
+            .string_column("playerID", vec!["a", "b", "a"])
+            .int_column("homeRuns", vec![1, 2, 3])
+            .write(
+                &dir.path()
+                    .join(format!("{}_OFFLINE", table))
+                    .join(format!("{}_0", table))
+                    .join("v3"),
+            )
+            .unwrap();
+    }
+
+    // Strict by default: unquoted column names are lowercased and miss
+    let catalog = PinotCatalog::builder()
+        .filesystem(dir.path())
+        .build()
+        .unwrap();
+    let schema = catalog.schema("default").unwrap();
+    assert!(schema.table("EVENTS").await.unwrap().is_some());
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    assert!(ctx
+        .sql("SELECT playerid FROM pinot.default.baseballstats")
+        .await
+        .is_err());
+
+    let catalog = PinotCatalog::builder()
+        .filesystem(dir.path())
+        .with_case_insensitive_names(true)
+        .build()
+        .unwrap();
+    let schema = catalog.schema("default").unwrap();
+    let err = schema.table("EVENTS").await.unwrap_err();
+    assert!(err.to_string().contains("ambiguous"), "{}", err);
+    // An exact match is not ambiguous
+    assert!(schema.table("Events").await.unwrap().is_some());
 
+    let ctx = SessionContext::new();
     ctx.register_catalog("pinot", Arc::new(catalog));
+    let batches = ctx
+        .sql(
+            "SELECT playerid, SUM(homeruns) AS total FROM pinot.default.baseballstats \
+             WHERE homeruns > 1 GROUP BY playerid ORDER BY playerid",
+        )
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let players = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    let totals = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(players.iter().flatten().collect::<Vec<_>>(), vec!["a", "b"]);
+    assert_eq!(totals.values(), &[3, 2]);
+}
 
-    // Query first table
-    let df1 = ctx
-        .sql("SELECT COUNT(*) FROM pinot.default.\"baseballStats\"")
+#[tokio::test]
+async fn test_schema_provider_evict() {
+    let dir = tempfile::tempdir().unwrap();
+    write_events_table(dir.path(), &[("events_0", vec![1, 2, 3])]);
+    let schema = PinotSchemaProvider::new(Arc::new(FileSystemMetadataProvider::new(dir.path())));
+
+    let first = schema.table("events").await.unwrap().unwrap();
+    let second = schema.table("events").await.unwrap().unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+
+    // Evicting re-lists the segments, picking up the new one
+    write_events_table(dir.path(), &[("events_1", vec![4, 5])]);
+    assert!(schema.evict("EVENTS"));
+    assert!(!schema.evict("events"));
+    let reopened = schema.table("events").await.unwrap().unwrap();
+    assert!(!Arc::ptr_eq(&first, &reopened));
+    let reopened = reopened.as_any().downcast_ref::<PinotTable>().unwrap();
+    assert_eq!(reopened.num_segments(), 2);
+}
+
+#[tokio::test]
+async fn test_catalog_clone_shares_tables() {
+    let dir = tempfile::tempdir().unwrap();
+    write_events_table(dir.path(), &[("events_0", vec![1, 2, 3])]);
+    let catalog = PinotCatalog::new(dir.path()).unwrap();
+    let clone = catalog.clone();
+
+    let first = catalog
+        .schema("default")
+        .unwrap()
+        .table("events")
+        .await
+        .unwrap()
+        .unwrap();
+    let second = clone
+        .schema("default")
+        .unwrap()
+        .table("events")
         .await
-        .expect("Failed to query baseballStats");
+        .unwrap()
+        .unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
 
-    let results1 = df1.collect().await.expect("Failed to collect");
-    println!("baseballStats count: {:?}", results1[0]);
+    for catalog in [catalog, clone] {
+        let ctx = SessionContext::new();
+        ctx.register_catalog("pinot", Arc::new(catalog));
+        assert_eq!(count_events(&ctx).await, 3);
+    }
+}
 
-    // Query second table if it exists
-    let result = ctx
-        .sql("SELECT COUNT(*) FROM pinot.default.\"dimBaseballTeams\"")
-        .await;
+#[tokio::test]
+async fn test_catalog_filesystem_dirs() {
+    let server_0 = tempfile::tempdir().unwrap();
+    let server_1 = tempfile::tempdir().unwrap();
+    // `events` is split across both servers, `users` lives on one only
+    let segments = [
+        (server_0.path(), "events", "events_0", vec![1, 2]),
+        (server_0.path(), "events", "events_1", vec![3]),
+        (server_1.path(), "events", "events_2", vec![4, 5, 6]),
+        (server_1.path(), "users", "users_0", vec![1]),
+    ];
+    for (dir, table, segment, ids) in segments {
+        SegmentBuilder::new(table, segment)
+            .int_column("id", ids)
+            .write(
+                &dir.join(format!("{}_OFFLINE", table))
+                    .join(segment)
+                    .join("v3"),
+            )
+            .unwrap();
+    }
+
+    let provider = FileSystemMetadataProvider::new_multi(vec![
+        server_0.path().to_path_buf(),
+        server_1.path().to_path_buf(),
+    ]);
+    assert_eq!(
+        provider.list_tables().await.unwrap(),
+        vec!["events", "users"]
+    );
+    assert!(provider.table_exists("users").await);
+    let paths = provider.get_segment_paths("events").await.unwrap();
+    assert_eq!(paths.len(), 3);
+    assert!(paths.iter().all(|path| path.ends_with("v3")));
+    assert!(provider.get_segment_paths("clicks").await.is_err());
+
+    let catalog = PinotCatalog::builder()
+        .filesystem_dirs([server_0.path(), server_1.path()])
+        .build()
+        .unwrap();
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    assert_eq!(count_rows(&ctx, "pinot.default.events").await, 6);
+    assert_eq!(count_rows(&ctx, "pinot.default.users").await, 1);
+
+    let missing = server_0.path().join("missing");
+    assert!(PinotCatalog::builder()
+        .filesystem_dirs([server_0.path(), missing.as_path()])
+        .build()
+        .is_err());
+    assert!(PinotCatalog::builder()
+        .filesystem_dirs(Vec::<std::path::PathBuf>::new())
+        .build()
+        .is_err());
+}
 
-    if let Ok(df2) = result {
-        let results2 = df2.collect().await.expect("Failed to collect");
-        println!("dimBaseballTeams count: {:?}", results2[0]);
-        println!("✓ Successfully queried multiple tables via catalog");
-    } else {
-        println!("✓ Successfully queried baseballStats via catalog");
+#[tokio::test]
+async fn test_catalog_filesystem_dirs_duplicate_segment() {
+    let server_0 = tempfile::tempdir().unwrap();
+    let server_1 = tempfile::tempdir().unwrap();
+    // `events_1` was moved from server 0 to server 1 and left behind on 0
+    let segments = [
+        (server_0.path(), "events_0", vec![1, 2]),
+        (server_0.path(), "events_1", vec![3]),
+        (server_1.path(), "events_1", vec![3, 4, 5]),
+    ];
+    for (dir, segment, ids) in segments {
+        SegmentBuilder::new("events", segment)
+            .int_column("id", ids)
+            .write(&dir.join("events_OFFLINE").join(segment).join("v3"))
+            .unwrap();
     }
+    let stale = server_0
+        .path()
+        .join("events_OFFLINE/events_1/v3/metadata.properties");
+    std::fs::File::options()
+        .write(true)
+        .open(&stale)
+        .unwrap()
+        .set_modified(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap();
+
+    let provider = FileSystemMetadataProvider::new_multi(vec![
+        server_0.path().to_path_buf(),
+        server_1.path().to_path_buf(),
+    ]);
+    let paths = provider.get_segment_paths("events").await.unwrap();
+    assert_eq!(paths.len(), 2);
+    assert!(paths.contains(&server_1.path().join("events_OFFLINE/events_1/v3")));
+
+    let catalog = PinotCatalog::builder()
+        .filesystem_dirs([server_0.path(), server_1.path()])
+        .build()
+        .unwrap();
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    assert_eq!(count_rows(&ctx, "pinot.default.events").await, 5);
 }
 
 #[tokio::test]
-async fn test_catalog_select_query() {
-    if !Path::new(DATA_DIR).exists() {
-        println!("Skipping test: data directory not found");
-        return;
+async fn test_catalog_flat_segment_layout() {
+    let dir = tempfile::tempdir().unwrap();
+    let table_dir = dir.path().join("events_OFFLINE");
+    // events_0 has a v3/ level, events_1 was untarred flat, events_2 has
+    // both and events_3 is still being written
+    let segments = [
+        (table_dir.join("events_0/v3"), "events_0", vec![1, 2]),
+        (table_dir.join("events_1"), "events_1", vec![3, 4, 5]),
+        (table_dir.join("events_2/v3"), "events_2", vec![6]),
+        (table_dir.join("events_2"), "events_2", vec![6, 7, 8, 9]),
+    ];
+    for (segment_dir, segment, ids) in segments {
+        SegmentBuilder::new("events", segment)
+            .int_column("id", ids)
+            .write(&segment_dir)
+            .unwrap();
+    }
+    std::fs::create_dir_all(table_dir.join("events_3")).unwrap();
+
+    let provider = FileSystemMetadataProvider::new(dir.path());
+    let mut paths = provider.get_segment_paths("events").await.unwrap();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            table_dir.join("events_0/v3"),
+            table_dir.join("events_1"),
+            table_dir.join("events_2/v3"),
+        ]
+    );
+
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(PinotCatalog::new(dir.path()).unwrap()));
+    assert_eq!(count_rows(&ctx, "pinot.default.events").await, 6);
+
+    let table = PinotTable::open_table(&table_dir).unwrap();
+    assert_eq!(
+        table.segment_names(),
+        vec!["events_0", "events_1", "events_2"]
+    );
+}
+
+#[tokio::test]
+async fn test_segments_sorted_by_sequence_number() {
+    let expected = [
+        "events_OFFLINE_0",
+        "events_OFFLINE_1",
+        "events_OFFLINE_2",
+        "events_OFFLINE_3",
+        "events_OFFLINE_5",
+        "events_OFFLINE_10",
+        "events_OFFLINE_11",
+        "events_OFFLINE_20",
+        "events_OFFLINE_100",
+        // Realtime segments order by partition, sequence number, creation time
+        "events__0__2__20240101T0000Z",
+        "events__0__10__20240102T0000Z",
+        // Names without a sequence number sort by name
+        "events_manual",
+    ];
+    let dir = tempfile::tempdir().unwrap();
+    let segments: Vec<(&str, Vec<i32>)> = expected.iter().map(|name| (*name, vec![1])).collect();
+    write_events_table(dir.path(), &segments);
+    let table_dir = dir.path().join("events_OFFLINE");
+
+    let provider = FileSystemMetadataProvider::new(dir.path());
+    let paths = provider.get_segment_paths("events").await.unwrap();
+    let expected_paths: Vec<_> = expected
+        .iter()
+        .map(|name| table_dir.join(name).join("v3"))
+        .collect();
+    assert_eq!(paths, expected_paths);
+
+    let table = PinotTable::open_table(&table_dir).unwrap();
+    assert_eq!(table.segment_names(), expected);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_catalog_symlinked_segments() {
+    use std::os::unix::fs::symlink;
+
+    // Segments live on a volume directory and are linked into the data dir
+    let dir = tempfile::tempdir().unwrap();
+    let root = std::fs::canonicalize(dir.path()).unwrap();
+    let volume = root.join("volume0");
+    write_events_table(&volume, &[("events_0", vec![1, 2]), ("events_1", vec![3])]);
+
+    let data_dir = root.join("data0");
+    let table_dir = data_dir.join("events_OFFLINE");
+    std::fs::create_dir_all(&table_dir).unwrap();
+    for segment in ["events_0", "events_1"] {
+        symlink(
+            volume.join("events_OFFLINE").join(segment),
+            table_dir.join(segment),
+        )
+        .unwrap();
     }
+    // A link cycle is skipped rather than followed
+    symlink(table_dir.join("events_2"), table_dir.join("events_2")).unwrap();
+
+    let provider = FileSystemMetadataProvider::new(&data_dir);
+    assert_eq!(
+        provider.get_segment_paths("events").await.unwrap(),
+        vec![
+            volume.join("events_OFFLINE/events_0/v3"),
+            volume.join("events_OFFLINE/events_1/v3"),
+        ]
+    );
 
     let ctx = SessionContext::new();
-    let catalog = PinotCatalog::new(DATA_DIR).expect("Failed to create catalog");
+    ctx.register_catalog("pinot", Arc::new(PinotCatalog::new(&data_dir).unwrap()));
+    assert_eq!(count_rows(&ctx, "pinot.default.events").await, 3);
 
+    // A linked table directory
+    let linked_dir = root.join("data1");
+    std::fs::create_dir_all(&linked_dir).unwrap();
+    symlink(&table_dir, linked_dir.join("events_OFFLINE")).unwrap();
+    ctx.register_catalog("linked", Arc::new(PinotCatalog::new(&linked_dir).unwrap()));
+    assert_eq!(count_rows(&ctx, "linked.default.events").await, 3);
+
+    let table = PinotTable::open_table(linked_dir.join("events_OFFLINE")).unwrap();
+    assert_eq!(table.segment_names(), vec!["events_0", "events_1"]);
+}
+
+#[tokio::test]
+async fn test_catalog_static_tables() {
+    // Segments laid out without any Pinot directory naming
+    let dir = tempfile::tempdir().unwrap();
+    let segment = |name: &str, ids: Vec<i32>| {
+        let path = dir.path().join(name);
+        SegmentBuilder::new("events", name)
+            .int_column("id", ids)
+            .write(&path)
+            .unwrap();
+        path
+    };
+    let events = vec![segment("a", vec![1, 2]), segment("b", vec![3])];
+    let clicks = vec![segment("c", vec![1, 2, 3, 4])];
+
+    let catalog = PinotCatalog::builder()
+        .static_tables(HashMap::from([("events".to_string(), events.clone())]))
+        .with_validate_on_build(true)
+        .build()
+        .unwrap();
+    let ctx = SessionContext::new();
     ctx.register_catalog("pinot", Arc::new(catalog));
+    assert_eq!(count_rows(&ctx, "pinot.default.events").await, 3);
 
-    // Execute SELECT with projection
+    let missing = HashMap::from([("events".to_string(), vec![dir.path().join("missing")])]);
+    let err = PinotCatalog::builder()
+        .static_tables(missing.clone())
+        .with_validate_on_build(true)
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("missing"), "{}", err);
+    // Unchecked, the bad path only fails when the table is opened
+    assert!(PinotCatalog::builder()
+        .static_tables(missing)
+        .build()
+        .is_ok());
+
+    // Tables added and removed at runtime show up after a refresh
+    let provider = Arc::new(StaticMetadataProvider::new(HashMap::from([(
+        "events".to_string(),
+        events,
+    )])));
+    let catalog = Arc::new(
+        PinotCatalog::builder()
+            .provider(provider.clone())
+            .build()
+            .unwrap(),
+    );
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", catalog.clone());
+    assert!(provider.insert_table("clicks", clicks).is_none());
+    assert!(provider.remove_table("events").is_some());
+    assert_eq!(provider.list_tables().await.unwrap(), vec!["clicks"]);
+    catalog.refresh();
+    assert_eq!(
+        catalog.schema("default").unwrap().table_names(),
+        vec!["clicks".to_string()]
+    );
+    assert_eq!(count_rows(&ctx, "pinot.default.clicks").await, 4);
+    assert!(provider
+        .get_segment_paths("events")
+        .await
+        .unwrap_err()
+        .to_string()
+        .contains("not found"));
+    assert_eq!(provider.get_segment_paths("clicks").await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_catalog_static_table_schema() {
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_pinot::LazyPinotTable;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events_0");
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", vec![1, 2, 3])
+        .string_column("country", vec!["de", "fr", "us"])
+        .write(&path)
+        .unwrap();
+
+    let provider = Arc::new(StaticMetadataProvider::new(HashMap::from([
+        ("events".to_string(), vec![path]),
+        ("missing".to_string(), vec![dir.path().join("missing_0")]),
+    ])));
+    let schema = |id_type: DataType| {
+        Arc::new(Schema::new(vec![
+            Field::new("id", id_type, false),
+            Field::new("country", DataType::Utf8, false),
+        ]))
+    };
+    assert!(provider
+        .set_table_schema("events", schema(DataType::Int32))
+        .is_none());
+    provider.set_table_schema("missing", schema(DataType::Int32));
+    let catalog = Arc::new(
+        PinotCatalog::builder()
+            .provider(provider.clone())
+            .build()
+            .unwrap(),
+    );
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", catalog.clone());
+
+    // Planning uses the provided schema; segments open on the first scan
+    let events = catalog
+        .schema("default")
+        .unwrap()
+        .table("events")
+        .await
+        .unwrap()
+        .unwrap();
+    let lazy = events.as_any().downcast_ref::<LazyPinotTable>().unwrap();
     let df = ctx
-        .sql("SELECT \"playerID\", \"hits\" FROM pinot.default.\"baseballStats\" LIMIT 10")
+        .sql("SELECT country FROM pinot.default.events WHERE id > 1 ORDER BY country")
         .await
-        .expect("Failed to create DataFrame");
+        .unwrap();
+    assert!(!lazy.is_open());
+    let batches = df.collect().await.unwrap();
+    let countries = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(countries.iter().flatten().collect::<Vec<_>>(), ["fr", "us"]);
+    assert!(lazy.is_open());
 
-    let results = df.collect().await.expect("Failed to collect results");
+    // A table whose segments cannot be opened still plans
+    let df = ctx
+        .sql("SELECT id FROM pinot.default.missing")
+        .await
+        .unwrap();
+    let err = df.collect().await.unwrap_err();
+    assert!(
+        err.to_string().contains("Failed to open segments"),
+        "{}",
+        err
+    );
 
-    assert!(!results.is_empty());
-    assert_eq!(results[0].num_columns(), 2);
+    // The schema is checked against the segments at scan time
+    provider.set_table_schema("events", schema(DataType::Int64));
+    catalog.refresh();
+    let err = ctx
+        .sql("SELECT SUM(id) FROM pinot.default.events")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("is Int64 in its schema"),
+        "{}",
+        err
+    );
+
+    // Removing a table drops its schema too
+    assert!(provider.remove_table("events").is_some());
+    assert!(provider.get_table_schema("events").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_catalog_change_detection() {
+    let data_dir = tempfile::tempdir().unwrap();
+    write_events_table(data_dir.path(), &[("events_0", vec![1, 2, 3])]);
+    let catalog = PinotCatalog::builder()
+        .filesystem(data_dir.path())
+        .with_change_detection(true)
+        .build()
+        .unwrap();
+    let unchanged = PinotCatalog::new(data_dir.path()).unwrap();
+    let schema = catalog.schema("default").unwrap();
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    ctx.register_catalog("unchanged", Arc::new(unchanged));
+    assert_eq!(count_rows(&ctx, "pinot.default.events").await, 3);
+    assert_eq!(count_rows(&ctx, "unchanged.default.events").await, 3);
+
+    // Unchanged segments keep the opened table
+    let first = schema.table("events").await.unwrap().unwrap();
+    assert!(Arc::ptr_eq(
+        &first,
+        &schema.table("events").await.unwrap().unwrap()
+    ));
+
+    // Copy a segment in the way a flush would, metadata.properties last
+    let staging = tempfile::tempdir().unwrap();
+    write_events_table(staging.path(), &[("events_1", vec![4, 5])]);
+    let source = staging.path().join("events_OFFLINE/events_1/v3");
+    let target = data_dir.path().join("events_OFFLINE/events_1/v3");
+    std::fs::create_dir_all(&target).unwrap();
+    for file in ["columns.psf", "index_map"] {
+        std::fs::copy(source.join(file), target.join(file)).unwrap();
+    }
+    assert_eq!(count_rows(&ctx, "pinot.default.events").await, 3);
+    assert!(Arc::ptr_eq(
+        &first,
+        &schema.table("events").await.unwrap().unwrap()
+    ));
+
+    std::fs::copy(
+        source.join("metadata.properties"),
+        target.join("metadata.properties"),
+    )
+    .unwrap();
+    assert_eq!(count_rows(&ctx, "pinot.default.events").await, 5);
+    assert_eq!(count_rows(&ctx, "unchanged.default.events").await, 3);
+
+    // New tables are found too
+    SegmentBuilder::new("users", "users_0")
+        .string_column("name", vec!["a", "b"])
+        .write(&data_dir.path().join("users_OFFLINE/users_0/v3"))
+        .unwrap();
+    assert_eq!(count_rows(&ctx, "pinot.default.users").await, 2);
+}
+
+#[cfg(feature = "object-store")]
+#[tokio::test]
+async fn test_catalog_object_store() {
+    use object_store::{memory::InMemory, path::Path as ObjectPath, ObjectStore};
+
+    // Upload a local table to a bucket as <table>_OFFLINE/<segment>/<file>
+    async fn upload(store: &InMemory, segments: &[(&str, Vec<i32>)]) {
+        let local = tempfile::tempdir().unwrap();
+        write_events_table(local.path(), segments);
+        for (segment_name, _) in segments {
+            let v3 = local
+                .path()
+                .join("events_OFFLINE")
+                .join(segment_name)
+                .join("v3");
+            for entry in std::fs::read_dir(&v3).unwrap() {
+                let entry = entry.unwrap();
+                let location = ObjectPath::from(format!(
+                    "deep-store/events_OFFLINE/{}/{}",
+                    segment_name,
+                    entry.file_name().to_str().unwrap()
+                ));
+                let data = std::fs::read(entry.path()).unwrap();
+                store.put(&location, data.into()).await.unwrap();
+            }
+        }
+    }
+    let store = Arc::new(InMemory::new());
+    upload(&store, &[("events_0", vec![1, 2, 3]), ("events_1", vec![4, 5])]).await;
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let catalog = PinotCatalog::builder()
+        .object_store("memory:///deep-store", store.clone())
+        .with_segment_cache_dir(cache_dir.path())
+        .with_change_detection(true)
+        .build()
+        .unwrap();
+    assert_eq!(
+        catalog.schema("default").unwrap().table_names(),
+        vec!["events"]
+    );
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    assert_eq!(count_events(&ctx).await, 5);
+
+    // Replacing a segment in the store re-opens the table over a new copy
+    upload(&store, &[("events_1", vec![4, 5, 6, 7])]).await;
+    assert_eq!(count_events(&ctx).await, 7);
+    let copies = std::fs::read_dir(cache_dir.path().join("events_OFFLINE"))
+        .unwrap()
+        .filter(|version| {
+            version
+                .as_ref()
+                .unwrap()
+                .path()
+                .join("events_1/v3/metadata.properties")
+                .is_file()
+        })
+        .count();
+    assert_eq!(copies, 2);
+}
+
+/// Filesystem provider counting `get_segment_paths` calls
+#[derive(Debug)]
+struct CountingProvider {
+    inner: FileSystemMetadataProvider,
+    segment_calls: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl MetadataProvider for CountingProvider {
+    async fn list_tables(&self) -> datafusion_pinot::Result<Vec<String>> {
+        self.inner.list_tables().await
+    }
+
+    async fn get_segment_paths(
+        &self,
+        table_name: &str,
+    ) -> datafusion_pinot::Result<Vec<std::path::PathBuf>> {
+        self.segment_calls
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        self.inner.get_segment_paths(table_name).await
+    }
+}
+
+#[tokio::test]
+async fn test_catalog_metadata_cache() {
+    let data_dir = tempfile::tempdir().unwrap();
+    write_events_table(data_dir.path(), &[("events_0", vec![1, 2, 3])]);
+
+    for (cache_ttl, expected_calls) in [(None, 4), (Some(Duration::from_secs(60)), 1)] {
+        let provider = Arc::new(CountingProvider {
+            inner: FileSystemMetadataProvider::new(data_dir.path()),
+            segment_calls: Default::default(),
+        });
+        let mut builder = PinotCatalog::builder()
+            .provider(provider.clone())
+            .with_change_detection(true);
+        if let Some(ttl) = cache_ttl {
+            builder = builder.with_metadata_cache(ttl);
+        }
+        let ctx = SessionContext::new();
+        ctx.register_catalog("pinot", Arc::new(builder.build().unwrap()));
+
+        // Change detection re-lists segments on every lookup after the first
+        for _ in 0..4 {
+            assert_eq!(count_events(&ctx).await, 3);
+        }
+        assert_eq!(
+            provider
+                .segment_calls
+                .load(std::sync::atomic::Ordering::SeqCst),
+            expected_calls,
+            "cache {:?}",
+            cache_ttl
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_catalog_filesystem_schemas() {
+    let data_dir = tempfile::tempdir().unwrap();
+    write_events_table(
+        &data_dir.path().join("sales"),
+        &[("events_0", vec![1, 2, 3])],
+    );
+    SegmentBuilder::new("clicks", "clicks_0")
+        .int_column("id", vec![1, 2])
+        .write(&data_dir.path().join("marketing/clicks_OFFLINE/clicks_0/v3"))
+        .unwrap();
+    // Neither a table directory at the top level nor a plain file is a schema
+    write_events_table(data_dir.path(), &[("events_0", vec![4])]);
+    std::fs::write(data_dir.path().join("README"), "").unwrap();
+
+    let catalog = PinotCatalog::builder()
+        .filesystem_schemas(data_dir.path())
+        .build()
+        .unwrap();
+    assert_eq!(catalog.schema_names(), vec!["marketing", "sales", "system"]);
+    assert_eq!(
+        catalog.schema("marketing").unwrap().table_names(),
+        vec!["clicks"]
+    );
+    assert_eq!(
+        catalog.schema("sales").unwrap().table_names(),
+        vec!["events"]
+    );
+    assert!(catalog.schema("default").is_none());
+
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    assert_eq!(count_rows(&ctx, "pinot.sales.events").await, 3);
+    assert_eq!(count_rows(&ctx, "pinot.marketing.clicks").await, 2);
+    assert!(ctx
+        .sql("SELECT * FROM pinot.marketing.events")
+        .await
+        .is_err());
+
+    assert!(PinotCatalog::builder()
+        .filesystem_schemas(data_dir.path())
+        .with_schema_name("analytics")
+        .build()
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_catalog_combined_sources() {
+    let data_dir = tempfile::tempdir().unwrap();
+    write_events_table(data_dir.path(), &[("events_0", vec![1, 2, 3])]);
+    let adhoc_dir = tempfile::tempdir().unwrap();
+    write_events_table(adhoc_dir.path(), &[("events_0", vec![4])]);
+    SegmentBuilder::new("clicks", "clicks_0")
+        .int_column("id", vec![1, 2])
+        .write(&adhoc_dir.path().join("clicks_OFFLINE/clicks_0/v3"))
+        .unwrap();
+
+    // The second source adds to the first instead of replacing it
+    let catalog = PinotCatalog::builder()
+        .filesystem(data_dir.path())
+        .filesystem(adhoc_dir.path())
+        .build()
+        .unwrap();
+    assert_eq!(
+        catalog.schema("default").unwrap().table_names(),
+        vec!["clicks", "events"]
+    );
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    // events is served by the source added first
+    assert_eq!(count_events(&ctx).await, 3);
+    assert_eq!(count_rows(&ctx, "pinot.default.clicks").await, 2);
 
-    println!("✓ Catalog SELECT query successful");
+    assert!(PinotCatalog::builder()
+        .filesystem(data_dir.path())
+        .filesystem_schemas(adhoc_dir.path())
+        .build()
+        .is_err());
 }
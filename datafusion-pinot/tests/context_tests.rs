@@ -0,0 +1,138 @@
+use datafusion::arrow::array::{Array, Int64Array};
+use datafusion::prelude::*;
+use datafusion_pinot::{PinotCatalog, SessionContextExt};
+use pinot_segment::testutil::{write_baseball_stats, SegmentBuilder};
+
+async fn count(ctx: &SessionContext, sql: &str) -> i64 {
+    let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap()
+        .value(0)
+}
+
+#[tokio::test]
+async fn test_register_pinot_quickstart() {
+    let dir = tempfile::tempdir().unwrap();
+    write_baseball_stats(
+        &dir.path()
+            .join("baseballStats_OFFLINE/baseballStats_OFFLINE_0/v3"),
+        100,
+    )
+    .unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_pinot("pinot", dir.path()).unwrap();
+    let rows = count(&ctx, "SELECT COUNT(*) FROM pinot.default.\"baseballStats\"").await;
+    assert_eq!(rows, 100);
+}
+
+#[tokio::test]
+async fn test_register_pinot() {
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", vec![1, 2, 3])
+        .write(
+            &dir.path()
+                .join("events_OFFLINE")
+                .join("events_0")
+                .join("v3"),
+        )
+        .unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_pinot("pinot", dir.path()).unwrap();
+    assert_eq!(
+        count(&ctx, "SELECT COUNT(*) FROM pinot.default.events").await,
+        3
+    );
+
+    ctx.register_pinot_catalog(
+        "renamed",
+        PinotCatalog::builder()
+            .filesystem(dir.path())
+            .with_schema_name("analytics"),
+    )
+    .unwrap();
+    assert_eq!(
+        count(&ctx, "SELECT SUM(id) FROM renamed.analytics.events").await,
+        6
+    );
+
+    let err = ctx
+        .register_pinot("missing", dir.path().join("missing"))
+        .unwrap_err();
+    assert!(err.to_string().contains("does not exist"), "{}", err);
+    assert!(ctx
+        .register_pinot_catalog("empty", PinotCatalog::builder())
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_read_pinot_segment() {
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", vec![1, 2, 3])
+        .write(dir.path())
+        .unwrap();
+
+    let ctx = SessionContext::new();
+    let df = ctx
+        .read_pinot_segment(dir.path())
+        .unwrap()
+        .filter(col("id").gt(lit(1)))
+        .unwrap();
+    assert_eq!(df.count().await.unwrap(), 2);
+
+    let missing = dir.path().join("missing");
+    let err = ctx.read_pinot_segment(&missing).unwrap_err();
+    assert!(
+        err.to_string().contains(&missing.display().to_string()),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_pinot_table_functions() {
+    let dir = tempfile::tempdir().unwrap();
+    for (segment, ids) in [("events_0", vec![1, 2, 3]), ("events_1", vec![4, 5])] {
+        SegmentBuilder::new("events", segment)
+            .int_column("id", ids)
+            .write(&dir.path().join(segment).join("v3"))
+            .unwrap();
+    }
+    let segment_dir = dir.path().join("events_0").join("v3");
+
+    let ctx = SessionContext::new();
+    ctx.register_pinot_functions();
+    let sql = format!(
+        "SELECT SUM(id) FROM pinot_segment('{}')",
+        segment_dir.display()
+    );
+    assert_eq!(count(&ctx, &sql).await, 6);
+    let sql = format!(
+        "SELECT COUNT(*) FROM pinot_table('{}') WHERE id > 1",
+        dir.path().display()
+    );
+    assert_eq!(count(&ctx, &sql).await, 4);
+
+    // Bad paths and arguments fail planning, before anything runs
+    let err = ctx
+        .sql("SELECT * FROM pinot_segment('/nonexistent/v3')")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("/nonexistent/v3"), "{}", err);
+    let err = ctx
+        .sql("SELECT * FROM pinot_table('/nonexistent')")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("pinot_table"), "{}", err);
+    let err = ctx
+        .sql("SELECT * FROM pinot_segment(42)")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("string literal"), "{}", err);
+}
@@ -0,0 +1,156 @@
+use datafusion::arrow::array::{Array, DictionaryArray, RecordBatch, StringArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Int32Type};
+use datafusion::datasource::TableProvider;
+use datafusion::prelude::*;
+use datafusion_pinot::PinotTable;
+use pinot_segment::testutil::SegmentBuilder;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+fn write_segments(dir: &Path) -> Vec<PathBuf> {
+    let first = dir.join("teams_0");
+    SegmentBuilder::new("teams", "teams_0")
+        .string_column("team", vec!["NYA", "BOS", "NYA", "CHN"])
+        .int_column("wins", vec![90, 85, 92, 70])
+        .write(&first)
+        .unwrap();
+
+    // Different dictionary contents, so dict ids mean different values per segment
+    let second = dir.join("teams_1");
+    SegmentBuilder::new("teams", "teams_1")
+        .string_column("team", vec!["ANA", "NYA"])
+        .int_column("wins", vec![88, 101])
+        .write(&second)
+        .unwrap();
+
+    vec![first, second]
+}
+
+fn decoded_strings(batches: &[RecordBatch], column: usize) -> Vec<String> {
+    batches
+        .iter()
+        .flat_map(|batch| {
+            let array = cast(batch.column(column), &DataType::Utf8).unwrap();
+            let strings = array.as_any().downcast_ref::<StringArray>().unwrap();
+            strings
+                .iter()
+                .map(|v| v.unwrap().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_dictionary_arrays_decode_to_same_values() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_segments(dir.path());
+
+    let plain = PinotTable::open_segments(&paths, "teams").unwrap();
+    let dictionary = PinotTable::open_segments(&paths, "teams")
+        .unwrap()
+        .with_dictionary_arrays(true);
+
+    assert_eq!(
+        plain.schema().field_with_name("team").unwrap().data_type(),
+        &DataType::Utf8
+    );
+    assert_eq!(
+        dictionary
+            .schema()
+            .field_with_name("team")
+            .unwrap()
+            .data_type(),
+        &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+    );
+    // Non-string columns are untouched
+    assert_eq!(
+        dictionary
+            .schema()
+            .field_with_name("wins")
+            .unwrap()
+            .data_type(),
+        &DataType::Int32
+    );
+
+    let ctx = SessionContext::new();
+    ctx.register_table("plain", Arc::new(plain)).unwrap();
+    ctx.register_table("dict", Arc::new(dictionary)).unwrap();
+
+    let plain_batches = ctx
+        .sql("SELECT team FROM plain")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let dict_batches = ctx
+        .sql("SELECT team FROM dict")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    for batch in &dict_batches {
+        assert!(batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int32Type>>()
+            .is_some());
+    }
+
+    let mut expected = decoded_strings(&plain_batches, 0);
+    let mut actual = decoded_strings(&dict_batches, 0);
+    expected.sort();
+    actual.sort();
+    assert_eq!(actual, expected);
+    assert_eq!(actual.len(), 6);
+}
+
+#[tokio::test]
+async fn test_dictionary_arrays_in_queries() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_segments(dir.path());
+    let table = PinotTable::open_segments(&paths, "teams")
+        .unwrap()
+        .with_dictionary_arrays(true);
+
+    let ctx = SessionContext::new();
+    ctx.register_table("teams", Arc::new(table)).unwrap();
+
+    let batches = ctx
+        .sql("SELECT team, SUM(wins) FROM teams WHERE team <> 'BOS' GROUP BY team ORDER BY team")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let formatted = datafusion::arrow::util::pretty::pretty_format_batches(&batches)
+        .unwrap()
+        .to_string();
+    let expected = [
+        "+------+-----------------+",
+        "| team | sum(teams.wins) |",
+        "+------+-----------------+",
+        "| ANA  | 88              |",
+        "| CHN  | 70              |",
+        "| NYA  | 283             |",
+        "+------+-----------------+",
+    ]
+    .join("\n");
+    assert_eq!(formatted, expected);
+
+    let batches = ctx
+        .sql("SELECT MIN(team), MAX(team) FROM teams")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let values = decoded_strings(&batches, 0)
+        .into_iter()
+        .chain(decoded_strings(&batches, 1))
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec!["ANA", "NYA"]);
+}
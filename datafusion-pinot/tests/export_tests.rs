@@ -0,0 +1,146 @@
+use datafusion::datasource::TableProvider;
+use datafusion::prelude::*;
+use datafusion_pinot::export::{write_csv, write_json, write_parquet};
+use datafusion_pinot::{CsvWriterOptions, ExportFormat, PinotTable};
+use pinot_segment::testutil::SegmentBuilder;
+use std::path::Path;
+use std::process::Command;
+
+/// Three players, one of them with a comma and one with a quote in the name
+fn write_segment(dir: &Path) {
+    SegmentBuilder::new("players", "players_0")
+        .int_column("id", vec![1, 2, 3])
+        .string_column("name", vec!["Smith, John", "Ann", "Bob \"Junior\""])
+        .write(dir)
+        .unwrap();
+}
+
+fn field_names(table: &PinotTable) -> Vec<String> {
+    table
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .collect()
+}
+
+/// Row of `csv` for player `id`, with `id` as its only unquoted value
+fn row_of<'a>(csv: &'a str, id: &str) -> &'a str {
+    csv.lines()
+        .skip(1)
+        .find(|line| line.split(',').any(|value| value == id))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_write_csv() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(dir.path());
+    let table = PinotTable::open(dir.path()).unwrap();
+
+    let mut out = Vec::new();
+    let rows = write_csv(&table, &mut out, CsvWriterOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(rows, 3);
+
+    let csv = String::from_utf8(out).unwrap();
+    assert_eq!(csv.lines().count(), 4);
+    assert_eq!(csv.lines().next().unwrap(), field_names(&table).join(","));
+    assert!(row_of(&csv, "1").contains("\"Smith, John\""));
+    assert!(row_of(&csv, "2").contains("Ann"));
+    assert!(row_of(&csv, "3").contains("\"Bob \"\"Junior\"\"\""));
+}
+
+#[tokio::test]
+async fn test_write_csv_options() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(dir.path());
+    let table = PinotTable::open(dir.path()).unwrap();
+
+    let options = CsvWriterOptions::default()
+        .with_delimiter(b'\t')
+        .with_quote(b'\'')
+        .with_header(false);
+    let mut out = Vec::new();
+    write_csv(&table, &mut out, options).await.unwrap();
+
+    let tsv = String::from_utf8(out).unwrap();
+    assert_eq!(tsv.lines().count(), 3);
+    // Commas no longer need quoting; the quote character does not occur
+    assert!(tsv
+        .lines()
+        .any(|line| line.split('\t').any(|v| v == "Smith, John")));
+}
+
+#[tokio::test]
+async fn test_write_json_and_parquet() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(dir.path());
+    let table = PinotTable::open(dir.path()).unwrap();
+
+    let mut out = Vec::new();
+    assert_eq!(write_json(&table, &mut out).await.unwrap(), 3);
+    let json = String::from_utf8(out).unwrap();
+    assert_eq!(json.lines().count(), 3);
+    assert!(json.contains(r#""name":"Smith, John""#));
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let path = out_dir.path().join("players.parquet");
+    let file = std::fs::File::create(&path).unwrap();
+    assert_eq!(write_parquet(&table, file).await.unwrap(), 3);
+
+    let ctx = SessionContext::new();
+    let df = ctx
+        .read_parquet(path.to_str().unwrap(), ParquetReadOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(df.count().await.unwrap(), 3);
+}
+
+#[test]
+fn test_export_format_from_str() {
+    assert_eq!("CSV".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+    assert_eq!(
+        "parquet".parse::<ExportFormat>().unwrap(),
+        ExportFormat::Parquet
+    );
+    assert!("xml".parse::<ExportFormat>().is_err());
+}
+
+#[test]
+fn test_export_command() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pinot"))
+        .args(["export", "--format", "csv", "--segment"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let csv = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(csv.lines().count(), 4);
+    assert!(csv.contains("\"Smith, John\""));
+
+    // A table directory holds one `<segment>/v3` directory per segment
+    let table_dir = tempfile::tempdir().unwrap();
+    write_segment(&table_dir.path().join("players_0").join("v3"));
+    let out_dir = tempfile::tempdir().unwrap();
+    let path = out_dir.path().join("players.json");
+    let status = Command::new(env!("CARGO_BIN_EXE_pinot"))
+        .args(["export", "--format", "json", "--table"])
+        .arg(table_dir.path())
+        .arg("--output")
+        .arg(&path)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pinot"))
+        .args(["export", "--format", "xml", "--segment", "/nonexistent"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
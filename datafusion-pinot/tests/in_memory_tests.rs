@@ -0,0 +1,90 @@
+use datafusion::arrow::array::{Int32Array, Int64Array, RecordBatch, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::prelude::*;
+use datafusion_pinot::PinotTable;
+use std::sync::Arc;
+
+fn schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, true),
+        Field::new("team", DataType::Utf8, false),
+    ]))
+}
+
+fn batch(ids: Vec<Option<i32>>, teams: Vec<&str>) -> RecordBatch {
+    RecordBatch::try_new(
+        schema(),
+        vec![
+            Arc::new(Int32Array::from(ids)),
+            Arc::new(StringArray::from(teams)),
+        ],
+    )
+    .unwrap()
+}
+
+fn table() -> PinotTable {
+    PinotTable::from_record_batches(
+        schema(),
+        vec![
+            batch(vec![Some(1), Some(2), Some(3)], vec!["A", "B", "A"]),
+            batch(vec![Some(4), None], vec!["C", "A"]),
+            batch(
+                vec![Some(6), Some(7), None, Some(9)],
+                vec!["B", "B", "C", "A"],
+            ),
+        ],
+        "players",
+    )
+    .unwrap()
+}
+
+async fn query_i64(ctx: &SessionContext, sql: &str) -> i64 {
+    let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap()
+        .value(0)
+}
+
+#[tokio::test]
+async fn test_count_over_record_batches() {
+    let table = table();
+    assert_eq!(table.num_segments(), 3);
+    assert_eq!(table.total_docs(), 9);
+
+    let ctx = SessionContext::new();
+    ctx.register_table("players", Arc::new(table)).unwrap();
+
+    assert_eq!(query_i64(&ctx, "SELECT COUNT(*) FROM players").await, 9);
+    assert_eq!(query_i64(&ctx, "SELECT COUNT(id) FROM players").await, 7);
+    assert_eq!(
+        query_i64(&ctx, "SELECT COUNT(*) FROM players WHERE team = 'A'").await,
+        4
+    );
+    assert_eq!(
+        query_i64(&ctx, "SELECT COUNT(*) FROM players WHERE id IS NULL").await,
+        2
+    );
+    assert_eq!(
+        query_i64(&ctx, "SELECT COUNT(team) FROM players WHERE id IS NOT NULL").await,
+        7
+    );
+    assert_eq!(
+        query_i64(&ctx, "SELECT CAST(SUM(id) AS BIGINT) FROM players").await,
+        32
+    );
+}
+
+#[tokio::test]
+async fn test_record_batches_must_match_schema() {
+    let other = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+    let mismatched =
+        RecordBatch::try_new(other, vec![Arc::new(Int64Array::from(vec![1i64]))]).unwrap();
+
+    let err = PinotTable::from_record_batches(schema(), vec![mismatched], "players").unwrap_err();
+    assert!(err.to_string().contains("no column id"), "{}", err);
+
+    assert!(PinotTable::from_record_batches(schema(), vec![], "players").is_err());
+}
@@ -0,0 +1,78 @@
+use datafusion::arrow::array::{Int32Array, RecordBatch};
+use datafusion::arrow::util::pretty::pretty_format_batches;
+use datafusion::prelude::*;
+use datafusion_pinot::PinotTable;
+use pinot_segment::testutil::SegmentBuilder;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Segment sorted on `ts` but not on `score`
+fn write_segment(dir: &Path, name: &str, start: i32) -> PathBuf {
+    let path = dir.join(name);
+    SegmentBuilder::new("events", name)
+        .int_column("ts", (start..start + 50).collect())
+        .int_column("score", (0..50).map(|i| (i * 37) % 11).collect())
+        .write(&path)
+        .unwrap();
+    path
+}
+
+async fn physical_plan(ctx: &SessionContext, sql: &str) -> String {
+    let batches = ctx
+        .sql(&format!("EXPLAIN {}", sql))
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    pretty_format_batches(&batches).unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_sort_on_sorted_column_is_eliminated() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_segment(dir.path(), "events_0", 0);
+
+    let ctx = SessionContext::new();
+    let table = PinotTable::open(&path).unwrap();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+
+    let plan = physical_plan(&ctx, "SELECT ts, score FROM events ORDER BY ts").await;
+    assert!(!plan.contains("SortExec"), "{}", plan);
+
+    let plan = physical_plan(&ctx, "SELECT ts, score FROM events ORDER BY score").await;
+    assert!(plan.contains("SortExec"), "{}", plan);
+    let plan = physical_plan(&ctx, "SELECT ts FROM events ORDER BY ts DESC").await;
+    assert!(plan.contains("SortExec"), "{}", plan);
+}
+
+#[tokio::test]
+async fn test_sorted_segments_are_merged() {
+    let dir = tempfile::tempdir().unwrap();
+    // Segment ranges overlap, so the merge has to interleave them
+    let paths = vec![
+        write_segment(dir.path(), "events_0", 30),
+        write_segment(dir.path(), "events_1", 0),
+    ];
+
+    let ctx = SessionContext::new();
+    let table = PinotTable::open_segments(&paths, "events").unwrap();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+
+    let sql = "SELECT ts FROM events ORDER BY ts";
+    let plan = physical_plan(&ctx, sql).await;
+    assert!(plan.contains("SortPreservingMergeExec"), "{}", plan);
+    assert!(!plan.contains("SortExec:"), "{}", plan);
+
+    let batches: Vec<RecordBatch> = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    let ts: Vec<i32> = batches
+        .iter()
+        .flat_map(|b| {
+            let ts = b.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+            ts.values().to_vec()
+        })
+        .collect();
+    let mut expected: Vec<i32> = (30..80).chain(0..50).collect();
+    expected.sort();
+    assert_eq!(ts, expected);
+}
@@ -0,0 +1,203 @@
+use datafusion::arrow::array::Int64Array;
+use datafusion::physical_plan::{collect, displayable, ExecutionPlan};
+use datafusion::prelude::*;
+use datafusion_pinot::exec::PinotExec;
+use datafusion_pinot::PinotTable;
+use pinot_segment::testutil::SegmentBuilder;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Three daily segments with time metadata plus one segment without it
+fn write_daily_segments(dir: &std::path::Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for day in 0..3i64 {
+        let path = dir.join(format!("events_{}", day));
+        let start = day * DAY_MS;
+        let end = start + DAY_MS - 1;
+        SegmentBuilder::new("events", &format!("events_{}", day))
+            .long_column("ts", vec![start, start + 1000, end])
+            .property("segment.time.column.name", "ts")
+            .property("segment.start.time", &start.to_string())
+            .property("segment.end.time", &end.to_string())
+            .property("segment.time.unit", "MILLISECONDS")
+            .write(&path)
+            .unwrap();
+        paths.push(path);
+    }
+
+    let path = dir.join("events_untimed");
+    SegmentBuilder::new("events", "events_untimed")
+        .long_column("ts", vec![5 * DAY_MS])
+        .write(&path)
+        .unwrap();
+    paths.push(path);
+    paths
+}
+
+fn find_pinot_exec(plan: &Arc<dyn ExecutionPlan>) -> Option<&PinotExec> {
+    if let Some(exec) = plan.as_any().downcast_ref::<PinotExec>() {
+        return Some(exec);
+    }
+    plan.children().into_iter().find_map(find_pinot_exec)
+}
+
+async fn plan_and_collect(ctx: &SessionContext, sql: &str) -> (Arc<dyn ExecutionPlan>, Vec<i64>) {
+    let plan = ctx
+        .sql(sql)
+        .await
+        .unwrap()
+        .create_physical_plan()
+        .await
+        .unwrap();
+    let batches = collect(plan.clone(), ctx.task_ctx()).await.unwrap();
+    let mut values: Vec<i64> = batches
+        .iter()
+        .flat_map(|b| {
+            b.column(0)
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap()
+                .values()
+                .to_vec()
+        })
+        .collect();
+    values.sort();
+    (plan, values)
+}
+
+#[tokio::test]
+async fn test_time_range_prunes_segments() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_daily_segments(dir.path());
+    let table = PinotTable::open_segments(&paths, "events").unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+
+    // Only day 1 overlaps; the untimed segment is never pruned
+    let sql = format!(
+        "SELECT ts FROM events WHERE ts >= {} AND ts < {}",
+        DAY_MS,
+        2 * DAY_MS
+    );
+    let (plan, values) = plan_and_collect(&ctx, &sql).await;
+    let exec = find_pinot_exec(&plan).unwrap();
+    assert_eq!(exec.pruned_segments(), 2);
+    assert_eq!(values, vec![DAY_MS, DAY_MS + 1000, 2 * DAY_MS - 1]);
+
+    let explain = displayable(plan.as_ref()).indent(true).to_string();
+    assert!(explain.contains("segments=2"), "{}", explain);
+    assert!(explain.contains("pruned_segments=2"), "{}", explain);
+
+    let metrics = exec.metrics().unwrap();
+    let pruned = metrics
+        .sum_by_name("segments_pruned")
+        .map(|m| m.as_usize())
+        .unwrap();
+    assert_eq!(pruned, 2);
+}
+
+#[tokio::test]
+async fn test_open_ended_range() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_daily_segments(dir.path());
+    let table = PinotTable::open_segments(&paths, "events").unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+
+    let sql = format!("SELECT ts FROM events WHERE ts > {}", 2 * DAY_MS + 1000);
+    let (plan, values) = plan_and_collect(&ctx, &sql).await;
+    assert_eq!(find_pinot_exec(&plan).unwrap().pruned_segments(), 2);
+    assert_eq!(values, vec![3 * DAY_MS - 1, 5 * DAY_MS]);
+
+    // Unrelated predicates never prune
+    let (plan, values) = plan_and_collect(&ctx, "SELECT ts FROM events WHERE ts <> 0").await;
+    assert_eq!(find_pinot_exec(&plan).unwrap().pruned_segments(), 0);
+    assert_eq!(values.len(), 9);
+}
+
+#[tokio::test]
+async fn test_all_segments_pruned() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_daily_segments(dir.path());
+    let table = PinotTable::open_segments(&paths[..3], "events").unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+
+    let sql = format!(
+        "SELECT ts FROM events WHERE ts BETWEEN {} AND {}",
+        10 * DAY_MS,
+        11 * DAY_MS
+    );
+    let (plan, values) = plan_and_collect(&ctx, &sql).await;
+    assert_eq!(find_pinot_exec(&plan).unwrap().pruned_segments(), 3);
+    assert!(values.is_empty());
+
+    let df = ctx
+        .sql(&format!(
+            "SELECT COUNT(*) FROM events WHERE ts < {}",
+            -DAY_MS
+        ))
+        .await
+        .unwrap();
+    let batches = df.collect().await.unwrap();
+    let count = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap()
+        .value(0);
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn test_descriptors_prune_before_opening() {
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::common::stats::Precision;
+    use datafusion::datasource::TableProvider;
+    use datafusion_pinot::{
+        FileSystemMetadataProvider, LazyPinotTable, MetadataProvider, PinotReadOptions,
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_daily_segments(&dir.path().join("events_OFFLINE"));
+    let provider = FileSystemMetadataProvider::new(dir.path());
+
+    let descriptors = provider.get_segment_infos("events").await.unwrap();
+    let names: Vec<&str> = descriptors.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, vec!["events_0", "events_1", "events_2", "events_untimed"]);
+    assert_eq!(descriptors[1].path, paths[1]);
+    assert_eq!(descriptors[1].total_docs, 3);
+    assert_eq!(descriptors[1].time_column.as_deref(), Some("ts"));
+    assert_eq!(descriptors[1].time_range_millis, Some((DAY_MS, 2 * DAY_MS - 1)));
+    assert_eq!(descriptors[3].time_range_millis, None);
+
+    // A scan that prunes day 0 never opens its (now corrupt) segment
+    std::fs::write(paths[0].join("columns.psf"), b"").unwrap();
+
+    let schema = Arc::new(Schema::new(vec![Field::new("ts", DataType::Int64, false)]));
+    let table = Arc::new(
+        LazyPinotTable::new("events", schema, paths, PinotReadOptions::default())
+            .with_segment_descriptors(descriptors),
+    );
+    assert_eq!(table.statistics().unwrap().num_rows, Precision::Inexact(10));
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", table.clone()).unwrap();
+    let sql = format!(
+        "SELECT ts FROM events WHERE ts >= {} AND ts < {}",
+        DAY_MS,
+        2 * DAY_MS
+    );
+    let (_, values) = plan_and_collect(&ctx, &sql).await;
+    assert_eq!(values, vec![DAY_MS, DAY_MS + 1000, 2 * DAY_MS - 1]);
+    assert!(!table.is_open());
+
+    // Without filters every segment is opened
+    let err = ctx.sql("SELECT ts FROM events").await.unwrap().collect().await;
+    assert!(err.is_err());
+}
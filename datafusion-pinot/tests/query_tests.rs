@@ -1,25 +1,23 @@
 use datafusion::prelude::*;
 use datafusion_pinot::PinotTable;
-use std::path::Path;
+use pinot_segment::testutil::write_baseball_stats;
 use std::sync::Arc;
+use tempfile::TempDir;
 
-const SEGMENT_DIR: &str = "/tmp/pinot/quickstart/PinotServerDataDir0/baseballStats_OFFLINE/baseballStats_OFFLINE_0_e40936cc-16f8-490e-a85f-bc61a9abee66/v3";
-
-#[tokio::test]
-async fn test_simple_select() {
-    if !Path::new(SEGMENT_DIR).exists() {
-        println!("Skipping test: segment directory not found");
-        return;
-    }
-
+/// Context with a synthetic 2500-row `baseballStats` segment registered
+fn baseball_stats_context() -> (TempDir, SessionContext) {
+    let dir = tempfile::tempdir().unwrap();
+    write_baseball_stats(dir.path(), 2500).unwrap();
     let ctx = SessionContext::new();
-
-    // Open Pinot table
-    let table = PinotTable::open(SEGMENT_DIR).expect("Failed to open Pinot table");
-
-    // Register table
+    let table = PinotTable::open(dir.path()).expect("Failed to open Pinot table");
     ctx.register_table("baseballStats", Arc::new(table))
         .expect("Failed to register table");
+    (dir, ctx)
+}
+
+#[tokio::test]
+async fn test_simple_select() {
+    let (_dir, ctx) = baseball_stats_context();
 
     // Execute simple SELECT query (use exact column names - case sensitive)
     let df = ctx
@@ -43,15 +41,7 @@ async fn test_simple_select() {
 
 #[tokio::test]
 async fn test_select_with_projection() {
-    if !Path::new(SEGMENT_DIR).exists() {
-        println!("Skipping test: segment directory not found");
-        return;
-    }
-
-    let ctx = SessionContext::new();
-    let table = PinotTable::open(SEGMENT_DIR).expect("Failed to open Pinot table");
-    ctx.register_table("baseballStats", Arc::new(table))
-        .expect("Failed to register table");
+    let (_dir, ctx) = baseball_stats_context();
 
     // Test projection (selecting specific columns)
     let df = ctx
@@ -69,15 +59,7 @@ async fn test_select_with_projection() {
 
 #[tokio::test]
 async fn test_count_query() {
-    if !Path::new(SEGMENT_DIR).exists() {
-        println!("Skipping test: segment directory not found");
-        return;
-    }
-
-    let ctx = SessionContext::new();
-    let table = PinotTable::open(SEGMENT_DIR).expect("Failed to open Pinot table");
-    ctx.register_table("baseballStats", Arc::new(table))
-        .expect("Failed to register table");
+    let (_dir, ctx) = baseball_stats_context();
 
     // Test COUNT query
     let df = ctx
@@ -90,7 +72,613 @@ async fn test_count_query() {
     // Verify count matches expected total docs
     assert_eq!(results.len(), 1, "Should have one batch");
     assert_eq!(results[0].num_rows(), 1, "Should have one row");
+    let count = results[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<datafusion::arrow::array::Int64Array>()
+        .unwrap();
+    assert_eq!(count.value(0), 2500);
 
     println!("Count query results: {:?}", results[0]);
     println!("✓ COUNT query executed successfully");
 }
+
+#[tokio::test]
+async fn test_count_multi_segment() {
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::datasource::TableProvider;
+    use datafusion::physical_plan::ExecutionPlan;
+    use datafusion_pinot::exec::PinotExec;
+    use futures::StreamExt;
+    use pinot_segment::testutil::SegmentBuilder;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut paths = Vec::new();
+    for (idx, rows) in [20_000, 5, 8192].into_iter().enumerate() {
+        let path = dir.path().join(format!("events_{}", idx));
+        SegmentBuilder::new("events", &format!("events_{}", idx))
+            .int_column("id", (0..rows).map(|v| v % 7).collect())
+            .write(&path)
+            .unwrap();
+        paths.push(path);
+    }
+    let table = PinotTable::open_segments(&paths, "events").unwrap();
+
+    // Empty projection: each partition emits a single batch carrying the doc count
+    let exec = PinotExec::new(
+        paths
+            .iter()
+            .map(|p| {
+                Arc::new(pinot_segment::SegmentReader::open(p).unwrap())
+                    as Arc<dyn datafusion_pinot::SegmentSource>
+            })
+            .collect(),
+        table.schema(),
+        Some(vec![]),
+    );
+    let task_ctx = SessionContext::new().task_ctx();
+    let mut rows_per_partition = Vec::new();
+    for partition in 0..3 {
+        let batches: Vec<_> = exec
+            .execute(partition, task_ctx.clone())
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(batches.len(), 1);
+        let batch = batches[0].as_ref().unwrap();
+        assert_eq!(batch.num_columns(), 0);
+        rows_per_partition.push(batch.num_rows());
+    }
+    assert_eq!(rows_per_partition, vec![20_000, 5, 8192]);
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    let results = ctx
+        .sql("SELECT COUNT(*) FROM events")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let count = results[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap()
+        .value(0);
+    assert_eq!(count, 28_197);
+}
+
+#[tokio::test]
+async fn test_projection_with_repeated_column() {
+    use datafusion::arrow::array::{Array, Int32Array, Int64Array, StringArray};
+    use datafusion::datasource::TableProvider;
+    use datafusion::physical_plan::ExecutionPlan;
+    use datafusion_pinot::exec::PinotExec;
+    use futures::StreamExt;
+    use pinot_segment::testutil::SegmentBuilder;
+
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("hits", vec![3, 1, 2])
+        .string_column("team", vec!["SF", "NY", "SF"])
+        .write(dir.path())
+        .unwrap();
+    let table = PinotTable::open(dir.path()).unwrap();
+    let schema = table.schema();
+    let hits_idx = schema.index_of("hits").unwrap();
+    let team_idx = schema.index_of("team").unwrap();
+
+    let exec = PinotExec::new(
+        table.segments().to_vec(),
+        schema,
+        Some(vec![hits_idx, hits_idx, team_idx]),
+    );
+    let batches: Vec<_> = exec
+        .execute(0, SessionContext::new().task_ctx())
+        .unwrap()
+        .collect()
+        .await;
+    let batch = batches[0].as_ref().unwrap();
+    assert_eq!(batch.num_columns(), 3);
+    let names: Vec<&str> = batch
+        .schema_ref()
+        .fields()
+        .iter()
+        .map(|f| f.name().as_str())
+        .collect();
+    assert_eq!(names, vec!["hits", "hits", "team"]);
+    for column in 0..2 {
+        let hits = batch
+            .column(column)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(hits.values(), &[3, 1, 2]);
+    }
+    let team = batch
+        .column(2)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(team.iter().flatten().collect::<Vec<_>>(), vec!["SF", "NY", "SF"]);
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    let results = ctx
+        .sql("SELECT hits, hits * 2 AS doubled FROM events ORDER BY hits")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let doubled = results[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(doubled.values(), &[2, 4, 6]);
+}
+
+#[tokio::test]
+async fn test_memory_limit_spills_batches() {
+    use datafusion::arrow::array::{Int32Array, StringArray};
+    use datafusion::datasource::TableProvider;
+    use datafusion::execution::memory_pool::{GreedyMemoryPool, MemoryPool};
+    use datafusion::execution::runtime_env::RuntimeEnvBuilder;
+    use datafusion::physical_plan::ExecutionPlan;
+    use datafusion_pinot::exec::PinotExec;
+    use datafusion_pinot::PinotReadOptions;
+    use futures::StreamExt;
+    use pinot_segment::testutil::SegmentBuilder;
+
+    let dir = tempfile::tempdir().unwrap();
+    let ids: Vec<i32> = (0..10_000).collect();
+    let teams: Vec<String> = ids.iter().map(|id| format!("team_{}", id % 10)).collect();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", ids.clone())
+        .string_column("team", teams.iter().map(String::as_str).collect())
+        .write(dir.path())
+        .unwrap();
+    let table = PinotTable::open(dir.path()).unwrap();
+    let options = PinotReadOptions::default().with_batch_size(1000);
+
+    let read_all = |exec: PinotExec, ctx: SessionContext| async move {
+        let batches: Vec<_> = exec
+            .execute(0, ctx.task_ctx())
+            .unwrap()
+            .map(|batch| batch.unwrap())
+            .collect()
+            .await;
+        let spilled = exec.metrics().unwrap().spill_count().unwrap_or_default();
+        (batches, spilled)
+    };
+    let check_rows = |batches: &[datafusion::arrow::array::RecordBatch]| {
+        assert_eq!(batches.len(), 10);
+        let mut read_ids = Vec::new();
+        for batch in batches {
+            let id = batch.column_by_name("id").unwrap();
+            let id = id.as_any().downcast_ref::<Int32Array>().unwrap();
+            let team = batch.column_by_name("team").unwrap();
+            let team = team.as_any().downcast_ref::<StringArray>().unwrap();
+            for row in 0..batch.num_rows() {
+                assert_eq!(team.value(row), format!("team_{}", id.value(row) % 10));
+            }
+            read_ids.extend(id.values().iter().copied());
+        }
+        assert_eq!(read_ids, ids);
+    };
+
+    // Room for one batch: left unconsumed, the producer decodes every batch
+    // and spills all but the newest, which stays in memory
+    let pool: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(usize::MAX));
+    let runtime = RuntimeEnvBuilder::new()
+        .with_memory_pool(pool.clone())
+        .build_arc()
+        .unwrap();
+    let ctx = SessionContext::new_with_config_rt(SessionConfig::new(), runtime);
+    let exec = PinotExec::new(table.segments().to_vec(), table.schema(), None)
+        .with_options(options.clone())
+        .with_memory_limit(30_000);
+    let stream = exec.execute(0, ctx.task_ctx()).unwrap();
+    let spilled_rows = || exec.metrics().unwrap().spilled_rows().unwrap_or_default();
+    for _ in 0..500 {
+        if spilled_rows() >= 9000 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    assert_eq!(spilled_rows(), 9000);
+    assert!(pool.reserved() <= 30_000, "{} bytes in memory", pool.reserved());
+    let batches: Vec<_> = stream.map(|batch| batch.unwrap()).collect().await;
+    check_rows(&batches);
+    assert_eq!(exec.metrics().unwrap().spill_count(), Some(1));
+    assert_eq!(pool.reserved(), 0);
+
+    // A limit everything fits in never spills
+    let exec = PinotExec::new(table.segments().to_vec(), table.schema(), None)
+        .with_options(options.clone())
+        .with_memory_limit(usize::MAX);
+    let (batches, spilled) = read_all(exec, SessionContext::new()).await;
+    check_rows(&batches);
+    assert_eq!(spilled, 0);
+
+    // The memory pool caps the budget too, and gets its memory back
+    let pool: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(1000));
+    let runtime = RuntimeEnvBuilder::new()
+        .with_memory_pool(pool.clone())
+        .build_arc()
+        .unwrap();
+    let ctx = SessionContext::new_with_config_rt(SessionConfig::new(), runtime);
+    let exec = PinotExec::new(table.segments().to_vec(), table.schema(), None)
+        .with_options(options)
+        .with_memory_limit(usize::MAX);
+    let (batches, spilled) = read_all(exec, ctx).await;
+    check_rows(&batches);
+    assert_eq!(spilled, 1);
+    assert_eq!(pool.reserved(), 0);
+}
+
+#[tokio::test]
+async fn test_query_bytes_column() {
+    use datafusion::arrow::array::{Array, BinaryArray, Int32Array};
+    use pinot_segment::testutil::SegmentBuilder;
+
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", vec![1, 2, 3, 4])
+        .bytes_column(
+            "payload",
+            vec![
+                vec![0xCA, 0xFE],
+                vec![],
+                vec![0x00, 0x01, 0x02],
+                vec![0xCA, 0xFE],
+            ],
+        )
+        .write(dir.path())
+        .unwrap();
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(PinotTable::open(dir.path()).unwrap()))
+        .unwrap();
+
+    let batches = ctx
+        .sql("SELECT id, payload FROM events ORDER BY id")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let payload = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .unwrap();
+    assert_eq!(payload.len(), 4);
+    assert_eq!(payload.value(0), [0xCA, 0xFE]);
+    assert!(payload.value(1).is_empty());
+    assert_eq!(payload.value(2), [0x00, 0x01, 0x02]);
+
+    let batches = ctx
+        .sql("SELECT id FROM events WHERE payload = X'CAFE' ORDER BY id")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let id = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(id.values(), &[1, 4]);
+}
+
+#[tokio::test]
+async fn test_query_raw_bytes_column() {
+    use datafusion::arrow::array::{Array, BinaryArray, Int32Array};
+    use datafusion_pinot::PinotReadOptions;
+    use pinot_segment::testutil::SegmentBuilder;
+
+    // 2500 rows: several RAW chunks and, at 1000 rows per batch, several batches
+    let payloads: Vec<Vec<u8>> = (0..2500u32).map(|i| i.to_be_bytes().to_vec()).collect();
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", (0..2500).collect())
+        .raw_bytes_column("payload", payloads.clone())
+        .write(dir.path())
+        .unwrap();
+    let table = PinotTable::open_segments_with_options(
+        &[dir.path()],
+        "events",
+        PinotReadOptions::default().with_batch_size(1000),
+    )
+    .unwrap();
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+
+    let batches = ctx
+        .sql("SELECT id, payload FROM events ORDER BY id LIMIT 4 OFFSET 998")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let mut rows = Vec::new();
+    for batch in &batches {
+        let ids = batch.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+        let payload = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        for row in 0..batch.num_rows() {
+            rows.push((ids.value(row), payload.value(row).to_vec()));
+        }
+    }
+    let expected: Vec<(i32, Vec<u8>)> = (998..1002)
+        .map(|id| (id, payloads[id as usize].clone()))
+        .collect();
+    assert_eq!(rows, expected);
+
+    let batches = ctx
+        .sql("SELECT COUNT(*) FROM events WHERE payload = X'000009C4' OR payload = X'000001F4'")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let count = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<datafusion::arrow::array::Int64Array>()
+        .unwrap();
+    assert_eq!(count.value(0), 1);
+}
+
+#[tokio::test]
+async fn test_execute_reads_ahead_of_consumer() {
+    use datafusion::arrow::array::RecordBatch;
+    use datafusion::arrow::datatypes::SchemaRef;
+    use datafusion::datasource::TableProvider;
+    use datafusion::physical_plan::ExecutionPlan;
+    use datafusion_pinot::exec::PinotExec;
+    use datafusion_pinot::{PinotReadOptions, SegmentSource};
+    use futures::StreamExt;
+    use pinot_segment::testutil::SegmentBuilder;
+    use pinot_segment::{DocIdSet, SegmentMetadata, SegmentReader, ValueRange};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc;
+
+    /// Segment that signals every batch decoded from it and records how far
+    /// decoding ran ahead of the batches the test pulled
+    #[derive(Debug)]
+    struct CountingSegment {
+        inner: SegmentReader,
+        reads: Arc<AtomicUsize>,
+        pulled: Arc<AtomicUsize>,
+        max_ahead: Arc<AtomicUsize>,
+        decoded: mpsc::Sender<()>,
+    }
+
+    impl CountingSegment {
+        fn record_read(&self) {
+            let reads = self.reads.fetch_add(1, Ordering::SeqCst) + 1;
+            let ahead = reads.saturating_sub(self.pulled.load(Ordering::SeqCst));
+            self.max_ahead.fetch_max(ahead, Ordering::SeqCst);
+            let _ = self.decoded.send(());
+        }
+    }
+
+    impl SegmentSource for CountingSegment {
+        fn metadata(&self) -> &SegmentMetadata {
+            self.inner.metadata()
+        }
+
+        fn read_batch(&self, schema: &SchemaRef) -> datafusion_pinot::Result<RecordBatch> {
+            self.record_read();
+            SegmentSource::read_batch(&self.inner, schema)
+        }
+
+        fn read_batch_range(
+            &self,
+            schema: &SchemaRef,
+            start: u32,
+            count: u32,
+        ) -> datafusion_pinot::Result<RecordBatch> {
+            self.record_read();
+            SegmentSource::read_batch_range(&self.inner, schema, start, count)
+        }
+
+        fn has_null_value_vector(&self, column_name: &str) -> bool {
+            self.inner.has_null_value_vector(column_name)
+        }
+
+        fn has_valid_doc_ids(&self) -> bool {
+            self.inner.has_valid_doc_ids()
+        }
+
+        fn read_valid_doc_ids(&self) -> datafusion_pinot::Result<Option<Vec<u32>>> {
+            SegmentSource::read_valid_doc_ids(&self.inner)
+        }
+
+        fn range_doc_ids(
+            &self,
+            column_name: &str,
+            range: &ValueRange,
+        ) -> datafusion_pinot::Result<Option<Vec<u32>>> {
+            SegmentSource::range_doc_ids(&self.inner, column_name, range)
+        }
+
+        fn null_doc_ids(&self, column_name: &str) -> datafusion_pinot::Result<DocIdSet> {
+            self.inner.null_doc_ids(column_name)
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", (0..10_000).collect())
+        .write(dir.path())
+        .unwrap();
+    let table = PinotTable::open(dir.path()).unwrap();
+    let reads = Arc::new(AtomicUsize::new(0));
+    let pulled = Arc::new(AtomicUsize::new(0));
+    let max_ahead = Arc::new(AtomicUsize::new(0));
+    let (decoded_tx, decoded) = mpsc::channel();
+    let segment = CountingSegment {
+        inner: SegmentReader::open(dir.path()).unwrap(),
+        reads: reads.clone(),
+        pulled: pulled.clone(),
+        max_ahead: max_ahead.clone(),
+        decoded: decoded_tx,
+    };
+    let exec = PinotExec::new(vec![Arc::new(segment)], table.schema(), None)
+        .with_options(PinotReadOptions::default().with_batch_size(100));
+
+    let mut stream = exec.execute(0, SessionContext::new().task_ctx()).unwrap();
+    let mut num_decoded = 0;
+    let mut num_rows = 0;
+    let mut num_batches = 0;
+    loop {
+        // Before each pull, wait for the background thread to decode the
+        // two batches it reads ahead, so it always runs as far ahead as it may
+        while num_decoded < (num_batches + 2).min(100) {
+            decoded.recv().unwrap();
+            num_decoded += 1;
+        }
+        // Counted before pulling, so a batch decoded while waiting for this
+        // one is not taken for reading further ahead
+        pulled.fetch_add(1, Ordering::SeqCst);
+        let Some(batch) = stream.next().await else {
+            break;
+        };
+        let batch = batch.unwrap();
+        if num_batches == 0 {
+            assert_eq!(batch.num_rows(), 100);
+        }
+        num_rows += batch.num_rows();
+        num_batches += 1;
+    }
+    assert_eq!((num_rows, num_batches), (10_000, 100));
+    assert_eq!(reads.load(Ordering::SeqCst), 100);
+    assert_eq!(
+        max_ahead.load(Ordering::SeqCst),
+        2,
+        "decoding ran other than two batches ahead of the consumer"
+    );
+}
+
+#[tokio::test]
+async fn test_null_value_vectors_read_once_per_segment() {
+    use datafusion::arrow::array::{Int32Array, RecordBatch};
+    use datafusion::arrow::buffer::NullBuffer;
+    use datafusion::arrow::datatypes::SchemaRef;
+    use datafusion::datasource::TableProvider;
+    use datafusion::physical_plan::{collect, ExecutionPlan};
+    use datafusion_pinot::exec::PinotExec;
+    use datafusion_pinot::{PinotReadOptions, SegmentSource};
+    use pinot_segment::testutil::SegmentBuilder;
+    use pinot_segment::{DocIdSet, SegmentMetadata, SegmentReader, ValueRange};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Segment that counts the null value vectors read from it
+    #[derive(Debug)]
+    struct CountingSegment {
+        inner: SegmentReader,
+        null_reads: Arc<AtomicUsize>,
+    }
+
+    impl SegmentSource for CountingSegment {
+        fn metadata(&self) -> &SegmentMetadata {
+            self.inner.metadata()
+        }
+
+        fn read_batch(&self, schema: &SchemaRef) -> datafusion_pinot::Result<RecordBatch> {
+            SegmentSource::read_batch(&self.inner, schema)
+        }
+
+        fn read_batch_range_with_nulls(
+            &self,
+            schema: &SchemaRef,
+            start: u32,
+            count: u32,
+            null_masks: &HashMap<String, NullBuffer>,
+        ) -> datafusion_pinot::Result<RecordBatch> {
+            self.inner
+                .read_batch_range_with_nulls(schema, start, count, null_masks)
+        }
+
+        fn has_null_value_vector(&self, column_name: &str) -> bool {
+            self.inner.has_null_value_vector(column_name)
+        }
+
+        fn has_valid_doc_ids(&self) -> bool {
+            self.inner.has_valid_doc_ids()
+        }
+
+        fn read_valid_doc_ids(&self) -> datafusion_pinot::Result<Option<Vec<u32>>> {
+            SegmentSource::read_valid_doc_ids(&self.inner)
+        }
+
+        fn range_doc_ids(
+            &self,
+            column_name: &str,
+            range: &ValueRange,
+        ) -> datafusion_pinot::Result<Option<Vec<u32>>> {
+            SegmentSource::range_doc_ids(&self.inner, column_name, range)
+        }
+
+        fn null_doc_ids(&self, column_name: &str) -> datafusion_pinot::Result<DocIdSet> {
+            self.null_reads.fetch_add(1, Ordering::SeqCst);
+            self.inner.null_doc_ids(column_name)
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", (0..1000).collect())
+        .int_column("score", (0..1000).collect())
+        .null_values("id", vec![3, 250, 999])
+        .write(dir.path())
+        .unwrap();
+    let options = PinotReadOptions::default()
+        .with_substitute_nulls(true)
+        .with_batch_size(100);
+    let table = PinotTable::open(dir.path())
+        .unwrap()
+        .with_options(options.clone());
+    let null_reads = Arc::new(AtomicUsize::new(0));
+    let segment = CountingSegment {
+        inner: SegmentReader::open(dir.path()).unwrap(),
+        null_reads: null_reads.clone(),
+    };
+    let exec: Arc<dyn ExecutionPlan> =
+        Arc::new(PinotExec::new(vec![Arc::new(segment)], table.schema(), None).with_options(options));
+
+    let batches = collect(exec, SessionContext::new().task_ctx()).await.unwrap();
+    assert_eq!(batches.len(), 10);
+    let ids: Vec<Option<i32>> = batches
+        .iter()
+        .flat_map(|b| {
+            let ids = b.column_by_name("id").unwrap();
+            let ids = ids.as_any().downcast_ref::<Int32Array>().unwrap();
+            ids.iter().collect::<Vec<_>>()
+        })
+        .collect();
+    let expected: Vec<Option<i32>> = (0..1000)
+        .map(|id| (![3, 250, 999].contains(&id)).then_some(id))
+        .collect();
+    assert_eq!(ids, expected);
+    // One read for the nullable column, none for the other or per window
+    assert_eq!(null_reads.load(Ordering::SeqCst), 1);
+}
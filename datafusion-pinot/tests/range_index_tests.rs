@@ -0,0 +1,80 @@
+use datafusion::arrow::array::{Int32Array, RecordBatch};
+use datafusion::prelude::*;
+use datafusion_pinot::PinotTable;
+use pinot_segment::testutil::SegmentBuilder;
+use std::path::Path;
+use std::sync::Arc;
+
+/// SQL predicate on `hits` and the same predicate evaluated in Rust
+type Case = (&'static str, fn(i32) -> bool);
+
+fn ids() -> Vec<i32> {
+    (0..500).collect()
+}
+
+fn hits() -> Vec<i32> {
+    (0..500).map(|i| (i * 7919) % 97).collect()
+}
+
+fn write_segment(dir: &Path, with_range_index: bool) {
+    let mut builder = SegmentBuilder::new("events", "events_0")
+        .int_column("id", ids())
+        .int_column("hits", hits());
+    if with_range_index {
+        builder = builder.range_index("hits", 8);
+    }
+    builder.write(dir).unwrap();
+}
+
+async fn query_ids(dir: &Path, sql: &str) -> Vec<i32> {
+    let ctx = SessionContext::new();
+    let table = PinotTable::open(dir).unwrap();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    let batches: Vec<RecordBatch> = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    batches
+        .iter()
+        .flat_map(|b| {
+            let ids = b.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+            ids.values().to_vec()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_range_index_matches_brute_force() {
+    let indexed = tempfile::tempdir().unwrap();
+    write_segment(indexed.path(), true);
+    let plain = tempfile::tempdir().unwrap();
+    write_segment(plain.path(), false);
+
+    let cases: [Case; 5] = [
+        ("hits BETWEEN 10 AND 20", |h| (10..=20).contains(&h)),
+        ("hits > 90", |h| h > 90),
+        ("hits < 5 AND hits >= 2", |h| (2..5).contains(&h)),
+        ("hits = 42", |h| h == 42),
+        ("hits > 1000", |_| false),
+    ];
+
+    for (predicate, matches) in cases {
+        let sql = format!("SELECT id FROM events WHERE {} ORDER BY id", predicate);
+        let expected: Vec<i32> = ids()
+            .into_iter()
+            .zip(hits())
+            .filter(|(_, h)| matches(*h))
+            .map(|(id, _)| id)
+            .collect();
+
+        assert_eq!(
+            query_ids(indexed.path(), &sql).await,
+            expected,
+            "{}",
+            predicate
+        );
+        assert_eq!(
+            query_ids(plain.path(), &sql).await,
+            expected,
+            "{}",
+            predicate
+        );
+    }
+}
@@ -0,0 +1,474 @@
+use datafusion::arrow::array::{Array, Int32Array, Int64Array, RecordBatch};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::catalog::CatalogProvider;
+use datafusion::datasource::TableProvider;
+use datafusion::physical_plan::collect;
+use datafusion::prelude::*;
+use datafusion_pinot::{PinotCatalog, PinotReadOptions, PinotTable};
+use pinot_segment::testutil::SegmentBuilder;
+use std::path::Path;
+use std::sync::Arc;
+
+/// One segment of ten rows: `id` 0..10 with docs 2 and 5 null, `team` strings
+fn write_segment(dir: &Path) {
+    SegmentBuilder::new("players", "players_0")
+        .int_column("id", (0..10).collect())
+        .string_column(
+            "team",
+            vec!["A", "B", "A", "C", "B", "A", "C", "A", "B", "A"],
+        )
+        .null_values("id", vec![2, 5])
+        .valid_doc_ids(vec![0, 1, 2, 3, 9])
+        .write(dir)
+        .unwrap();
+}
+
+async fn scan(table: PinotTable, sql: &str) -> Vec<RecordBatch> {
+    let ctx = SessionContext::new();
+    ctx.register_table("players", Arc::new(table)).unwrap();
+    let plan = ctx
+        .sql(sql)
+        .await
+        .unwrap()
+        .create_physical_plan()
+        .await
+        .unwrap();
+    collect(plan, ctx.task_ctx()).await.unwrap()
+}
+
+fn ids(batches: &[RecordBatch]) -> Vec<Option<i32>> {
+    batches
+        .iter()
+        .flat_map(|b| {
+            let ids = b.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+            ids.iter().collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn count(batches: &[RecordBatch]) -> i64 {
+    batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap()
+        .value(0)
+}
+
+#[tokio::test]
+async fn test_default_options_keep_plain_behavior() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(dir.path());
+    let table = PinotTable::open(dir.path()).unwrap();
+    assert_eq!(table.options(), &PinotReadOptions::default());
+    assert!(!table.schema().field_with_name("id").unwrap().is_nullable());
+
+    let batches = scan(table, "SELECT id FROM players").await;
+    assert_eq!(batches.len(), 1);
+    assert_eq!(ids(&batches), (0..10).map(Some).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_batch_size() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(dir.path());
+    let table = PinotTable::open(dir.path())
+        .unwrap()
+        .with_options(PinotReadOptions::default().with_batch_size(4));
+
+    let batches = scan(table, "SELECT id FROM players").await;
+    let sizes: Vec<usize> = batches.iter().map(|b| b.num_rows()).collect();
+    assert_eq!(sizes, vec![4, 4, 2]);
+}
+
+#[tokio::test]
+async fn test_substitute_nulls() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(dir.path());
+    let options = PinotReadOptions::default().with_substitute_nulls(true);
+    let table = PinotTable::open(dir.path()).unwrap().with_options(options);
+
+    // Only columns with a null value vector become nullable
+    assert!(table.schema().field_with_name("id").unwrap().is_nullable());
+    assert!(!table
+        .schema()
+        .field_with_name("team")
+        .unwrap()
+        .is_nullable());
+
+    let batches = scan(table, "SELECT id FROM players").await;
+    let expected: Vec<Option<i32>> = (0..10)
+        .map(|i| if i == 2 || i == 5 { None } else { Some(i) })
+        .collect();
+    assert_eq!(ids(&batches), expected);
+
+    let table = PinotTable::open(dir.path())
+        .unwrap()
+        .with_options(PinotReadOptions::default().with_substitute_nulls(true));
+    let batches = scan(table, "SELECT COUNT(id) FROM players").await;
+    assert_eq!(count(&batches), 8);
+}
+
+#[tokio::test]
+async fn test_nullable_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(dir.path());
+    let options = PinotReadOptions::default().with_nullable_schema(true);
+    let table = PinotTable::open(dir.path())
+        .unwrap()
+        .with_options(options.clone());
+    assert!(table.schema().fields().iter().all(|f| f.is_nullable()));
+
+    // Values keep full validity: the null value vector is not applied
+    let batches = scan(table, "SELECT id FROM players").await;
+    assert!(batches.iter().all(|b| b.schema().field(0).is_nullable()));
+    assert_eq!(ids(&batches), (0..10).map(Some).collect::<Vec<_>>());
+    let table = PinotTable::open(dir.path())
+        .unwrap()
+        .with_options(options.clone());
+    let batches = scan(table, "SELECT COUNT(*) FROM players WHERE id IS NULL").await;
+    assert_eq!(count(&batches), 0);
+
+    let table = PinotTable::open(dir.path())
+        .unwrap()
+        .with_options(options.with_substitute_nulls(true));
+    let batches = scan(table, "SELECT id FROM players").await;
+    assert_eq!(ids(&batches).iter().filter(|id| id.is_none()).count(), 2);
+}
+
+#[tokio::test]
+async fn test_lowercase_column_names() {
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("players", "players_0")
+        .int_column("homeRuns", vec![3, 1, 2])
+        .null_values("homeRuns", vec![1])
+        .string_column("teamID", vec!["SF", "NY", "SF"])
+        .string_column("teamid", vec!["x", "y", "z"])
+        .write(dir.path())
+        .unwrap();
+    let options = PinotReadOptions::default()
+        .with_lowercase_column_names(true)
+        .with_substitute_nulls(true);
+    let open = || {
+        PinotTable::open(dir.path())
+            .unwrap()
+            .with_options(options.clone())
+    };
+
+    // Colliding names keep their case
+    let schema = open().schema();
+    let mut names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["homeruns", "teamID", "teamid"]);
+
+    // Filters on the lowercased names reach the stored columns, including
+    // the null filter answered from the null value vector
+    let batches = scan(
+        open(),
+        "SELECT homeruns FROM players WHERE homeruns > 1 ORDER BY homeruns",
+    )
+    .await;
+    assert_eq!(batches[0].schema().field(0).name(), "homeruns");
+    assert_eq!(ids(&batches), vec![Some(2), Some(3)]);
+    let batches = scan(
+        open(),
+        "SELECT COUNT(*) FROM players WHERE homeruns IS NULL",
+    )
+    .await;
+    assert_eq!(count(&batches), 1);
+
+    // Off by default: the unquoted (lowercased) name does not resolve
+    let ctx = SessionContext::new();
+    ctx.register_table("players", Arc::new(PinotTable::open(dir.path()).unwrap()))
+        .unwrap();
+    assert!(ctx.sql("SELECT homeruns FROM players").await.is_err());
+}
+
+#[tokio::test]
+async fn test_honor_valid_doc_ids() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(dir.path());
+    let options = PinotReadOptions::default().with_honor_valid_doc_ids(true);
+
+    let table = PinotTable::open(dir.path())
+        .unwrap()
+        .with_options(options.clone());
+    let batches = scan(table, "SELECT id FROM players").await;
+    assert_eq!(
+        ids(&batches),
+        vec![Some(0), Some(1), Some(2), Some(3), Some(9)]
+    );
+
+    // COUNT(*) can no longer be answered from segment.total.docs
+    let table = PinotTable::open(dir.path()).unwrap().with_options(options);
+    let batches = scan(table, "SELECT COUNT(*) FROM players").await;
+    assert_eq!(count(&batches), 5);
+}
+
+#[tokio::test]
+async fn test_dictionary_arrays_option() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(dir.path());
+    let table = PinotTable::open(dir.path())
+        .unwrap()
+        .with_options(PinotReadOptions::default().with_dictionary_arrays(true));
+
+    let batches = scan(table, "SELECT team FROM players").await;
+    assert!(matches!(
+        batches[0].column(0).data_type(),
+        DataType::Dictionary(_, _)
+    ));
+}
+
+#[tokio::test]
+async fn test_string_view_arrays_option() {
+    let dir = tempfile::tempdir().unwrap();
+    let players: Vec<String> = (0..10).map(|i| format!("player_with_a_long_name_{}", i)).collect();
+    SegmentBuilder::new("players", "players_0")
+        .int_column("id", (0..10).collect())
+        .string_column("team", vec!["A", "B", "A", "C", "B", "A", "C", "A", "B", "A"])
+        .raw_string_column("player", players.iter().map(|s| s.as_str()).collect())
+        .write(dir.path())
+        .unwrap();
+    let sql = "SELECT team, player FROM players WHERE id >= 3 ORDER BY id";
+
+    let plain = scan(PinotTable::open(dir.path()).unwrap(), sql).await;
+    let table = PinotTable::open(dir.path()).unwrap().with_string_view_arrays(true);
+    assert_eq!(
+        table.schema().field_with_name("player").unwrap().data_type(),
+        &DataType::Utf8View
+    );
+    let batches = scan(table, sql).await;
+    for column in 0..2 {
+        assert_eq!(batches[0].column(column).data_type(), &DataType::Utf8View);
+        let values = datafusion::arrow::compute::cast(batches[0].column(column), &DataType::Utf8)
+            .unwrap();
+        assert_eq!(&values, plain[0].column(column));
+    }
+
+    let table = PinotTable::open(dir.path()).unwrap().with_options(
+        PinotReadOptions::default()
+            .with_dictionary_arrays(true)
+            .with_string_view_arrays(true),
+    );
+    let sql = "SELECT team, player FROM players WHERE player = 'player_with_a_long_name_7'";
+    let batches = scan(table, sql).await;
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+    assert!(matches!(
+        batches[0].column(0).data_type(),
+        DataType::Dictionary(_, _)
+    ));
+    assert_eq!(batches[0].column(1).data_type(), &DataType::Utf8View);
+}
+
+#[tokio::test]
+async fn test_catalog_default_options() {
+    let dir = tempfile::tempdir().unwrap();
+    write_segment(
+        &dir.path()
+            .join("players_OFFLINE")
+            .join("players_0")
+            .join("v3"),
+    );
+
+    let options = PinotReadOptions::default()
+        .with_batch_size(3)
+        .with_substitute_nulls(true);
+    let catalog = PinotCatalog::builder()
+        .filesystem(dir.path())
+        .with_read_options(options.clone())
+        .build()
+        .unwrap();
+
+    let table = catalog
+        .schema("default")
+        .unwrap()
+        .table("players")
+        .await
+        .unwrap()
+        .unwrap();
+    let table = table.as_any().downcast_ref::<PinotTable>().unwrap();
+    assert_eq!(table.options(), &options);
+
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    let batches = ctx
+        .sql("SELECT id FROM pinot.default.players")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(batches.len(), 4);
+    assert_eq!(ids(&batches).iter().filter(|id| id.is_none()).count(), 2);
+}
+
+#[tokio::test]
+async fn test_nullability_across_segments() {
+    // Only the first segment has a null value vector for `id`; `team` has
+    // none anywhere
+    let dir = tempfile::tempdir().unwrap();
+    let with_nulls = dir.path().join("players_0");
+    write_segment(&with_nulls);
+    let without_nulls = dir.path().join("players_1");
+    SegmentBuilder::new("players", "players_1")
+        .int_column("id", vec![10, 11])
+        .string_column("team", vec!["A", "B"])
+        .write(&without_nulls)
+        .unwrap();
+
+    let options = PinotReadOptions::default().with_substitute_nulls(true);
+    let open = || {
+        PinotTable::open_segments_with_options(
+            &[&with_nulls, &without_nulls],
+            "players",
+            options.clone(),
+        )
+        .unwrap()
+    };
+    let table = open();
+    assert!(table.schema().field_with_name("id").unwrap().is_nullable());
+    assert!(!table.schema().field_with_name("team").unwrap().is_nullable());
+
+    // Both segments' batches validate against the declared schema
+    let batches = scan(table, "SELECT id, team FROM players ORDER BY id NULLS FIRST").await;
+    let ids = ids(&batches);
+    assert_eq!(ids.len(), 12);
+    assert_eq!(&ids[..3], &[None, None, Some(0)]);
+    assert_eq!(ids[11], Some(11));
+
+    // UNION with a genuinely nullable source keeps the nulls of both sides
+    let batches = scan(
+        open(),
+        "SELECT count(*) FROM (SELECT id FROM players UNION ALL SELECT CAST(NULL AS INT)) \
+         WHERE id IS NULL",
+    )
+    .await;
+    assert_eq!(count(&batches), 3);
+}
+
+#[tokio::test]
+async fn test_null_filter_pushdown() {
+    let dir = tempfile::tempdir().unwrap();
+    let with_nulls = dir.path().join("players_0");
+    write_segment(&with_nulls);
+    let without_nulls = dir.path().join("players_1");
+    SegmentBuilder::new("players", "players_1")
+        .int_column("id", vec![10, 11])
+        .string_column("team", vec!["D", "E"])
+        .write(&without_nulls)
+        .unwrap();
+    let open = |options: PinotReadOptions| {
+        PinotTable::open_segments_with_options(&[&with_nulls, &without_nulls], "players", options)
+            .unwrap()
+    };
+    let options = PinotReadOptions::default().with_substitute_nulls(true);
+
+    // Answered by the null value vectors, with no filter left above the scan
+    let ctx = SessionContext::new();
+    ctx.register_table("players", Arc::new(open(options.clone())))
+        .unwrap();
+    let explain = ctx
+        .sql("EXPLAIN SELECT team FROM players WHERE id IS NULL")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let plan = datafusion::arrow::util::pretty::pretty_format_batches(&explain)
+        .unwrap()
+        .to_string();
+    assert!(!plan.contains("FilterExec"), "{}", plan);
+
+    let batches = scan(
+        open(options.clone()),
+        "SELECT id, team FROM players WHERE id IS NULL",
+    )
+    .await;
+    assert_eq!(ids(&batches), vec![None, None]);
+
+    let batches = scan(
+        open(options.clone()),
+        "SELECT id FROM players WHERE id IS NOT NULL ORDER BY id",
+    )
+    .await;
+    let expected: Vec<Option<i32>> = [0, 1, 3, 4, 6, 7, 8, 9, 10, 11]
+        .into_iter()
+        .map(Some)
+        .collect();
+    assert_eq!(ids(&batches), expected);
+
+    // Nothing projected: counted from the vectors alone
+    let batches = scan(
+        open(options.clone()),
+        "SELECT COUNT(*) FROM players WHERE id IS NULL",
+    )
+    .await;
+    assert_eq!(count(&batches), 2);
+    let batches = scan(
+        open(options.clone().with_honor_valid_doc_ids(true)),
+        "SELECT COUNT(*) FROM players WHERE id IS NOT NULL",
+    )
+    .await;
+    assert_eq!(count(&batches), 6);
+
+    // Without substitution the stored values are returned, none of them null
+    let batches = scan(
+        open(PinotReadOptions::default()),
+        "SELECT COUNT(*) FROM players WHERE id IS NULL",
+    )
+    .await;
+    assert_eq!(count(&batches), 0);
+}
+
+#[tokio::test]
+async fn test_unreadable_columns_are_excluded() {
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("players", "players_0")
+        .int_column("id", (0..10).collect())
+        .int_column("active", (0..10).map(|i| i % 2).collect())
+        .property("column.active.dataType", "BOOLEAN")
+        .write(dir.path())
+        .unwrap();
+
+    let table = PinotTable::open(dir.path()).unwrap();
+    let schema = table.schema();
+    let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["id"]);
+    let excluded = table.excluded_columns();
+    assert_eq!(excluded.len(), 1);
+    assert_eq!(excluded[0].name, "active");
+    assert_eq!(excluded[0].segment_name, "players_0");
+    assert!(excluded[0].reason.contains("Boolean"), "{}", excluded[0].reason);
+
+    // SELECT * covers the remaining columns; the excluded one is unknown
+    let ctx = SessionContext::new();
+    ctx.register_table("players", Arc::new(table)).unwrap();
+    let batches = ctx
+        .sql("SELECT * FROM players")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(ids(&batches), (0..10).map(Some).collect::<Vec<_>>());
+    let err = ctx.sql("SELECT active FROM players").await.unwrap_err();
+    assert!(err.to_string().contains("active"), "{}", err);
+
+    // Strict mode keeps the column and fails once it is scanned
+    let options = PinotReadOptions::default().with_strict_column_types(true);
+    let table = PinotTable::open(dir.path()).unwrap().with_options(options);
+    assert!(table.excluded_columns().is_empty());
+    let active = table.schema().field_with_name("active").unwrap().clone();
+    assert_eq!(active.data_type(), &DataType::Boolean);
+    let ctx = SessionContext::new();
+    ctx.register_table("players", Arc::new(table)).unwrap();
+    let err = ctx
+        .sql("SELECT active FROM players")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not yet supported"), "{}", err);
+}
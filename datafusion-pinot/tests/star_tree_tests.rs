@@ -0,0 +1,177 @@
+use datafusion::arrow::util::pretty::pretty_format_batches;
+use datafusion::prelude::*;
+use datafusion_pinot::{PinotTable, SessionContextExt};
+use pinot_segment::testutil::SegmentBuilder;
+use pinot_segment::AggFn;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const COUNTRIES: [&str; 3] = ["de", "fr", "us"];
+const BROWSERS: [&str; 4] = ["chrome", "edge", "firefox", "safari"];
+
+fn write_segment(dir: &Path, segment: i32, with_star_tree: bool) -> PathBuf {
+    let num_docs = 200;
+    let countries = (0..num_docs)
+        .map(|i| COUNTRIES[((i * 7 + segment) % 3) as usize])
+        .collect();
+    let browsers = (0..num_docs)
+        .map(|i| BROWSERS[((i * 5 + segment) % 4) as usize])
+        .collect();
+    let clicks = (0..num_docs).map(|i| (i * 31 + segment) % 53).collect();
+    let path = dir.join(format!("events_{}", segment));
+    let mut builder = SegmentBuilder::new("events", &format!("events_{}", segment))
+        .int_column("id", (0..num_docs).collect())
+        .string_column("country", countries)
+        .string_column("browser", browsers)
+        .int_column("clicks", clicks);
+    if with_star_tree {
+        builder = builder.star_tree(
+            &["country", "browser"],
+            &[
+                (AggFn::Sum, "clicks"),
+                (AggFn::Min, "clicks"),
+                (AggFn::Max, "clicks"),
+            ],
+            10,
+        );
+    }
+    builder.write(&path).unwrap();
+    path
+}
+
+fn context(segments: &[PathBuf], star_tree_rule: bool) -> SessionContext {
+    let ctx = SessionContext::new();
+    if star_tree_rule {
+        ctx.register_pinot_star_tree_rule();
+    }
+    let table = PinotTable::open_segments(segments, "events").unwrap();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    ctx
+}
+
+async fn run(ctx: &SessionContext, sql: &str) -> String {
+    let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    pretty_format_batches(&batches).unwrap().to_string()
+}
+
+async fn uses_star_tree(ctx: &SessionContext, sql: &str) -> bool {
+    run(ctx, &format!("EXPLAIN {}", sql))
+        .await
+        .contains("StarTreeExec")
+}
+
+#[tokio::test]
+async fn test_star_tree_answers_matching_aggregates() {
+    let dir = tempfile::tempdir().unwrap();
+    let segments: Vec<PathBuf> = (0..2).map(|i| write_segment(dir.path(), i, true)).collect();
+    let plain = context(&segments, false);
+    let star_tree = context(&segments, true);
+
+    let queries = [
+        "SELECT country, SUM(clicks), COUNT(*) FROM events GROUP BY country ORDER BY country",
+        "SELECT browser, country, MIN(clicks) AS low, MAX(clicks) AS high FROM events \
+         GROUP BY browser, country ORDER BY browser, country",
+        "SELECT SUM(clicks) AS total, COUNT(*) FROM events",
+        "SELECT DISTINCT browser FROM events ORDER BY browser",
+        "SELECT country, COUNT(*) AS n FROM events GROUP BY country HAVING COUNT(*) > 100 \
+         ORDER BY n DESC, country",
+    ];
+    for sql in queries {
+        assert!(uses_star_tree(&star_tree, sql).await, "{}", sql);
+        assert!(!uses_star_tree(&plain, sql).await, "{}", sql);
+        assert_eq!(
+            run(&star_tree, sql).await,
+            run(&plain, sql).await,
+            "{}",
+            sql
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_star_tree_falls_back_to_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    let segments: Vec<PathBuf> = (0..2).map(|i| write_segment(dir.path(), i, true)).collect();
+    let plain = context(&segments, false);
+    let star_tree = context(&segments, true);
+
+    let queries = [
+        // Filters select documents the pre-aggregated records cannot
+        "SELECT country, SUM(clicks) FROM events WHERE browser = 'edge' GROUP BY country \
+         ORDER BY country",
+        // Not a pre-aggregated function
+        "SELECT country, AVG(clicks) FROM events GROUP BY country ORDER BY country",
+        // Not a dimension of the tree
+        "SELECT id, SUM(clicks) FROM events GROUP BY id ORDER BY id LIMIT 5",
+        "SELECT COUNT(DISTINCT clicks) FROM events",
+    ];
+    for sql in queries {
+        assert!(!uses_star_tree(&star_tree, sql).await, "{}", sql);
+        assert_eq!(
+            run(&star_tree, sql).await,
+            run(&plain, sql).await,
+            "{}",
+            sql
+        );
+    }
+
+    // One segment without a star tree disables the rewrite for the table
+    let mixed = vec![segments[0].clone(), write_segment(dir.path(), 2, false)];
+    let sql = "SELECT country, SUM(clicks) FROM events GROUP BY country ORDER BY country";
+    assert!(!uses_star_tree(&context(&mixed, true), sql).await);
+    assert_eq!(
+        run(&context(&mixed, true), sql).await,
+        run(&context(&mixed, false), sql).await
+    );
+}
+
+#[tokio::test]
+async fn test_star_tree_picks_matching_tree() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events_0");
+    let num_docs = 120;
+    SegmentBuilder::new("events", "events_0")
+        .string_column("country", (0..num_docs).map(|i| COUNTRIES[i % 3]).collect())
+        .string_column("browser", (0..num_docs).map(|i| BROWSERS[i % 4]).collect())
+        .int_column(
+            "clicks",
+            (0..num_docs).map(|i| (i * 13 % 41) as i32).collect(),
+        )
+        .star_tree(&["country"], &[(AggFn::Sum, "clicks")], 10)
+        .star_tree(&["browser", "country"], &[(AggFn::Max, "clicks")], 10)
+        .write(&path)
+        .unwrap();
+    let plain = context(std::slice::from_ref(&path), false);
+    let star_tree = context(std::slice::from_ref(&path), true);
+    // Registering the rule again adds no second copy
+    star_tree.register_pinot_star_tree_rule();
+    let registered = star_tree
+        .state()
+        .optimizers()
+        .iter()
+        .filter(|rule| rule.name() == "pinot_star_tree")
+        .count();
+    assert_eq!(registered, 1);
+
+    // Each query is answered by the one tree holding its metric
+    for sql in [
+        "SELECT country, SUM(clicks) FROM events GROUP BY country ORDER BY country",
+        "SELECT country, MAX(clicks) FROM events GROUP BY country ORDER BY country",
+        "SELECT browser, MAX(clicks) FROM events GROUP BY browser ORDER BY browser",
+    ] {
+        assert!(uses_star_tree(&star_tree, sql).await, "{}", sql);
+        assert_eq!(
+            run(&star_tree, sql).await,
+            run(&plain, sql).await,
+            "{}",
+            sql
+        );
+    }
+    // No single tree has both metrics, or groups by browser with SUM
+    for sql in [
+        "SELECT country, SUM(clicks), MAX(clicks) FROM events GROUP BY country",
+        "SELECT browser, SUM(clicks) FROM events GROUP BY browser",
+    ] {
+        assert!(!uses_star_tree(&star_tree, sql).await, "{}", sql);
+    }
+}
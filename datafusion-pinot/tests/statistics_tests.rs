@@ -0,0 +1,164 @@
+use datafusion::arrow::array::RecordBatch;
+use datafusion::arrow::util::pretty::pretty_format_batches;
+use datafusion::physical_plan::{collect, displayable};
+use datafusion::prelude::*;
+use datafusion_pinot::PinotTable;
+use pinot_segment::testutil::SegmentBuilder;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const MIN_MAX_SQL: &str =
+    "SELECT MIN(hits), MAX(hits), MIN(avg), MAX(avg), MIN(team), MAX(team) FROM stats";
+
+/// Same query, but the filter forces a full scan instead of a statistics lookup
+const MIN_MAX_SCAN_SQL: &str = "SELECT MIN(hits), MAX(hits), MIN(avg), MAX(avg), MIN(team), MAX(team) FROM stats WHERE hits > -1000000";
+
+fn write_segments(dir: &Path, invalid_min_max: bool) -> Vec<PathBuf> {
+    let first = dir.join("stats_0");
+    SegmentBuilder::new("stats", "stats_0")
+        .int_column("hits", vec![12, -3, 40])
+        .double_column("avg", vec![0.25, 0.5, 0.125])
+        .string_column("team", vec!["BOS", "NYA", "CHN"])
+        .write(&first)
+        .unwrap();
+
+    let mut second = SegmentBuilder::new("stats", "stats_1")
+        .int_column("hits", vec![7, 262])
+        .double_column("avg", vec![0.75, 0.0625])
+        .string_column("team", vec!["ANA", "WS1"]);
+    if invalid_min_max {
+        second = second.property("column.team.minMaxValueInvalid", "true");
+    }
+    let second_path = dir.join("stats_1");
+    second.write(&second_path).unwrap();
+
+    vec![first, second_path]
+}
+
+async fn run(ctx: &SessionContext, sql: &str) -> (String, String) {
+    let plan = ctx
+        .sql(sql)
+        .await
+        .unwrap()
+        .create_physical_plan()
+        .await
+        .unwrap();
+    let explain = displayable(plan.as_ref()).indent(true).to_string();
+    let batches: Vec<RecordBatch> = collect(plan, ctx.task_ctx()).await.unwrap();
+    (
+        explain,
+        pretty_format_batches(&batches).unwrap().to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_min_max_answered_from_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_segments(dir.path(), false);
+    let table = PinotTable::open_segments(&paths, "stats").unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_table("stats", Arc::new(table)).unwrap();
+
+    let (explain, stats_result) = run(&ctx, MIN_MAX_SQL).await;
+    assert!(
+        !explain.contains("PinotExec"),
+        "MIN/MAX should not scan segments:\n{}",
+        explain
+    );
+
+    let (explain, scan_result) = run(&ctx, MIN_MAX_SCAN_SQL).await;
+    assert!(explain.contains("PinotExec"), "{}", explain);
+    assert_eq!(stats_result, scan_result);
+
+    for expected in ["-3", "262", "0.0625", "0.75", "ANA", "WS1"] {
+        assert!(stats_result.contains(expected), "{}", stats_result);
+    }
+}
+
+#[tokio::test]
+async fn test_missing_min_max_falls_back_to_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_segments(dir.path(), true);
+    let table = PinotTable::open_segments(&paths, "stats").unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_table("stats", Arc::new(table)).unwrap();
+
+    // One segment lacks trustworthy team min/max, so the query must scan
+    let (explain, stats_result) = run(&ctx, MIN_MAX_SQL).await;
+    assert!(explain.contains("PinotExec"), "{}", explain);
+
+    let (_, scan_result) = run(&ctx, MIN_MAX_SCAN_SQL).await;
+    assert_eq!(stats_result, scan_result);
+}
+
+#[tokio::test]
+async fn test_table_statistics() {
+    use datafusion::common::stats::Precision;
+    use datafusion::datasource::TableProvider;
+    use datafusion::scalar::ScalarValue;
+
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_segments(dir.path(), false);
+    let table = PinotTable::open_segments(&paths, "stats").unwrap();
+
+    let stats = table.statistics().unwrap();
+    assert_eq!(stats.num_rows, Precision::Exact(5));
+
+    let hits_idx = table.schema().index_of("hits").unwrap();
+    let hits = &stats.column_statistics[hits_idx];
+    assert_eq!(
+        hits.min_value,
+        Precision::Exact(ScalarValue::Int32(Some(-3)))
+    );
+    assert_eq!(
+        hits.max_value,
+        Precision::Exact(ScalarValue::Int32(Some(262)))
+    );
+    assert_eq!(hits.null_count, Precision::Exact(0));
+}
+
+#[test]
+fn test_estimated_size_bytes() {
+    use datafusion::common::stats::Precision;
+    use datafusion::datasource::TableProvider;
+    use pinot_segment::testutil::write_baseball_stats;
+
+    let dir = tempfile::tempdir().unwrap();
+    let paths: Vec<PathBuf> = (0..2)
+        .map(|i| {
+            let path = dir.path().join(format!("baseballStats_{}", i));
+            write_baseball_stats(&path, 5000).unwrap();
+            path
+        })
+        .collect();
+    let table = PinotTable::open_segments(&paths, "baseballStats").unwrap();
+
+    let sizes = table.segment_sizes();
+    assert_eq!(sizes.len(), 2);
+    for (size, path) in sizes.iter().zip(&paths) {
+        let file_size = std::fs::metadata(path.join("columns.psf")).unwrap().len();
+        assert!(*size > 0);
+        assert!(
+            *size > file_size / 10 && *size < file_size * 10,
+            "estimate {} vs columns.psf of {} bytes",
+            size,
+            file_size
+        );
+    }
+
+    let total = table.estimated_size_bytes();
+    assert_eq!(total, sizes.iter().sum::<u64>());
+    let stats = table.statistics().unwrap();
+    assert_eq!(stats.total_byte_size, Precision::Inexact(total as usize));
+
+    // A projection only counts the columns it reads
+    let segment = pinot_segment::SegmentReader::open(&paths[0]).unwrap();
+    let projected = table.schema().project(&[0]).unwrap();
+    let column = segment.metadata().columns[projected.field(0).name()].clone();
+    let stats = datafusion_pinot::statistics::segment_statistics(&segment, &projected);
+    let expected = segment.estimate_column_size_bytes(&column) as usize;
+    assert!(expected > 0 && (expected as u64) < sizes[0]);
+    assert_eq!(stats.total_byte_size, Precision::Inexact(expected));
+}
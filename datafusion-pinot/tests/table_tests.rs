@@ -0,0 +1,785 @@
+use datafusion::arrow::array::{Array, Int32Array, Int64Array, StringArray};
+use datafusion::datasource::TableProvider;
+use datafusion::prelude::*;
+use datafusion_pinot::{PinotCatalog, PinotReadOptions, PinotTable, SessionContextExt};
+use pinot_segment::testutil::SegmentBuilder;
+use pinot_segment::SegmentReader;
+use pinot_segment::TableType;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[test]
+fn test_segment_table_types() {
+    let dir = tempfile::tempdir().unwrap();
+    let segments = [
+        ("events", "events_OFFLINE_0"),
+        ("events", "events__0__12__20240101T0000Z"),
+        ("events_REALTIME", "events_consuming"),
+        ("events", "events_1"),
+    ];
+
+    let mut paths = Vec::new();
+    for (table_name, segment_name) in segments {
+        let path = dir.path().join(segment_name);
+        SegmentBuilder::new(table_name, segment_name)
+            .int_column("id", vec![1, 2])
+            .write(&path)
+            .unwrap();
+        paths.push(path);
+    }
+
+    let table = PinotTable::open_segments(&paths, "events").unwrap();
+    assert_eq!(
+        table.segment_table_types(),
+        vec![
+            ("events_OFFLINE_0", Some(TableType::Offline)),
+            ("events__0__12__20240101T0000Z", Some(TableType::Realtime)),
+            ("events_consuming", Some(TableType::Realtime)),
+            ("events_1", None),
+        ]
+    );
+    assert!(table.has_realtime_segments());
+
+    let offline = PinotTable::open_segments(&paths[..1], "events").unwrap();
+    assert!(!offline.has_realtime_segments());
+}
+
+/// Two segments of `events`: `v1` with `id` only, `v2` with `id` and `country`
+fn write_evolved_segments(dir: &Path) -> Vec<PathBuf> {
+    let v1 = dir.join("events_v1");
+    SegmentBuilder::new("events", "events_v1")
+        .int_column("id", vec![1, 2])
+        .write(&v1)
+        .unwrap();
+    let v2 = dir.join("events_v2");
+    SegmentBuilder::new("events", "events_v2")
+        .int_column("id", vec![3, 4, 5])
+        .string_column("country", vec!["US", "DE", "US"])
+        .write(&v2)
+        .unwrap();
+    vec![v1, v2]
+}
+
+#[tokio::test]
+async fn test_segment_with_added_column() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_evolved_segments(dir.path());
+
+    let err = PinotTable::open_segments(&paths, "events").unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("events_v2"), "{}", message);
+    assert!(message.contains("country"), "{}", message);
+
+    // Reversed order: the later segment lacks a column of the first
+    let reversed: Vec<_> = paths.iter().rev().collect();
+    let err = PinotTable::open_segments(&reversed, "events").unwrap_err();
+    assert!(err.to_string().contains("events_v1"), "{}", err);
+
+    let options = PinotReadOptions::default().with_union_schemas(true);
+    let table = PinotTable::open_segments_with_options(&paths, "events", options).unwrap();
+    let schema = table.schema();
+    assert!(!schema.field_with_name("id").unwrap().is_nullable());
+    assert!(schema.field_with_name("country").unwrap().is_nullable());
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    let batches = ctx
+        .sql("SELECT country FROM events ORDER BY id")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let countries: Vec<Option<String>> = batches
+        .iter()
+        .flat_map(|b| {
+            let column = b.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+            column
+                .iter()
+                .map(|v| v.map(str::to_string))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    assert_eq!(
+        countries,
+        vec![
+            None,
+            None,
+            Some("US".to_string()),
+            Some("DE".to_string()),
+            Some("US".to_string())
+        ]
+    );
+
+    let batches = ctx
+        .sql("SELECT COUNT(country) FROM events")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let count = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(count.value(0), 3);
+}
+
+#[test]
+fn test_segment_with_changed_column_type() {
+    let dir = tempfile::tempdir().unwrap();
+    let v1 = dir.path().join("events_v1");
+    SegmentBuilder::new("events", "events_v1")
+        .int_column("id", vec![1, 2])
+        .write(&v1)
+        .unwrap();
+    let v2 = dir.path().join("events_v2");
+    SegmentBuilder::new("events", "events_v2")
+        .long_column("id", vec![3, 4])
+        .write(&v2)
+        .unwrap();
+    let paths = [v1, v2];
+
+    let err = PinotTable::open_segments(&paths, "events").unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("events_v2"), "{}", message);
+    assert!(message.contains("'id'"), "{}", message);
+    assert!(message.contains("Int64"), "{}", message);
+
+    // A type change cannot be reconciled by a union either
+    let options = PinotReadOptions::default().with_union_schemas(true);
+    assert!(PinotTable::open_segments_with_options(&paths, "events", options).is_err());
+}
+
+#[test]
+fn test_open_segments_in_parallel() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut paths = Vec::new();
+    for i in 0..24 {
+        let segment_name = format!("events_{:02}", i);
+        let path = dir.path().join(&segment_name);
+        SegmentBuilder::new("events", &segment_name)
+            .int_column("id", vec![i])
+            .write(&path)
+            .unwrap();
+        paths.push(path);
+    }
+
+    // Segments keep the order of the given paths
+    let table = PinotTable::open_segments(&paths, "events").unwrap();
+    let expected: Vec<String> = (0..24).map(|i| format!("events_{:02}", i)).collect();
+    assert_eq!(table.segment_names(), expected);
+
+    // Every unreadable segment is reported, not just the first
+    paths.insert(3, dir.path().join("missing_a"));
+    paths.push(dir.path().join("missing_b"));
+    let message = PinotTable::open_segments(&paths, "events")
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("2 of 26 segments"), "{}", message);
+    assert!(message.contains("missing_a"), "{}", message);
+    assert!(message.contains("missing_b"), "{}", message);
+}
+
+#[tokio::test]
+async fn test_from_readers() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_evolved_segments(dir.path());
+    let readers: Vec<Arc<SegmentReader>> = paths
+        .iter()
+        .map(|p| Arc::new(SegmentReader::open(p).unwrap()))
+        .collect();
+
+    // Schemas are validated as when opening by path
+    assert!(PinotTable::from_readers(readers.clone(), "events").is_err());
+    assert!(PinotTable::from_readers(Vec::new(), "events").is_err());
+
+    let table = PinotTable::from_readers(readers[1..].to_vec(), "events").unwrap();
+    assert_eq!(table.segment_names(), vec!["events_v2"]);
+    assert_eq!(table.segment_doc_counts(), vec![("events_v2", 3)]);
+    let reader = table.segments()[0]
+        .as_any()
+        .downcast_ref::<SegmentReader>()
+        .unwrap();
+    assert_eq!(reader.total_docs(), 3);
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    let batches = ctx
+        .sql("SELECT count(*) FROM events WHERE country = 'US'")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let count = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(count.value(0), 2);
+}
+
+#[test]
+fn test_segment_infos() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("events_0");
+    SegmentBuilder::new("events", "events_0")
+        .long_column("ts", vec![1_000, 2_000])
+        .int_column("id", vec![1, 2])
+        .property("segment.time.column.name", "ts")
+        .property("segment.start.time", "1000")
+        .property("segment.end.time", "2000")
+        .property("segment.time.unit", "MILLISECONDS")
+        .creation_meta(0xCAFE_BABE, 1_700_000_000_000)
+        .write(&first)
+        .unwrap();
+    let second = dir.path().join("events_1");
+    SegmentBuilder::new("events", "events_1")
+        .long_column("ts", vec![3_000])
+        .int_column("id", vec![3])
+        .write(&second)
+        .unwrap();
+
+    let table = PinotTable::open_segments(&[&first, &second], "events").unwrap();
+    let infos = table.segment_infos();
+    assert_eq!(infos.len(), 2);
+
+    assert_eq!(infos[0].name, "events_0");
+    assert_eq!(infos[0].path.as_deref(), Some(first.as_path()));
+    assert_eq!(infos[0].total_docs, 2);
+    assert_eq!(infos[0].crc, Some(0xCAFE_BABE));
+    assert_eq!(infos[0].creation_time, Some(1_700_000_000_000));
+    assert_eq!(infos[0].time_range_millis, Some((1_000, 2_000)));
+    assert_eq!(infos[0].num_columns, 2);
+
+    // No creation.meta and no time column
+    assert_eq!(infos[1].name, "events_1");
+    assert_eq!(infos[1].path.as_deref(), Some(second.as_path()));
+    assert_eq!(infos[1].total_docs, 1);
+    assert_eq!(infos[1].crc, None);
+    assert_eq!(infos[1].creation_time, None);
+    assert_eq!(infos[1].time_range_millis, None);
+}
+
+/// `data/events_OFFLINE` holding a valid segment and one whose columns.psf
+/// was cut short
+fn write_table_with_truncated_segment(data_dir: &Path) -> PathBuf {
+    let table_dir = data_dir.join("events_OFFLINE");
+    for (segment_name, start) in [("events_0", 0), ("events_1", 10)] {
+        SegmentBuilder::new("events", segment_name)
+            .int_column("id", (start..start + 10).collect())
+            .write(&table_dir.join(segment_name).join("v3"))
+            .unwrap();
+    }
+    let psf_path = table_dir.join("events_1").join("v3").join("columns.psf");
+    let psf = std::fs::read(&psf_path).unwrap();
+    std::fs::write(&psf_path, &psf[..psf.len() / 2]).unwrap();
+    table_dir
+}
+
+#[tokio::test]
+async fn test_skip_unreadable_segments() {
+    let dir = tempfile::tempdir().unwrap();
+    let table_dir = write_table_with_truncated_segment(dir.path());
+    let paths = vec![
+        table_dir.join("events_0").join("v3"),
+        table_dir.join("events_1").join("v3"),
+    ];
+
+    // Strict by default
+    let message = PinotTable::open_table(&table_dir).unwrap_err().to_string();
+    assert!(message.contains("events_1"), "{}", message);
+
+    let options = PinotReadOptions::default().with_skip_unreadable_segments(true);
+    let table = PinotTable::open_segments_with_options(&paths, "events", options.clone()).unwrap();
+    assert_eq!(table.segment_names(), vec!["events_0"]);
+    assert_eq!(table.skipped_segments().len(), 1);
+    assert_eq!(table.skipped_segments()[0].path, paths[1]);
+    assert!(table.skipped_segments()[0].error.contains("truncated"));
+
+    // Still an error when nothing could be opened
+    assert!(
+        PinotTable::open_segments_with_options(&paths[1..], "events", options.clone()).is_err()
+    );
+
+    // Through the catalog the table stays queryable
+    let catalog = PinotCatalog::builder()
+        .filesystem(dir.path())
+        .with_read_options(options)
+        .build()
+        .unwrap();
+    let ctx = SessionContext::new();
+    ctx.register_catalog("pinot", Arc::new(catalog));
+    let batches = ctx
+        .sql("SELECT count(*) FROM pinot.default.events")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let count = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(count.value(0), 10);
+}
+
+#[tokio::test]
+async fn test_limit_bounds_segment_reads() {
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", (0..10_000).collect())
+        .string_column(
+            "team",
+            (0..10_000).map(|i| ["a", "b", "c"][i % 3]).collect(),
+        )
+        .write(dir.path())
+        .unwrap();
+
+    let ctx = SessionContext::new();
+    let table = PinotTable::open(dir.path()).unwrap();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+
+    let sql = "SELECT id, team FROM events LIMIT 10 OFFSET 5000";
+    let explain = ctx
+        .sql(&format!("EXPLAIN {}", sql))
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let plan = datafusion::arrow::util::pretty::pretty_format_batches(&explain)
+        .unwrap()
+        .to_string();
+    assert!(plan.contains("limit=5010"), "{}", plan);
+
+    let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    let ids: Vec<i32> = batches
+        .iter()
+        .flat_map(|b| {
+            let ids = b.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+            ids.values().to_vec()
+        })
+        .collect();
+    assert_eq!(ids, (5000..5010).collect::<Vec<i32>>());
+    let teams = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(teams.value(0), ["a", "b", "c"][5000 % 3]);
+}
+
+#[tokio::test]
+async fn test_segment_info_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut paths = Vec::new();
+    for (segment_name, ids) in [("events_0", vec![1, 2, 3]), ("events_1", vec![4, 5])] {
+        let path = dir.path().join(segment_name);
+        SegmentBuilder::new("events", segment_name)
+            .int_column("id", ids)
+            .write(&path)
+            .unwrap();
+        paths.push(path);
+    }
+
+    let table = PinotTable::open_segments(&paths, "events").unwrap();
+    assert!(table.schema().field_with_name("__segment_name__").is_err());
+
+    let table = table.with_segment_info_columns(true);
+    let schema = table.schema();
+    let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["id", "__segment_name__", "__segment_index__"]);
+
+    let ctx = SessionContext::new();
+    ctx.register_pinot_segment_info_rule();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    let batches = ctx
+        .sql(
+            "SELECT __segment_name__, COUNT(*) AS docs FROM events \
+             GROUP BY __segment_name__ ORDER BY __segment_name__",
+        )
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let batch = &batches[0];
+    let segment_names = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert!(segment_names.iter().all(|name| !name.unwrap().is_empty()));
+    assert_eq!(segment_names.value(0), "events_0");
+    assert_eq!(segment_names.value(1), "events_1");
+    let docs = batch
+        .column(1)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(docs.values(), &[3, 2]);
+
+    // Mixed with stored columns, filtered and limited
+    let batches = ctx
+        .sql("SELECT __segment_index__, id FROM events WHERE id > 3 ORDER BY id LIMIT 1")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let index = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(index.value(0), 1);
+}
+
+#[tokio::test]
+async fn test_segment_info_columns_hidden_from_wildcard() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events_0");
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", vec![1, 2, 3])
+        .string_column("team", vec!["a", "b", "c"])
+        .write(&path)
+        .unwrap();
+    let table = PinotTable::open_segments(&[path], "events")
+        .unwrap()
+        .with_segment_info_columns(true);
+
+    let ctx = SessionContext::new();
+    ctx.register_pinot_segment_info_rule();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+
+    // Sorted, as the table's column order follows the segment metadata
+    let column_names = |batches: &[datafusion::arrow::record_batch::RecordBatch]| {
+        let mut names = batches[0]
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+
+    for sql in [
+        "SELECT * FROM events",
+        "SELECT e.* FROM events e",
+        "SELECT * FROM (SELECT * FROM events) t ORDER BY id",
+    ] {
+        let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+        assert_eq!(column_names(&batches), vec!["id", "team"], "{}", sql);
+        assert_eq!(batches[0].num_rows(), 3, "{}", sql);
+    }
+
+    // Named next to a wildcard, the column is returned
+    let batches = ctx
+        .sql("SELECT *, __segment_name__ AS segment FROM events")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(column_names(&batches), vec!["id", "segment", "team"]);
+
+    let batches = ctx
+        .sql("DESCRIBE events")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let described = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    let mut described: Vec<_> = described.iter().map(|name| name.unwrap()).collect();
+    described.sort();
+    assert_eq!(described, vec!["id", "team"]);
+}
+
+#[tokio::test]
+async fn test_execute_merged() {
+    use futures::StreamExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut paths = Vec::new();
+    for (idx, rows) in [10_000, 3, 8192].into_iter().enumerate() {
+        let segment_name = format!("events_{}", idx);
+        let path = dir.path().join(&segment_name);
+        SegmentBuilder::new("events", &segment_name)
+            .int_column("segment", vec![idx as i32; rows])
+            .write(&path)
+            .unwrap();
+        paths.push(path);
+    }
+    let table = PinotTable::open_segments(&paths, "events").unwrap();
+
+    let batches: Vec<_> = table
+        .execute_merged(SessionContext::new().task_ctx())
+        .collect()
+        .await;
+    let mut total_rows = 0;
+    let mut segments = Vec::new();
+    for batch in batches {
+        let batch = batch.unwrap();
+        total_rows += batch.num_rows();
+        let column = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        segments.extend(column.values().iter().copied());
+    }
+    assert_eq!(total_rows as u64, table.total_docs());
+    // Segments follow one another in scan order
+    assert!(segments.is_sorted());
+    assert_eq!(segments.first(), Some(&0));
+    assert_eq!(segments.last(), Some(&2));
+}
+
+#[tokio::test]
+async fn test_lazy_segments() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut paths = Vec::new();
+    for i in 0..5 {
+        let segment_name = format!("events_{}", i);
+        let path = dir.path().join(&segment_name);
+        SegmentBuilder::new("events", &segment_name)
+            .int_column("id", vec![i, i + 10])
+            .write(&path)
+            .unwrap();
+        paths.push(path);
+    }
+    // Only the first segment is opened up front, so a broken later one goes
+    // unnoticed until the table is scanned
+    std::fs::remove_file(paths[3].join("metadata.properties")).unwrap();
+
+    let options = PinotReadOptions::default().with_lazy_segments(true);
+    let table = PinotTable::open_segments_with_options(&paths, "events", options.clone()).unwrap();
+    assert_eq!(table.num_segments(), 5);
+    assert!(table.schema().field_with_name("id").is_ok());
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    let err = ctx
+        .sql("SELECT COUNT(*) FROM events")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("events_3"), "{}", err);
+
+    let options = options.with_skip_unreadable_segments(true);
+    let table = PinotTable::open_segments_with_options(&paths, "events", options).unwrap();
+    assert!(table.skipped_segments().is_empty());
+    assert_eq!(table.total_docs(), 8);
+    assert_eq!(table.num_segments(), 4);
+    assert_eq!(table.skipped_segments().len(), 1);
+    assert_eq!(table.skipped_segments()[0].path, paths[3]);
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    let batches = ctx
+        .sql("SELECT COUNT(*) FROM events")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let count = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(count.value(0), 8);
+}
+
+#[tokio::test]
+async fn test_lazy_segments_schema_mismatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_evolved_segments(dir.path());
+
+    // The first segment alone is consistent; the mismatch shows at scan time
+    let options = PinotReadOptions::default().with_lazy_segments(true);
+    let table = PinotTable::open_segments_with_options(&paths, "events", options).unwrap();
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    let err = ctx
+        .sql("SELECT id FROM events")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("events_v2"), "{}", err);
+    assert!(err.to_string().contains("country"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_clone_registers_under_several_names() {
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", vec![1, 2, 3])
+        .write(dir.path())
+        .unwrap();
+    let table = PinotTable::open(dir.path()).unwrap();
+    let clone = table.clone();
+    // The segments are shared, not re-opened
+    assert!(Arc::ptr_eq(&table.segments()[0], &clone.segments()[0]));
+
+    let ctx = SessionContext::new();
+    ctx.register_table("events", Arc::new(table)).unwrap();
+    ctx.register_table("events_copy", Arc::new(clone)).unwrap();
+    let batches = ctx
+        .sql("SELECT (SELECT SUM(id) FROM events) + (SELECT SUM(id) FROM events_copy)")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let total = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(total.value(0), 12);
+}
+
+#[tokio::test]
+async fn test_projection_reads_only_projected_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    let columns: Vec<String> = (0..50).map(|i| format!("c{:02}", i)).collect();
+    columns
+        .iter()
+        .enumerate()
+        .fold(
+            SegmentBuilder::new("wide", "wide_0"),
+            |builder, (i, name)| {
+                builder.int_column(name, (0..100).map(|doc| doc * 100 + i as i32).collect())
+            },
+        )
+        .write(dir.path())
+        .unwrap();
+
+    // Mark every index region but c00's as compressed with a codec no reader
+    // knows, so any read of the other 49 columns fails
+    let index_map = std::fs::read_to_string(dir.path().join("index_map")).unwrap();
+    let index_map: String = index_map
+        .lines()
+        .map(|line| match line.split_once(".startOffset=") {
+            Some((region, _)) if !region.starts_with("c00.") => {
+                format!("{}\n{}.compressionType=UNREADABLE\n", line, region)
+            }
+            _ => format!("{}\n", line),
+        })
+        .collect();
+    std::fs::write(dir.path().join("index_map"), index_map).unwrap();
+
+    let ctx = SessionContext::new();
+    let table = PinotTable::open(dir.path()).unwrap();
+    assert_eq!(table.schema().fields().len(), 50);
+    ctx.register_table("wide", Arc::new(table)).unwrap();
+
+    let batches = ctx
+        .sql("SELECT c00 FROM wide WHERE c00 >= 5000")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let values: Vec<i32> = batches
+        .iter()
+        .flat_map(|b| {
+            let values = b.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+            values.values().to_vec()
+        })
+        .collect();
+    assert_eq!(values, (50..100).map(|doc| doc * 100).collect::<Vec<i32>>());
+
+    let count = ctx
+        .sql("SELECT COUNT(*) FROM wide")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let count = count[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(count.value(0), 100);
+
+    // The trap is live: reading another column does fail
+    let err = ctx
+        .sql("SELECT c01 FROM wide")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("UNREADABLE"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_column_allow_list() {
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("users", "users_0")
+        .int_column("id", vec![1, 2, 3])
+        .string_column("name", vec!["a", "b", "c"])
+        .int_column("age", vec![30, 40, 50])
+        .string_column("email", vec!["a@x", "b@x", "c@x"])
+        .write(dir.path())
+        .unwrap();
+
+    let table = PinotTable::open(dir.path()).unwrap();
+    let err = table.clone().with_columns(&["id", "phone"]).unwrap_err();
+    assert!(err.to_string().contains("phone"), "{}", err);
+
+    // Allowed columns keep the table's column order
+    let expected: Vec<String> = table
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .filter(|name| name == "id" || name == "age")
+        .collect();
+    let table = table.with_columns(&["age", "id"]).unwrap();
+    let names: Vec<String> = table
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .collect();
+    assert_eq!(names, expected);
+
+    let ctx = SessionContext::new();
+    ctx.register_table("users", Arc::new(table)).unwrap();
+    let batches = ctx
+        .sql("SELECT * FROM users")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(batches[0].num_columns(), 2);
+    let ages = batches[0]
+        .column_by_name("age")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(ages.values(), &[30, 40, 50]);
+
+    // Columns left out cannot be queried
+    assert!(ctx.sql("SELECT email FROM users").await.is_err());
+}
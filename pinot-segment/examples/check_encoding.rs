@@ -8,37 +8,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let reader = SegmentReader::open(segment_path)?;
     let metadata = reader.metadata();
 
-    println!("Segment: {}", metadata.segment_name);
-    println!("Total docs: {}\n", metadata.total_docs);
+    println!("{}\n", metadata);
 
-    // Check playerName encoding
-    if let Ok(col) = metadata.get_column("playerName") {
-        println!("playerName column:");
-        println!("  Data type: {:?}", col.data_type);
-        println!("  Cardinality: {}", col.cardinality);
-        println!("  Has dictionary: {}", col.has_dictionary);
-        println!("  Total docs: {}", col.total_docs);
-        println!();
-    }
-
-    // Check homeRuns encoding
-    if let Ok(col) = metadata.get_column("homeRuns") {
-        println!("homeRuns column:");
-        println!("  Data type: {:?}", col.data_type);
-        println!("  Cardinality: {}", col.cardinality);
-        println!("  Has dictionary: {}", col.has_dictionary);
-        println!("  Total docs: {}", col.total_docs);
-        println!();
-    }
-
-    // Check playerID too
-    if let Ok(col) = metadata.get_column("playerID") {
-        println!("playerID column:");
-        println!("  Data type: {:?}", col.data_type);
-        println!("  Cardinality: {}", col.cardinality);
-        println!("  Has dictionary: {}", col.has_dictionary);
-        println!("  Total docs: {}", col.total_docs);
-        println!();
+    for name in ["playerName", "homeRuns", "playerID"] {
+        if let Ok(col) = metadata.get_column(name) {
+            println!("{}", col);
+        }
     }
 
     Ok(())
@@ -18,21 +18,7 @@ fn main() {
     // Print metadata
     let metadata = reader.metadata();
     println!("\n=== Segment Metadata ===");
-    println!("Table name: {}", metadata.table_name);
-    println!("Segment name: {}", metadata.segment_name);
-    println!("Total docs: {}", metadata.total_docs);
-    println!("Number of columns: {}", metadata.columns.len());
-
-    println!("\n=== Columns ===");
-    let mut columns: Vec<_> = metadata.columns.values().collect();
-    columns.sort_by_key(|c| &c.name);
-
-    for col in columns.iter() {
-        println!(
-            "  {} ({:?}): cardinality={}, has_dict={}, bits_per_elem={}",
-            col.name, col.data_type, col.cardinality, col.has_dictionary, col.bits_per_element
-        );
-    }
+    println!("{}", metadata);
 
     // Read some sample data
     println!("\n=== Sample Data (first 10 rows) ===");
@@ -1,33 +1,45 @@
-use std::fmt;
 use std::io;
 
-#[derive(Debug)]
+#[derive(Debug, thiserror::Error)]
 pub enum Error {
-    Io(io::Error),
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Parse error: {0}")]
     Parse(String),
+    #[error("Invalid format: {0}")]
     InvalidFormat(String),
+    #[error("Unsupported feature: {0}")]
     UnsupportedFeature(String),
+    #[error("Column not found: {0}")]
     ColumnNotFound(String),
 }
 
-impl fmt::Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Error::Io(e) => write!(f, "I/O error: {}", e),
-            Error::Parse(msg) => write!(f, "Parse error: {}", msg),
-            Error::InvalidFormat(msg) => write!(f, "Invalid format: {}", msg),
-            Error::UnsupportedFeature(msg) => write!(f, "Unsupported feature: {}", msg),
-            Error::ColumnNotFound(name) => write!(f, "Column not found: {}", name),
-        }
+impl Error {
+    /// Whether a file the read needed does not exist
+    ///
+    /// Distinguishes absent (e.g. optional index) files from corrupt ones,
+    /// which surface as the other variants.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Error::Io(e) if e.kind() == io::ErrorKind::NotFound)
     }
 }
 
-impl std::error::Error for Error {}
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_io_error_source_and_not_found() {
+        let err = Error::from(io::Error::new(io::ErrorKind::NotFound, "columns.psf"));
+        assert!(err.is_not_found());
+        let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
 
-impl From<io::Error> for Error {
-    fn from(err: io::Error) -> Self {
-        Error::Io(err)
+        let denied = Error::from(io::Error::new(io::ErrorKind::PermissionDenied, "denied"));
+        assert!(!denied.is_not_found());
+        assert!(!Error::InvalidFormat("truncated".to_string()).is_not_found());
     }
 }
-
-pub type Result<T> = std::result::Result<T, Error>;
@@ -1,11 +1,18 @@
 use crate::error::{Error, Result};
+use crate::forward_index::Utf8Policy;
 use crate::metadata::DataType;
+use crate::range_index::{RangeValue, ValueRange};
+use std::cmp::Ordering;
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
 
 const MAGIC_MARKER: u64 = 0xDEADBEEFDEAFBEAD;
 
+/// Highest front-coded dictionary version; version 0 shares prefixes with
+/// the first entry of each bucket, version 1 with the previous entry
+const FRONT_CODED_MAX_VERSION: u8 = 1;
+
 #[derive(Debug)]
 pub enum DictionaryValue {
     Int(Vec<i32>),
@@ -13,32 +20,173 @@ pub enum DictionaryValue {
     Float(Vec<f32>),
     Double(Vec<f64>),
     String(Vec<String>),
+    Bytes(Vec<Vec<u8>>),
 }
 
 pub struct DictionaryReader {
+    data_type: DataType,
     values: DictionaryValue,
 }
 
+/// Decode one STRING dictionary entry
+fn decode_entry(bytes: Vec<u8>, utf8_policy: Utf8Policy) -> Result<String> {
+    utf8_policy
+        .decode(bytes)
+        .map_err(|e| Error::Parse(format!("Invalid UTF-8 in dictionary: {}", e)))
+}
+
+/// Cursor over a front-coded dictionary region
+struct FrontCodedCursor<'a> {
+    region: &'a [u8],
+    pos: usize,
+}
+
+impl FrontCodedCursor<'_> {
+    fn truncated(&self) -> Error {
+        Error::InvalidFormat(format!(
+            "Front-coded dictionary truncated at byte {} of {}",
+            self.pos,
+            self.region.len()
+        ))
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&[u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.region.len())
+            .ok_or_else(|| self.truncated())?;
+        let bytes = &self.region[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32_le(&mut self) -> Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Variable-byte integer: 7-bit groups, least significant first, the
+    /// last byte flagged by its high bit
+    fn vbyte(&mut self) -> Result<usize> {
+        let mut value = 0usize;
+        for shift in (0..35).step_by(7) {
+            let b = self.byte()?;
+            value |= ((b & 0x7F) as usize) << shift;
+            if b & 0x80 != 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::InvalidFormat(format!(
+            "Front-coded dictionary integer at byte {} is longer than 5 bytes",
+            self.pos
+        )))
+    }
+
+    /// Length-prefixed byte string
+    fn blob(&mut self) -> Result<&[u8]> {
+        let len = self.vbyte()?;
+        self.bytes(len)
+    }
+}
+
+/// Read a variable-length entry: a 4-byte big-endian length, then the bytes
+///
+/// The length is checked against the `remaining` bytes of the region before
+/// allocating, so a corrupt prefix fails instead of requesting up to 4 GiB.
+fn read_prefixed_entry<R: Read>(file: &mut R, remaining: &mut usize) -> Result<Vec<u8>> {
+    let mut len_bytes = [0u8; 4];
+    file.read_exact(&mut len_bytes)?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+
+    let available = remaining.saturating_sub(len_bytes.len());
+    if len > available {
+        return Err(Error::InvalidFormat(format!(
+            "Dictionary entry of {} bytes exceeds the {} bytes left in the region",
+            len, available
+        )));
+    }
+    *remaining = available - len;
+
+    let mut entry = vec![0u8; len];
+    file.read_exact(&mut entry)?;
+    Ok(entry)
+}
+
 impl DictionaryReader {
     /// Read dictionary from columns.psf file at given offset
     pub fn read(
         file_path: &Path,
         offset: usize,
-        _size: usize,
+        size: usize,
         data_type: &DataType,
         cardinality: u32,
         length_of_each_entry: usize,
+        utf8_policy: Utf8Policy,
     ) -> Result<Self> {
         let mut file = File::open(file_path)?;
 
         // Seek to the dictionary offset
         file.seek(SeekFrom::Start(offset as u64))?;
 
-        // Read and verify magic marker (8 bytes, big-endian)
+        Self::parse(
+            &mut file,
+            size,
+            data_type,
+            cardinality,
+            length_of_each_entry,
+            utf8_policy,
+        )
+    }
+
+    /// Read a dictionary from an in-memory region (starting at the magic marker)
+    ///
+    /// Used for index regions that are stored compressed inside columns.psf.
+    pub fn from_bytes(
+        region: &[u8],
+        data_type: &DataType,
+        cardinality: u32,
+        length_of_each_entry: usize,
+        utf8_policy: Utf8Policy,
+    ) -> Result<Self> {
+        Self::parse(
+            &mut Cursor::new(region),
+            region.len(),
+            data_type,
+            cardinality,
+            length_of_each_entry,
+            utf8_policy,
+        )
+    }
+
+    fn parse<R: Read>(
+        file: &mut R,
+        size: usize,
+        data_type: &DataType,
+        cardinality: u32,
+        length_of_each_entry: usize,
+        utf8_policy: Utf8Policy,
+    ) -> Result<Self> {
+        // Front-coded dictionaries start with their version byte instead of
+        // the magic marker, whose first byte is 0xDE
         let mut magic_bytes = [0u8; 8];
-        file.read_exact(&mut magic_bytes)?;
-        let magic = u64::from_be_bytes(magic_bytes);
+        file.read_exact(&mut magic_bytes[..1])?;
+        if magic_bytes[0] <= FRONT_CODED_MAX_VERSION
+            && matches!(data_type, DataType::String | DataType::Bytes)
+        {
+            let mut region = magic_bytes[..1].to_vec();
+            file.take(size.saturating_sub(1) as u64)
+                .read_to_end(&mut region)?;
+            return Self::parse_front_coded(&region, data_type, cardinality, utf8_policy);
+        }
 
+        // Read and verify magic marker (8 bytes, big-endian)
+        file.read_exact(&mut magic_bytes[1..])?;
+        let magic = u64::from_be_bytes(magic_bytes);
         if magic != MAGIC_MARKER {
             return Err(Error::InvalidFormat(format!(
                 "Invalid magic marker: expected 0x{:X}, got 0x{:X}",
@@ -46,6 +194,9 @@ impl DictionaryReader {
             )));
         }
 
+        // Bytes left in the region, to bound length-prefixed entries
+        let mut remaining = size.saturating_sub(magic_bytes.len());
+
         // Read dictionary values based on data type
         let values = match data_type {
             DataType::Int => {
@@ -93,39 +244,54 @@ impl DictionaryReader {
                         let mut str_bytes = vec![0u8; length_of_each_entry];
                         file.read_exact(&mut str_bytes)?;
 
-                        // Trim trailing null bytes (padding)
-                        let end = str_bytes.iter().position(|&b| b == 0).unwrap_or(str_bytes.len());
-                        let trimmed = &str_bytes[..end];
+                        // Trim the trailing null bytes the entry was padded with
+                        let end = str_bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+                        str_bytes.truncate(end);
 
-                        let s = String::from_utf8(trimmed.to_vec()).map_err(|e| {
-                            Error::Parse(format!("Invalid UTF-8 in dictionary: {}", e))
-                        })?;
-                        values.push(s);
+                        values.push(decode_entry(str_bytes, utf8_policy)?);
                     }
                 } else {
                     // Variable-length strings (with 4-byte length prefixes)
                     for _ in 0..cardinality {
-                        // Read length (4 bytes, big-endian)
-                        let mut len_bytes = [0u8; 4];
-                        file.read_exact(&mut len_bytes)?;
-                        let len = u32::from_be_bytes(len_bytes) as usize;
-
-                        // Read string bytes
-                        let mut str_bytes = vec![0u8; len];
-                        file.read_exact(&mut str_bytes)?;
-                        let s = String::from_utf8(str_bytes).map_err(|e| {
-                            Error::Parse(format!("Invalid UTF-8 in dictionary: {}", e))
-                        })?;
-                        values.push(s);
+                        let str_bytes = read_prefixed_entry(file, &mut remaining)?;
+                        values.push(decode_entry(str_bytes, utf8_policy)?);
                     }
                 }
 
                 DictionaryValue::String(values)
             }
             DataType::Bytes => {
-                return Err(Error::UnsupportedFeature(
-                    "BYTES dictionary not yet supported".to_string(),
-                ))
+                if length_of_each_entry > 0 {
+                    // Fixed-length entries, kept as stored (no padding to trim).
+                    // The total is checked against the region before
+                    // allocating, as corrupt metadata could ask for any size.
+                    let total = (cardinality as usize)
+                        .checked_mul(length_of_each_entry)
+                        .filter(|&total| total <= remaining)
+                        .ok_or_else(|| {
+                            Error::InvalidFormat(format!(
+                                "{} dictionary entries of {} bytes exceed the {} bytes left in the region",
+                                cardinality, length_of_each_entry, remaining
+                            ))
+                        })?;
+                    let mut all_bytes = vec![0u8; total];
+                    file.read_exact(&mut all_bytes)?;
+                    DictionaryValue::Bytes(
+                        all_bytes
+                            .chunks_exact(length_of_each_entry)
+                            .map(<[u8]>::to_vec)
+                            .collect(),
+                    )
+                } else {
+                    // Variable-length entries (with 4-byte length prefixes),
+                    // each taking at least its prefix from the region
+                    let capacity = (cardinality as usize).min(remaining / 4);
+                    let mut values = Vec::with_capacity(capacity);
+                    for _ in 0..cardinality {
+                        values.push(read_prefixed_entry(file, &mut remaining)?);
+                    }
+                    DictionaryValue::Bytes(values)
+                }
             }
             DataType::Boolean => {
                 return Err(Error::UnsupportedFeature(
@@ -134,7 +300,219 @@ impl DictionaryReader {
             }
         };
 
-        Ok(DictionaryReader { values })
+        Ok(DictionaryReader {
+            data_type: data_type.clone(),
+            values,
+        })
+    }
+
+    /// Parse a front-coded (prefix-compressed) STRING or BYTES dictionary
+    ///
+    /// Layout of the `FrontCodedIndexed` Pinot adopted from Druid: a
+    /// version byte, the bucket size (a power of two) and a has-null byte,
+    /// then the number of entries and the byte size of what follows as
+    /// variable-byte integers. Next come the little-endian 4-byte end
+    /// offsets of every bucket but the last, relative to the first bucket,
+    /// and the buckets. A bucket stores its first entry whole (length,
+    /// bytes), and each other entry as the length of the prefix it shares
+    /// with the bucket's first entry (version 0) or the previous entry
+    /// (version 1), then the rest of its bytes (length, bytes). Every length
+    /// is a variable-byte integer.
+    fn parse_front_coded(
+        region: &[u8],
+        data_type: &DataType,
+        cardinality: u32,
+        utf8_policy: Utf8Policy,
+    ) -> Result<Self> {
+        let mut cursor = FrontCodedCursor { region, pos: 0 };
+        let version = cursor.byte()?;
+        let bucket_size = cursor.byte()? as usize;
+        if !bucket_size.is_power_of_two() {
+            return Err(Error::InvalidFormat(format!(
+                "Front-coded dictionary bucket size {} is not a power of two",
+                bucket_size
+            )));
+        }
+        if cursor.byte()? != 0 {
+            return Err(Error::UnsupportedFeature(
+                "Front-coded dictionary with a null entry".to_string(),
+            ));
+        }
+        let num_values = cursor.vbyte()?;
+        if num_values != cardinality as usize {
+            return Err(Error::InvalidFormat(format!(
+                "Front-coded dictionary holds {} entries, expected {}",
+                num_values, cardinality
+            )));
+        }
+        let _section_size = cursor.vbyte()?;
+
+        let num_buckets = num_values.div_ceil(bucket_size);
+        let mut bucket_ends = Vec::with_capacity(num_buckets.saturating_sub(1));
+        for _ in 1..num_buckets {
+            bucket_ends.push(cursor.u32_le()? as usize);
+        }
+        let buckets_start = cursor.pos;
+
+        let mut entries: Vec<Vec<u8>> = Vec::with_capacity(num_values);
+        for bucket in 0..num_buckets {
+            let first_idx = entries.len();
+            let bucket_len = bucket_size.min(num_values - first_idx);
+            entries.push(cursor.blob()?.to_vec());
+            for idx in first_idx + 1..first_idx + bucket_len {
+                let prefix_len = cursor.vbyte()?;
+                let base = match version {
+                    0 => &entries[first_idx],
+                    _ => &entries[idx - 1],
+                };
+                let prefix = base.get(..prefix_len).ok_or_else(|| {
+                    Error::InvalidFormat(format!(
+                        "Front-coded dictionary entry {} shares {} bytes with a {}-byte entry",
+                        idx,
+                        prefix_len,
+                        base.len()
+                    ))
+                })?;
+                let mut entry = prefix.to_vec();
+                entry.extend_from_slice(cursor.blob()?);
+                entries.push(entry);
+            }
+            if let Some(&end) = bucket_ends.get(bucket) {
+                if cursor.pos - buckets_start != end {
+                    return Err(Error::InvalidFormat(format!(
+                        "Front-coded dictionary bucket {} ends at byte {}, its offset says {}",
+                        bucket,
+                        cursor.pos - buckets_start,
+                        end
+                    )));
+                }
+            }
+        }
+
+        let values = match data_type {
+            DataType::String => DictionaryValue::String(
+                entries
+                    .into_iter()
+                    .map(|entry| decode_entry(entry, utf8_policy))
+                    .collect::<Result<_>>()?,
+            ),
+            _ => DictionaryValue::Bytes(entries),
+        };
+
+        Ok(DictionaryReader {
+            data_type: data_type.clone(),
+            values,
+        })
+    }
+
+    /// Number of entries (the column's cardinality)
+    pub fn len(&self) -> usize {
+        match &self.values {
+            DictionaryValue::Int(values) => values.len(),
+            DictionaryValue::Long(values) => values.len(),
+            DictionaryValue::Float(values) => values.len(),
+            DictionaryValue::Double(values) => values.len(),
+            DictionaryValue::String(values) => values.len(),
+            DictionaryValue::Bytes(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Data type of the dictionary values
+    pub fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    /// Whether an INT dictionary contains `value`
+    ///
+    /// Relies on Pinot storing dictionaries sorted ascending.
+    pub fn contains_int(&self, value: i32) -> bool {
+        match &self.values {
+            DictionaryValue::Int(values) => values.binary_search(&value).is_ok(),
+            _ => false,
+        }
+    }
+
+    /// Dict id of `value` in a STRING dictionary (reverse lookup)
+    ///
+    /// Relies on Pinot storing dictionaries sorted ascending. Pinot sorts by
+    /// UTF-16 code units, which matches the byte order used here for all
+    /// characters in the Basic Multilingual Plane.
+    pub fn lookup_string(&self, value: &str) -> Option<u32> {
+        match &self.values {
+            DictionaryValue::String(values) => values
+                .binary_search_by(|entry| entry.as_str().cmp(value))
+                .ok()
+                .map(|idx| idx as u32),
+            _ => None,
+        }
+    }
+
+    /// First and last dict id of the numeric entries that fall in `range`
+    ///
+    /// Returns `None` when no entry matches or the dictionary is not numeric.
+    /// Relies on Pinot storing dictionaries sorted ascending.
+    pub fn dict_id_range(&self, range: &ValueRange) -> Option<(u32, u32)> {
+        fn bounds<T: Copy>(
+            values: &[T],
+            range: &ValueRange,
+            to_value: impl Fn(T) -> RangeValue,
+        ) -> (usize, usize) {
+            let start = values.partition_point(|&v| !range.satisfies_lower(to_value(v)));
+            let end = values.partition_point(|&v| range.satisfies_upper(to_value(v)));
+            (start, end)
+        }
+
+        let (start, end) = match &self.values {
+            DictionaryValue::Int(values) => bounds(values, range, |v| RangeValue::Long(v as i64)),
+            DictionaryValue::Long(values) => bounds(values, range, RangeValue::Long),
+            DictionaryValue::Float(values) => {
+                bounds(values, range, |v| RangeValue::Double(v as f64))
+            }
+            DictionaryValue::Double(values) => bounds(values, range, RangeValue::Double),
+            DictionaryValue::String(_) | DictionaryValue::Bytes(_) => return None,
+        };
+        (start < end).then(|| (start as u32, end as u32 - 1))
+    }
+
+    /// For each dict id of this dictionary, the dict id of the equal value in
+    /// `other`, if it has one
+    ///
+    /// Relies on Pinot storing dictionaries sorted ascending.
+    pub fn dict_id_mapping(&self, other: &DictionaryReader) -> Result<Vec<Option<u32>>> {
+        fn map<T>(from: &[T], to: &[T], cmp: impl Fn(&T, &T) -> Ordering) -> Vec<Option<u32>> {
+            from.iter()
+                .map(|value| {
+                    to.binary_search_by(|entry| cmp(entry, value))
+                        .ok()
+                        .map(|idx| idx as u32)
+                })
+                .collect()
+        }
+
+        match (&self.values, &other.values) {
+            (DictionaryValue::Int(from), DictionaryValue::Int(to)) => Ok(map(from, to, Ord::cmp)),
+            (DictionaryValue::Long(from), DictionaryValue::Long(to)) => Ok(map(from, to, Ord::cmp)),
+            (DictionaryValue::Float(from), DictionaryValue::Float(to)) => {
+                Ok(map(from, to, f32::total_cmp))
+            }
+            (DictionaryValue::Double(from), DictionaryValue::Double(to)) => {
+                Ok(map(from, to, f64::total_cmp))
+            }
+            (DictionaryValue::String(from), DictionaryValue::String(to)) => {
+                Ok(map(from, to, Ord::cmp))
+            }
+            (DictionaryValue::Bytes(from), DictionaryValue::Bytes(to)) => {
+                Ok(map(from, to, Ord::cmp))
+            }
+            _ => Err(Error::UnsupportedFeature(format!(
+                "Cannot map {:?} dictionary onto {:?} dictionary",
+                self.data_type, other.data_type
+            ))),
+        }
     }
 
     pub fn get_int(&self, dict_id: u32) -> Option<i32> {
@@ -165,10 +543,292 @@ impl DictionaryReader {
         }
     }
 
+    /// All values of a STRING dictionary, indexed by dict id
+    pub fn string_values(&self) -> Option<&[String]> {
+        match &self.values {
+            DictionaryValue::String(values) => Some(values),
+            _ => None,
+        }
+    }
+
     pub fn get_string(&self, dict_id: u32) -> Option<&str> {
         match &self.values {
             DictionaryValue::String(values) => values.get(dict_id as usize).map(|s| s.as_str()),
             _ => None,
         }
     }
+
+    pub fn get_bytes(&self, dict_id: u32) -> Option<&[u8]> {
+        match &self.values {
+            DictionaryValue::Bytes(values) => values.get(dict_id as usize).map(|b| b.as_slice()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary_region(entries: &[&[u8]]) -> Vec<u8> {
+        let mut region = MAGIC_MARKER.to_be_bytes().to_vec();
+        for entry in entries {
+            region.extend_from_slice(entry);
+        }
+        region
+    }
+
+    #[test]
+    fn test_int_dictionary_accessors() {
+        let values = [-5i32, 0, 7, 42];
+        let entries: Vec<[u8; 4]> = values.iter().map(|v| v.to_be_bytes()).collect();
+        let refs: Vec<&[u8]> = entries.iter().map(|e| e.as_slice()).collect();
+        let dict = DictionaryReader::from_bytes(
+            &dictionary_region(&refs),
+            &DataType::Int,
+            4,
+            0,
+            Utf8Policy::Strict,
+        )
+        .unwrap();
+
+        assert_eq!(dict.len(), 4);
+        assert!(!dict.is_empty());
+        assert_eq!(dict.data_type(), &DataType::Int);
+        assert!(dict.contains_int(7));
+        assert!(!dict.contains_int(8));
+        assert_eq!(dict.lookup_string("7"), None);
+
+        let range = |low: i64, high: i64| {
+            ValueRange::between(RangeValue::Long(low), RangeValue::Long(high))
+        };
+        assert_eq!(dict.dict_id_range(&range(0, 42)), Some((1, 3)));
+        assert_eq!(dict.dict_id_range(&range(-10, 6)), Some((0, 1)));
+        assert_eq!(dict.dict_id_range(&range(1, 6)), None);
+        assert_eq!(dict.dict_id_range(&ValueRange::default()), Some((0, 3)));
+    }
+
+    #[test]
+    fn test_string_dictionary_lookup() {
+        // Fixed-length entries, null-padded to the longest value
+        let dict = DictionaryReader::from_bytes(
+            &dictionary_region(&[b"ANA\0", b"BOS\0", b"CHN1"]),
+            &DataType::String,
+            3,
+            4,
+            Utf8Policy::Strict,
+        )
+        .unwrap();
+
+        assert_eq!(dict.len(), 3);
+        assert_eq!(dict.data_type(), &DataType::String);
+        assert_eq!(dict.lookup_string("ANA"), Some(0));
+        assert_eq!(dict.lookup_string("CHN1"), Some(2));
+        assert_eq!(dict.lookup_string("BOS\0"), None);
+        assert_eq!(dict.lookup_string("NYA"), None);
+        assert!(!dict.contains_int(0));
+
+        let empty = DictionaryReader::from_bytes(
+            &dictionary_region(&[]),
+            &DataType::String,
+            0,
+            0,
+            Utf8Policy::Strict,
+        )
+        .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_padded_string_dictionary() {
+        let dict = DictionaryReader::from_bytes(
+            &dictionary_region(&[b"abc\0\0\0\0\0", b"hello\0\0\0", b"pinotdb!"]),
+            &DataType::String,
+            3,
+            8,
+            Utf8Policy::Strict,
+        )
+        .unwrap();
+        assert_eq!(dict.get_string(0), Some("abc"));
+        assert_eq!(dict.get_string(1), Some("hello"));
+        assert_eq!(dict.get_string(2), Some("pinotdb!"));
+        assert_eq!(dict.lookup_string("hello"), Some(1));
+    }
+
+    #[test]
+    fn test_bytes_dictionary() {
+        // Fixed-length entries keep trailing zero bytes
+        let dict = DictionaryReader::from_bytes(
+            &dictionary_region(&[&[0x00, 0x01], &[0xAB, 0x00], &[0xFF, 0xFF]]),
+            &DataType::Bytes,
+            3,
+            2,
+            Utf8Policy::Strict,
+        )
+        .unwrap();
+        assert_eq!(dict.len(), 3);
+        assert_eq!(dict.get_bytes(1), Some(&[0xAB, 0x00][..]));
+        assert_eq!(dict.get_bytes(3), None);
+        assert_eq!(dict.get_string(0), None);
+
+        // Variable-length entries carry 4-byte big-endian length prefixes
+        let dict = DictionaryReader::from_bytes(
+            &dictionary_region(&[&[0, 0, 0, 0], &[0, 0, 0, 3, 1, 2, 3], &[0, 0, 0, 1, 9]]),
+            &DataType::Bytes,
+            3,
+            0,
+            Utf8Policy::Strict,
+        )
+        .unwrap();
+        assert_eq!(dict.get_bytes(0), Some(&[][..]));
+        assert_eq!(dict.get_bytes(1), Some(&[1, 2, 3][..]));
+        assert_eq!(dict.get_bytes(2), Some(&[9][..]));
+        assert_eq!(
+            dict.dict_id_mapping(&dict).unwrap(),
+            vec![Some(0), Some(1), Some(2)]
+        );
+
+        // A corrupt length prefix is rejected before allocating the entry
+        let err = DictionaryReader::from_bytes(
+            &dictionary_region(&[&[0, 0, 0, 1, 9], &[0xFF, 0xFF, 0xFF, 0xF0, 1, 2]]),
+            &DataType::Bytes,
+            2,
+            0,
+            Utf8Policy::Strict,
+        )
+        .err()
+        .unwrap();
+        assert!(
+            err.to_string()
+                .contains("exceeds the 2 bytes left in the region"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_truncated_fixed_length_bytes_dictionary() {
+        // Metadata claims three 2-byte entries, the region holds two
+        let region = dictionary_region(&[&[0x00, 0x01], &[0xAB, 0x00]]);
+        let err = DictionaryReader::from_bytes(&region, &DataType::Bytes, 3, 2, Utf8Policy::Strict)
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::InvalidFormat(_)), "{:?}", err);
+        assert!(
+            err.to_string()
+                .contains("3 dictionary entries of 2 bytes exceed the 4 bytes left"),
+            "{}",
+            err
+        );
+
+        // A cardinality and entry length whose product overflows
+        let err = DictionaryReader::from_bytes(
+            &region,
+            &DataType::Bytes,
+            u32::MAX,
+            usize::MAX / 2,
+            Utf8Policy::Strict,
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(err, Error::InvalidFormat(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn test_front_coded_dictionary() {
+        // Version 1, buckets of 4, no null, 6 entries over 46 bytes
+        let mut region = vec![1, 4, 0, 0x86, 0xAE];
+        // The first bucket ends 25 bytes in
+        region.extend_from_slice(&25u32.to_le_bytes());
+        for part in [
+            &b"\x85apple"[..],
+            b"\x84\x87ication",
+            b"\x84\x81y",
+            b"\x82\x85ricot",
+            b"\x88apricots",
+            b"\x80\x86banana",
+        ] {
+            region.extend_from_slice(part);
+        }
+        let expected = [
+            "apple",
+            "application",
+            "apply",
+            "apricot",
+            "apricots",
+            "banana",
+        ];
+
+        let dict =
+            DictionaryReader::from_bytes(&region, &DataType::String, 6, 11, Utf8Policy::Strict)
+                .unwrap();
+        assert_eq!(dict.string_values().unwrap(), expected);
+        assert_eq!(dict.lookup_string("apricot"), Some(3));
+        let dict =
+            DictionaryReader::from_bytes(&region, &DataType::Bytes, 6, 0, Utf8Policy::Strict)
+                .unwrap();
+        assert_eq!(dict.get_bytes(1), Some(&b"application"[..]));
+
+        // The cardinality must match, and bucket offsets the buckets
+        let err =
+            DictionaryReader::from_bytes(&region, &DataType::String, 5, 0, Utf8Policy::Strict)
+                .err()
+                .unwrap();
+        assert!(err.to_string().contains("holds 6 entries"), "{}", err);
+        let mut bad_offset = region.clone();
+        bad_offset[5] = 24;
+        let err =
+            DictionaryReader::from_bytes(&bad_offset, &DataType::String, 6, 0, Utf8Policy::Strict)
+                .err()
+                .unwrap();
+        assert!(
+            err.to_string().contains("bucket 0 ends at byte 25"),
+            "{}",
+            err
+        );
+
+        // Version 0 shares prefixes with the bucket's first entry, version 1
+        // with the previous one
+        let bucket = b"\x83abc\x82\x81d\x83\x81e";
+        for (version, third) in [(0, "abce"), (1, "abde")] {
+            let mut region = vec![version, 4, 0, 0x83, 0x80 | bucket.len() as u8];
+            region.extend_from_slice(bucket);
+            let dict =
+                DictionaryReader::from_bytes(&region, &DataType::String, 3, 0, Utf8Policy::Strict)
+                    .unwrap();
+            assert_eq!(dict.string_values().unwrap(), ["abc", "abd", third]);
+        }
+
+        // Lengths of 128 bytes and more take two variable-byte groups
+        let long = "x".repeat(200);
+        // (one entry over 202 bytes, its length of 200)
+        let mut region = vec![1, 1, 0, 0x81, 0x4A, 0x81, 0x48, 0x81];
+        region.extend_from_slice(long.as_bytes());
+        let dict =
+            DictionaryReader::from_bytes(&region, &DataType::String, 1, 0, Utf8Policy::Strict)
+                .unwrap();
+        assert_eq!(dict.string_values().unwrap(), [long]);
+
+        // A prefix longer than the entry it is shared with is corrupt
+        let region = [&[1u8, 4, 0, 0x82, 0x86][..], b"\x82ab\x83\x81c"].concat();
+        let err =
+            DictionaryReader::from_bytes(&region, &DataType::String, 2, 0, Utf8Policy::Strict)
+                .err()
+                .unwrap();
+        assert!(err.to_string().contains("shares 3 bytes"), "{}", err);
+    }
+
+    #[test]
+    fn test_invalid_utf8_entry() {
+        let region = dictionary_region(&[b"ANA\0", b"B\xFFS\0", b"CHN1"]);
+        let read = |policy| DictionaryReader::from_bytes(&region, &DataType::String, 3, 4, policy);
+
+        let err = read(Utf8Policy::Strict).err().unwrap();
+        assert!(err.to_string().contains("Invalid UTF-8"), "{}", err);
+
+        let dict = read(Utf8Policy::default()).unwrap();
+        assert_eq!(dict.get_string(0), Some("ANA"));
+        assert_eq!(dict.get_string(1), Some("B\u{FFFD}S"));
+        assert_eq!(dict.get_string(2), Some("CHN1"));
+    }
 }
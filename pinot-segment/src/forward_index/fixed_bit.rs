@@ -1,16 +1,37 @@
 use crate::error::{Error, Result};
+use crate::forward_index::ByteOrder;
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
 use std::path::Path;
 
 const MAGIC_MARKER_SIZE: usize = 8;
 
+/// Magic marker (0xDEADBEEFDEAFBEAD) opening V3 regions
+const MAGIC_MARKER: [u8; MAGIC_MARKER_SIZE] = [0xDE, 0xAD, 0xBE, 0xEF, 0xDE, 0xAF, 0xBE, 0xAD];
+
 /// Fixed-bit width forward index reader for dictionary-encoded columns
 /// Based on PinotDataBitSet.java algorithm (big-endian byte order)
+///
+/// Regions of pre-V3 (V1) segments have no magic marker and pack values
+/// little-endian, least significant bit first. The payload cannot tell the
+/// two layouts apart, so callers pass the [`ByteOrder`] of the segment's
+/// format: big-endian for V3 regions, little-endian for V1 ones.
+///
+/// Values are up to 64 bits wide, so dictionary IDs are returned as `u64`;
+/// [`dict_index`](Self::dict_index) narrows one to the `u32` index
+/// dictionaries are addressed by.
 pub struct FixedBitWidthReader {
     buffer: Vec<u8>,
+    /// Position of `buffer[0]` within the packed data; non-zero for readers
+    /// that only loaded a window of documents
+    first_byte: usize,
+    /// Size of the whole packed data, even when only a window is loaded
+    packed_len: usize,
     bits_per_value: u8,
     num_values: u32,
+    /// V1 layout: values packed least significant bit first
+    little_endian: bool,
 }
 
 impl FixedBitWidthReader {
@@ -21,6 +42,7 @@ impl FixedBitWidthReader {
         size: usize,
         bits_per_value: u8,
         num_values: u32,
+        byte_order: ByteOrder,
     ) -> Result<Self> {
         let mut file = File::open(file_path)?;
 
@@ -31,26 +53,116 @@ impl FixedBitWidthReader {
         let mut buffer_with_magic = vec![0u8; size];
         file.read_exact(&mut buffer_with_magic)?;
 
-        // Skip the 8-byte magic marker (0xDEADBEEFDEAFBEAD)
-        // The actual bit-packed data starts after the magic marker
-        let buffer = if size >= MAGIC_MARKER_SIZE {
-            buffer_with_magic[MAGIC_MARKER_SIZE..].to_vec()
-        } else {
-            return Err(Error::InvalidFormat(
-                "Forward index too small to contain magic marker".to_string(),
-            ));
-        };
+        Self::from_bytes(&buffer_with_magic, bits_per_value, num_values, byte_order)
+    }
+
+    /// Read a forward index from an in-memory region (starting at the magic
+    /// marker, or at the packed data for little-endian V1 regions)
+    ///
+    /// Used for index regions that are stored compressed inside columns.psf.
+    pub fn from_bytes(
+        region: &[u8],
+        bits_per_value: u8,
+        num_values: u32,
+        byte_order: ByteOrder,
+    ) -> Result<Self> {
+        Self::check_bits_per_value(bits_per_value)?;
+        let header_len = Self::check_header(region, byte_order)?;
+        let mut reader = Self::from_packed(region[header_len..].to_vec(), bits_per_value, num_values);
+        reader.little_endian = byte_order == ByteOrder::LittleEndian;
+        Ok(reader)
+    }
+
+    /// Read only the packed bytes holding the documents in `docs`
+    ///
+    /// The returned reader decodes exactly those documents, so a small window
+    /// of a large column costs a small read. The window is clamped to
+    /// `num_values`.
+    pub fn read_window(
+        file_path: &Path,
+        offset: usize,
+        size: usize,
+        bits_per_value: u8,
+        num_values: u32,
+        byte_order: ByteOrder,
+        docs: Range<u32>,
+    ) -> Result<Self> {
+        Self::check_bits_per_value(bits_per_value)?;
+        let mut file = File::open(file_path)?;
+        file.seek(SeekFrom::Start(offset as u64))?;
+        let mut header = vec![0u8; Self::header_len(byte_order).min(size)];
+        file.read_exact(&mut header)?;
+        let header_len = Self::check_header(&header, byte_order)?;
+
+        let packed_len = size - header_len;
+        let end = docs.end.min(num_values);
+        let start = docs.start.min(end);
+
+        let bits = bits_per_value as u64;
+        let first_byte = ((start as u64 * bits) / 8) as usize;
+        let end_byte = ((end as u64 * bits).div_ceil(8) as usize).min(packed_len);
+
+        file.seek(SeekFrom::Start((offset + header_len + first_byte) as u64))?;
+        let mut buffer = vec![0u8; end_byte.saturating_sub(first_byte)];
+        file.read_exact(&mut buffer)?;
 
         Ok(FixedBitWidthReader {
             buffer,
+            first_byte,
+            packed_len,
             bits_per_value,
             num_values,
+            little_endian: byte_order == ByteOrder::LittleEndian,
         })
     }
 
+    /// Bytes before the packed data: the magic marker of V3 regions
+    fn header_len(byte_order: ByteOrder) -> usize {
+        match byte_order {
+            ByteOrder::BigEndian => MAGIC_MARKER_SIZE,
+            ByteOrder::LittleEndian => 0,
+        }
+    }
+
+    /// Check that `region` opens with the header its layout has, returning
+    /// the header's length
+    fn check_header(region: &[u8], byte_order: ByteOrder) -> Result<usize> {
+        let header_len = Self::header_len(byte_order);
+        if header_len > 0 && !region.starts_with(&MAGIC_MARKER) {
+            return Err(Error::InvalidFormat(if region.len() < MAGIC_MARKER_SIZE {
+                "Forward index too small to contain magic marker".to_string()
+            } else {
+                "Forward index does not start with the magic marker".to_string()
+            }));
+        }
+        Ok(header_len)
+    }
+
+    fn check_bits_per_value(bits_per_value: u8) -> Result<()> {
+        if bits_per_value > 64 {
+            return Err(Error::InvalidFormat(format!(
+                "bitsPerElement {} exceeds 64",
+                bits_per_value
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reader over a fully loaded packed buffer (big-endian, no magic marker)
+    pub(crate) fn from_packed(buffer: Vec<u8>, bits_per_value: u8, num_values: u32) -> Self {
+        FixedBitWidthReader {
+            packed_len: buffer.len(),
+            buffer,
+            first_byte: 0,
+            bits_per_value,
+            num_values,
+            little_endian: false,
+        }
+    }
+
     /// Read dictionary ID for a given document ID
     /// Based on PinotDataBitSet.java:80-101 (big-endian)
-    pub fn get_dict_id(&self, doc_id: u32) -> Result<u32> {
+    pub fn get_dict_id(&self, doc_id: u32) -> Result<u64> {
         if doc_id >= self.num_values {
             return Err(Error::InvalidFormat(format!(
                 "doc_id {} out of range (num_values={})",
@@ -58,58 +170,155 @@ impl FixedBitWidthReader {
             )));
         }
 
-        let bit_offset = (doc_id as u64) * (self.bits_per_value as u64);
-        let byte_offset = (bit_offset / 8) as usize;
-        let bit_offset_in_first_byte = (bit_offset % 8) as usize;
+        let (byte_offset, bit_offset_in_first_byte) = self.locate(doc_id, 1)?;
+        Ok(self.decode(byte_offset, bit_offset_in_first_byte))
+    }
+
+    /// Byte offset into `buffer` and bit offset within that byte of document
+    /// `start`, after checking that documents `[start, start + count)` are
+    /// loaded
+    fn locate(&self, start: u32, count: u32) -> Result<(usize, usize)> {
+        let bits = self.bits_per_value as u64;
+        let bit_offset = start as u64 * bits;
+        let Some(byte_offset) = ((bit_offset / 8) as usize).checked_sub(self.first_byte) else {
+            return Err(Error::InvalidFormat(format!(
+                "doc_id {} is before the loaded window",
+                start
+            )));
+        };
 
-        if byte_offset >= self.buffer.len() {
+        let end_byte =
+            ((start as u64 + count as u64) * bits).div_ceil(8) as usize - self.first_byte;
+        if end_byte > self.buffer.len() || byte_offset >= self.buffer.len() {
             return Err(Error::InvalidFormat(format!(
                 "Buffer overflow: byte_offset={}, buffer_len={}",
-                byte_offset,
+                end_byte.max(byte_offset),
                 self.buffer.len()
             )));
         }
+        Ok((byte_offset, (bit_offset % 8) as usize))
+    }
+
+    /// Decode the value starting at `bit_offset_in_first_byte` of
+    /// `buffer[byte_offset]`, whose bytes [`locate`](Self::locate) checked
+    fn decode(&self, byte_offset: usize, bit_offset_in_first_byte: usize) -> u64 {
+        if self.little_endian {
+            return self.decode_le(byte_offset, bit_offset_in_first_byte);
+        }
 
         let byte_mask = 0xFF >> bit_offset_in_first_byte;
-        let mut current_value = (self.buffer[byte_offset] & byte_mask) as u32;
+        let current_value = (self.buffer[byte_offset] & byte_mask) as u64;
 
         let mut num_bits_left =
             self.bits_per_value as i32 - (8 - bit_offset_in_first_byte as i32);
 
         if num_bits_left <= 0 {
             // Value is fully contained within the first byte
-            Ok(current_value >> (-num_bits_left))
-        } else {
-            // Value spans multiple bytes
-            let mut byte_offset = byte_offset + 1;
-
-            while num_bits_left > 8 {
-                if byte_offset >= self.buffer.len() {
-                    return Err(Error::InvalidFormat("Buffer overflow in multi-byte read".to_string()));
-                }
-                current_value = (current_value << 8) | (self.buffer[byte_offset] as u32);
-                num_bits_left -= 8;
-                byte_offset += 1;
-            }
+            return current_value >> (-num_bits_left);
+        }
 
-            if byte_offset >= self.buffer.len() {
-                return Err(Error::InvalidFormat("Buffer overflow in final byte read".to_string()));
-            }
+        // Value spans multiple bytes
+        let mut current_value = current_value;
+        let mut byte_offset = byte_offset + 1;
+        while num_bits_left > 8 {
+            current_value = (current_value << 8) | (self.buffer[byte_offset] as u64);
+            num_bits_left -= 8;
+            byte_offset += 1;
+        }
+        (current_value << num_bits_left)
+            | ((self.buffer[byte_offset] as u64) >> (8 - num_bits_left))
+    }
+
+    /// Decode a V1 value: the bytes holding it, read as a little-endian
+    /// integer, shifted down by its bit offset in the first byte
+    ///
+    /// A 64-bit value not aligned to a byte spans 9 bytes, hence the `u128`.
+    fn decode_le(&self, byte_offset: usize, bit_offset_in_first_byte: usize) -> u64 {
+        let num_bytes = (bit_offset_in_first_byte + self.bits_per_value as usize).div_ceil(8);
+        let word = self.buffer[byte_offset..byte_offset + num_bytes]
+            .iter()
+            .rev()
+            .fold(0u128, |word, &b| (word << 8) | b as u128);
+        let mask = (1u128 << self.bits_per_value) - 1;
+        ((word >> bit_offset_in_first_byte) & mask) as u64
+    }
+
+    /// Narrow a dictionary ID to a dictionary index
+    ///
+    /// # Errors
+    /// Returns error if `dict_id` exceeds `u32::MAX`, which no Pinot
+    /// dictionary can hold.
+    pub fn dict_index(dict_id: u64) -> Result<u32> {
+        u32::try_from(dict_id).map_err(|_| {
+            Error::InvalidFormat(format!("Dictionary ID {} exceeds u32::MAX", dict_id))
+        })
+    }
 
-            let final_value = (current_value << num_bits_left)
-                | ((self.buffer[byte_offset] as u32) >> (8 - num_bits_left));
-            Ok(final_value)
+    /// Number of values this reader was asked to decode
+    pub fn num_values(&self) -> u32 {
+        self.num_values
+    }
+
+    /// Number of values the packed buffer can hold at this bit width
+    ///
+    /// Trailing padding bits mean this can slightly exceed the number of
+    /// values the writer actually packed.
+    pub fn packed_capacity(&self) -> u64 {
+        if self.bits_per_value == 0 {
+            return 0;
         }
+        (self.packed_len as u64 * 8) / self.bits_per_value as u64
     }
 
-    /// Read all dictionary IDs as a batch
-    pub fn read_all(&self) -> Result<Vec<u32>> {
-        let mut dict_ids = Vec::with_capacity(self.num_values as usize);
-        for doc_id in 0..self.num_values {
-            dict_ids.push(self.get_dict_id(doc_id)?);
+    /// Size in bytes of the packed data (excluding the magic marker)
+    pub fn packed_len(&self) -> usize {
+        self.packed_len
+    }
+
+    /// Bytes needed to pack `num_values` values of `bits_per_value` bits
+    pub fn packed_size(num_values: u32, bits_per_value: u8) -> usize {
+        (num_values as u64 * bits_per_value as u64).div_ceil(8) as usize
+    }
+
+    /// Read the dictionary IDs of documents `[start, start + count)`
+    ///
+    /// The bit offset of `start` is computed and bounds-checked once, then
+    /// each value is decoded by walking forward from the previous one.
+    pub fn read_range(&self, start: u32, count: u32) -> Result<Vec<u64>> {
+        let end = start.checked_add(count).ok_or_else(|| {
+            Error::InvalidFormat(format!("doc_id range {}+{} overflows", start, count))
+        })?;
+        if end > self.num_values {
+            return Err(Error::InvalidFormat(format!(
+                "doc_id range {}..{} out of range (num_values={})",
+                start, end, self.num_values
+            )));
+        }
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let (mut byte_offset, mut bit_offset) = self.locate(start, count)?;
+        let bits = self.bits_per_value as usize;
+        let mut dict_ids = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            dict_ids.push(self.decode(byte_offset, bit_offset));
+            bit_offset += bits;
+            byte_offset += bit_offset / 8;
+            bit_offset %= 8;
         }
         Ok(dict_ids)
     }
+
+    /// Read the dictionary IDs of the given documents, in the order given
+    pub fn read_doc_ids(&self, doc_ids: &[u32]) -> Result<Vec<u64>> {
+        doc_ids.iter().map(|&doc_id| self.get_dict_id(doc_id)).collect()
+    }
+
+    /// Read all dictionary IDs as a batch
+    pub fn read_all(&self) -> Result<Vec<u64>> {
+        self.read_range(0, self.num_values)
+    }
 }
 
 #[cfg(test)]
@@ -120,11 +329,7 @@ mod tests {
     fn test_bit_packing_1bit() {
         // Test 1-bit values: [0, 1, 0, 1, 1, 0, 0, 1]
         // Packed as: 01011001 = 0x59
-        let reader = FixedBitWidthReader {
-            buffer: vec![0x59],
-            bits_per_value: 1,
-            num_values: 8,
-        };
+        let reader = FixedBitWidthReader::from_packed(vec![0x59], 1, 8);
 
         assert_eq!(reader.get_dict_id(0).unwrap(), 0);
         assert_eq!(reader.get_dict_id(1).unwrap(), 1);
@@ -140,11 +345,7 @@ mod tests {
     fn test_bit_packing_4bit() {
         // Test 4-bit values: [5, 10, 15, 3]
         // Packed as: 0101 1010 1111 0011 = 0x5A 0xF3
-        let reader = FixedBitWidthReader {
-            buffer: vec![0x5A, 0xF3],
-            bits_per_value: 4,
-            num_values: 4,
-        };
+        let reader = FixedBitWidthReader::from_packed(vec![0x5A, 0xF3], 4, 4);
 
         assert_eq!(reader.get_dict_id(0).unwrap(), 5);
         assert_eq!(reader.get_dict_id(1).unwrap(), 10);
@@ -157,14 +358,195 @@ mod tests {
         // Test 5-bit values: [10, 20, 5]
         // 10 = 01010, 20 = 10100, 5 = 00101
         // Packed: 01010 10100 00101 = 01010101 00001010 = 0x55 0x0A
-        let reader = FixedBitWidthReader {
-            buffer: vec![0x55, 0x0A],
-            bits_per_value: 5,
-            num_values: 3,
-        };
+        let reader = FixedBitWidthReader::from_packed(vec![0x55, 0x0A], 5, 3);
 
         assert_eq!(reader.get_dict_id(0).unwrap(), 10);
         assert_eq!(reader.get_dict_id(1).unwrap(), 20);
         assert_eq!(reader.get_dict_id(2).unwrap(), 5);
     }
+
+    #[test]
+    fn test_v1_little_endian_layout() {
+        // 5-bit values [10, 20, 5] packed least significant bit first:
+        // byte 0 = 100 01010 (low 3 bits of 20, then 10) = 0x8A,
+        // byte 1 = 0 00101 10 (5, then high 2 bits of 20) = 0x16
+        let v1 = FixedBitWidthReader::from_bytes(&[0x8A, 0x16], 5, 3, ByteOrder::LittleEndian)
+            .unwrap();
+        assert!(v1.little_endian);
+        assert_eq!(v1.packed_len(), 2);
+
+        let mut v3_region = MAGIC_MARKER.to_vec();
+        v3_region.extend_from_slice(&[0x55, 0x0A]);
+        let v3 = FixedBitWidthReader::from_bytes(&v3_region, 5, 3, ByteOrder::BigEndian).unwrap();
+        assert!(!v3.little_endian);
+
+        assert_eq!(v1.read_all().unwrap(), vec![10, 20, 5]);
+        assert_eq!(v1.read_all().unwrap(), v3.read_all().unwrap());
+        assert_eq!(v1.read_range(1, 2).unwrap(), vec![20, 5]);
+        assert!(v1.get_dict_id(3).is_err());
+    }
+
+    #[test]
+    fn test_layout_follows_the_given_byte_order() {
+        // 8-bit V1 values whose packed bytes happen to spell the magic marker
+        let v1 = FixedBitWidthReader::from_bytes(&MAGIC_MARKER, 8, 8, ByteOrder::LittleEndian)
+            .unwrap();
+        assert_eq!(v1.packed_len(), 8);
+        assert_eq!(
+            v1.read_all().unwrap(),
+            MAGIC_MARKER.iter().map(|&b| b as u64).collect::<Vec<_>>()
+        );
+
+        // A V3 region must open with the magic marker
+        let err = FixedBitWidthReader::from_bytes(&[0x55, 0x0A, 0, 0, 0, 0, 0, 0], 5, 3, ByteOrder::BigEndian)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("does not start with the magic marker"), "{}", err);
+        let err = FixedBitWidthReader::from_bytes(&[0x55, 0x0A], 5, 3, ByteOrder::BigEndian)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("too small"), "{}", err);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        std::fs::write(&path, MAGIC_MARKER).unwrap();
+        let window =
+            FixedBitWidthReader::read_window(&path, 0, 8, 8, 8, ByteOrder::LittleEndian, 6..8)
+                .unwrap();
+        assert_eq!(window.read_range(6, 2).unwrap(), vec![0xBE, 0xAD]);
+    }
+
+    #[test]
+    fn test_v1_window_matches_full_read() {
+        // 12-bit values spanning byte boundaries, packed little-endian
+        let values: Vec<u64> = (0..50).map(|i| (i * 157) % 4096).collect();
+        let packed = pack_le(&values, 12);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        let mut file_bytes = vec![0xFF; 3];
+        file_bytes.extend_from_slice(&packed);
+        std::fs::write(&path, &file_bytes).unwrap();
+
+        let le = ByteOrder::LittleEndian;
+        let full = FixedBitWidthReader::read(&path, 3, packed.len(), 12, 50, le).unwrap();
+        assert_eq!(full.read_all().unwrap(), values);
+        let window =
+            FixedBitWidthReader::read_window(&path, 3, packed.len(), 12, 50, le, 7..27).unwrap();
+        assert_eq!(window.read_range(7, 20).unwrap(), values[7..27]);
+    }
+
+    #[test]
+    fn test_read_range() {
+        // 5-bit values: [10, 20, 5]
+        let reader = FixedBitWidthReader::from_packed(vec![0x55, 0x0A], 5, 3);
+
+        assert_eq!(reader.read_range(1, 2).unwrap(), vec![20, 5]);
+        assert_eq!(reader.read_range(0, 0).unwrap(), Vec::<u64>::new());
+        assert!(reader.read_range(2, 2).is_err());
+    }
+
+    #[test]
+    fn test_read_range_matches_read_all() {
+        for bits_per_value in [1u8, 3, 5, 7, 8, 9, 12, 17, 31, 33, 64] {
+            let max = u64::MAX >> (64 - bits_per_value);
+            let values: Vec<u64> = (0..101u64)
+                .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) & max)
+                .collect();
+            let packed = pack_be(&values, bits_per_value);
+            let v3 = FixedBitWidthReader::from_packed(packed, bits_per_value, 101);
+            let packed = pack_le(&values, bits_per_value);
+            let v1 = FixedBitWidthReader {
+                little_endian: true,
+                ..FixedBitWidthReader::from_packed(packed, bits_per_value, 101)
+            };
+
+            for reader in [&v3, &v1] {
+                let all = reader.read_all().unwrap();
+                assert_eq!(all, values, "{} bits", bits_per_value);
+                // Windows starting and ending mid-byte
+                for (start, count) in [(0, 1), (1, 7), (3, 13), (7, 50), (13, 88), (100, 1)] {
+                    assert_eq!(
+                        reader.read_range(start, count).unwrap(),
+                        all[start as usize..(start + count) as usize],
+                        "{} bits, range {}+{}",
+                        bits_per_value,
+                        start,
+                        count
+                    );
+                }
+                assert!(reader.read_range(100, 2).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_doc_ids() {
+        // 5-bit values: [10, 20, 5]
+        let reader = FixedBitWidthReader::from_packed(vec![0x55, 0x0A], 5, 3);
+
+        assert_eq!(reader.read_doc_ids(&[2, 0]).unwrap(), vec![5, 10]);
+        assert_eq!(reader.read_doc_ids(&[]).unwrap(), Vec::<u64>::new());
+        assert!(reader.read_doc_ids(&[1, 3]).is_err());
+    }
+
+    /// Pack `values` most significant bit first, as V3 regions are
+    fn pack_be(values: &[u64], bits_per_value: u8) -> Vec<u8> {
+        let bits = bits_per_value as usize;
+        let size = FixedBitWidthReader::packed_size(values.len() as u32, bits_per_value);
+        let mut packed = vec![0u8; size];
+        for (i, &value) in values.iter().enumerate() {
+            for bit in 0..bits {
+                if value >> (bits - 1 - bit) & 1 == 1 {
+                    let pos = i * bits + bit;
+                    packed[pos / 8] |= 0x80 >> (pos % 8);
+                }
+            }
+        }
+        packed
+    }
+
+    /// Pack `values` least significant bit first, as V1 regions are
+    fn pack_le(values: &[u64], bits_per_value: u8) -> Vec<u8> {
+        let bits = bits_per_value as usize;
+        let size = FixedBitWidthReader::packed_size(values.len() as u32, bits_per_value);
+        let mut packed = vec![0u8; size];
+        for (i, &value) in values.iter().enumerate() {
+            for bit in 0..bits {
+                if value >> bit & 1 == 1 {
+                    let pos = i * bits + bit;
+                    packed[pos / 8] |= 1 << (pos % 8);
+                }
+            }
+        }
+        packed
+    }
+
+    #[test]
+    fn test_wide_dict_ids() {
+        for bits_per_value in [33u8, 47, 63, 64] {
+            let max = u64::MAX >> (64 - bits_per_value);
+            let values: Vec<u64> = vec![max, 0, max - 1, 1 << (bits_per_value - 1), 12345, max];
+
+            let packed = pack_be(&values, bits_per_value);
+            let v3 = FixedBitWidthReader::from_packed(packed, bits_per_value, 6);
+            assert_eq!(v3.read_all().unwrap(), values, "{} bits", bits_per_value);
+
+            let packed = pack_le(&values, bits_per_value);
+            let v1 =
+                FixedBitWidthReader::from_bytes(&packed, bits_per_value, 6, ByteOrder::LittleEndian)
+                    .unwrap();
+            assert!(v1.little_endian);
+            assert_eq!(v1.read_all().unwrap(), values, "{} bits", bits_per_value);
+        }
+
+        assert!(FixedBitWidthReader::from_bytes(&[0; 16], 65, 1, ByteOrder::LittleEndian).is_err());
+    }
+
+    #[test]
+    fn test_dict_index() {
+        assert_eq!(FixedBitWidthReader::dict_index(7).unwrap(), 7);
+        assert_eq!(FixedBitWidthReader::dict_index(u32::MAX as u64).unwrap(), u32::MAX);
+        assert!(FixedBitWidthReader::dict_index(u32::MAX as u64 + 1).is_err());
+    }
 }
@@ -0,0 +1,312 @@
+use crate::error::{Error, Result};
+
+/// Fixed header: version, chunk count, docs per chunk, bytes per value,
+/// total docs, compression type and the offset of the chunk offset table
+const HEADER_SIZE: usize = 28;
+
+// Compression type constants (from Pinot ChunkCompressionType)
+const PASS_THROUGH: i32 = 0;
+const SNAPPY: i32 = 1;
+const ZSTANDARD: i32 = 2;
+const LZ4: i32 = 3;
+const LZ4_LENGTH_PREFIXED: i32 = 4;
+
+/// Fixed-byte chunk forward index reader (versions 2 and 3)
+///
+/// Pinot stores fixed-width RAW values this way, e.g. the pre-aggregated
+/// metrics of a star-tree. Region layout (big-endian):
+/// - header: version, number of chunks, docs per chunk, bytes per value,
+///   total docs, chunk compression type and the offset of the chunk offset
+///   table, one i32 each
+/// - one chunk offset per chunk, counted from the region start: i32 in
+///   version 2, i64 in version 3
+/// - the chunks, each holding the values of `docs_per_chunk` docs (fewer in
+///   the last one), compressed on their own
+///
+/// The whole region is decoded up front.
+#[derive(Debug, Clone)]
+pub struct FixedByteChunkReader {
+    /// Values of every doc, back to back
+    values: Vec<u8>,
+    size_of_entry: usize,
+    total_docs: u32,
+}
+
+impl FixedByteChunkReader {
+    /// Decode a fixed-byte chunk forward index region
+    pub fn from_bytes(region: &[u8]) -> Result<Self> {
+        let header = region.get(..HEADER_SIZE).ok_or_else(|| {
+            Error::InvalidFormat("Fixed-byte forward index too small for its header".to_string())
+        })?;
+        let field =
+            |idx: usize| i32::from_be_bytes(header[idx * 4..idx * 4 + 4].try_into().unwrap());
+        let [version, num_chunks, docs_per_chunk, size_of_entry, total_docs, compression_type, offsets_start] =
+            std::array::from_fn(field);
+
+        let offset_size = match version {
+            2 => 4,
+            3 => 8,
+            _ => {
+                return Err(Error::UnsupportedFeature(format!(
+                    "Fixed-byte forward index version {} not supported",
+                    version
+                )))
+            }
+        };
+        check_compression(compression_type)?;
+        let (
+            Ok(num_chunks),
+            Ok(docs_per_chunk),
+            Ok(size_of_entry),
+            Ok(total_docs),
+            Ok(offsets_start),
+        ) = (
+            usize::try_from(num_chunks),
+            usize::try_from(docs_per_chunk),
+            usize::try_from(size_of_entry),
+            u32::try_from(total_docs),
+            usize::try_from(offsets_start),
+        )
+        else {
+            return Err(Error::InvalidFormat(
+                "Negative field in fixed-byte forward index header".to_string(),
+            ));
+        };
+        if (docs_per_chunk == 0 && total_docs > 0)
+            || num_chunks != (total_docs as usize).div_ceil(docs_per_chunk.max(1))
+        {
+            return Err(Error::InvalidFormat(format!(
+                "Fixed-byte forward index has {} chunks of {} docs for {} docs",
+                num_chunks, docs_per_chunk, total_docs
+            )));
+        }
+
+        let offsets_end = offsets_start + num_chunks * offset_size;
+        let offsets = region.get(offsets_start..offsets_end).ok_or_else(|| {
+            Error::InvalidFormat("Truncated fixed-byte forward index chunk offsets".to_string())
+        })?;
+        let chunk_offsets: Vec<usize> = offsets
+            .chunks_exact(offset_size)
+            .map(|entry| match offset_size {
+                4 => i32::from_be_bytes(entry.try_into().unwrap()) as usize,
+                _ => i64::from_be_bytes(entry.try_into().unwrap()) as usize,
+            })
+            .collect();
+
+        let mut values = Vec::with_capacity(total_docs as usize * size_of_entry);
+        for (idx, &chunk_offset) in chunk_offsets.iter().enumerate() {
+            let chunk_end = chunk_offsets.get(idx + 1).copied().unwrap_or(region.len());
+            let chunk = region.get(chunk_offset..chunk_end).ok_or_else(|| {
+                Error::InvalidFormat(format!(
+                    "Chunk {} spans {}..{}, outside the {}-byte forward index",
+                    idx,
+                    chunk_offset,
+                    chunk_end,
+                    region.len()
+                ))
+            })?;
+            let docs = docs_per_chunk.min(total_docs as usize - idx * docs_per_chunk);
+            let expected = docs * size_of_entry;
+            let decompressed =
+                decompress_chunk(compression_type, chunk, docs_per_chunk * size_of_entry)?;
+            let chunk_values = decompressed.get(..expected).ok_or_else(|| {
+                Error::InvalidFormat(format!(
+                    "Chunk {} holds {} bytes, expected {}",
+                    idx,
+                    decompressed.len(),
+                    expected
+                ))
+            })?;
+            values.extend_from_slice(chunk_values);
+        }
+
+        Ok(FixedByteChunkReader {
+            values,
+            size_of_entry,
+            total_docs,
+        })
+    }
+
+    pub fn num_values(&self) -> u32 {
+        self.total_docs
+    }
+
+    /// Bytes of each value
+    pub fn size_of_entry(&self) -> usize {
+        self.size_of_entry
+    }
+
+    /// Every value as a LONG
+    pub fn read_longs(&self) -> Result<Vec<i64>> {
+        Ok(self.entries::<8>()?.map(i64::from_be_bytes).collect())
+    }
+
+    /// Every value as a DOUBLE
+    pub fn read_doubles(&self) -> Result<Vec<f64>> {
+        Ok(self.entries::<8>()?.map(f64::from_be_bytes).collect())
+    }
+
+    fn entries<const N: usize>(&self) -> Result<impl Iterator<Item = [u8; N]> + '_> {
+        if self.size_of_entry != N {
+            return Err(Error::InvalidFormat(format!(
+                "Forward index holds {}-byte values, expected {}",
+                self.size_of_entry, N
+            )));
+        }
+        Ok(self
+            .values
+            .chunks_exact(N)
+            .map(|entry| entry.try_into().unwrap()))
+    }
+}
+
+fn check_compression(compression_type: i32) -> Result<()> {
+    match compression_type {
+        PASS_THROUGH => Ok(()),
+        #[cfg(feature = "lz4")]
+        LZ4 | LZ4_LENGTH_PREFIXED => Ok(()),
+        #[cfg(not(feature = "lz4"))]
+        LZ4 | LZ4_LENGTH_PREFIXED => Err(Error::UnsupportedFeature(
+            "LZ4 compression support not enabled. Enable 'lz4' feature.".to_string(),
+        )),
+        SNAPPY => Err(Error::UnsupportedFeature(
+            "Snappy compression not yet supported".to_string(),
+        )),
+        ZSTANDARD => Err(Error::UnsupportedFeature(
+            "Zstandard compression not yet supported".to_string(),
+        )),
+        _ => Err(Error::UnsupportedFeature(format!(
+            "Unknown compression type: {}",
+            compression_type
+        ))),
+    }
+}
+
+/// Decompress a chunk of at most `chunk_size` bytes
+#[cfg_attr(not(feature = "lz4"), allow(unused_variables))]
+fn decompress_chunk(compression_type: i32, chunk: &[u8], chunk_size: usize) -> Result<Vec<u8>> {
+    #[cfg(feature = "lz4")]
+    if matches!(compression_type, LZ4 | LZ4_LENGTH_PREFIXED) {
+        // LZ4_LENGTH_PREFIXED chunks open with their decompressed size (LE)
+        let (size, compressed) = match compression_type {
+            LZ4_LENGTH_PREFIXED => {
+                let prefix = chunk.get(..4).ok_or_else(|| {
+                    Error::InvalidFormat(
+                        "LZ4_LENGTH_PREFIXED data too short for length prefix".to_string(),
+                    )
+                })?;
+                (
+                    u32::from_le_bytes(prefix.try_into().unwrap()) as usize,
+                    &chunk[4..],
+                )
+            }
+            _ => (chunk_size, chunk),
+        };
+        return lz4::block::decompress(compressed, Some(size as i32))
+            .map_err(|e| Error::InvalidFormat(format!("LZ4 decompression failed: {}", e)));
+    }
+
+    // PASS_THROUGH, the only other type check_compression accepts
+    Ok(chunk.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::fixed_byte_region;
+
+    fn doubles(values: &[f64]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn test_read_chunks() {
+        let values: Vec<f64> = (0..10).map(|i| i as f64 * 1.5).collect();
+        let region = fixed_byte_region(&doubles(&values), 8, 4);
+        let reader = FixedByteChunkReader::from_bytes(&region).unwrap();
+        assert_eq!(reader.num_values(), 10);
+        assert_eq!(reader.read_doubles().unwrap(), values);
+        assert_eq!(reader.read_longs().unwrap()[1], 1.5f64.to_bits() as i64);
+
+        let empty = FixedByteChunkReader::from_bytes(&fixed_byte_region(&[], 8, 4)).unwrap();
+        assert!(empty.read_longs().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_version_3_offsets() {
+        let values = [1i64, -2, 3].map(i64::to_be_bytes).concat();
+        let mut region = Vec::new();
+        for field in [3, 2, 2, 8, 3, PASS_THROUGH, HEADER_SIZE as i32] {
+            region.extend_from_slice(&field.to_be_bytes());
+        }
+        let data_start = (HEADER_SIZE + 16) as i64;
+        region.extend_from_slice(&data_start.to_be_bytes());
+        region.extend_from_slice(&(data_start + 16).to_be_bytes());
+        region.extend_from_slice(&values);
+
+        let reader = FixedByteChunkReader::from_bytes(&region).unwrap();
+        assert_eq!(reader.read_longs().unwrap(), vec![1, -2, 3]);
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn test_lz4_chunks() {
+        let values: Vec<f64> = (0..5).map(|i| i as f64).collect();
+        let bytes = doubles(&values);
+        let mut region = Vec::new();
+        for field in [2, 2, 3, 8, 5, LZ4, HEADER_SIZE as i32] {
+            region.extend_from_slice(&field.to_be_bytes());
+        }
+        let chunks: Vec<Vec<u8>> = bytes
+            .chunks(24)
+            .map(|chunk| lz4::block::compress(chunk, None, false).unwrap())
+            .collect();
+        let data_start = HEADER_SIZE + 8;
+        region.extend_from_slice(&(data_start as i32).to_be_bytes());
+        region.extend_from_slice(&((data_start + chunks[0].len()) as i32).to_be_bytes());
+        region.extend(chunks.concat());
+
+        let reader = FixedByteChunkReader::from_bytes(&region).unwrap();
+        assert_eq!(reader.read_doubles().unwrap(), values);
+    }
+
+    #[test]
+    fn test_rejects_bad_region() {
+        let region = fixed_byte_region(&doubles(&[1.0, 2.0, 3.0]), 8, 2);
+        assert!(matches!(
+            FixedByteChunkReader::from_bytes(&region[..region.len() - 1]),
+            Err(Error::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            FixedByteChunkReader::from_bytes(&region[..HEADER_SIZE - 1]),
+            Err(Error::InvalidFormat(_))
+        ));
+
+        let mut bad_version = region.clone();
+        bad_version[..4].copy_from_slice(&4i32.to_be_bytes());
+        assert!(matches!(
+            FixedByteChunkReader::from_bytes(&bad_version),
+            Err(Error::UnsupportedFeature(_))
+        ));
+
+        let mut snappy = region.clone();
+        snappy[20..24].copy_from_slice(&SNAPPY.to_be_bytes());
+        assert!(matches!(
+            FixedByteChunkReader::from_bytes(&snappy),
+            Err(Error::UnsupportedFeature(_))
+        ));
+
+        // Three docs need two chunks of two
+        let mut bad_count = region.clone();
+        bad_count[4..8].copy_from_slice(&1i32.to_be_bytes());
+        assert!(matches!(
+            FixedByteChunkReader::from_bytes(&bad_count),
+            Err(Error::InvalidFormat(_))
+        ));
+
+        let ints = fixed_byte_region(&[0, 0, 0, 1], 4, 2);
+        let reader = FixedByteChunkReader::from_bytes(&ints).unwrap();
+        assert_eq!(reader.size_of_entry(), 4);
+        assert!(reader.read_doubles().is_err());
+    }
+}
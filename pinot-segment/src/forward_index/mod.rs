@@ -1,7 +1,50 @@
 pub mod dictionary;
 pub mod fixed_bit;
+pub mod fixed_byte;
 pub mod var_byte;
 
+#[cfg(feature = "async")]
+mod string_stream;
+
 pub use dictionary::DictionaryReader;
 pub use fixed_bit::FixedBitWidthReader;
-pub use var_byte::VarByteChunkReader;
+pub use fixed_byte::FixedByteChunkReader;
+pub use var_byte::{ByteOrder, VarByteChunkReader};
+
+use std::string::FromUtf8Error;
+
+/// How STRING values that are not valid UTF-8 are decoded
+///
+/// Applies to dictionaries and RAW forward indexes alike. Defaults to
+/// `Lossy`, so one malformed value does not make a whole column unreadable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Utf8Policy {
+    /// Fail the read with a parse error
+    Strict,
+    /// Replace invalid sequences with U+FFFD
+    #[default]
+    Lossy,
+}
+
+impl Utf8Policy {
+    /// Decode an owned byte buffer; only `Strict` can fail
+    pub fn decode(self, bytes: Vec<u8>) -> std::result::Result<String, FromUtf8Error> {
+        match (self, String::from_utf8(bytes)) {
+            (Utf8Policy::Lossy, Err(e)) => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+            (_, decoded) => decoded,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_policy_decode() {
+        let invalid = vec![b'a', 0xFF, b'b'];
+        assert!(Utf8Policy::Strict.decode(invalid.clone()).is_err());
+        assert_eq!(Utf8Policy::Lossy.decode(invalid).unwrap(), "a\u{FFFD}b");
+        assert_eq!(Utf8Policy::Strict.decode(b"ok".to_vec()).unwrap(), "ok");
+    }
+}
@@ -0,0 +1,145 @@
+//! Async streaming of RAW STRING values (`async` feature)
+
+use crate::error::{Error, Result};
+use crate::forward_index::VarByteChunkReader;
+use futures::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use tokio::task::JoinHandle;
+
+impl VarByteChunkReader {
+    /// Stream every value as a `String`, one item per document in doc id order
+    ///
+    /// Chunks are read and decompressed on tokio's blocking thread pool, one
+    /// at a time, and their values are yielded from a per-chunk buffer, so
+    /// at most one decompressed chunk is held in memory (unlike
+    /// [`read_all_strings`](Self::read_all_strings), which materializes every
+    /// value). Values that are not valid UTF-8 are yielded as errors; an I/O
+    /// or format error ends the stream. Must be polled within a tokio runtime.
+    pub fn into_string_stream(self) -> impl Stream<Item = Result<String>> + Send + Unpin {
+        let num_chunks = self.num_chunks();
+        StringStream {
+            reader: Arc::new(self),
+            next_chunk: 0,
+            num_chunks,
+            pending: None,
+            buffer: Vec::new().into_iter(),
+            next_doc_id: 0,
+            done: false,
+        }
+    }
+}
+
+struct StringStream {
+    reader: Arc<VarByteChunkReader>,
+    next_chunk: usize,
+    num_chunks: usize,
+    /// Chunk currently being decoded on the blocking pool
+    pending: Option<JoinHandle<Result<Vec<Vec<u8>>>>>,
+    /// Undelivered values of the last decoded chunk
+    buffer: std::vec::IntoIter<Vec<u8>>,
+    next_doc_id: u32,
+    done: bool,
+}
+
+impl Stream for StringStream {
+    type Item = Result<String>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(bytes) = self.buffer.next() {
+                let doc_id = self.next_doc_id;
+                self.next_doc_id += 1;
+                return Poll::Ready(Some(String::from_utf8(bytes).map_err(|e| {
+                    Error::Parse(format!("Invalid UTF-8 at doc_id {}: {}", doc_id, e))
+                })));
+            }
+            if self.done {
+                return Poll::Ready(None);
+            }
+
+            if let Some(handle) = self.pending.as_mut() {
+                let result = ready!(Pin::new(handle).poll(cx));
+                self.pending = None;
+                match result {
+                    Ok(Ok(values)) => self.buffer = values.into_iter(),
+                    Ok(Err(e)) => {
+                        self.done = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    Err(e) => {
+                        self.done = true;
+                        return Poll::Ready(Some(Err(Error::Io(std::io::Error::other(e)))));
+                    }
+                }
+                continue;
+            }
+
+            if self.next_chunk >= self.num_chunks {
+                self.done = true;
+                continue;
+            }
+            let reader = self.reader.clone();
+            let chunk = self.next_chunk;
+            self.next_chunk += 1;
+            self.pending = Some(tokio::task::spawn_blocking(move || {
+                reader.chunk_values(chunk)
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::var_byte_region;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_string_stream_matches_get_string() {
+        let values: Vec<String> = (0..250).map(|i| format!("player_{}", i * 7)).collect();
+        let bytes: Vec<&[u8]> = values.iter().map(|v| v.as_bytes()).collect();
+        let region = var_byte_region(&bytes, 32);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        std::fs::write(&path, &region).unwrap();
+        let reader = VarByteChunkReader::read(&path, 0, region.len(), 250).unwrap();
+
+        let expected: Vec<String> = (0..100)
+            .map(|doc| reader.get_string(doc).unwrap())
+            .collect();
+        let streamed: Vec<String> = reader
+            .clone()
+            .into_string_stream()
+            .take(100)
+            .map(|value| value.unwrap())
+            .collect()
+            .await;
+        assert_eq!(streamed, expected);
+
+        let count = reader.into_string_stream().count().await;
+        assert_eq!(count, 250);
+    }
+
+    #[tokio::test]
+    async fn test_string_stream_reports_invalid_utf8() {
+        let region = var_byte_region(&[b"ok", &[0xFF, 0xFE], b"after"], 2);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        std::fs::write(&path, &region).unwrap();
+        let reader = VarByteChunkReader::read(&path, 0, region.len(), 3).unwrap();
+
+        let items: Vec<Result<String>> = reader.into_string_stream().collect().await;
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].as_ref().unwrap(), "ok");
+        assert!(items[1]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("doc_id 1"));
+        assert_eq!(items[2].as_ref().unwrap(), "after");
+    }
+}
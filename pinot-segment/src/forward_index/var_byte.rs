@@ -1,9 +1,21 @@
 use crate::error::{Error, Result};
+use crate::forward_index::Utf8Policy;
+#[cfg(feature = "arrow")]
+use arrow_array::{builder::make_view, StringViewArray};
+#[cfg(feature = "arrow")]
+use arrow_buffer::{Buffer, ScalarBuffer};
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
 const METADATA_ENTRY_SIZE: usize = 8; // 4 bytes docId + 4 bytes offset
+const MAGIC_MARKER_SIZE: usize = 8;
+const HEADER_SIZE: usize = 16;
+const HUGE_VALUE_FLAG: u32 = 0x80000000;
+/// Longest value an Arrow string view stores inline instead of pointing at a buffer
+#[cfg(feature = "arrow")]
+const MAX_INLINE_VIEW_LEN: usize = 12;
 
 // Compression type constants (from Pinot ChunkCompressionType)
 const PASS_THROUGH: i32 = 0;
@@ -12,8 +24,61 @@ const ZSTANDARD: i32 = 2;
 const LZ4: i32 = 3;
 const LZ4_LENGTH_PREFIXED: i32 = 4;
 
+/// Byte order of multi-byte values in a forward index
+///
+/// Pinot writes the chunk metadata entries of a V4 var-byte index
+/// little-endian, but some builds write them big-endian. Fixed-bit indexes
+/// pack values big-endian, except in V1 segments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ByteOrder {
+    #[default]
+    LittleEndian,
+    BigEndian,
+}
+
+impl ByteOrder {
+    /// Decode a `u32` in this byte order
+    pub fn read_u32(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+        }
+    }
+
+    /// Detect the byte order from the first (up to two) metadata entries
+    ///
+    /// The first entry always starts at doc 0 and chunk offset 0, so its doc
+    /// id word holds at most the huge-value flag, and the second entry's doc
+    /// id is a doc count in `1..=total_docs`. Little-endian wins when both
+    /// orders fit, e.g. for a single chunk of regular values.
+    fn detect(entries: &[u8], total_docs: u32) -> Self {
+        let fits = |order: ByteOrder| {
+            entries
+                .chunks_exact(METADATA_ENTRY_SIZE)
+                .take(2)
+                .enumerate()
+                .all(|(idx, entry)| {
+                    let doc_id =
+                        order.read_u32([entry[0], entry[1], entry[2], entry[3]]) & !HUGE_VALUE_FLAG;
+                    let offset = order.read_u32([entry[4], entry[5], entry[6], entry[7]]);
+                    match idx {
+                        0 => doc_id == 0 && offset == 0,
+                        _ => doc_id > 0 && doc_id <= total_docs,
+                    }
+                })
+        };
+
+        if !fits(ByteOrder::LittleEndian) && fits(ByteOrder::BigEndian) {
+            ByteOrder::BigEndian
+        } else {
+            ByteOrder::LittleEndian
+        }
+    }
+}
+
 /// Variable-byte chunk forward index reader for RAW (non-dictionary) columns
 /// Version 4 format (different from v2/v3)
+#[derive(Debug, Clone)]
 pub struct VarByteChunkReader {
     file_path: PathBuf,
     base_offset: usize,
@@ -24,11 +89,27 @@ pub struct VarByteChunkReader {
     metadata_size: usize,
     chunks_offset: usize,
     total_docs: u32,
+    metadata_byte_order: ByteOrder,
 }
 
 impl VarByteChunkReader {
     /// Read variable-byte chunk forward index (V4 format)
+    ///
+    /// The byte order of the chunk metadata is detected from its leading
+    /// entries; see [`read_with_byte_order`](Self::read_with_byte_order).
     pub fn read(file_path: &Path, offset: usize, size: usize, total_docs: u32) -> Result<Self> {
+        Self::read_with_byte_order(file_path, offset, size, total_docs, None)
+    }
+
+    /// Like [`read`](Self::read), with the metadata byte order given up front
+    /// when the segment's writer is known to use one, detected when `None`
+    pub fn read_with_byte_order(
+        file_path: &Path,
+        offset: usize,
+        size: usize,
+        total_docs: u32,
+        metadata_byte_order: Option<ByteOrder>,
+    ) -> Result<Self> {
         let mut file = File::open(file_path)?;
         file.seek(SeekFrom::Start(offset as u64))?;
 
@@ -72,11 +153,29 @@ impl VarByteChunkReader {
             )));
         }
 
+        // chunks_start_offset counts from the header, which follows the magic
+        // marker if there is one; every other position is derived from it.
         // Metadata starts at byte 16 (after header) and goes until chunks_start_offset
-        let header_end = if has_magic { offset + 8 + 16 } else { offset + 16 };
-        let metadata_offset = header_end;
-        let metadata_size = chunks_start_offset - 16;
-        let chunks_offset = offset + chunks_start_offset + if has_magic { 8 } else { 0 };
+        let header_offset = offset + if has_magic { MAGIC_MARKER_SIZE } else { 0 };
+        let metadata_offset = header_offset + HEADER_SIZE;
+        let metadata_size = chunks_start_offset.checked_sub(HEADER_SIZE).ok_or_else(|| {
+            Error::InvalidFormat(format!(
+                "Chunks start offset {} lies inside the {}-byte header",
+                chunks_start_offset, HEADER_SIZE
+            ))
+        })?;
+        let chunks_offset = header_offset + chunks_start_offset;
+
+        // Otherwise detect the metadata byte order from the leading entries
+        let metadata_byte_order = match metadata_byte_order {
+            Some(order) => order,
+            None => {
+                let mut leading_entries = vec![0u8; metadata_size.min(2 * METADATA_ENTRY_SIZE)];
+                file.seek(SeekFrom::Start(metadata_offset as u64))?;
+                file.read_exact(&mut leading_entries)?;
+                ByteOrder::detect(&leading_entries, total_docs)
+            }
+        };
 
         Ok(VarByteChunkReader {
             file_path: file_path.to_path_buf(),
@@ -88,9 +187,45 @@ impl VarByteChunkReader {
             metadata_size,
             chunks_offset,
             total_docs,
+            metadata_byte_order,
         })
     }
 
+    /// Byte order of the chunk metadata entries
+    pub fn metadata_byte_order(&self) -> ByteOrder {
+        self.metadata_byte_order
+    }
+
+    /// Bytes of chunk data, from `chunks_offset` to the end of the forward index
+    ///
+    /// `forward_index_size` covers the whole region, magic marker included, and
+    /// `chunks_offset` already skips the marker, so the last chunk ends at the
+    /// region end whether or not the marker is present.
+    fn chunks_size(&self) -> usize {
+        self.forward_index_size
+            .saturating_sub(self.chunks_offset - self.base_offset)
+    }
+
+    /// Bytes of the chunk spanning `chunk_offset..chunk_limit`
+    fn chunk_size(chunk_offset: usize, chunk_limit: usize) -> Result<usize> {
+        chunk_limit.checked_sub(chunk_offset).ok_or_else(|| {
+            Error::InvalidFormat(format!(
+                "Chunk at offset {} ends before it starts (limit {})",
+                chunk_offset, chunk_limit
+            ))
+        })
+    }
+
+    /// Split a metadata entry into its doc id word (with the huge-value flag)
+    /// and its chunk offset
+    fn decode_entry(&self, entry: &[u8; 8]) -> (u32, usize) {
+        let order = self.metadata_byte_order;
+        (
+            order.read_u32([entry[0], entry[1], entry[2], entry[3]]),
+            order.read_u32([entry[4], entry[5], entry[6], entry[7]]) as usize,
+        )
+    }
+
     /// Binary search metadata to find chunk index for given doc_id
     fn find_chunk_metadata(&self, doc_id: u32) -> Result<(usize, usize)> {
         let mut file = File::open(&self.file_path)?;
@@ -107,7 +242,7 @@ impl VarByteChunkReader {
             let mut entry = [0u8; 8];
             file.read_exact(&mut entry)?;
 
-            let entry_doc_id = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]) & 0x7FFFFFFF;
+            let entry_doc_id = self.decode_entry(&entry).0 & !HUGE_VALUE_FLAG;
 
             if entry_doc_id < doc_id {
                 low = mid as i64 + 1;
@@ -129,43 +264,46 @@ impl VarByteChunkReader {
         // Find the chunk containing this doc_id
         let (metadata_pos, entry_idx) = self.find_chunk_metadata(doc_id)?;
 
-        // Read metadata entry (8 bytes, little-endian)
+        // Read metadata entry (8 bytes)
         file.seek(SeekFrom::Start((self.metadata_offset + metadata_pos) as u64))?;
         let mut entry = [0u8; 8];
         file.read_exact(&mut entry)?;
 
-        let chunk_doc_id_offset = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]) & 0x7FFFFFFF;
-        let chunk_offset = u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]) as usize;
+        let (doc_id_word, chunk_offset) = self.decode_entry(&entry);
+        let chunk_doc_id_offset = doc_id_word & !HUGE_VALUE_FLAG;
 
         // Check if this is a "huge value" (single value spanning entire chunk)
-        let is_regular_chunk = (u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]) & 0x80000000) == 0;
+        let is_regular_chunk = (doc_id_word & HUGE_VALUE_FLAG) == 0;
 
         // Determine chunk limit and num_docs
         let (chunk_limit, num_docs_in_chunk) = if (entry_idx + 1) * METADATA_ENTRY_SIZE < self.metadata_size {
             // Read next entry to get limit and calculate num_docs
             let mut next_entry = [0u8; 8];
             file.read_exact(&mut next_entry)?;
-            let next_doc_id = u32::from_le_bytes([next_entry[0], next_entry[1], next_entry[2], next_entry[3]]) & 0x7FFFFFFF;
-            let next_chunk_offset = u32::from_le_bytes([next_entry[4], next_entry[5], next_entry[6], next_entry[7]]) as usize;
+            let (next_doc_id, next_chunk_offset) = self.decode_entry(&next_entry);
+            let next_doc_id = next_doc_id & !HUGE_VALUE_FLAG;
 
             // Check if next_chunk_offset is sentinel value (0xFFFFFFFF means end of chunks)
             if next_chunk_offset == 0xFFFFFFFF {
-                // Last chunk - use forward index size to calculate limit
-                let limit = self.forward_index_size - (self.chunks_offset - self.base_offset);
-                (limit, 0) // num_docs will be read from decompressed chunk
+                // Last chunk - runs to the end of the forward index
+                (self.chunks_size(), 0) // num_docs will be read from decompressed chunk
             } else {
-                let num_docs = (next_doc_id - chunk_doc_id_offset) as usize;
-                (next_chunk_offset, num_docs)
+                let num_docs = next_doc_id.checked_sub(chunk_doc_id_offset).ok_or_else(|| {
+                    Error::InvalidFormat(format!(
+                        "Chunk metadata doc ids decrease: {} after {}",
+                        next_doc_id, chunk_doc_id_offset
+                    ))
+                })?;
+                (next_chunk_offset, num_docs as usize)
             }
         } else {
-            // Last chunk - use forward index size to calculate limit
-            let limit = self.forward_index_size - (self.chunks_offset - self.base_offset);
+            // Last chunk - runs to the end of the forward index
             // For last chunk, we need to determine num_docs from the decompressed data
             // We'll handle this below
-            (limit, 0)
+            (self.chunks_size(), 0)
         };
 
-        let chunk_size = chunk_limit - chunk_offset;
+        let chunk_size = Self::chunk_size(chunk_offset, chunk_limit)?;
 
         // Read chunk data
         file.seek(SeekFrom::Start((self.chunks_offset + chunk_offset) as u64))?;
@@ -191,117 +329,39 @@ impl VarByteChunkReader {
 
         // If we don't know num_docs (last chunk), read it from the decompressed chunk
         let num_docs_in_chunk = if num_docs_in_chunk == 0 {
-            if decompressed_chunk.len() < 8 {
-                return Err(Error::InvalidFormat("Decompressed chunk too small".to_string()));
-            }
-            // Read num_docs from first 4 bytes
-            u32::from_le_bytes([
-                decompressed_chunk[0],
-                decompressed_chunk[1],
-                decompressed_chunk[2],
-                decompressed_chunk[3],
-            ]) as usize
+            chunk_num_docs(&decompressed_chunk)?
         } else {
             num_docs_in_chunk
         };
 
         // Calculate index within chunk
-        let doc_index_in_chunk = (doc_id - chunk_doc_id_offset) as usize;
-
-        if doc_index_in_chunk >= num_docs_in_chunk {
-            return Err(Error::InvalidFormat(format!(
-                "doc_id {} not in chunk (chunk starts at {}, has {} docs)",
-                doc_id, chunk_doc_id_offset, num_docs_in_chunk
-            )));
-        }
-
-        // Read offset for this document (offset array starts at position 4, after num_docs field)
-        let offset_pos = 4 + doc_index_in_chunk * 4;
-        if offset_pos + 4 > decompressed_chunk.len() {
-            return Err(Error::InvalidFormat(format!(
-                "Offset position {} out of range",
-                offset_pos
-            )));
-        }
-
-        let value_offset = u32::from_le_bytes([
-            decompressed_chunk[offset_pos],
-            decompressed_chunk[offset_pos + 1],
-            decompressed_chunk[offset_pos + 2],
-            decompressed_chunk[offset_pos + 3],
-        ]) as usize;
-
-        // For the last document in chunk, use chunk size as next offset
-        // Otherwise read next offset from array
-        let next_offset = if doc_index_in_chunk == num_docs_in_chunk - 1 {
-            decompressed_chunk.len()
-        } else {
-            let next_offset_pos = offset_pos + 4;
-            if next_offset_pos + 4 > decompressed_chunk.len() {
-                return Err(Error::InvalidFormat(format!(
-                    "Next offset position {} out of range",
-                    next_offset_pos
-                )));
-            }
-            u32::from_le_bytes([
-                decompressed_chunk[next_offset_pos],
-                decompressed_chunk[next_offset_pos + 1],
-                decompressed_chunk[next_offset_pos + 2],
-                decompressed_chunk[next_offset_pos + 3],
-            ]) as usize
-        };
+        let doc_index_in_chunk = doc_id
+            .checked_sub(chunk_doc_id_offset)
+            .map(|idx| idx as usize)
+            .filter(|&idx| idx < num_docs_in_chunk)
+            .ok_or_else(|| {
+                Error::InvalidFormat(format!(
+                    "doc_id {} not in chunk (chunk starts at {}, has {} docs)",
+                    doc_id, chunk_doc_id_offset, num_docs_in_chunk
+                ))
+            })?;
 
-        if value_offset > decompressed_chunk.len() || next_offset > decompressed_chunk.len() {
-            return Err(Error::InvalidFormat(format!(
-                "Value offsets out of range: {} to {} (chunk size: {})",
-                value_offset, next_offset, decompressed_chunk.len()
-            )));
-        }
-
-        let value_bytes = decompressed_chunk[value_offset..next_offset].to_vec();
-        Ok(value_bytes)
+        let value = chunk_value_range(&decompressed_chunk, num_docs_in_chunk, doc_index_in_chunk)?;
+        Ok(decompressed_chunk[value].to_vec())
     }
 
-    /// Decompress chunk data based on compression type
-    fn decompress_chunk(&self, compressed_data: &[u8]) -> Result<Vec<u8>> {
+    /// Check that chunks use a compression type this reader can decode
+    ///
+    /// Lets callers reject a column up front instead of on the first chunk read.
+    pub fn check_compression(&self) -> Result<()> {
         match self.compression_type {
-            PASS_THROUGH => Ok(compressed_data.to_vec()),
-            LZ4 | LZ4_LENGTH_PREFIXED => {
-                #[cfg(feature = "lz4")]
-                {
-                    // For LZ4_LENGTH_PREFIXED, first 4 bytes contain the decompressed size
-                    let (decompressed_size, compressed_bytes) = if self.compression_type == LZ4_LENGTH_PREFIXED {
-                        if compressed_data.len() < 4 {
-                            return Err(Error::InvalidFormat(
-                                "LZ4_LENGTH_PREFIXED data too short for length prefix".to_string(),
-                            ));
-                        }
-                        let size = u32::from_le_bytes([
-                            compressed_data[0],
-                            compressed_data[1],
-                            compressed_data[2],
-                            compressed_data[3],
-                        ]) as usize;
-                        (size, &compressed_data[4..])
-                    } else {
-                        (self.target_decompressed_chunk_size as usize, compressed_data)
-                    };
-
-                    // Decompress using lz4 block decompression
-                    let decompressed = lz4::block::decompress(compressed_bytes, Some(decompressed_size as i32))
-                        .map_err(|e| {
-                            Error::InvalidFormat(format!("LZ4 decompression failed: {}", e))
-                        })?;
-
-                    Ok(decompressed)
-                }
-                #[cfg(not(feature = "lz4"))]
-                {
-                    Err(Error::UnsupportedFeature(
-                        "LZ4 compression support not enabled. Enable 'lz4' feature.".to_string(),
-                    ))
-                }
-            }
+            PASS_THROUGH => Ok(()),
+            #[cfg(feature = "lz4")]
+            LZ4 | LZ4_LENGTH_PREFIXED => Ok(()),
+            #[cfg(not(feature = "lz4"))]
+            LZ4 | LZ4_LENGTH_PREFIXED => Err(Error::UnsupportedFeature(
+                "LZ4 compression support not enabled. Enable 'lz4' feature.".to_string(),
+            )),
             SNAPPY => Err(Error::UnsupportedFeature(
                 "Snappy compression not yet supported".to_string(),
             )),
@@ -315,6 +375,43 @@ impl VarByteChunkReader {
         }
     }
 
+    /// Decompress chunk data based on compression type
+    fn decompress_chunk(&self, compressed_data: &[u8]) -> Result<Vec<u8>> {
+        self.check_compression()?;
+
+        #[cfg(feature = "lz4")]
+        if matches!(self.compression_type, LZ4 | LZ4_LENGTH_PREFIXED) {
+            // For LZ4_LENGTH_PREFIXED, first 4 bytes contain the decompressed size
+            let (decompressed_size, compressed_bytes) = if self.compression_type == LZ4_LENGTH_PREFIXED {
+                if compressed_data.len() < 4 {
+                    return Err(Error::InvalidFormat(
+                        "LZ4_LENGTH_PREFIXED data too short for length prefix".to_string(),
+                    ));
+                }
+                let size = u32::from_le_bytes([
+                    compressed_data[0],
+                    compressed_data[1],
+                    compressed_data[2],
+                    compressed_data[3],
+                ]) as usize;
+                (size, &compressed_data[4..])
+            } else {
+                (self.target_decompressed_chunk_size as usize, compressed_data)
+            };
+
+            // Decompress using lz4 block decompression
+            let decompressed = lz4::block::decompress(compressed_bytes, Some(decompressed_size as i32))
+                .map_err(|e| {
+                    Error::InvalidFormat(format!("LZ4 decompression failed: {}", e))
+                })?;
+
+            return Ok(decompressed);
+        }
+
+        // PASS_THROUGH, the only other type check_compression accepts
+        Ok(compressed_data.to_vec())
+    }
+
     /// Read a single value as string
     pub fn get_string(&self, doc_id: u32) -> Result<String> {
         let bytes = self.get_bytes(doc_id)?;
@@ -332,107 +429,314 @@ impl VarByteChunkReader {
     /// instead of calling get_string() for each doc (which re-decompresses chunks)
     fn read_all_strings_chunked(&self) -> Result<Vec<String>> {
         let mut values = Vec::with_capacity(self.total_docs as usize);
+        self.for_each_string(|value| values.push(value.to_string()))?;
+        Ok(values)
+    }
 
-        // Read metadata to find all chunks
-        let num_entries = self.metadata_size / METADATA_ENTRY_SIZE;
-        let mut file = File::open(&self.file_path)?;
+    /// Iterate over every value as a (lossy) UTF-8 string, in doc id order
+    ///
+    /// Chunks are read and decompressed lazily, one at a time, and their
+    /// values yielded before the next chunk is touched, so at most one
+    /// chunk's strings are held in memory (unlike
+    /// [`read_all_strings`](Self::read_all_strings)). An I/O or format error
+    /// is yielded once and ends the iteration.
+    pub fn iter_strings(&self) -> impl Iterator<Item = Result<String>> + '_ {
+        StringIter {
+            reader: self,
+            file: None,
+            next_chunk: 0,
+            buffer: Vec::new().into_iter(),
+            done: false,
+        }
+    }
 
-        // Process each chunk
-        for entry_idx in 0..num_entries {
-            // Read metadata entry
-            file.seek(SeekFrom::Start((self.metadata_offset + entry_idx * METADATA_ENTRY_SIZE) as u64))?;
-            let mut entry = [0u8; 8];
-            file.read_exact(&mut entry)?;
+    /// Decode every value as a (lossy) UTF-8 string, passing each to `f` in doc id order
+    ///
+    /// Each chunk is decompressed once; no owned `String` is allocated per value.
+    pub fn for_each_string<F: FnMut(&str)>(&self, mut f: F) -> Result<()> {
+        self.for_each_value_bytes(|bytes| f(&String::from_utf8_lossy(bytes)))
+    }
 
-            let _chunk_doc_id_offset = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]) & 0x7FFFFFFF;
-            let chunk_offset = u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]) as usize;
-
-            // Check if this is a "huge value"
-            let is_regular_chunk = (u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]) & 0x80000000) == 0;
-
-            // Determine chunk limit
-            let chunk_limit = if (entry_idx + 1) * METADATA_ENTRY_SIZE < self.metadata_size {
-                let mut next_entry = [0u8; 8];
-                file.read_exact(&mut next_entry)?;
-                let next_chunk_offset = u32::from_le_bytes([next_entry[4], next_entry[5], next_entry[6], next_entry[7]]) as usize;
-                if next_chunk_offset == 0xFFFFFFFF {
-                    self.forward_index_size - (self.chunks_offset - self.base_offset)
-                } else {
-                    next_chunk_offset
-                }
-            } else {
-                self.forward_index_size - (self.chunks_offset - self.base_offset)
-            };
+    /// Like [`for_each_string`](Self::for_each_string), decoding with the given policy
+    ///
+    /// Under [`Utf8Policy::Strict`] the first invalid value fails the read
+    /// and no further values are passed to `f`.
+    pub fn for_each_string_with_policy<F: FnMut(&str)>(
+        &self,
+        utf8_policy: Utf8Policy,
+        f: F,
+    ) -> Result<()> {
+        decode_strings(utf8_policy, 0, |g| self.for_each_value_bytes(g), f)
+    }
 
-            let chunk_size = chunk_limit - chunk_offset;
+    /// Like [`for_each_string_with_policy`](Self::for_each_string_with_policy),
+    /// for the documents in `docs` only
+    ///
+    /// Only the chunks holding those documents are read and decompressed.
+    pub fn for_each_string_in_range<F: FnMut(&str)>(
+        &self,
+        docs: Range<u32>,
+        utf8_policy: Utf8Policy,
+        f: F,
+    ) -> Result<()> {
+        let first_doc_id = docs.start;
+        decode_strings(
+            utf8_policy,
+            first_doc_id,
+            |g| self.for_each_value_bytes_in_range(docs, g),
+            f,
+        )
+    }
 
-            // Read and decompress chunk ONCE
-            file.seek(SeekFrom::Start((self.chunks_offset + chunk_offset) as u64))?;
-            let mut chunk_data = vec![0u8; chunk_size];
-            file.read_exact(&mut chunk_data)?;
+    /// Read the values of documents `[start, start + count)` as (lossy) strings
+    ///
+    /// The window is clamped to the column's documents.
+    pub fn read_range(&self, start: u32, count: u32) -> Result<Vec<String>> {
+        let mut values = Vec::with_capacity(count.min(self.total_docs) as usize);
+        self.for_each_string_in_range(
+            start..start.saturating_add(count),
+            Utf8Policy::Lossy,
+            |value| values.push(value.to_string()),
+        )?;
+        Ok(values)
+    }
 
-            let decompressed_chunk = if self.compression_type == PASS_THROUGH {
-                chunk_data
-            } else {
-                self.decompress_chunk(&chunk_data)?
-            };
+    /// Pass the bytes of each value of the documents in `docs` to `f`, in
+    /// doc id order
+    ///
+    /// Only the chunks holding those documents are read and decompressed.
+    pub fn for_each_bytes_in_range<F: FnMut(&[u8])>(&self, docs: Range<u32>, f: F) -> Result<()> {
+        self.for_each_value_bytes_in_range(docs, f)
+    }
+
+    /// Read every value into a `StringViewArray`, decoding invalid UTF-8 lossily
+    ///
+    /// See [`read_range_as_string_view_array`](Self::read_range_as_string_view_array).
+    #[cfg(feature = "arrow")]
+    pub fn read_as_string_view_array(&self) -> Result<StringViewArray> {
+        self.read_range_as_string_view_array(0..self.total_docs, Utf8Policy::Lossy)
+    }
 
-            // Handle huge values (single value per chunk)
-            if !is_regular_chunk {
-                values.push(String::from_utf8_lossy(&decompressed_chunk).to_string());
-                continue;
+    /// Read the values of the documents in `docs` into a `StringViewArray`
+    ///
+    /// Each decompressed chunk becomes one of the array's data buffers and
+    /// the views point into it, so no value is copied or allocated on its
+    /// own. Values of up to 12 bytes are inlined in their view, and chunks
+    /// holding only such values are dropped. Under [`Utf8Policy::Lossy`]
+    /// invalid values are replaced by their lossy decoding, kept in an extra
+    /// buffer; under [`Utf8Policy::Strict`] the first one fails the read.
+    #[cfg(feature = "arrow")]
+    pub fn read_range_as_string_view_array(
+        &self,
+        docs: Range<u32>,
+        utf8_policy: Utf8Policy,
+    ) -> Result<StringViewArray> {
+        let docs = docs.start..docs.end.min(self.total_docs);
+        let mut views: Vec<u128> = Vec::with_capacity(docs.len());
+        let mut buffers = Vec::new();
+        // Lossy decodings of invalid values, by index of their view
+        let mut replaced: Vec<(usize, String)> = Vec::new();
+
+        if !docs.is_empty() {
+            let mut file = File::open(&self.file_path)?;
+            let (_, first_entry) = self.find_chunk_metadata(docs.start)?;
+            for entry_idx in first_entry..self.num_chunks() {
+                let first_doc_id = self.chunk_first_doc_id(&mut file, entry_idx)?;
+                if first_doc_id >= docs.end {
+                    break;
+                }
+                let (chunk, values) = self.read_chunk(&mut file, entry_idx)?;
+                let block_id = buffers.len() as u32;
+                let mut referenced = false;
+                for (doc_id, value) in (first_doc_id..).zip(values) {
+                    if docs.contains(&doc_id) {
+                        let bytes = &chunk[value.clone()];
+                        match std::str::from_utf8(bytes) {
+                            Ok(_) => {
+                                referenced |= bytes.len() > MAX_INLINE_VIEW_LEN;
+                                views.push(make_view(bytes, block_id, value.start as u32));
+                            }
+                            Err(e) if utf8_policy == Utf8Policy::Strict => {
+                                return Err(Error::Parse(format!(
+                                    "Invalid UTF-8 at doc_id {}: {}",
+                                    doc_id, e
+                                )));
+                            }
+                            Err(_) => {
+                                replaced.push((views.len(), String::from_utf8_lossy(bytes).into_owned()));
+                                views.push(0);
+                            }
+                        }
+                    }
+                }
+                if referenced {
+                    buffers.push(Buffer::from_vec(chunk));
+                }
             }
+        }
 
-            // Extract all values from this chunk
-            if decompressed_chunk.len() < 8 {
-                return Err(Error::InvalidFormat("Decompressed chunk too small".to_string()));
+        if !replaced.is_empty() {
+            let block_id = buffers.len() as u32;
+            let mut replacements = Vec::new();
+            for (view_idx, value) in &replaced {
+                views[*view_idx] = make_view(value.as_bytes(), block_id, replacements.len() as u32);
+                replacements.extend_from_slice(value.as_bytes());
             }
+            buffers.push(Buffer::from_vec(replacements));
+        }
 
-            let num_docs_in_chunk = u32::from_le_bytes([
-                decompressed_chunk[0],
-                decompressed_chunk[1],
-                decompressed_chunk[2],
-                decompressed_chunk[3],
-            ]) as usize;
-
-            // Extract all strings from this chunk
-            for doc_idx in 0..num_docs_in_chunk {
-                let offset_pos = 4 + doc_idx * 4;
-                let value_offset = u32::from_le_bytes([
-                    decompressed_chunk[offset_pos],
-                    decompressed_chunk[offset_pos + 1],
-                    decompressed_chunk[offset_pos + 2],
-                    decompressed_chunk[offset_pos + 3],
-                ]) as usize;
+        StringViewArray::try_new(ScalarBuffer::from(views), buffers, None)
+            .map_err(|e| Error::InvalidFormat(format!("Invalid string view array: {}", e)))
+    }
 
-                // For last document in chunk, use chunk size as next offset
-                let next_offset = if doc_idx == num_docs_in_chunk - 1 {
-                    decompressed_chunk.len()
-                } else {
-                    let next_offset_pos = offset_pos + 4;
-                    u32::from_le_bytes([
-                        decompressed_chunk[next_offset_pos],
-                        decompressed_chunk[next_offset_pos + 1],
-                        decompressed_chunk[next_offset_pos + 2],
-                        decompressed_chunk[next_offset_pos + 3],
-                    ]) as usize
-                };
-
-                if value_offset > decompressed_chunk.len() || next_offset > decompressed_chunk.len() {
-                    return Err(Error::InvalidFormat(format!(
-                        "Value offsets out of range: {} to {} (chunk size: {})",
-                        value_offset, next_offset, decompressed_chunk.len()
-                    )));
-                }
+    /// Walk all chunks sequentially, passing each value's bytes to `f`
+    fn for_each_value_bytes<F: FnMut(&[u8])>(&self, mut f: F) -> Result<()> {
+        let mut file = File::open(&self.file_path)?;
+        for entry_idx in 0..self.num_chunks() {
+            self.for_each_chunk_value(&mut file, entry_idx, &mut f)?;
+        }
+        Ok(())
+    }
+
+    /// Walk the chunks overlapping `docs`, passing the bytes of each value in
+    /// `docs` to `f`
+    fn for_each_value_bytes_in_range<F: FnMut(&[u8])>(&self, docs: Range<u32>, mut f: F) -> Result<()> {
+        let docs = docs.start..docs.end.min(self.total_docs);
+        if docs.is_empty() {
+            return Ok(());
+        }
 
-                let value_bytes = &decompressed_chunk[value_offset..next_offset];
-                values.push(String::from_utf8_lossy(value_bytes).to_string());
+        let mut file = File::open(&self.file_path)?;
+        let (_, first_entry) = self.find_chunk_metadata(docs.start)?;
+        for entry_idx in first_entry..self.num_chunks() {
+            let mut doc_id = self.chunk_first_doc_id(&mut file, entry_idx)?;
+            if doc_id >= docs.end {
+                break;
             }
+            self.for_each_chunk_value(&mut file, entry_idx, &mut |bytes: &[u8]| {
+                if docs.contains(&doc_id) {
+                    f(bytes);
+                }
+                doc_id += 1;
+            })?;
         }
+        Ok(())
+    }
+
+    /// Documents held by each chunk, in chunk order
+    ///
+    /// Only the chunk metadata is read; no chunk is decompressed.
+    pub fn chunk_doc_ranges(&self) -> Result<Vec<Range<u32>>> {
+        let mut file = File::open(&self.file_path)?;
+        let first_doc_ids = (0..self.num_chunks())
+            .map(|entry_idx| self.chunk_first_doc_id(&mut file, entry_idx))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(first_doc_ids
+            .iter()
+            .enumerate()
+            .map(|(entry_idx, &start)| {
+                let end = first_doc_ids
+                    .get(entry_idx + 1)
+                    .copied()
+                    .unwrap_or(self.total_docs);
+                start..end.max(start)
+            })
+            .collect())
+    }
+
+    /// Doc id of the first value in chunk `entry_idx`
+    fn chunk_first_doc_id(&self, file: &mut File, entry_idx: usize) -> Result<u32> {
+        file.seek(SeekFrom::Start((self.metadata_offset + entry_idx * METADATA_ENTRY_SIZE) as u64))?;
+        let mut doc_id = [0u8; 4];
+        file.read_exact(&mut doc_id)?;
+        Ok(self.metadata_byte_order.read_u32(doc_id) & !HUGE_VALUE_FLAG)
+    }
 
+    /// Bytes of every value in chunk `entry_idx`, in doc id order
+    #[cfg(feature = "async")]
+    pub(super) fn chunk_values(&self, entry_idx: usize) -> Result<Vec<Vec<u8>>> {
+        let mut file = File::open(&self.file_path)?;
+        let mut values = Vec::new();
+        self.for_each_chunk_value(&mut file, entry_idx, &mut |bytes: &[u8]| {
+            values.push(bytes.to_vec())
+        })?;
         Ok(values)
     }
 
+    /// Number of chunks (one metadata entry each)
+    pub(super) fn num_chunks(&self) -> usize {
+        self.metadata_size / METADATA_ENTRY_SIZE
+    }
+
+    /// Read and decompress chunk `entry_idx` once, passing each value's bytes to `f`
+    fn for_each_chunk_value<F: FnMut(&[u8])>(
+        &self,
+        file: &mut File,
+        entry_idx: usize,
+        f: &mut F,
+    ) -> Result<()> {
+        let (chunk, values) = self.read_chunk(file, entry_idx)?;
+        for value in values {
+            f(&chunk[value]);
+        }
+        Ok(())
+    }
+
+    /// Read and decompress chunk `entry_idx`, returning its bytes and the
+    /// byte range of each of its values, in doc id order
+    fn read_chunk(&self, file: &mut File, entry_idx: usize) -> Result<(Vec<u8>, Vec<Range<usize>>)> {
+        // Read metadata entry
+        file.seek(SeekFrom::Start((self.metadata_offset + entry_idx * METADATA_ENTRY_SIZE) as u64))?;
+        let mut entry = [0u8; 8];
+        file.read_exact(&mut entry)?;
+
+        let (doc_id_word, chunk_offset) = self.decode_entry(&entry);
+
+        // Check if this is a "huge value"
+        let is_regular_chunk = (doc_id_word & HUGE_VALUE_FLAG) == 0;
+
+        // Determine chunk limit
+        let chunk_limit = if (entry_idx + 1) * METADATA_ENTRY_SIZE < self.metadata_size {
+            let mut next_entry = [0u8; 8];
+            file.read_exact(&mut next_entry)?;
+            let (_, next_chunk_offset) = self.decode_entry(&next_entry);
+            if next_chunk_offset == 0xFFFFFFFF {
+                self.chunks_size()
+            } else {
+                next_chunk_offset
+            }
+        } else {
+            self.chunks_size()
+        };
+
+        let chunk_size = Self::chunk_size(chunk_offset, chunk_limit)?;
+
+        // Read and decompress chunk ONCE
+        file.seek(SeekFrom::Start((self.chunks_offset + chunk_offset) as u64))?;
+        let mut chunk_data = vec![0u8; chunk_size];
+        file.read_exact(&mut chunk_data)?;
+
+        let decompressed_chunk = if self.compression_type == PASS_THROUGH {
+            chunk_data
+        } else {
+            self.decompress_chunk(&chunk_data)?
+        };
+
+        // Handle huge values (single value per chunk)
+        if !is_regular_chunk {
+            let len = decompressed_chunk.len();
+            return Ok((decompressed_chunk, std::iter::once(0..len).collect()));
+        }
+
+        // Locate every value in this chunk
+        let num_docs_in_chunk = chunk_num_docs(&decompressed_chunk)?;
+        let values = (0..num_docs_in_chunk)
+            .map(|doc_idx| chunk_value_range(&decompressed_chunk, num_docs_in_chunk, doc_idx))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok((decompressed_chunk, values))
+    }
+
     /// Read all values as raw bytes
     pub fn read_all_bytes(&self) -> Result<Vec<Vec<u8>>> {
         let mut values = Vec::with_capacity(self.total_docs as usize);
@@ -442,3 +746,464 @@ impl VarByteChunkReader {
         Ok(values)
     }
 }
+
+/// Iterator returned by [`VarByteChunkReader::iter_strings`]
+struct StringIter<'a> {
+    reader: &'a VarByteChunkReader,
+    /// Opened on the first call to `next`
+    file: Option<File>,
+    next_chunk: usize,
+    /// Undelivered values of the last decompressed chunk
+    buffer: std::vec::IntoIter<String>,
+    done: bool,
+}
+
+impl StringIter<'_> {
+    /// Decompress the next chunk into `buffer`
+    fn read_chunk(&mut self) -> Result<()> {
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => self.file.insert(File::open(&self.reader.file_path)?),
+        };
+        let mut values = Vec::new();
+        self.reader
+            .for_each_chunk_value(file, self.next_chunk, &mut |bytes: &[u8]| {
+                values.push(String::from_utf8_lossy(bytes).into_owned())
+            })?;
+        self.next_chunk += 1;
+        self.buffer = values.into_iter();
+        Ok(())
+    }
+}
+
+impl Iterator for StringIter<'_> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.buffer.next() {
+                return Some(Ok(value));
+            }
+            if self.done || self.next_chunk >= self.reader.num_chunks() {
+                return None;
+            }
+            if let Err(e) = self.read_chunk() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Little-endian `u32` at `pos` of a decompressed chunk, if the chunk holds it
+fn chunk_u32(chunk: &[u8], pos: usize) -> Option<usize> {
+    let bytes = chunk.get(pos..pos.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+}
+
+/// Number of values in a regular chunk, from its leading 4 bytes
+///
+/// Checked against the chunk's size: the count and the offset array it
+/// implies must fit, so a corrupt count fails instead of indexing past the
+/// chunk.
+fn chunk_num_docs(chunk: &[u8]) -> Result<usize> {
+    if chunk.len() < 8 {
+        return Err(Error::InvalidFormat("Decompressed chunk too small".to_string()));
+    }
+    let num_docs = chunk_u32(chunk, 0).unwrap_or_default();
+    let offsets_end = num_docs.checked_mul(4).and_then(|len| len.checked_add(4));
+    match offsets_end {
+        Some(end) if end <= chunk.len() => Ok(num_docs),
+        _ => Err(Error::InvalidFormat(format!(
+            "Chunk claims {} docs, more than its {} bytes can hold",
+            num_docs,
+            chunk.len()
+        ))),
+    }
+}
+
+/// Byte range of value `doc_idx` in a regular chunk of `num_docs` values
+///
+/// The offset array starts at byte 4, after the doc count; the last value
+/// runs to the end of the chunk.
+fn chunk_value_range(chunk: &[u8], num_docs: usize, doc_idx: usize) -> Result<Range<usize>> {
+    let offset_pos = 4 + doc_idx * 4;
+    let value_offset = chunk_u32(chunk, offset_pos).ok_or_else(|| {
+        Error::InvalidFormat(format!("Offset position {} out of range", offset_pos))
+    })?;
+    let next_offset = if doc_idx + 1 == num_docs {
+        chunk.len()
+    } else {
+        chunk_u32(chunk, offset_pos + 4).ok_or_else(|| {
+            Error::InvalidFormat(format!(
+                "Next offset position {} out of range",
+                offset_pos + 4
+            ))
+        })?
+    };
+
+    if value_offset > next_offset || next_offset > chunk.len() {
+        return Err(Error::InvalidFormat(format!(
+            "Value offsets out of range: {} to {} (chunk size: {})",
+            value_offset,
+            next_offset,
+            chunk.len()
+        )));
+    }
+    Ok(value_offset..next_offset)
+}
+
+/// Decode the values `walk` produces as strings under `utf8_policy`
+///
+/// `first_doc_id` is the doc id of the first value, for error messages.
+fn decode_strings<W, F>(utf8_policy: Utf8Policy, first_doc_id: u32, walk: W, mut f: F) -> Result<()>
+where
+    W: FnOnce(&mut dyn FnMut(&[u8])) -> Result<()>,
+    F: FnMut(&str),
+{
+    if utf8_policy == Utf8Policy::Lossy {
+        return walk(&mut |bytes| f(&String::from_utf8_lossy(bytes)));
+    }
+
+    let mut doc_id = first_doc_id;
+    let mut invalid = None;
+    walk(&mut |bytes| {
+        if invalid.is_none() {
+            match std::str::from_utf8(bytes) {
+                Ok(value) => f(value),
+                Err(e) => invalid = Some((doc_id, e)),
+            }
+        }
+        doc_id += 1;
+    })?;
+
+    match invalid {
+        Some((doc_id, e)) => Err(Error::Parse(format!(
+            "Invalid UTF-8 at doc_id {}: {}",
+            doc_id, e
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::var_byte_region;
+
+    #[test]
+    fn test_read_range_matches_read_all() {
+        let values: Vec<String> = (0..100).map(|i| format!("value_{}", i)).collect();
+        let bytes: Vec<&[u8]> = values.iter().map(|v| v.as_bytes()).collect();
+        let region = var_byte_region(&bytes, 16);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        std::fs::write(&path, &region).unwrap();
+        let reader = VarByteChunkReader::read(&path, 0, region.len(), 100).unwrap();
+
+        let all = reader.read_all_strings().unwrap();
+        for (start, count) in [(0, 10), (15, 2), (16, 16), (30, 45), (90, 50), (100, 5)] {
+            let end = (start + count).min(100) as usize;
+            assert_eq!(
+                reader.read_range(start, count).unwrap(),
+                all[start as usize..end],
+                "window {}+{}",
+                start,
+                count
+            );
+        }
+    }
+
+    #[test]
+    fn test_iter_strings_matches_read_all() {
+        let values: Vec<String> = (0..100).map(|i| format!("value_{}", i * 3)).collect();
+        let mut bytes: Vec<&[u8]> = values.iter().map(|v| v.as_bytes()).collect();
+        bytes[42] = &[b'x', 0xFF];
+        let region = var_byte_region(&bytes, 16);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        std::fs::write(&path, &region).unwrap();
+        let reader = VarByteChunkReader::read(&path, 0, region.len(), 100).unwrap();
+
+        let iterated: Vec<String> = reader.iter_strings().map(|v| v.unwrap()).collect();
+        assert_eq!(iterated, reader.read_all_strings().unwrap());
+        assert_eq!(iterated[42], "x\u{FFFD}");
+        assert_eq!(reader.iter_strings().nth(17).unwrap().unwrap(), "value_51");
+
+        // A chunk that cannot be read ends the iteration with its error
+        let truncated = &region[..region.len() - 40];
+        std::fs::write(&path, truncated).unwrap();
+        let reader = VarByteChunkReader::read(&path, 0, truncated.len(), 100).unwrap();
+        let results: Vec<Result<String>> = reader.iter_strings().collect();
+        assert!(results.last().unwrap().is_err());
+        assert!(results.len() < 100);
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn test_string_view_array_points_into_chunks() {
+        // Values of up to 12 bytes are inlined in their views
+        let values: Vec<String> = (0..100)
+            .map(|i| if i % 3 == 0 { format!("v{}", i) } else { format!("a_longer_value_{}", i) })
+            .collect();
+        let mut bytes: Vec<&[u8]> = values.iter().map(|v| v.as_bytes()).collect();
+        bytes[42] = &[b'x', 0xFF];
+        let region = var_byte_region(&bytes, 16);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        std::fs::write(&path, &region).unwrap();
+        let reader = VarByteChunkReader::read(&path, 0, region.len(), 100).unwrap();
+        let all = reader.read_all_strings().unwrap();
+
+        let array = reader.read_as_string_view_array().unwrap();
+        assert_eq!(array.iter().map(Option::unwrap).collect::<Vec<_>>(), all);
+        // One buffer per chunk, plus one for the lossy decoding of doc 42
+        assert_eq!(array.data_buffers().len(), 8);
+
+        let window = reader
+            .read_range_as_string_view_array(20..40, Utf8Policy::Strict)
+            .unwrap();
+        assert_eq!(window.iter().map(Option::unwrap).collect::<Vec<_>>(), all[20..40]);
+        assert_eq!(window.data_buffers().len(), 2);
+
+        let err = reader
+            .read_range_as_string_view_array(30..50, Utf8Policy::Strict)
+            .unwrap_err();
+        assert!(err.to_string().contains("doc_id 42"), "{}", err);
+
+        // Chunks holding only inlined values are not kept
+        let short: Vec<String> = (0..40).map(|i| format!("v{}", i)).collect();
+        let bytes: Vec<&[u8]> = short.iter().map(|v| v.as_bytes()).collect();
+        let region = var_byte_region(&bytes, 16);
+        std::fs::write(&path, &region).unwrap();
+        let reader = VarByteChunkReader::read(&path, 0, region.len(), 40).unwrap();
+        let array = reader.read_as_string_view_array().unwrap();
+        assert_eq!(array.iter().map(Option::unwrap).collect::<Vec<_>>(), short);
+        assert!(array.data_buffers().is_empty());
+    }
+
+    #[test]
+    fn test_corrupt_chunks_are_invalid_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        let read = |region: &[u8]| {
+            std::fs::write(&path, region).unwrap();
+            VarByteChunkReader::read(&path, 0, region.len(), 4).unwrap()
+        };
+        let assert_invalid = |result: Result<_>| match result {
+            Err(Error::InvalidFormat(_)) => {}
+            other => panic!("expected InvalidFormat, got {:?}", other.map(|_| ())),
+        };
+
+        // Two chunks of two values; the metadata (two 8-byte entries)
+        // follows the magic marker and header, then the first chunk
+        let region = var_byte_region(&[b"ab", b"cd", b"ef", b"gh"], 2);
+        let first_chunk = MAGIC_MARKER_SIZE + HEADER_SIZE + 2 * METADATA_ENTRY_SIZE;
+        assert_eq!(read(&region).read_all_strings().unwrap(), ["ab", "cd", "ef", "gh"]);
+
+        // A doc count larger than the chunk can hold
+        let mut corrupt = region.clone();
+        corrupt[first_chunk..first_chunk + 4].copy_from_slice(&0xFFFFu32.to_le_bytes());
+        assert_invalid(read(&corrupt).read_all_strings().map(|_| ()));
+        // The last chunk's doc count is only known from the chunk itself
+        let last_chunk = region.len() - 16;
+        let mut corrupt = region.clone();
+        corrupt[last_chunk..last_chunk + 4].copy_from_slice(&0xFFFFu32.to_le_bytes());
+        assert_invalid(read(&corrupt).get_bytes(3).map(|_| ()));
+
+        // A value that ends before it starts
+        let mut corrupt = region.clone();
+        corrupt[first_chunk + 4..first_chunk + 8].copy_from_slice(&15u32.to_le_bytes());
+        let reader = read(&corrupt);
+        assert_invalid(reader.read_all_strings().map(|_| ()));
+        assert_invalid(reader.get_bytes(0).map(|_| ()));
+
+        // Chunk metadata whose doc ids decrease
+        let mut corrupt = region.clone();
+        let metadata = MAGIC_MARKER_SIZE + HEADER_SIZE;
+        corrupt[metadata..metadata + 4].copy_from_slice(&3u32.to_le_bytes());
+        assert_invalid(read(&corrupt).get_bytes(3).map(|_| ()));
+    }
+
+    #[test]
+    fn test_chunk_doc_ranges() {
+        let values: Vec<String> = (0..40).map(|i| format!("value_{}", i)).collect();
+        let bytes: Vec<&[u8]> = values.iter().map(|v| v.as_bytes()).collect();
+        let region = var_byte_region(&bytes, 16);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        std::fs::write(&path, &region).unwrap();
+        let reader = VarByteChunkReader::read(&path, 0, region.len(), 40).unwrap();
+
+        assert_eq!(
+            reader.chunk_doc_ranges().unwrap(),
+            vec![0..16, 16..32, 32..40]
+        );
+    }
+
+    #[test]
+    fn test_range_reports_invalid_utf8_doc_id() {
+        let region = var_byte_region(&[b"a", b"b", b"c", &[0xFF], b"e"], 2);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        std::fs::write(&path, &region).unwrap();
+        let reader = VarByteChunkReader::read(&path, 0, region.len(), 5).unwrap();
+
+        let mut values = Vec::new();
+        reader
+            .for_each_string_in_range(1..3, Utf8Policy::Strict, |v| values.push(v.to_string()))
+            .unwrap();
+        assert_eq!(values, vec!["b", "c"]);
+
+        let err = reader
+            .for_each_string_in_range(2..5, Utf8Policy::Strict, |_| {})
+            .unwrap_err();
+        assert!(err.to_string().contains("doc_id 3"), "{}", err);
+    }
+
+    /// Rewrite the metadata entries of a `var_byte_region` big-endian
+    fn to_big_endian_metadata(mut region: Vec<u8>) -> Vec<u8> {
+        // Magic marker (8 bytes), then the header ending with the chunks start offset
+        let chunks_start = i32::from_be_bytes(region[20..24].try_into().unwrap()) as usize;
+        for word in region[24..8 + chunks_start].chunks_exact_mut(4) {
+            word.reverse();
+        }
+        region
+    }
+
+    #[test]
+    fn test_metadata_byte_order_round_trip() {
+        let values: Vec<String> = (0..50).map(|i| format!("value_{}", i)).collect();
+        let bytes: Vec<&[u8]> = values.iter().map(|v| v.as_bytes()).collect();
+        let little = var_byte_region(&bytes, 8);
+        let big = to_big_endian_metadata(little.clone());
+
+        // Second entry: the second chunk starts at doc 8
+        assert_eq!(little[32..36], 8u32.to_le_bytes());
+        assert_eq!(big[32..36], 8u32.to_be_bytes());
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut readers = Vec::new();
+        for (name, region, order) in [
+            ("little.psf", &little, ByteOrder::LittleEndian),
+            ("big.psf", &big, ByteOrder::BigEndian),
+        ] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, region).unwrap();
+            let reader = VarByteChunkReader::read(&path, 0, region.len(), 50).unwrap();
+            assert_eq!(reader.metadata_byte_order(), order);
+            readers.push(reader);
+        }
+
+        for reader in &readers {
+            for (doc_id, chunk) in [(0, 0), (7, 0), (8, 1), (30, 3), (49, 6)] {
+                let (_, entry_idx) = reader.find_chunk_metadata(doc_id).unwrap();
+                assert_eq!(entry_idx, chunk, "doc {}", doc_id);
+                assert_eq!(reader.get_string(doc_id).unwrap(), values[doc_id as usize]);
+            }
+            assert_eq!(reader.read_all_strings().unwrap(), values);
+            assert_eq!(reader.read_range(45, 10).unwrap(), values[45..]);
+        }
+    }
+
+    #[test]
+    fn test_detect_byte_order() {
+        let entry = |doc_id: u32, offset: u32, order: ByteOrder| {
+            let (doc_id, offset) = match order {
+                ByteOrder::LittleEndian => (doc_id.to_le_bytes(), offset.to_le_bytes()),
+                ByteOrder::BigEndian => (doc_id.to_be_bytes(), offset.to_be_bytes()),
+            };
+            [doc_id, offset].concat()
+        };
+
+        for order in [ByteOrder::LittleEndian, ByteOrder::BigEndian] {
+            // A single huge value: only the flag tells the orders apart
+            let huge = entry(HUGE_VALUE_FLAG, 0, order);
+            assert_eq!(ByteOrder::detect(&huge, 1), order);
+
+            let two_chunks = [entry(0, 0, order), entry(100, 1200, order)].concat();
+            assert_eq!(ByteOrder::detect(&two_chunks, 150), order);
+        }
+
+        // A single regular chunk reads the same either way
+        assert_eq!(ByteOrder::detect(&[0u8; 8], 10), ByteOrder::LittleEndian);
+    }
+
+    #[test]
+    fn test_magic_prefixed_column_offsets() {
+        use crate::index_map::IndexMap;
+        use crate::testutil::SegmentBuilder;
+
+        // The RAW column follows a dictionary column, so its region starts
+        // well inside columns.psf
+        let values: Vec<String> = (0..2500).map(|i| format!("player_{}", i)).collect();
+        let dir = tempfile::tempdir().unwrap();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .int_column("hits", (0..2500).collect())
+            .raw_string_column("name", values.iter().map(String::as_str).collect())
+            .write(dir.path())
+            .unwrap();
+        let index_map = IndexMap::from_file(&dir.path().join("index_map")).unwrap();
+        let location = index_map.get_forward_index("name").unwrap();
+        assert!(location.start_offset > 0);
+
+        let path = dir.path().join("columns.psf");
+        let reader =
+            VarByteChunkReader::read(&path, location.start_offset, location.size, 2500).unwrap();
+        let header_offset = location.start_offset + MAGIC_MARKER_SIZE;
+        assert_eq!(reader.metadata_offset, header_offset + HEADER_SIZE);
+        assert_eq!(reader.num_chunks(), 3);
+        assert_eq!(
+            reader.chunks_offset,
+            reader.metadata_offset + reader.metadata_size
+        );
+
+        for (doc_id, chunk) in [(0, 0), (999, 0), (1000, 1), (1999, 1), (2000, 2), (2499, 2)] {
+            let (metadata_pos, entry_idx) = reader.find_chunk_metadata(doc_id).unwrap();
+            assert_eq!((metadata_pos, entry_idx), (chunk * 8, chunk), "doc {}", doc_id);
+            assert_eq!(reader.get_string(doc_id).unwrap(), values[doc_id as usize]);
+        }
+    }
+
+    #[test]
+    fn test_huge_value_last_chunk_with_magic() {
+        let huge = "h".repeat(5000);
+        // Regular chunk of two values, then a huge value filling the last chunk
+        let regular = var_byte_region(&[b"ab", b"cde"], 2);
+        let chunks_start = i32::from_be_bytes(regular[20..24].try_into().unwrap()) as usize;
+        let first_chunk = &regular[8 + chunks_start..];
+
+        let mut region = regular[..8].to_vec();
+        region.extend_from_slice(&regular[8..20]);
+        region.extend_from_slice(&(chunks_start as i32 + 8).to_be_bytes());
+        region.extend_from_slice(&regular[24..8 + chunks_start]);
+        region.extend_from_slice(&(2 | HUGE_VALUE_FLAG).to_le_bytes());
+        region.extend_from_slice(&(first_chunk.len() as u32).to_le_bytes());
+        region.extend_from_slice(first_chunk);
+        region.extend_from_slice(huge.as_bytes());
+
+        // Surround the region with other data, as in columns.psf
+        let mut file = vec![0xAB; 13];
+        file.extend_from_slice(&region);
+        file.extend_from_slice(&[0xCD; 7]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        std::fs::write(&path, &file).unwrap();
+
+        let reader = VarByteChunkReader::read(&path, 13, region.len(), 3).unwrap();
+        assert_eq!(reader.get_string(1).unwrap(), "cde");
+        assert_eq!(reader.get_string(2).unwrap(), huge);
+        assert_eq!(
+            reader.read_all_strings().unwrap(),
+            vec!["ab", "cde", huge.as_str()]
+        );
+
+        // A region cut short of its chunk data is rejected, not read past
+        let reader = VarByteChunkReader::read(&path, 13, 8 + chunks_start + 4, 3).unwrap();
+        assert!(reader.get_bytes(2).is_err());
+    }
+}
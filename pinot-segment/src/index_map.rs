@@ -7,9 +7,89 @@ use std::path::Path;
 pub struct IndexLocation {
     pub start_offset: usize,
     pub size: usize,
+    /// Codec the whole region is compressed with (`compressionType`), if any
+    pub compression: Option<String>,
+    /// Region size after decompression (`uncompressedSize`)
+    pub uncompressed_size: Option<usize>,
 }
 
-#[derive(Debug)]
+impl IndexLocation {
+    /// Whether the region must be decompressed before it can be parsed
+    pub fn is_compressed(&self) -> bool {
+        self.compression
+            .as_deref()
+            .is_some_and(|c| {
+                !matches!(
+                    RegionCompression::from_string(c),
+                    Ok(RegionCompression::PassThrough)
+                )
+            })
+    }
+}
+
+/// Compression applied to an entire index region inside columns.psf
+///
+/// Distinct from the per-chunk compression of var-byte forward indexes,
+/// which the chunk reader handles itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionCompression {
+    PassThrough,
+    Lz4,
+}
+
+impl RegionCompression {
+    pub fn from_string(s: &str) -> Result<Self> {
+        match s {
+            "PASS_THROUGH" | "NONE" => Ok(RegionCompression::PassThrough),
+            "LZ4" => Ok(RegionCompression::Lz4),
+            _ => Err(Error::UnsupportedFeature(format!(
+                "Index region compression {} not supported",
+                s
+            ))),
+        }
+    }
+
+    /// Decompress a region whose decompressed length is `uncompressed_size`
+    pub fn decompress(&self, data: &[u8], uncompressed_size: usize) -> Result<Vec<u8>> {
+        let decompressed = match self {
+            RegionCompression::PassThrough => data.to_vec(),
+            RegionCompression::Lz4 => {
+                #[cfg(feature = "lz4")]
+                {
+                    lz4::block::decompress(data, Some(uncompressed_size as i32)).map_err(|e| {
+                        Error::InvalidFormat(format!("LZ4 decompression failed: {}", e))
+                    })?
+                }
+                #[cfg(not(feature = "lz4"))]
+                {
+                    return Err(Error::UnsupportedFeature(
+                        "LZ4 compression requires 'lz4' feature".to_string(),
+                    ));
+                }
+            }
+        };
+
+        if decompressed.len() != uncompressed_size {
+            return Err(Error::InvalidFormat(format!(
+                "Decompressed index region is {} bytes, expected {}",
+                decompressed.len(),
+                uncompressed_size
+            )));
+        }
+        Ok(decompressed)
+    }
+}
+
+/// Index types this crate knows how to read; other entries are still parsed
+/// and reported through [`IndexMap::index_types_for_column`]
+const SUPPORTED_INDEX_TYPES: &[&str] = &[
+    "dictionary",
+    "forward_index",
+    "nullvalue_vector",
+    "range_index",
+];
+
+#[derive(Debug, Clone)]
 pub struct IndexMap {
     /// Maps (column_name, index_type) -> IndexLocation
     pub indexes: HashMap<(String, String), IndexLocation>,
@@ -56,22 +136,40 @@ impl IndexMap {
                 // Everything before is the column name
                 let column_name = parts[..parts.len() - 2].join(".");
 
-                let value_num = value
-                    .parse::<usize>()
-                    .map_err(|e| Error::Parse(format!("Invalid number '{}': {}", value, e)))?;
+                let key = (column_name, index_type.to_string());
 
-                let key = (column_name.to_string(), index_type.to_string());
-
-                let location = indexes.entry(key).or_insert(IndexLocation {
-                    start_offset: 0,
-                    size: 0,
+                let location = indexes.entry(key).or_insert_with_key(|(column, index_type)| {
+                    if !SUPPORTED_INDEX_TYPES.contains(&index_type.as_str()) {
+                        tracing::debug!(
+                            "Column '{}' has unsupported index type '{}'; it will be ignored",
+                            column,
+                            index_type
+                        );
+                    }
+                    IndexLocation {
+                        start_offset: 0,
+                        size: 0,
+                        compression: None,
+                        uncompressed_size: None,
+                    }
                 });
 
-                match property {
-                    "startOffset" => location.start_offset = value_num,
-                    "size" => location.size = value_num,
-                    _ => {} // Ignore unknown properties
+                // The codec is validated when the region is read, so an
+                // unsupported codec only fails queries touching that index
+                if property == "compressionType" {
+                    location.compression = Some(value.to_string());
+                    continue;
                 }
+
+                let field = match property {
+                    "startOffset" => &mut location.start_offset,
+                    "size" => &mut location.size,
+                    "uncompressedSize" => location.uncompressed_size.insert(0),
+                    _ => continue, // Ignore unknown properties
+                };
+                *field = value
+                    .parse::<usize>()
+                    .map_err(|e| Error::Parse(format!("Invalid number '{}': {}", value, e)))?;
             }
         }
 
@@ -83,6 +181,24 @@ impl IndexMap {
             .get(&(column.to_string(), index_type.to_string()))
     }
 
+    /// Whether the column has an index of the given type, including types
+    /// this crate cannot read (e.g. `fst_index`, `h3_index`)
+    pub fn has_index(&self, column: &str, index_type: &str) -> bool {
+        self.get_index(column, index_type).is_some()
+    }
+
+    /// Every index type listed for the column, sorted by name
+    pub fn index_types_for_column(&self, column: &str) -> Vec<&str> {
+        let mut types: Vec<&str> = self
+            .indexes
+            .keys()
+            .filter(|(name, _)| name == column)
+            .map(|(_, index_type)| index_type.as_str())
+            .collect();
+        types.sort_unstable();
+        types
+    }
+
     pub fn get_dictionary(&self, column: &str) -> Option<&IndexLocation> {
         self.get_index(column, "dictionary")
     }
@@ -92,6 +208,85 @@ impl IndexMap {
     }
 }
 
+/// Locations of the star-tree parts inside the segment's `star_tree_index`
+/// file, parsed from its `star_tree_index_map`
+///
+/// Keys have the form `startree{id}.STAR_TREE.{OFFSET|SIZE}` for the tree
+/// itself and `startree{id}.{column}.FORWARD_INDEX.{OFFSET|SIZE}` for the
+/// forward index of a dimension or metric (`sum__hits`, `count__*`) over the
+/// star-tree documents. Offsets count from the start of `star_tree_index`.
+#[derive(Debug, Clone, Default)]
+pub struct StarTreeIndexMap {
+    /// Maps (tree id, column) -> location; the column is `None` for the tree
+    pub indexes: HashMap<(usize, Option<String>), IndexLocation>,
+}
+
+impl StarTreeIndexMap {
+    /// Parse a star_tree_index_map file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let mut indexes = HashMap::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            // Column names may contain dots, so the key is split from both ends
+            let parts: Vec<&str> = key.trim().split('.').collect();
+            let (Some(tree), [.., index_type, property]) = (
+                parts[0]
+                    .strip_prefix("startree")
+                    .and_then(|id| id.parse::<usize>().ok()),
+                parts.as_slice(),
+            ) else {
+                continue;
+            };
+            let column = match *index_type {
+                "STAR_TREE" if parts.len() == 3 => None,
+                "FORWARD_INDEX" if parts.len() > 3 => Some(parts[1..parts.len() - 2].join(".")),
+                _ => continue,
+            };
+
+            let location = indexes.entry((tree, column)).or_insert(IndexLocation {
+                start_offset: 0,
+                size: 0,
+                compression: None,
+                uncompressed_size: None,
+            });
+            let field = match *property {
+                "OFFSET" => &mut location.start_offset,
+                "SIZE" => &mut location.size,
+                _ => continue,
+            };
+            let value = value.trim();
+            *field = value
+                .parse::<usize>()
+                .map_err(|e| Error::Parse(format!("Invalid number '{}': {}", value, e)))?;
+        }
+
+        Ok(StarTreeIndexMap { indexes })
+    }
+
+    /// Region of star-tree `tree_id` itself: its header and nodes
+    pub fn get_star_tree(&self, tree_id: usize) -> Option<&IndexLocation> {
+        self.indexes.get(&(tree_id, None))
+    }
+
+    /// Forward index of `column` over the documents of star-tree `tree_id`
+    pub fn get_forward_index(&self, tree_id: usize, column: &str) -> Option<&IndexLocation> {
+        self.indexes.get(&(tree_id, Some(column.to_string())))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,6 +321,51 @@ playerID.forward_index.size=5000000
         assert_eq!(player_fwd.start_offset, 50050);
     }
 
+    #[test]
+    fn test_parse_region_compression() {
+        let content = r#"
+hits.dictionary.startOffset=0
+hits.dictionary.size=20
+hits.dictionary.compressionType=LZ4
+hits.dictionary.uncompressedSize=48
+hits.forward_index.startOffset=20
+hits.forward_index.size=10
+hits.forward_index.compressionType=PASS_THROUGH
+"#;
+
+        let index_map = IndexMap::parse(content).unwrap();
+        let dict = index_map.get_dictionary("hits").unwrap();
+        assert_eq!(dict.compression.as_deref(), Some("LZ4"));
+        assert_eq!(dict.uncompressed_size, Some(48));
+        assert!(dict.is_compressed());
+        assert!(!index_map.get_forward_index("hits").unwrap().is_compressed());
+        assert!(RegionCompression::from_string("SNAPPY").is_err());
+    }
+
+    #[test]
+    fn test_unknown_index_types() {
+        let content = r#"
+city.dictionary.startOffset=0
+city.dictionary.size=64
+city.fst_index.startOffset=64
+city.fst_index.size=128
+city.fst_index.fstType=LUCENE
+location.h3_index.startOffset=192
+location.h3_index.size=32
+"#;
+
+        let index_map = IndexMap::parse(content).unwrap();
+        assert_eq!(
+            index_map.index_types_for_column("city"),
+            vec!["dictionary", "fst_index"]
+        );
+        assert!(index_map.has_index("city", "fst_index"));
+        assert!(!index_map.has_index("city", "h3_index"));
+        assert!(index_map.has_index("location", "h3_index"));
+        assert_eq!(index_map.get_index("city", "fst_index").unwrap().size, 128);
+        assert!(index_map.index_types_for_column("missing").is_empty());
+    }
+
     #[test]
     fn test_column_name_with_dots() {
         let content = r#"
@@ -138,4 +378,29 @@ some.column.name.dictionary.size=200
         assert_eq!(dict.start_offset, 100);
         assert_eq!(dict.size, 200);
     }
+
+    #[test]
+    fn test_parse_star_tree_index_map() {
+        let content = r#"
+startree0.STAR_TREE.OFFSET = 0
+startree0.STAR_TREE.SIZE = 160
+startree0.country.FORWARD_INDEX.OFFSET = 160
+startree0.country.FORWARD_INDEX.SIZE = 12
+startree0.count__*.FORWARD_INDEX.OFFSET = 172
+startree0.count__*.FORWARD_INDEX.SIZE = 300
+startree1.some.column.FORWARD_INDEX.OFFSET = 472
+startree1.some.column.FORWARD_INDEX.SIZE = 8
+"#;
+
+        let index_map = StarTreeIndexMap::parse(content).unwrap();
+        let tree = index_map.get_star_tree(0).unwrap();
+        assert_eq!((tree.start_offset, tree.size), (0, 160));
+        let count = index_map.get_forward_index(0, "count__*").unwrap();
+        assert_eq!((count.start_offset, count.size), (172, 300));
+        assert_eq!(index_map.get_forward_index(1, "some.column").unwrap().size, 8);
+        assert!(index_map.get_star_tree(1).is_none());
+        assert!(index_map.get_forward_index(1, "country").is_none());
+
+        assert!(StarTreeIndexMap::parse("startree0.STAR_TREE.SIZE = x").is_err());
+    }
 }
@@ -3,9 +3,24 @@ pub mod metadata;
 pub mod index_map;
 pub mod forward_index;
 pub mod segment_reader;
+pub mod range_index;
+pub mod null_bitmap;
+pub mod star_tree;
+
+#[cfg(any(test, feature = "testutil"))]
+pub mod testutil;
 
 pub use error::{Error, Result};
-pub use metadata::{ColumnMetadata, DataType, SegmentMetadata};
-pub use index_map::{IndexLocation, IndexMap};
-pub use forward_index::{DictionaryReader, FixedBitWidthReader, VarByteChunkReader};
-pub use segment_reader::SegmentReader;
+pub use metadata::{
+    ColumnMetadata, DataType, DefaultNullValue, SegmentMetadata, SegmentVersion, StarTreeMetadata,
+    TableType, TimeUnit,
+};
+pub use index_map::{IndexLocation, IndexMap, RegionCompression, StarTreeIndexMap};
+pub use forward_index::{
+    ByteOrder, DictionaryReader, FixedBitWidthReader, FixedByteChunkReader, Utf8Policy,
+    VarByteChunkReader,
+};
+pub use segment_reader::{ColumnChunks, ColumnData, DocCountMismatchPolicy, SegmentReader, CHUNK_BATCH_SIZE};
+pub use null_bitmap::{DocIdSet, NullBitmapReader};
+pub use range_index::{RangeIndexReader, RangeMatch, RangeValue, ValueRange};
+pub use star_tree::{AggFn, ColumnValue, StarTreeIndexReader};
@@ -1,5 +1,6 @@
 use crate::error::{Error, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::Path;
 
@@ -27,6 +28,168 @@ impl DataType {
             _ => Err(Error::Parse(format!("Unknown data type: {}", s))),
         }
     }
+
+    /// Name Pinot uses for the type in metadata (`INT`, `STRING`, ...)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataType::Int => "INT",
+            DataType::Long => "LONG",
+            DataType::Float => "FLOAT",
+            DataType::Double => "DOUBLE",
+            DataType::String => "STRING",
+            DataType::Bytes => "BYTES",
+            DataType::Boolean => "BOOLEAN",
+        }
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Value Pinot stores in place of null (`defaultNullValue`), typed by the
+/// column's data type
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefaultNullValue {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    /// Hex-decoded, as Pinot writes BYTES values to the properties file
+    Bytes(Vec<u8>),
+    Boolean(bool),
+}
+
+impl DefaultNullValue {
+    /// Parse `value` as written for a column of `data_type`
+    ///
+    /// BOOLEAN defaults may be recorded either as `true`/`false` or as the
+    /// stored `1`/`0`.
+    pub fn parse(data_type: &DataType, value: &str) -> Result<Self> {
+        let invalid = || Error::Parse(format!("Invalid {:?} default null value: {}", data_type, value));
+        match data_type {
+            DataType::Int => value.parse().map(DefaultNullValue::Int).map_err(|_| invalid()),
+            DataType::Long => value.parse().map(DefaultNullValue::Long).map_err(|_| invalid()),
+            DataType::Float => value.parse().map(DefaultNullValue::Float).map_err(|_| invalid()),
+            DataType::Double => value.parse().map(DefaultNullValue::Double).map_err(|_| invalid()),
+            DataType::String => Ok(DefaultNullValue::String(value.to_string())),
+            DataType::Bytes => decode_hex(value).map(DefaultNullValue::Bytes).ok_or_else(invalid),
+            DataType::Boolean => match value {
+                "true" | "1" => Ok(DefaultNullValue::Boolean(true)),
+                "false" | "0" => Ok(DefaultNullValue::Boolean(false)),
+                _ => Err(invalid()),
+            },
+        }
+    }
+}
+
+/// Side of a (possibly hybrid) Pinot table a segment belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableType {
+    Offline,
+    /// Segments produced from a stream; the latest ones may still be consuming
+    Realtime,
+}
+
+impl TableType {
+    /// Suffix Pinot appends to table names (`OFFLINE` / `REALTIME`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TableType::Offline => "OFFLINE",
+            TableType::Realtime => "REALTIME",
+        }
+    }
+
+    /// Type named by a `_OFFLINE` / `_REALTIME` suffix or infix in `name`
+    fn from_name(name: &str) -> Option<Self> {
+        [TableType::Realtime, TableType::Offline]
+            .into_iter()
+            .find(|table_type| {
+                let tag = format!("_{}", table_type.as_str());
+                name.ends_with(&tag) || name.contains(&format!("{}_", tag))
+            })
+    }
+}
+
+/// On-disk format of a segment (`segment.index.version`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentVersion {
+    /// Pre-V3 layout; fixed-bit forward indexes have no magic marker and
+    /// pack values little-endian
+    V1,
+    V2,
+    /// Single-file layout (`columns.psf`)
+    V3,
+}
+
+impl SegmentVersion {
+    /// Parse `v1`, `v2` or `v3`; other values are not formats this crate knows
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s {
+            "v1" => Some(SegmentVersion::V1),
+            "v2" => Some(SegmentVersion::V2),
+            "v3" => Some(SegmentVersion::V3),
+            _ => None,
+        }
+    }
+}
+
+/// Unit of a segment's time column values (`segment.time.unit`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl TimeUnit {
+    pub fn from_string(s: &str) -> Result<Self> {
+        match s {
+            "NANOSECONDS" => Ok(TimeUnit::Nanoseconds),
+            "MICROSECONDS" => Ok(TimeUnit::Microseconds),
+            "MILLISECONDS" => Ok(TimeUnit::Milliseconds),
+            "SECONDS" => Ok(TimeUnit::Seconds),
+            "MINUTES" => Ok(TimeUnit::Minutes),
+            "HOURS" => Ok(TimeUnit::Hours),
+            "DAYS" => Ok(TimeUnit::Days),
+            _ => Err(Error::Parse(format!("Unknown time unit: {}", s))),
+        }
+    }
+
+    /// Convert a value in this unit to milliseconds, rounding down
+    pub fn to_millis_floor(&self, value: i64) -> i64 {
+        match self {
+            TimeUnit::Nanoseconds => value.div_euclid(1_000_000),
+            TimeUnit::Microseconds => value.div_euclid(1_000),
+            _ => value.saturating_mul(self.millis_per_unit()),
+        }
+    }
+
+    /// Convert a value in this unit to milliseconds, rounding up
+    pub fn to_millis_ceil(&self, value: i64) -> i64 {
+        match self {
+            TimeUnit::Nanoseconds => -(-value).div_euclid(1_000_000),
+            TimeUnit::Microseconds => -(-value).div_euclid(1_000),
+            _ => value.saturating_mul(self.millis_per_unit()),
+        }
+    }
+
+    fn millis_per_unit(&self) -> i64 {
+        match self {
+            TimeUnit::Nanoseconds | TimeUnit::Microseconds | TimeUnit::Milliseconds => 1,
+            TimeUnit::Seconds => 1_000,
+            TimeUnit::Minutes => 60_000,
+            TimeUnit::Hours => 3_600_000,
+            TimeUnit::Days => 86_400_000,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -38,22 +201,136 @@ pub struct ColumnMetadata {
     pub bits_per_element: u8,
     pub has_dictionary: bool,
     pub is_sorted: bool,
+    /// Whether some documents are null (`hasNullValue`), recorded in the
+    /// column's null value vector
+    pub has_null_value: bool,
+    /// Whether each document holds a list of values (`isSingleValue=false`)
+    pub is_multi_value: bool,
+    /// Most values any document holds (`maxNumberOfMultiValues`); 0 for
+    /// single-value columns
+    pub max_multi_values: u32,
+    /// Values across all documents (`totalNumberOfEntries`); equals
+    /// `total_docs` for single-value columns
+    pub total_number_of_entries: u32,
+    /// Raw `lengthOfEachEntry`: the null-padded width of STRING dictionary
+    /// entries, but the unpadded value length of BYTES entries
     pub length_of_each_entry: usize,
+    /// Width STRING dictionary entries are null-padded to; 0 for other types
+    /// and variable-length dictionaries (see `padded_length_for_dictionary`)
+    pub padded_length: usize,
+    /// Whether the dictionary is front-coded (`dictionaryType=FRONT_CODED`),
+    /// storing STRING or BYTES entries as prefixes shared with earlier ones
+    pub front_coded_dictionary: bool,
+    /// Smallest value recorded by the segment writer (`minValue`), unparsed
+    pub min_value: Option<String>,
+    /// Largest value recorded by the segment writer (`maxValue`), unparsed
+    pub max_value: Option<String>,
+    /// Value written for null entries (`defaultNullValue`); `None` when the
+    /// segment does not record one
+    pub default_null_value: Option<DefaultNullValue>,
+}
+
+impl ColumnMetadata {
+    /// `DICTIONARY` or `RAW`, as the forward index is encoded
+    pub fn encoding(&self) -> &'static str {
+        if self.has_dictionary {
+            "DICTIONARY"
+        } else {
+            "RAW"
+        }
+    }
+
+    /// Average number of values per document; 1.0 for single-value columns
+    /// and 0.0 for an empty segment
+    pub fn avg_multi_values_per_doc(&self) -> f32 {
+        if self.total_docs == 0 {
+            return 0.0;
+        }
+        self.total_number_of_entries as f32 / self.total_docs as f32
+    }
+
+    /// Fixed width of the column's STRING dictionary entries, whose values
+    /// are null-padded to it; 0 for other types and variable-length entries
+    pub fn padded_length_for_dictionary(&self) -> usize {
+        match self.data_type {
+            DataType::String => self.length_of_each_entry,
+            _ => 0,
+        }
+    }
 }
 
-#[derive(Debug)]
+/// One line, e.g. `hits: INT, DICTIONARY (4 bits), cardinality=10, sorted`
+impl fmt::Display for ColumnMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}, {}", self.name, self.data_type, self.encoding())?;
+        if self.has_dictionary {
+            write!(f, " ({} bits)", self.bits_per_element)?;
+        }
+        write!(f, ", cardinality={}", self.cardinality)?;
+        if self.is_sorted {
+            write!(f, ", sorted")?;
+        }
+        if self.is_multi_value {
+            write!(f, ", multi-value (max {})", self.max_multi_values)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct SegmentMetadata {
     pub segment_name: String,
     pub table_name: String,
     pub total_docs: u32,
     pub columns: HashMap<String, ColumnMetadata>,
+    /// Time column the segment is partitioned on (`segment.time.column.name`)
+    pub time_column: Option<String>,
+    /// Smallest time column value, in `time_unit` (`segment.start.time`)
+    pub start_time: Option<i64>,
+    /// Largest time column value, in `time_unit` (`segment.end.time`)
+    pub end_time: Option<i64>,
+    pub time_unit: Option<TimeUnit>,
+    /// Segment format (`segment.index.version`), when recorded
+    pub segment_version: Option<SegmentVersion>,
+    /// Pinot version that wrote the segment (`segment.creator.version`)
+    pub creator_version: Option<String>,
+    /// Segment generator implementation (`segment.generator.class.name`)
+    pub generator_class: Option<String>,
+    /// Checksum of the segment data, from `creation.meta`
+    pub crc: Option<u64>,
+    /// When the segment was built, in epoch milliseconds, from `creation.meta`
+    pub creation_time: Option<i64>,
+    /// Star-trees built for the segment (`startree.count`), in tree id order
+    pub star_trees: Vec<StarTreeMetadata>,
+}
+
+/// A star-tree recorded in metadata.properties under `startree.<id>.`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StarTreeMetadata {
+    /// Dimension columns, in the order the tree splits on them (`split.order`)
+    pub dimensions: Vec<String>,
+    /// Pre-aggregated metrics named `<function>__<column>`, e.g. `sum__hits`
+    /// or `count__*` (`function.column.pairs`)
+    pub function_column_pairs: Vec<String>,
+    /// Documents of the star-tree, pre-aggregated ones included (`total.docs`)
+    pub total_docs: u32,
+    /// Largest number of documents a leaf node holds (`max.leaf.records`)
+    pub max_leaf_records: Option<u32>,
 }
 
 impl SegmentMetadata {
     /// Parse metadata.properties file
+    ///
+    /// The CRC and creation time are read from the `creation.meta` file next
+    /// to it when present.
     pub fn from_file(path: &Path) -> Result<Self> {
         let content = fs::read_to_string(path)?;
-        Self::parse(&content)
+        let mut metadata = Self::parse(&content)?;
+        if let Some((crc, creation_time)) = read_creation_meta(&path.with_file_name("creation.meta")) {
+            metadata.crc = Some(crc);
+            metadata.creation_time = Some(creation_time);
+        }
+        Ok(metadata)
     }
 
     fn parse(content: &str) -> Result<Self> {
@@ -96,6 +373,31 @@ impl SegmentMetadata {
             .parse::<u32>()
             .map_err(|e| Error::Parse(format!("Invalid total.docs: {}", e)))?;
 
+        // Time range metadata is optional (absent for tables without a time column)
+        let time_column = properties
+            .get("segment.time.column.name")
+            .filter(|s| !s.is_empty())
+            .cloned();
+        let start_time = Self::parse_optional_i64(&properties, "segment.start.time")?;
+        let end_time = Self::parse_optional_i64(&properties, "segment.end.time")?;
+        let time_unit = properties
+            .get("segment.time.unit")
+            .filter(|s| !s.is_empty())
+            .map(|s| TimeUnit::from_string(s))
+            .transpose()?;
+
+        let segment_version = properties
+            .get("segment.index.version")
+            .and_then(|s| SegmentVersion::from_string(s));
+        let creator_version = properties
+            .get("segment.creator.version")
+            .filter(|s| !s.is_empty())
+            .cloned();
+        let generator_class = properties
+            .get("segment.generator.class.name")
+            .filter(|s| !s.is_empty())
+            .cloned();
+
         // Parse column metadata
         let mut columns = HashMap::new();
 
@@ -118,9 +420,12 @@ impl SegmentMetadata {
             column_names.extend(datetime_cols.split(',').map(|s| s.trim()));
         }
 
-        // Parse each column
+        // Parse each column once, in the order first listed. Virtual columns
+        // (`$docId`, `$segmentName`, ...) are computed by Pinot at query
+        // time and have no stored data or dataType.
+        let mut seen = HashSet::new();
         for column_name in column_names {
-            if column_name.is_empty() {
+            if column_name.is_empty() || column_name.starts_with('$') || !seen.insert(column_name) {
                 continue;
             }
 
@@ -128,14 +433,117 @@ impl SegmentMetadata {
             columns.insert(column_name.to_string(), col_meta);
         }
 
+        let star_trees = Self::parse_star_trees(&properties)?;
+
         Ok(SegmentMetadata {
             segment_name,
             table_name,
             total_docs,
             columns,
+            time_column,
+            start_time,
+            end_time,
+            time_unit,
+            segment_version,
+            creator_version,
+            generator_class,
+            crc: None,
+            creation_time: None,
+            star_trees,
         })
     }
 
+    /// Parse the `startree.<id>.*` entries of the `startree.count` trees
+    ///
+    /// A tree missing its split order, function column pairs or document
+    /// count is skipped with a warning, so the segment stays readable by scan.
+    fn parse_star_trees(properties: &HashMap<String, String>) -> Result<Vec<StarTreeMetadata>> {
+        let count = match properties.get("startree.count").filter(|s| !s.is_empty()) {
+            Some(count) => count
+                .parse::<usize>()
+                .map_err(|e| Error::Parse(format!("Invalid startree.count: {}", e)))?,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut star_trees = Vec::with_capacity(count);
+        for tree_id in 0..count {
+            let get_prop = |suffix: &str| {
+                properties
+                    .get(&format!("startree.{}.{}", tree_id, suffix))
+                    .filter(|s| !s.is_empty())
+            };
+            let list = |value: &String| -> Vec<String> {
+                value.split(',').map(|s| s.trim().to_string()).collect()
+            };
+            let (Some(split_order), Some(pairs), Some(total_docs)) = (
+                get_prop("split.order"),
+                get_prop("function.column.pairs"),
+                get_prop("total.docs"),
+            ) else {
+                tracing::warn!(
+                    "Star-tree {} has incomplete metadata; it will be ignored",
+                    tree_id
+                );
+                continue;
+            };
+            let total_docs = total_docs.parse::<u32>().map_err(|e| {
+                Error::Parse(format!("Invalid startree.{}.total.docs: {}", tree_id, e))
+            })?;
+            star_trees.push(StarTreeMetadata {
+                dimensions: list(split_order),
+                function_column_pairs: list(pairs),
+                total_docs,
+                max_leaf_records: get_prop("max.leaf.records").and_then(|s| s.parse().ok()),
+            });
+        }
+        Ok(star_trees)
+    }
+
+    fn parse_optional_i64(properties: &HashMap<String, String>, key: &str) -> Result<Option<i64>> {
+        match properties.get(key).filter(|s| !s.is_empty()) {
+            Some(value) => value
+                .parse::<i64>()
+                .map(Some)
+                .map_err(|e| Error::Parse(format!("Invalid {}: {}", key, e))),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether the segment was written by Pinot `major.minor` or newer
+    ///
+    /// Returns `false` when the creator version is missing or unparseable
+    /// (e.g. builds from source report values like `1.1.0-SNAPSHOT-abc123`,
+    /// which parse as 1.1).
+    pub fn is_created_by_pinot_version_at_least(&self, major: u32, minor: u32) -> bool {
+        self.creator_version
+            .as_deref()
+            .and_then(parse_major_minor)
+            .is_some_and(|version| version >= (major, minor))
+    }
+
+    /// Whether the segment is OFFLINE or REALTIME, inferred from its names
+    ///
+    /// Checks `segment.table.name` (e.g. `events_REALTIME`), then the segment
+    /// name, then the LLC realtime naming scheme
+    /// `{table}__{partition}__{sequence}__{creationTime}`. `None` when no
+    /// name says either way, as for segments of plain `open_table` layouts.
+    pub fn table_type(&self) -> Option<TableType> {
+        TableType::from_name(&self.table_name)
+            .or_else(|| TableType::from_name(&self.segment_name))
+            .or_else(|| is_llc_segment_name(&self.segment_name).then_some(TableType::Realtime))
+    }
+
+    /// Segment time range converted to epoch milliseconds (inclusive)
+    ///
+    /// `None` when the segment lacks start/end time or a time unit.
+    pub fn time_range_millis(&self) -> Option<(i64, i64)> {
+        let unit = self.time_unit?;
+        Some((
+            unit.to_millis_floor(self.start_time?),
+            unit.to_millis_ceil(self.end_time?),
+        ))
+    }
+
     fn parse_column_metadata(
         name: &str,
         properties: &HashMap<String, String>,
@@ -167,10 +575,50 @@ impl SegmentMetadata {
             .map(|s| s == "true")
             .unwrap_or(false);
 
+        let has_null_value = get_prop("hasNullValue")
+            .map(|s| s == "true")
+            .unwrap_or(false);
+
+        let is_multi_value = get_prop("isSingleValue")
+            .map(|s| s == "false")
+            .unwrap_or(false);
+
+        let max_multi_values = get_prop("maxNumberOfMultiValues")
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(0);
+
+        let total_number_of_entries = get_prop("totalNumberOfEntries")
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(total_docs);
+
         let length_of_each_entry = get_prop("lengthOfEachEntry")
             .and_then(|s| s.parse::<usize>().ok())
             .unwrap_or(0);
 
+        // Pinot sets minMaxValueInvalid when a value could not be stored in the
+        // properties file; the recorded min/max must not be trusted then
+        let min_max_invalid = get_prop("minMaxValueInvalid")
+            .map(|s| s == "true")
+            .unwrap_or(false);
+        let (min_value, max_value) = if min_max_invalid {
+            (None, None)
+        } else {
+            (get_prop("minValue"), get_prop("maxValue"))
+        };
+
+        let default_null_value = get_prop("defaultNullValue")
+            .map(|s| DefaultNullValue::parse(&data_type, &s))
+            .transpose()?;
+
+        let padded_length = match data_type {
+            DataType::String => length_of_each_entry,
+            _ => 0,
+        };
+
+        let front_coded_dictionary = get_prop("dictionaryType")
+            .map(|s| s.eq_ignore_ascii_case("FRONT_CODED"))
+            .unwrap_or(false);
+
         Ok(ColumnMetadata {
             name: name.to_string(),
             data_type,
@@ -179,7 +627,16 @@ impl SegmentMetadata {
             bits_per_element,
             has_dictionary,
             is_sorted,
+            has_null_value,
+            is_multi_value,
+            max_multi_values,
+            total_number_of_entries,
             length_of_each_entry,
+            padded_length,
+            front_coded_dictionary,
+            min_value,
+            max_value,
+            default_null_value,
         })
     }
 
@@ -224,7 +681,10 @@ impl SegmentMetadata {
                             result.push('\\');
                         }
                         _ => {
-                            result.push(ch);
+                            // Java properties drop the backslash before any
+                            // other character (e.g. escaped `\,` or `\:`)
+                            chars.next();
+                            result.push(next);
                         }
                     }
                 } else {
@@ -245,6 +705,97 @@ impl SegmentMetadata {
     }
 }
 
+/// Summary header followed by one row per column, sorted by name:
+///
+/// ```text
+/// Segment test_segment of table testTable: 100 docs, 2 columns
+///   name  type    cardinality  encoding
+///   col1  INT     10           DICTIONARY
+///   col2  STRING  50           RAW
+/// ```
+impl fmt::Display for SegmentMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Segment {} of table {}: {} docs, {} columns",
+            self.segment_name,
+            self.table_name,
+            self.total_docs,
+            self.columns.len()
+        )?;
+        if self.columns.is_empty() {
+            return Ok(());
+        }
+
+        let mut columns: Vec<&ColumnMetadata> = self.columns.values().collect();
+        columns.sort_by(|a, b| a.name.cmp(&b.name));
+        let types: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                if c.is_multi_value {
+                    format!("{}[]", c.data_type)
+                } else {
+                    c.data_type.to_string()
+                }
+            })
+            .collect();
+        let name_width = columns.iter().map(|c| c.name.len()).max().unwrap_or(0).max(4);
+        let type_width = types.iter().map(String::len).max().unwrap_or(0).max(4);
+
+        write!(
+            f,
+            "\n  {:<name_width$}  {:<type_width$}  {:<11}  encoding",
+            "name", "type", "cardinality"
+        )?;
+        for (column, data_type) in columns.iter().zip(&types) {
+            write!(
+                f,
+                "\n  {:<name_width$}  {:<type_width$}  {:<11}  {}",
+                column.name,
+                data_type,
+                column.cardinality,
+                column.encoding()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Read `creation.meta`: the segment CRC and creation time as two
+/// big-endian longs (Java `DataOutputStream`). `None` when absent or short
+fn read_creation_meta(path: &Path) -> Option<(u64, i64)> {
+    let bytes = fs::read(path).ok()?;
+    let crc = u64::from_be_bytes(bytes.get(0..8)?.try_into().ok()?);
+    let creation_time = i64::from_be_bytes(bytes.get(8..16)?.try_into().ok()?);
+    Some((crc, creation_time))
+}
+
+/// Whether `name` follows `{table}__{partition}__{sequence}__{creationTime}`
+/// Decode a hex string such as `deadbeef`; `None` if it is not valid hex
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn is_llc_segment_name(name: &str) -> bool {
+    let parts: Vec<&str> = name.split("__").collect();
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    parts.len() == 4 && is_number(parts[1]) && is_number(parts[2])
+}
+
+/// Parse the leading `major.minor` of a version string such as `1.2.0-SNAPSHOT`
+fn parse_major_minor(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    Some((major, minor))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +817,7 @@ column.col2.cardinality=50
 column.col2.bitsPerElement=6
 column.col2.hasDictionary=true
 column.col2.isSorted=true
+column.col2.lengthOfEachEntry=12
 "#;
 
         let metadata = SegmentMetadata::parse(content).unwrap();
@@ -281,6 +833,212 @@ column.col2.isSorted=true
         assert_eq!(col1.bits_per_element, 4);
         assert!(col1.has_dictionary);
         assert!(!col1.is_sorted);
+        assert!(!col1.is_multi_value);
+        assert_eq!(col1.max_multi_values, 0);
+        assert_eq!(col1.padded_length_for_dictionary(), 0);
+
+        let col2 = metadata.get_column("col2").unwrap();
+        assert_eq!(col2.length_of_each_entry, 12);
+        assert_eq!(col2.padded_length, 12);
+        assert_eq!(col2.padded_length_for_dictionary(), 12);
+    }
+
+    #[test]
+    fn test_display() {
+        let content = r#"
+segment.name=test_segment
+segment.table.name=testTable
+segment.total.docs=100
+columns=hits,playerName,tags
+column.hits.dataType=INT
+column.hits.cardinality=10
+column.hits.bitsPerElement=4
+column.hits.hasDictionary=true
+column.hits.isSorted=true
+column.playerName.dataType=STRING
+column.playerName.cardinality=100
+column.playerName.hasDictionary=false
+column.tags.dataType=STRING
+column.tags.cardinality=5
+column.tags.bitsPerElement=3
+column.tags.hasDictionary=true
+column.tags.isSingleValue=false
+column.tags.maxNumberOfMultiValues=4
+"#;
+
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        assert_eq!(
+            metadata.get_column("hits").unwrap().to_string(),
+            "hits: INT, DICTIONARY (4 bits), cardinality=10, sorted"
+        );
+        assert_eq!(
+            metadata.get_column("playerName").unwrap().to_string(),
+            "playerName: STRING, RAW, cardinality=100"
+        );
+        assert_eq!(
+            metadata.get_column("tags").unwrap().to_string(),
+            "tags: STRING, DICTIONARY (3 bits), cardinality=5, multi-value (max 4)"
+        );
+
+        let rendered = metadata.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "Segment test_segment of table testTable: 100 docs, 3 columns");
+        assert_eq!(lines[1], "  name        type      cardinality  encoding");
+        assert_eq!(lines[2], "  hits        INT       10           DICTIONARY");
+        assert_eq!(lines[3], "  playerName  STRING    100          RAW");
+        assert_eq!(lines[4], "  tags        STRING[]  5            DICTIONARY");
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_multi_value_column() {
+        let content = r#"
+segment.name=test_segment
+segment.table.name=testTable
+segment.total.docs=10
+columns=tags,id
+column.tags.dataType=STRING
+column.tags.hasDictionary=true
+column.tags.isSingleValue=false
+column.tags.maxNumberOfMultiValues=4
+column.tags.hasNullValue=true
+column.id.dataType=INT
+column.id.isSingleValue=true
+"#;
+
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        let tags = metadata.get_column("tags").unwrap();
+        assert!(tags.has_null_value);
+        assert!(!metadata.get_column("id").unwrap().has_null_value);
+        assert!(tags.is_multi_value);
+        assert_eq!(tags.max_multi_values, 4);
+        assert!(!metadata.get_column("id").unwrap().is_multi_value);
+    }
+
+    #[test]
+    fn test_parse_total_number_of_entries() {
+        let content = r#"
+segment.name=test_segment
+segment.table.name=testTable
+segment.total.docs=10
+columns=tags,id
+column.tags.dataType=STRING
+column.tags.isSingleValue=false
+column.tags.maxNumberOfMultiValues=4
+column.tags.totalNumberOfEntries=25
+column.id.dataType=INT
+column.id.isSingleValue=true
+"#;
+
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        let tags = metadata.get_column("tags").unwrap();
+        assert_eq!(tags.total_number_of_entries, 25);
+        assert_eq!(tags.avg_multi_values_per_doc(), 2.5);
+
+        // Single-value columns hold exactly one entry per document
+        let id = metadata.get_column("id").unwrap();
+        assert_eq!(id.total_number_of_entries, 10);
+        assert_eq!(id.avg_multi_values_per_doc(), 1.0);
+    }
+
+    #[test]
+    fn test_parse_time_range() {
+        let content = r#"
+segment.name=test_segment
+segment.table.name=testTable
+segment.total.docs=0
+segment.time.column.name=daysSinceEpoch
+segment.start.time=19000
+segment.end.time=19001
+segment.time.unit=DAYS
+"#;
+
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        assert_eq!(metadata.time_column.as_deref(), Some("daysSinceEpoch"));
+        assert_eq!(metadata.start_time, Some(19000));
+        assert_eq!(metadata.end_time, Some(19001));
+        assert_eq!(metadata.time_unit, Some(TimeUnit::Days));
+        assert_eq!(
+            metadata.time_range_millis(),
+            Some((19000 * 86_400_000, 19001 * 86_400_000))
+        );
+
+        let content = "segment.name=s\nsegment.table.name=t\nsegment.total.docs=0\n";
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        assert!(metadata.time_column.is_none());
+        assert!(metadata.time_range_millis().is_none());
+    }
+
+    #[test]
+    fn test_parse_star_trees() {
+        let content = r#"
+segment.name=s
+segment.table.name=t
+segment.total.docs=100
+startree.count=2
+startree.0.split.order=country,browser
+startree.0.function.column.pairs=count__*,sum__clicks
+startree.0.max.leaf.records=10000
+startree.0.total.docs=42
+startree.1.split.order=browser
+"#;
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        // Tree 1 has no function column pairs or document count
+        assert_eq!(
+            metadata.star_trees,
+            vec![StarTreeMetadata {
+                dimensions: vec!["country".to_string(), "browser".to_string()],
+                function_column_pairs: vec!["count__*".to_string(), "sum__clicks".to_string()],
+                total_docs: 42,
+                max_leaf_records: Some(10000),
+            }]
+        );
+
+        let content = "segment.name=s\nsegment.table.name=t\nsegment.total.docs=0\n";
+        assert!(SegmentMetadata::parse(content).unwrap().star_trees.is_empty());
+        let content = format!("{}startree.count=x\n", content);
+        assert!(matches!(SegmentMetadata::parse(&content), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn test_creator_version() {
+        let content = r#"
+segment.name=s
+segment.table.name=t
+segment.total.docs=0
+segment.creator.version=1.1.0-SNAPSHOT-5a57ab2
+segment.index.version=v3
+segment.generator.class.name=org.apache.pinot.segment.local.segment.creator.impl.SegmentIndexCreationDriverImpl
+"#;
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        assert_eq!(metadata.creator_version.as_deref(), Some("1.1.0-SNAPSHOT-5a57ab2"));
+        assert_eq!(metadata.segment_version, Some(SegmentVersion::V3));
+        assert!(metadata.generator_class.as_deref().unwrap().ends_with("DriverImpl"));
+        assert!(metadata.is_created_by_pinot_version_at_least(1, 1));
+        assert!(metadata.is_created_by_pinot_version_at_least(0, 12));
+        assert!(!metadata.is_created_by_pinot_version_at_least(1, 2));
+        assert!(!metadata.is_created_by_pinot_version_at_least(2, 0));
+
+        let content = "segment.name=s\nsegment.table.name=t\nsegment.total.docs=0\n";
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        assert!(metadata.creator_version.is_none());
+        assert!(metadata.segment_version.is_none());
+        assert!(!metadata.is_created_by_pinot_version_at_least(0, 0));
+    }
+
+    #[test]
+    fn test_parse_major_minor() {
+        assert_eq!(parse_major_minor("0.12.1"), Some((0, 12)));
+        assert_eq!(parse_major_minor("1"), Some((1, 0)));
+        assert_eq!(parse_major_minor("unknown"), None);
+    }
+
+    #[test]
+    fn test_time_unit_rounding() {
+        assert_eq!(TimeUnit::Nanoseconds.to_millis_floor(1_999_999), 1);
+        assert_eq!(TimeUnit::Nanoseconds.to_millis_ceil(1_000_001), 2);
+        assert_eq!(TimeUnit::Microseconds.to_millis_floor(-1), -1);
+        assert_eq!(TimeUnit::Seconds.to_millis_floor(2), 2_000);
     }
 
     #[test]
@@ -288,5 +1046,143 @@ column.col2.isSorted=true
         assert_eq!(SegmentMetadata::decode_java_string("hello"), "hello");
         assert_eq!(SegmentMetadata::decode_java_string("hello\\u0020world"), "hello world");
         assert_eq!(SegmentMetadata::decode_java_string("tab\\there"), "tab\there");
+        assert_eq!(SegmentMetadata::decode_java_string("a\\,b\\:c"), "a,b:c");
+        assert_eq!(SegmentMetadata::decode_java_string("back\\\\slash"), "back\\slash");
+    }
+
+    #[test]
+    fn test_parse_min_max_values() {
+        let content = r#"
+segment.name=test_segment
+segment.table.name=testTable
+segment.total.docs=3
+segment.dimension.column.names=team,hits
+column.team.dataType=STRING
+column.team.minValue=ANA
+column.team.maxValue=WS1
+column.hits.dataType=INT
+column.hits.minValue=0
+column.hits.maxValue=262
+column.hits.minMaxValueInvalid=true
+"#;
+
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        let team = metadata.get_column("team").unwrap();
+        assert_eq!(team.min_value.as_deref(), Some("ANA"));
+        assert_eq!(team.max_value.as_deref(), Some("WS1"));
+        let hits = metadata.get_column("hits").unwrap();
+        assert!(hits.min_value.is_none());
+        assert!(hits.max_value.is_none());
+    }
+
+    #[test]
+    fn test_parse_overlapping_column_lists() {
+        let content = r#"
+segment.name=test_segment
+segment.table.name=testTable
+segment.total.docs=3
+columns=team,hits,$docId,ts
+segment.dimension.column.names=team,$segmentName
+segment.metric.column.names=hits
+segment.datetime.column.names=ts,hits
+column.team.dataType=STRING
+column.hits.dataType=INT
+column.ts.dataType=LONG
+"#;
+
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        let mut names: Vec<&String> = metadata.columns.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["hits", "team", "ts"]);
+        assert!(metadata.get_column("$docId").is_err());
+
+        // Field-spec lists alone are enough
+        let content = content.replace("columns=team,hits,$docId,ts\n", "");
+        let metadata = SegmentMetadata::parse(&content).unwrap();
+        assert_eq!(metadata.columns.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_default_null_values() {
+        let content = r#"
+segment.name=test_segment
+segment.table.name=testTable
+segment.total.docs=3
+columns=hits,ts,score,ratio,team,payload,active,plain
+column.hits.dataType=INT
+column.hits.defaultNullValue=-2147483648
+column.ts.dataType=LONG
+column.ts.defaultNullValue=-9223372036854775808
+column.score.dataType=FLOAT
+column.score.defaultNullValue=-Infinity
+column.ratio.dataType=DOUBLE
+column.ratio.defaultNullValue=0.5
+column.team.dataType=STRING
+column.team.defaultNullValue=null
+column.payload.dataType=BYTES
+column.payload.defaultNullValue=cafe
+column.active.dataType=BOOLEAN
+column.active.defaultNullValue=0
+column.plain.dataType=STRING
+"#;
+
+        let metadata = SegmentMetadata::parse(content).unwrap();
+        let default = |name: &str| metadata.get_column(name).unwrap().default_null_value.clone();
+        assert_eq!(default("hits"), Some(DefaultNullValue::Int(i32::MIN)));
+        assert_eq!(default("ts"), Some(DefaultNullValue::Long(i64::MIN)));
+        assert_eq!(default("score"), Some(DefaultNullValue::Float(f32::NEG_INFINITY)));
+        assert_eq!(default("ratio"), Some(DefaultNullValue::Double(0.5)));
+        assert_eq!(default("team"), Some(DefaultNullValue::String("null".to_string())));
+        assert_eq!(default("payload"), Some(DefaultNullValue::Bytes(vec![0xCA, 0xFE])));
+        assert_eq!(default("active"), Some(DefaultNullValue::Boolean(false)));
+        assert_eq!(default("plain"), None);
+
+        // An empty string is a valid STRING default
+        assert_eq!(
+            DefaultNullValue::parse(&DataType::String, "").unwrap(),
+            DefaultNullValue::String(String::new())
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_default_null_value() {
+        let content = r#"
+segment.name=test_segment
+segment.table.name=testTable
+segment.total.docs=3
+columns=hits
+column.hits.dataType=INT
+column.hits.defaultNullValue=abc
+"#;
+
+        let message = SegmentMetadata::parse(content).unwrap_err().to_string();
+        assert!(message.contains("abc"), "{}", message);
+        assert!(DefaultNullValue::parse(&DataType::Bytes, "abc").is_err());
+        assert!(DefaultNullValue::parse(&DataType::Boolean, "yes").is_err());
+    }
+
+    #[test]
+    fn test_table_type() {
+        let metadata = |table: &str, segment: &str| {
+            SegmentMetadata::parse(&format!(
+                "segment.name={}\nsegment.table.name={}\nsegment.total.docs=0\n",
+                segment, table
+            ))
+            .unwrap()
+        };
+
+        let cases = [
+            ("events_REALTIME", "whatever", Some(TableType::Realtime)),
+            ("events_OFFLINE", "whatever", Some(TableType::Offline)),
+            ("events", "events_OFFLINE_0", Some(TableType::Offline)),
+            ("events", "events_REALTIME", Some(TableType::Realtime)),
+            ("events", "events__3__17__20240101T0000Z", Some(TableType::Realtime)),
+            ("events", "events_0", None),
+            ("events", "events__x__17__20240101T0000Z", None),
+            ("OFFLINE_events", "events_0", None),
+        ];
+        for (table, segment, expected) in cases {
+            assert_eq!(metadata(table, segment).table_type(), expected, "{} {}", table, segment);
+        }
     }
 }
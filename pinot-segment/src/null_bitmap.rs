@@ -0,0 +1,153 @@
+//! Null value vectors
+//!
+//! Columns with `hasNullValue=true` store a `nullvalue_vector` region in
+//! columns.psf: a serialized RoaringBitmap of the documents whose stored
+//! value is the column's default null substitute.
+
+use crate::error::{Error, Result};
+use roaring::RoaringBitmap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Set of doc ids within one segment, iterated in ascending order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocIdSet {
+    bitmap: RoaringBitmap,
+}
+
+impl DocIdSet {
+    /// Every document of a segment with `total_docs` documents
+    pub fn all(total_docs: u32) -> Self {
+        let mut bitmap = RoaringBitmap::new();
+        bitmap.insert_range(0..total_docs);
+        Self { bitmap }
+    }
+
+    /// The documents of `[0, total_docs)` not in this set
+    pub fn complement(&self, total_docs: u32) -> Self {
+        let mut all = Self::all(total_docs);
+        all.bitmap -= &self.bitmap;
+        all
+    }
+
+    pub fn len(&self) -> u64 {
+        self.bitmap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bitmap.is_empty()
+    }
+
+    pub fn contains(&self, doc_id: u32) -> bool {
+        self.bitmap.contains(doc_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.bitmap.iter()
+    }
+
+    pub fn to_vec(&self) -> Vec<u32> {
+        self.bitmap.iter().collect()
+    }
+}
+
+impl FromIterator<u32> for DocIdSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        Self {
+            bitmap: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Reader for a column's null value vector
+#[derive(Debug, Clone)]
+pub struct NullBitmapReader {
+    nulls: RoaringBitmap,
+    total_docs: u32,
+}
+
+impl NullBitmapReader {
+    /// Read the null value vector stored at `offset` in `file_path`
+    pub fn new<P: AsRef<Path>>(file_path: P, offset: u64, size: usize, total_docs: u32) -> Result<Self> {
+        let mut file = File::open(file_path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut region = vec![0u8; size];
+        file.read_exact(&mut region)?;
+        Self::from_bytes(&region, total_docs)
+    }
+
+    /// Parse a null value vector region already read into memory
+    pub fn from_bytes(region: &[u8], total_docs: u32) -> Result<Self> {
+        let nulls = RoaringBitmap::deserialize_from(region)
+            .map_err(|e| Error::InvalidFormat(format!("Invalid null value vector: {}", e)))?;
+        Ok(Self { nulls, total_docs })
+    }
+
+    /// Documents whose value is null
+    pub fn get_null_doc_ids(&self) -> DocIdSet {
+        DocIdSet {
+            bitmap: self.nulls.clone(),
+        }
+    }
+
+    /// Documents whose value is not null
+    pub fn get_non_null_doc_ids(&self) -> DocIdSet {
+        self.get_null_doc_ids().complement(self.total_docs)
+    }
+
+    pub fn is_null(&self, doc_id: u32) -> bool {
+        self.nulls.contains(doc_id)
+    }
+
+    pub fn null_count(&self) -> u64 {
+        self.nulls.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serialize(doc_ids: &[u32]) -> Vec<u8> {
+        let bitmap: RoaringBitmap = doc_ids.iter().copied().collect();
+        let mut bytes = Vec::new();
+        bitmap.serialize_into(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_null_and_non_null_doc_ids() {
+        let reader = NullBitmapReader::from_bytes(&serialize(&[1, 4]), 6).unwrap();
+        assert_eq!(reader.get_null_doc_ids().to_vec(), vec![1, 4]);
+        assert_eq!(reader.get_non_null_doc_ids().to_vec(), vec![0, 2, 3, 5]);
+        assert!(reader.is_null(4));
+        assert!(!reader.is_null(5));
+        assert_eq!(reader.null_count(), 2);
+    }
+
+    #[test]
+    fn test_read_from_file_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("columns.psf");
+        let region = serialize(&[0, 2]);
+        let mut content = vec![0xAB; 16];
+        content.extend_from_slice(&region);
+        std::fs::write(&path, &content).unwrap();
+
+        let reader = NullBitmapReader::new(&path, 16, region.len(), 3).unwrap();
+        assert_eq!(reader.get_null_doc_ids().to_vec(), vec![0, 2]);
+        assert_eq!(reader.get_non_null_doc_ids().to_vec(), vec![1]);
+
+        assert!(NullBitmapReader::from_bytes(&[0xAB; 4], 3).is_err());
+    }
+
+    #[test]
+    fn test_doc_id_set() {
+        let set: DocIdSet = [3, 1].into_iter().collect();
+        assert_eq!(set.to_vec(), vec![1, 3]);
+        assert_eq!(set.complement(4).to_vec(), vec![0, 2]);
+        assert_eq!(DocIdSet::all(3).len(), 3);
+        assert!(DocIdSet::default().is_empty());
+    }
+}
@@ -0,0 +1,442 @@
+//! Range index reader (`range_index`, version 1)
+//!
+//! A range index splits a column's value domain into contiguous ranges and
+//! stores a RoaringBitmap of the documents whose value falls in each range.
+//! For dictionary-encoded columns the ranges are over dict ids. A query range
+//! fully covers some index ranges (all of their documents match) and only
+//! overlaps others, whose documents must be checked against the forward index.
+//!
+//! Region layout (big-endian, after the 8-byte magic marker):
+//! - version: i32 (1)
+//! - value type: i32 length followed by the UTF-8 type name (`INT`, `LONG`,
+//!   `FLOAT` or `DOUBLE`)
+//! - number of ranges `n`: i32
+//! - `n + 1` boundaries of the value type: the start of each range followed
+//!   by the inclusive end of the last range
+//! - `n + 1` i64 bitmap offsets, relative to the version field; bitmap `i`
+//!   spans offsets `i` to `i + 1`
+//! - `n` serialized RoaringBitmaps
+
+use crate::error::{Error, Result};
+use crate::metadata::DataType;
+use roaring::RoaringBitmap;
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Bound;
+use std::path::Path;
+
+const MAGIC_MARKER: u64 = 0xDEADBEEFDEAFBEAD;
+const MAGIC_MARKER_SIZE: usize = 8;
+const SUPPORTED_VERSION: i32 = 1;
+
+/// A numeric value compared against range index boundaries or dictionary entries
+///
+/// Integers compare exactly with each other; any comparison involving a
+/// floating point value is done in `f64`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RangeValue {
+    Long(i64),
+    Double(f64),
+}
+
+impl RangeValue {
+    fn as_f64(self) -> f64 {
+        match self {
+            RangeValue::Long(v) => v as f64,
+            RangeValue::Double(v) => v,
+        }
+    }
+}
+
+impl PartialOrd for RangeValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (RangeValue::Long(a), RangeValue::Long(b)) => Some(a.cmp(b)),
+            _ => self.as_f64().partial_cmp(&other.as_f64()),
+        }
+    }
+}
+
+/// Numeric range predicate, e.g. `hits BETWEEN 10 AND 20`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueRange {
+    pub lower: Bound<RangeValue>,
+    pub upper: Bound<RangeValue>,
+}
+
+impl Default for ValueRange {
+    fn default() -> Self {
+        Self {
+            lower: Bound::Unbounded,
+            upper: Bound::Unbounded,
+        }
+    }
+}
+
+impl ValueRange {
+    pub fn new(lower: Bound<RangeValue>, upper: Bound<RangeValue>) -> Self {
+        Self { lower, upper }
+    }
+
+    /// Inclusive range `[low, high]`
+    pub fn between(low: RangeValue, high: RangeValue) -> Self {
+        Self::new(Bound::Included(low), Bound::Included(high))
+    }
+
+    /// Whether `value` satisfies the lower bound
+    pub fn satisfies_lower(&self, value: RangeValue) -> bool {
+        match self.lower {
+            Bound::Included(lower) => value >= lower,
+            Bound::Excluded(lower) => value > lower,
+            Bound::Unbounded => true,
+        }
+    }
+
+    /// Whether `value` satisfies the upper bound
+    pub fn satisfies_upper(&self, value: RangeValue) -> bool {
+        match self.upper {
+            Bound::Included(upper) => value <= upper,
+            Bound::Excluded(upper) => value < upper,
+            Bound::Unbounded => true,
+        }
+    }
+
+    pub fn contains(&self, value: RangeValue) -> bool {
+        self.satisfies_lower(value) && self.satisfies_upper(value)
+    }
+
+    /// Narrow this range by a further lower bound
+    pub fn with_lower(mut self, lower: Bound<RangeValue>) -> Self {
+        let tighter = match (self.lower, lower) {
+            (_, Bound::Unbounded) => false,
+            (Bound::Unbounded, _) => true,
+            (Bound::Included(a) | Bound::Excluded(a), Bound::Included(b)) => b > a,
+            (Bound::Included(a), Bound::Excluded(b)) => b >= a,
+            (Bound::Excluded(a), Bound::Excluded(b)) => b > a,
+        };
+        if tighter {
+            self.lower = lower;
+        }
+        self
+    }
+
+    /// Narrow this range by a further upper bound
+    pub fn with_upper(mut self, upper: Bound<RangeValue>) -> Self {
+        let tighter = match (self.upper, upper) {
+            (_, Bound::Unbounded) => false,
+            (Bound::Unbounded, _) => true,
+            (Bound::Included(a) | Bound::Excluded(a), Bound::Included(b)) => b < a,
+            (Bound::Included(a), Bound::Excluded(b)) => b <= a,
+            (Bound::Excluded(a), Bound::Excluded(b)) => b < a,
+        };
+        if tighter {
+            self.upper = upper;
+        }
+        self
+    }
+
+    /// Whether every value of `[start, end)` (or `[start, end]` when
+    /// `end_inclusive`) is in this range
+    fn covers(&self, start: RangeValue, end: RangeValue, end_inclusive: bool) -> bool {
+        if !self.satisfies_lower(start) {
+            return false;
+        }
+        match self.upper {
+            Bound::Unbounded => true,
+            _ if end_inclusive => self.satisfies_upper(end),
+            // Values stay below `end`, so `end <= upper` suffices either way
+            Bound::Included(upper) | Bound::Excluded(upper) => end <= upper,
+        }
+    }
+
+    /// Whether no value of `[start, end)` (or `[start, end]` when
+    /// `end_inclusive`) is in this range
+    fn excludes(&self, start: RangeValue, end: RangeValue, end_inclusive: bool) -> bool {
+        if !self.satisfies_upper(start) {
+            return true;
+        }
+        match self.lower {
+            Bound::Unbounded => false,
+            _ if end_inclusive => !self.satisfies_lower(end),
+            Bound::Included(lower) | Bound::Excluded(lower) => end <= lower,
+        }
+    }
+}
+
+/// Documents selected by a range index for a [`ValueRange`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeMatch {
+    /// Documents in index ranges fully covered by the query; all of them match
+    pub matching: Vec<u32>,
+    /// Documents in index ranges the query only overlaps; each must be
+    /// checked against its actual value
+    pub candidates: Vec<u32>,
+}
+
+/// Range index of a single column
+#[derive(Debug, Clone)]
+pub struct RangeIndexReader {
+    value_type: DataType,
+    /// Start of each range, followed by the inclusive end of the last range
+    boundaries: Vec<RangeValue>,
+    bitmaps: Vec<RoaringBitmap>,
+}
+
+impl RangeIndexReader {
+    /// Read a range index from columns.psf at the given offset
+    pub fn read(file_path: &Path, offset: usize, size: usize) -> Result<Self> {
+        let mut file = File::open(file_path)?;
+        file.seek(SeekFrom::Start(offset as u64))?;
+        let mut region = vec![0u8; size];
+        file.read_exact(&mut region)?;
+        Self::from_bytes(&region)
+    }
+
+    /// Read a range index from an in-memory region (starting at the magic marker)
+    pub fn from_bytes(region: &[u8]) -> Result<Self> {
+        if region.len() < MAGIC_MARKER_SIZE {
+            return Err(Error::InvalidFormat(
+                "Range index too small to contain magic marker".to_string(),
+            ));
+        }
+        let magic = u64::from_be_bytes(region[..MAGIC_MARKER_SIZE].try_into().unwrap());
+        if magic != MAGIC_MARKER {
+            return Err(Error::InvalidFormat(format!(
+                "Invalid magic marker: expected 0x{:X}, got 0x{:X}",
+                MAGIC_MARKER, magic
+            )));
+        }
+
+        let data = &region[MAGIC_MARKER_SIZE..];
+        let mut cursor = ByteCursor { data, offset: 0 };
+
+        let version = cursor.read_i32()?;
+        if version != SUPPORTED_VERSION {
+            return Err(Error::UnsupportedFeature(format!(
+                "Range index version {} not supported",
+                version
+            )));
+        }
+
+        let type_len = cursor.read_len()?;
+        let type_name = String::from_utf8(cursor.take(type_len)?.to_vec())
+            .map_err(|e| Error::Parse(format!("Invalid range index value type: {}", e)))?;
+        let value_type = DataType::from_string(&type_name)?;
+
+        let num_ranges = cursor.read_len()?;
+        if num_ranges == 0 {
+            return Err(Error::InvalidFormat(
+                "Range index has no ranges".to_string(),
+            ));
+        }
+
+        let boundaries = (0..=num_ranges)
+            .map(|_| match value_type {
+                DataType::Int => cursor.read_i32().map(|v| RangeValue::Long(v as i64)),
+                DataType::Long => cursor.read_i64().map(RangeValue::Long),
+                DataType::Float => cursor
+                    .read_i32()
+                    .map(|v| RangeValue::Double(f32::from_bits(v as u32) as f64)),
+                DataType::Double => cursor
+                    .read_i64()
+                    .map(|v| RangeValue::Double(f64::from_bits(v as u64))),
+                _ => Err(Error::UnsupportedFeature(format!(
+                    "Range index over {:?} values",
+                    value_type
+                ))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let offsets = (0..=num_ranges)
+            .map(|_| {
+                let offset = cursor.read_i64()?;
+                usize::try_from(offset).map_err(|_| {
+                    Error::InvalidFormat(format!("Invalid range index bitmap offset {}", offset))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let bitmaps = offsets
+            .windows(2)
+            .map(|w| {
+                let bytes = data.get(w[0]..w[1]).ok_or_else(|| {
+                    Error::InvalidFormat(format!(
+                        "Range index bitmap {}..{} outside region of {} bytes",
+                        w[0],
+                        w[1],
+                        data.len()
+                    ))
+                })?;
+                RoaringBitmap::deserialize_from(bytes)
+                    .map_err(|e| Error::InvalidFormat(format!("Invalid range index bitmap: {}", e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RangeIndexReader {
+            value_type,
+            boundaries,
+            bitmaps,
+        })
+    }
+
+    /// Type of the indexed values (INT for dictionary-encoded columns, whose
+    /// index is built over dict ids)
+    pub fn value_type(&self) -> &DataType {
+        &self.value_type
+    }
+
+    pub fn num_ranges(&self) -> usize {
+        self.bitmaps.len()
+    }
+
+    /// Documents whose indexed value may fall in `range`
+    pub fn doc_ids(&self, range: &ValueRange) -> RangeMatch {
+        let mut matching = RoaringBitmap::new();
+        let mut candidates = RoaringBitmap::new();
+
+        let last = self.bitmaps.len() - 1;
+        for (i, bitmap) in self.bitmaps.iter().enumerate() {
+            let (start, end) = (self.boundaries[i], self.boundaries[i + 1]);
+            // Integer ranges end right before the next start
+            let (end, end_inclusive) = match end {
+                RangeValue::Long(end) if i != last => (RangeValue::Long(end - 1), true),
+                end => (end, i == last),
+            };
+            if range.covers(start, end, end_inclusive) {
+                matching |= bitmap;
+            } else if !range.excludes(start, end, end_inclusive) {
+                candidates |= bitmap;
+            }
+        }
+
+        RangeMatch {
+            matching: matching.into_iter().collect(),
+            candidates: candidates.into_iter().collect(),
+        }
+    }
+}
+
+/// Big-endian reader over the index region
+struct ByteCursor<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl ByteCursor<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8]> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + len)
+            .ok_or_else(|| Error::InvalidFormat("Truncated range index header".to_string()))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_i64(&mut self) -> Result<i64> {
+        Ok(i64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        let len = self.read_i32()?;
+        usize::try_from(len)
+            .map_err(|_| Error::InvalidFormat(format!("Negative length {} in range index", len)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// INT range index with the given boundaries and one bitmap per range
+    fn index_region(boundaries: &[i32], bitmaps: &[&[u32]]) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend_from_slice(&1i32.to_be_bytes());
+        header.extend_from_slice(&3i32.to_be_bytes());
+        header.extend_from_slice(b"INT");
+        header.extend_from_slice(&(bitmaps.len() as i32).to_be_bytes());
+        for boundary in boundaries {
+            header.extend_from_slice(&boundary.to_be_bytes());
+        }
+
+        let mut serialized = Vec::new();
+        let mut offsets = Vec::new();
+        let offsets_len = (bitmaps.len() + 1) * 8;
+        for doc_ids in bitmaps {
+            offsets.push(header.len() + offsets_len + serialized.len());
+            let bitmap: RoaringBitmap = doc_ids.iter().copied().collect();
+            bitmap.serialize_into(&mut serialized).unwrap();
+        }
+        offsets.push(header.len() + offsets_len + serialized.len());
+
+        let mut region = MAGIC_MARKER.to_be_bytes().to_vec();
+        region.extend_from_slice(&header);
+        for offset in offsets {
+            region.extend_from_slice(&(offset as i64).to_be_bytes());
+        }
+        region.extend_from_slice(&serialized);
+        region
+    }
+
+    fn between(low: i64, high: i64) -> ValueRange {
+        ValueRange::between(RangeValue::Long(low), RangeValue::Long(high))
+    }
+
+    #[test]
+    fn test_covered_and_partial_ranges() {
+        // Ranges [0, 10), [10, 20), [20, 30]
+        let region = index_region(&[0, 10, 20, 30], &[&[0, 3], &[1, 4], &[2, 5]]);
+        let index = RangeIndexReader::from_bytes(&region).unwrap();
+        assert_eq!(index.value_type(), &DataType::Int);
+        assert_eq!(index.num_ranges(), 3);
+
+        let result = index.doc_ids(&between(10, 30));
+        assert_eq!(result.matching, vec![1, 2, 4, 5]);
+        assert!(result.candidates.is_empty());
+
+        let result = index.doc_ids(&between(5, 19));
+        assert_eq!(result.matching, vec![1, 4]);
+        assert_eq!(result.candidates, vec![0, 3]);
+
+        // Upper bound equal to a range start overlaps that range only at its start
+        let result = index.doc_ids(&between(0, 20));
+        assert_eq!(result.matching, vec![0, 1, 3, 4]);
+        assert_eq!(result.candidates, vec![2, 5]);
+
+        assert_eq!(index.doc_ids(&between(31, 40)), RangeMatch::default());
+
+        let open = ValueRange::new(Bound::Excluded(RangeValue::Long(5)), Bound::Unbounded);
+        let result = index.doc_ids(&open);
+        assert_eq!(result.matching, vec![1, 2, 4, 5]);
+        assert_eq!(result.candidates, vec![0, 3]);
+    }
+
+    #[test]
+    fn test_value_range_bounds() {
+        let range = ValueRange::default()
+            .with_lower(Bound::Included(RangeValue::Long(5)))
+            .with_lower(Bound::Excluded(RangeValue::Long(5)))
+            .with_lower(Bound::Included(RangeValue::Long(3)))
+            .with_upper(Bound::Included(RangeValue::Double(9.5)))
+            .with_upper(Bound::Unbounded);
+        assert!(!range.contains(RangeValue::Long(5)));
+        assert!(range.contains(RangeValue::Long(6)));
+        assert!(range.contains(RangeValue::Long(9)));
+        assert!(!range.contains(RangeValue::Long(10)));
+        assert!(range.contains(RangeValue::Double(5.5)));
+    }
+
+    #[test]
+    fn test_rejects_unknown_version() {
+        let mut region = index_region(&[0, 1], &[&[0]]);
+        region[MAGIC_MARKER_SIZE..MAGIC_MARKER_SIZE + 4].copy_from_slice(&2i32.to_be_bytes());
+        assert!(matches!(
+            RangeIndexReader::from_bytes(&region),
+            Err(Error::UnsupportedFeature(_))
+        ));
+    }
+}
@@ -1,14 +1,148 @@
 use crate::error::{Error, Result};
-use crate::forward_index::{DictionaryReader, FixedBitWidthReader};
-use crate::index_map::IndexMap;
-use crate::metadata::{DataType, SegmentMetadata};
+use crate::forward_index::{
+    ByteOrder, DictionaryReader, FixedBitWidthReader, FixedByteChunkReader, Utf8Policy,
+    VarByteChunkReader,
+};
+use crate::index_map::{IndexLocation, IndexMap, RegionCompression, StarTreeIndexMap};
+use crate::metadata::{
+    ColumnMetadata, DataType, SegmentMetadata, SegmentVersion, StarTreeMetadata,
+};
+use crate::null_bitmap::{DocIdSet, NullBitmapReader};
+use crate::range_index::{RangeIndexReader, RangeMatch, RangeValue, ValueRange};
+use crate::star_tree::{
+    AggFn, StarTreeIndexReader, STAR_TREE_INDEX_FILE, STAR_TREE_INDEX_MAP_FILE,
+};
+#[cfg(feature = "arrow")]
+use arrow_array::{builder::StringViewBuilder, StringViewArray};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use roaring::RoaringBitmap;
+
+/// Trailing bytes tolerated after the packed dict ids before a forward index
+/// is considered longer than `segment.total.docs` (writers may pad the region)
+const MAX_FORWARD_INDEX_PADDING: usize = 8;
+
+/// Document range covering every document of a segment
+const ALL_DOCS: Range<u32> = 0..u32::MAX;
+
+/// Snapshot of the valid (non-superseded) doc ids of an upsert table segment,
+/// stored next to metadata.properties
+const VALID_DOC_IDS_SNAPSHOT_FILE: &str = "validdocids.bitmap.snapshot";
+
+/// Values per chunk yielded by the chunked readers of dictionary-encoded
+/// columns, such as [`SegmentReader::read_int_column_in_chunks`]
+pub const CHUNK_BATCH_SIZE: u32 = 8192;
+
+/// What to do when `segment.total.docs` disagrees with the number of values
+/// packed into a column's forward index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocCountMismatchPolicy {
+    /// Fail the read with an error reporting both counts
+    #[default]
+    Error,
+    /// Log a warning and read only the values both sides agree on
+    Warn,
+}
+
+/// A column's values, read one chunk of documents at a time
+///
+/// Returned by [`SegmentReader::read_string_column_in_chunks`] and
+/// [`SegmentReader::read_int_column_in_chunks`]. Only the chunk being
+/// yielded is held in memory. Each chunk is read on its own, so an error
+/// fails that chunk only and [`len`](ExactSizeIterator::len) stays exact.
+pub struct ColumnChunks<'a, T> {
+    chunks: std::vec::IntoIter<Range<u32>>,
+    read_chunk: Box<dyn FnMut(Range<u32>) -> Result<Vec<T>> + 'a>,
+}
+
+impl<'a, T> ColumnChunks<'a, T> {
+    fn new<F>(chunks: Vec<Range<u32>>, read_chunk: F) -> Self
+    where
+        F: FnMut(Range<u32>) -> Result<Vec<T>> + 'a,
+    {
+        Self {
+            chunks: chunks.into_iter(),
+            read_chunk: Box::new(read_chunk),
+        }
+    }
+}
+
+impl<T> Iterator for ColumnChunks<'_, T> {
+    type Item = Result<Vec<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let docs = self.chunks.next()?;
+        Some((self.read_chunk)(docs))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for ColumnChunks<'_, T> {}
+
+impl<T> std::iter::FusedIterator for ColumnChunks<'_, T> {}
+
+/// Every value of one column, typed by the column's Pinot data type
+///
+/// Returned by [`SegmentReader::read_column`] for callers that handle
+/// columns generically instead of picking a typed `read_*_column` method.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnData {
+    Int(Vec<i32>),
+    Long(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    String(Vec<String>),
+    Bytes(Vec<Vec<u8>>),
+    Bool(Vec<bool>),
+}
+
+impl ColumnData {
+    /// Pinot data type of the values
+    pub fn data_type(&self) -> DataType {
+        match self {
+            ColumnData::Int(_) => DataType::Int,
+            ColumnData::Long(_) => DataType::Long,
+            ColumnData::Float(_) => DataType::Float,
+            ColumnData::Double(_) => DataType::Double,
+            ColumnData::String(_) => DataType::String,
+            ColumnData::Bytes(_) => DataType::Bytes,
+            ColumnData::Bool(_) => DataType::Boolean,
+        }
+    }
+
+    /// Number of values (one per document)
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnData::Int(values) => values.len(),
+            ColumnData::Long(values) => values.len(),
+            ColumnData::Float(values) => values.len(),
+            ColumnData::Double(values) => values.len(),
+            ColumnData::String(values) => values.len(),
+            ColumnData::Bytes(values) => values.len(),
+            ColumnData::Bool(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct SegmentReader {
     segment_dir: PathBuf,
     metadata: SegmentMetadata,
     index_map: IndexMap,
+    doc_count_mismatch: DocCountMismatchPolicy,
+    utf8_policy: Utf8Policy,
 }
 
 impl SegmentReader {
@@ -23,25 +157,390 @@ impl SegmentReader {
         // Read index_map
         let index_map_path = segment_dir.join("index_map");
         let index_map = IndexMap::from_file(&index_map_path)?;
+        check_columns_file(&segment_dir.join("columns.psf"), &index_map)?;
 
         Ok(SegmentReader {
             segment_dir,
             metadata,
             index_map,
+            doc_count_mismatch: DocCountMismatchPolicy::default(),
+            utf8_policy: Utf8Policy::default(),
         })
     }
 
+    /// Set how forward indexes whose length disagrees with `segment.total.docs`
+    /// are handled (defaults to [`DocCountMismatchPolicy::Error`])
+    pub fn with_doc_count_mismatch_policy(mut self, policy: DocCountMismatchPolicy) -> Self {
+        self.doc_count_mismatch = policy;
+        self
+    }
+
+    /// Decode STRING dictionaries and RAW STRING columns with the given policy
+    /// (defaults to [`Utf8Policy::Lossy`])
+    pub fn with_utf8_policy(mut self, policy: Utf8Policy) -> Self {
+        self.utf8_policy = policy;
+        self
+    }
+
+    /// Directory the segment was opened from
+    pub fn segment_dir(&self) -> &Path {
+        &self.segment_dir
+    }
+
     pub fn metadata(&self) -> &SegmentMetadata {
         &self.metadata
     }
 
+    pub fn index_map(&self) -> &IndexMap {
+        &self.index_map
+    }
+
     pub fn total_docs(&self) -> u32 {
         self.metadata.total_docs
     }
 
+    /// Estimated size in bytes of the segment's values once decoded
+    ///
+    /// Meant for cost-based planning rather than memory accounting: see
+    /// [`SegmentReader::estimate_column_size_bytes`] for how each column is
+    /// counted.
+    pub fn estimate_size_bytes(&self) -> u64 {
+        self.metadata
+            .columns
+            .values()
+            .map(|column| self.estimate_column_size_bytes(column))
+            .sum()
+    }
+
+    /// Estimated size in bytes of a column's values once decoded
+    ///
+    /// Fixed-width values count their width. STRING and BYTES values count
+    /// the longest dictionary entry (`lengthOfEachEntry`) plus a 4-byte
+    /// offset, or for raw columns the (uncompressed) size of the forward
+    /// index. Multi-value columns add a 4-byte offset per document.
+    pub fn estimate_column_size_bytes(&self, column: &ColumnMetadata) -> u64 {
+        let entries = column.total_number_of_entries as u64;
+        let values = match column.data_type {
+            DataType::Boolean => entries,
+            DataType::Int | DataType::Float => entries * 4,
+            DataType::Long | DataType::Double => entries * 8,
+            DataType::String | DataType::Bytes => {
+                let data = if column.has_dictionary {
+                    entries * column.length_of_each_entry as u64
+                } else {
+                    self.index_map
+                        .get_forward_index(&column.name)
+                        .map(|loc| loc.uncompressed_size.unwrap_or(loc.size) as u64)
+                        .unwrap_or(0)
+                };
+                data + entries * 4
+            }
+        };
+        if column.is_multi_value {
+            values + column.total_docs as u64 * 4
+        } else {
+            values
+        }
+    }
+
+    /// Whether the column has an index of the given type (`index_map` name,
+    /// e.g. `inverted_index`), including types this crate cannot read
+    pub fn has_index(&self, column_name: &str, index_type: &str) -> bool {
+        self.index_map.has_index(column_name, index_type)
+    }
+
+    /// Whether the column has an inverted index
+    pub fn has_inverted_index(&self, column_name: &str) -> bool {
+        self.has_index(column_name, "inverted_index")
+    }
+
+    /// Whether the column has a bloom filter
+    pub fn has_bloom_filter(&self, column_name: &str) -> bool {
+        self.has_index(column_name, "bloom_filter")
+    }
+
+    /// Whether the column has a null value vector (documents whose stored
+    /// value is the column's default null substitute)
+    pub fn has_null_value_vector(&self, column_name: &str) -> bool {
+        self.has_index(column_name, "nullvalue_vector")
+    }
+
+    /// Read the doc ids flagged in a column's null value vector
+    ///
+    /// Returns `None` when the column has no null value vector.
+    pub fn read_null_doc_ids(&self, column_name: &str) -> Result<Option<Vec<u32>>> {
+        Ok(self
+            .null_bitmap_reader(column_name)?
+            .map(|reader| reader.get_null_doc_ids().to_vec()))
+    }
+
+    /// Open a column's null value vector
+    ///
+    /// Returns `None` when the column has no null value vector.
+    pub fn null_bitmap_reader(&self, column_name: &str) -> Result<Option<NullBitmapReader>> {
+        let Some(loc) = self.index_map.get_index(column_name, "nullvalue_vector") else {
+            return Ok(None);
+        };
+
+        let region = self.read_region(column_name, loc)?;
+        NullBitmapReader::from_bytes(&region, self.metadata.total_docs).map(Some)
+    }
+
+    /// Whether the segment has a validDocIds snapshot (upsert tables only)
+    pub fn has_valid_doc_ids(&self) -> bool {
+        self.segment_dir.join(VALID_DOC_IDS_SNAPSHOT_FILE).is_file()
+    }
+
+    /// Read the validDocIds snapshot of an upsert table segment
+    ///
+    /// Documents missing from the snapshot were superseded by a newer record
+    /// with the same primary key. Returns `None` when there is no snapshot.
+    pub fn read_valid_doc_ids(&self) -> Result<Option<Vec<u32>>> {
+        let path = self.segment_dir.join(VALID_DOC_IDS_SNAPSHOT_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let bitmap = RoaringBitmap::deserialize_from(File::open(&path)?).map_err(|e| {
+            Error::InvalidFormat(format!("Invalid validDocIds snapshot {:?}: {}", path, e))
+        })?;
+        Ok(Some(bitmap.into_iter().collect()))
+    }
+
+    /// Whether the column has a range index
+    pub fn has_range_index(&self, column_name: &str) -> bool {
+        self.has_index(column_name, "range_index")
+    }
+
+    /// Doc ids whose value of a numeric column falls in `range`, looked up
+    /// through the column's range index
+    ///
+    /// Documents in index ranges the query only partially overlaps are checked
+    /// against the forward index, so the result is exact. Returns `None` when
+    /// the column has no range index or is not a single-value dictionary-encoded
+    /// numeric column (other forward indexes cannot be read to verify candidates).
+    pub fn range_doc_ids(&self, column_name: &str, range: &ValueRange) -> Result<Option<Vec<u32>>> {
+        let Some(loc) = self.index_map.get_index(column_name, "range_index") else {
+            return Ok(None);
+        };
+        let col_meta = self.metadata.get_column(column_name)?;
+        let numeric = matches!(
+            col_meta.data_type,
+            DataType::Int | DataType::Long | DataType::Float | DataType::Double
+        );
+        if !numeric || !col_meta.has_dictionary || col_meta.is_multi_value {
+            return Ok(None);
+        }
+
+        // The index of a dictionary-encoded column is built over dict ids,
+        // so translate the value range through the sorted dictionary first
+        let dictionary = self.read_dictionary(column_name, col_meta)?;
+        let Some((first, last)) = dictionary.dict_id_range(range) else {
+            return Ok(Some(Vec::new()));
+        };
+
+        let region = self.read_region(column_name, loc)?;
+        let index = RangeIndexReader::from_bytes(&region)?;
+        let dict_id_range = ValueRange::between(
+            RangeValue::Long(first as i64),
+            RangeValue::Long(last as i64),
+        );
+        let RangeMatch {
+            matching,
+            candidates,
+        } = index.doc_ids(&dict_id_range);
+
+        let mut doc_ids: RoaringBitmap = matching.into_iter().collect();
+        if !candidates.is_empty() {
+            let forward_index = self.forward_index_reader(column_name, col_meta)?;
+            for doc_id in candidates {
+                if (first as u64..=last as u64).contains(&forward_index.get_dict_id(doc_id)?) {
+                    doc_ids.insert(doc_id);
+                }
+            }
+        }
+        Ok(Some(doc_ids.into_iter().collect()))
+    }
+
+    /// Whether the segment has star-tree indexes (`startree.count` > 0)
+    pub fn has_star_tree_index(&self) -> bool {
+        !self.metadata.star_trees.is_empty()
+    }
+
+    /// The segment's star-trees, in tree id order, read from
+    /// `star_tree_index` with their dimension dictionaries; empty when the
+    /// segment has none
+    ///
+    /// Only `sum`, `count`, `min` and `max` function column pairs are loaded;
+    /// other pairs are listed by [`StarTreeIndexReader::metrics`] but cannot
+    /// be queried.
+    pub fn star_tree_indexes(&self) -> Result<Vec<StarTreeIndexReader>> {
+        if self.metadata.star_trees.is_empty() {
+            return Ok(Vec::new());
+        }
+        let index_map =
+            StarTreeIndexMap::from_file(&self.segment_dir.join(STAR_TREE_INDEX_MAP_FILE))?;
+        let data = std::fs::read(self.segment_dir.join(STAR_TREE_INDEX_FILE))?;
+        self.metadata
+            .star_trees
+            .iter()
+            .enumerate()
+            .map(|(tree_id, tree)| self.star_tree_index(tree_id, tree, &index_map, &data))
+            .collect()
+    }
+
+    /// Load star-tree `tree_id` from the `star_tree_index` bytes in `data`
+    fn star_tree_index(
+        &self,
+        tree_id: usize,
+        tree: &StarTreeMetadata,
+        index_map: &StarTreeIndexMap,
+        data: &[u8],
+    ) -> Result<StarTreeIndexReader> {
+        let region = |loc: Option<&IndexLocation>, part: &str| {
+            let loc = loc.ok_or_else(|| {
+                Error::InvalidFormat(format!(
+                    "Star-tree {} has no {} in {}",
+                    tree_id, part, STAR_TREE_INDEX_MAP_FILE
+                ))
+            })?;
+            data.get(loc.start_offset..loc.start_offset + loc.size)
+                .ok_or_else(|| {
+                    Error::InvalidFormat(format!(
+                        "Star-tree {} {} lies outside {}",
+                        tree_id, part, STAR_TREE_INDEX_FILE
+                    ))
+                })
+        };
+
+        let tree_region = region(index_map.get_star_tree(tree_id), "tree")?;
+        let mut index = StarTreeIndexReader::new(tree_region, tree)?;
+        for dimension in &tree.dimensions {
+            let col_meta = self.single_value_column(dimension)?;
+            if !col_meta.has_dictionary {
+                return Err(Error::InvalidFormat(format!(
+                    "Star-tree dimension {} has no dictionary",
+                    dimension
+                )));
+            }
+            // Dict ids are packed as narrowly as the column's cardinality allows
+            let bits_per_value = match col_meta.cardinality {
+                0 | 1 => 1,
+                cardinality => (32 - (cardinality - 1).leading_zeros()) as u8,
+            };
+            let packed = region(index_map.get_forward_index(tree_id, dimension), dimension)?;
+            if packed.len() < FixedBitWidthReader::packed_size(tree.total_docs, bits_per_value) {
+                return Err(Error::InvalidFormat(format!(
+                    "Star-tree {} forward index of {} is too small for {} docs",
+                    tree_id, dimension, tree.total_docs
+                )));
+            }
+            let dict_ids =
+                FixedBitWidthReader::from_packed(packed.to_vec(), bits_per_value, tree.total_docs);
+            let dictionary = self.read_dictionary(dimension, col_meta)?;
+            index = index.with_dimension(dimension, dict_ids, dictionary);
+        }
+        for pair in &tree.function_column_pairs {
+            let Some(aggregation) = AggFn::from_metric_name(pair) else {
+                continue;
+            };
+            let metric_region = region(index_map.get_forward_index(tree_id, pair), pair)?;
+            let reader = FixedByteChunkReader::from_bytes(metric_region)?;
+            if reader.num_values() != tree.total_docs {
+                return Err(Error::InvalidFormat(format!(
+                    "Star-tree {} forward index of {} has {} values, expected {}",
+                    tree_id,
+                    pair,
+                    reader.num_values(),
+                    tree.total_docs
+                )));
+            }
+            let values = match aggregation {
+                AggFn::Count => reader.read_longs()?.into_iter().map(|v| v as f64).collect(),
+                _ => reader.read_doubles()?,
+            };
+            index = index.with_metric(pair, values);
+        }
+        Ok(index)
+    }
+
+    /// Read every value of a column, dispatching on its metadata data type
+    ///
+    /// BOOLEAN and RAW numeric columns are not decodable yet and return
+    /// [`Error::UnsupportedFeature`].
+    pub fn read_column(&self, column_name: &str) -> Result<ColumnData> {
+        let col_meta = self.single_value_column(column_name)?;
+        match col_meta.data_type {
+            DataType::Int => self.read_int_column(column_name).map(ColumnData::Int),
+            DataType::Long => self.read_long_column(column_name).map(ColumnData::Long),
+            DataType::Float => self.read_float_column(column_name).map(ColumnData::Float),
+            DataType::Double => self.read_double_column(column_name).map(ColumnData::Double),
+            DataType::String => self.read_string_column(column_name).map(ColumnData::String),
+            DataType::Bytes => self.read_bytes_column(column_name).map(ColumnData::Bytes),
+            DataType::Boolean => Err(Error::UnsupportedFeature(format!(
+                "Reading {:?} column {} not yet supported",
+                col_meta.data_type, column_name
+            ))),
+        }
+    }
+
+    /// Check that [`read_column`](Self::read_column) can decode a column
+    ///
+    /// Only metadata and, for RAW STRING columns, the forward index header
+    /// are looked at. Returns the [`Error::UnsupportedFeature`] a read of the
+    /// column would fail with, so callers can leave it out up front.
+    pub fn check_column_readable(&self, column_name: &str) -> Result<()> {
+        let col_meta = self.single_value_column(column_name)?;
+        match (&col_meta.data_type, col_meta.has_dictionary) {
+            (DataType::Boolean, _) => Err(Error::UnsupportedFeature(format!(
+                "Reading {:?} column {} not yet supported",
+                col_meta.data_type, column_name
+            ))),
+            (DataType::String | DataType::Bytes, false) => self
+                .raw_reader(column_name, col_meta)?
+                .check_compression(),
+            (data_type, false) => Err(Error::UnsupportedFeature(format!(
+                "RAW {:?} columns not yet supported (column {})",
+                data_type, column_name
+            ))),
+            (DataType::String | DataType::Bytes, true) => Ok(()),
+            (data_type, true) if col_meta.front_coded_dictionary => {
+                Err(Error::InvalidFormat(format!(
+                    "Front-coded dictionary for {:?} column {}",
+                    data_type, column_name
+                )))
+            }
+            (_, true) => Ok(()),
+        }
+    }
+
     /// Read a dictionary-encoded INT column
     pub fn read_int_column(&self, column_name: &str) -> Result<Vec<i32>> {
-        let col_meta = self.metadata.get_column(column_name)?;
+        let mut values = Vec::with_capacity(self.metadata.total_docs as usize);
+        self.for_each_int(column_name, |value| values.push(value))?;
+        Ok(values)
+    }
+
+    /// Read rows `[start, start + count)` of a dictionary-encoded INT column
+    ///
+    /// Only the forward index bytes holding those rows are read, so a small
+    /// window of a large segment stays cheap. The window is clamped to the
+    /// column's documents.
+    pub fn read_int_column_range(&self, column_name: &str, start: u32, count: u32) -> Result<Vec<i32>> {
+        let mut values = Vec::with_capacity(count.min(self.metadata.total_docs) as usize);
+        self.for_each_int_in_range(column_name, start..start.saturating_add(count), |value| {
+            values.push(value)
+        })?;
+        Ok(values)
+    }
+
+    /// Read a dictionary-encoded INT column [`CHUNK_BATCH_SIZE`] values at a
+    /// time
+    ///
+    /// The dictionary is read once; each chunk reads only its part of the
+    /// forward index.
+    pub fn read_int_column_in_chunks(&self, column_name: &str) -> Result<ColumnChunks<'_, i32>> {
+        let col_meta = self.single_value_column(column_name)?;
 
         if col_meta.data_type != DataType::Int {
             return Err(Error::InvalidFormat(format!(
@@ -56,55 +555,88 @@ impl SegmentReader {
             ));
         }
 
-        // Read dictionary
-        let dict_loc = self
-            .index_map
-            .get_dictionary(column_name)
-            .ok_or_else(|| Error::InvalidFormat(format!("No dictionary for {}", column_name)))?;
+        let dictionary = self.read_dictionary(column_name, col_meta)?;
+        let column_name = column_name.to_string();
+        Ok(ColumnChunks::new(self.batch_ranges(), move |docs| {
+            let mut values = Vec::with_capacity(docs.len());
+            self.for_each_dict_id(&column_name, col_meta, docs, |dict_id| {
+                let value = dictionary.get_int(dict_id).ok_or_else(|| {
+                    Error::InvalidFormat(format!(
+                        "Invalid dict_id {} for column {}",
+                        dict_id, column_name
+                    ))
+                })?;
+                values.push(value);
+                Ok(())
+            })?;
+            Ok(values)
+        }))
+    }
 
-        let columns_psf = self.segment_dir.join("columns.psf");
-        let dictionary = DictionaryReader::read(
-            &columns_psf,
-            dict_loc.start_offset,
-            dict_loc.size,
-            &col_meta.data_type,
-            col_meta.cardinality,
-            col_meta.length_of_each_entry,
-        )?;
+    /// Decode a dictionary-encoded INT column, passing each value to `f` in doc id order
+    pub fn for_each_int<F: FnMut(i32)>(&self, column_name: &str, f: F) -> Result<()> {
+        self.for_each_int_in_range(column_name, ALL_DOCS, f)
+    }
 
-        // Read forward index (dictionary IDs)
-        let fwd_loc = self.index_map.get_forward_index(column_name).ok_or_else(|| {
-            Error::InvalidFormat(format!("No forward index for {}", column_name))
-        })?;
+    /// Like [`for_each_int`](Self::for_each_int), for documents in `docs` only
+    ///
+    /// Only the part of the forward index holding those documents is read.
+    pub fn for_each_int_in_range<F: FnMut(i32)>(
+        &self,
+        column_name: &str,
+        docs: Range<u32>,
+        mut f: F,
+    ) -> Result<()> {
+        let col_meta = self.single_value_column(column_name)?;
 
-        let fixed_bit_reader = FixedBitWidthReader::read(
-            &columns_psf,
-            fwd_loc.start_offset,
-            fwd_loc.size,
-            col_meta.bits_per_element,
-            col_meta.total_docs,
-        )?;
+        if col_meta.data_type != DataType::Int {
+            return Err(Error::InvalidFormat(format!(
+                "Column {} is not INT type",
+                column_name
+            )));
+        }
 
-        // Read all dict IDs and lookup values
-        let dict_ids = fixed_bit_reader.read_all()?;
-        let mut values = Vec::with_capacity(dict_ids.len());
+        if !col_meta.has_dictionary {
+            return Err(Error::UnsupportedFeature(
+                "RAW INT columns not yet supported".to_string(),
+            ));
+        }
 
-        for dict_id in dict_ids {
+        let dictionary = self.read_dictionary(column_name, col_meta)?;
+        self.for_each_dict_id(column_name, col_meta, docs.clone(), |dict_id| {
             let value = dictionary.get_int(dict_id).ok_or_else(|| {
                 Error::InvalidFormat(format!(
                     "Invalid dict_id {} for column {}",
                     dict_id, column_name
                 ))
             })?;
-            values.push(value);
-        }
-
-        Ok(values)
+            f(value);
+            Ok(())
+        })
     }
 
     /// Read a dictionary-encoded LONG column
     pub fn read_long_column(&self, column_name: &str) -> Result<Vec<i64>> {
-        let col_meta = self.metadata.get_column(column_name)?;
+        let mut values = Vec::with_capacity(self.metadata.total_docs as usize);
+        self.for_each_long(column_name, |value| values.push(value))?;
+        Ok(values)
+    }
+
+    /// Decode a dictionary-encoded LONG column, passing each value to `f` in doc id order
+    pub fn for_each_long<F: FnMut(i64)>(&self, column_name: &str, f: F) -> Result<()> {
+        self.for_each_long_in_range(column_name, ALL_DOCS, f)
+    }
+
+    /// Like [`for_each_long`](Self::for_each_long), for documents in `docs` only
+    ///
+    /// Only the part of the forward index holding those documents is read.
+    pub fn for_each_long_in_range<F: FnMut(i64)>(
+        &self,
+        column_name: &str,
+        docs: Range<u32>,
+        mut f: F,
+    ) -> Result<()> {
+        let col_meta = self.single_value_column(column_name)?;
 
         if col_meta.data_type != DataType::Long {
             return Err(Error::InvalidFormat(format!(
@@ -119,52 +651,38 @@ impl SegmentReader {
             ));
         }
 
-        let dict_loc = self
-            .index_map
-            .get_dictionary(column_name)
-            .ok_or_else(|| Error::InvalidFormat(format!("No dictionary for {}", column_name)))?;
-
-        let columns_psf = self.segment_dir.join("columns.psf");
-        let dictionary = DictionaryReader::read(
-            &columns_psf,
-            dict_loc.start_offset,
-            dict_loc.size,
-            &col_meta.data_type,
-            col_meta.cardinality,
-            col_meta.length_of_each_entry,
-        )?;
-
-        let fwd_loc = self.index_map.get_forward_index(column_name).ok_or_else(|| {
-            Error::InvalidFormat(format!("No forward index for {}", column_name))
-        })?;
-
-        let fixed_bit_reader = FixedBitWidthReader::read(
-            &columns_psf,
-            fwd_loc.start_offset,
-            fwd_loc.size,
-            col_meta.bits_per_element,
-            col_meta.total_docs,
-        )?;
-
-        let dict_ids = fixed_bit_reader.read_all()?;
-        let mut values = Vec::with_capacity(dict_ids.len());
-
-        for dict_id in dict_ids {
+        let dictionary = self.read_dictionary(column_name, col_meta)?;
+        self.for_each_dict_id(column_name, col_meta, docs.clone(), |dict_id| {
             let value = dictionary.get_long(dict_id).ok_or_else(|| {
                 Error::InvalidFormat(format!(
                     "Invalid dict_id {} for column {}",
                     dict_id, column_name
                 ))
             })?;
-            values.push(value);
-        }
-
-        Ok(values)
+            f(value);
+            Ok(())
+        })
     }
 
     /// Read a STRING column (supports both dictionary-encoded and RAW)
     pub fn read_string_column(&self, column_name: &str) -> Result<Vec<String>> {
-        let col_meta = self.metadata.get_column(column_name)?;
+        let mut values = Vec::with_capacity(self.metadata.total_docs as usize);
+        self.for_each_string(column_name, |value| values.push(value.to_string()))?;
+        Ok(values)
+    }
+
+    /// Read a STRING column one chunk at a time
+    ///
+    /// A RAW column yields one chunk per physical chunk of its forward index,
+    /// each decompressed once; a dictionary-encoded column yields
+    /// [`CHUNK_BATCH_SIZE`] values at a time. Either way only one chunk of
+    /// values is held in memory, unlike
+    /// [`read_string_column`](Self::read_string_column).
+    pub fn read_string_column_in_chunks(
+        &self,
+        column_name: &str,
+    ) -> Result<ColumnChunks<'_, String>> {
+        let col_meta = self.single_value_column(column_name)?;
 
         if col_meta.data_type != DataType::String {
             return Err(Error::InvalidFormat(format!(
@@ -174,149 +692,507 @@ impl SegmentReader {
         }
 
         if col_meta.has_dictionary {
-            // Dictionary-encoded STRING
-            self.read_dict_encoded_string(column_name, col_meta)
-        } else {
-            // RAW STRING (variable-byte chunk format)
-            self.read_raw_string(column_name, col_meta)
+            let dictionary = self.read_dictionary(column_name, col_meta)?;
+            let column_name = column_name.to_string();
+            return Ok(ColumnChunks::new(self.batch_ranges(), move |docs| {
+                let mut values = Vec::with_capacity(docs.len());
+                self.for_each_dict_id(&column_name, col_meta, docs, |dict_id| {
+                    let value = dictionary.get_string(dict_id).ok_or_else(|| {
+                        Error::InvalidFormat(format!(
+                            "Invalid dict_id {} for column {}",
+                            dict_id, column_name
+                        ))
+                    })?;
+                    values.push(value.to_string());
+                    Ok(())
+                })?;
+                Ok(values)
+            }));
         }
-    }
-
-    /// Read dictionary-encoded STRING column
-    fn read_dict_encoded_string(
-        &self,
-        column_name: &str,
-        col_meta: &crate::metadata::ColumnMetadata,
-    ) -> Result<Vec<String>> {
-        let dict_loc = self
-            .index_map
-            .get_dictionary(column_name)
-            .ok_or_else(|| Error::InvalidFormat(format!("No dictionary for {}", column_name)))?;
-
-        let columns_psf = self.segment_dir.join("columns.psf");
-        let dictionary = DictionaryReader::read(
-            &columns_psf,
-            dict_loc.start_offset,
-            dict_loc.size,
-            &col_meta.data_type,
-            col_meta.cardinality,
-            col_meta.length_of_each_entry,
-        )?;
-
-        let fwd_loc = self.index_map.get_forward_index(column_name).ok_or_else(|| {
-            Error::InvalidFormat(format!("No forward index for {}", column_name))
-        })?;
-
-        let fixed_bit_reader = FixedBitWidthReader::read(
-            &columns_psf,
-            fwd_loc.start_offset,
-            fwd_loc.size,
-            col_meta.bits_per_element,
-            col_meta.total_docs,
-        )?;
 
-        let dict_ids = fixed_bit_reader.read_all()?;
-        let mut values = Vec::with_capacity(dict_ids.len());
-
-        for dict_id in dict_ids {
-            let value = dictionary.get_string(dict_id).ok_or_else(|| {
-                Error::InvalidFormat(format!(
-                    "Invalid dict_id {} for column {}",
-                    dict_id, column_name
-                ))
+        let reader = self.raw_reader(column_name, col_meta)?;
+        let chunks = reader.chunk_doc_ranges()?;
+        let utf8_policy = self.utf8_policy;
+        Ok(ColumnChunks::new(chunks, move |docs| {
+            let mut values = Vec::with_capacity(docs.len());
+            reader.for_each_string_in_range(docs, utf8_policy, |value| {
+                values.push(value.to_string())
             })?;
-            values.push(value.to_string());
-        }
+            Ok(values)
+        }))
+    }
 
-        Ok(values)
+    /// Decode a STRING column, passing each value to `f` in doc id order
+    ///
+    /// Unlike `read_string_column` this never allocates an owned `String` per row,
+    /// so callers can copy values straight into their own buffers.
+    pub fn for_each_string<F: FnMut(&str)>(&self, column_name: &str, f: F) -> Result<()> {
+        self.for_each_string_in_range(column_name, ALL_DOCS, f)
     }
 
-    /// Read RAW (non-dictionary) STRING column
-    fn read_raw_string(
+    /// Like [`for_each_string`](Self::for_each_string), for documents in `docs` only
+    ///
+    /// Only the part of the forward index holding those documents is read.
+    pub fn for_each_string_in_range<F: FnMut(&str)>(
         &self,
         column_name: &str,
-        col_meta: &crate::metadata::ColumnMetadata,
-    ) -> Result<Vec<String>> {
-        use crate::forward_index::VarByteChunkReader;
-
-        let fwd_loc = self.index_map.get_forward_index(column_name).ok_or_else(|| {
-            Error::InvalidFormat(format!("No forward index for {}", column_name))
-        })?;
+        docs: Range<u32>,
+        mut f: F,
+    ) -> Result<()> {
+        let col_meta = self.single_value_column(column_name)?;
 
-        let columns_psf = self.segment_dir.join("columns.psf");
-        let var_byte_reader = VarByteChunkReader::read(
-            &columns_psf,
-            fwd_loc.start_offset,
-            fwd_loc.size,
-            col_meta.total_docs,
-        )?;
+        if col_meta.data_type != DataType::String {
+            return Err(Error::InvalidFormat(format!(
+                "Column {} is not STRING type",
+                column_name
+            )));
+        }
 
-        var_byte_reader.read_all_strings()
+        if col_meta.has_dictionary {
+            // Dictionary-encoded STRING
+            let dictionary = self.read_dictionary(column_name, col_meta)?;
+            self.for_each_dict_id(column_name, col_meta, docs.clone(), |dict_id| {
+                let value = dictionary.get_string(dict_id).ok_or_else(|| {
+                    Error::InvalidFormat(format!(
+                        "Invalid dict_id {} for column {}",
+                        dict_id, column_name
+                    ))
+                })?;
+                f(value);
+                Ok(())
+            })
+        } else {
+            // RAW STRING (variable-byte chunk format)
+            let reader = self.raw_reader(column_name, col_meta)?;
+            reader.for_each_string_in_range(docs, self.utf8_policy, f)
+        }
     }
 
-    /// Read a dictionary-encoded FLOAT column
-    pub fn read_float_column(&self, column_name: &str) -> Result<Vec<f32>> {
-        let col_meta = self.metadata.get_column(column_name)?;
-
-        if col_meta.data_type != DataType::Float {
+    /// Read the documents in `docs` of a single-value STRING column as a
+    /// `StringViewArray`
+    ///
+    /// RAW columns point into their decompressed chunks instead of copying
+    /// each value (see [`VarByteChunkReader::read_range_as_string_view_array`]);
+    /// dictionary-encoded columns copy values longer than 12 bytes.
+    #[cfg(feature = "arrow")]
+    pub fn read_string_view_array_in_range(
+        &self,
+        column_name: &str,
+        docs: Range<u32>,
+    ) -> Result<StringViewArray> {
+        let col_meta = self.single_value_column(column_name)?;
+        if col_meta.data_type != DataType::String {
             return Err(Error::InvalidFormat(format!(
-                "Column {} is not FLOAT type",
+                "Column {} is not STRING type",
                 column_name
             )));
         }
 
         if !col_meta.has_dictionary {
-            return Err(Error::UnsupportedFeature(
-                "RAW FLOAT columns not yet supported".to_string(),
-            ));
+            return self
+                .raw_reader(column_name, col_meta)?
+                .read_range_as_string_view_array(docs, self.utf8_policy);
         }
+        let mut builder = StringViewBuilder::with_capacity(docs.len());
+        self.for_each_string_in_range(column_name, docs, |v| builder.append_value(v))?;
+        Ok(builder.finish())
+    }
 
-        let dict_loc = self
+    /// Read a dictionary-encoded STRING column without decoding it
+    ///
+    /// Returns the column's dictionary and one dict id per document, so callers
+    /// can keep the compact encoding (e.g. as an Arrow `DictionaryArray`).
+    pub fn read_string_dictionary_column(
+        &self,
+        column_name: &str,
+    ) -> Result<(DictionaryReader, Vec<u32>)> {
+        self.read_string_dictionary_column_in_range(column_name, ALL_DOCS)
+    }
+
+    /// Like [`read_string_dictionary_column`](Self::read_string_dictionary_column),
+    /// with dict ids for the documents in `docs` only
+    pub fn read_string_dictionary_column_in_range(
+        &self,
+        column_name: &str,
+        docs: Range<u32>,
+    ) -> Result<(DictionaryReader, Vec<u32>)> {
+        let col_meta = self.single_value_column(column_name)?;
+
+        if col_meta.data_type != DataType::String || !col_meta.has_dictionary {
+            return Err(Error::InvalidFormat(format!(
+                "Column {} is not a dictionary-encoded STRING column",
+                column_name
+            )));
+        }
+
+        let dictionary = self.read_dictionary(column_name, col_meta)?;
+        let mut dict_ids = Vec::with_capacity(col_meta.total_docs as usize);
+        self.for_each_dict_id(column_name, col_meta, docs.clone(), |dict_id| {
+            dict_ids.push(dict_id);
+            Ok(())
+        })?;
+        Ok((dictionary, dict_ids))
+    }
+
+    /// Read the raw forward-index dict ids of a dictionary-encoded column
+    ///
+    /// One id per document, with no dictionary lookup; meant for debugging
+    /// segments whose decoded values look wrong. Works for any data type.
+    pub fn read_dict_ids(&self, column_name: &str) -> Result<Vec<u32>> {
+        let col_meta = self.single_value_column(column_name)?;
+
+        if !col_meta.has_dictionary {
+            return Err(Error::InvalidFormat(format!(
+                "Column {} is not dictionary-encoded",
+                column_name
+            )));
+        }
+
+        let mut dict_ids = Vec::with_capacity(col_meta.total_docs as usize);
+        self.for_each_dict_id(column_name, col_meta, ALL_DOCS, |dict_id| {
+            dict_ids.push(dict_id);
+            Ok(())
+        })?;
+        Ok(dict_ids)
+    }
+
+    /// Pairs `(doc_id, other_doc_id)` of the documents of this segment and
+    /// `other` whose `key_column` values are equal
+    ///
+    /// A low-level primitive for joining corresponding segments of
+    /// co-partitioned tables without a hash join. Both key columns must be
+    /// single-value and dictionary-encoded with the same data type; values are
+    /// matched once per dictionary entry, not per document. Null documents
+    /// match nothing. Pairs are ordered by `doc_id`, then `other_doc_id`.
+    pub fn correlate_with(&self, other: &SegmentReader, key_column: &str) -> Result<Vec<(u32, u32)>> {
+        let dictionary = self.key_dictionary(key_column)?;
+        let other_dictionary = other.key_dictionary(key_column)?;
+        let to_other = dictionary.dict_id_mapping(&other_dictionary)?;
+
+        // Documents of `other` per dict id of its dictionary
+        let other_nulls = other.key_null_doc_ids(key_column)?;
+        let mut other_docs: Vec<Vec<u32>> = vec![Vec::new(); other_dictionary.len()];
+        for (doc_id, dict_id) in (0u32..).zip(other.read_dict_ids(key_column)?) {
+            if let Some(docs) = other_docs.get_mut(dict_id as usize) {
+                if !other_nulls.contains(doc_id) {
+                    docs.push(doc_id);
+                }
+            }
+        }
+
+        let nulls = self.key_null_doc_ids(key_column)?;
+        let mut pairs = Vec::new();
+        for (doc_id, dict_id) in (0u32..).zip(self.read_dict_ids(key_column)?) {
+            if nulls.contains(doc_id) {
+                continue;
+            }
+            if let Some(Some(other_dict_id)) = to_other.get(dict_id as usize) {
+                let matches = &other_docs[*other_dict_id as usize];
+                pairs.extend(matches.iter().map(|&other_doc_id| (doc_id, other_doc_id)));
+            }
+        }
+        Ok(pairs)
+    }
+
+    /// Dictionary of a join key column of [`correlate_with`](Self::correlate_with)
+    fn key_dictionary(&self, column_name: &str) -> Result<DictionaryReader> {
+        let col_meta = self.single_value_column(column_name)?;
+        if !col_meta.has_dictionary {
+            return Err(Error::InvalidFormat(format!(
+                "Column {} is not dictionary-encoded",
+                column_name
+            )));
+        }
+        self.read_dictionary(column_name, col_meta)
+    }
+
+    /// Null doc ids of a join key column, empty without a null value vector
+    fn key_null_doc_ids(&self, column_name: &str) -> Result<DocIdSet> {
+        Ok(self
+            .null_bitmap_reader(column_name)?
+            .map(|reader| reader.get_null_doc_ids())
+            .unwrap_or_default())
+    }
+
+    /// Open the var-byte forward index of a RAW (non-dictionary) column
+    fn raw_reader(
+        &self,
+        column_name: &str,
+        col_meta: &ColumnMetadata,
+    ) -> Result<VarByteChunkReader> {
+        let fwd_loc = self.index_map.get_forward_index(column_name).ok_or_else(|| {
+            Error::InvalidFormat(format!("No forward index for {}", column_name))
+        })?;
+
+        if fwd_loc.is_compressed() {
+            // The chunk reader seeks into columns.psf lazily, so it cannot
+            // work from a decompressed copy of the region
+            return Err(Error::UnsupportedFeature(format!(
+                "Compressed raw forward index region for column {}",
+                column_name
+            )));
+        }
+
+        // Releases since 1.0 write the chunk metadata little-endian; only
+        // segments of older or unknown writers have it detected
+        let metadata_byte_order = self
+            .metadata
+            .is_created_by_pinot_version_at_least(1, 0)
+            .then_some(ByteOrder::LittleEndian);
+
+        let columns_psf = self.segment_dir.join("columns.psf");
+        VarByteChunkReader::read_with_byte_order(
+            &columns_psf,
+            fwd_loc.start_offset,
+            fwd_loc.size,
+            col_meta.total_docs,
+            metadata_byte_order,
+        )
+        .map_err(|e| match (e, &self.metadata.creator_version) {
+            // Only the V4 chunk format is implemented; point at the writer
+            // version so older segments are easy to recognize
+            (Error::UnsupportedFeature(msg), Some(version)) => Error::UnsupportedFeature(format!(
+                "{} (column {}, segment created by Pinot {})",
+                msg, column_name, version
+            )),
+            (e, _) => e,
+        })
+    }
+
+    /// Read an index region from columns.psf, decompressing it if needed
+    fn read_region(&self, column_name: &str, loc: &IndexLocation) -> Result<Vec<u8>> {
+        if let Some(region) = self.decompressed_region(column_name, loc)? {
+            return Ok(region);
+        }
+        let mut file = File::open(self.segment_dir.join("columns.psf"))?;
+        file.seek(SeekFrom::Start(loc.start_offset as u64))?;
+        let mut region = vec![0u8; loc.size];
+        file.read_exact(&mut region)?;
+        Ok(region)
+    }
+
+    /// Read and decompress an index region stored compressed in columns.psf
+    ///
+    /// Returns `None` for uncompressed regions, which readers map directly.
+    fn decompressed_region(&self, column_name: &str, loc: &IndexLocation) -> Result<Option<Vec<u8>>> {
+        let Some(codec) = loc.compression.as_deref().filter(|_| loc.is_compressed()) else {
+            return Ok(None);
+        };
+        let compression = RegionCompression::from_string(codec)?;
+        let uncompressed_size = loc.uncompressed_size.ok_or_else(|| {
+            Error::InvalidFormat(format!(
+                "Compressed index region of column {} has no uncompressedSize",
+                column_name
+            ))
+        })?;
+
+        let mut file = File::open(self.segment_dir.join("columns.psf"))?;
+        file.seek(SeekFrom::Start(loc.start_offset as u64))?;
+        let mut compressed = vec![0u8; loc.size];
+        file.read_exact(&mut compressed)?;
+
+        compression.decompress(&compressed, uncompressed_size).map(Some)
+    }
+
+    /// Read the dictionary of a dictionary-encoded column
+    fn read_dictionary(&self, column_name: &str, col_meta: &ColumnMetadata) -> Result<DictionaryReader> {
+        let dict_loc = self
             .index_map
             .get_dictionary(column_name)
             .ok_or_else(|| Error::InvalidFormat(format!("No dictionary for {}", column_name)))?;
 
+        if let Some(region) = self.decompressed_region(column_name, dict_loc)? {
+            return DictionaryReader::from_bytes(
+                &region,
+                &col_meta.data_type,
+                col_meta.cardinality,
+                col_meta.length_of_each_entry,
+                self.utf8_policy,
+            );
+        }
+
         let columns_psf = self.segment_dir.join("columns.psf");
-        let dictionary = DictionaryReader::read(
+        DictionaryReader::read(
             &columns_psf,
             dict_loc.start_offset,
             dict_loc.size,
             &col_meta.data_type,
             col_meta.cardinality,
             col_meta.length_of_each_entry,
-        )?;
+            self.utf8_policy,
+        )
+    }
+
+    /// Consecutive ranges of [`CHUNK_BATCH_SIZE`] documents covering the
+    /// segment
+    fn batch_ranges(&self) -> Vec<Range<u32>> {
+        let total_docs = self.metadata.total_docs;
+        (0..total_docs)
+            .step_by(CHUNK_BATCH_SIZE as usize)
+            .map(|start| start..start.saturating_add(CHUNK_BATCH_SIZE).min(total_docs))
+            .collect()
+    }
+
+    /// Metadata of a column the single-value readers can decode
+    ///
+    /// Multi-value forward indexes hold a variable number of entries per
+    /// document; reading them as one value per document would return garbage.
+    fn single_value_column(&self, column_name: &str) -> Result<&ColumnMetadata> {
+        let col_meta = self.metadata.get_column(column_name)?;
+        if col_meta.is_multi_value {
+            return Err(Error::UnsupportedFeature(format!(
+                "Multi-value columns not yet supported (column {} holds up to {} values per document)",
+                column_name, col_meta.max_multi_values
+            )));
+        }
+        Ok(col_meta)
+    }
+
+    /// Decode the dict ids of the documents in `docs` in doc id order
+    ///
+    /// The forward index length is checked against `segment.total.docs` first,
+    /// see [`DocCountMismatchPolicy`].
+    fn for_each_dict_id<F>(
+        &self,
+        column_name: &str,
+        col_meta: &ColumnMetadata,
+        docs: Range<u32>,
+        mut f: F,
+    ) -> Result<()>
+    where
+        F: FnMut(u32) -> Result<()>,
+    {
+        let fixed_bit_reader = self.forward_index_window(column_name, col_meta, docs.clone())?;
+        let num_values = self.check_forward_index_doc_count(column_name, col_meta, &fixed_bit_reader)?;
+        for doc_id in docs.start.min(num_values)..docs.end.min(num_values) {
+            f(FixedBitWidthReader::dict_index(fixed_bit_reader.get_dict_id(doc_id)?)?)?;
+        }
+        Ok(())
+    }
+
+    /// Open the fixed-bit forward index of a dictionary-encoded column
+    fn forward_index_reader(
+        &self,
+        column_name: &str,
+        col_meta: &ColumnMetadata,
+    ) -> Result<FixedBitWidthReader> {
+        self.forward_index_window(column_name, col_meta, ALL_DOCS)
+    }
 
+    /// Open the fixed-bit forward index, loading only the bytes of `docs`
+    /// unless the region is compressed
+    fn forward_index_window(
+        &self,
+        column_name: &str,
+        col_meta: &ColumnMetadata,
+        docs: Range<u32>,
+    ) -> Result<FixedBitWidthReader> {
         let fwd_loc = self.index_map.get_forward_index(column_name).ok_or_else(|| {
             Error::InvalidFormat(format!("No forward index for {}", column_name))
         })?;
 
-        let fixed_bit_reader = FixedBitWidthReader::read(
-            &columns_psf,
-            fwd_loc.start_offset,
-            fwd_loc.size,
-            col_meta.bits_per_element,
-            col_meta.total_docs,
-        )?;
+        let byte_order = self.fixed_bit_byte_order();
+        match self.decompressed_region(column_name, fwd_loc)? {
+            Some(region) => FixedBitWidthReader::from_bytes(
+                &region,
+                col_meta.bits_per_element,
+                col_meta.total_docs,
+                byte_order,
+            ),
+            None => FixedBitWidthReader::read_window(
+                &self.segment_dir.join("columns.psf"),
+                fwd_loc.start_offset,
+                fwd_loc.size,
+                col_meta.bits_per_element,
+                col_meta.total_docs,
+                byte_order,
+                docs,
+            ),
+        }
+    }
+
+    /// Bit packing of fixed-bit forward indexes, from `segment.index.version`
+    ///
+    /// Only V1 segments pack little-endian (without a magic marker); a
+    /// segment that does not record its version is read as V3.
+    fn fixed_bit_byte_order(&self) -> ByteOrder {
+        match self.metadata.segment_version {
+            Some(SegmentVersion::V1) => ByteOrder::LittleEndian,
+            _ => ByteOrder::BigEndian,
+        }
+    }
+
+    /// Read a dictionary-encoded FLOAT column
+    pub fn read_float_column(&self, column_name: &str) -> Result<Vec<f32>> {
+        let mut values = Vec::with_capacity(self.metadata.total_docs as usize);
+        self.for_each_float(column_name, |value| values.push(value))?;
+        Ok(values)
+    }
+
+    /// Decode a dictionary-encoded FLOAT column, passing each value to `f` in doc id order
+    pub fn for_each_float<F: FnMut(f32)>(&self, column_name: &str, f: F) -> Result<()> {
+        self.for_each_float_in_range(column_name, ALL_DOCS, f)
+    }
+
+    /// Like [`for_each_float`](Self::for_each_float), for documents in `docs` only
+    ///
+    /// Only the part of the forward index holding those documents is read.
+    pub fn for_each_float_in_range<F: FnMut(f32)>(
+        &self,
+        column_name: &str,
+        docs: Range<u32>,
+        mut f: F,
+    ) -> Result<()> {
+        let col_meta = self.single_value_column(column_name)?;
 
-        let dict_ids = fixed_bit_reader.read_all()?;
-        let mut values = Vec::with_capacity(dict_ids.len());
+        if col_meta.data_type != DataType::Float {
+            return Err(Error::InvalidFormat(format!(
+                "Column {} is not FLOAT type",
+                column_name
+            )));
+        }
+
+        if !col_meta.has_dictionary {
+            return Err(Error::UnsupportedFeature(
+                "RAW FLOAT columns not yet supported".to_string(),
+            ));
+        }
 
-        for dict_id in dict_ids {
+        let dictionary = self.read_dictionary(column_name, col_meta)?;
+        self.for_each_dict_id(column_name, col_meta, docs.clone(), |dict_id| {
             let value = dictionary.get_float(dict_id).ok_or_else(|| {
                 Error::InvalidFormat(format!(
                     "Invalid dict_id {} for column {}",
                     dict_id, column_name
                 ))
             })?;
-            values.push(value);
-        }
-
-        Ok(values)
+            f(value);
+            Ok(())
+        })
     }
 
     /// Read a dictionary-encoded DOUBLE column
     pub fn read_double_column(&self, column_name: &str) -> Result<Vec<f64>> {
-        let col_meta = self.metadata.get_column(column_name)?;
+        let mut values = Vec::with_capacity(self.metadata.total_docs as usize);
+        self.for_each_double(column_name, |value| values.push(value))?;
+        Ok(values)
+    }
+
+    /// Decode a dictionary-encoded DOUBLE column, passing each value to `f` in doc id order
+    pub fn for_each_double<F: FnMut(f64)>(&self, column_name: &str, f: F) -> Result<()> {
+        self.for_each_double_in_range(column_name, ALL_DOCS, f)
+    }
+
+    /// Like [`for_each_double`](Self::for_each_double), for documents in `docs` only
+    ///
+    /// Only the part of the forward index holding those documents is read.
+    pub fn for_each_double_in_range<F: FnMut(f64)>(
+        &self,
+        column_name: &str,
+        docs: Range<u32>,
+        mut f: F,
+    ) -> Result<()> {
+        let col_meta = self.single_value_column(column_name)?;
 
         if col_meta.data_type != DataType::Double {
             return Err(Error::InvalidFormat(format!(
@@ -331,46 +1207,946 @@ impl SegmentReader {
             ));
         }
 
-        let dict_loc = self
-            .index_map
-            .get_dictionary(column_name)
-            .ok_or_else(|| Error::InvalidFormat(format!("No dictionary for {}", column_name)))?;
+        let dictionary = self.read_dictionary(column_name, col_meta)?;
+        self.for_each_dict_id(column_name, col_meta, docs.clone(), |dict_id| {
+            let value = dictionary.get_double(dict_id).ok_or_else(|| {
+                Error::InvalidFormat(format!(
+                    "Invalid dict_id {} for column {}",
+                    dict_id, column_name
+                ))
+            })?;
+            f(value);
+            Ok(())
+        })
+    }
 
-        let columns_psf = self.segment_dir.join("columns.psf");
-        let dictionary = DictionaryReader::read(
-            &columns_psf,
-            dict_loc.start_offset,
-            dict_loc.size,
-            &col_meta.data_type,
-            col_meta.cardinality,
-            col_meta.length_of_each_entry,
-        )?;
+    /// Read a BYTES column (dictionary-encoded or RAW)
+    pub fn read_bytes_column(&self, column_name: &str) -> Result<Vec<Vec<u8>>> {
+        let mut values = Vec::with_capacity(self.metadata.total_docs as usize);
+        self.for_each_bytes(column_name, |value| values.push(value.to_vec()))?;
+        Ok(values)
+    }
 
-        let fwd_loc = self.index_map.get_forward_index(column_name).ok_or_else(|| {
-            Error::InvalidFormat(format!("No forward index for {}", column_name))
-        })?;
+    /// Decode a BYTES column, passing each value to `f` in doc id order
+    pub fn for_each_bytes<F: FnMut(&[u8])>(&self, column_name: &str, f: F) -> Result<()> {
+        self.for_each_bytes_in_range(column_name, ALL_DOCS, f)
+    }
 
-        let fixed_bit_reader = FixedBitWidthReader::read(
-            &columns_psf,
-            fwd_loc.start_offset,
-            fwd_loc.size,
-            col_meta.bits_per_element,
-            col_meta.total_docs,
-        )?;
+    /// Like [`for_each_bytes`](Self::for_each_bytes), for documents in `docs` only
+    ///
+    /// Only the part of the forward index (or, for RAW columns, the chunks)
+    /// holding those documents is read.
+    pub fn for_each_bytes_in_range<F: FnMut(&[u8])>(
+        &self,
+        column_name: &str,
+        docs: Range<u32>,
+        mut f: F,
+    ) -> Result<()> {
+        let col_meta = self.single_value_column(column_name)?;
 
-        let dict_ids = fixed_bit_reader.read_all()?;
-        let mut values = Vec::with_capacity(dict_ids.len());
+        if col_meta.data_type != DataType::Bytes {
+            return Err(Error::InvalidFormat(format!(
+                "Column {} is not BYTES type",
+                column_name
+            )));
+        }
 
-        for dict_id in dict_ids {
-            let value = dictionary.get_double(dict_id).ok_or_else(|| {
+        if !col_meta.has_dictionary {
+            // RAW BYTES (variable-byte chunk format)
+            let reader = self.raw_reader(column_name, col_meta)?;
+            return reader.for_each_bytes_in_range(docs, f);
+        }
+
+        let dictionary = self.read_dictionary(column_name, col_meta)?;
+        self.for_each_dict_id(column_name, col_meta, docs.clone(), |dict_id| {
+            let value = dictionary.get_bytes(dict_id).ok_or_else(|| {
                 Error::InvalidFormat(format!(
                     "Invalid dict_id {} for column {}",
                     dict_id, column_name
                 ))
             })?;
-            values.push(value);
+            f(value);
+            Ok(())
+        })
+    }
+
+    /// Pick a seeded pseudo-random sample of this segment's doc ids
+    ///
+    /// Returns `round(total_docs * sample_fraction)` distinct doc ids in
+    /// ascending order. The same seed selects the same documents, so the
+    /// sampled readers of several columns line up row by row, and the
+    /// generator is fixed (ChaCha8) so samples stay stable across releases.
+    pub fn sample_doc_ids(&self, sample_fraction: f64, seed: u64) -> Result<Vec<u32>> {
+        if !(0.0..=1.0).contains(&sample_fraction) {
+            return Err(Error::InvalidFormat(format!(
+                "sample_fraction must be within [0, 1], got {}",
+                sample_fraction
+            )));
+        }
+
+        let total_docs = self.metadata.total_docs;
+        let amount = (total_docs as f64 * sample_fraction).round() as usize;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut doc_ids: Vec<u32> = rand::seq::index::sample(&mut rng, total_docs as usize, amount)
+            .into_iter()
+            .map(|doc_id| doc_id as u32)
+            .collect();
+        doc_ids.sort_unstable();
+        Ok(doc_ids)
+    }
+
+    /// Read a sample of a dictionary-encoded INT column
+    ///
+    /// Only the documents picked by [`sample_doc_ids`](Self::sample_doc_ids)
+    /// are decoded, in doc id order.
+    pub fn read_int_column_sampled(
+        &self,
+        column_name: &str,
+        sample_fraction: f64,
+        seed: u64,
+    ) -> Result<Vec<i32>> {
+        let doc_ids = self.sample_doc_ids(sample_fraction, seed)?;
+        self.read_dictionary_values(column_name, DataType::Int, &doc_ids, |dictionary, dict_id| {
+            dictionary.get_int(dict_id)
+        })
+    }
+
+    /// Read a sample of a dictionary-encoded LONG column, see
+    /// [`read_int_column_sampled`](Self::read_int_column_sampled)
+    pub fn read_long_column_sampled(
+        &self,
+        column_name: &str,
+        sample_fraction: f64,
+        seed: u64,
+    ) -> Result<Vec<i64>> {
+        let doc_ids = self.sample_doc_ids(sample_fraction, seed)?;
+        self.read_dictionary_values(column_name, DataType::Long, &doc_ids, |dictionary, dict_id| {
+            dictionary.get_long(dict_id)
+        })
+    }
+
+    /// Read a sample of a dictionary-encoded FLOAT column, see
+    /// [`read_int_column_sampled`](Self::read_int_column_sampled)
+    pub fn read_float_column_sampled(
+        &self,
+        column_name: &str,
+        sample_fraction: f64,
+        seed: u64,
+    ) -> Result<Vec<f32>> {
+        let doc_ids = self.sample_doc_ids(sample_fraction, seed)?;
+        self.read_dictionary_values(column_name, DataType::Float, &doc_ids, |dictionary, dict_id| {
+            dictionary.get_float(dict_id)
+        })
+    }
+
+    /// Read a sample of a dictionary-encoded DOUBLE column, see
+    /// [`read_int_column_sampled`](Self::read_int_column_sampled)
+    pub fn read_double_column_sampled(
+        &self,
+        column_name: &str,
+        sample_fraction: f64,
+        seed: u64,
+    ) -> Result<Vec<f64>> {
+        let doc_ids = self.sample_doc_ids(sample_fraction, seed)?;
+        self.read_dictionary_values(column_name, DataType::Double, &doc_ids, |dictionary, dict_id| {
+            dictionary.get_double(dict_id)
+        })
+    }
+
+    /// Read a sample of a STRING column (dictionary-encoded or RAW), see
+    /// [`read_int_column_sampled`](Self::read_int_column_sampled)
+    ///
+    /// For RAW columns the chunk holding each sampled document is
+    /// decompressed separately, so this pays off for small fractions only.
+    pub fn read_string_column_sampled(
+        &self,
+        column_name: &str,
+        sample_fraction: f64,
+        seed: u64,
+    ) -> Result<Vec<String>> {
+        let doc_ids = self.sample_doc_ids(sample_fraction, seed)?;
+        let col_meta = self.single_value_column(column_name)?;
+        if col_meta.has_dictionary {
+            return self.read_dictionary_values(
+                column_name,
+                DataType::String,
+                &doc_ids,
+                |dictionary, dict_id| dictionary.get_string(dict_id).map(str::to_string),
+            );
         }
 
+        if col_meta.data_type != DataType::String {
+            return Err(Error::InvalidFormat(format!(
+                "Column {} is not STRING type",
+                column_name
+            )));
+        }
+        let reader = self.raw_reader(column_name, col_meta)?;
+        let mut values = Vec::with_capacity(doc_ids.len());
+        for doc_id in doc_ids {
+            reader.for_each_string_in_range(doc_id..doc_id + 1, self.utf8_policy, |value| {
+                values.push(value.to_string())
+            })?;
+        }
         Ok(values)
     }
+
+    /// Decode the values of `doc_ids` (ascending) of a dictionary-encoded column
+    ///
+    /// Only the forward index bytes between the first and last document are
+    /// read. Documents past the end of the forward index (possible under
+    /// [`DocCountMismatchPolicy::Warn`]) are an error rather than dropped, so
+    /// the result always has one value per requested document.
+    fn read_dictionary_values<T>(
+        &self,
+        column_name: &str,
+        data_type: DataType,
+        doc_ids: &[u32],
+        get: impl Fn(&DictionaryReader, u32) -> Option<T>,
+    ) -> Result<Vec<T>> {
+        let col_meta = self.single_value_column(column_name)?;
+        if col_meta.data_type != data_type {
+            return Err(Error::InvalidFormat(format!(
+                "Column {} is not {} type",
+                column_name, data_type
+            )));
+        }
+        if !col_meta.has_dictionary {
+            return Err(Error::UnsupportedFeature(format!(
+                "RAW {} columns not yet supported",
+                data_type
+            )));
+        }
+
+        let (Some(&first), Some(&last)) = (doc_ids.first(), doc_ids.last()) else {
+            return Ok(Vec::new());
+        };
+        let dictionary = self.read_dictionary(column_name, col_meta)?;
+        let fixed_bit_reader = self.forward_index_window(column_name, col_meta, first..last + 1)?;
+        let num_values = self.check_forward_index_doc_count(column_name, col_meta, &fixed_bit_reader)?;
+        if last >= num_values {
+            return Err(Error::InvalidFormat(format!(
+                "Doc id {} is past the end of the forward index of column {} ({} values)",
+                last, column_name, num_values
+            )));
+        }
+
+        fixed_bit_reader
+            .read_doc_ids(doc_ids)?
+            .into_iter()
+            .map(|dict_id| {
+                get(&dictionary, FixedBitWidthReader::dict_index(dict_id)?).ok_or_else(|| {
+                    Error::InvalidFormat(format!(
+                        "Invalid dict_id {} for column {}",
+                        dict_id, column_name
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Compare the packed forward index size with `segment.total.docs`
+    ///
+    /// Returns the number of values that can safely be read.
+    fn check_forward_index_doc_count(
+        &self,
+        column_name: &str,
+        col_meta: &ColumnMetadata,
+        reader: &FixedBitWidthReader,
+    ) -> Result<u32> {
+        let total_docs = reader.num_values();
+        if col_meta.bits_per_element == 0 {
+            return Ok(total_docs);
+        }
+
+        let expected_len = FixedBitWidthReader::packed_size(total_docs, col_meta.bits_per_element);
+        let actual_len = reader.packed_len();
+        if actual_len >= expected_len && actual_len - expected_len < MAX_FORWARD_INDEX_PADDING {
+            return Ok(total_docs);
+        }
+
+        let index_docs = reader.packed_capacity().min(u32::MAX as u64) as u32;
+        let message = format!(
+            "Forward index of column {} holds {} values ({} bytes at {} bits per value) but segment.total.docs is {}",
+            column_name, index_docs, actual_len, col_meta.bits_per_element, total_docs
+        );
+
+        match self.doc_count_mismatch {
+            DocCountMismatchPolicy::Error => Err(Error::InvalidFormat(message)),
+            DocCountMismatchPolicy::Warn => {
+                tracing::warn!("{} in segment {}", message, self.metadata.segment_name);
+                Ok(total_docs.min(index_docs))
+            }
+        }
+    }
+}
+
+/// Fail fast on a truncated columns.psf: every index region listed in the
+/// index map must lie within the file, otherwise reads fail mid-query
+fn check_columns_file(path: &Path, index_map: &IndexMap) -> Result<()> {
+    // Segments whose columns live in another layout have no columns.psf
+    let Ok(file_meta) = std::fs::metadata(path) else {
+        return Ok(());
+    };
+    let file_len = file_meta.len() as usize;
+
+    let truncated = index_map
+        .indexes
+        .iter()
+        .find(|(_, loc)| loc.start_offset.saturating_add(loc.size) > file_len);
+    if let Some(((column, index_type), loc)) = truncated {
+        return Err(Error::InvalidFormat(format!(
+            "{:?} is truncated: {}.{} spans bytes {}..{} but the file has {} bytes",
+            path,
+            column,
+            index_type,
+            loc.start_offset,
+            loc.start_offset + loc.size,
+            file_len
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::SegmentBuilder;
+    use std::ops::Bound;
+
+    fn hits_segment(dir: &Path, builder: impl FnOnce(SegmentBuilder) -> SegmentBuilder) {
+        let segment = SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .int_column("hits", (0..100).map(|i| i % 13).collect());
+        builder(segment).write(dir).unwrap();
+    }
+
+    #[test]
+    fn test_forward_index_matches_total_docs() {
+        let dir = tempfile::tempdir().unwrap();
+        hits_segment(dir.path(), |b| b);
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        let hits = reader.read_int_column("hits").unwrap();
+        assert_eq!(hits.len(), 100);
+        assert_eq!(hits[14], 1);
+    }
+
+    #[test]
+    fn test_v1_forward_index_follows_segment_version() {
+        let hits: Vec<i32> = (0..100).map(|i| i % 13).collect();
+        let v3_dir = tempfile::tempdir().unwrap();
+        hits_segment(v3_dir.path(), |b| b);
+        let v1_dir = tempfile::tempdir().unwrap();
+        hits_segment(v1_dir.path(), |b| b.v1_forward_indexes());
+
+        let v3 = SegmentReader::open(v3_dir.path()).unwrap();
+        let v1 = SegmentReader::open(v1_dir.path()).unwrap();
+        assert_eq!(v3.metadata().segment_version, Some(SegmentVersion::V3));
+        assert_eq!(v1.metadata().segment_version, Some(SegmentVersion::V1));
+        assert_eq!(v1.read_int_column("hits").unwrap(), hits);
+        assert_eq!(v3.read_int_column("hits").unwrap(), hits);
+        let mut window = Vec::new();
+        v1.for_each_int_in_range("hits", 30..45, |v| window.push(v)).unwrap();
+        assert_eq!(window, hits[30..45]);
+
+        // Read as V3, the V1 region lacks the magic marker
+        let metadata = v1_dir.path().join("metadata.properties");
+        let properties = std::fs::read_to_string(&metadata).unwrap();
+        std::fs::write(&metadata, properties.replace("segment.index.version = v1", "")).unwrap();
+        let err = SegmentReader::open(v1_dir.path())
+            .unwrap()
+            .read_int_column("hits")
+            .unwrap_err();
+        assert!(err.to_string().contains("magic marker"), "{}", err);
+    }
+
+    #[test]
+    fn test_raw_metadata_byte_order_follows_creator_version() {
+        let names: Vec<String> = (0..2500).map(|i| format!("player_{}", i)).collect();
+        let dir = tempfile::tempdir().unwrap();
+        let open = |creator_version: Option<&str>| {
+            let mut builder = SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+                .raw_string_column("name", names.iter().map(String::as_str).collect());
+            if let Some(version) = creator_version {
+                builder = builder.property("segment.creator.version", version);
+            }
+            builder.write(dir.path()).unwrap();
+
+            // Rewrite the chunk metadata big-endian; the RAW column is the
+            // only region, so it starts at the magic marker at offset 0
+            let path = dir.path().join("columns.psf");
+            let mut psf = std::fs::read(&path).unwrap();
+            let chunks_start = i32::from_be_bytes(psf[20..24].try_into().unwrap()) as usize;
+            for word in psf[24..8 + chunks_start].chunks_exact_mut(4) {
+                word.reverse();
+            }
+            std::fs::write(&path, psf).unwrap();
+            SegmentReader::open(dir.path()).unwrap()
+        };
+        let byte_order = |reader: &SegmentReader| {
+            let col_meta = &reader.metadata().columns["name"];
+            reader.raw_reader("name", col_meta).unwrap().metadata_byte_order()
+        };
+
+        // Unknown and pre-1.0 writers: detected from the entries
+        for version in [None, Some("0.12.1")] {
+            let reader = open(version);
+            assert_eq!(byte_order(&reader), ByteOrder::BigEndian, "{:?}", version);
+            assert_eq!(reader.read_string_column("name").unwrap(), names);
+        }
+
+        // 1.x writers: little-endian, without looking at the entries
+        let reader = open(Some("1.2.0"));
+        assert_eq!(byte_order(&reader), ByteOrder::LittleEndian);
+    }
+
+    #[test]
+    fn test_total_docs_exceeds_forward_index() {
+        let dir = tempfile::tempdir().unwrap();
+        hits_segment(dir.path(), |b| b.total_docs(150));
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        let err = reader.read_int_column("hits").unwrap_err().to_string();
+        assert!(err.contains("holds 100 values"), "unexpected error: {}", err);
+        assert!(err.contains("segment.total.docs is 150"), "unexpected error: {}", err);
+
+        let reader = reader.with_doc_count_mismatch_policy(DocCountMismatchPolicy::Warn);
+        let hits = reader.read_int_column("hits").unwrap();
+        assert_eq!(hits.len(), 100);
+
+        // A sample cannot silently lose the documents the index lacks
+        let err = reader.read_int_column_sampled("hits", 1.0, 42).unwrap_err().to_string();
+        assert!(err.contains("Doc id 149 is past the end"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_forward_index_exceeds_total_docs() {
+        let dir = tempfile::tempdir().unwrap();
+        // 4 bits per value: 50 extra bytes hold 100 more values than declared
+        hits_segment(dir.path(), |b| b.forward_index_padding(50));
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        let err = reader.read_int_column("hits").unwrap_err().to_string();
+        assert!(err.contains("holds 200 values"), "unexpected error: {}", err);
+        assert!(err.contains("segment.total.docs is 100"), "unexpected error: {}", err);
+
+        let reader = reader.with_doc_count_mismatch_policy(DocCountMismatchPolicy::Warn);
+        let hits = reader.read_int_column("hits").unwrap();
+        assert_eq!(hits.len(), 100);
+    }
+
+    #[test]
+    fn test_forward_index_padding_is_tolerated() {
+        let dir = tempfile::tempdir().unwrap();
+        hits_segment(dir.path(), |b| b.forward_index_padding(4));
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        assert_eq!(reader.read_int_column("hits").unwrap().len(), 100);
+    }
+
+    #[test]
+    fn test_read_string_dictionary_column() {
+        let dir = tempfile::tempdir().unwrap();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .string_column("team", vec!["NYA", "BOS", "NYA"])
+            .int_column("hits", vec![1, 2, 3])
+            .write(dir.path())
+            .unwrap();
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        let (dictionary, dict_ids) = reader.read_string_dictionary_column("team").unwrap();
+        assert_eq!(dictionary.string_values().unwrap(), ["BOS", "NYA"]);
+        assert_eq!(dict_ids, vec![1, 0, 1]);
+
+        assert!(reader.read_string_dictionary_column("hits").is_err());
+    }
+
+    #[test]
+    fn test_read_raw_string_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let names: Vec<String> = (0..2500).map(|i| format!("player_{}", i)).collect();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .int_column("hits", (0..2500).collect())
+            .raw_string_column("name", names.iter().map(|s| s.as_str()).collect())
+            .write(dir.path())
+            .unwrap();
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        let meta = reader.metadata().get_column("name").unwrap();
+        assert!(!meta.has_dictionary);
+        assert_eq!(meta.cardinality, 2500);
+        reader.check_column_readable("name").unwrap();
+        assert_eq!(reader.read_string_column("name").unwrap(), names);
+        assert!(reader.read_dict_ids("name").is_err());
+    }
+
+    #[test]
+    fn test_read_bytes_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixed = vec![vec![0xCA, 0xFE], vec![0x00, 0x01], vec![0xCA, 0xFE]];
+        let variable = vec![vec![0x01, 0x02, 0x03], vec![], vec![0xFF]];
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .bytes_column("fixed", fixed.clone())
+            .bytes_column("variable", variable.clone())
+            .write(dir.path())
+            .unwrap();
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        assert_eq!(reader.metadata().get_column("fixed").unwrap().length_of_each_entry, 2);
+        assert_eq!(reader.metadata().get_column("variable").unwrap().length_of_each_entry, 0);
+        reader.check_column_readable("fixed").unwrap();
+        assert_eq!(reader.read_bytes_column("fixed").unwrap(), fixed);
+        assert_eq!(
+            reader.read_column("variable").unwrap(),
+            ColumnData::Bytes(variable.clone())
+        );
+
+        let mut values = Vec::new();
+        reader
+            .for_each_bytes_in_range("variable", 1..3, |v| values.push(v.to_vec()))
+            .unwrap();
+        assert_eq!(values, variable[1..]);
+        assert!(reader.read_string_column("fixed").is_err());
+    }
+
+    #[test]
+    fn test_read_raw_bytes_column() {
+        let dir = tempfile::tempdir().unwrap();
+        // Several 1000-document chunks, including empty values
+        let values: Vec<Vec<u8>> = (0..2500u32)
+            .map(|i| i.to_be_bytes()[..(i % 5) as usize].to_vec())
+            .collect();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .raw_bytes_column("payload", values.clone())
+            .write(dir.path())
+            .unwrap();
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        assert!(!reader.metadata().get_column("payload").unwrap().has_dictionary);
+        reader.check_column_readable("payload").unwrap();
+        assert_eq!(reader.read_bytes_column("payload").unwrap(), values);
+        assert_eq!(
+            reader.read_column("payload").unwrap(),
+            ColumnData::Bytes(values.clone())
+        );
+
+        let mut range = Vec::new();
+        reader
+            .for_each_bytes_in_range("payload", 995..1005, |v| range.push(v.to_vec()))
+            .unwrap();
+        assert_eq!(range, values[995..1005]);
+        assert!(reader.read_string_column("payload").is_err());
+    }
+
+    #[test]
+    fn test_correlate_with() {
+        let left_dir = tempfile::tempdir().unwrap();
+        let right_dir = tempfile::tempdir().unwrap();
+        SegmentBuilder::new("orders", "orders_0")
+            .string_column("team", vec!["NYA", "BOS", "SFN", "NYA", "LAN"])
+            .int_column("id", vec![1, 2, 3, 4, 5])
+            .null_values("team", vec![4])
+            .write(left_dir.path())
+            .unwrap();
+        SegmentBuilder::new("teams", "teams_0")
+            .string_column("team", vec!["BOS", "NYA", "CHA", "BOS"])
+            .long_column("id", vec![4, 3, 9, 1])
+            .write(right_dir.path())
+            .unwrap();
+        let left = SegmentReader::open(left_dir.path()).unwrap();
+        let right = SegmentReader::open(right_dir.path()).unwrap();
+
+        // Doc 4 (LAN) is null; SFN and CHA have no partner
+        assert_eq!(
+            left.correlate_with(&right, "team").unwrap(),
+            vec![(0, 1), (1, 0), (1, 3), (3, 1)]
+        );
+        assert_eq!(
+            right.correlate_with(&left, "team").unwrap(),
+            vec![(0, 1), (1, 0), (1, 3), (3, 1)]
+        );
+
+        // INT and LONG keys do not share a dictionary type
+        let err = left.correlate_with(&right, "id").unwrap_err();
+        assert!(matches!(err, Error::UnsupportedFeature(_)), "{}", err);
+        assert!(left.correlate_with(&right, "missing").is_err());
+    }
+
+    #[test]
+    fn test_read_dict_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .string_column("team", vec!["NYA", "BOS", "NYA", "SFN"])
+            .int_column("hits", vec![30, 10, 20, 10])
+            .write(dir.path())
+            .unwrap();
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+
+        let meta = reader.metadata().columns.get("hits").unwrap();
+        let dictionary = reader.read_dictionary("hits", meta).unwrap();
+        let dict_ids = reader.read_dict_ids("hits").unwrap();
+        let resolved: Vec<i32> = dict_ids
+            .iter()
+            .map(|&id| dictionary.get_int(id).unwrap())
+            .collect();
+        assert_eq!(resolved, reader.read_int_column("hits").unwrap());
+
+        let meta = reader.metadata().columns.get("team").unwrap();
+        let dictionary = reader.read_dictionary("team", meta).unwrap();
+        let dict_ids = reader.read_dict_ids("team").unwrap();
+        assert_eq!(dict_ids, vec![1, 0, 1, 2]);
+        let resolved: Vec<String> = dict_ids
+            .iter()
+            .map(|&id| dictionary.get_string(id).unwrap().to_string())
+            .collect();
+        assert_eq!(resolved, reader.read_string_column("team").unwrap());
+
+        assert!(reader.read_dict_ids("missing").is_err());
+    }
+
+    #[test]
+    fn test_null_value_vector_and_valid_doc_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        hits_segment(dir.path(), |b| {
+            b.null_values("hits", vec![3, 70]).valid_doc_ids(vec![0, 1, 99])
+        });
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        assert!(reader.has_null_value_vector("hits"));
+        assert_eq!(reader.read_null_doc_ids("hits").unwrap(), Some(vec![3, 70]));
+        assert!(reader.metadata().get_column("hits").unwrap().has_null_value);
+        let nulls = reader.null_bitmap_reader("hits").unwrap().unwrap();
+        assert_eq!(nulls.null_count(), 2);
+        assert_eq!(
+            nulls.get_non_null_doc_ids().len(),
+            reader.total_docs() as u64 - 2
+        );
+        assert!(reader.has_valid_doc_ids());
+        assert_eq!(reader.read_valid_doc_ids().unwrap(), Some(vec![0, 1, 99]));
+
+        let dir = tempfile::tempdir().unwrap();
+        hits_segment(dir.path(), |b| b);
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        assert_eq!(reader.read_null_doc_ids("hits").unwrap(), None);
+        assert_eq!(reader.read_valid_doc_ids().unwrap(), None);
+    }
+
+    #[test]
+    fn test_has_index() {
+        let dir = tempfile::tempdir().unwrap();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .int_column("hits", vec![1, 2, 3, 4])
+            .int_column("runs", vec![5, 6, 7, 8])
+            .range_index("hits", 2)
+            .null_values("runs", vec![1])
+            .write(dir.path())
+            .unwrap();
+        // Indexes this crate cannot read are still listed in the index map
+        let mut index_map = std::fs::read_to_string(dir.path().join("index_map")).unwrap();
+        index_map.push_str("hits.inverted_index.startOffset=0\nhits.inverted_index.size=0\n");
+        index_map.push_str("runs.bloom_filter.startOffset=0\nruns.bloom_filter.size=0\n");
+        std::fs::write(dir.path().join("index_map"), index_map).unwrap();
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        assert!(reader.has_index("hits", "forward_index"));
+        assert!(reader.has_index("hits", "dictionary"));
+        assert!(reader.has_range_index("hits"));
+        assert!(reader.has_inverted_index("hits"));
+        assert!(!reader.has_bloom_filter("hits"));
+        assert!(!reader.has_null_value_vector("hits"));
+
+        assert!(!reader.has_range_index("runs"));
+        assert!(!reader.has_inverted_index("runs"));
+        assert!(reader.has_bloom_filter("runs"));
+        assert!(reader.has_null_value_vector("runs"));
+
+        assert!(!reader.has_index("missing", "forward_index"));
+        assert!(!reader.has_index("hits", "json_index"));
+    }
+
+    #[test]
+    fn test_range_index_matches_brute_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let values: Vec<f64> = (0..200).map(|i| ((i * 37) % 101) as f64 / 4.0).collect();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .int_column("hits", (0..200).map(|i| (i * 7919) % 150 - 20).collect())
+            .double_column("avg", values)
+            .range_index("hits", 6)
+            .range_index("avg", 4)
+            .write(dir.path())
+            .unwrap();
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        assert!(reader.has_range_index("hits"));
+        let hits = reader.read_int_column("hits").unwrap();
+        let avg = reader.read_double_column("avg").unwrap();
+
+        let ranges = [
+            ValueRange::between(RangeValue::Long(10), RangeValue::Long(20)),
+            ValueRange::between(RangeValue::Long(-100), RangeValue::Long(-19)),
+            ValueRange::between(RangeValue::Long(500), RangeValue::Long(600)),
+            ValueRange::new(Bound::Excluded(RangeValue::Long(64)), Bound::Unbounded),
+            ValueRange::new(Bound::Unbounded, Bound::Excluded(RangeValue::Double(3.5))),
+            ValueRange::default(),
+        ];
+        for range in &ranges {
+            let expected: Vec<u32> = (0..hits.len() as u32)
+                .filter(|&doc| range.contains(RangeValue::Long(hits[doc as usize] as i64)))
+                .collect();
+            assert_eq!(
+                reader.range_doc_ids("hits", range).unwrap(),
+                Some(expected),
+                "{:?}",
+                range
+            );
+
+            let expected: Vec<u32> = (0..avg.len() as u32)
+                .filter(|&doc| range.contains(RangeValue::Double(avg[doc as usize])))
+                .collect();
+            assert_eq!(
+                reader.range_doc_ids("avg", range).unwrap(),
+                Some(expected),
+                "{:?}",
+                range
+            );
+        }
+
+        // Columns without a range index fall back to a scan
+        let dir = tempfile::tempdir().unwrap();
+        hits_segment(dir.path(), |b| b);
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        assert!(!reader.has_range_index("hits"));
+        assert_eq!(reader.range_doc_ids("hits", &ranges[0]).unwrap(), None);
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn test_compressed_dictionary_region() {
+        let dir = tempfile::tempdir().unwrap();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .int_column("hits", vec![7, -2, 7, 40])
+            .string_column("team", vec!["BOS", "NYA", "BOS", "CHN"])
+            .compress_dictionaries()
+            .write(dir.path())
+            .unwrap();
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        let dict = reader.index_map.get_dictionary("team").unwrap();
+        assert!(dict.is_compressed());
+
+        assert_eq!(reader.read_int_column("hits").unwrap(), vec![7, -2, 7, 40]);
+        let teams = reader.read_string_column("team").unwrap();
+        assert_eq!(teams, vec!["BOS", "NYA", "BOS", "CHN"]);
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn test_unsupported_region_compression() {
+        let dir = tempfile::tempdir().unwrap();
+        hits_segment(dir.path(), |b| b.compress_dictionaries());
+
+        let index_map_path = dir.path().join("index_map");
+        let index_map = std::fs::read_to_string(&index_map_path).unwrap();
+        std::fs::write(&index_map_path, index_map.replace("=LZ4", "=SNAPPY")).unwrap();
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        let err = reader.read_int_column("hits").unwrap_err();
+        assert!(matches!(err, Error::UnsupportedFeature(_)), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_read_int_column_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let values: Vec<i32> = (0..1000).map(|i| (i * 31) % 101).collect();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .int_column("hits", values.clone())
+            .write(dir.path())
+            .unwrap();
+        let reader = SegmentReader::open(dir.path()).unwrap();
+
+        // At 7 bits per value windows start and end mid-byte; some run past the end
+        for (start, count) in [(0, 10), (5, 3), (333, 100), (990, 50), (1000, 1), (2000, 5)] {
+            let end = (start + count).min(1000) as usize;
+            let start_idx = (start as usize).min(end);
+            assert_eq!(
+                reader.read_int_column_range("hits", start, count).unwrap(),
+                values[start_idx..end],
+                "window {}+{}",
+                start,
+                count
+            );
+        }
+    }
+
+    #[test]
+    fn test_sampled_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let hits: Vec<i32> = (0..1000).map(|i| (i * 31) % 101).collect();
+        let teams: Vec<String> = (0..1000).map(|i| format!("team{}", i % 7)).collect();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .int_column("hits", hits.clone())
+            .long_column("ts", (0..1000).map(|i| i as i64 * 1000).collect())
+            .string_column("team", teams.iter().map(String::as_str).collect())
+            .write(dir.path())
+            .unwrap();
+        let reader = SegmentReader::open(dir.path()).unwrap();
+
+        let doc_ids = reader.sample_doc_ids(0.1, 42).unwrap();
+        assert_eq!(doc_ids.len(), 100);
+        assert!(doc_ids.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(reader.sample_doc_ids(0.1, 42).unwrap(), doc_ids);
+        assert_ne!(reader.sample_doc_ids(0.1, 7).unwrap(), doc_ids);
+
+        // The same seed picks the same rows in every column
+        let sampled_hits = reader.read_int_column_sampled("hits", 0.1, 42).unwrap();
+        let sampled_ts = reader.read_long_column_sampled("ts", 0.1, 42).unwrap();
+        let sampled_teams = reader.read_string_column_sampled("team", 0.1, 42).unwrap();
+        for (i, &doc_id) in doc_ids.iter().enumerate() {
+            assert_eq!(sampled_hits[i], hits[doc_id as usize]);
+            assert_eq!(sampled_ts[i], doc_id as i64 * 1000);
+            assert_eq!(sampled_teams[i], teams[doc_id as usize]);
+        }
+
+        assert!(reader.read_int_column_sampled("hits", 0.0, 42).unwrap().is_empty());
+        assert_eq!(reader.read_int_column_sampled("hits", 1.0, 42).unwrap(), hits);
+        assert!(reader.read_int_column_sampled("hits", 1.5, 42).is_err());
+        assert!(reader.read_int_column_sampled("hits", f64::NAN, 42).is_err());
+        assert!(reader.read_long_column_sampled("hits", 0.1, 42).is_err());
+    }
+
+    #[test]
+    fn test_read_column_dispatches_on_type() {
+        let dir = tempfile::tempdir().unwrap();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .int_column("hits", vec![3, 1, 2])
+            .long_column("ts", vec![30, 10, 20])
+            .double_column("score", vec![0.5, 1.5, 2.5])
+            .string_column("team", vec!["SF", "NY", "SF"])
+            .write(dir.path())
+            .unwrap();
+        let reader = SegmentReader::open(dir.path()).unwrap();
+
+        assert_eq!(reader.read_column("hits").unwrap(), ColumnData::Int(vec![3, 1, 2]));
+        assert_eq!(reader.read_column("ts").unwrap(), ColumnData::Long(vec![30, 10, 20]));
+        assert_eq!(
+            reader.read_column("score").unwrap(),
+            ColumnData::Double(vec![0.5, 1.5, 2.5])
+        );
+        let team = reader.read_column("team").unwrap();
+        assert_eq!(team.data_type(), DataType::String);
+        assert_eq!(team.len(), 3);
+        assert_eq!(
+            team,
+            ColumnData::String(vec!["SF".to_string(), "NY".to_string(), "SF".to_string()])
+        );
+        assert!(matches!(
+            reader.read_column("missing"),
+            Err(Error::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_multi_value_columns_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        hits_segment(dir.path(), |b| {
+            b.property("column.hits.isSingleValue", "false")
+                .property("column.hits.maxNumberOfMultiValues", "3")
+                .range_index("hits", 2)
+        });
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        assert!(reader.metadata().get_column("hits").unwrap().is_multi_value);
+        let err = reader.read_int_column("hits").unwrap_err();
+        assert!(matches!(err, Error::UnsupportedFeature(_)), "unexpected error: {}", err);
+        assert!(err.to_string().contains("Multi-value"), "{}", err);
+        assert!(reader.read_int_column_range("hits", 0, 10).is_err());
+        assert!(reader.read_column("hits").is_err());
+        // The range index of a multi-value column is not used for pruning
+        let range = ValueRange::new(Bound::Included(RangeValue::Long(0)), Bound::Unbounded);
+        assert_eq!(reader.range_doc_ids("hits", &range).unwrap(), None);
+    }
+
+    #[test]
+    fn test_check_column_readable() {
+        let dir = tempfile::tempdir().unwrap();
+        SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+            .int_column("hits", vec![3, 1, 2])
+            .string_column("team", vec!["SF", "NY", "SF"])
+            .int_column("flag", vec![0, 1, 0])
+            .int_column("raw", vec![7, 8, 9])
+            .int_column("tags", vec![1, 2, 3])
+            .property("column.flag.dataType", "BOOLEAN")
+            .property("column.raw.hasDictionary", "false")
+            .property("column.tags.isSingleValue", "false")
+            .write(dir.path())
+            .unwrap();
+        let reader = SegmentReader::open(dir.path()).unwrap();
+
+        assert!(reader.check_column_readable("hits").is_ok());
+        assert!(reader.check_column_readable("team").is_ok());
+        for (column, message) in [("flag", "Boolean"), ("raw", "RAW Int"), ("tags", "Multi-value")] {
+            let err = reader.check_column_readable(column).unwrap_err();
+            assert!(matches!(err, Error::UnsupportedFeature(_)), "unexpected error: {}", err);
+            assert!(err.to_string().contains(message), "{}", err);
+            assert!(reader.read_column(column).is_err());
+        }
+        assert!(matches!(
+            reader.check_column_readable("missing"),
+            Err(Error::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_front_coded_dictionary_matches_plain() {
+        let teams = vec!["SFG", "SFN", "NYA", "NYN", "SFG", "BOS", "NYA", "BAL"];
+        let write = |dir: &Path, front_coded: bool| {
+            let mut builder = SegmentBuilder::new("testTable", "testTable_OFFLINE_0")
+                .string_column("team", teams.clone())
+                .bytes_column("tag", teams.iter().map(|t| t.as_bytes().to_vec()).collect());
+            if front_coded {
+                builder = builder.front_coded_dictionaries(4);
+            }
+            builder.write(dir).unwrap();
+            SegmentReader::open(dir).unwrap()
+        };
+        let plain_dir = tempfile::tempdir().unwrap();
+        let front_coded_dir = tempfile::tempdir().unwrap();
+        let plain = write(plain_dir.path(), false);
+        let front_coded = write(front_coded_dir.path(), true);
+
+        let team = front_coded.metadata().get_column("team").unwrap();
+        assert!(team.front_coded_dictionary);
+        assert!(front_coded.check_column_readable("team").is_ok());
+        let dictionary = |reader: &SegmentReader, column: &str| {
+            let col_meta = reader.metadata().get_column(column).unwrap();
+            reader.read_dictionary(column, col_meta).unwrap()
+        };
+        assert_eq!(
+            dictionary(&front_coded, "team").string_values().unwrap(),
+            dictionary(&plain, "team").string_values().unwrap()
+        );
+        assert_eq!(
+            front_coded.read_string_column("team").unwrap(),
+            plain.read_string_column("team").unwrap()
+        );
+        assert_eq!(
+            front_coded.read_column("tag").unwrap(),
+            plain.read_column("tag").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_truncated_columns_file() {
+        let dir = tempfile::tempdir().unwrap();
+        hits_segment(dir.path(), |b| b);
+
+        let psf_path = dir.path().join("columns.psf");
+        let psf = std::fs::read(&psf_path).unwrap();
+        std::fs::write(&psf_path, &psf[..psf.len() - 1]).unwrap();
+
+        let err = SegmentReader::open(dir.path()).unwrap_err();
+        assert!(matches!(err, Error::InvalidFormat(_)), "unexpected error: {}", err);
+        assert!(err.to_string().contains("truncated"), "{}", err);
+    }
 }
@@ -0,0 +1,844 @@
+//! Star-tree index reader
+//!
+//! A star-tree pre-aggregates metric columns over combinations of dimension
+//! columns. Each tree node splits its documents on one dimension: one child
+//! per dictionary id, plus a star child whose documents have that dimension
+//! aggregated away. An aggregation grouped by a subset of the dimensions then
+//! only reads the documents under the star children of every other dimension,
+//! typically a few rows instead of the whole segment.
+//!
+//! Pinot writes the star-trees of a segment to `star_tree_index`, next to
+//! columns.psf, and locates their parts through `star_tree_index_map` (see
+//! [`StarTreeIndexMap`](crate::StarTreeIndexMap)). metadata.properties lists
+//! them under `startree.<id>.` (see [`StarTreeMetadata`]). Each tree has:
+//! - the tree itself, written little-endian: magic marker u64
+//!   (`0xBADDA55B00DAD00D`), version i32 (1), header size i32, number of
+//!   dimensions i32, then per dimension its id, name length and UTF-8 name,
+//!   and the number of nodes i32. From the header size on come the nodes in
+//!   breadth-first order, 7 i32 each: dimension id, dimension value (dict id,
+//!   or -1 for star nodes and the root), start doc, end doc (exclusive),
+//!   aggregated doc (-1 if none), first and last child (-1 for leaves)
+//! - per dimension, a fixed-bit forward index of the dict ids of the
+//!   star-tree documents, without the magic marker of columns.psf regions;
+//!   documents with the dimension aggregated away hold a placeholder
+//! - per function column pair, e.g. `sum__clicks` or `count__*`, a fixed-byte
+//!   chunk forward index of the pre-aggregated values: LONG for `COUNT`,
+//!   DOUBLE for the others
+//!
+//! [`StarTreeMetadata`]: crate::StarTreeMetadata
+
+use crate::error::{Error, Result};
+use crate::forward_index::{DictionaryReader, FixedBitWidthReader};
+use crate::metadata::{DataType, StarTreeMetadata};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+pub(crate) const MAGIC_MARKER: u64 = 0xBADDA55B00DAD00D;
+pub(crate) const SUPPORTED_VERSION: i32 = 1;
+/// Dimension value of star nodes and of the root
+pub(crate) const STAR: i32 = -1;
+/// Bytes per serialized node
+const NODE_SIZE: usize = 28;
+/// File holding every star-tree of a segment
+pub(crate) const STAR_TREE_INDEX_FILE: &str = "star_tree_index";
+/// File locating the parts of each star-tree inside [`STAR_TREE_INDEX_FILE`]
+pub(crate) const STAR_TREE_INDEX_MAP_FILE: &str = "star_tree_index_map";
+
+/// Aggregation pre-computed by a star-tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AggFn {
+    Sum,
+    /// Number of documents; the metric column is ignored
+    Count,
+    Min,
+    Max,
+}
+
+impl AggFn {
+    /// Function column pair holding this aggregation of `column`, e.g.
+    /// `sum__hits`; always `count__*` for [`AggFn::Count`]
+    pub fn metric_name(&self, column: &str) -> String {
+        match self {
+            AggFn::Sum => format!("sum__{}", column),
+            AggFn::Count => "count__*".to_string(),
+            AggFn::Min => format!("min__{}", column),
+            AggFn::Max => format!("max__{}", column),
+        }
+    }
+
+    /// Aggregation of a function column pair `<function>__<column>`; `None`
+    /// for functions other than `sum`, `count`, `min` and `max`
+    pub fn from_metric_name(name: &str) -> Option<Self> {
+        match name.split_once("__")?.0 {
+            "sum" => Some(AggFn::Sum),
+            "count" => Some(AggFn::Count),
+            "min" => Some(AggFn::Min),
+            "max" => Some(AggFn::Max),
+            _ => None,
+        }
+    }
+
+    /// Combine two partial aggregates
+    pub fn merge(&self, a: f64, b: f64) -> f64 {
+        match self {
+            AggFn::Sum | AggFn::Count => a + b,
+            AggFn::Min => a.min(b),
+            AggFn::Max => a.max(b),
+        }
+    }
+}
+
+/// Value of a dimension column, looked up in its dictionary
+///
+/// Floating point values compare and hash by bit pattern, so values can key
+/// the groups of results merged across segments.
+#[derive(Debug, Clone)]
+pub enum ColumnValue {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+impl PartialEq for ColumnValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ColumnValue::Int(a), ColumnValue::Int(b)) => a == b,
+            (ColumnValue::Long(a), ColumnValue::Long(b)) => a == b,
+            (ColumnValue::Float(a), ColumnValue::Float(b)) => a.to_bits() == b.to_bits(),
+            (ColumnValue::Double(a), ColumnValue::Double(b)) => a.to_bits() == b.to_bits(),
+            (ColumnValue::String(a), ColumnValue::String(b)) => a == b,
+            (ColumnValue::Bytes(a), ColumnValue::Bytes(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ColumnValue {}
+
+impl Hash for ColumnValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            ColumnValue::Int(v) => v.hash(state),
+            ColumnValue::Long(v) => v.hash(state),
+            ColumnValue::Float(v) => v.to_bits().hash(state),
+            ColumnValue::Double(v) => v.to_bits().hash(state),
+            ColumnValue::String(v) => v.hash(state),
+            ColumnValue::Bytes(v) => v.hash(state),
+        }
+    }
+}
+
+/// Dictionary entry `dict_id` as a [`ColumnValue`]
+fn dictionary_value(dictionary: &DictionaryReader, dict_id: u32) -> Option<ColumnValue> {
+    match dictionary.data_type() {
+        DataType::Int => dictionary.get_int(dict_id).map(ColumnValue::Int),
+        DataType::Long => dictionary.get_long(dict_id).map(ColumnValue::Long),
+        DataType::Float => dictionary.get_float(dict_id).map(ColumnValue::Float),
+        DataType::Double => dictionary.get_double(dict_id).map(ColumnValue::Double),
+        DataType::String => dictionary
+            .get_string(dict_id)
+            .map(|v| ColumnValue::String(v.to_string())),
+        DataType::Bytes => dictionary
+            .get_bytes(dict_id)
+            .map(|v| ColumnValue::Bytes(v.to_vec())),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StarTreeNode {
+    dimension_id: i32,
+    dimension_value: i32,
+    start_doc: u32,
+    end_doc: u32,
+    aggregated_doc: i32,
+    first_child: i32,
+    last_child: i32,
+}
+
+impl StarTreeNode {
+    fn children(&self) -> Option<std::ops::RangeInclusive<usize>> {
+        (self.first_child >= 0).then_some(self.first_child as usize..=self.last_child as usize)
+    }
+}
+
+/// Forward index and dictionary of a star-tree dimension
+struct Dimension {
+    dict_ids: FixedBitWidthReader,
+    dictionary: DictionaryReader,
+}
+
+/// One star-tree of a segment
+///
+/// [`crate::SegmentReader::star_tree_indexes`] loads a segment's trees
+/// along with the dimension dictionaries and metric values they need.
+pub struct StarTreeIndexReader {
+    /// Dimension columns, in split order
+    dimensions: Vec<String>,
+    /// Every function column pair of the tree
+    metrics: Vec<String>,
+    nodes: Vec<StarTreeNode>,
+    num_docs: u32,
+    /// Loaded dimensions, keyed by column
+    dimension_columns: HashMap<String, Dimension>,
+    /// Values of the loaded function column pairs, one per document
+    metric_values: HashMap<String, Vec<f64>>,
+}
+
+impl fmt::Debug for StarTreeIndexReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StarTreeIndexReader")
+            .field("dimensions", &self.dimensions)
+            .field("metrics", &self.metrics)
+            .field("num_nodes", &self.nodes.len())
+            .field("num_docs", &self.num_docs)
+            .finish()
+    }
+}
+
+impl StarTreeIndexReader {
+    /// Parse the tree region of the star-tree `metadata` describes
+    ///
+    /// The dimensions stored in the tree must match the split order, and its
+    /// nodes must stay within the `metadata.total_docs` documents.
+    pub(crate) fn new(tree: &[u8], metadata: &StarTreeMetadata) -> Result<Self> {
+        let mut cursor = ByteCursor {
+            data: tree,
+            offset: 0,
+        };
+        let magic = u64::from_le_bytes(cursor.take(8)?.try_into().unwrap());
+        if magic != MAGIC_MARKER {
+            return Err(Error::InvalidFormat(format!(
+                "Invalid star-tree magic marker: expected 0x{:X}, got 0x{:X}",
+                MAGIC_MARKER, magic
+            )));
+        }
+        let version = cursor.read_i32()?;
+        if version != SUPPORTED_VERSION {
+            return Err(Error::UnsupportedFeature(format!(
+                "Star-tree version {} not supported",
+                version
+            )));
+        }
+        let header_size = cursor.read_len()?;
+
+        let num_dimensions = cursor.read_len()?;
+        let mut dimensions = vec![String::new(); num_dimensions];
+        for _ in 0..num_dimensions {
+            let dimension_id = cursor.read_len()?;
+            let len = cursor.read_len()?;
+            let name = String::from_utf8(cursor.take(len)?.to_vec())
+                .map_err(|e| Error::Parse(format!("Invalid star-tree dimension name: {}", e)))?;
+            let slot = dimensions.get_mut(dimension_id).ok_or_else(|| {
+                Error::InvalidFormat(format!(
+                    "Star-tree dimension id {} out of range ({} dimensions)",
+                    dimension_id, num_dimensions
+                ))
+            })?;
+            *slot = name;
+        }
+        if dimensions != metadata.dimensions {
+            return Err(Error::InvalidFormat(format!(
+                "Star-tree dimensions {:?} differ from split order {:?}",
+                dimensions, metadata.dimensions
+            )));
+        }
+
+        let num_nodes = cursor.read_len()?;
+        if cursor.offset != header_size {
+            return Err(Error::InvalidFormat(format!(
+                "Star-tree header is {} bytes, expected {}",
+                cursor.offset, header_size
+            )));
+        }
+        let nodes = (0..num_nodes)
+            .map(|_| {
+                let mut fields = [0i32; NODE_SIZE / 4];
+                for field in &mut fields {
+                    *field = cursor.read_i32()?;
+                }
+                let [dimension_id, dimension_value, start, end, aggregated_doc, first_child, last_child] =
+                    fields;
+                Ok(StarTreeNode {
+                    dimension_id,
+                    dimension_value,
+                    start_doc: start as u32,
+                    end_doc: end as u32,
+                    aggregated_doc,
+                    first_child,
+                    last_child,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if nodes.is_empty() {
+            return Err(Error::InvalidFormat(
+                "Star-tree has no root node".to_string(),
+            ));
+        }
+        check_nodes(&nodes, num_dimensions, metadata.total_docs)?;
+
+        Ok(StarTreeIndexReader {
+            dimensions,
+            metrics: metadata.function_column_pairs.clone(),
+            nodes,
+            num_docs: metadata.total_docs,
+            dimension_columns: HashMap::new(),
+            metric_values: HashMap::new(),
+        })
+    }
+
+    /// Attach the star-tree forward index and dictionary of dimension `column`
+    pub(crate) fn with_dimension(
+        mut self,
+        column: &str,
+        dict_ids: FixedBitWidthReader,
+        dictionary: DictionaryReader,
+    ) -> Self {
+        let dimension = Dimension {
+            dict_ids,
+            dictionary,
+        };
+        self.dimension_columns.insert(column.to_string(), dimension);
+        self
+    }
+
+    /// Attach the values of function column pair `metric`, one per document
+    pub(crate) fn with_metric(mut self, metric: &str, values: Vec<f64>) -> Self {
+        self.metric_values.insert(metric.to_string(), values);
+        self
+    }
+
+    /// Dimension columns, in split order
+    pub fn dimensions(&self) -> &[String] {
+        &self.dimensions
+    }
+
+    /// Pre-aggregated metrics as function column pairs, e.g. `sum__hits`,
+    /// including ones [`Self::query`] cannot answer
+    pub fn metrics(&self) -> &[String] {
+        &self.metrics
+    }
+
+    /// Documents of the tree, pre-aggregated ones included
+    pub fn num_docs(&self) -> u32 {
+        self.num_docs
+    }
+
+    /// Whether [`Self::query`] can answer `aggregation(metric_column)`
+    /// grouped by `group_by_columns`
+    pub fn supports(
+        &self,
+        group_by_columns: &[&str],
+        metric_column: &str,
+        aggregation: AggFn,
+    ) -> bool {
+        group_by_columns
+            .iter()
+            .all(|column| self.dimension_columns.contains_key(*column))
+            && self
+                .metric_values
+                .contains_key(&aggregation.metric_name(metric_column))
+    }
+
+    /// `aggregation(metric_column)` grouped by `group_by_columns`, from the
+    /// pre-aggregated documents
+    ///
+    /// Returns one row per group: the group's values, in `group_by_columns`
+    /// order, and the aggregate. Rows are ordered by the dictionary ids of
+    /// the group values, so by value. Without group-by columns the result is
+    /// a single row, or none for an empty tree.
+    ///
+    /// # Errors
+    /// Returns error if a group-by column is not a dimension of the tree or
+    /// the tree does not pre-aggregate `aggregation` over `metric_column`.
+    pub fn query(
+        &self,
+        group_by_columns: &[&str],
+        metric_column: &str,
+        aggregation: AggFn,
+    ) -> Result<Vec<(Vec<ColumnValue>, f64)>> {
+        let group_dims = group_by_columns
+            .iter()
+            .map(|column| {
+                self.dimensions
+                    .iter()
+                    .position(|d| d == column)
+                    .filter(|_| self.dimension_columns.contains_key(*column))
+                    .ok_or_else(|| {
+                        Error::ColumnNotFound(format!("{} is not a star-tree dimension", column))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let metric_name = aggregation.metric_name(metric_column);
+        let values = self.metric_values.get(&metric_name).ok_or_else(|| {
+            Error::UnsupportedFeature(format!("Star-tree has no {} metric", metric_name))
+        })?;
+        let group_columns: Vec<&Dimension> = group_by_columns
+            .iter()
+            .map(|column| &self.dimension_columns[*column])
+            .collect();
+
+        let mut groups: BTreeMap<Vec<u32>, f64> = BTreeMap::new();
+        for docs in self.matching_docs(&group_dims) {
+            let dict_ids = group_columns
+                .iter()
+                .map(|column| column.dict_ids.read_range(docs.start, docs.len() as u32))
+                .collect::<Result<Vec<_>>>()?;
+            for (i, doc) in docs.enumerate() {
+                let key = dict_ids
+                    .iter()
+                    .map(|ids| FixedBitWidthReader::dict_index(ids[i]))
+                    .collect::<Result<Vec<_>>>()?;
+                let value = values[doc as usize];
+                groups
+                    .entry(key)
+                    .and_modify(|acc| *acc = aggregation.merge(*acc, value))
+                    .or_insert(value);
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(key, value)| {
+                let values = key
+                    .iter()
+                    .zip(group_by_columns.iter().zip(&group_columns))
+                    .map(|(&dict_id, (name, column))| {
+                        dictionary_value(&column.dictionary, dict_id).ok_or_else(|| {
+                            Error::InvalidFormat(format!(
+                                "Star-tree dict id {} out of range for dimension {}",
+                                dict_id, name
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok((values, value))
+            })
+            .collect()
+    }
+
+    /// Documents to aggregate for a query grouped by the dimensions at
+    /// `group_dims`, as Pinot's star-tree traversal picks them
+    ///
+    /// Children splitting on a dimension that is not grouped are skipped for
+    /// their star child, when there is one. Once no grouped dimension is left
+    /// to split on, a node's aggregated document stands for all of its
+    /// documents.
+    fn matching_docs(&self, group_dims: &[usize]) -> Vec<Range<u32>> {
+        let mut ranges = Vec::new();
+        let mut pending = vec![0usize];
+        while let Some(idx) = pending.pop() {
+            let node = &self.nodes[idx];
+            let Some(children) = node.children() else {
+                ranges.push(node.start_doc..node.end_doc);
+                continue;
+            };
+            let split_dim = self.nodes[*children.start()].dimension_id as usize;
+            if node.aggregated_doc >= 0 && group_dims.iter().all(|&d| d < split_dim) {
+                let doc = node.aggregated_doc as u32;
+                ranges.push(doc..doc + 1);
+                continue;
+            }
+            let star = children
+                .clone()
+                .find(|&child| self.nodes[child].dimension_value == STAR);
+            match star {
+                Some(star) if !group_dims.contains(&split_dim) => pending.push(star),
+                _ => pending
+                    .extend(children.filter(|&child| self.nodes[child].dimension_value != STAR)),
+            }
+        }
+        ranges
+    }
+}
+
+/// Check that nodes reference documents and children that exist, and that
+/// children split on one dimension below their parent's
+fn check_nodes(nodes: &[StarTreeNode], num_dimensions: usize, num_docs: u32) -> Result<()> {
+    for (idx, node) in nodes.iter().enumerate() {
+        let invalid = |what: &str| {
+            Err(Error::InvalidFormat(format!(
+                "Star-tree node {} has invalid {} ({} nodes, {} docs)",
+                idx,
+                what,
+                nodes.len(),
+                num_docs
+            )))
+        };
+        if node.start_doc > node.end_doc || node.end_doc > num_docs {
+            return invalid("documents");
+        }
+        if node.aggregated_doc < -1 || node.aggregated_doc >= num_docs as i32 {
+            return invalid("aggregated document");
+        }
+        let Some(children) = node.children() else {
+            continue;
+        };
+        if *children.start() <= idx || *children.end() >= nodes.len() || children.is_empty() {
+            return invalid("children");
+        }
+        let split_dim = nodes[*children.start()].dimension_id;
+        let splits_below = split_dim >= 0
+            && (split_dim as usize) < num_dimensions
+            && (idx == 0 || split_dim > node.dimension_id)
+            && children.clone().all(|child| {
+                nodes[child].dimension_id == split_dim && nodes[child].dimension_value >= STAR
+            });
+        if !splits_below {
+            return invalid("child dimensions");
+        }
+    }
+    Ok(())
+}
+
+/// Little-endian reader over the tree region
+struct ByteCursor<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl ByteCursor<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8]> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + len)
+            .ok_or_else(|| Error::InvalidFormat("Truncated star-tree".to_string()))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        let len = self.read_i32()?;
+        usize::try_from(len)
+            .map_err(|_| Error::InvalidFormat(format!("Negative length {} in star-tree", len)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::SegmentBuilder;
+    use crate::{SegmentReader, StarTreeIndexMap};
+
+    const COUNTRIES: [&str; 3] = ["CA", "DE", "US"];
+    const BROWSERS: [&str; 2] = ["chrome", "firefox"];
+
+    /// `(country, browser, device, clicks)` of document `doc`
+    fn row(doc: usize) -> (&'static str, &'static str, i32, i32) {
+        (
+            COUNTRIES[doc % 3],
+            BROWSERS[doc / 3 % 2],
+            (doc % 4) as i32,
+            (doc * 7 % 23) as i32,
+        )
+    }
+
+    fn events_segment(
+        dir: &std::path::Path,
+        num_docs: usize,
+        max_leaf_records: usize,
+    ) -> SegmentReader {
+        let rows: Vec<_> = (0..num_docs).map(row).collect();
+        SegmentBuilder::new("events", "events_0")
+            .string_column("country", rows.iter().map(|r| r.0).collect())
+            .string_column("browser", rows.iter().map(|r| r.1).collect())
+            .int_column("device", rows.iter().map(|r| r.2).collect())
+            .int_column("clicks", rows.iter().map(|r| r.3).collect())
+            .star_tree(
+                &["country", "browser", "device"],
+                &[(AggFn::Sum, "clicks"), (AggFn::Max, "clicks")],
+                max_leaf_records,
+            )
+            .write(dir)
+            .unwrap();
+        SegmentReader::open(dir).unwrap()
+    }
+
+    /// The group-by values of document `doc` for `columns`
+    fn group_values(doc: usize, columns: &[&str]) -> Vec<ColumnValue> {
+        let (country, browser, device, _) = row(doc);
+        columns
+            .iter()
+            .map(|column| match *column {
+                "country" => ColumnValue::String(country.to_string()),
+                "browser" => ColumnValue::String(browser.to_string()),
+                _ => ColumnValue::Int(device),
+            })
+            .collect()
+    }
+
+    /// Tree region of the first star-tree of the segment in `dir`
+    fn tree_region(dir: &std::path::Path) -> Vec<u8> {
+        let index_map = StarTreeIndexMap::from_file(&dir.join(STAR_TREE_INDEX_MAP_FILE)).unwrap();
+        let loc = index_map.get_star_tree(0).unwrap();
+        let data = std::fs::read(dir.join(STAR_TREE_INDEX_FILE)).unwrap();
+        data[loc.start_offset..loc.start_offset + loc.size].to_vec()
+    }
+
+    #[test]
+    fn test_query_matches_brute_force() {
+        let num_docs = 100;
+        for max_leaf_records in [1, 4, 1000] {
+            let dir = tempfile::tempdir().unwrap();
+            let reader = events_segment(dir.path(), num_docs, max_leaf_records);
+            assert!(reader.has_star_tree_index());
+            let indexes = reader.star_tree_indexes().unwrap();
+            let [index] = indexes.as_slice() else {
+                panic!("expected one star-tree, got {:?}", indexes);
+            };
+            assert_eq!(index.dimensions(), ["country", "browser", "device"]);
+            assert_eq!(index.metrics(), ["count__*", "sum__clicks", "max__clicks"]);
+
+            let group_bys: [&[&str]; 6] = [
+                &[],
+                &["country"],
+                &["device"],
+                &["browser", "device"],
+                &["device", "country"],
+                &["country", "browser", "device"],
+            ];
+            for group_by in group_bys {
+                for aggregation in [AggFn::Count, AggFn::Sum, AggFn::Max] {
+                    let mut expected: HashMap<Vec<ColumnValue>, f64> = HashMap::new();
+                    for doc in 0..num_docs {
+                        let value = match aggregation {
+                            AggFn::Count => 1.0,
+                            _ => row(doc).3 as f64,
+                        };
+                        expected
+                            .entry(group_values(doc, group_by))
+                            .and_modify(|acc| *acc = aggregation.merge(*acc, value))
+                            .or_insert(value);
+                    }
+
+                    let rows = index.query(group_by, "clicks", aggregation).unwrap();
+                    let context = (max_leaf_records, group_by, aggregation);
+                    assert_eq!(rows.len(), expected.len(), "{:?}", context);
+                    for (values, value) in rows {
+                        assert_eq!(
+                            expected.get(&values),
+                            Some(&value),
+                            "{:?} {:?}",
+                            context,
+                            values
+                        );
+                    }
+                }
+            }
+
+            // Rows come ordered by value
+            let rows = index.query(&["country"], "clicks", AggFn::Count).unwrap();
+            let countries: Vec<ColumnValue> =
+                rows.into_iter().flat_map(|(values, _)| values).collect();
+            assert_eq!(
+                countries,
+                COUNTRIES.map(|c| ColumnValue::String(c.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_star_nodes_skip_ungrouped_dimensions() {
+        let dir = tempfile::tempdir().unwrap();
+        let reader = events_segment(dir.path(), 1000, 1);
+        let index = reader.star_tree_indexes().unwrap().remove(0);
+        let docs = |group_dims: &[usize]| -> usize {
+            index
+                .matching_docs(group_dims)
+                .iter()
+                .map(|r| r.len())
+                .sum()
+        };
+
+        // Grouping by nothing reads the root's aggregated document, grouping
+        // by country one document per country, and grouping by browser the
+        // star node over every country
+        assert_eq!(docs(&[]), 1);
+        assert_eq!(docs(&[0]), 3);
+        assert_eq!(docs(&[1]), 2);
+        let combinations: std::collections::HashSet<_> = (0..1000)
+            .map(|doc| {
+                let (country, browser, device, _) = row(doc);
+                (country, browser, device)
+            })
+            .collect();
+        assert_eq!(docs(&[0, 1, 2]), combinations.len());
+
+        let rows = index.query(&[], "clicks", AggFn::Count).unwrap();
+        assert_eq!(rows, vec![(Vec::new(), 1000.0)]);
+    }
+
+    #[test]
+    fn test_unsupported_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let reader = events_segment(dir.path(), 10, 4);
+        let index = reader.star_tree_indexes().unwrap().remove(0);
+
+        assert!(index.supports(&["country"], "clicks", AggFn::Sum));
+        assert!(index.supports(&[], "*", AggFn::Count));
+        assert!(!index.supports(&["country"], "clicks", AggFn::Min));
+        assert!(!index.supports(&["clicks"], "clicks", AggFn::Sum));
+        assert!(matches!(
+            index.query(&["clicks"], "clicks", AggFn::Sum),
+            Err(Error::ColumnNotFound(_))
+        ));
+        assert!(matches!(
+            index.query(&["country"], "device", AggFn::Sum),
+            Err(Error::UnsupportedFeature(_))
+        ));
+
+        let plain = tempfile::tempdir().unwrap();
+        SegmentBuilder::new("events", "events_1")
+            .int_column("clicks", vec![1, 2])
+            .write(plain.path())
+            .unwrap();
+        let reader = SegmentReader::open(plain.path()).unwrap();
+        assert!(!reader.has_star_tree_index());
+        assert!(reader.star_tree_indexes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_other_functions_are_listed_but_not_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        events_segment(dir.path(), 10, 4);
+        // A pair this reader cannot aggregate, e.g. a serialized HLL, is
+        // listed without a forward index being read for it
+        let properties = std::fs::read_to_string(dir.path().join("metadata.properties")).unwrap();
+        let properties = properties.replace(
+            "count__*,sum__clicks,max__clicks",
+            "count__*,sum__clicks,max__clicks,distinctCountHLL__device",
+        );
+        std::fs::write(dir.path().join("metadata.properties"), properties).unwrap();
+
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        let index = reader.star_tree_indexes().unwrap().remove(0);
+        assert_eq!(index.metrics().last().unwrap(), "distinctCountHLL__device");
+        assert!(index.supports(&["country"], "clicks", AggFn::Max));
+    }
+
+    #[test]
+    fn test_parse_tree_layout() {
+        // Dimension `d` with dict ids 0 and 1: the root splits on it into a
+        // star node and one node per value, documents 0-1 hold the values
+        // and document 2 both aggregated
+        let mut tree = MAGIC_MARKER.to_le_bytes().to_vec();
+        let header_size: i32 = 8 + 4 + 4 + 4 + (4 + 4 + 1) + 4;
+        for field in [1, header_size, 1, 0, 1] {
+            tree.extend_from_slice(&field.to_le_bytes());
+        }
+        tree.push(b'd');
+        tree.extend_from_slice(&4i32.to_le_bytes());
+        let nodes: [[i32; 7]; 4] = [
+            [-1, STAR, 0, 2, 2, 1, 3],
+            [0, STAR, 2, 3, 2, -1, -1],
+            [0, 0, 0, 1, 0, -1, -1],
+            [0, 1, 1, 2, 1, -1, -1],
+        ];
+        for field in nodes.iter().flatten() {
+            tree.extend_from_slice(&field.to_le_bytes());
+        }
+        let metadata = StarTreeMetadata {
+            dimensions: vec!["d".to_string()],
+            function_column_pairs: vec!["count__*".to_string()],
+            total_docs: 3,
+            max_leaf_records: None,
+        };
+
+        let index = StarTreeIndexReader::new(&tree, &metadata).unwrap();
+        assert_eq!(index.dimensions(), ["d"]);
+        assert_eq!(
+            index.nodes[3],
+            StarTreeNode {
+                dimension_id: 0,
+                dimension_value: 1,
+                start_doc: 1,
+                end_doc: 2,
+                aggregated_doc: 1,
+                first_child: -1,
+                last_child: -1,
+            }
+        );
+        assert_eq!(index.matching_docs(&[]), vec![2..3]);
+        let mut grouped = index.matching_docs(&[0]);
+        grouped.sort_by_key(|docs| docs.start);
+        assert_eq!(grouped, vec![0..1, 1..2]);
+
+        // The split order in metadata must name the same dimensions
+        let renamed = StarTreeMetadata {
+            dimensions: vec!["e".to_string()],
+            ..metadata.clone()
+        };
+        assert!(matches!(
+            StarTreeIndexReader::new(&tree, &renamed),
+            Err(Error::InvalidFormat(_))
+        ));
+        // Documents past total.docs
+        let fewer_docs = StarTreeMetadata {
+            total_docs: 2,
+            ..metadata
+        };
+        assert!(matches!(
+            StarTreeIndexReader::new(&tree, &fewer_docs),
+            Err(Error::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_bad_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let reader = events_segment(dir.path(), 10, 1);
+        let metadata = &reader.metadata().star_trees[0];
+        let region = tree_region(dir.path());
+        assert!(StarTreeIndexReader::new(&region, metadata).is_ok());
+
+        let mut bad_version = region.clone();
+        bad_version[8..12].copy_from_slice(&2i32.to_le_bytes());
+        assert!(matches!(
+            StarTreeIndexReader::new(&bad_version, metadata),
+            Err(Error::UnsupportedFeature(_))
+        ));
+        let mut bad_header_size = region.clone();
+        bad_header_size[12..16].copy_from_slice(&1000i32.to_le_bytes());
+        assert!(matches!(
+            StarTreeIndexReader::new(&bad_header_size, metadata),
+            Err(Error::InvalidFormat(_))
+        ));
+        // Big-endian trees do not start with the little-endian marker
+        let mut big_endian = region.clone();
+        big_endian[..8].copy_from_slice(&MAGIC_MARKER.to_be_bytes());
+        assert!(matches!(
+            StarTreeIndexReader::new(&big_endian, metadata),
+            Err(Error::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            StarTreeIndexReader::new(&region[..region.len() - 1], metadata),
+            Err(Error::InvalidFormat(_))
+        ));
+
+        // The root's last child pointing past the last node
+        let header_size = i32::from_le_bytes(region[12..16].try_into().unwrap()) as usize;
+        let mut bad_child = region.clone();
+        let last_child = header_size + 6 * 4;
+        bad_child[last_child..last_child + 4].copy_from_slice(&i32::MAX.to_le_bytes());
+        assert!(matches!(
+            StarTreeIndexReader::new(&bad_child, metadata),
+            Err(Error::InvalidFormat(_))
+        ));
+
+        // A missing star_tree_index fails the read, not the open
+        std::fs::remove_file(dir.path().join(STAR_TREE_INDEX_FILE)).unwrap();
+        let reader = SegmentReader::open(dir.path()).unwrap();
+        assert!(reader.has_star_tree_index());
+        assert!(reader.star_tree_indexes().unwrap_err().is_not_found());
+    }
+}
@@ -0,0 +1,1293 @@
+//! Helpers for writing small synthetic v3 segments
+//!
+//! Only available with the `testutil` feature (or inside this crate's own tests).
+//! The produced files follow the same byte layout the readers in this crate
+//! expect, so tests can exercise the real decode paths without a Pinot install.
+
+use crate::error::Result;
+use crate::star_tree::{self, AggFn};
+use roaring::RoaringBitmap;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::Path;
+
+const MAGIC_MARKER: u64 = 0xDEADBEEFDEAFBEAD;
+
+/// Values of a single dictionary-encoded column
+#[derive(Debug, Clone)]
+pub enum TestColumnValues {
+    Int(Vec<i32>),
+    Long(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    String(Vec<String>),
+    Bytes(Vec<Vec<u8>>),
+}
+
+impl TestColumnValues {
+    fn len(&self) -> usize {
+        match self {
+            TestColumnValues::Int(v) => v.len(),
+            TestColumnValues::Long(v) => v.len(),
+            TestColumnValues::Float(v) => v.len(),
+            TestColumnValues::Double(v) => v.len(),
+            TestColumnValues::String(v) => v.len(),
+            TestColumnValues::Bytes(v) => v.len(),
+        }
+    }
+
+    fn data_type(&self) -> &'static str {
+        match self {
+            TestColumnValues::Int(_) => "INT",
+            TestColumnValues::Long(_) => "LONG",
+            TestColumnValues::Float(_) => "FLOAT",
+            TestColumnValues::Double(_) => "DOUBLE",
+            TestColumnValues::String(_) => "STRING",
+            TestColumnValues::Bytes(_) => "BYTES",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TestColumn {
+    name: String,
+    values: TestColumnValues,
+    /// Stored without a dictionary, as a var-byte forward index
+    raw: bool,
+}
+
+/// Star-tree to build over the columns of a segment
+#[derive(Debug, Clone)]
+struct TestStarTree {
+    dimensions: Vec<String>,
+    /// Pre-aggregated metrics, `count__*` first
+    metrics: Vec<(AggFn, String)>,
+    max_leaf_records: usize,
+}
+
+/// Values per chunk of the var-byte forward index of RAW columns, and of
+/// the fixed-byte forward indexes of star-tree metrics
+const RAW_DOCS_PER_CHUNK: usize = 1000;
+
+/// Encoded column: dictionary bytes, dict ids and metadata
+struct EncodedColumn {
+    dictionary: Vec<u8>,
+    /// Sorted dictionary entries of STRING and BYTES columns, for front coding
+    entries: Vec<Vec<u8>>,
+    dict_ids: Vec<u32>,
+    cardinality: u32,
+    length_of_each_entry: usize,
+    min_value: Option<String>,
+    max_value: Option<String>,
+}
+
+/// Builder for a synthetic v3 segment directory
+///
+/// # Example
+/// ```ignore
+/// let dir = tempfile::tempdir()?;
+/// SegmentBuilder::new("myTable", "myTable_OFFLINE_0")
+///     .int_column("hits", vec![3, 1, 2])
+///     .string_column("team", vec!["a", "b", "a"])
+///     .write(dir.path())?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SegmentBuilder {
+    table_name: String,
+    segment_name: String,
+    total_docs: Option<u32>,
+    columns: Vec<TestColumn>,
+    properties: BTreeMap<String, String>,
+    forward_index_padding: isize,
+    compress_dictionaries: bool,
+    /// Bucket size STRING and BYTES dictionaries are front-coded with
+    front_coding_bucket_size: Option<u8>,
+    compress_raw_chunks: bool,
+    null_doc_ids: BTreeMap<String, Vec<u32>>,
+    valid_doc_ids: Option<Vec<u32>>,
+    range_indexes: BTreeMap<String, usize>,
+    creation_meta: Option<(u64, i64)>,
+    v1_forward_indexes: bool,
+    star_trees: Vec<TestStarTree>,
+}
+
+impl SegmentBuilder {
+    pub fn new(table_name: &str, segment_name: &str) -> Self {
+        Self {
+            table_name: table_name.to_string(),
+            segment_name: segment_name.to_string(),
+            total_docs: None,
+            columns: Vec::new(),
+            properties: BTreeMap::new(),
+            forward_index_padding: 0,
+            compress_dictionaries: false,
+            front_coding_bucket_size: None,
+            compress_raw_chunks: false,
+            null_doc_ids: BTreeMap::new(),
+            valid_doc_ids: None,
+            range_indexes: BTreeMap::new(),
+            creation_meta: None,
+            v1_forward_indexes: false,
+            star_trees: Vec::new(),
+        }
+    }
+
+    /// Add a dictionary-encoded INT column
+    pub fn int_column(self, name: &str, values: Vec<i32>) -> Self {
+        self.column(name, TestColumnValues::Int(values))
+    }
+
+    /// Add a dictionary-encoded LONG column
+    pub fn long_column(self, name: &str, values: Vec<i64>) -> Self {
+        self.column(name, TestColumnValues::Long(values))
+    }
+
+    /// Add a dictionary-encoded FLOAT column
+    pub fn float_column(self, name: &str, values: Vec<f32>) -> Self {
+        self.column(name, TestColumnValues::Float(values))
+    }
+
+    /// Add a dictionary-encoded DOUBLE column
+    pub fn double_column(self, name: &str, values: Vec<f64>) -> Self {
+        self.column(name, TestColumnValues::Double(values))
+    }
+
+    /// Add a dictionary-encoded STRING column
+    pub fn string_column(self, name: &str, values: Vec<&str>) -> Self {
+        let values = values.into_iter().map(|s| s.to_string()).collect();
+        self.column(name, TestColumnValues::String(values))
+    }
+
+    /// Add a dictionary-encoded BYTES column
+    ///
+    /// Entries are fixed-length when all values have the same length, and
+    /// length-prefixed (`lengthOfEachEntry=0`) otherwise.
+    pub fn bytes_column(self, name: &str, values: Vec<Vec<u8>>) -> Self {
+        self.column(name, TestColumnValues::Bytes(values))
+    }
+
+    /// Add a dictionary-encoded column of any supported type
+    pub fn column(mut self, name: &str, values: TestColumnValues) -> Self {
+        self.columns.push(TestColumn {
+            name: name.to_string(),
+            values,
+            raw: false,
+        });
+        self
+    }
+
+    /// Add a RAW (no dictionary) STRING column, stored as an uncompressed V4
+    /// var-byte forward index
+    pub fn raw_string_column(mut self, name: &str, values: Vec<&str>) -> Self {
+        self.columns.push(TestColumn {
+            name: name.to_string(),
+            values: TestColumnValues::String(values.into_iter().map(|s| s.to_string()).collect()),
+            raw: true,
+        });
+        self
+    }
+
+    /// Add a RAW (no dictionary) BYTES column, stored like
+    /// [`raw_string_column`](Self::raw_string_column)
+    pub fn raw_bytes_column(mut self, name: &str, values: Vec<Vec<u8>>) -> Self {
+        self.columns.push(TestColumn {
+            name: name.to_string(),
+            values: TestColumnValues::Bytes(values),
+            raw: true,
+        });
+        self
+    }
+
+    /// Override `segment.total.docs` (defaults to the length of the first column)
+    pub fn total_docs(mut self, total_docs: u32) -> Self {
+        self.total_docs = Some(total_docs);
+        self
+    }
+
+    /// Set an additional metadata.properties entry (overrides generated ones)
+    pub fn property(mut self, key: &str, value: &str) -> Self {
+        self.properties.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Grow (positive) or shrink (negative) every forward index region by
+    /// the given number of bytes, to simulate corrupt or mismatched segments
+    pub fn forward_index_padding(mut self, bytes: isize) -> Self {
+        self.forward_index_padding = bytes;
+        self
+    }
+
+    /// Store every dictionary region LZ4-compressed, flagged in the index map
+    /// with `compressionType` and `uncompressedSize`
+    #[cfg(feature = "lz4")]
+    pub fn compress_dictionaries(mut self) -> Self {
+        self.compress_dictionaries = true;
+        self
+    }
+
+    /// Front-code every STRING and BYTES dictionary (version 1, sharing
+    /// prefixes with the previous entry) in buckets of `bucket_size`, a
+    /// power of two, flagged with `dictionaryType=FRONT_CODED`
+    pub fn front_coded_dictionaries(mut self, bucket_size: u8) -> Self {
+        self.front_coding_bucket_size = Some(bucket_size);
+        self
+    }
+
+    /// LZ4-compress each chunk of the var-byte forward index of RAW columns,
+    /// as Pinot's default `LZ4` chunk compression does
+    #[cfg(feature = "lz4")]
+    pub fn compress_raw_chunks(mut self) -> Self {
+        self.compress_raw_chunks = true;
+        self
+    }
+
+    /// Write a null value vector flagging `doc_ids` of `column` as null
+    ///
+    /// The column's values for those docs are written unchanged, standing in
+    /// for the default null value Pinot stores in their place.
+    pub fn null_values(mut self, column: &str, doc_ids: Vec<u32>) -> Self {
+        self.null_doc_ids.insert(column.to_string(), doc_ids);
+        self
+    }
+
+    /// Write a validDocIds snapshot marking only `doc_ids` as valid
+    pub fn valid_doc_ids(mut self, doc_ids: Vec<u32>) -> Self {
+        self.valid_doc_ids = Some(doc_ids);
+        self
+    }
+
+    /// Write a version 1 range index over the dict ids of `column`, splitting
+    /// its dictionary into `num_ranges` contiguous ranges
+    pub fn range_index(mut self, column: &str, num_ranges: usize) -> Self {
+        self.range_indexes.insert(column.to_string(), num_ranges.max(1));
+        self
+    }
+
+    /// Write fixed-bit forward indexes in the V1 layout (no magic marker,
+    /// values packed little-endian) and record `segment.index.version=v1`
+    pub fn v1_forward_indexes(mut self) -> Self {
+        self.v1_forward_indexes = true;
+        self
+    }
+
+    /// Write a `creation.meta` file holding the segment CRC and creation time
+    pub fn creation_meta(mut self, crc: u64, creation_time: i64) -> Self {
+        self.creation_meta = Some((crc, creation_time));
+        self
+    }
+
+    /// Build a star-tree splitting on the dictionary columns `dimensions`,
+    /// in that order, and pre-aggregating `count__*` plus `metrics` over
+    /// numeric dictionary columns, as Pinot's builder does: nodes with more
+    /// than `max_leaf_records` documents are split further
+    pub fn star_tree(
+        mut self,
+        dimensions: &[&str],
+        metrics: &[(AggFn, &str)],
+        max_leaf_records: usize,
+    ) -> Self {
+        let metrics = std::iter::once((AggFn::Count, "*".to_string()))
+            .chain(
+                metrics
+                    .iter()
+                    .filter(|(aggregation, _)| *aggregation != AggFn::Count)
+                    .map(|(aggregation, column)| (*aggregation, column.to_string())),
+            )
+            .collect();
+        self.star_trees.push(TestStarTree {
+            dimensions: dimensions.iter().map(|d| d.to_string()).collect(),
+            metrics,
+            max_leaf_records: max_leaf_records.max(1),
+        });
+        self
+    }
+
+    /// Write `metadata.properties`, `index_map` and `columns.psf` into `dir`,
+    /// plus `star_tree_index` and `star_tree_index_map` for star-trees
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+
+        let total_docs = self
+            .total_docs
+            .unwrap_or_else(|| self.columns.first().map(|c| c.values.len()).unwrap_or(0) as u32);
+
+        let mut properties: BTreeMap<String, String> = BTreeMap::new();
+        properties.insert("segment.name".to_string(), self.segment_name.clone());
+        properties.insert("segment.table.name".to_string(), self.table_name.clone());
+        properties.insert("segment.total.docs".to_string(), total_docs.to_string());
+        let segment_version = if self.v1_forward_indexes { "v1" } else { "v3" };
+        properties.insert("segment.index.version".to_string(), segment_version.to_string());
+        let column_names: Vec<&str> = self.columns.iter().map(|c| c.name.as_str()).collect();
+        properties.insert(
+            "segment.dimension.column.names".to_string(),
+            column_names.join(","),
+        );
+
+        let mut psf = Vec::new();
+        let mut index_map = String::new();
+        // Dict ids and cardinality of each dictionary column, for star-trees
+        let mut column_dict_ids: BTreeMap<&str, (Vec<u32>, u32)> = BTreeMap::new();
+
+        for column in &self.columns {
+            let raw_values: Option<Vec<&[u8]>> = match (column.raw, &column.values) {
+                (true, TestColumnValues::String(values)) => {
+                    Some(values.iter().map(|v| v.as_bytes()).collect())
+                }
+                (true, TestColumnValues::Bytes(values)) => {
+                    Some(values.iter().map(Vec::as_slice).collect())
+                }
+                _ => None,
+            };
+            if let Some(values) = raw_values {
+                self.write_raw_column(
+                    column,
+                    &values,
+                    total_docs,
+                    &mut psf,
+                    &mut index_map,
+                    &mut properties,
+                );
+                continue;
+            }
+
+            let encoded = encode_column(&column.values);
+            let bits_per_element = bits_for_cardinality(encoded.cardinality);
+            if !self.star_trees.is_empty() {
+                column_dict_ids.insert(
+                    &column.name,
+                    (encoded.dict_ids.clone(), encoded.cardinality),
+                );
+            }
+            let is_sorted = encoded.dict_ids.windows(2).all(|w| w[0] <= w[1]);
+
+            // Dictionary region
+            let dict_offset = psf.len();
+            let front_coding_bucket_size = self.front_coding_bucket_size.filter(|_| {
+                matches!(
+                    column.values,
+                    TestColumnValues::String(_) | TestColumnValues::Bytes(_)
+                )
+            });
+            let dict_region = match front_coding_bucket_size {
+                Some(bucket_size) => front_coded_region(&encoded.entries, bucket_size),
+                None => {
+                    let mut region = MAGIC_MARKER.to_be_bytes().to_vec();
+                    region.extend_from_slice(&encoded.dictionary);
+                    region
+                }
+            };
+            if self.compress_dictionaries {
+                psf.extend_from_slice(&compress_region(&dict_region));
+                index_map.push_str(&format!(
+                    "{}.dictionary.compressionType=LZ4\n{}.dictionary.uncompressedSize={}\n",
+                    column.name,
+                    column.name,
+                    dict_region.len()
+                ));
+            } else {
+                psf.extend_from_slice(&dict_region);
+            }
+            index_map.push_str(&format!(
+                "{}.dictionary.startOffset={}\n{}.dictionary.size={}\n",
+                column.name,
+                dict_offset,
+                column.name,
+                psf.len() - dict_offset
+            ));
+
+            // Forward index region
+            let fwd_offset = psf.len();
+            let mut packed = if self.v1_forward_indexes {
+                pack_bits_le(&encoded.dict_ids, bits_per_element)
+            } else {
+                psf.extend_from_slice(&MAGIC_MARKER.to_be_bytes());
+                pack_bits(&encoded.dict_ids, bits_per_element)
+            };
+            let padded_len = (packed.len() as isize + self.forward_index_padding).max(0) as usize;
+            packed.resize(padded_len, 0);
+            psf.extend_from_slice(&packed);
+            index_map.push_str(&format!(
+                "{}.forward_index.startOffset={}\n{}.forward_index.size={}\n",
+                column.name,
+                fwd_offset,
+                column.name,
+                psf.len() - fwd_offset
+            ));
+
+            self.write_null_vector(&column.name, &mut psf, &mut index_map);
+
+            if let Some(&num_ranges) = self.range_indexes.get(&column.name) {
+                let range_offset = psf.len();
+                psf.extend_from_slice(&range_index_region(
+                    &encoded.dict_ids,
+                    encoded.cardinality,
+                    num_ranges,
+                ));
+                index_map.push_str(&format!(
+                    "{}.range_index.startOffset={}\n{}.range_index.size={}\n",
+                    column.name,
+                    range_offset,
+                    column.name,
+                    psf.len() - range_offset
+                ));
+            }
+
+            let prefix = format!("column.{}", column.name);
+            let column_props = [
+                ("dataType", column.values.data_type().to_string()),
+                ("cardinality", encoded.cardinality.to_string()),
+                ("totalDocs", total_docs.to_string()),
+                ("bitsPerElement", bits_per_element.to_string()),
+                ("hasDictionary", "true".to_string()),
+                ("isSorted", is_sorted.to_string()),
+                (
+                    "hasNullValue",
+                    self.null_doc_ids.contains_key(&column.name).to_string(),
+                ),
+                (
+                    "lengthOfEachEntry",
+                    encoded.length_of_each_entry.to_string(),
+                ),
+            ];
+            for (suffix, value) in column_props {
+                properties.insert(format!("{}.{}", prefix, suffix), value);
+            }
+            if front_coding_bucket_size.is_some() {
+                properties.insert(
+                    format!("{}.dictionaryType", prefix),
+                    "FRONT_CODED".to_string(),
+                );
+            }
+            // Empty columns have no min/max, like segments written by Pinot
+            if let (Some(min), Some(max)) = (&encoded.min_value, &encoded.max_value) {
+                properties.insert(format!("{}.minValue", prefix), min.clone());
+                properties.insert(format!("{}.maxValue", prefix), max.clone());
+            }
+        }
+
+        if !self.star_trees.is_empty() {
+            self.write_star_trees(dir, &column_dict_ids, &mut properties)?;
+        }
+
+        for (key, value) in &self.properties {
+            properties.insert(key.clone(), value.clone());
+        }
+
+        let metadata: String = properties
+            .iter()
+            .map(|(k, v)| format!("{} = {}\n", k, v))
+            .collect();
+
+        fs::write(dir.join("metadata.properties"), metadata)?;
+        fs::write(dir.join("index_map"), index_map)?;
+        fs::write(dir.join("columns.psf"), psf)?;
+
+        if let Some(doc_ids) = &self.valid_doc_ids {
+            let mut snapshot = Vec::new();
+            serialize_bitmap(doc_ids, &mut snapshot);
+            fs::write(dir.join("validdocids.bitmap.snapshot"), snapshot)?;
+        }
+        if let Some((crc, creation_time)) = self.creation_meta {
+            let mut creation_meta = crc.to_be_bytes().to_vec();
+            creation_meta.extend_from_slice(&creation_time.to_be_bytes());
+            fs::write(dir.join("creation.meta"), creation_meta)?;
+        }
+        Ok(())
+    }
+
+    /// Append the var-byte forward index and metadata of a RAW STRING or
+    /// BYTES column
+    fn write_raw_column(
+        &self,
+        column: &TestColumn,
+        values: &[&[u8]],
+        total_docs: u32,
+        psf: &mut Vec<u8>,
+        index_map: &mut String,
+        properties: &mut BTreeMap<String, String>,
+    ) {
+        let name = column.name.as_str();
+        let fwd_offset = psf.len();
+        psf.extend_from_slice(&encode_var_byte_region(
+            values,
+            RAW_DOCS_PER_CHUNK,
+            self.compress_raw_chunks,
+        ));
+        index_map.push_str(&format!(
+            "{}.forward_index.startOffset={}\n{}.forward_index.size={}\n",
+            name,
+            fwd_offset,
+            name,
+            psf.len() - fwd_offset
+        ));
+        self.write_null_vector(name, psf, index_map);
+
+        let mut distinct: Vec<&[u8]> = values.to_vec();
+        distinct.sort();
+        distinct.dedup();
+        let prefix = format!("column.{}", name);
+        let column_props = [
+            ("dataType", column.values.data_type().to_string()),
+            ("cardinality", distinct.len().to_string()),
+            ("totalDocs", total_docs.to_string()),
+            ("hasDictionary", "false".to_string()),
+            (
+                "isSorted",
+                values.windows(2).all(|w| w[0] <= w[1]).to_string(),
+            ),
+            (
+                "hasNullValue",
+                self.null_doc_ids.contains_key(name).to_string(),
+            ),
+        ];
+        for (suffix, value) in column_props {
+            properties.insert(format!("{}.{}", prefix, suffix), value);
+        }
+    }
+
+    /// Write `star_tree_index`, `star_tree_index_map` and the `startree.`
+    /// metadata of every star-tree
+    fn write_star_trees(
+        &self,
+        dir: &Path,
+        column_dict_ids: &BTreeMap<&str, (Vec<u32>, u32)>,
+        properties: &mut BTreeMap<String, String>,
+    ) -> Result<()> {
+        let mut data = Vec::new();
+        let mut index_map = String::new();
+        let mut push_region = |key: &str, region: &[u8], data: &mut Vec<u8>| {
+            index_map.push_str(&format!(
+                "{}.OFFSET = {}\n{}.SIZE = {}\n",
+                key,
+                data.len(),
+                key,
+                region.len()
+            ));
+            data.extend_from_slice(region);
+        };
+
+        for (tree_id, tree) in self.star_trees.iter().enumerate() {
+            let dimension_ids: Vec<&[u32]> = tree
+                .dimensions
+                .iter()
+                .map(|d| column_dict_ids[d.as_str()].0.as_slice())
+                .collect();
+            let metric_values: Vec<Vec<f64>> = tree
+                .metrics
+                .iter()
+                .map(|(aggregation, column)| match aggregation {
+                    AggFn::Count => vec![1.0; dimension_ids.first().map_or(0, |ids| ids.len())],
+                    _ => self.numeric_values(column),
+                })
+                .collect();
+            let records = (0..metric_values[0].len())
+                .map(|doc| StarTreeRecord {
+                    dims: dimension_ids.iter().map(|ids| ids[doc] as i32).collect(),
+                    metrics: metric_values.iter().map(|values| values[doc]).collect(),
+                })
+                .collect();
+            let aggregations: Vec<AggFn> = tree.metrics.iter().map(|(a, _)| *a).collect();
+            let mut builder = StarTreeBuilder {
+                records: Vec::new(),
+                nodes: Vec::new(),
+                aggregations: &aggregations,
+                max_leaf_records: tree.max_leaf_records,
+            };
+            builder.build(records);
+
+            let prefix = format!("startree{}", tree_id);
+            push_region(
+                &format!("{}.STAR_TREE", prefix),
+                &builder.serialize(&tree.dimensions),
+                &mut data,
+            );
+            for (dim, dimension) in tree.dimensions.iter().enumerate() {
+                let dict_ids: Vec<u32> = builder
+                    .records
+                    .iter()
+                    .map(|record| record.dims[dim].max(0) as u32)
+                    .collect();
+                let cardinality = column_dict_ids[dimension.as_str()].1;
+                push_region(
+                    &format!("{}.{}.FORWARD_INDEX", prefix, dimension),
+                    &pack_bits(&dict_ids, bits_for_cardinality(cardinality)),
+                    &mut data,
+                );
+            }
+            for (metric, (aggregation, column)) in tree.metrics.iter().enumerate() {
+                let values: Vec<u8> = builder
+                    .records
+                    .iter()
+                    .flat_map(|record| match aggregation {
+                        AggFn::Count => (record.metrics[metric] as i64).to_be_bytes(),
+                        _ => record.metrics[metric].to_be_bytes(),
+                    })
+                    .collect();
+                push_region(
+                    &format!(
+                        "{}.{}.FORWARD_INDEX",
+                        prefix,
+                        aggregation.metric_name(column)
+                    ),
+                    &fixed_byte_region(&values, 8, RAW_DOCS_PER_CHUNK),
+                    &mut data,
+                );
+            }
+
+            let pairs: Vec<String> = tree
+                .metrics
+                .iter()
+                .map(|(aggregation, column)| aggregation.metric_name(column))
+                .collect();
+            let tree_props = [
+                ("split.order", tree.dimensions.join(",")),
+                ("function.column.pairs", pairs.join(",")),
+                ("total.docs", builder.records.len().to_string()),
+                ("max.leaf.records", tree.max_leaf_records.to_string()),
+            ];
+            for (suffix, value) in tree_props {
+                properties.insert(format!("startree.{}.{}", tree_id, suffix), value);
+            }
+        }
+        properties.insert(
+            "startree.count".to_string(),
+            self.star_trees.len().to_string(),
+        );
+
+        fs::write(dir.join(star_tree::STAR_TREE_INDEX_FILE), data)?;
+        fs::write(dir.join(star_tree::STAR_TREE_INDEX_MAP_FILE), index_map)?;
+        Ok(())
+    }
+
+    /// Values of the numeric column `name`, as star-tree metrics
+    fn numeric_values(&self, name: &str) -> Vec<f64> {
+        let column = self
+            .columns
+            .iter()
+            .find(|c| c.name == name)
+            .unwrap_or_else(|| panic!("no star-tree metric column {}", name));
+        match &column.values {
+            TestColumnValues::Int(v) => v.iter().map(|&v| v as f64).collect(),
+            TestColumnValues::Long(v) => v.iter().map(|&v| v as f64).collect(),
+            TestColumnValues::Float(v) => v.iter().map(|&v| v as f64).collect(),
+            TestColumnValues::Double(v) => v.clone(),
+            _ => panic!("star-tree metric column {} is not numeric", name),
+        }
+    }
+
+    /// Append the null value vector of `column`, if it has one
+    fn write_null_vector(&self, column: &str, psf: &mut Vec<u8>, index_map: &mut String) {
+        if let Some(doc_ids) = self.null_doc_ids.get(column) {
+            let null_offset = psf.len();
+            serialize_bitmap(doc_ids, psf);
+            index_map.push_str(&format!(
+                "{}.nullvalue_vector.startOffset={}\n{}.nullvalue_vector.size={}\n",
+                column,
+                null_offset,
+                column,
+                psf.len() - null_offset
+            ));
+        }
+    }
+}
+
+/// Teams of [`write_baseball_stats`], row `i` plays for `TEAMS[i % 8]`
+pub const BASEBALL_STATS_TEAMS: [&str; 8] =
+    ["ATL", "BOS", "CHN", "DET", "NYA", "OAK", "SFN", "TEX"];
+
+/// Write a `baseballStats`-shaped segment with `num_docs` rows to `dir`
+///
+/// Columns mirror the quickstart table so tests need no Pinot install:
+/// dictionary INT `hits` (`i * 37 % 263`) and `homeRuns` (`i % 60`),
+/// dictionary STRING `teamID` ([`BASEBALL_STATS_TEAMS`]) and RAW STRING
+/// `playerID` (`player<i>`), where `i` is the row number.
+pub fn write_baseball_stats(dir: &Path, num_docs: usize) -> Result<()> {
+    let players: Vec<String> = (0..num_docs).map(|i| format!("player{}", i)).collect();
+    SegmentBuilder::new("baseballStats", "baseballStats_OFFLINE_0")
+        .int_column(
+            "hits",
+            (0..num_docs).map(|i| (i * 37 % 263) as i32).collect(),
+        )
+        .int_column("homeRuns", (0..num_docs).map(|i| (i % 60) as i32).collect())
+        .string_column(
+            "teamID",
+            (0..num_docs).map(|i| BASEBALL_STATS_TEAMS[i % 8]).collect(),
+        )
+        .raw_string_column("playerID", players.iter().map(String::as_str).collect())
+        .write(dir)
+}
+
+/// Encode `values` as an uncompressed V4 var-byte forward index region
+/// (starting at the magic marker) with `docs_per_chunk` values per chunk
+pub fn var_byte_region(values: &[&[u8]], docs_per_chunk: usize) -> Vec<u8> {
+    encode_var_byte_region(values, docs_per_chunk, false)
+}
+
+/// [`var_byte_region`], with each chunk LZ4-compressed when `compress` is set
+///
+/// Compressed regions record the largest chunk as the target chunk size,
+/// which bounds what the reader decompresses a chunk into.
+fn encode_var_byte_region(values: &[&[u8]], docs_per_chunk: usize, compress: bool) -> Vec<u8> {
+    let chunks: Vec<&[&[u8]]> = values.chunks(docs_per_chunk.max(1)).collect();
+
+    // Each chunk: value count, one offset per value (from the chunk start), data
+    let mut metadata = Vec::new();
+    let mut chunk_data = Vec::new();
+    let mut first_doc_id = 0u32;
+    let mut target_chunk_size = 1024;
+    for chunk in &chunks {
+        metadata.extend_from_slice(&first_doc_id.to_le_bytes());
+        metadata.extend_from_slice(&(chunk_data.len() as u32).to_le_bytes());
+        first_doc_id += chunk.len() as u32;
+
+        let mut offset = 4 + 4 * chunk.len();
+        let mut encoded = (chunk.len() as u32).to_le_bytes().to_vec();
+        for value in chunk.iter() {
+            encoded.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += value.len();
+        }
+        for value in chunk.iter() {
+            encoded.extend_from_slice(value);
+        }
+        if compress {
+            target_chunk_size = target_chunk_size.max(encoded.len());
+            chunk_data.extend_from_slice(&compress_region(&encoded));
+        } else {
+            chunk_data.extend_from_slice(&encoded);
+        }
+    }
+
+    // PASS_THROUGH (0) or LZ4 (3)
+    let compression_type: i32 = if compress { 3 } else { 0 };
+    let mut region = MAGIC_MARKER.to_be_bytes().to_vec();
+    region.extend_from_slice(&4i32.to_be_bytes());
+    region.extend_from_slice(&(target_chunk_size as i32).to_be_bytes());
+    region.extend_from_slice(&compression_type.to_be_bytes());
+    region.extend_from_slice(&(16 + metadata.len() as i32).to_be_bytes());
+    region.extend_from_slice(&metadata);
+    region.extend_from_slice(&chunk_data);
+    region
+}
+
+/// Encode big-endian `values` of `size_of_entry` bytes each as an
+/// uncompressed version 2 fixed-byte chunk forward index region with
+/// `docs_per_chunk` values per chunk
+pub fn fixed_byte_region(values: &[u8], size_of_entry: usize, docs_per_chunk: usize) -> Vec<u8> {
+    let chunks: Vec<&[u8]> = values.chunks(docs_per_chunk * size_of_entry).collect();
+    let header_size = 28;
+    let total_docs = values.len() / size_of_entry;
+    let header = [
+        2,
+        chunks.len(),
+        docs_per_chunk,
+        size_of_entry,
+        total_docs,
+        0, // PASS_THROUGH
+        header_size,
+    ];
+    let mut region: Vec<u8> = header
+        .iter()
+        .flat_map(|&field| (field as i32).to_be_bytes())
+        .collect();
+    let mut chunk_offset = header_size + 4 * chunks.len();
+    for chunk in &chunks {
+        region.extend_from_slice(&(chunk_offset as i32).to_be_bytes());
+        chunk_offset += chunk.len();
+    }
+    region.extend_from_slice(values);
+    region
+}
+
+/// A star-tree document: dict id per dimension (`STAR` when aggregated
+/// away) and one value per metric
+#[derive(Debug, Clone)]
+struct StarTreeRecord {
+    dims: Vec<i32>,
+    metrics: Vec<f64>,
+}
+
+#[derive(Debug)]
+struct StarTreeBuildNode {
+    dimension_id: i32,
+    dimension_value: i32,
+    start_doc: usize,
+    end_doc: usize,
+    aggregated_doc: i32,
+    /// Star child first, then by dimension value
+    children: Vec<usize>,
+}
+
+/// Star-tree construction following Pinot's single tree builder
+struct StarTreeBuilder<'a> {
+    /// Star-tree documents
+    records: Vec<StarTreeRecord>,
+    /// Nodes, the root first
+    nodes: Vec<StarTreeBuildNode>,
+    aggregations: &'a [AggFn],
+    max_leaf_records: usize,
+}
+
+impl StarTreeBuilder<'_> {
+    fn build(&mut self, records: Vec<StarTreeRecord>) {
+        self.records = self.sort_and_merge(records);
+        self.nodes.push(StarTreeBuildNode {
+            dimension_id: star_tree::STAR,
+            dimension_value: star_tree::STAR,
+            start_doc: 0,
+            end_doc: self.records.len(),
+            aggregated_doc: -1,
+            children: Vec::new(),
+        });
+        // The root splits however few documents it has
+        self.split(0, 0);
+        self.aggregate(0);
+    }
+
+    /// Sort records by dimensions and merge those with equal dimensions
+    fn sort_and_merge(&self, mut records: Vec<StarTreeRecord>) -> Vec<StarTreeRecord> {
+        records.sort_by(|a, b| a.dims.cmp(&b.dims));
+        let mut merged: Vec<StarTreeRecord> = Vec::with_capacity(records.len());
+        for record in records {
+            match merged.last_mut() {
+                Some(last) if last.dims == record.dims => self.merge_into(last, &record),
+                _ => merged.push(record),
+            }
+        }
+        merged
+    }
+
+    fn merge_into(&self, acc: &mut StarTreeRecord, record: &StarTreeRecord) {
+        for ((acc, value), aggregation) in acc
+            .metrics
+            .iter_mut()
+            .zip(&record.metrics)
+            .zip(self.aggregations)
+        {
+            *acc = aggregation.merge(*acc, *value);
+        }
+    }
+
+    /// Split the documents of node `idx` on dimension `dim`: one child per
+    /// value and, with more than one value, a star child over new documents
+    /// with `dim` aggregated away. Children with more than
+    /// `max_leaf_records` documents split on the next dimension.
+    fn split(&mut self, idx: usize, dim: usize) {
+        let num_dimensions = self.records.first().map_or(0, |r| r.dims.len());
+        if dim >= num_dimensions {
+            return;
+        }
+        let (start, end) = (self.nodes[idx].start_doc, self.nodes[idx].end_doc);
+        let mut children = Vec::new();
+        let mut child_start = start;
+        for doc in start..end {
+            let value = self.records[doc].dims[dim];
+            if doc + 1 == end || self.records[doc + 1].dims[dim] != value {
+                children.push((value, child_start, doc + 1));
+                child_start = doc + 1;
+            }
+        }
+        if children.len() > 1 {
+            let star_records = self.records[start..end]
+                .iter()
+                .map(|record| {
+                    let mut record = record.clone();
+                    record.dims[dim] = star_tree::STAR;
+                    record
+                })
+                .collect();
+            let star_records = self.sort_and_merge(star_records);
+            let star_start = self.records.len();
+            self.records.extend(star_records);
+            children.insert(0, (star_tree::STAR, star_start, self.records.len()));
+        }
+
+        for (value, start_doc, end_doc) in children {
+            let child = self.nodes.len();
+            self.nodes.push(StarTreeBuildNode {
+                dimension_id: dim as i32,
+                dimension_value: value,
+                start_doc,
+                end_doc,
+                aggregated_doc: -1,
+                children: Vec::new(),
+            });
+            self.nodes[idx].children.push(child);
+            if end_doc - start_doc > self.max_leaf_records {
+                self.split(child, dim + 1);
+            }
+        }
+    }
+
+    /// Set the aggregated document of node `idx` and its descendants,
+    /// appending a document where no existing one covers the node
+    fn aggregate(&mut self, idx: usize) {
+        let children = self.nodes[idx].children.clone();
+        for &child in &children {
+            self.aggregate(child);
+        }
+        let node = &self.nodes[idx];
+        let star_child = children
+            .first()
+            .filter(|&&child| self.nodes[child].dimension_value == star_tree::STAR);
+        let merged_docs: Vec<usize> = match (children.is_empty(), star_child) {
+            (true, _) if node.end_doc - node.start_doc == 1 => {
+                self.nodes[idx].aggregated_doc = node.start_doc as i32;
+                return;
+            }
+            (true, _) => (node.start_doc..node.end_doc).collect(),
+            (false, Some(&star)) => {
+                self.nodes[idx].aggregated_doc = self.nodes[star].aggregated_doc;
+                return;
+            }
+            (false, None) => children
+                .iter()
+                .map(|&child| self.nodes[child].aggregated_doc as usize)
+                .collect(),
+        };
+
+        let mut aggregated = self.records[merged_docs[0]].clone();
+        for &doc in &merged_docs[1..] {
+            let record = self.records[doc].clone();
+            self.merge_into(&mut aggregated, &record);
+        }
+        let first_star_dim = (node.dimension_id + 1) as usize;
+        for value in &mut aggregated.dims[first_star_dim..] {
+            *value = star_tree::STAR;
+        }
+        self.nodes[idx].aggregated_doc = self.records.len() as i32;
+        self.records.push(aggregated);
+    }
+
+    /// The tree region: little-endian header, then the nodes in
+    /// breadth-first order
+    fn serialize(&self, dimensions: &[String]) -> Vec<u8> {
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut queue = VecDeque::from([0usize]);
+        while let Some(idx) = queue.pop_front() {
+            order.push(idx);
+            queue.extend(&self.nodes[idx].children);
+        }
+        let mut node_ids = vec![0i32; self.nodes.len()];
+        for (id, &idx) in order.iter().enumerate() {
+            node_ids[idx] = id as i32;
+        }
+
+        let header_size: usize =
+            8 + 4 * 3 + dimensions.iter().map(|d| 8 + d.len()).sum::<usize>() + 4;
+        let mut tree = star_tree::MAGIC_MARKER.to_le_bytes().to_vec();
+        for field in [
+            star_tree::SUPPORTED_VERSION,
+            header_size as i32,
+            dimensions.len() as i32,
+        ] {
+            tree.extend_from_slice(&field.to_le_bytes());
+        }
+        for (id, dimension) in dimensions.iter().enumerate() {
+            tree.extend_from_slice(&(id as i32).to_le_bytes());
+            tree.extend_from_slice(&(dimension.len() as i32).to_le_bytes());
+            tree.extend_from_slice(dimension.as_bytes());
+        }
+        tree.extend_from_slice(&(self.nodes.len() as i32).to_le_bytes());
+
+        for &idx in &order {
+            let node = &self.nodes[idx];
+            let (first_child, last_child) = match (node.children.first(), node.children.last()) {
+                (Some(&first), Some(&last)) => (node_ids[first], node_ids[last]),
+                _ => (-1, -1),
+            };
+            let fields = [
+                node.dimension_id,
+                node.dimension_value,
+                node.start_doc as i32,
+                node.end_doc as i32,
+                node.aggregated_doc,
+                first_child,
+                last_child,
+            ];
+            for field in fields {
+                tree.extend_from_slice(&field.to_le_bytes());
+            }
+        }
+        tree
+    }
+}
+
+/// Append `doc_ids` as a portable-format RoaringBitmap, as Pinot writes them
+fn serialize_bitmap(doc_ids: &[u32], out: &mut Vec<u8>) {
+    let bitmap: RoaringBitmap = doc_ids.iter().copied().collect();
+    bitmap
+        .serialize_into(out)
+        .expect("writing to a Vec cannot fail");
+}
+
+/// Version 1 range index over dict ids `0..cardinality`, split into at most
+/// `num_ranges` ranges of equal width
+fn range_index_region(dict_ids: &[u32], cardinality: u32, num_ranges: usize) -> Vec<u8> {
+    let width = (cardinality.max(1) as usize).div_ceil(num_ranges) as u32;
+    let starts: Vec<u32> = (0..cardinality.max(1)).step_by(width as usize).collect();
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&1i32.to_be_bytes());
+    header.extend_from_slice(&3i32.to_be_bytes());
+    header.extend_from_slice(b"INT");
+    header.extend_from_slice(&(starts.len() as i32).to_be_bytes());
+    for start in &starts {
+        header.extend_from_slice(&(*start as i32).to_be_bytes());
+    }
+    header.extend_from_slice(&(cardinality.saturating_sub(1) as i32).to_be_bytes());
+
+    // Bitmap offsets are relative to the version field and follow the header
+    let bitmaps_start = header.len() + (starts.len() + 1) * 8;
+    let mut bitmaps = Vec::new();
+    let mut offsets = Vec::new();
+    for start in &starts {
+        offsets.push(bitmaps_start + bitmaps.len());
+        let doc_ids: Vec<u32> = dict_ids
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| (*start..start + width).contains(id))
+            .map(|(doc_id, _)| doc_id as u32)
+            .collect();
+        serialize_bitmap(&doc_ids, &mut bitmaps);
+    }
+    offsets.push(bitmaps_start + bitmaps.len());
+
+    let mut region = MAGIC_MARKER.to_be_bytes().to_vec();
+    region.extend_from_slice(&header);
+    for offset in offsets {
+        region.extend_from_slice(&(offset as i64).to_be_bytes());
+    }
+    region.extend_from_slice(&bitmaps);
+    region
+}
+
+#[cfg(feature = "lz4")]
+fn compress_region(region: &[u8]) -> Vec<u8> {
+    lz4::block::compress(region, None, false).expect("LZ4 compression of test region")
+}
+
+#[cfg(not(feature = "lz4"))]
+fn compress_region(_region: &[u8]) -> Vec<u8> {
+    unreachable!("compress_dictionaries and compress_raw_chunks require the lz4 feature")
+}
+
+/// Front-coded dictionary region (version 1) over sorted `entries`
+fn front_coded_region(entries: &[Vec<u8>], bucket_size: u8) -> Vec<u8> {
+    fn vbyte(mut value: usize, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push((value & 0x7F) as u8);
+            value >>= 7;
+        }
+        out.push(value as u8 | 0x80);
+    }
+
+    let mut buckets = Vec::new();
+    let mut bucket_ends = Vec::new();
+    for (bucket_idx, bucket) in entries.chunks(bucket_size as usize).enumerate() {
+        if bucket_idx > 0 {
+            bucket_ends.extend_from_slice(&(buckets.len() as u32).to_le_bytes());
+        }
+        vbyte(bucket[0].len(), &mut buckets);
+        buckets.extend_from_slice(&bucket[0]);
+        for pair in bucket.windows(2) {
+            let shared = pair[0]
+                .iter()
+                .zip(&pair[1])
+                .take_while(|(a, b)| a == b)
+                .count();
+            vbyte(shared, &mut buckets);
+            vbyte(pair[1].len() - shared, &mut buckets);
+            buckets.extend_from_slice(&pair[1][shared..]);
+        }
+    }
+
+    let mut region = vec![1, bucket_size, 0];
+    vbyte(entries.len(), &mut region);
+    vbyte(bucket_ends.len() + buckets.len(), &mut region);
+    region.extend_from_slice(&bucket_ends);
+    region.extend_from_slice(&buckets);
+    region
+}
+
+/// Number of bits Pinot uses to store dict ids for a given cardinality
+fn bits_for_cardinality(cardinality: u32) -> u8 {
+    if cardinality <= 1 {
+        1
+    } else {
+        (32 - (cardinality - 1).leading_zeros()) as u8
+    }
+}
+
+/// Pack values MSB-first (big-endian bit order), matching `PinotDataBitSet`
+fn pack_bits(values: &[u32], bits_per_value: u8) -> Vec<u8> {
+    let total_bits = values.len() * bits_per_value as usize;
+    let mut buffer = vec![0u8; total_bits.div_ceil(8)];
+
+    for (idx, &value) in values.iter().enumerate() {
+        let start_bit = idx * bits_per_value as usize;
+        for bit in 0..bits_per_value as usize {
+            if (value >> (bits_per_value as usize - 1 - bit)) & 1 == 1 {
+                let pos = start_bit + bit;
+                buffer[pos / 8] |= 0x80 >> (pos % 8);
+            }
+        }
+    }
+
+    buffer
+}
+
+/// Pack values LSB-first (little-endian bit order), as V1 segments do
+fn pack_bits_le(values: &[u32], bits_per_value: u8) -> Vec<u8> {
+    let total_bits = values.len() * bits_per_value as usize;
+    let mut buffer = vec![0u8; total_bits.div_ceil(8)];
+
+    for (idx, &value) in values.iter().enumerate() {
+        let start_bit = idx * bits_per_value as usize;
+        for bit in 0..bits_per_value as usize {
+            if (value >> bit) & 1 == 1 {
+                let pos = start_bit + bit;
+                buffer[pos / 8] |= 1 << (pos % 8);
+            }
+        }
+    }
+
+    buffer
+}
+
+/// Min/max as written to metadata.properties
+fn bound_to_string<T: ToString>(value: Option<&T>) -> Option<String> {
+    value.map(|v| v.to_string())
+}
+
+/// Build a sorted dictionary and per-document dict ids
+fn encode_column(values: &TestColumnValues) -> EncodedColumn {
+    fn dict_ids_for<T: PartialOrd + Copy>(values: &[T]) -> (Vec<T>, Vec<u32>) {
+        let mut dictionary: Vec<T> = values.to_vec();
+        dictionary.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        dictionary.dedup_by(|a, b| a == b);
+        let ids = values
+            .iter()
+            .map(|v| dictionary.iter().position(|d| d == v).unwrap() as u32)
+            .collect();
+        (dictionary, ids)
+    }
+
+    match values {
+        TestColumnValues::Int(values) => {
+            let (dict, dict_ids) = dict_ids_for(values);
+            EncodedColumn {
+                entries: Vec::new(),
+                dictionary: dict.iter().flat_map(|v| v.to_be_bytes()).collect(),
+                cardinality: dict.len() as u32,
+                min_value: bound_to_string(dict.first()),
+                max_value: bound_to_string(dict.last()),
+                dict_ids,
+                length_of_each_entry: 4,
+            }
+        }
+        TestColumnValues::Long(values) => {
+            let (dict, dict_ids) = dict_ids_for(values);
+            EncodedColumn {
+                entries: Vec::new(),
+                dictionary: dict.iter().flat_map(|v| v.to_be_bytes()).collect(),
+                cardinality: dict.len() as u32,
+                min_value: bound_to_string(dict.first()),
+                max_value: bound_to_string(dict.last()),
+                dict_ids,
+                length_of_each_entry: 8,
+            }
+        }
+        TestColumnValues::Float(values) => {
+            let (dict, dict_ids) = dict_ids_for(values);
+            EncodedColumn {
+                entries: Vec::new(),
+                dictionary: dict.iter().flat_map(|v| v.to_be_bytes()).collect(),
+                cardinality: dict.len() as u32,
+                min_value: bound_to_string(dict.first()),
+                max_value: bound_to_string(dict.last()),
+                dict_ids,
+                length_of_each_entry: 4,
+            }
+        }
+        TestColumnValues::Double(values) => {
+            let (dict, dict_ids) = dict_ids_for(values);
+            EncodedColumn {
+                entries: Vec::new(),
+                dictionary: dict.iter().flat_map(|v| v.to_be_bytes()).collect(),
+                cardinality: dict.len() as u32,
+                min_value: bound_to_string(dict.first()),
+                max_value: bound_to_string(dict.last()),
+                dict_ids,
+                length_of_each_entry: 8,
+            }
+        }
+        TestColumnValues::String(values) => {
+            let refs: Vec<&str> = values.iter().map(|s| s.as_str()).collect();
+            let (dict, dict_ids) = dict_ids_for(&refs);
+            // Fixed-length entries padded with NUL bytes
+            let width = dict.iter().map(|s| s.len()).max().unwrap_or(0).max(1);
+            let mut dictionary = Vec::with_capacity(dict.len() * width);
+            for s in &dict {
+                dictionary.extend_from_slice(s.as_bytes());
+                dictionary.resize(dictionary.len() + width - s.len(), 0);
+            }
+            EncodedColumn {
+                entries: dict.iter().map(|s| s.as_bytes().to_vec()).collect(),
+                min_value: bound_to_string(dict.first()),
+                max_value: bound_to_string(dict.last()),
+                dictionary,
+                cardinality: dict.len() as u32,
+                dict_ids,
+                length_of_each_entry: width,
+            }
+        }
+        TestColumnValues::Bytes(values) => {
+            let refs: Vec<&[u8]> = values.iter().map(|b| b.as_slice()).collect();
+            let (dict, dict_ids) = dict_ids_for(&refs);
+            let fixed_width = dict
+                .first()
+                .map(|b| b.len())
+                .filter(|&width| width > 0 && dict.iter().all(|b| b.len() == width));
+            let mut dictionary = Vec::new();
+            for entry in &dict {
+                if fixed_width.is_none() {
+                    dictionary.extend_from_slice(&(entry.len() as u32).to_be_bytes());
+                }
+                dictionary.extend_from_slice(entry);
+            }
+            // Pinot records BYTES bounds as hex strings
+            let hex =
+                |b: Option<&&[u8]>| b.map(|b| b.iter().map(|v| format!("{:02x}", v)).collect());
+            EncodedColumn {
+                entries: dict.iter().map(|b| b.to_vec()).collect(),
+                min_value: hex(dict.first()),
+                max_value: hex(dict.last()),
+                dictionary,
+                cardinality: dict.len() as u32,
+                dict_ids,
+                length_of_each_entry: fixed_width.unwrap_or(0),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_for_cardinality() {
+        assert_eq!(bits_for_cardinality(1), 1);
+        assert_eq!(bits_for_cardinality(2), 1);
+        assert_eq!(bits_for_cardinality(3), 2);
+        assert_eq!(bits_for_cardinality(250), 8);
+        assert_eq!(bits_for_cardinality(257), 9);
+    }
+
+    #[test]
+    fn test_pack_bits_matches_reader_layout() {
+        // Same fixture as FixedBitWidthReader::test_bit_packing_cross_byte
+        assert_eq!(pack_bits(&[10, 20, 5], 5), vec![0x55, 0x0A]);
+        assert_eq!(pack_bits(&[5, 10, 15, 3], 4), vec![0x5A, 0xF3]);
+    }
+}
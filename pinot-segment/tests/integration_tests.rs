@@ -1,144 +1,125 @@
-use pinot_segment::SegmentReader;
-use std::path::Path;
-
-const SEGMENT_DIR: &str = "/tmp/pinot/quickstart/PinotServerDataDir0/baseballStats_OFFLINE/baseballStats_OFFLINE_0_e40936cc-16f8-490e-a85f-bc61a9abee66/v3";
+use pinot_segment::testutil::{write_baseball_stats, SegmentBuilder, BASEBALL_STATS_TEAMS};
+use pinot_segment::{SegmentReader, CHUNK_BATCH_SIZE};
+use tempfile::TempDir;
+
+/// Rows of the synthetic segment; more than two RAW chunks of 1000 values
+const NUM_DOCS: usize = 2500;
+
+/// A `baseballStats`-shaped segment in a fresh temp dir
+fn baseball_stats() -> (TempDir, SegmentReader) {
+    let dir = tempfile::tempdir().unwrap();
+    write_baseball_stats(dir.path(), NUM_DOCS).expect("Failed to write segment");
+    let reader = SegmentReader::open(dir.path()).expect("Failed to open segment");
+    (dir, reader)
+}
 
 #[test]
 fn test_read_baseball_stats_metadata() {
-    if !Path::new(SEGMENT_DIR).exists() {
-        println!("Skipping test: segment directory not found");
-        return;
-    }
-
-    let reader = SegmentReader::open(SEGMENT_DIR).expect("Failed to open segment");
+    let (_dir, reader) = baseball_stats();
     let metadata = reader.metadata();
 
     assert_eq!(metadata.table_name, "baseballStats");
-    assert_eq!(metadata.total_docs, 97889);
+    assert_eq!(metadata.total_docs, NUM_DOCS as u32);
 
     // Check hits column
     let hits_col = metadata.get_column("hits").expect("hits column not found");
-    assert_eq!(hits_col.cardinality, 250);
-    assert_eq!(hits_col.bits_per_element, 8);
+    assert_eq!(hits_col.cardinality, 263);
+    assert_eq!(hits_col.bits_per_element, 9);
     assert!(hits_col.has_dictionary);
-
-    println!("✓ Metadata loaded successfully");
+    assert!(!metadata.get_column("playerID").unwrap().has_dictionary);
 }
 
 #[test]
 fn test_read_dict_encoded_int_column() {
-    if !Path::new(SEGMENT_DIR).exists() {
-        println!("Skipping test: segment directory not found");
-        return;
-    }
-
-    let reader = SegmentReader::open(SEGMENT_DIR).expect("Failed to open segment");
+    let (_dir, reader) = baseball_stats();
 
     // Read hits column (dictionary-encoded INT)
-    let hits = reader.read_int_column("hits").expect("Failed to read hits column");
+    let hits = reader
+        .read_int_column("hits")
+        .expect("Failed to read hits column");
 
-    assert_eq!(hits.len(), 97889, "Should have 97889 rows");
-
-    // Verify values are in valid range (0-262 based on metadata)
+    assert_eq!(hits.len(), NUM_DOCS);
     for (idx, &value) in hits.iter().enumerate() {
-        assert!(
-            value >= 0 && value <= 262,
-            "Value {} at index {} out of range",
+        assert_eq!(
             value,
+            (idx * 37 % 263) as i32,
+            "Wrong hits at index {}",
             idx
         );
     }
-
-    // Print some sample values
-    println!("Sample hits values:");
-    for i in 0..10 {
-        println!("  Row {}: {}", i, hits[i]);
-    }
-
-    println!("✓ Successfully read {} hits values", hits.len());
 }
 
 #[test]
 fn test_read_dict_encoded_string_column() {
-    if !Path::new(SEGMENT_DIR).exists() {
-        println!("Skipping test: segment directory not found");
-        return;
-    }
-
-    let reader = SegmentReader::open(SEGMENT_DIR).expect("Failed to open segment");
+    let (_dir, reader) = baseball_stats();
 
     // Read teamID column (dictionary-encoded STRING)
     let team_ids = reader
         .read_string_column("teamID")
         .expect("Failed to read teamID column");
 
-    assert_eq!(team_ids.len(), 97889, "Should have 97889 rows");
-
-    // Verify all values are non-empty
+    assert_eq!(team_ids.len(), NUM_DOCS);
     for (idx, value) in team_ids.iter().enumerate() {
-        assert!(
-            !value.is_empty(),
-            "Empty teamID at index {}",
+        assert_eq!(
+            value,
+            BASEBALL_STATS_TEAMS[idx % 8],
+            "Wrong teamID at index {}",
             idx
         );
     }
-
-    // Print some sample values
-    println!("Sample teamID values:");
-    for i in 0..10 {
-        println!("  Row {}: {}", i, team_ids[i]);
-    }
-
-    println!("✓ Successfully read {} teamID values", team_ids.len());
 }
 
 #[test]
 fn test_read_raw_string_column() {
-    if !Path::new(SEGMENT_DIR).exists() {
-        println!("Skipping test: segment directory not found");
-        return;
-    }
-
-    let reader = SegmentReader::open(SEGMENT_DIR).expect("Failed to open segment");
+    let (_dir, reader) = baseball_stats();
 
-    // Read playerID column (RAW STRING with LZ4 compression)
+    // Read playerID column (RAW STRING, spanning several chunks)
     let player_ids = reader
         .read_string_column("playerID")
         .expect("Failed to read playerID column");
 
-    assert_eq!(player_ids.len(), 97889, "Should have 97889 rows");
-
-    // Verify all values are non-empty
+    assert_eq!(player_ids.len(), NUM_DOCS);
     for (idx, value) in player_ids.iter().enumerate() {
-        assert!(
-            !value.is_empty(),
-            "Empty playerID at index {}",
+        assert_eq!(
+            *value,
+            format!("player{}", idx),
+            "Wrong playerID at index {}",
             idx
         );
     }
+}
 
-    // Print some sample values
-    println!("Sample playerID values (RAW encoding with LZ4 compression):");
-    for i in 0..10 {
-        println!("  Row {}: {}", i, player_ids[i]);
-    }
-
-    // Verify expected patterns (playerID format is typically lowercase letters + digits)
-    assert!(player_ids[0]
-        .chars()
-        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()));
-
-    println!("✓ Successfully read {} LZ4-compressed RAW STRING values", player_ids.len());
+#[cfg(feature = "lz4")]
+#[test]
+fn test_read_lz4_raw_string_column() {
+    let dir = tempfile::tempdir().unwrap();
+    let players: Vec<String> = (0..NUM_DOCS).map(|i| format!("player{}", i)).collect();
+    SegmentBuilder::new("baseballStats", "baseballStats_OFFLINE_0")
+        .raw_string_column("playerID", players.iter().map(String::as_str).collect())
+        .compress_raw_chunks()
+        .write(dir.path())
+        .expect("Failed to write segment");
+    let reader = SegmentReader::open(dir.path()).expect("Failed to open segment");
+
+    // Read playerID column (RAW STRING with LZ4 chunk compression)
+    let player_ids = reader
+        .read_string_column("playerID")
+        .expect("Failed to read playerID column");
+    assert_eq!(player_ids, players);
+
+    // Chunked reads decompress the same values
+    let chunks: Vec<Vec<String>> = reader
+        .read_string_column_in_chunks("playerID")
+        .expect("Failed to read playerID chunks")
+        .map(|chunk| chunk.expect("Failed to read chunk"))
+        .collect();
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks.concat(), players);
 }
 
 #[test]
 fn test_read_multiple_columns() {
-    if !Path::new(SEGMENT_DIR).exists() {
-        println!("Skipping test: segment directory not found");
-        return;
-    }
-
-    let reader = SegmentReader::open(SEGMENT_DIR).expect("Failed to open segment");
+    let (_dir, reader) = baseball_stats();
 
     // Read multiple columns
     let hits = reader.read_int_column("hits").expect("Failed to read hits");
@@ -149,17 +130,66 @@ fn test_read_multiple_columns() {
         .read_string_column("teamID")
         .expect("Failed to read teamID");
 
-    assert_eq!(hits.len(), 97889);
-    assert_eq!(home_runs.len(), 97889);
-    assert_eq!(team_ids.len(), 97889);
+    assert_eq!(hits.len(), NUM_DOCS);
+    assert_eq!(home_runs.len(), NUM_DOCS);
+    assert_eq!(team_ids.len(), NUM_DOCS);
 
-    // Print first few rows as a mini table
-    println!("\nFirst 10 rows:");
-    println!("{:<10} {:<10} {:<10}", "teamID", "hits", "homeRuns");
-    println!("{}", "-".repeat(30));
-    for i in 0..10 {
-        println!("{:<10} {:<10} {:<10}", team_ids[i], hits[i], home_runs[i]);
-    }
+    // Rows line up across columns
+    assert_eq!(
+        (team_ids[1234].as_str(), hits[1234], home_runs[1234]),
+        ("CHN", 1234 * 37 % 263, 34)
+    );
+}
 
-    println!("\n✓ Successfully read multiple columns");
+#[test]
+fn test_read_columns_in_chunks() {
+    let (_dir, reader) = baseball_stats();
+
+    // RAW STRING: one chunk per physical chunk of 1000 values
+    let mut chunks = reader.read_string_column_in_chunks("playerID").unwrap();
+    assert_eq!(chunks.len(), 3);
+    let chunks: Vec<Vec<String>> = chunks.by_ref().map(|chunk| chunk.unwrap()).collect();
+    assert_eq!(
+        chunks.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![1000, 1000, 500]
+    );
+    assert_eq!(
+        chunks.concat(),
+        reader.read_string_column("playerID").unwrap()
+    );
+
+    // Dictionary-encoded columns: CHUNK_BATCH_SIZE values per chunk
+    let mut team_ids = reader.read_string_column_in_chunks("teamID").unwrap();
+    assert_eq!(team_ids.len(), 1);
+    assert_eq!(
+        team_ids.next().unwrap().unwrap(),
+        reader.read_string_column("teamID").unwrap()
+    );
+    assert!(team_ids.next().is_none());
+    assert!(team_ids.next().is_none());
+
+    let hits: Vec<i32> = reader
+        .read_int_column_in_chunks("hits")
+        .unwrap()
+        .map(|chunk| chunk.unwrap())
+        .collect::<Vec<_>>()
+        .concat();
+    assert_eq!(hits, reader.read_int_column("hits").unwrap());
+
+    assert!(reader.read_int_column_in_chunks("teamID").is_err());
+    assert!(reader.read_string_column_in_chunks("hits").is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    SegmentBuilder::new("events", "events_0")
+        .int_column("id", (0..20_000).map(|i| i % 100).collect())
+        .write(dir.path())
+        .unwrap();
+    let reader = SegmentReader::open(dir.path()).unwrap();
+    let chunks = reader.read_int_column_in_chunks("id").unwrap();
+    assert_eq!(chunks.len(), 3);
+    let sizes: Vec<usize> = chunks.map(|chunk| chunk.unwrap().len()).collect();
+    assert_eq!(
+        sizes,
+        vec![CHUNK_BATCH_SIZE as usize, CHUNK_BATCH_SIZE as usize, 3616]
+    );
 }